@@ -0,0 +1,50 @@
+//! Start a JVM from Rust and use the bindings of the driver crate (`tests/driver/native`).
+//!
+//! Build the Java classes and run with:
+//! ```text
+//! (cd tests/driver && ./gradlew compileJava)
+//! cargo run --example embed --features invocation [-- <classes dir or jar>...]
+//! ```
+//!
+//! The JVM library must be found by the dynamic loader, e.g. with `LD_LIBRARY_PATH=$JAVA_HOME/lib/server` on Linux.
+
+use std::error::Error;
+use std::path::PathBuf;
+
+use native::jni::User;
+use robusta_jni::embed::{check_exception, Classpath, VmBuilder};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut classpath = Classpath::from_env();
+    for entry in std::env::args_os().skip(1) {
+        classpath = classpath.entry(entry);
+    }
+    if classpath.is_empty() {
+        classpath = classpath.entry(
+            ["tests", "driver", "build", "classes", "java", "main"]
+                .iter()
+                .collect::<PathBuf>(),
+        );
+    }
+
+    // `User` loads the driver library, which is built next to the `examples` directory
+    let library_dir = std::env::current_exe()?
+        .parent()
+        .and_then(|examples_dir| examples_dir.parent())
+        .map(PathBuf::from)
+        .ok_or("can't find the target directory")?;
+
+    let vm = VmBuilder::new()
+        .classpath(classpath)
+        .library_path(library_dir)
+        .option("-Xcheck:jni")
+        .build()?;
+    let env = vm.attach_current_thread()?;
+
+    let user = check_exception(&env, User::new(&env, "user".into(), "password".into()))?;
+    let count = check_exception(&env, User::getTotalUsersCount(&env))?;
+    let password = check_exception(&env, user.getPassword(&env))?;
+
+    println!("created {} user(s), password: {}", count, password);
+    Ok(())
+}
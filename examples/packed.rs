@@ -0,0 +1,82 @@
+//! Compare `Vec<T>` and `PackedList<T>` conversions of numeric lists in an embedded JVM.
+//!
+//! Run with:
+//! ```text
+//! cargo run --release --example packed --features invocation
+//! ```
+//!
+//! The JVM library must be found by the dynamic loader, e.g. with `LD_LIBRARY_PATH=$JAVA_HOME/lib/server` on Linux.
+
+use std::error::Error;
+use std::time::{Duration, Instant};
+
+use robusta_jni::convert::{PackedList, TryFromJavaValue, TryIntoJavaValue};
+use robusta_jni::embed::VmBuilder;
+use robusta_jni::jni::objects::JObject;
+use robusta_jni::jni::JNIEnv;
+
+/// Average duration of `f` over `iterations` runs, each in its own local frame.
+fn measure<F>(env: &JNIEnv, iterations: usize, mut f: F) -> Result<Duration, Box<dyn Error>>
+where
+    F: FnMut(&JNIEnv) -> Result<(), Box<dyn Error>>,
+{
+    let start = Instant::now();
+    for _ in 0..iterations {
+        env.push_local_frame(16)?;
+        f(env)?;
+        env.pop_local_frame(JObject::null())?;
+    }
+
+    Ok(start.elapsed() / iterations as u32)
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let vm = VmBuilder::new().build()?;
+    let env = vm.attach_current_thread()?;
+
+    println!("{:>8} | {:>14} | {:>14} | {:>14} | {:>14}", "length", "Vec to Java", "Packed to Java", "Vec from Java", "Packed from Java");
+
+    for &len in &[1usize, 10, 100, 1_000, 10_000, 100_000] {
+        let values: Vec<i32> = (0..len as i32).collect();
+        let iterations = (1_000_000 / len).clamp(10, 10_000);
+
+        let list = TryIntoJavaValue::try_into(values.clone(), &env)?;
+        let list = env.new_global_ref(unsafe { JObject::from_raw(list) })?;
+
+        // Warm up caches and the JIT
+        for _ in 0..2 {
+            measure(&env, iterations, |env| {
+                TryIntoJavaValue::try_into(values.clone(), env)?;
+                TryIntoJavaValue::try_into(PackedList(values.clone()), env)?;
+                let _: Vec<i32> = TryFromJavaValue::try_from(list.as_obj(), env)?;
+                let _: PackedList<i32> = TryFromJavaValue::try_from(list.as_obj(), env)?;
+                Ok(())
+            })?;
+        }
+
+        let vec_to_java = measure(&env, iterations, |env| {
+            TryIntoJavaValue::try_into(values.clone(), env)?;
+            Ok(())
+        })?;
+        let packed_to_java = measure(&env, iterations, |env| {
+            TryIntoJavaValue::try_into(PackedList(values.clone()), env)?;
+            Ok(())
+        })?;
+
+        let vec_from_java = measure(&env, iterations, |env| {
+            let _: Vec<i32> = TryFromJavaValue::try_from(list.as_obj(), env)?;
+            Ok(())
+        })?;
+        let packed_from_java = measure(&env, iterations, |env| {
+            let _: PackedList<i32> = TryFromJavaValue::try_from(list.as_obj(), env)?;
+            Ok(())
+        })?;
+
+        println!(
+            "{:>8} | {:>14?} | {:>14?} | {:>14?} | {:>14?}",
+            len, vec_to_java, packed_to_java, vec_from_java, packed_from_java
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+//! Measure autoboxing and `Vec` conversions in an embedded JVM, to compare builds with and without the `pool` feature.
+//!
+//! Run with:
+//! ```text
+//! cargo run --release --example pool --features invocation
+//! cargo run --release --example pool --features invocation,pool
+//! ```
+//!
+//! The JVM library must be found by the dynamic loader, e.g. with `LD_LIBRARY_PATH=$JAVA_HOME/lib/server` on Linux.
+
+use std::error::Error;
+use std::time::{Duration, Instant};
+
+use robusta_jni::convert::{JavaValue, TryIntoJavaValue};
+use robusta_jni::embed::VmBuilder;
+use robusta_jni::jni::objects::JObject;
+use robusta_jni::jni::JNIEnv;
+
+const ITERATIONS: usize = 100_000;
+
+/// Average duration of `f` over `ITERATIONS` runs, each in its own local frame.
+fn measure<F>(env: &JNIEnv, mut f: F) -> Result<Duration, Box<dyn Error>>
+where
+    F: FnMut(&JNIEnv) -> Result<(), Box<dyn Error>>,
+{
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        env.push_local_frame(16)?;
+        f(env)?;
+        env.pop_local_frame(JObject::null())?;
+    }
+
+    Ok(start.elapsed() / ITERATIONS as u32)
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let vm = VmBuilder::new().build()?;
+    let env = vm.attach_current_thread()?;
+
+    println!("pool feature: {}", cfg!(feature = "pool"));
+
+    // Warm up caches and the JIT
+    measure(&env, |env| {
+        JavaValue::autobox(42i32, env);
+        Ok(())
+    })?;
+
+    let small_int = measure(&env, |env| {
+        JavaValue::autobox(42i32, env);
+        Ok(())
+    })?;
+    println!("autobox(42i32): {:?}", small_int);
+
+    let large_int = measure(&env, |env| {
+        JavaValue::autobox(100_000i32, env);
+        Ok(())
+    })?;
+    println!("autobox(100_000i32): {:?}", large_int);
+
+    let vec = measure(&env, |env| {
+        TryIntoJavaValue::try_into(vec![1i32, 2, 3, 4, 5, 6, 7, 8], env)?;
+        Ok(())
+    })?;
+    println!("Vec<i32> of 8 elements: {:?}", vec);
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+package robusta;
+
+import java.lang.ref.Cleaner;
+import java.lang.reflect.InvocationHandler;
+import java.lang.reflect.Method;
+
+/**
+ * Invocation handler of proxies wrapping Rust closures, created by {@code robusta_jni::convert::RustFunction}.
+ * The native methods are registered by the Rust library when the first closure is converted.
+ * Default methods of the interface (e.g. {@code Function.andThen}) are not forwarded to the closure.
+ */
+public final class RustFunction implements InvocationHandler {
+    private static final Cleaner CLEANER = Cleaner.create();
+
+    private final long handle;
+
+    private RustFunction(long handle) {
+        this.handle = handle;
+        CLEANER.register(this, () -> drop(handle));
+    }
+
+    @Override
+    public Object invoke(Object proxy, Method method, Object[] args) throws Throwable {
+        if (method.isDefault()) {
+            return InvocationHandler.invokeDefault(proxy, method, args);
+        }
+
+        if (method.getDeclaringClass() == Object.class) {
+            switch (method.getName()) {
+                case "equals":
+                    return proxy == args[0];
+                case "hashCode":
+                    return System.identityHashCode(proxy);
+                default:
+                    return "RustFunction@" + Long.toHexString(handle);
+            }
+        }
+
+        return call(handle, args);
+    }
+
+    private static native Object call(long handle, Object[] args);
+
+    private static native void drop(long handle);
+}
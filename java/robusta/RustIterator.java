@@ -0,0 +1,47 @@
+package robusta;
+
+import java.lang.ref.Cleaner;
+import java.util.Iterator;
+import java.util.NoSuchElementException;
+
+/**
+ * Iterator over the items of a Rust iterator, created by {@code robusta_jni::convert::RustIterator}.
+ * The native methods are registered by the Rust library when the first iterator is converted.
+ * Items are produced by the Rust iterator one at a time, when they are requested.
+ */
+public final class RustIterator<T> implements Iterator<T> {
+    private static final Cleaner CLEANER = Cleaner.create();
+
+    private final long handle;
+
+    private RustIterator(long handle) {
+        this.handle = handle;
+        CLEANER.register(this, () -> drop(handle));
+    }
+
+    @Override
+    public synchronized boolean hasNext() {
+        return hasItem(handle);
+    }
+
+    @Override
+    @SuppressWarnings("unchecked")
+    public synchronized T next() {
+        if (!hasItem(handle)) {
+            throw new NoSuchElementException();
+        }
+
+        return (T) nextItem(handle);
+    }
+
+    @Override
+    public String toString() {
+        return "RustIterator@" + Long.toHexString(handle);
+    }
+
+    private static native boolean hasItem(long handle);
+
+    private static native Object nextItem(long handle);
+
+    private static native void drop(long handle);
+}
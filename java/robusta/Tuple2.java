@@ -0,0 +1,47 @@
+package robusta;
+
+import java.util.Objects;
+
+/**
+ * Pair of values, converted from and to Rust tuples {@code (A, B)} by {@code robusta_jni::convert::tuple}.
+ * Primitive elements are boxed (e.g. {@code i32} as {@code Integer}).
+ */
+public final class Tuple2<A, B> {
+    private final A first;
+    private final B second;
+
+    public Tuple2(A first, B second) {
+        this.first = first;
+        this.second = second;
+    }
+
+    public A first() {
+        return first;
+    }
+
+    public B second() {
+        return second;
+    }
+
+    @Override
+    public boolean equals(Object o) {
+        if (this == o) {
+            return true;
+        }
+        if (!(o instanceof Tuple2)) {
+            return false;
+        }
+        Tuple2<?, ?> other = (Tuple2<?, ?>) o;
+        return Objects.equals(first, other.first) && Objects.equals(second, other.second);
+    }
+
+    @Override
+    public int hashCode() {
+        return Objects.hash(first, second);
+    }
+
+    @Override
+    public String toString() {
+        return "(" + first + ", " + second + ")";
+    }
+}
@@ -0,0 +1,54 @@
+package robusta;
+
+import java.util.Objects;
+
+/**
+ * Triple of values, converted from and to Rust tuples {@code (A, B, C)} by {@code robusta_jni::convert::tuple}.
+ * Primitive elements are boxed (e.g. {@code i32} as {@code Integer}).
+ */
+public final class Tuple3<A, B, C> {
+    private final A first;
+    private final B second;
+    private final C third;
+
+    public Tuple3(A first, B second, C third) {
+        this.first = first;
+        this.second = second;
+        this.third = third;
+    }
+
+    public A first() {
+        return first;
+    }
+
+    public B second() {
+        return second;
+    }
+
+    public C third() {
+        return third;
+    }
+
+    @Override
+    public boolean equals(Object o) {
+        if (this == o) {
+            return true;
+        }
+        if (!(o instanceof Tuple3)) {
+            return false;
+        }
+        Tuple3<?, ?, ?> other = (Tuple3<?, ?, ?>) o;
+        return Objects.equals(first, other.first) && Objects.equals(second, other.second)
+                && Objects.equals(third, other.third);
+    }
+
+    @Override
+    public int hashCode() {
+        return Objects.hash(first, second, third);
+    }
+
+    @Override
+    public String toString() {
+        return "(" + first + ", " + second + ", " + third + ")";
+    }
+}
@@ -1,22 +1,21 @@
 pub(crate) mod thread_func;
 
 use ::jni::objects::GlobalRef;
-use ::jni::JavaVM;
 use robusta_jni::bridge;
 use std::sync::OnceLock;
 
-static APP_CONTEXT: OnceLock<(JavaVM, GlobalRef)> = OnceLock::new();
+static APP_CLASS: OnceLock<GlobalRef> = OnceLock::new();
 
 #[bridge]
 mod jni {
-    use crate::APP_CONTEXT;
-    use android_logger::Config;
+    use crate::APP_CLASS;
     use jni::objects::{GlobalRef, JObject, JValue};
-    use log::info;
+    use log::{info, LevelFilter};
     use robusta_jni::convert::{IntoJavaValue, Signature, TryFromJavaValue, TryIntoJavaValue};
     use robusta_jni::jni::errors::Result as JniResult;
     use robusta_jni::jni::objects::AutoLocal;
     use robusta_jni::jni::JNIEnv;
+    use robusta_jni::logging::{self, Backend, Config};
     use std::thread;
 
     #[derive(Signature, TryIntoJavaValue, IntoJavaValue, TryFromJavaValue)]
@@ -28,16 +27,20 @@ mod jni {
 
     impl<'env: 'borrow, 'borrow> RobustaAndroidExample<'env, 'borrow> {
         pub extern "jni" fn runRustExample(self, env: &JNIEnv, context: JObject<'env>) {
-            android_logger::init_once(Config::default().with_tag("RUST_ROBUSTA_ANDROID_EXAMPLE"));
+            // Fails if the example was already run, in which case the logger is already installed
+            let _ = logging::install_with(
+                env,
+                Config::new()
+                    .backend(Backend::Android("RUST_ROBUSTA_ANDROID_EXAMPLE".to_string()))
+                    .max_level(LevelFilter::Trace),
+            );
 
             info!("TEST START");
             let java_class = env
                 .find_class("com/example/robustaandroidexample/RobustaAndroidExample")
                 .unwrap();
-            let _ = APP_CONTEXT.set((
-                env.get_java_vm().unwrap(),
-                env.new_global_ref(java_class).unwrap(),
-            ));
+            // The VM is stored by the generated code for the threads below, which also need the class
+            let _ = APP_CLASS.set(env.new_global_ref(java_class).unwrap());
 
             let app_files_dir = RobustaAndroidExample::getAppFilesDir(env, context).unwrap();
             info!("App files dir: {}", app_files_dir);
@@ -59,13 +62,13 @@ mod jni {
 
             let thread_handler = thread::Builder::new()
                 .name("test_thread_fail".to_string())
-                .spawn(move || crate::thread_func::thread_test_fail());
+                .spawn(crate::thread_func::thread_test_fail);
             let join_res = thread_handler.unwrap().join().unwrap();
             assert!(join_res.is_err());
 
             let thread_handler = thread::Builder::new()
                 .name("test_thread_good".to_string())
-                .spawn(move || crate::thread_func::thread_test_good());
+                .spawn(crate::thread_func::thread_test_good);
             let join_res = thread_handler.unwrap().join().unwrap();
             assert!(join_res.is_ok());
 
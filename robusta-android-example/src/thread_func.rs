@@ -5,11 +5,7 @@ use log::{debug, error};
 pub(crate) fn thread_test_fail() -> Result<(), String> {
     debug!("TEST_THREAD_FAIL: start...");
 
-    let (app_vm, _) = crate::APP_CONTEXT
-        .get()
-        .ok_or_else(|| "Couldn't get APP_CONTEXT".to_string())?;
-    let env = app_vm
-        .attach_current_thread_permanently()
+    let env = robusta_jni::attach::attach_permanently()
         .map_err(|_| "Couldn't attach to current thread".to_string())?;
 
     debug!("TEST_THREAD_FAIL: via JNI");
@@ -44,11 +40,10 @@ pub(crate) fn thread_test_fail() -> Result<(), String> {
 pub(crate) fn thread_test_good() -> Result<(), String> {
     debug!("TEST_THREAD_GOOD: start...");
 
-    let (app_vm, class_ref) = crate::APP_CONTEXT
+    let class_ref = crate::APP_CLASS
         .get()
-        .ok_or_else(|| "Couldn't get APP_CONTEXT".to_string())?;
-    let env = app_vm
-        .attach_current_thread_permanently()
+        .ok_or_else(|| "Couldn't get APP_CLASS".to_string())?;
+    let env = robusta_jni::attach::attach_permanently()
         .map_err(|_| "Couldn't attach to current thread".to_string())?;
 
     debug!("TEST_THREAD_GOOD: via JNI");
@@ -1,25 +1,38 @@
 use std::collections::HashMap;
 
-use crate::derive::utils::generic_params_to_args;
+use crate::derive::enums::{
+    from_java_value_enum_derive, get_enum_impl_components, into_java_value_enum_derive,
+    tryfrom_java_value_enum_derive, tryinto_java_value_enum_derive,
+};
+use crate::derive::record::{
+    from_java_value_record_derive, get_record_impl_components, into_java_value_record_derive, is_record,
+    tryfrom_java_value_record_derive, tryinto_java_value_record_derive,
+};
+use crate::derive::utils::{first_use_call, generic_params_to_args, has_first_use_hook};
 use crate::transformation::JavaPath;
+use crate::utils::{check_helper_attributes, FIELD_ATTRIBUTES};
 use proc_macro2::{Ident, TokenStream};
 use proc_macro_error::{abort, emit_error, emit_warning};
-use quote::{quote, quote_spanned, ToTokens};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
 use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
 use syn::{
-    AngleBracketedGenericArguments, Data, DataStruct, DeriveInput, Field, GenericArgument,
-    GenericParam, Generics, LifetimeParam, PathArguments, Type, TypePath,
+    parse_quote, AngleBracketedGenericArguments, Attribute, Data, DataStruct, DeriveInput, Field,
+    Fields, GenericArgument, GenericParam, Generics, Lifetime, LifetimeParam, LitStr, PathArguments, Type,
+    TypePath, WhereClause, WherePredicate,
 };
 
 struct TraitAutoDeriveData {
     instance_field_type_assertion: TokenStream,
     impl_target: Ident,
-    classpath_path: String,
     generics: Generics,
     instance_ident: Ident,
     generic_args: AngleBracketedGenericArguments,
     data_fields: Vec<Field>,
     class_fields: Vec<Field>,
+    writeback_fields: Vec<Field>,
+    /// Whether the struct has an `#[on_first_use]` function
+    first_use: bool,
 }
 
 pub(crate) fn into_java_value_macro_derive(input: DeriveInput) -> TokenStream {
@@ -31,6 +44,15 @@ pub(crate) fn into_java_value_macro_derive(input: DeriveInput) -> TokenStream {
 }
 
 fn into_java_value_macro_derive_impl(input: DeriveInput) -> syn::Result<TokenStream> {
+    if let Data::Enum(data) = &input.data {
+        return Ok(into_java_value_enum_derive(get_enum_impl_components(&input, data)));
+    }
+    if let Data::Struct(data) = &input.data {
+        if is_record(&input) {
+            return Ok(into_java_value_record_derive(get_record_impl_components(&input, data)));
+        }
+    }
+
     let TraitAutoDeriveData {
         instance_field_type_assertion,
         impl_target,
@@ -39,21 +61,24 @@ fn into_java_value_macro_derive_impl(input: DeriveInput) -> syn::Result<TokenStr
         generic_args,
         ..
     } = get_trait_impl_components("IntoJavaValue", input);
+    let where_clause = &generics.where_clause;
 
     Ok(quote! {
         #instance_field_type_assertion
 
         #[automatically_derived]
-        impl#generics ::robusta_jni::convert::IntoJavaValue<'env> for #impl_target#generic_args {
+        impl#generics ::robusta_jni::convert::IntoJavaValue<'env> for #impl_target#generic_args #where_clause {
             type Target = ::robusta_jni::jni::objects::JObject<'env>;
+            const NEW_LOCAL_REF: bool = true;
 
             fn into(self, env: &::robusta_jni::jni::JNIEnv<'env>) -> Self::Target {
-                ::robusta_jni::convert::IntoJavaValue::into(self, env)
+                // The local reference is handed over to the caller, so it must not be deleted here
+                self.#instance_ident.forget()
             }
         }
 
         #[automatically_derived]
-        impl#generics ::robusta_jni::convert::IntoJavaValue<'env> for &#impl_target#generic_args {
+        impl#generics ::robusta_jni::convert::IntoJavaValue<'env> for &#impl_target#generic_args #where_clause {
             type Target = ::robusta_jni::jni::objects::JObject<'env>;
 
             fn into(self, env: &::robusta_jni::jni::JNIEnv<'env>) -> Self::Target {
@@ -62,11 +87,11 @@ fn into_java_value_macro_derive_impl(input: DeriveInput) -> syn::Result<TokenStr
         }
 
         #[automatically_derived]
-        impl#generics ::robusta_jni::convert::IntoJavaValue<'env> for &mut #impl_target#generic_args {
+        impl#generics ::robusta_jni::convert::IntoJavaValue<'env> for &mut #impl_target#generic_args #where_clause {
             type Target = ::robusta_jni::jni::objects::JObject<'env>;
 
             fn into(self, env: &::robusta_jni::jni::JNIEnv<'env>) -> Self::Target {
-                ::robusta_jni::convert::IntoJavaValue::into(self, env)
+                self.#instance_ident.as_obj()
             }
         }
     })
@@ -81,6 +106,15 @@ pub(crate) fn tryinto_java_value_macro_derive(input: DeriveInput) -> TokenStream
 }
 
 fn tryinto_java_value_macro_derive_impl(input: DeriveInput) -> syn::Result<TokenStream> {
+    if let Data::Enum(data) = &input.data {
+        return Ok(tryinto_java_value_enum_derive(get_enum_impl_components(&input, data)));
+    }
+    if let Data::Struct(data) = &input.data {
+        if is_record(&input) {
+            return Ok(tryinto_java_value_record_derive(get_record_impl_components(&input, data)));
+        }
+    }
+
     let TraitAutoDeriveData {
         instance_field_type_assertion,
         impl_target,
@@ -89,21 +123,24 @@ fn tryinto_java_value_macro_derive_impl(input: DeriveInput) -> syn::Result<Token
         generic_args,
         ..
     } = get_trait_impl_components("TryIntoJavaValue", input);
+    let where_clause = &generics.where_clause;
 
     Ok(quote! {
         #instance_field_type_assertion
 
         #[automatically_derived]
-        impl#generics ::robusta_jni::convert::TryIntoJavaValue<'env> for #impl_target#generic_args {
+        impl#generics ::robusta_jni::convert::TryIntoJavaValue<'env> for #impl_target#generic_args #where_clause {
             type Target = ::robusta_jni::jni::objects::JObject<'env>;
+            const NEW_LOCAL_REF: bool = true;
 
             fn try_into(self, env: &::robusta_jni::jni::JNIEnv<'env>) -> ::robusta_jni::jni::errors::Result<Self::Target> {
-                ::robusta_jni::convert::TryIntoJavaValue::try_into(self, env)
+                // The local reference is handed over to the caller, so it must not be deleted here
+                Ok(self.#instance_ident.forget())
             }
         }
 
         #[automatically_derived]
-        impl#generics ::robusta_jni::convert::TryIntoJavaValue<'env> for &#impl_target#generic_args {
+        impl#generics ::robusta_jni::convert::TryIntoJavaValue<'env> for &#impl_target#generic_args #where_clause {
             type Target = ::robusta_jni::jni::objects::JObject<'env>;
 
             fn try_into(self, env: &::robusta_jni::jni::JNIEnv<'env>) -> ::robusta_jni::jni::errors::Result<Self::Target> {
@@ -112,11 +149,11 @@ fn tryinto_java_value_macro_derive_impl(input: DeriveInput) -> syn::Result<Token
         }
 
         #[automatically_derived]
-        impl#generics ::robusta_jni::convert::TryIntoJavaValue<'env> for &mut #impl_target#generic_args {
+        impl#generics ::robusta_jni::convert::TryIntoJavaValue<'env> for &mut #impl_target#generic_args #where_clause {
             type Target = ::robusta_jni::jni::objects::JObject<'env>;
 
             fn try_into(self, env: &::robusta_jni::jni::JNIEnv<'env>) -> ::robusta_jni::jni::errors::Result<Self::Target> {
-                ::robusta_jni::convert::TryIntoJavaValue::try_into(self, env)
+                Ok(self.#instance_ident.as_obj())
             }
         }
     })
@@ -131,16 +168,54 @@ pub(crate) fn from_java_value_macro_derive(input: DeriveInput) -> TokenStream {
 }
 
 fn from_java_value_macro_derive_impl(input: DeriveInput) -> syn::Result<TokenStream> {
+    if let Data::Enum(data) = &input.data {
+        return Ok(from_java_value_enum_derive(get_enum_impl_components(&input, data)));
+    }
+    if let Data::Struct(data) = &input.data {
+        if is_record(&input) {
+            return Ok(from_java_value_record_derive(get_record_impl_components(&input, data)));
+        }
+        if is_data_struct(&input) {
+            return Ok(from_java_value_data_struct_derive(get_data_struct_impl_components(&input, data)));
+        }
+    }
+
     let TraitAutoDeriveData {
         instance_field_type_assertion,
         impl_target,
-        classpath_path,
         generics,
         instance_ident,
         generic_args,
         data_fields,
         class_fields,
+        writeback_fields,
+        first_use,
     } = get_trait_impl_components("FromJavaValue", input);
+    let first_use = first_use_call(first_use, false);
+
+    let from_where_clause = with_field_bounds(
+        &generics,
+        data_fields.iter().filter(|f| !is_lazy_field(f)),
+        |ty| {
+            vec![
+                parse_quote! { #ty: ::robusta_jni::convert::FromJavaValue<'env, 'borrow> },
+            ]
+        },
+        &class_fields,
+        |ty| {
+            vec![
+                parse_quote! { #ty: ::robusta_jni::convert::IntoJavaValue<'env> + ::robusta_jni::convert::FromJavaValue<'env, 'borrow> },
+                parse_quote! { ::robusta_jni::jni::objects::JValue<'env>: ::core::convert::From<<#ty as ::robusta_jni::convert::IntoJavaValue<'env>>::Target> },
+            ]
+        },
+    );
+    let from_writeback_where_clause = with_field_bounds(
+        &generics,
+        writeback_fields.iter(),
+        |ty| vec![parse_quote! { #ty: ::robusta_jni::convert::IntoJavaValue<'env> }],
+        &[],
+        |_| vec![],
+    );
 
     let data_fields_struct_init: Vec<_> = data_fields
         .iter()
@@ -150,11 +225,14 @@ fn from_java_value_macro_derive_impl(input: DeriveInput) -> syn::Result<TokenStr
         let field_ident = f.ident.as_ref().unwrap();
         let field_name = field_ident.to_string();
         let field_type = &f.ty;
-        let field_type_sig = quote_spanned! { field_type.span() =>
-            <#field_type as Signature>::SIG_TYPE
-        };
+        if is_lazy_field(f) {
+            return quote_spanned! { f.span() =>
+                let #field_ident: #field_type = ::robusta_jni::convert::Lazy::new(source, #field_name, env);
+            };
+        }
+        let field_value = read_data_field(f, &generics, quote! { source });
         quote_spanned! { f.span() =>
-            let #field_ident: #field_type = ::robusta_jni::convert::FromJavaValue::from(::core::convert::TryInto::try_into(::robusta_jni::convert::JValueWrapper::from(env.get_field(source, #field_name, #field_type_sig).unwrap())).unwrap(), env);
+            let #field_ident: #field_type = ::robusta_jni::convert::FromJavaValue::from(::robusta_jni::convert::JniValue::from_jvalue(#field_value.unwrap(), env).unwrap(), env);
         }
     }).collect();
 
@@ -169,33 +247,62 @@ fn from_java_value_macro_derive_impl(input: DeriveInput) -> syn::Result<TokenStr
             let field_name = field_ident.to_string();
             let field_type = &f.ty;
 
+            if let Some(FieldAccessors { getter, setter }) = field_accessors(f) {
+                let (getter, setter) = (option_tokens(getter), option_tokens(setter));
+                return quote_spanned! { f.span() =>
+                    let #field_ident: #field_type = ::robusta_jni::convert::Field::accessors_from(source,
+                        &env.auto_local(<Self as ::robusta_jni::convert::JavaClass>::class(env).unwrap()),
+                        #getter,
+                        #setter,
+                        env);
+                };
+            }
+
             quote_spanned! { f.span() =>
                 let #field_ident: #field_type = ::robusta_jni::convert::Field::field_from(source,
-                    #classpath_path,
+                    &env.auto_local(<Self as ::robusta_jni::convert::JavaClass>::class(env).unwrap()),
                     #field_name,
                     env);
             }
         })
         .collect();
 
+    let writeback_fields_env_set: Vec<_> = writeback_fields.iter().filter_map(|f| {
+        let field_ident = f.ident.as_ref().unwrap();
+        let field_type_sig = data_field_signature(f, &generics);
+        let write = write_data_field(f, field_type_sig, quote! { self.#instance_ident.as_obj() }, quote_spanned! { f.span() =>
+            ::robusta_jni::convert::JavaValue::into_jvalue(::robusta_jni::convert::IntoJavaValue::into(self.#field_ident, env), env)
+        })?;
+        Some(quote_spanned! { f.span() => #write.unwrap(); })
+    }).collect();
+
     Ok(quote! {
         #instance_field_type_assertion
 
         #[automatically_derived]
-        impl#generics ::robusta_jni::convert::FromJavaValue<'env, 'borrow> for #impl_target#generic_args {
+        impl#generics ::robusta_jni::convert::FromJavaValue<'env, 'borrow> for #impl_target#generic_args #from_where_clause {
             type Source = ::robusta_jni::jni::objects::JObject<'env>;
 
             fn from(source: Self::Source, env: &'borrow ::robusta_jni::jni::JNIEnv<'env>) -> Self {
+                #first_use
                 #(#data_fields_env_init)*
                 #(#class_fields_env_init)*
 
                 Self {
                     #instance_ident: ::robusta_jni::jni::objects::AutoLocal::new(env, source),
-                    #(#data_fields_struct_init),*
-                    #(#class_fields_struct_init),*
+                    #(#data_fields_struct_init,)*
+                    #(#class_fields_struct_init,)*
                 }
             }
         }
+
+        #[automatically_derived]
+        impl#generics ::robusta_jni::convert::WriteBack<'env> for #impl_target#generic_args #from_writeback_where_clause {
+            #[allow(unused_variables)]
+            fn write_back(self, env: &::robusta_jni::jni::JNIEnv<'env>) {
+                #(#writeback_fields_env_set)*
+            }
+        }
     })
 }
 
@@ -208,16 +315,54 @@ pub(crate) fn tryfrom_java_value_macro_derive(input: DeriveInput) -> TokenStream
 }
 
 fn tryfrom_java_value_macro_derive_impl(input: DeriveInput) -> syn::Result<TokenStream> {
+    if let Data::Enum(data) = &input.data {
+        return Ok(tryfrom_java_value_enum_derive(get_enum_impl_components(&input, data)));
+    }
+    if let Data::Struct(data) = &input.data {
+        if is_record(&input) {
+            return Ok(tryfrom_java_value_record_derive(get_record_impl_components(&input, data)));
+        }
+        if is_data_struct(&input) {
+            return Ok(tryfrom_java_value_data_struct_derive(get_data_struct_impl_components(&input, data)));
+        }
+    }
+
     let TraitAutoDeriveData {
         instance_field_type_assertion,
         impl_target,
-        classpath_path,
         generics,
         instance_ident,
         generic_args,
         data_fields,
         class_fields,
+        writeback_fields,
+        first_use,
     } = get_trait_impl_components("FromJavaValue", input);
+    let first_use = first_use_call(first_use, true);
+
+    let try_from_where_clause = with_field_bounds(
+        &generics,
+        data_fields.iter().filter(|f| !is_lazy_field(f)),
+        |ty| {
+            vec![
+                parse_quote! { #ty: ::robusta_jni::convert::TryFromJavaValue<'env, 'borrow> },
+            ]
+        },
+        &class_fields,
+        |ty| {
+            vec![
+                parse_quote! { #ty: ::robusta_jni::convert::TryIntoJavaValue<'env> + ::robusta_jni::convert::TryFromJavaValue<'env, 'borrow> },
+                parse_quote! { ::robusta_jni::jni::objects::JValue<'env>: ::core::convert::From<<#ty as ::robusta_jni::convert::TryIntoJavaValue<'env>>::Target> },
+            ]
+        },
+    );
+    let try_from_writeback_where_clause = with_field_bounds(
+        &generics,
+        writeback_fields.iter(),
+        |ty| vec![parse_quote! { #ty: ::robusta_jni::convert::TryIntoJavaValue<'env> }],
+        &[],
+        |_| vec![],
+    );
 
     let data_fields_struct_init: Vec<_> = data_fields
         .iter()
@@ -227,11 +372,14 @@ fn tryfrom_java_value_macro_derive_impl(input: DeriveInput) -> syn::Result<Token
         let field_ident = f.ident.as_ref().unwrap();
         let field_name = field_ident.to_string();
         let field_type = &f.ty;
-        let field_type_sig = quote_spanned! { field_type.span() =>
-            <#field_type as Signature>::SIG_TYPE
-        };
+        if is_lazy_field(f) {
+            return quote_spanned! { f.span() =>
+                let #field_ident: #field_type = ::robusta_jni::convert::Lazy::new(source, #field_name, env);
+            };
+        }
+        let field_value = read_data_field(f, &generics, quote! { source });
         quote_spanned! { f.span() =>
-            let #field_ident: #field_type = ::robusta_jni::convert::TryFromJavaValue::try_from(::core::convert::TryInto::try_into(::robusta_jni::convert::JValueWrapper::from(env.get_field(source, #field_name, #field_type_sig)?))?, env)?;
+            let #field_ident: #field_type = ::robusta_jni::convert::TryFromJavaValue::try_from(::robusta_jni::convert::JniValue::from_jvalue(#field_value?, env)?, env)?;
         }
     }).collect();
 
@@ -244,40 +392,492 @@ fn tryfrom_java_value_macro_derive_impl(input: DeriveInput) -> syn::Result<Token
         let field_name = field_ident.to_string();
         let field_type = &f.ty;
 
+        if let Some(FieldAccessors { getter, setter }) = field_accessors(f) {
+            let (getter, setter) = (option_tokens(getter), option_tokens(setter));
+            return quote_spanned! { f.span() =>
+                let #field_ident: #field_type = ::robusta_jni::convert::Field::accessors_try_from(source,
+                    &env.auto_local(<Self as ::robusta_jni::convert::JavaClass>::class(env)?),
+                    #getter,
+                    #setter,
+                    env)?;
+            };
+        }
+
         quote_spanned! { f.span() =>
             let #field_ident: #field_type = ::robusta_jni::convert::Field::field_try_from(source,
-                #classpath_path,
+                &env.auto_local(<Self as ::robusta_jni::convert::JavaClass>::class(env)?),
                 #field_name,
                 env)?;
         }
     }).collect();
 
+    let writeback_fields_env_set: Vec<_> = writeback_fields.iter().filter_map(|f| {
+        let field_ident = f.ident.as_ref().unwrap();
+        let field_type_sig = data_field_signature(f, &generics);
+        let write = write_data_field(f, field_type_sig, quote! { self.#instance_ident.as_obj() }, quote_spanned! { f.span() =>
+            ::robusta_jni::convert::JavaValue::into_jvalue(::robusta_jni::convert::TryIntoJavaValue::try_into(self.#field_ident, env)?, env)
+        })?;
+        Some(quote_spanned! { f.span() => #write?; })
+    }).collect();
+
+    // `Global` and `WeakGlobal` handles are only supported for structs that are generic over lifetimes only
+    let global_impl = if generics
+        .params
+        .iter()
+        .all(|p| matches!(p, GenericParam::Lifetime(_)))
+    {
+        let static_args: Vec<_> = generics.params.iter().map(|_| quote! { 'static }).collect();
+
+        quote! {
+            #[automatically_derived]
+            impl ::robusta_jni::convert::GlobalInstance for #impl_target<#(#static_args),*> {
+                type Local<'env: 'borrow, 'borrow> = #impl_target#generic_args;
+            }
+
+            #[automatically_derived]
+            impl#generics #impl_target#generic_args {
+                /// Create a handle to the underlying Java object that can be stored and used from any thread.
+                pub fn to_global(&self, env: &::robusta_jni::jni::JNIEnv) -> ::robusta_jni::jni::errors::Result<::robusta_jni::convert::Global<#impl_target<#(#static_args),*>>> {
+                    ::robusta_jni::convert::Global::new(env, self.#instance_ident.as_obj())
+                }
+
+                /// Create a weak handle to the underlying Java object, which doesn't prevent it from being garbage collected.
+                pub fn to_weak(&self, env: &::robusta_jni::jni::JNIEnv) -> ::robusta_jni::jni::errors::Result<::robusta_jni::convert::WeakGlobal<#impl_target<#(#static_args),*>>> {
+                    ::robusta_jni::convert::WeakGlobal::new(env, self.#instance_ident.as_obj())
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
     Ok(quote! {
         #instance_field_type_assertion
 
+        #global_impl
+
         #[automatically_derived]
-        impl#generics ::robusta_jni::convert::TryFromJavaValue<'env, 'borrow> for #impl_target#generic_args {
+        impl#generics #impl_target#generic_args {
+            /// Description of the underlying Java object for debugging output, see [`describe`](::robusta_jni::convert::debug::describe).
+            pub fn describe(&self, env: &::robusta_jni::jni::JNIEnv) -> ::std::string::String {
+                ::robusta_jni::convert::debug::describe(env, self.#instance_ident.as_obj())
+            }
+
+            /// Raw reference to the underlying Java object, for use with other JNI libraries.
+            /// The reference remains owned by `self`.
+            pub fn as_raw(&self) -> ::robusta_jni::jni::sys::jobject {
+                self.#instance_ident.as_obj().into_raw()
+            }
+
+            /// Hand over the local reference to the underlying Java object, e.g. to other JNI libraries.
+            /// The reference is no longer deleted when `self` is dropped, and fields are not written back.
+            pub fn into_raw(self) -> ::robusta_jni::jni::sys::jobject {
+                self.#instance_ident.forget().into_raw()
+            }
+        }
+
+        #[automatically_derived]
+        impl#generics #impl_target#generic_args #try_from_where_clause {
+            /// Convert a raw reference obtained from other JNI libraries, taking ownership of it (it is deleted when the
+            /// returned value is dropped). This is the conversion done by [`TryFromJavaValue`](::robusta_jni::convert::TryFromJavaValue).
+            ///
+            /// # Safety
+            /// `obj` must be a valid local reference of the thread of `env` to an instance of the Java class of this struct,
+            /// which is not deleted elsewhere. Neither condition can be checked.
+            pub unsafe fn from_raw(env: &'borrow ::robusta_jni::jni::JNIEnv<'env>, obj: ::robusta_jni::jni::sys::jobject) -> ::robusta_jni::jni::errors::Result<Self> {
+                let obj = unsafe { ::robusta_jni::jni::objects::JObject::from_raw(obj) };
+                ::robusta_jni::convert::TryFromJavaValue::try_from(obj, env)
+            }
+        }
+
+        #[automatically_derived]
+        impl#generics ::robusta_jni::convert::TryFromJavaValue<'env, 'borrow> for #impl_target#generic_args #try_from_where_clause {
             type Source = ::robusta_jni::jni::objects::JObject<'env>;
 
             fn try_from(source: Self::Source, env: &'borrow ::robusta_jni::jni::JNIEnv<'env>) -> ::robusta_jni::jni::errors::Result<Self> {
+                #first_use
                 #(#data_fields_env_init)*
                 #(#class_fields_env_init)*
 
                 Ok(Self {
                     #instance_ident: ::robusta_jni::jni::objects::AutoLocal::new(env, source),
-                    #(#data_fields_struct_init),*
-                    #(#class_fields_struct_init),*
+                    #(#data_fields_struct_init,)*
+                    #(#class_fields_struct_init,)*
+                })
+            }
+        }
+
+        #[automatically_derived]
+        impl#generics ::robusta_jni::convert::TryWriteBack<'env> for #impl_target#generic_args #try_from_writeback_where_clause {
+            #[allow(unused_variables)]
+            fn try_write_back(self, env: &::robusta_jni::jni::JNIEnv<'env>) -> ::robusta_jni::jni::errors::Result<()> {
+                #(#writeback_fields_env_set)*
+                Ok(())
+            }
+        }
+    })
+}
+
+pub(crate) fn into_global_macro_derive(input: DeriveInput) -> TokenStream {
+    let input_span = input.span();
+    match into_global_macro_derive_impl(input) {
+        Ok(t) => t,
+        Err(_) => quote_spanned! { input_span => },
+    }
+}
+
+fn into_global_macro_derive_impl(input: DeriveInput) -> syn::Result<TokenStream> {
+    if !input
+        .generics
+        .params
+        .iter()
+        .all(|p| matches!(p, GenericParam::Lifetime(_)))
+    {
+        abort!(input.generics, "`IntoGlobal` can only be derived for structs that are generic over lifetimes only")
+    }
+
+    let vis = input.vis.clone();
+    let TraitAutoDeriveData {
+        impl_target,
+        generics,
+        instance_ident,
+        generic_args,
+        data_fields,
+        ..
+    } = get_trait_impl_components("IntoGlobal", input);
+
+    // Fields borrowing from the environment (e.g. `Lazy` handles) are read again from the Java instance when converting back
+    let owned_fields: Vec<_> = data_fields.iter().filter(|f| !mentions_lifetimes(&f.ty)).collect();
+    let owned_idents: Vec<_> = owned_fields.iter().map(|f| f.ident.as_ref().unwrap()).collect();
+    let owned_vis = owned_fields.iter().map(|f| &f.vis);
+    let owned_types = owned_fields.iter().map(|f| &f.ty);
+
+    let global_ident = format_ident!("{}Global", impl_target);
+    let static_args: Vec<_> = generics.params.iter().map(|_| quote! { 'static }).collect();
+    let global_type = quote! { ::robusta_jni::convert::Global<#impl_target<#(#static_args),*>> };
+
+    let global_doc = format!(
+        "Owned version of [`{0}`], holding a global reference to the Java object and a copy of its data fields. \
+        It isn't bound to a `JNIEnv`, so it can be stored (e.g. in a `static`) and sent to other threads.",
+        impl_target
+    );
+
+    Ok(quote! {
+        #[doc = #global_doc]
+        #vis struct #global_ident {
+            #instance_ident: #global_type,
+            #(#owned_vis #owned_idents: #owned_types,)*
+        }
+
+        #[automatically_derived]
+        impl#generics #impl_target#generic_args {
+            /// Convert into an owned value that isn't bound to `env`, moving the data fields.
+            pub fn into_global(self, env: &::robusta_jni::jni::JNIEnv) -> ::robusta_jni::jni::errors::Result<#global_ident> {
+                Ok(#global_ident {
+                    #instance_ident: ::robusta_jni::convert::Global::new(env, self.#instance_ident.as_obj())?,
+                    #(#owned_idents: self.#owned_idents,)*
+                })
+            }
+        }
+
+        #[automatically_derived]
+        impl #global_ident {
+            /// Handle to the Java object.
+            pub fn global(&self) -> &#global_type {
+                &self.#instance_ident
+            }
+
+            /// Convert back into a local instance bound to `env`, moving the data fields.
+            /// Fields that aren't copied by the owned value are read again from the Java object.
+            pub fn into_local#generics(self, env: &'borrow ::robusta_jni::jni::JNIEnv<'env>) -> ::robusta_jni::jni::errors::Result<#impl_target#generic_args> {
+                let local = env.new_local_ref(self.#instance_ident.global_ref().as_obj())?;
+                #[allow(unused_mut)]
+                let mut instance: #impl_target#generic_args = ::robusta_jni::convert::TryFromJavaValue::try_from(local, env)?;
+                #(instance.#owned_idents = self.#owned_idents;)*
+                Ok(instance)
+            }
+        }
+    })
+}
+
+pub(crate) fn into_data_macro_derive(input: DeriveInput) -> TokenStream {
+    let input_span = input.span();
+    match into_data_macro_derive_impl(input) {
+        Ok(t) => t,
+        Err(_) => quote_spanned! { input_span => },
+    }
+}
+
+fn into_data_macro_derive_impl(input: DeriveInput) -> syn::Result<TokenStream> {
+    if !input
+        .generics
+        .params
+        .iter()
+        .all(|p| matches!(p, GenericParam::Lifetime(_)))
+    {
+        abort!(input.generics, "`IntoData` can only be derived for structs that are generic over lifetimes only")
+    }
+
+    let vis = input.vis.clone();
+    let TraitAutoDeriveData {
+        impl_target,
+        generics,
+        generic_args,
+        data_fields,
+        class_fields,
+        ..
+    } = get_trait_impl_components("IntoData", input);
+
+    // `Lazy` and `#[field]` fields hold their value type, other fields are kept as they are.
+    // Fields whose (value) type borrows from the environment have no owned counterpart and are left out.
+    let fields: Vec<(&Field, &Type, DataFieldKind)> = data_fields
+        .iter()
+        .map(|f| match is_lazy_field(f) {
+            true => (f, field_value_type(&f.ty), DataFieldKind::Lazy),
+            false => (f, Some(&f.ty), DataFieldKind::Plain),
+        })
+        .chain(class_fields.iter().map(|f| (f, field_value_type(&f.ty), DataFieldKind::Class)))
+        .filter_map(|(f, ty, kind)| ty.filter(|ty| !mentions_lifetimes(ty)).map(|ty| (f, ty, kind)))
+        .collect();
+
+    let idents: Vec<_> = fields.iter().map(|(f, _, _)| f.ident.as_ref().unwrap()).collect();
+    let field_vis = fields.iter().map(|(f, _, _)| &f.vis);
+    let types = fields.iter().map(|(_, ty, _)| ty);
+
+    let values = fields.iter().map(|(f, _, kind)| {
+        let ident = f.ident.as_ref().unwrap();
+        match kind {
+            DataFieldKind::Plain => quote_spanned! { f.span() => self.#ident },
+            DataFieldKind::Lazy => quote_spanned! { f.span() => {
+                self.#ident.get()?;
+                self.#ident.into_inner().unwrap()
+            }},
+            DataFieldKind::Class => quote_spanned! { f.span() => self.#ident.get()? },
+        }
+    });
+
+    // Properties without a setter are left out
+    let field_sets = fields.iter().filter_map(|(f, ty, kind)| {
+        let ident = f.ident.as_ref().unwrap();
+        let sig = match kind {
+            DataFieldKind::Plain => data_field_signature(f, &generics),
+            DataFieldKind::Lazy | DataFieldKind::Class => quote_spanned! { ty.span() => <#ty as ::robusta_jni::convert::Signature>::SIG_TYPE },
+        };
+        let write = write_data_field(f, sig, quote! { obj }, quote_spanned! { f.span() =>
+            ::robusta_jni::convert::JavaValue::into_jvalue(::robusta_jni::convert::TryIntoJavaValue::try_into(self.#ident, env)?, env)
+        })?;
+        Some(quote_spanned! { f.span() => #write?; })
+    });
+
+    let data_ident = format_ident!("{}Data", impl_target);
+    let data_doc = format!(
+        "Plain copy of the data of [`{0}`], holding the values of its data, `Lazy` and `#[field]` fields. \
+        It has no reference to the Java object and isn't bound to a `JNIEnv`, so it can be handed to code that doesn't use JNI.",
+        impl_target
+    );
+
+    Ok(quote! {
+        #[doc = #data_doc]
+        #vis struct #data_ident {
+            #(#field_vis #idents: #types,)*
+        }
+
+        #[automatically_derived]
+        impl#generics #impl_target#generic_args {
+            /// Convert into a plain copy of the data, moving the data fields and reading `Lazy` and `#[field]` fields.
+            pub fn into_data(self) -> ::robusta_jni::jni::errors::Result<#data_ident> {
+                Ok(#data_ident {
+                    #(#idents: #values,)*
                 })
             }
         }
+
+        #[automatically_derived]
+        impl#generics ::core::convert::TryFrom<#impl_target#generic_args> for #data_ident {
+            type Error = ::robusta_jni::jni::errors::Error;
+
+            fn try_from(value: #impl_target#generic_args) -> ::robusta_jni::jni::errors::Result<Self> {
+                value.into_data()
+            }
+        }
+
+        #[automatically_derived]
+        impl #data_ident {
+            /// Create a new Java object holding the values, and convert it.
+            /// The object is allocated without running any constructor, and fields that aren't copied keep their default value.
+            pub fn into_java#generics(self, env: &'borrow ::robusta_jni::jni::JNIEnv<'env>) -> ::robusta_jni::jni::errors::Result<#impl_target#generic_args> {
+                let obj = env.alloc_object(&env.auto_local(<#impl_target#generic_args as ::robusta_jni::convert::JavaClass>::class(env)?))?;
+                #(#field_sets)*
+                ::robusta_jni::convert::TryFromJavaValue::try_from(obj, env)
+            }
+        }
     })
 }
 
+/// Data needed to derive conversion traits for structs without an `#[instance]` field, which are converted by value
+struct DataStructDeriveData {
+    impl_target: Ident,
+    data_fields: Vec<Field>,
+    /// Whether the struct has an `#[on_first_use]` function
+    first_use: bool,
+}
+
+/// Whether the struct only has data fields, with no `#[instance]` field to hold the Java object
+fn is_data_struct(input: &DeriveInput) -> bool {
+    match &input.data {
+        Data::Struct(DataStruct { fields, .. }) => !fields
+            .iter()
+            .any(|f| f.attrs.iter().any(|a| a.path().is_ident("instance"))),
+        _ => false,
+    }
+}
+
+fn get_data_struct_impl_components(input: &DeriveInput, data: &DataStruct) -> DataStructDeriveData {
+    let input_span = input.span();
+
+    if !input.generics.params.is_empty() {
+        emit_error!(input.generics, "structs without an `#[instance]` field cannot have generic parameters";
+            help = "they are converted by value, and don't hold a reference to the Java object: add an `#[instance]` field to keep it")
+    }
+
+    let fields: Vec<Field> = match &data.fields {
+        Fields::Named(fields) => fields.named.iter().cloned().collect(),
+        Fields::Unit => Vec::new(),
+        Fields::Unnamed(_) => abort!(input_span, "structs without an `#[instance]` field must have named fields, matching the Java fields"),
+    };
+
+    fields
+        .iter()
+        .for_each(|f| check_helper_attributes(&f.attrs, FIELD_ATTRIBUTES, "struct fields"));
+
+    for field in &fields {
+        if is_lazy_field(field) {
+            emit_error!(field, "`Lazy` fields require an `#[instance]` field";
+                note = "structs without an `#[instance]` field don't hold a reference to the Java object")
+        }
+
+        for attr in &field.attrs {
+            if attr.path().is_ident("writeback") {
+                emit_error!(attr, "`#[writeback]` fields require an `#[instance]` field";
+                    note = "structs without an `#[instance]` field don't hold a reference to the Java object")
+            } else if attr.path().is_ident("field") {
+                // Only `#[field(getter = "...")]` fields hold a value, `#[field]` and `#[field(lazy)]` are handles
+                let has_accessors = attr.meta.require_list().is_ok() && attr.parse_args::<Ident>().is_err();
+                if !has_accessors || is_field_handle(field) {
+                    emit_error!(attr, "`#[field]` handles require an `#[instance]` field";
+                        note = "structs without an `#[instance]` field don't hold a reference to the Java object")
+                } else {
+                    match parse_field_accessors(attr) {
+                        Err(e) => emit_error!(e.span(), "{}", e),
+                        Ok(FieldAccessors { getter: None, .. }) => emit_error!(field, "missing `getter` for field read during conversion"),
+                        Ok(_) => {}
+                    }
+                }
+            }
+        }
+    }
+
+    DataStructDeriveData {
+        impl_target: input.ident.clone(),
+        data_fields: fields,
+        first_use: has_first_use_hook(&input.attrs),
+    }
+}
+
+fn tryfrom_java_value_data_struct_derive(data: DataStructDeriveData) -> TokenStream {
+    let DataStructDeriveData {
+        impl_target,
+        data_fields,
+        first_use,
+    } = data;
+    let first_use = first_use_call(first_use, true);
+
+    let idents: Vec<_> = data_fields.iter().map(|f| f.ident.as_ref().unwrap()).collect();
+    let fields_env_init: Vec<_> = data_fields.iter().map(|f| {
+        let field_ident = f.ident.as_ref().unwrap();
+        let field_type = &f.ty;
+        let field_value = read_data_field(f, &Generics::default(), quote! { source });
+        quote_spanned! { f.span() =>
+            let #field_ident: #field_type = ::robusta_jni::convert::TryFromJavaValue::try_from(::robusta_jni::convert::JniValue::from_jvalue(#field_value?, env)?, env)?;
+        }
+    }).collect();
+
+    quote! {
+        #[automatically_derived]
+        impl<'env: 'borrow, 'borrow> ::robusta_jni::convert::TryFromJavaValue<'env, 'borrow> for #impl_target {
+            type Source = ::robusta_jni::jni::objects::JObject<'env>;
+            const KEEPS_SOURCE_REF: bool = false;
+
+            #[allow(unused_variables)]
+            fn try_from(source: Self::Source, env: &'borrow ::robusta_jni::jni::JNIEnv<'env>) -> ::robusta_jni::jni::errors::Result<Self> {
+                #first_use
+                #(#fields_env_init)*
+
+                Ok(#impl_target {
+                    #(#idents,)*
+                })
+            }
+        }
+    }
+}
+
+fn from_java_value_data_struct_derive(data: DataStructDeriveData) -> TokenStream {
+    let DataStructDeriveData {
+        impl_target,
+        data_fields,
+        first_use,
+    } = data;
+    let first_use = first_use_call(first_use, false);
+
+    let idents: Vec<_> = data_fields.iter().map(|f| f.ident.as_ref().unwrap()).collect();
+    let fields_env_init: Vec<_> = data_fields.iter().map(|f| {
+        let field_ident = f.ident.as_ref().unwrap();
+        let field_type = &f.ty;
+        let field_value = read_data_field(f, &Generics::default(), quote! { source });
+        quote_spanned! { f.span() =>
+            let #field_ident: #field_type = ::robusta_jni::convert::FromJavaValue::from(::robusta_jni::convert::JniValue::from_jvalue(#field_value.unwrap(), env).unwrap(), env);
+        }
+    }).collect();
+
+    quote! {
+        #[automatically_derived]
+        impl<'env: 'borrow, 'borrow> ::robusta_jni::convert::FromJavaValue<'env, 'borrow> for #impl_target {
+            type Source = ::robusta_jni::jni::objects::JObject<'env>;
+            const KEEPS_SOURCE_REF: bool = false;
+
+            #[allow(unused_variables)]
+            fn from(source: Self::Source, env: &'borrow ::robusta_jni::jni::JNIEnv<'env>) -> Self {
+                #first_use
+                #(#fields_env_init)*
+
+                #impl_target {
+                    #(#idents,)*
+                }
+            }
+        }
+    }
+}
+
+/// How the value of a field is stored in a derived struct
+enum DataFieldKind {
+    /// Converted when the struct is converted
+    Plain,
+    /// `Lazy` handle, converted on first access
+    Lazy,
+    /// `#[field]` handle, converted on each access
+    Class,
+}
+
 fn get_trait_impl_components(trait_name: &str, input: DeriveInput) -> TraitAutoDeriveData {
     let input_span = input.span();
-    let input_ident = &input.ident;
 
     match input.data {
+        Data::Struct(_) if is_record(&input) => abort!(
+            input,
+            "`{}` cannot be derived for records",
+            trait_name;
+            note = "records are converted by value, and don't hold a reference to the Java object"
+        ),
         Data::Struct(DataStruct { fields, .. }) => {
             let package_attr = input.attrs.iter().find(|a| {
                 a.path().get_ident().map(ToString::to_string).as_deref() == Some("package")
@@ -286,22 +886,10 @@ fn get_trait_impl_components(trait_name: &str, input: DeriveInput) -> TraitAutoD
                 abort!(input_span, "missing `#[package]` attribute")
             }
 
-            let classpath_path = package_attr
-                .unwrap()
-                .parse_args()
-                .map(|p: JavaPath| p.to_classpath_path())
-                .map(|s| {
-                    let mut s = s.clone();
-                    if !s.is_empty() {
-                        s.push('/');
-                    }
-                    s.push_str(&input_ident.to_string());
-                    s
-                })
-                .unwrap_or_else(|_| {
-                    emit_error!(package_attr, "invalid Java class path");
-                    "".to_string()
-                });
+            // The class path itself is used by the `Signature` derive, through `JavaClass`
+            if package_attr.unwrap().parse_args::<JavaPath>().is_err() {
+                emit_error!(package_attr, "invalid Java class path");
+            }
 
             let lifetimes: HashMap<String, &LifetimeParam> = input
                 .generics
@@ -331,6 +919,17 @@ fn get_trait_impl_components(trait_name: &str, input: DeriveInput) -> TraitAutoD
                 ),
             }
 
+            fields
+                .iter()
+                .for_each(|f| check_helper_attributes(&f.attrs, FIELD_ATTRIBUTES, "struct fields"));
+
+            // `#[bridge]` replaces lazy fields with accessor methods, which derive macros can't do
+            fields
+                .iter()
+                .flat_map(|f| &f.attrs)
+                .filter(|a| a.path().is_ident("field") && a.parse_args::<Ident>().is_ok_and(|i| i == "lazy"))
+                .for_each(|a| emit_error!(a, "`#[field(lazy)]` can only be used on structs declared in a `#[bridge]` module"));
+
             let instance_fields: Vec<_> = fields
                 .iter()
                 .filter_map(|f| {
@@ -341,13 +940,21 @@ fn get_trait_impl_components(trait_name: &str, input: DeriveInput) -> TraitAutoD
                 })
                 .collect();
 
+            fields
+                .iter()
+                .flat_map(|f| &f.attrs)
+                .filter(|a| a.path().is_ident("field") && a.meta.require_list().is_ok() && a.parse_args::<Ident>().is_err())
+                .filter_map(|a| parse_field_accessors(a).err())
+                .for_each(|e| emit_error!(e.span(), "{}", e));
+
+            // Fields with accessor methods are only `Field` handles if declared as such, otherwise they hold a value
             let class_fields: Vec<_> = fields
                 .iter()
                 .filter(|f| {
                     let attr = f.attrs.iter().find(|a| {
                         a.path().get_ident().map(|i| i.to_string()).as_deref() == Some("field")
                     });
-                    attr.is_some()
+                    attr.is_some() && (field_accessors(f).is_none() || is_field_handle(f))
                 })
                 .collect();
 
@@ -358,7 +965,7 @@ fn get_trait_impl_components(trait_name: &str, input: DeriveInput) -> TraitAutoD
                 )
             }
 
-            let instance_field_data = instance_fields.get(0);
+            let instance_field_data = instance_fields.first();
 
             match instance_field_data {
                 None => abort!(input_span, "missing `#[instance] field attribute"),
@@ -412,15 +1019,75 @@ fn get_trait_impl_components(trait_name: &str, input: DeriveInput) -> TraitAutoD
                         .cloned()
                         .collect();
 
+                    let has_writeback_attribute = |f: &Field| {
+                        f.attrs.iter().any(|a| {
+                            a.path().get_ident().map(|i| i.to_string()).as_deref()
+                                == Some("writeback")
+                        })
+                    };
+
+                    if has_writeback_attribute(instance) {
+                        emit_error!(instance, "`#[writeback]` attribute cannot be used on the `#[instance]` field")
+                    }
+
+                    if let Some(field_attribute) = instance.attrs.iter().find(|a| a.path().is_ident("field")) {
+                        emit_error!(field_attribute, "`#[field]` attribute cannot be used on the `#[instance]` field";
+                            help = "the `#[instance]` field holds the Java object itself")
+                    }
+
+                    class_fields
+                        .iter()
+                        .filter(|f| has_writeback_attribute(f))
+                        .for_each(|f| {
+                            emit_error!(f, "`#[writeback]` attribute cannot be used on `#[field]` fields";
+                                note = "`Field` handles already write through to the Java instance")
+                        });
+
+                    data_fields
+                        .iter()
+                        .filter(|f| has_writeback_attribute(f) && is_lazy_field(f))
+                        .for_each(|f| {
+                            emit_error!(f, "`#[writeback]` attribute cannot be used on `Lazy` fields";
+                                help = "use a `#[field]` field to write to the Java instance")
+                        });
+
+                    data_fields
+                        .iter()
+                        .filter(|f| is_lazy_field(f) && field_accessors(f).is_some())
+                        .for_each(|f| {
+                            emit_error!(f, "accessor methods cannot be used on `Lazy` fields";
+                                help = "use a `Field` handle or a plain value instead")
+                        });
+
+                    data_fields
+                        .iter()
+                        .filter(|f| field_accessors(f).is_some_and(|a| a.getter.is_none()))
+                        .for_each(|f| {
+                            emit_error!(f, "missing `getter` for field read during conversion";
+                                help = "use a `Field` handle for write-only properties")
+                        });
+
+                    data_fields
+                        .iter()
+                        .filter(|f| has_writeback_attribute(f) && field_accessors(f).is_some_and(|a| a.setter.is_none()))
+                        .for_each(|f| emit_error!(f, "`#[writeback]` fields with a `getter` must also have a `setter`"));
+
+                    let writeback_fields: Vec<_> = data_fields
+                        .iter()
+                        .filter(|f| has_writeback_attribute(f) && !is_lazy_field(f))
+                        .cloned()
+                        .collect();
+
                     TraitAutoDeriveData {
                         instance_field_type_assertion,
+                        first_use: has_first_use_hook(&input.attrs),
                         impl_target: input.ident,
-                        classpath_path,
                         generics,
                         instance_ident: instance_ident.clone(),
                         generic_args,
                         data_fields,
                         class_fields: class_fields.into_iter().cloned().collect(),
+                        writeback_fields,
                     }
                 }
             }
@@ -432,3 +1099,204 @@ fn get_trait_impl_components(trait_name: &str, input: DeriveInput) -> TraitAutoD
         ),
     }
 }
+
+/// Whether the field is a `Lazy` handle, which is read from the Java instance on first access instead of during conversion
+fn is_lazy_field(field: &Field) -> bool {
+    match &field.ty {
+        Type::Path(TypePath { path, .. }) => path.segments.last().is_some_and(|s| s.ident == "Lazy"),
+        _ => false,
+    }
+}
+
+/// Whether the field is a `Field` handle
+fn is_field_handle(field: &Field) -> bool {
+    match &field.ty {
+        Type::Path(TypePath { path, .. }) => path.segments.last().is_some_and(|s| s.ident == "Field"),
+        _ => false,
+    }
+}
+
+/// Accessor methods of a field, given with `#[field(getter = "getName", setter = "setName")]`
+struct FieldAccessors {
+    getter: Option<String>,
+    setter: Option<String>,
+}
+
+/// Accessor methods declared in the `#[field]` attribute of `field`, if any
+fn field_accessors(field: &Field) -> Option<FieldAccessors> {
+    let attr = field.attrs.iter().find(|a| a.path().is_ident("field"))?;
+    // `#[field]` and `#[field(lazy)]`
+    if attr.meta.require_list().is_err() || attr.parse_args::<Ident>().is_ok() {
+        return None;
+    }
+
+    parse_field_accessors(attr).ok()
+}
+
+fn parse_field_accessors(attr: &Attribute) -> syn::Result<FieldAccessors> {
+    let mut accessors = FieldAccessors { getter: None, setter: None };
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("getter") {
+            accessors.getter = Some(meta.value()?.parse::<LitStr>()?.value());
+            Ok(())
+        } else if meta.path.is_ident("setter") {
+            accessors.setter = Some(meta.value()?.parse::<LitStr>()?.value());
+            Ok(())
+        } else {
+            Err(meta.error("expected `getter = \"...\"` or `setter = \"...\"`"))
+        }
+    })?;
+
+    Ok(accessors)
+}
+
+fn option_tokens(value: Option<String>) -> TokenStream {
+    match value {
+        Some(value) => quote! { ::core::option::Option::Some(#value) },
+        None => quote! { ::core::option::Option::None },
+    }
+}
+
+/// Expression reading the `JValue` of data field `field` from the Java object `source`, as a `jni::errors::Result`:
+/// with its getter if it has one, otherwise from the Java field with the same name
+fn read_data_field(field: &Field, generics: &Generics, source: TokenStream) -> TokenStream {
+    let sig = data_field_signature(field, generics);
+
+    match field_accessors(field).and_then(|a| a.getter) {
+        Some(getter) => quote_spanned! { field.span() =>
+            env.call_method(#source, #getter, ::std::format!("(){}", #sig), &[])
+        },
+        None => {
+            let field_name = field.ident.as_ref().unwrap().to_string();
+            quote_spanned! { field.span() => env.get_field(#source, #field_name, #sig) }
+        }
+    }
+}
+
+/// Expression writing `value` to data field `field` of the Java object `obj`, as a `jni::errors::Result`: with its
+/// setter if it has accessor methods, otherwise to the Java field with the same name.
+///
+/// Returns `None` if the field has accessor methods but no setter.
+fn write_data_field(field: &Field, sig: TokenStream, obj: TokenStream, value: TokenStream) -> Option<TokenStream> {
+    match field_accessors(field) {
+        Some(FieldAccessors { setter: Some(setter), .. }) => Some(quote_spanned! { field.span() =>
+            env.call_method(#obj, #setter, ::std::format!("({})V", #sig), &[#value])
+        }),
+        Some(FieldAccessors { setter: None, .. }) => None,
+        None => {
+            let field_name = field.ident.as_ref().unwrap().to_string();
+            Some(quote_spanned! { field.span() => env.set_field(#obj, #field_name, #sig, #value) })
+        }
+    }
+}
+
+/// `where` clause of a derived impl: the predicates declared on the struct, plus `field_bounds` for each data field
+/// and `class_field_bounds` for the value type of each `#[field]` field, when their type depends on a type parameter
+fn with_field_bounds<'a>(
+    generics: &Generics,
+    data_fields: impl Iterator<Item = &'a Field>,
+    field_bounds: impl Fn(&Type) -> Vec<WherePredicate>,
+    class_fields: &[Field],
+    class_field_bounds: impl Fn(&Type) -> Vec<WherePredicate>,
+) -> Option<WhereClause> {
+    let type_params: Vec<&Ident> = generics.type_params().map(|p| &p.ident).collect();
+    let mut where_clause = generics.where_clause.clone().unwrap_or_else(|| WhereClause {
+        where_token: Default::default(),
+        predicates: Default::default(),
+    });
+
+    for field in data_fields {
+        if mentions_type_params(&field.ty, &type_params) {
+            where_clause.predicates.extend(field_bounds(&field.ty));
+        }
+    }
+
+    // `Field<'env, 'borrow, T>` handles are converted through their value type `T`
+    for field in class_fields {
+        if let Some(ty) = field_value_type(&field.ty).filter(|ty| mentions_type_params(ty, &type_params)) {
+            where_clause.predicates.extend(class_field_bounds(ty));
+        }
+    }
+
+    if where_clause.predicates.is_empty() {
+        None
+    } else {
+        Some(where_clause)
+    }
+}
+
+/// Value type `T` of a field handle such as `Field<'env, 'borrow, T>` or `Lazy<'env, 'borrow, T>`
+fn field_value_type(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Path(TypePath { path, .. }) => path.segments.last().and_then(|s| match &s.arguments {
+            PathArguments::AngleBracketed(a) => a.args.iter().rev().find_map(|g| match g {
+                GenericArgument::Type(t) => Some(t),
+                _ => None,
+            }),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+/// Signature of the Java field backing a data field.
+///
+/// Fields typed with a bare type parameter are declared with a type variable on the Java side, which is erased to `Object`.
+fn data_field_signature(field: &Field, generics: &Generics) -> TokenStream {
+    let field_type = &field.ty;
+    let is_type_param = match field_type {
+        Type::Path(TypePath { qself: None, path }) => path
+            .get_ident()
+            .is_some_and(|i| generics.type_params().any(|p| p.ident == *i)),
+        _ => false,
+    };
+
+    if is_type_param {
+        quote! { "Ljava/lang/Object;" }
+    } else {
+        quote_spanned! { field_type.span() =>
+            <#field_type as ::robusta_jni::convert::Signature>::SIG_TYPE
+        }
+    }
+}
+
+/// Whether `ty` has any lifetime, e.g. `Lazy<'env, 'borrow, String>`
+fn mentions_lifetimes(ty: &Type) -> bool {
+    struct LifetimeVisitor {
+        found: bool,
+    }
+
+    impl<'ast> Visit<'ast> for LifetimeVisitor {
+        fn visit_lifetime(&mut self, _: &'ast Lifetime) {
+            self.found = true;
+        }
+    }
+
+    let mut visitor = LifetimeVisitor { found: false };
+    visitor.visit_type(ty);
+    visitor.found
+}
+
+/// Whether `ty` refers to any of `type_params`
+fn mentions_type_params(ty: &Type, type_params: &[&Ident]) -> bool {
+    struct TypeParamVisitor<'a> {
+        type_params: &'a [&'a Ident],
+        found: bool,
+    }
+
+    impl<'a, 'ast> Visit<'ast> for TypeParamVisitor<'a> {
+        fn visit_type_path(&mut self, t: &'ast TypePath) {
+            if t.qself.is_none() && t.path.segments.first().is_some_and(|s| self.type_params.contains(&&s.ident)) {
+                self.found = true;
+            }
+            visit::visit_type_path(self, t);
+        }
+    }
+
+    let mut visitor = TypeParamVisitor {
+        type_params,
+        found: false,
+    };
+    visitor.visit_type(ty);
+    visitor.found
+}
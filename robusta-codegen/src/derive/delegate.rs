@@ -0,0 +1,113 @@
+use proc_macro2::TokenStream;
+use proc_macro_error::abort;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{parse_quote, Data, DataStruct, DeriveInput, Fields, GenericParam, Generics, Type};
+
+pub(crate) fn java_delegate_macro_derive(input: DeriveInput) -> TokenStream {
+    let input_span = input.span();
+
+    let inner_type: Type = match &input.data {
+        Data::Struct(DataStruct {
+            fields: Fields::Unnamed(fields),
+            ..
+        }) if fields.unnamed.len() == 1 => fields.unnamed[0].ty.clone(),
+        _ => abort!(input_span, "`JavaDelegate` can only be derived for tuple structs with a single field";
+            help = "use e.g. `struct Names(Box<[String]>);`"),
+    };
+
+    let struct_name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let env_generics = with_lifetimes(&input.generics, &[parse_quote! { '__env }]);
+    let (env_impl_generics, _, _) = env_generics.split_for_impl();
+    let borrow_generics = with_lifetimes(
+        &input.generics,
+        &[parse_quote! { '__env: '__borrow }, parse_quote! { '__borrow }],
+    );
+    let (borrow_impl_generics, _, _) = borrow_generics.split_for_impl();
+    let where_predicates = where_clause.map(|w| &w.predicates);
+
+    quote! {
+        #[automatically_derived]
+        impl #impl_generics ::robusta_jni::convert::Signature for #struct_name #ty_generics #where_clause {
+            const SIG_TYPE: &'static str = <#inner_type as ::robusta_jni::convert::Signature>::SIG_TYPE;
+        }
+
+        // The higher-ranked bound defers the check to use sites, so that the derive also works for inner types that can't be array elements
+        #[automatically_derived]
+        impl #impl_generics ::robusta_jni::convert::ArrayElement for #struct_name #ty_generics
+        where
+            for<'__a> #inner_type: ::robusta_jni::convert::ArrayElement,
+            #where_predicates
+        {
+            const ARRAY_SIG_TYPE: &'static str = <#inner_type as ::robusta_jni::convert::ArrayElement>::ARRAY_SIG_TYPE;
+            const NESTED_ARRAY_SIG_TYPES: &'static [&'static str] = <#inner_type as ::robusta_jni::convert::ArrayElement>::NESTED_ARRAY_SIG_TYPES;
+        }
+
+        #[automatically_derived]
+        impl #env_impl_generics ::robusta_jni::convert::TryIntoJavaValue<'__env> for #struct_name #ty_generics
+        where
+            #inner_type: ::robusta_jni::convert::TryIntoJavaValue<'__env>,
+            #where_predicates
+        {
+            type Target = <#inner_type as ::robusta_jni::convert::TryIntoJavaValue<'__env>>::Target;
+            const NEW_LOCAL_REF: bool = <#inner_type as ::robusta_jni::convert::TryIntoJavaValue<'__env>>::NEW_LOCAL_REF;
+
+            fn try_into(self, env: &::robusta_jni::jni::JNIEnv<'__env>) -> ::robusta_jni::jni::errors::Result<Self::Target> {
+                ::robusta_jni::convert::TryIntoJavaValue::try_into(self.0, env)
+            }
+        }
+
+        #[automatically_derived]
+        impl #env_impl_generics ::robusta_jni::convert::IntoJavaValue<'__env> for #struct_name #ty_generics
+        where
+            #inner_type: ::robusta_jni::convert::IntoJavaValue<'__env>,
+            #where_predicates
+        {
+            type Target = <#inner_type as ::robusta_jni::convert::IntoJavaValue<'__env>>::Target;
+            const NEW_LOCAL_REF: bool = <#inner_type as ::robusta_jni::convert::IntoJavaValue<'__env>>::NEW_LOCAL_REF;
+
+            fn into(self, env: &::robusta_jni::jni::JNIEnv<'__env>) -> Self::Target {
+                ::robusta_jni::convert::IntoJavaValue::into(self.0, env)
+            }
+        }
+
+        #[automatically_derived]
+        impl #borrow_impl_generics ::robusta_jni::convert::TryFromJavaValue<'__env, '__borrow> for #struct_name #ty_generics
+        where
+            #inner_type: ::robusta_jni::convert::TryFromJavaValue<'__env, '__borrow>,
+            #where_predicates
+        {
+            type Source = <#inner_type as ::robusta_jni::convert::TryFromJavaValue<'__env, '__borrow>>::Source;
+            const KEEPS_SOURCE_REF: bool = <#inner_type as ::robusta_jni::convert::TryFromJavaValue<'__env, '__borrow>>::KEEPS_SOURCE_REF;
+
+            fn try_from(s: Self::Source, env: &'__borrow ::robusta_jni::jni::JNIEnv<'__env>) -> ::robusta_jni::jni::errors::Result<Self> {
+                <#inner_type as ::robusta_jni::convert::TryFromJavaValue<'__env, '__borrow>>::try_from(s, env).map(#struct_name)
+            }
+        }
+
+        #[automatically_derived]
+        impl #borrow_impl_generics ::robusta_jni::convert::FromJavaValue<'__env, '__borrow> for #struct_name #ty_generics
+        where
+            #inner_type: ::robusta_jni::convert::FromJavaValue<'__env, '__borrow>,
+            #where_predicates
+        {
+            type Source = <#inner_type as ::robusta_jni::convert::FromJavaValue<'__env, '__borrow>>::Source;
+            const KEEPS_SOURCE_REF: bool = <#inner_type as ::robusta_jni::convert::FromJavaValue<'__env, '__borrow>>::KEEPS_SOURCE_REF;
+
+            fn from(s: Self::Source, env: &'__borrow ::robusta_jni::jni::JNIEnv<'__env>) -> Self {
+                #struct_name(<#inner_type as ::robusta_jni::convert::FromJavaValue<'__env, '__borrow>>::from(s, env))
+            }
+        }
+    }
+}
+
+/// Copy of `generics` with the additional `lifetimes` declared first
+fn with_lifetimes(generics: &Generics, lifetimes: &[GenericParam]) -> Generics {
+    let mut generics = generics.clone();
+    for (idx, lifetime) in lifetimes.iter().enumerate() {
+        generics.params.insert(idx, lifetime.clone());
+    }
+
+    generics
+}
@@ -0,0 +1,191 @@
+use proc_macro2::{Ident, TokenStream};
+use proc_macro_error::{abort, emit_error};
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{DataEnum, DeriveInput, Fields, LitStr};
+
+use crate::transformation::{java_class_name, JavaPath};
+
+/// Data needed to derive conversion traits for fieldless enums, which are mapped to Java enums by constant name
+pub(crate) struct EnumAutoDeriveData {
+    enum_ident: Ident,
+    classpath_path: String,
+    variants: Vec<Ident>,
+    java_names: Vec<String>,
+}
+
+pub(crate) fn get_enum_impl_components(input: &DeriveInput, data: &DataEnum) -> EnumAutoDeriveData {
+    let input_span = input.span();
+
+    let package_attr = input.attrs.iter().find(|a| a.path().is_ident("package"));
+    let classpath_path = match package_attr {
+        None => abort!(input_span, "missing `#[package]` attribute"),
+        Some(attr) => attr
+            .parse_args()
+            .map(|p: JavaPath| {
+                let mut s = p.to_classpath_path();
+                if !s.is_empty() {
+                    s.push('/');
+                }
+                match java_class_name(&input.attrs, &input.ident) {
+                    Ok(class_name) => s.push_str(&class_name),
+                    Err(e) => emit_error!(e.span(), "invalid `class` attribute: {}", e),
+                }
+                s
+            })
+            .unwrap_or_else(|_| {
+                emit_error!(attr, "invalid Java class path");
+                "".to_string()
+            }),
+    };
+
+    if !input.generics.params.is_empty() {
+        emit_error!(input.generics, "deriving enums cannot have generic parameters");
+    }
+
+    let (variants, java_names) = data
+        .variants
+        .iter()
+        .map(|v| {
+            if !matches!(v.fields, Fields::Unit) {
+                abort!(v, "only fieldless enums can be converted to Java enums");
+            }
+
+            let java_name = v
+                .attrs
+                .iter()
+                .find(|a| a.path().is_ident("java_name"))
+                .map(|a| match a.parse_args::<LitStr>() {
+                    Ok(name) => name.value(),
+                    Err(_) => abort!(a, "expected string literal argument for `#[java_name]`"),
+                })
+                .unwrap_or_else(|| v.ident.to_string());
+
+            (v.ident.clone(), java_name)
+        })
+        .unzip();
+
+    EnumAutoDeriveData {
+        enum_ident: input.ident.clone(),
+        classpath_path,
+        variants,
+        java_names,
+    }
+}
+
+pub(crate) fn tryinto_java_value_enum_derive(data: EnumAutoDeriveData) -> TokenStream {
+    let EnumAutoDeriveData {
+        enum_ident,
+        classpath_path,
+        variants,
+        java_names,
+    } = data;
+
+    quote! {
+        #[automatically_derived]
+        impl<'env> ::robusta_jni::convert::TryIntoJavaValue<'env> for #enum_ident {
+            type Target = ::robusta_jni::jni::objects::JObject<'env>;
+            const NEW_LOCAL_REF: bool = true;
+
+            fn try_into(self, env: &::robusta_jni::jni::JNIEnv<'env>) -> ::robusta_jni::jni::errors::Result<Self::Target> {
+                let constant_name = match self {
+                    #(#enum_ident::#variants => #java_names,)*
+                };
+
+                let class = ::robusta_jni::loader::find_class(env, #classpath_path)?;
+                env.get_static_field(class, constant_name, <Self as ::robusta_jni::convert::Signature>::SIG_TYPE)?.l()
+            }
+        }
+    }
+}
+
+pub(crate) fn into_java_value_enum_derive(data: EnumAutoDeriveData) -> TokenStream {
+    let EnumAutoDeriveData {
+        enum_ident,
+        classpath_path,
+        variants,
+        java_names,
+    } = data;
+
+    quote! {
+        #[automatically_derived]
+        impl<'env> ::robusta_jni::convert::IntoJavaValue<'env> for #enum_ident {
+            type Target = ::robusta_jni::jni::objects::JObject<'env>;
+            const NEW_LOCAL_REF: bool = true;
+
+            fn into(self, env: &::robusta_jni::jni::JNIEnv<'env>) -> Self::Target {
+                let constant_name = match self {
+                    #(#enum_ident::#variants => #java_names,)*
+                };
+
+                ::robusta_jni::loader::find_class(env, #classpath_path)
+                    .and_then(|class| env.get_static_field(class, constant_name, <Self as ::robusta_jni::convert::Signature>::SIG_TYPE))
+                    .and_then(|v| v.l())
+                    .unwrap()
+            }
+        }
+    }
+}
+
+pub(crate) fn tryfrom_java_value_enum_derive(data: EnumAutoDeriveData) -> TokenStream {
+    let EnumAutoDeriveData {
+        enum_ident,
+        variants,
+        java_names,
+        ..
+    } = data;
+    quote! {
+        #[automatically_derived]
+        impl<'env: 'borrow, 'borrow> ::robusta_jni::convert::TryFromJavaValue<'env, 'borrow> for #enum_ident {
+            type Source = ::robusta_jni::jni::objects::JObject<'env>;
+            const KEEPS_SOURCE_REF: bool = false;
+
+            fn try_from(s: Self::Source, env: &'borrow ::robusta_jni::jni::JNIEnv<'env>) -> ::robusta_jni::jni::errors::Result<Self> {
+                let constant_name = env.call_method(s, "name", "()Ljava/lang/String;", &[])?.l()?;
+                let constant_name: String = env.get_string(<::robusta_jni::jni::objects::JString as ::std::convert::From<_>>::from(constant_name))?.to_string_lossy().into_owned();
+
+                match constant_name.as_str() {
+                    #(#java_names => Ok(#enum_ident::#variants),)*
+                    // The Java enum has a constant the Rust enum doesn't
+                    _ => Err(::robusta_jni::jni::errors::Error::FieldNotFound {
+                        name: constant_name,
+                        sig: <Self as ::robusta_jni::convert::Signature>::SIG_TYPE.to_string(),
+                    }),
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn from_java_value_enum_derive(data: EnumAutoDeriveData) -> TokenStream {
+    let EnumAutoDeriveData {
+        enum_ident,
+        variants,
+        java_names,
+        ..
+    } = data;
+
+    quote! {
+        #[automatically_derived]
+        impl<'env: 'borrow, 'borrow> ::robusta_jni::convert::FromJavaValue<'env, 'borrow> for #enum_ident {
+            type Source = ::robusta_jni::jni::objects::JObject<'env>;
+            const KEEPS_SOURCE_REF: bool = false;
+
+            fn from(s: Self::Source, env: &'borrow ::robusta_jni::jni::JNIEnv<'env>) -> Self {
+                let constant_name = env.call_method(s, "name", "()Ljava/lang/String;", &[])
+                    .and_then(|v| v.l())
+                    .unwrap();
+                let constant_name: String = env.get_string(<::robusta_jni::jni::objects::JString as ::std::convert::From<_>>::from(constant_name)).unwrap().to_string_lossy().into_owned();
+
+                match constant_name.as_str() {
+                    #(#java_names => #enum_ident::#variants,)*
+                    _ => ::std::result::Result::<Self, _>::Err(::robusta_jni::jni::errors::Error::FieldNotFound {
+                        name: constant_name,
+                        sig: <Self as ::robusta_jni::convert::Signature>::SIG_TYPE.to_string(),
+                    })
+                    .unwrap(),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use darling::ast::NestedMeta;
+use darling::util::Flag;
+use darling::FromMeta;
+use proc_macro2::TokenStream;
+use proc_macro_error::{abort, emit_error};
+use syn::DeriveInput;
+
+use crate::derive::convert::{
+    from_java_value_macro_derive, into_java_value_macro_derive, tryfrom_java_value_macro_derive,
+    tryinto_java_value_macro_derive,
+};
+use crate::derive::signature::signature_macro_derive;
+
+/// Arguments of the `#[java()]` attribute of the `JavaClass` derive
+#[derive(Default, FromMeta)]
+#[darling(default)]
+struct JavaClassArgs {
+    /// Only derive conversions from Rust to Java (`TryIntoJavaValue` and `IntoJavaValue`)
+    only_into: Flag,
+    /// Only derive conversions from Java to Rust (`TryFromJavaValue` and `FromJavaValue`)
+    only_from: Flag,
+    /// Only derive the safe conversion traits (`TryIntoJavaValue` and `TryFromJavaValue`)
+    only_try: Flag,
+}
+
+/// Derive `Signature` and all the conversion traits selected by the `#[java()]` attribute.
+pub(crate) fn java_class_macro_derive(input: DeriveInput) -> TokenStream {
+    // Arguments of several `#[java()]` attributes are merged
+    let mut metas = Vec::new();
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("java")) {
+        match attr.meta.require_list().and_then(|list| NestedMeta::parse_meta_list(list.tokens.clone())) {
+            Ok(list) => metas.extend(list),
+            Err(e) => emit_error!(e.span(), "{}", e),
+        }
+    }
+    let args = match JavaClassArgs::from_list(&metas) {
+        Ok(args) => args,
+        Err(e) => return e.write_errors(),
+    };
+
+    if args.only_into.is_present() && args.only_from.is_present() {
+        abort!(input.ident, "`only_into` and `only_from` can't be used together";
+            help = "derive `Signature` alone if no conversion is needed");
+    }
+
+    let into = !args.only_from.is_present();
+    let from = !args.only_into.is_present();
+    let unchecked = !args.only_try.is_present();
+
+    let mut tokens = signature_macro_derive(input.clone());
+    if into {
+        tokens.extend(tryinto_java_value_macro_derive(input.clone()));
+        if unchecked {
+            tokens.extend(into_java_value_macro_derive(input.clone()));
+        }
+    }
+    if from {
+        tokens.extend(tryfrom_java_value_macro_derive(input.clone()));
+        if unchecked {
+            tokens.extend(from_java_value_macro_derive(input));
+        }
+    }
+
+    tokens
+}
@@ -1,3 +1,7 @@
 pub(crate) mod convert;
+pub(crate) mod delegate;
+mod enums;
+pub(crate) mod java_class;
+mod record;
 pub(crate) mod signature;
 mod utils;
@@ -0,0 +1,222 @@
+use proc_macro2::{Ident, TokenStream};
+use proc_macro_error::{abort, emit_error};
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{DataStruct, DeriveInput, Fields, LitStr, Type};
+
+use crate::derive::utils::{first_use_call, has_first_use_hook};
+use crate::transformation::JavaPath;
+
+/// Data needed to derive conversion traits for structs mapped to Java records, which are converted by value
+pub(crate) struct RecordAutoDeriveData {
+    record_ident: Ident,
+    idents: Vec<Ident>,
+    types: Vec<Type>,
+    /// Names of the record components, which are also the names of their accessor methods
+    component_names: Vec<String>,
+    /// Whether the record has an `#[on_first_use]` function
+    first_use: bool,
+}
+
+/// Whether the struct is mapped to a Java record, with the `#[record]` attribute
+pub(crate) fn is_record(input: &DeriveInput) -> bool {
+    input.attrs.iter().any(|a| a.path().is_ident("record"))
+}
+
+pub(crate) fn get_record_impl_components(input: &DeriveInput, data: &DataStruct) -> RecordAutoDeriveData {
+    let input_span = input.span();
+
+    match input.attrs.iter().find(|a| a.path().is_ident("package")) {
+        None => abort!(input_span, "missing `#[package]` attribute"),
+        // The class path itself is used by the `Signature` derive, through `JavaClass`
+        Some(attr) => {
+            if attr.parse_args::<JavaPath>().is_err() {
+                emit_error!(attr, "invalid Java class path");
+            }
+        }
+    }
+
+    if let Some(attr) = input.attrs.iter().find(|a| a.path().is_ident("record")) {
+        if attr.meta.require_path_only().is_err() {
+            emit_error!(attr, "`#[record]` attribute doesn't have any arguments");
+        }
+    }
+
+    if !input.generics.params.is_empty() {
+        emit_error!(input.generics, "records cannot have generic parameters";
+            help = "records are converted by value, and don't hold a reference to the Java object")
+    }
+
+    let fields: Vec<_> = match &data.fields {
+        Fields::Named(fields) => fields.named.iter().collect(),
+        Fields::Unit => Vec::new(),
+        Fields::Unnamed(_) => abort!(input_span, "records must have named fields, matching the record components"),
+    };
+
+    for attr in fields.iter().flat_map(|f| &f.attrs) {
+        if let Some(name) = ["instance", "field", "writeback"].iter().find(|n| attr.path().is_ident(n)) {
+            emit_error!(attr, "`#[{}]` attribute cannot be used on record components", name;
+                note = "records are immutable, and converted by value through their accessors and canonical constructor")
+        }
+    }
+
+    let component_names = fields
+        .iter()
+        .map(|f| {
+            f.attrs
+                .iter()
+                .find(|a| a.path().is_ident("java_name"))
+                .map(|a| match a.parse_args::<LitStr>() {
+                    Ok(name) => name.value(),
+                    Err(_) => abort!(a, "expected string literal argument for `#[java_name]`"),
+                })
+                .unwrap_or_else(|| f.ident.as_ref().unwrap().to_string())
+        })
+        .collect();
+
+    RecordAutoDeriveData {
+        record_ident: input.ident.clone(),
+        idents: fields.iter().map(|f| f.ident.clone().unwrap()).collect(),
+        types: fields.iter().map(|f| f.ty.clone()).collect(),
+        component_names,
+        first_use: has_first_use_hook(&input.attrs),
+    }
+}
+
+/// Expression with the signature of the canonical constructor of the record
+fn constructor_signature(types: &[Type]) -> TokenStream {
+    quote! {
+        ::std::format!("({})V", [#(<#types as ::robusta_jni::convert::Signature>::SIG_TYPE),*].concat())
+    }
+}
+
+pub(crate) fn tryinto_java_value_record_derive(data: RecordAutoDeriveData) -> TokenStream {
+    let RecordAutoDeriveData {
+        record_ident,
+        idents,
+        types,
+        first_use,
+        ..
+    } = data;
+    let first_use = first_use_call(first_use, true);
+    let constructor_signature = constructor_signature(&types);
+
+    let components: Vec<_> = idents.iter().zip(&types).map(|(ident, ty)| quote_spanned! { ty.span() =>
+        ::robusta_jni::convert::JavaValue::into_jvalue(::robusta_jni::convert::TryIntoJavaValue::try_into(self.#ident, env)?, env)
+    }).collect();
+
+    quote! {
+        #[automatically_derived]
+        impl<'env> ::robusta_jni::convert::TryIntoJavaValue<'env> for #record_ident {
+            type Target = ::robusta_jni::jni::objects::JObject<'env>;
+            const NEW_LOCAL_REF: bool = true;
+
+            fn try_into(self, env: &::robusta_jni::jni::JNIEnv<'env>) -> ::robusta_jni::jni::errors::Result<Self::Target> {
+                #first_use
+                let components = [#(#components),*];
+                let class = env.auto_local(<Self as ::robusta_jni::convert::JavaClass>::class(env)?);
+                env.new_object(&class, #constructor_signature, &components)
+            }
+        }
+    }
+}
+
+pub(crate) fn into_java_value_record_derive(data: RecordAutoDeriveData) -> TokenStream {
+    let RecordAutoDeriveData {
+        record_ident,
+        idents,
+        types,
+        first_use,
+        ..
+    } = data;
+    let first_use = first_use_call(first_use, false);
+    let constructor_signature = constructor_signature(&types);
+
+    let components: Vec<_> = idents.iter().zip(&types).map(|(ident, ty)| quote_spanned! { ty.span() =>
+        ::robusta_jni::convert::JavaValue::into_jvalue(::robusta_jni::convert::IntoJavaValue::into(self.#ident, env), env)
+    }).collect();
+
+    quote! {
+        #[automatically_derived]
+        impl<'env> ::robusta_jni::convert::IntoJavaValue<'env> for #record_ident {
+            type Target = ::robusta_jni::jni::objects::JObject<'env>;
+            const NEW_LOCAL_REF: bool = true;
+
+            fn into(self, env: &::robusta_jni::jni::JNIEnv<'env>) -> Self::Target {
+                #first_use
+                let components = [#(#components),*];
+                <Self as ::robusta_jni::convert::JavaClass>::class(env)
+                    .and_then(|class| env.new_object(&env.auto_local(class), #constructor_signature, &components))
+                    .unwrap()
+            }
+        }
+    }
+}
+
+pub(crate) fn tryfrom_java_value_record_derive(data: RecordAutoDeriveData) -> TokenStream {
+    let RecordAutoDeriveData {
+        record_ident,
+        idents,
+        types,
+        component_names,
+        first_use,
+    } = data;
+    let first_use = first_use_call(first_use, true);
+
+    let components_env_init: Vec<_> = idents.iter().zip(&types).zip(&component_names).map(|((ident, ty), name)| quote_spanned! { ty.span() =>
+        let value = env.call_method(source, #name, ::std::format!("(){}", <#ty as ::robusta_jni::convert::Signature>::SIG_TYPE), &[])?;
+        let #ident: #ty = ::robusta_jni::convert::TryFromJavaValue::try_from(::robusta_jni::convert::JniValue::from_jvalue(value, env)?, env)?;
+    }).collect();
+
+    quote! {
+        #[automatically_derived]
+        impl<'env: 'borrow, 'borrow> ::robusta_jni::convert::TryFromJavaValue<'env, 'borrow> for #record_ident {
+            type Source = ::robusta_jni::jni::objects::JObject<'env>;
+            const KEEPS_SOURCE_REF: bool = false;
+
+            #[allow(unused_variables)]
+            fn try_from(source: Self::Source, env: &'borrow ::robusta_jni::jni::JNIEnv<'env>) -> ::robusta_jni::jni::errors::Result<Self> {
+                #first_use
+                #(#components_env_init)*
+
+                Ok(#record_ident {
+                    #(#idents,)*
+                })
+            }
+        }
+    }
+}
+
+pub(crate) fn from_java_value_record_derive(data: RecordAutoDeriveData) -> TokenStream {
+    let RecordAutoDeriveData {
+        record_ident,
+        idents,
+        types,
+        component_names,
+        first_use,
+    } = data;
+    let first_use = first_use_call(first_use, false);
+
+    let components_env_init: Vec<_> = idents.iter().zip(&types).zip(&component_names).map(|((ident, ty), name)| quote_spanned! { ty.span() =>
+        let value = env.call_method(source, #name, ::std::format!("(){}", <#ty as ::robusta_jni::convert::Signature>::SIG_TYPE), &[]).unwrap();
+        let #ident: #ty = ::robusta_jni::convert::FromJavaValue::from(::robusta_jni::convert::JniValue::from_jvalue(value, env).unwrap(), env);
+    }).collect();
+
+    quote! {
+        #[automatically_derived]
+        impl<'env: 'borrow, 'borrow> ::robusta_jni::convert::FromJavaValue<'env, 'borrow> for #record_ident {
+            type Source = ::robusta_jni::jni::objects::JObject<'env>;
+            const KEEPS_SOURCE_REF: bool = false;
+
+            #[allow(unused_variables)]
+            fn from(source: Self::Source, env: &'borrow ::robusta_jni::jni::JNIEnv<'env>) -> Self {
+                #first_use
+                #(#components_env_init)*
+
+                #record_ident {
+                    #(#idents,)*
+                }
+            }
+        }
+    }
+}
@@ -2,9 +2,9 @@ use proc_macro2::TokenStream;
 use proc_macro_error::abort;
 use quote::{quote, quote_spanned};
 use syn::spanned::Spanned;
-use syn::{Data, DataStruct, DeriveInput};
+use syn::{Data, DataEnum, DataStruct, DeriveInput};
 
-use crate::transformation::JavaPath;
+use crate::transformation::{java_class_name, JavaPath};
 
 use super::utils::generic_params_to_args;
 
@@ -20,7 +20,7 @@ fn signature_macro_derive_impl(input: DeriveInput) -> syn::Result<TokenStream> {
     let input_span = input.span();
 
     match input.data {
-        Data::Struct(DataStruct { .. }) => {
+        Data::Struct(DataStruct { .. }) | Data::Enum(DataEnum { .. }) => {
             let package_attr = input.attrs.iter().find(|a| {
                 a.path().get_ident().map(ToString::to_string).as_deref() == Some("package")
             });
@@ -30,6 +30,7 @@ fn signature_macro_derive_impl(input: DeriveInput) -> syn::Result<TokenStream> {
                 Some(attr) => {
                     let struct_name = input.ident;
                     let package = attr.parse_args::<JavaPath>()?;
+                    let class_name = java_class_name(&input.attrs, &struct_name)?;
                     let package_str = {
                         let mut s = package.to_classpath_path();
                         if !s.is_empty() {
@@ -41,27 +42,49 @@ fn signature_macro_derive_impl(input: DeriveInput) -> syn::Result<TokenStream> {
                     let signature = [
                         "L",
                         package_str.as_str(),
-                        struct_name.to_string().as_str(),
+                        class_name.as_str(),
                         ";",
                     ]
                     .join("");
+                    let class_path = [package_str.as_str(), class_name.as_str()].join("");
+                    let array_signature = ["[", signature.as_str()].join("");
+                    let nested_array_signatures = (2..=3).map(|dimensions| {
+                        ["[".repeat(dimensions), signature.clone()].join("")
+                    });
                     let generics = input.generics.clone();
-                    let generic_args = generic_params_to_args(input.generics);
+                    let where_clause = &generics.where_clause;
+                    let generic_args = generic_params_to_args(input.generics.clone());
 
                     Ok(quote! {
                         #[automatically_derived]
-                        impl#generics ::robusta_jni::convert::Signature for #struct_name#generic_args {
+                        impl#generics ::robusta_jni::convert::Signature for #struct_name#generic_args #where_clause {
                             const SIG_TYPE: &'static str = #signature;
                         }
 
                         #[automatically_derived]
-                        impl#generics ::robusta_jni::convert::Signature for &#struct_name#generic_args {
-                            const SIG_TYPE: &'static str = <#struct_name as ::robusta_jni::convert::Signature>::SIG_TYPE;
+                        impl#generics ::robusta_jni::convert::ArrayElement for #struct_name#generic_args #where_clause {
+                            const ARRAY_SIG_TYPE: &'static str = #array_signature;
+                            const NESTED_ARRAY_SIG_TYPES: &'static [&'static str] = &[#(#nested_array_signatures),*];
                         }
 
                         #[automatically_derived]
-                        impl#generics ::robusta_jni::convert::Signature for &mut #struct_name#generic_args {
-                            const SIG_TYPE: &'static str = <#struct_name as ::robusta_jni::convert::Signature>::SIG_TYPE;
+                        impl#generics ::robusta_jni::convert::JavaClass for #struct_name#generic_args #where_clause {
+                            const CLASS_PATH: &'static str = #class_path;
+
+                            fn class_cache() -> &'static ::robusta_jni::convert::ClassCache {
+                                static CLASS_CACHE: ::robusta_jni::convert::ClassCache = ::robusta_jni::convert::ClassCache::new();
+                                &CLASS_CACHE
+                            }
+                        }
+
+                        #[automatically_derived]
+                        impl#generics ::robusta_jni::convert::Signature for &#struct_name#generic_args #where_clause {
+                            const SIG_TYPE: &'static str = <#struct_name#generic_args as ::robusta_jni::convert::Signature>::SIG_TYPE;
+                        }
+
+                        #[automatically_derived]
+                        impl#generics ::robusta_jni::convert::Signature for &mut #struct_name#generic_args #where_clause {
+                            const SIG_TYPE: &'static str = <#struct_name#generic_args as ::robusta_jni::convert::Signature>::SIG_TYPE;
                         }
                     })
                 }
@@ -69,7 +92,7 @@ fn signature_macro_derive_impl(input: DeriveInput) -> syn::Result<TokenStream> {
         }
         _ => abort!(
             input_span,
-            "`Signature` auto-derive implemented for structs only"
+            "`Signature` auto-derive implemented for structs and enums only"
         ),
     }
 }
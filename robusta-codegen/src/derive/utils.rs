@@ -1,9 +1,11 @@
+use proc_macro2::TokenStream;
+use quote::quote;
 use syn::parse_quote;
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::Token;
 use syn::{
-    AngleBracketedGenericArguments, ConstParam, GenericArgument, GenericParam, Generics, TypeParam,
+    AngleBracketedGenericArguments, Attribute, ConstParam, GenericArgument, GenericParam, Generics, TypeParam,
 };
 
 pub(crate) fn generic_params_to_args(generics: Generics) -> AngleBracketedGenericArguments {
@@ -32,3 +34,18 @@ pub(crate) fn generic_params_to_args(generics: Generics) -> AngleBracketedGeneri
             .unwrap_or_else(|| Token![>](generics.span())),
     }
 }
+
+/// Whether `#[bridge]` marked the struct with `#[on_first_use]`, as having a function to call on first use
+pub(crate) fn has_first_use_hook(attributes: &[Attribute]) -> bool {
+    attributes.iter().any(|a| a.path().is_ident("on_first_use"))
+}
+
+/// Statement calling the `#[on_first_use]` function of the struct (see `robusta_jni::first_use`) if `enabled`, in a
+/// conversion with an `env` binding returning a `Result` if `safe`
+pub(crate) fn first_use_call(enabled: bool, safe: bool) -> TokenStream {
+    match (enabled, safe) {
+        (false, _) => TokenStream::new(),
+        (true, true) => quote! { Self::robusta_first_use(env)?; },
+        (true, false) => quote! { Self::robusta_first_use(env).unwrap(); },
+    }
+}
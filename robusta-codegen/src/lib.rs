@@ -1,35 +1,78 @@
 use proc_macro::TokenStream;
 
+use darling::ast::NestedMeta;
+use darling::FromMeta;
 use proc_macro_error::proc_macro_error;
-use syn::{parse_macro_input, DeriveInput};
+use syn::{parse_macro_input, DeriveInput, ItemFn};
 
 use validation::JNIBridgeModule;
 
 use crate::derive::convert::{
-    from_java_value_macro_derive, into_java_value_macro_derive, tryfrom_java_value_macro_derive,
-    tryinto_java_value_macro_derive,
+    from_java_value_macro_derive, into_data_macro_derive, into_global_macro_derive, into_java_value_macro_derive,
+    tryfrom_java_value_macro_derive, tryinto_java_value_macro_derive,
 };
-use crate::transformation::ModTransformer;
+use crate::testing::{robusta_test_transform, RobustaTestArgs};
+use crate::transformation::{BridgeArgs, ModTransformer};
+use crate::utils::StrictMode;
+use derive::delegate::java_delegate_macro_derive;
+use derive::java_class::java_class_macro_derive;
 use derive::signature::signature_macro_derive;
 
+/// Emit a warning, or an error in a `#[bridge(strict)]` module. Takes the same arguments as `emit_warning!`.
+macro_rules! emit_lint {
+    ($($tt:tt)*) => {
+        if $crate::utils::is_strict() {
+            ::proc_macro_error::emit_error!($($tt)*)
+        } else {
+            ::proc_macro_error::emit_warning!($($tt)*)
+        }
+    };
+}
+
 mod derive;
+mod testing;
 mod transformation;
 mod utils;
 mod validation;
 
 #[proc_macro_error]
 #[proc_macro_attribute]
-pub fn bridge(_args: TokenStream, raw_input: TokenStream) -> TokenStream {
+pub fn bridge(args: TokenStream, raw_input: TokenStream) -> TokenStream {
+    let args = match NestedMeta::parse_meta_list(args.into()) {
+        Ok(args) => args,
+        Err(e) => return darling::Error::from(e).write_errors().into(),
+    };
+    let bridge_args = match BridgeArgs::from_list(&args) {
+        Ok(bridge_args) => bridge_args,
+        Err(e) => return e.write_errors().into(),
+    };
+    let _strict = StrictMode::enable(bridge_args.strict.is_present());
     let module_data = parse_macro_input!(raw_input as JNIBridgeModule);
 
-    let mut transformer = ModTransformer::new(module_data);
+    let mut transformer = ModTransformer::new(module_data, bridge_args);
     let tokens = transformer.transform_module();
 
     tokens.into()
 }
 
 #[proc_macro_error]
-#[proc_macro_derive(Signature, attributes(package))]
+#[proc_macro_attribute]
+pub fn robusta_test(args: TokenStream, raw_input: TokenStream) -> TokenStream {
+    let args = match NestedMeta::parse_meta_list(args.into()) {
+        Ok(args) => args,
+        Err(e) => return darling::Error::from(e).write_errors().into(),
+    };
+    let test_args = match RobustaTestArgs::from_list(&args) {
+        Ok(test_args) => test_args,
+        Err(e) => return e.write_errors().into(),
+    };
+    let input = parse_macro_input!(raw_input as ItemFn);
+
+    robusta_test_transform(test_args, input).into()
+}
+
+#[proc_macro_error]
+#[proc_macro_derive(Signature, attributes(package, class, java_name))]
 pub fn signature_derive(raw_input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(raw_input as DeriveInput);
 
@@ -37,7 +80,7 @@ pub fn signature_derive(raw_input: TokenStream) -> TokenStream {
 }
 
 #[proc_macro_error]
-#[proc_macro_derive(IntoJavaValue, attributes(package, instance, field))]
+#[proc_macro_derive(IntoJavaValue, attributes(package, class, instance, field, writeback, java_name, record, on_first_use))]
 pub fn into_java_value_derive(raw_input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(raw_input as DeriveInput);
 
@@ -45,7 +88,7 @@ pub fn into_java_value_derive(raw_input: TokenStream) -> TokenStream {
 }
 
 #[proc_macro_error]
-#[proc_macro_derive(TryIntoJavaValue, attributes(package, instance, field))]
+#[proc_macro_derive(TryIntoJavaValue, attributes(package, class, instance, field, writeback, java_name, record, on_first_use))]
 pub fn tryinto_java_value_derive(raw_input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(raw_input as DeriveInput);
 
@@ -53,7 +96,7 @@ pub fn tryinto_java_value_derive(raw_input: TokenStream) -> TokenStream {
 }
 
 #[proc_macro_error]
-#[proc_macro_derive(FromJavaValue, attributes(package, instance, field))]
+#[proc_macro_derive(FromJavaValue, attributes(package, class, instance, field, writeback, java_name, record, on_first_use))]
 pub fn from_java_value_derive(raw_input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(raw_input as DeriveInput);
 
@@ -61,9 +104,41 @@ pub fn from_java_value_derive(raw_input: TokenStream) -> TokenStream {
 }
 
 #[proc_macro_error]
-#[proc_macro_derive(TryFromJavaValue, attributes(package, instance, field))]
+#[proc_macro_derive(TryFromJavaValue, attributes(package, class, instance, field, writeback, java_name, record, on_first_use))]
 pub fn tryfrom_java_value_derive(raw_input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(raw_input as DeriveInput);
 
     tryfrom_java_value_macro_derive(input).into()
 }
+
+#[proc_macro_error]
+#[proc_macro_derive(IntoGlobal, attributes(package, class, instance, field, writeback, java_name))]
+pub fn into_global_derive(raw_input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(raw_input as DeriveInput);
+
+    into_global_macro_derive(input).into()
+}
+
+#[proc_macro_error]
+#[proc_macro_derive(IntoData, attributes(package, class, instance, field, writeback, java_name))]
+pub fn into_data_derive(raw_input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(raw_input as DeriveInput);
+
+    into_data_macro_derive(input).into()
+}
+
+#[proc_macro_error]
+#[proc_macro_derive(JavaClass, attributes(package, class, instance, field, writeback, java_name, java, record, on_first_use))]
+pub fn java_class_derive(raw_input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(raw_input as DeriveInput);
+
+    java_class_macro_derive(input).into()
+}
+
+#[proc_macro_error]
+#[proc_macro_derive(JavaDelegate)]
+pub fn java_delegate_derive(raw_input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(raw_input as DeriveInput);
+
+    java_delegate_macro_derive(input).into()
+}
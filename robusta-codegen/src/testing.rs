@@ -0,0 +1,46 @@
+use darling::FromMeta;
+use proc_macro2::TokenStream;
+use proc_macro_error::abort;
+use quote::quote;
+use syn::{FnArg, ItemFn};
+
+/// Arguments of the `#[robusta_test]` attribute
+#[derive(Default, FromMeta)]
+#[darling(default)]
+pub(crate) struct RobustaTestArgs {
+    /// Function returning the `VmBuilder` of the shared JVM, instead of `default_vm`
+    jvm: Option<syn::Path>,
+}
+
+/// Turn `fn name(env: &JNIEnv) -> R` into a `#[test]` function calling it with the environment of the shared test JVM.
+pub(crate) fn robusta_test_transform(args: RobustaTestArgs, input: ItemFn) -> TokenStream {
+    if input.sig.inputs.len() != 1 || !matches!(input.sig.inputs.first(), Some(FnArg::Typed(_))) {
+        abort!(input.sig, "`#[robusta_test]` functions must have exactly one parameter, the `&JNIEnv` of the test";
+            help = "declare the function as `fn {}(env: &JNIEnv)`", input.sig.ident);
+    }
+    if let Some(asyncness) = input.sig.asyncness {
+        abort!(asyncness, "`#[robusta_test]` functions can't be async");
+    }
+    if !input.sig.generics.params.is_empty() {
+        abort!(input.sig.generics, "`#[robusta_test]` functions can't be generic");
+    }
+
+    let vm = match args.jvm {
+        Some(builder) => quote! { ::robusta_jni::testing::jvm_with(#builder) },
+        None => quote! { ::robusta_jni::testing::jvm() },
+    };
+
+    let ItemFn { attrs, vis, sig, block } = input;
+    let name = &sig.ident;
+    let output = &sig.output;
+
+    quote! {
+        #[test]
+        #(#attrs)*
+        #vis fn #name() #output {
+            #sig #block
+
+            ::robusta_jni::testing::with_env_in(#vm, #name)
+        }
+    }
+}
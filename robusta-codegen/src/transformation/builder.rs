@@ -0,0 +1,144 @@
+use proc_macro2::{Ident, TokenStream};
+use proc_macro_error::emit_error;
+use quote::{format_ident, quote};
+use syn::fold::Fold;
+use syn::{Generics, Type, Visibility};
+
+use crate::transformation::context::StructContext;
+use crate::transformation::FreestandingTransformer;
+
+/// An imported constructor annotated with `#[constructor(builder)]`
+pub(crate) struct BuilderConstructor {
+    pub(crate) vis: Visibility,
+    pub(crate) ident: Ident,
+    pub(crate) env_type: Type,
+    /// Parameters, excluding the environment
+    pub(crate) params: Vec<(Ident, Type)>,
+    /// Whether the constructor returns a `Result`
+    pub(crate) safe: bool,
+}
+
+/// Generate a `<Struct>Builder` type constructing the struct with one of `constructors`.
+///
+/// The builder has a setter for every parameter of the constructors, and `build` calls the constructor
+/// whose parameters are exactly the ones that were set.
+pub(crate) fn builder_items(
+    context: &StructContext,
+    generics: &Generics,
+    self_ty: &Type,
+    constructors: &[BuilderConstructor],
+) -> TokenStream {
+    let first = match constructors.first() {
+        Some(first) => first,
+        None => return TokenStream::new(),
+    };
+
+    // Parameters of all constructors, in order of first appearance
+    let mut params: Vec<(&Ident, &Type)> = Vec::new();
+    for constructor in constructors {
+        for (ident, ty) in &constructor.params {
+            match params.iter().find(|(i, _)| *i == ident) {
+                Some((_, other_ty)) if quote!(#other_ty).to_string() != quote!(#ty).to_string() => {
+                    emit_error!(ty, "parameter `{}` has different types in `#[constructor(builder)]` constructors", ident;
+                        note = "builder setters are shared by all constructors, rename one of the parameters")
+                }
+                Some(_) => {}
+                None => params.push((ident, ty)),
+            }
+        }
+    }
+
+    let mut freestanding_transformer = FreestandingTransformer::new(context.struct_type.clone());
+    let param_idents: Vec<_> = params.iter().map(|(i, _)| *i).collect();
+    let param_names: Vec<_> = param_idents.iter().map(|i| i.to_string()).collect();
+    let param_types: Vec<_> = params
+        .iter()
+        .map(|(_, t)| freestanding_transformer.fold_type((*t).clone()))
+        .collect();
+
+    let struct_name = &context.struct_name;
+    let builder_ident = format_ident!("{}Builder", struct_name.rsplit("::").next().unwrap_or(struct_name));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let vis = &first.vis;
+    let env_type = &first.env_type;
+
+    let arms = constructors.iter().map(|constructor| {
+        let patterns = param_idents.iter().map(|param| {
+            if constructor.params.iter().any(|(i, _)| i == *param) {
+                quote! { ::std::option::Option::Some(#param) }
+            } else {
+                quote! { ::std::option::Option::None }
+            }
+        });
+        let ident = &constructor.ident;
+        let args = constructor.params.iter().map(|(i, _)| i);
+        let call = quote! { <#self_ty>::#ident(env, #(#args),*) };
+        let call = if constructor.safe {
+            call
+        } else {
+            quote! { ::std::result::Result::Ok(#call) }
+        };
+
+        quote! { (#(#patterns),*) => #call, }
+    });
+
+    let builder_doc = format!(
+        "Builder for [`{0}`], calling the `#[constructor(builder)]` constructor of `{0}` matching the set parameters.",
+        struct_name
+    );
+
+    quote! {
+        #[doc = #builder_doc]
+        #vis struct #builder_ident #impl_generics #where_clause {
+            #(#param_idents: ::std::option::Option<#param_types>,)*
+            _marker: ::std::marker::PhantomData<fn() -> #self_ty>,
+        }
+
+        #[automatically_derived]
+        impl #impl_generics ::std::default::Default for #builder_ident #ty_generics #where_clause {
+            fn default() -> Self {
+                #builder_ident {
+                    #(#param_idents: ::std::option::Option::None,)*
+                    _marker: ::std::marker::PhantomData,
+                }
+            }
+        }
+
+        impl #impl_generics #builder_ident #ty_generics #where_clause {
+            #vis fn new() -> Self {
+                ::std::default::Default::default()
+            }
+
+            #(
+                #vis fn #param_idents(mut self, #param_idents: #param_types) -> Self {
+                    self.#param_idents = ::std::option::Option::Some(#param_idents);
+                    self
+                }
+            )*
+
+            /// Construct the Java object, with the constructor taking exactly the parameters that were set.
+            #vis fn build(self, env: #env_type) -> ::robusta_jni::jni::errors::Result<#self_ty> {
+                let set_params: ::std::vec::Vec<&str> = [#((#param_names, self.#param_idents.is_some())),*]
+                    .iter()
+                    .filter(|(_, set)| *set)
+                    .map(|(name, _)| *name)
+                    .collect();
+
+                #[allow(unreachable_patterns)]
+                match (#(self.#param_idents),*) {
+                    #(#arms)*
+                    _ => ::std::result::Result::Err(::robusta_jni::jni::errors::Error::MethodNotFound {
+                        name: ::std::format!("{} constructor", #struct_name),
+                        sig: ::std::format!("with parameters ({})", set_params.join(", ")),
+                    }),
+                }
+            }
+        }
+
+        impl #impl_generics #self_ty #where_clause {
+            #vis fn builder() -> #builder_ident #ty_generics {
+                #builder_ident::new()
+            }
+        }
+    }
+}
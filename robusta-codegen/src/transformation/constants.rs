@@ -0,0 +1,54 @@
+use inflector::cases::screamingsnakecase::to_screaming_snake_case;
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use syn::{GenericParam, Generics, Type};
+
+/// Java name and JNI signature of a bridged method, exposed as associated constants of its struct
+pub(crate) struct MethodConstants {
+    pub(crate) rust_name: Ident,
+    pub(crate) java_name: String,
+    /// Constant expressions whose concatenation is the JNI signature of the method, usable outside of the impl block
+    pub(crate) signature_parts: Vec<TokenStream>,
+}
+
+/// Generate an impl block with `<METHOD>_NAME` and `<METHOD>_SIG` constants for each of `methods`, e.g.
+/// `GET_PASSWORD_NAME` and `GET_PASSWORD_SIG` for `getPassword`.
+pub(crate) fn method_constant_items(generics: &Generics, self_ty: &Type, methods: &[MethodConstants]) -> TokenStream {
+    // Signatures are computed in nested constants, which can't use the type parameters of the impl
+    if methods.is_empty() || generics.params.iter().any(|p| !matches!(p, GenericParam::Lifetime(_))) {
+        return TokenStream::new();
+    }
+
+    let constants = methods.iter().map(|m| {
+        let MethodConstants {
+            rust_name,
+            java_name,
+            signature_parts,
+        } = m;
+        let prefix = to_screaming_snake_case(&rust_name.to_string());
+        let name_ident = format_ident!("{}_NAME", prefix, span = rust_name.span());
+        let sig_ident = format_ident!("{}_SIG", prefix, span = rust_name.span());
+        let name_doc = format!("Java name of `{}`.", rust_name);
+        let sig_doc = format!("JNI signature of `{}`.", rust_name);
+
+        quote! {
+            #[doc = #name_doc]
+            pub const #name_ident: &'static str = #java_name;
+
+            #[doc = #sig_doc]
+            pub const #sig_ident: &'static str = {
+                const PARTS: &[&str] = &[#(#signature_parts),*];
+                const BYTES: [u8; ::robusta_jni::convert::signature_len(PARTS)] = ::robusta_jni::convert::concat_signature(PARTS);
+                ::robusta_jni::convert::signature_str(&BYTES)
+            };
+        }
+    });
+    let where_clause = &generics.where_clause;
+
+    quote! {
+        #[allow(dead_code)]
+        impl #generics #self_ty #where_clause {
+            #(#constants)*
+        }
+    }
+}
@@ -1,10 +1,36 @@
-use crate::transformation::JavaPath;
+use std::collections::BTreeSet;
+
+use crate::transformation::{JavaPath, NameFormat, RenameRule};
 use syn::{LifetimeParam, Path};
 
 #[derive(Clone)]
 pub(crate) struct StructContext {
     pub(crate) struct_type: Path,
     pub(crate) struct_name: String,
+    /// Name of the Java class, which differs from `struct_name` for nested classes (e.g. `Outer$Inner`)
+    pub(crate) class_name: String,
     pub(crate) struct_lifetimes: Vec<LifetimeParam>,
     pub(crate) package: Option<JavaPath>,
+    pub(crate) kotlin_object: bool,
+    pub(crate) rename_all: RenameRule,
+    pub(crate) java_name_format: Option<NameFormat>,
+    pub(crate) env_aliases: BTreeSet<String>,
+}
+
+impl StructContext {
+    /// Class path of the struct in JNI format (e.g. `com/example/Foo`)
+    pub(crate) fn class_path(&self) -> String {
+        let jni_package_path = self
+            .package
+            .as_ref()
+            .map(|p| p.to_classpath_path())
+            .unwrap_or_default();
+
+        [jni_package_path, self.class_name.clone()]
+            .iter()
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_owned())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
 }
@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use proc_macro2::{Span, TokenStream};
+use proc_macro_error::emit_error;
+use quote::{quote, quote_spanned};
+use serde_json::Value;
+use syn::LitStr;
+
+/// Java method descriptors read from the file given with `#[bridge(check_against = "...")]`, used to check imported
+/// methods at compile time.
+///
+/// The file maps class paths to method names to descriptors:
+/// ```json
+/// {
+///   "com/example/User": {
+///     "<init>": ["(Ljava/lang/String;)V"],
+///     "getPassword": ["()Ljava/lang/String;"]
+///   }
+/// }
+/// ```
+pub(crate) struct Descriptors {
+    /// Path of the file as given in the attribute
+    path: String,
+    /// Absolute path of the file
+    full_path: PathBuf,
+    classes: BTreeMap<String, BTreeMap<String, Vec<String>>>,
+}
+
+/// An imported method to check against the descriptors.
+pub(crate) struct CheckedMethod<'a> {
+    pub(crate) rust_name: &'a str,
+    pub(crate) class_path: &'a str,
+    pub(crate) java_name: &'a str,
+    /// Parts of the signature computed from the Rust types, concatenated at compile time
+    pub(crate) signature_parts: Option<Vec<TokenStream>>,
+    /// Signatures given as literals (`#[constructor(signature = "...")]` and `#[fallback_signature]`)
+    pub(crate) literal_signatures: Vec<&'a LitStr>,
+    pub(crate) span: Span,
+}
+
+impl Descriptors {
+    /// Read the descriptors from `path`, relative to the directory of the manifest of the crate being compiled
+    pub(crate) fn load(path: &str) -> Option<Self> {
+        let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").map(PathBuf::from).unwrap_or_default();
+        let full_path = manifest_dir.join(path);
+
+        let contents = match std::fs::read_to_string(&full_path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                emit_error!(Span::call_site(), "can't read `{}`: {}", full_path.display(), e;
+                    note = "paths given to `check_against` are relative to the directory of `Cargo.toml`");
+                return None;
+            }
+        };
+
+        let classes = match serde_json::from_str::<Value>(&contents).map(|v| parse_classes(&v)) {
+            Ok(Some(classes)) => classes,
+            Ok(None) => {
+                emit_error!(Span::call_site(), "invalid descriptor list in `{}`", path;
+                    help = "expected an object mapping class paths to objects mapping method names to lists of descriptors");
+                return None;
+            }
+            Err(e) => {
+                emit_error!(Span::call_site(), "invalid JSON in `{}`: {}", path, e);
+                return None;
+            }
+        };
+
+        Some(Descriptors {
+            path: path.to_string(),
+            full_path,
+            classes,
+        })
+    }
+
+    /// Item making Cargo rebuild the crate when the file changes
+    pub(crate) fn dependency_item(&self) -> TokenStream {
+        let full_path = self.full_path.to_string_lossy();
+
+        quote! {
+            const _: &[u8] = include_bytes!(#full_path);
+        }
+    }
+
+    /// Check `method` against the descriptors of its class. Methods of classes that aren't listed are not checked.
+    ///
+    /// Literal signatures are checked right away, while signatures computed from Rust types are checked by the returned
+    /// item, which fails to compile if they don't match.
+    pub(crate) fn check(&self, method: CheckedMethod) -> TokenStream {
+        let methods = match self.classes.get(method.class_path) {
+            Some(methods) => methods,
+            None => return TokenStream::new(),
+        };
+
+        let descriptors = match methods.get(method.java_name) {
+            Some(descriptors) => descriptors,
+            None => {
+                emit_error!(method.span, "`{}` has no matching Java method: class `{}` has no method named `{}` in `{}`",
+                    method.rust_name, method.class_path, method.java_name, self.path);
+                return TokenStream::new();
+            }
+        };
+
+        if method.literal_signatures.iter().any(|s| descriptors.contains(&s.value())) {
+            return TokenStream::new();
+        }
+
+        let expected = descriptors.join(" or ");
+        match method.signature_parts {
+            Some(parts) => {
+                let message = format!(
+                    "`{}` has no matching Java method: the signature of `{}.{}` isn't {} (as declared in `{}`)",
+                    method.rust_name, method.class_path, method.java_name, expected, self.path
+                );
+
+                quote_spanned! { method.span =>
+                    const _: () = ::std::assert!(
+                        ::robusta_jni::verify::matches_any(&[#(#parts),*], &[#(#descriptors),*]),
+                        #message
+                    );
+                }
+            }
+            None => {
+                emit_error!(method.span, "`{}` has no matching Java method: the signature of `{}.{}` is {} (as declared in `{}`)",
+                    method.rust_name, method.class_path, method.java_name, expected, self.path);
+                TokenStream::new()
+            }
+        }
+    }
+}
+
+fn parse_classes(value: &Value) -> Option<BTreeMap<String, BTreeMap<String, Vec<String>>>> {
+    value
+        .as_object()?
+        .iter()
+        .map(|(class_path, methods)| {
+            let methods = methods
+                .as_object()?
+                .iter()
+                .map(|(name, descriptors)| {
+                    let descriptors = descriptors
+                        .as_array()?
+                        .iter()
+                        .map(|d| d.as_str().map(ToString::to_string))
+                        .collect::<Option<Vec<_>>>()?;
+                    Some((name.clone(), descriptors))
+                })
+                .collect::<Option<BTreeMap<_, _>>>()?;
+
+            // Class paths can also be written with dots
+            Some((class_path.replace('.', "/"), methods))
+        })
+        .collect()
+}
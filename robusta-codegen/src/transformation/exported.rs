@@ -1,32 +1,46 @@
 use std::collections::HashSet;
 
-use proc_macro2::Ident;
-use proc_macro_error::{emit_error, emit_warning};
-use quote::ToTokens;
+use proc_macro2::{Ident, TokenStream};
+use proc_macro_error::emit_error;
+use quote::{quote, ToTokens};
 use syn::fold::Fold;
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::token::Extern;
 use syn::Lifetime;
 use syn::Token;
-use syn::{parse_quote, GenericParam, Generics, LifetimeParam, TypeTuple};
+use syn::{parse_quote, GenericArgument, GenericParam, Generics, LifetimeParam, TypeTuple};
 use syn::{
-    Abi, Block, Expr, FnArg, ImplItemFn, LitStr, Pat, PatIdent, PatType, ReturnType, Signature,
-    Type, Visibility,
+    Abi, Attribute, Block, Expr, ExprLit, FnArg, ImplItemFn, Lit, LitStr, Meta, MetaNameValue, Pat, PatIdent, PatType, Path, PathArguments, Receiver,
+    ReturnType, Signature, Stmt, TraitBound, Type, TypeImplTrait, TypeParamBound, TypePath, TypeReference, TypeSlice,
+    Visibility,
 };
 
+use crate::transformation::constants::MethodConstants;
 use crate::transformation::context::StructContext;
+use crate::transformation::imported::descriptor_params;
 use crate::transformation::utils::get_call_type;
-use crate::transformation::{CallType, FreestandingTransformer, SafeParams};
-use crate::utils::{get_abi, get_env_arg, is_self_method};
+use crate::transformation::{CallType, FreestandingTransformer, JavaPath, SafeParams};
+use crate::utils::{
+    check_convert_attributes, check_helper_attributes, check_misplaced_env_args, converted_return_type, converted_type, converter_type,
+    get_abi, get_env_arg, get_jclass_arg, get_vm_arg, is_critical_param, is_instrumented, is_mut_env_arg, is_result_type, is_self_method,
+    is_strict, result_value_type, java_name_override, local_frame_capacity, type_name, EXPORTED_METHOD_ATTRIBUTES,
+};
 use std::iter::FromIterator;
+use std::str::FromStr;
 
 pub struct ExportedMethodTransformer<'ctx> {
     pub(crate) struct_context: &'ctx StructContext,
+    /// When set, exported functions are not exposed as symbols and are instead registered by `JNI_OnLoad`
+    pub(crate) register_natives: bool,
+    /// Exported functions, for `JNI_OnLoad` and the symbol manifest
+    pub(crate) native_methods: Vec<NativeMethodRegistration>,
+    /// Names and signatures of the exported functions, exposed as constants of the struct
+    pub(crate) method_constants: Vec<MethodConstants>,
 }
 
 impl<'ctx> Fold for ExportedMethodTransformer<'ctx> {
-    fn fold_impl_item_fn(&mut self, node: ImplItemFn) -> ImplItemFn {
+    fn fold_impl_item_fn(&mut self, mut node: ImplItemFn) -> ImplItemFn {
         let abi = get_abi(&node.sig);
         match (&node.vis, &abi.as_deref()) {
             (Visibility::Public(_), Some("jni")) => {
@@ -34,46 +48,419 @@ impl<'ctx> Fold for ExportedMethodTransformer<'ctx> {
                     .map(|c| c.call_type)
                     .unwrap_or(CallType::Safe(None));
 
-                let mut jni_method_transformer =
-                    ExternJNIMethodTransformer::new(self.struct_context, call_type_attribute);
-                jni_method_transformer.fold_impl_item_fn(node)
+                check_misplaced_env_args(&node.sig, &self.struct_context.env_aliases);
+                check_helper_attributes(&node.attrs, EXPORTED_METHOD_ATTRIBUTES, "`extern \"jni\"` methods");
+                check_convert_attributes(&node.attrs, &node.sig);
+
+                // Return values of methods marked with `#[convert]` are converted as `Converted` values
+                if let Some(converter) = converter_type(&node.attrs) {
+                    if matches!(node.sig.output, ReturnType::Default) {
+                        emit_error!(node.sig, "`#[convert]` can only be used on methods with a return value";
+                            help = "use `#[convert(with = \"...\")]` on parameters to convert them");
+                    }
+                    node.sig.output = converted_return_type(&converter, node.sig.output);
+                }
+
+                let original_signature = node.sig.clone();
+                let java_name = exported_java_name(&node, self.struct_context);
+                let export_name = export_name_override(&node.attrs);
+                let throws = throws_class(&node.attrs).and_then(Result::ok).map(|class| class.0);
+                let mut jni_method_transformer = ExternJNIMethodTransformer::new(
+                    self.struct_context,
+                    call_type_attribute.clone(),
+                    self.register_natives,
+                );
+                let transformed = jni_method_transformer.fold_impl_item_fn(node);
+
+                let symbol = match export_name {
+                    _ if self.register_natives => None,
+                    Some(export_name) => Some(export_name),
+                    None => Some(transformed.sig.ident.to_string()),
+                };
+                let rust_name = original_signature.ident.clone();
+                let registration = NativeMethodRegistration::new(
+                    self.struct_context,
+                    &call_type_attribute,
+                    original_signature,
+                    java_name,
+                    transformed.sig.ident.clone(),
+                    symbol,
+                    throws,
+                );
+                self.method_constants.push(MethodConstants {
+                    rust_name,
+                    java_name: registration.java_name.clone(),
+                    signature_parts: registration.signature_parts.clone(),
+                });
+                self.native_methods.push(registration);
+
+                transformed
+            }
+            (_, Some("jni")) if is_strict() => {
+                emit_error!(node.sig, "`extern \"jni\"` methods must be public to be exported";
+                    help = "add `pub` to export it");
+                node
             }
             _ => node,
         }
     }
 }
 
+/// Data needed to register an exported function with `RegisterNatives`, or to list it in the symbol manifest
+pub(crate) struct NativeMethodRegistration {
+    pub(crate) class_path: String,
+    pub(crate) java_name: String,
+    /// Constant expressions whose concatenation is the JNI method signature of the function
+    pub(crate) signature_parts: Vec<TokenStream>,
+    pub(crate) function: Ident,
+    /// Name of the exported symbol, `None` with `register_natives`
+    pub(crate) symbol: Option<String>,
+    pub(crate) is_static: bool,
+    /// Checked exception declared with `#[throws]`, e.g. `java.io.IOException`
+    pub(crate) throws: Option<String>,
+}
+
+impl NativeMethodRegistration {
+    /// Expression evaluating to the JNI method signature of the function
+    pub(crate) fn signature(&self) -> TokenStream {
+        let parts = &self.signature_parts;
+        quote! { [#(#parts),*].join("") }
+    }
+
+    fn new(
+        struct_context: &StructContext,
+        call_type: &CallType,
+        signature: Signature,
+        java_name: String,
+        function: Ident,
+        symbol: Option<String>,
+        throws: Option<String>,
+    ) -> Self {
+        let is_static = !is_self_method(&signature);
+        let (signature, _) = get_env_arg(signature, &struct_context.env_aliases);
+        let (signature, _) = get_vm_arg(signature);
+        let (signature, _) = get_jclass_arg(signature);
+
+        let input_signatures = parameter_signature_parts(struct_context, call_type, &signature);
+
+        let mut type_transformer = FreestandingTransformer::new(struct_context.struct_type.clone());
+        let mut lifetime_eraser = LifetimeEraser;
+        let mut erase = |ty: Type| lifetime_eraser.fold_type(type_transformer.fold_type(ty));
+
+        let output_signature = match signature.output {
+            ReturnType::Type(_, ty) if !matches!(&*ty, Type::Tuple(TypeTuple { elems, .. }) if elems.is_empty()) => {
+                // Errors of the method's own type are thrown, only the value is converted
+                let ty = match (call_type, result_value_type(&ty)) {
+                    (CallType::Safe(_), Some(value)) => erase(value.clone()),
+                    _ => erase(*ty),
+                };
+                match call_type {
+                    CallType::Safe(_) => quote! { <#ty as ::robusta_jni::convert::TryIntoJavaValue<'_>>::SIG_TYPE },
+                    CallType::Unchecked(_) => quote! { <#ty as ::robusta_jni::convert::IntoJavaValue<'_>>::SIG_TYPE },
+                }
+            }
+            _ => quote! { "V" },
+        };
+
+        NativeMethodRegistration {
+            class_path: struct_context.class_path(),
+            java_name,
+            signature_parts: std::iter::once(quote! { "(" })
+                .chain(input_signatures)
+                .chain(vec![quote! { ")" }, output_signature])
+                .collect(),
+            function,
+            symbol,
+            is_static,
+            throws,
+        }
+    }
+}
+
+/// Java name of an exported method: the argument of its `#[java_name("...")]` attribute, or its Rust name formatted
+/// with the `java_name_format` argument of `#[bridge]` (the Rust name itself by default)
+fn exported_java_name(node: &ImplItemFn, struct_context: &StructContext) -> String {
+    let rust_name = node.sig.ident.to_string();
+
+    java_name_override(&node.attrs).unwrap_or_else(|| match &struct_context.java_name_format {
+        Some(format) => format.apply(&rust_name),
+        None => rust_name,
+    })
+}
+
+/// Constant expressions evaluating to the JNI signatures of the parameters of `signature` (without the environment and
+/// class parameters)
+fn parameter_signature_parts(struct_context: &StructContext, call_type: &CallType, signature: &Signature) -> Vec<TokenStream> {
+    // Types are used outside of the impl block, so `Self` must be replaced and lifetimes must be inferred
+    let mut type_transformer = FreestandingTransformer::new(struct_context.struct_type.clone());
+    let mut lifetime_eraser = LifetimeEraser;
+    let mut erase = |ty: Type| lifetime_eraser.fold_type(type_transformer.fold_type(ty));
+
+    signature
+        .inputs
+        .iter()
+        .filter_map(|i| match i {
+            FnArg::Typed(t) => match &*t.pat {
+                Pat::Ident(PatIdent { ident, .. }) if ident == "self" => None,
+                _ if converter_type(&t.attrs).is_some() => Some(erase(converted_type(&converter_type(&t.attrs).unwrap(), &t.ty))),
+                _ if is_critical_param(&t.attrs) => match critical_array_type(&t.ty) {
+                    Ok(critical_type) => Some(erase(critical_type)),
+                    Err(_) => Some(erase((*t.ty).clone())),
+                },
+                _ => match java_function_type(&t.ty) {
+                    Some(Ok(function_type)) => Some(erase(function_type)),
+                    _ => Some(erase((*t.ty).clone())),
+                },
+            },
+            FnArg::Receiver(_) => None,
+        })
+        .map(|ty| match call_type {
+            CallType::Safe(_) => quote! { <#ty as ::robusta_jni::convert::TryFromJavaValue<'_, '_>>::SIG_TYPE },
+            CallType::Unchecked(_) => quote! { <#ty as ::robusta_jni::convert::FromJavaValue<'_, '_>>::SIG_TYPE },
+        })
+        .collect()
+}
+
+/// Symbol name given with `#[export_name = "..."]`
+fn export_name_override(attrs: &[Attribute]) -> Option<String> {
+    attrs.iter().find_map(|a| match &a.meta {
+        Meta::NameValue(MetaNameValue { path, value: Expr::Lit(ExprLit { lit: Lit::Str(name), .. }), .. })
+            if path.is_ident("export_name") =>
+        {
+            Some(name.value())
+        }
+        _ => None,
+    })
+}
+
+/// `JavaFunction` type of an `impl Fn(...)` parameter, with the Java interface chosen from the closure signature
+/// (see `robusta_jni::convert::function`). Returns `None` if the type is not a closure.
+fn java_function_type(ty: &Type) -> Option<Result<Type, syn::Error>> {
+    let bounds = match ty {
+        Type::ImplTrait(TypeImplTrait { bounds, .. }) => bounds,
+        _ => return None,
+    };
+    let arguments = bounds.iter().find_map(|b| match b {
+        TypeParamBound::Trait(TraitBound { path, .. }) => {
+            let segment = path.segments.last()?;
+            match &segment.arguments {
+                PathArguments::Parenthesized(arguments) if ["Fn", "FnMut", "FnOnce"].iter().any(|f| segment.ident == f) => {
+                    Some(arguments)
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    })?;
+
+    let inputs: Vec<&Type> = arguments.inputs.iter().collect();
+    let output: Type = match &arguments.output {
+        ReturnType::Default => parse_quote!(()),
+        ReturnType::Type(_, ty) => (**ty).clone(),
+    };
+
+    let type_name = |ty: &Type| match ty {
+        Type::Path(TypePath { qself: None, path }) => path.get_ident().map(ToString::to_string),
+        Type::Tuple(TypeTuple { elems, .. }) if elems.is_empty() => Some("()".to_string()),
+        _ => None,
+    };
+    // Interfaces specialized for primitives, e.g. `IntUnaryOperator`
+    let primitive_prefix = |ty: &Type| match type_name(ty).as_deref() {
+        Some("i32") => Some("Int"),
+        Some("i64") => Some("Long"),
+        Some("f64") => Some("Double"),
+        _ => None,
+    };
+    let output_name = type_name(&output);
+    let output_name = output_name.as_deref();
+    let same_as_output = |ty: &&Type| type_name(ty).as_deref() == output_name;
+
+    let interface = match (inputs.as_slice(), output_name) {
+        ([], Some("()")) => "Runnable".to_string(),
+        ([], _) => "Supplier".to_string(),
+        ([a], _) if same_as_output(a) && primitive_prefix(a).is_some() => {
+            format!("{}UnaryOperator", primitive_prefix(a).unwrap())
+        }
+        ([a, b], _) if same_as_output(a) && same_as_output(b) && primitive_prefix(a).is_some() => {
+            format!("{}BinaryOperator", primitive_prefix(a).unwrap())
+        }
+        ([a], Some("bool")) if primitive_prefix(a).is_some() => format!("{}Predicate", primitive_prefix(a).unwrap()),
+        ([a], Some("()")) if primitive_prefix(a).is_some() => format!("{}Consumer", primitive_prefix(a).unwrap()),
+        ([_], Some("bool")) => "Predicate".to_string(),
+        ([_, _], Some("bool")) => "BiPredicate".to_string(),
+        ([_], Some("()")) => "Consumer".to_string(),
+        ([_, _], Some("()")) => "BiConsumer".to_string(),
+        ([_], _) => "Function".to_string(),
+        ([_, _], _) => "BiFunction".to_string(),
+        _ => {
+            return Some(Err(syn::Error::new(
+                ty.span(),
+                "closures with more than two parameters don't match any interface of `java.util.function`",
+            )))
+        }
+    };
+    let interface = Ident::new(&interface, ty.span());
+
+    Some(Ok(parse_quote_spanned! { ty.span() =>
+        ::robusta_jni::convert::JavaFunction<::robusta_jni::convert::function::#interface, fn(#(#inputs),*) -> #output>
+    }))
+}
+
+/// `Critical` type of a `#[critical]` slice parameter (see `robusta_jni::convert::critical`).
+fn critical_array_type(ty: &Type) -> Result<Type, syn::Error> {
+    match ty {
+        Type::Reference(TypeReference { elem, .. }) => match elem.as_ref() {
+            Type::Slice(TypeSlice { elem, .. }) => Ok(parse_quote_spanned! { ty.span() =>
+                ::robusta_jni::convert::Critical<'env, 'borrow, #elem>
+            }),
+            _ => Err(syn::Error::new(ty.span(), "`#[critical]` parameters must be slices of primitive types")),
+        },
+        _ => Err(syn::Error::new(ty.span(), "`#[critical]` parameters must be slices of primitive types")),
+    }
+}
+
+pub(crate) struct LifetimeEraser;
+
+impl Fold for LifetimeEraser {
+    fn fold_lifetime(&mut self, node: Lifetime) -> Lifetime {
+        Lifetime::new("'_", node.span())
+    }
+}
+
+/// Give a `'static` lifetime to a returned reference without one: the converted value doesn't borrow from it, so
+/// elided lifetimes only need to be resolved in the signature of the original method.
+fn fill_elided_lifetime(reference: &TypeReference) -> TypeReference {
+    let mut reference = reference.clone();
+    if reference.lifetime.is_none() {
+        reference.lifetime = Some(Lifetime::new("'static", reference.and_token.span));
+    }
+
+    reference
+}
+
 struct ExternJNIMethodTransformer<'ctx> {
     struct_context: &'ctx StructContext,
     call_type: CallType,
+    register_natives: bool,
 }
 
 impl<'ctx> ExternJNIMethodTransformer<'ctx> {
-    fn new(struct_context: &'ctx StructContext, call_type: CallType) -> Self {
+    fn new(struct_context: &'ctx StructContext, call_type: CallType, register_natives: bool) -> Self {
         ExternJNIMethodTransformer {
             struct_context,
             call_type,
+            register_natives,
         }
     }
+
+    /// Transform original signature in JNI-ready one, including JClass and JNIEnv parameters into the function signature.
+    ///
+    /// Overloaded methods get the long JNI name, with the parameter descriptors `overload` (e.g. `(I[B)`) appended.
+    fn jni_signature(&mut self, node: Signature, java_name: &str, overload: Option<&str>) -> Signature {
+        let jni_signature =
+            JNISignature::new(node.clone(), self.struct_context, self.call_type.clone());
+
+        let mut sig = jni_signature.transformed_signature;
+
+        let jni_method_name = {
+            let jni_package = self
+                .struct_context
+                .package
+                .as_ref()
+                .map(|s| s.to_jni_symbol())
+                .unwrap_or_else(|| "".into());
+
+            let short_name = [
+                "Java",
+                &jni_package,
+                &mangle_jni_name(&self.struct_context.class_name),
+                &mangle_jni_name(java_name),
+            ]
+            .iter()
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_owned())
+            .collect::<Vec<_>>()
+            .join("_");
+
+            match overload {
+                Some(descriptor) => format!("{}__{}", short_name, mangle_jni_descriptor(descriptor)),
+                None => short_name,
+            }
+        };
+
+        sig.inputs = {
+            let mut res = Punctuated::new();
+            res.push(parse_quote!(env: ::robusta_jni::jni::JNIEnv<'env>));
+
+            if !is_self_method(&node) {
+                res.push(parse_quote!(class: ::robusta_jni::jni::objects::JClass));
+            }
+
+            res.extend(sig.inputs);
+            res
+        };
+
+        sig.ident = Ident::new(&jni_method_name, sig.ident.span());
+        sig.abi = Some(Abi {
+            extern_token: Extern { span: sig.span() },
+            name: Some(LitStr::new("system", sig.span())),
+        });
+
+        sig
+    }
 }
 
 impl<'ctx> Fold for ExternJNIMethodTransformer<'ctx> {
     fn fold_impl_item_fn(&mut self, node: ImplItemFn) -> ImplItemFn {
-        let jni_signature = JNISignature::new(
+        let mut jni_signature = JNISignature::new(
             node.sig.clone(),
-            &self.struct_context,
+            self.struct_context,
             self.call_type.clone(),
         );
+        jni_signature.instrument = is_instrumented(&node.attrs);
 
         let transformed_jni_signature = jni_signature.transformed_signature();
         let method_call = jni_signature.signature_call();
+        // The return type was replaced with a `Converted` type, see `ExportedMethodTransformer`
+        let method_call: Expr = match converter_type(&node.attrs) {
+            Some(converter) if matches!(&node.sig.output, ReturnType::Type(_, ty) if is_result_type(ty)) => parse_quote_spanned! { node.sig.output.span() =>
+                (#method_call).map(::robusta_jni::convert::converter::Converted::<#converter, _>::new)
+            },
+            Some(converter) => parse_quote_spanned! { node.sig.output.span() =>
+                ::robusta_jni::convert::converter::Converted::<#converter, _>::new(#method_call)
+            },
+            None => method_call,
+        };
+        // Conversions, the method body and the conversion of the result run in the local frame, see `robusta_jni::frame`
+        let local_frame = local_frame_capacity(&node.attrs);
+
+        // Errors are thrown as the declared checked exception, which takes precedence over the call type's exception class
+        let throws = match throws_class(&node.attrs) {
+            Some(Ok(class)) => Some(class),
+            Some(Err(e)) => {
+                emit_error!(e.span(), "invalid `throws` attribute: {}", e;
+                    help = "use e.g. `#[throws(\"java.io.IOException\")]`");
+                None
+            }
+            None => None,
+        };
+        if let (Some(_), CallType::Unchecked(_)) = (&throws, &self.call_type) {
+            let attr = node.attrs.iter().find(|a| a.path().is_ident("throws")).unwrap();
+            emit_error!(attr, "`#[throws]` can only be used on methods with a safe call type";
+                help = "`#[call_type(unchecked)]` methods can't return errors");
+        }
 
-        let new_block: Block = match &self.call_type {
-            CallType::Unchecked { .. } => {
-                parse_quote_spanned! { node.span() => {
+        let mut new_block: Block = match &self.call_type {
+            CallType::Unchecked { .. } => match &local_frame {
+                Some(capacity) => parse_quote_spanned! { node.span() => {
+                    ::robusta_jni::frame::with_local_frame(&env, #capacity, || {
+                        ::std::result::Result::Ok::<_, ::robusta_jni::jni::errors::Error>(::robusta_jni::convert::IntoJavaValue::into(#method_call, &env))
+                    }).unwrap()
+                }},
+                None => parse_quote_spanned! { node.span() => {
                     ::robusta_jni::convert::IntoJavaValue::into(#method_call, &env)
-                }}
-            }
+                }},
+            },
 
             CallType::Safe(exception_details) => {
                 let outer_call_inputs = {
@@ -92,6 +479,9 @@ impl<'ctx> Fold for ExternJNIMethodTransformer<'ctx> {
                         .collect();
 
                     inputs.push(parse_quote!(&env));
+                    if jni_signature.class_arg.is_some() {
+                        inputs.push(parse_quote!(class));
+                    }
                     inputs
                 };
                 let outer_signature = {
@@ -119,6 +509,10 @@ impl<'ctx> Fold for ExternJNIMethodTransformer<'ctx> {
                         ty: Box::new(parse_quote! { &'borrow ::robusta_jni::jni::JNIEnv<'env> }),
                     }));
 
+                    if jni_signature.class_arg.is_some() {
+                        s.inputs.push(parse_quote! { class: ::robusta_jni::jni::objects::JClass });
+                    }
+
                     let outer_signature_span = s.span();
                     let outer_output_type: Type = match s.output {
                         ReturnType::Default => parse_quote!(()),
@@ -128,7 +522,7 @@ impl<'ctx> Fold for ExternJNIMethodTransformer<'ctx> {
                     s.output = ReturnType::Type(
                         Token![->](outer_signature_span),
                         Box::new(
-                            parse_quote_spanned!(outer_output_type.span() => ::robusta_jni::jni::errors::Result<#outer_output_type>),
+                            parse_quote_spanned!(outer_output_type.span() => ::std::result::Result<#outer_output_type, ::robusta_jni::convert::ConversionError>),
                         ),
                     );
                     s.abi = None;
@@ -139,31 +533,57 @@ impl<'ctx> Fold for ExternJNIMethodTransformer<'ctx> {
                     "java.lang.RuntimeException".parse().unwrap(),
                     "JNI call error!",
                 );
-                let (exception_class, message) = match exception_details {
+                let (exception_class, message, cause_class) = match exception_details {
                     Some(SafeParams {
                         exception_class,
                         message,
+                        cause_class,
                     }) => {
                         let exception_class_result =
                             exception_class.as_ref().unwrap_or(&default_exception_class);
                         let message_result = message.as_deref().unwrap_or(default_message);
+                        let cause_class_result = cause_class.as_ref().unwrap_or(&default_exception_class);
 
-                        (exception_class_result, message_result)
+                        (exception_class_result, message_result, cause_class_result)
                     }
-                    None => (&default_exception_class, default_message),
+                    None => (&default_exception_class, default_message, &default_exception_class),
                 };
 
-                let exception_classpath_path = exception_class.to_classpath_path();
+                let exception_classpath_path = throws.as_ref().unwrap_or(exception_class).to_classpath_path();
+                let cause_classpath_path = cause_class.to_classpath_path();
+                // Errors of the method's own type are kept whole, so that their source chain is thrown
+                let method_call: Expr = match &node.sig.output {
+                    ReturnType::Type(_, ty) if result_value_type(ty).is_some() => parse_quote_spanned! { node.sig.output.span() =>
+                        (#method_call).map_err(::robusta_jni::convert::ConversionError::method)?
+                    },
+                    _ => method_call,
+                };
+                let outer_body: Expr = match &local_frame {
+                    Some(capacity) => parse_quote_spanned! { node.span() =>
+                        ::robusta_jni::frame::with_local_frame(env, #capacity, || {
+                            Ok(::robusta_jni::convert::TryIntoJavaValue::try_into(#method_call, &env)?)
+                        })
+                    },
+                    None => parse_quote_spanned! { node.span() =>
+                        Ok(::robusta_jni::convert::TryIntoJavaValue::try_into(#method_call, &env)?)
+                    },
+                };
 
                 parse_quote_spanned! { node.span() => {
                     #outer_signature {
-                        ::robusta_jni::convert::TryIntoJavaValue::try_into(#method_call, &env)
+                        #outer_body
                     }
 
                     match outer(#outer_call_inputs) {
                         Ok(result) => result,
                         Err(e) => {
-                            let r = env.throw_new(#exception_classpath_path, format!("{}. Cause: {}", #message, e));
+                            let r = ::robusta_jni::convert::error::throw_chained(
+                                &env,
+                                #exception_classpath_path,
+                                &format!("{}. Cause: {}", #message, e),
+                                &e,
+                                #cause_classpath_path,
+                            );
 
                             if let Err(e) = r {
                                 println!("Error while throwing Java exception: {}", e);
@@ -182,14 +602,92 @@ impl<'ctx> Fold for ExternJNIMethodTransformer<'ctx> {
             }
         };
 
-        let no_mangle = parse_quote! { #[no_mangle] };
+        if node.attrs.iter().any(|a| a.path().is_ident("synchronized")) {
+            new_block.stmts.insert(0, jni_signature.monitor_enter());
+        }
+
+        if let Some(policy) = get_aliasing_policy(&node) {
+            new_block.stmts.insert(0, jni_signature.aliasing_check(policy));
+        }
+
+        if get_null_check(&node, &jni_signature.call_type) {
+            if let Some(null_check) = jni_signature.null_check() {
+                new_block.stmts.insert(0, null_check);
+            }
+        }
+
+        let overload = get_overload(&node, self.struct_context);
+        if let Some(descriptor) = &overload {
+            // The symbol name is spelled out at expansion time, so it's checked against the conversion traits here
+            let parts = parameter_signature_parts(self.struct_context, &self.call_type, &node.sig);
+            let message = format!(
+                "`{}`: the parameters don't match the signature `{}` given to `#[overload]`",
+                jni_signature.method_name(),
+                descriptor
+            );
+            new_block.stmts.insert(0, parse_quote_spanned! { node.sig.span() =>
+                const _: () = ::std::assert!(
+                    ::robusta_jni::verify::matches_any(&["(", #(#parts,)* ")"], &[#descriptor]),
+                    #message
+                );
+            });
+        }
+
+        // Unwinding into the JVM is undefined behaviour, so panics are always caught, see `robusta_jni::panic`
+        let method_name = jni_signature.method_name();
+        let panic_handler = match get_panic_policy(&node) {
+            PanicPolicy::Throw(exception_class) => quote! {{
+                ::robusta_jni::panic::throw(&env, #method_name, #exception_class, payload);
+                unsafe { ::std::mem::zeroed() }
+            }},
+            PanicPolicy::Abort => quote! { ::robusta_jni::panic::abort(#method_name, payload) },
+        };
+        // The VM is stored on the first call, for `robusta_jni::attach`
+        let mut new_block: Block = parse_quote_spanned! { node.span() => {
+            ::robusta_jni::attach::register(&env);
+            let result = ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| #new_block));
+
+            match result {
+                Ok(result) => result,
+                Err(payload) => #panic_handler,
+            }
+        }};
+
+        // The span ends after the exception is thrown, see `robusta_jni::instrument`
+        if jni_signature.instrument {
+            let arg_count = jni_signature.args_iter().count() - usize::from(jni_signature.self_method);
+            new_block.stmts.insert(0, parse_quote! {
+                let _robusta_span = ::robusta_jni::instrument::Span::enter(
+                    ::robusta_jni::instrument::CallDirection::Exported,
+                    #method_name,
+                    #arg_count,
+                );
+            });
+        }
+
         let impl_item_attributes = {
             let mut attributes = node.attrs.clone();
-            attributes.push(no_mangle);
+            // `#[export_name]` replaces the generated symbol name, e.g. for methods of inner classes
+            let has_export_name = attributes.iter().any(|a| a.path().is_ident("export_name"));
+            if !self.register_natives && !has_export_name {
+                attributes.push(parse_quote! { #[no_mangle] });
+            }
+            // Parameter and return types are spelled as `<T as Trait>::Target`, which gets long with nested types
+            attributes.push(parse_quote! { #[allow(clippy::type_complexity)] });
 
             let discarded_known_attributes: HashSet<&str> = {
                 let mut h = HashSet::new();
                 h.insert("call_type");
+                h.insert("convert");
+                h.insert("check_aliasing");
+                h.insert("null_check");
+                h.insert("on_panic");
+                h.insert("overload");
+                h.insert("synchronized");
+                h.insert("java_name");
+                h.insert("local_frame");
+                h.insert("instrument");
+                h.insert("throws");
                 h
             };
 
@@ -203,79 +701,962 @@ impl<'ctx> Fold for ExternJNIMethodTransformer<'ctx> {
         };
 
         let node_span = node.span();
+        let java_name = exported_java_name(&node, self.struct_context);
         ImplItemFn {
             attrs: impl_item_attributes,
             vis: Visibility::Public(Token![pub](node_span)),
             defaultness: node.defaultness,
-            sig: self.fold_signature(node.sig),
+            sig: self.jni_signature(node.sig, &java_name, overload.as_deref()),
             block: new_block,
         }
     }
+}
 
-    /// Transform original signature in JNI-ready one, including JClass and JNIEnv parameters into the function signature.
-    fn fold_signature(&mut self, node: Signature) -> Signature {
-        let jni_signature =
-            JNISignature::new(node.clone(), &self.struct_context, self.call_type.clone());
-
-        let mut sig = jni_signature.transformed_signature;
+struct JNISignatureTransformer {
+    struct_freestanding_transformer: FreestandingTransformer,
+    struct_lifetimes: Vec<LifetimeParam>,
+    call_type: CallType,
+    /// `impl Fn(...)` parameters, converted from a `JavaFunction`
+    closure_params: Vec<Ident>,
+    /// `#[critical]` parameters, converted from a `Critical`, with their slice type
+    critical_params: Vec<(Ident, Type)>,
+    /// `#[convert]` parameters, converted from a `Converted`, with their type
+    converted_params: Vec<(Ident, Type)>,
+}
 
-        if sig.ident.to_string().contains('_') {
-            emit_error!(sig.ident, "JNI methods cannot contain `_` character");
+impl JNISignatureTransformer {
+    fn new(
+        struct_freestanding_transformer: FreestandingTransformer,
+        struct_lifetimes: Vec<LifetimeParam>,
+        call_type: CallType,
+    ) -> Self {
+        JNISignatureTransformer {
+            struct_freestanding_transformer,
+            struct_lifetimes,
+            call_type,
+            closure_params: vec![],
+            critical_params: vec![],
+            converted_params: vec![],
         }
+    }
 
-        let jni_method_name = {
-            let snake_case_package = self
-                .struct_context
-                .package
-                .as_ref()
-                .map(|s| s.to_snake_case())
-                .unwrap_or_else(|| "".into());
-
-            [
-                "Java",
-                &snake_case_package,
-                &self.struct_context.struct_name,
-                &sig.ident.to_string(),
-            ]
-            .iter()
-            .filter(|s| !s.is_empty())
-            .map(|s| s.to_owned())
-            .collect::<Vec<_>>()
-            .join("_")
-        };
+    fn transform_generics(&mut self, mut generics: Generics) -> Generics {
+        let generics_span = generics.span();
+        generics.params.extend(
+            self.struct_lifetimes
+                .iter()
+                .cloned()
+                .map(GenericParam::Lifetime),
+        );
 
-        sig.inputs = {
-            let mut res = Punctuated::new();
-            res.push(parse_quote!(env: ::robusta_jni::jni::JNIEnv<'env>));
+        let (env_lifetime, borrow_lifetime) = generics.params.iter_mut().fold((None, None), |acc, l| {
+            match l {
+                GenericParam::Lifetime(l) => {
+                    if l.lifetime.ident == "env" {
+                        if l.bounds.iter().any(|b| b.ident != "borrow") {
+                            emit_lint!(l, "using JNI-reserved `'env` lifetime with non `'borrow` bounds";
+                                note = "If you need to access to the lifetime of the `JNIEnv`, please use `'borrow` instead")
+                        }
 
-            if !is_self_method(&node) {
-                res.push(parse_quote!(class: ::robusta_jni::jni::objects::JClass));
+                        (Some(l), acc.1)
+                    } else if l.lifetime.ident == "borrow" {
+                        (acc.0, Some(l))
+                    } else {
+                        acc
+                    }
+                },
+                _ => acc
             }
-
-            res.extend(sig.inputs);
-            res
-        };
-
-        sig.ident = Ident::new(&jni_method_name, sig.ident.span());
-        sig.abi = Some(Abi {
-            extern_token: Extern { span: sig.span() },
-            name: Some(LitStr::new("system", sig.span())),
         });
 
-        sig
-    }
-}
+        match (env_lifetime, borrow_lifetime) {
+            (Some(_), Some(_)) => {}
+            (Some(e), None) => {
+                let borrow_lifetime_value = Lifetime {
+                    apostrophe: generics_span,
+                    ident: Ident::new("borrow", generics_span),
+                };
 
-#[cfg(test)]
-mod test {
-    use std::str::FromStr;
+                e.bounds.push(borrow_lifetime_value.clone());
 
-    use proc_macro2::TokenStream;
+                generics.params.push(GenericParam::Lifetime(LifetimeParam {
+                    attrs: vec![],
+                    lifetime: borrow_lifetime_value,
+                    colon_token: None,
+                    bounds: Default::default(),
+                }))
+            }
+            (None, Some(l)) => {
+                emit_error!(l, "Can't use JNI-reserved `'borrow` lifetime without accompanying `'env: 'borrow` lifetime";
+                    help = "Add `'env: 'borrow` lifetime here")
+            }
+            (None, None) => {
+                let borrow_lifetime_value = Lifetime {
+                    apostrophe: generics_span,
+                    ident: Ident::new("borrow", generics_span),
+                };
 
-    use super::*;
-    use crate::transformation::JavaPath;
+                generics.params.push(GenericParam::Lifetime(LifetimeParam {
+                    attrs: vec![],
+                    lifetime: Lifetime {
+                        apostrophe: generics_span,
+                        ident: Ident::new("env", generics_span),
+                    },
+                    colon_token: None,
+                    bounds: {
+                        let mut p = Punctuated::new();
+                        p.push(borrow_lifetime_value.clone());
+                        p
+                    },
+                }));
 
-    fn setup_package(
+                generics.params.push(GenericParam::Lifetime(LifetimeParam {
+                    attrs: vec![],
+                    lifetime: borrow_lifetime_value,
+                    colon_token: None,
+                    bounds: Default::default(),
+                }))
+            }
+        }
+
+        generics
+    }
+}
+
+impl Fold for JNISignatureTransformer {
+    fn fold_fn_arg(&mut self, arg: FnArg) -> FnArg {
+        let is_receiver = matches!(arg, FnArg::Receiver(_));
+
+        match self.struct_freestanding_transformer.fold_fn_arg(arg) {
+            FnArg::Receiver(_) => panic!("Bug -- please report to library author. Found receiver input after freestanding conversion"),
+            FnArg::Typed(mut t) => {
+                // Reference receivers are converted to an owned value first, and then borrowed in the method call
+                let original_input_type = match *t.ty {
+                    Type::Reference(TypeReference { elem, .. }) if is_receiver => elem,
+                    ty if converter_type(&t.attrs).is_some() => {
+                        if let Pat::Ident(PatIdent { ident, .. }) = t.pat.as_ref() {
+                            self.converted_params.push((ident.clone(), ty.clone()));
+                        }
+                        Box::new(converted_type(&converter_type(&t.attrs).unwrap(), &ty))
+                    }
+                    ty if is_critical_param(&t.attrs) => match critical_array_type(&ty) {
+                        Ok(critical_type) => {
+                            if let Pat::Ident(PatIdent { ident, .. }) = t.pat.as_ref() {
+                                self.critical_params.push((ident.clone(), ty));
+                            }
+                            Box::new(critical_type)
+                        }
+                        Err(e) => {
+                            emit_error!(e.span(), "{}", e;
+                                help = "use e.g. `#[critical] values: &[i32]` or `#[critical] values: &mut [u8]`");
+                            Box::new(ty)
+                        }
+                    },
+                    ty => match java_function_type(&ty) {
+                        Some(Ok(function_type)) => {
+                            if let Pat::Ident(PatIdent { ident, .. }) = t.pat.as_ref() {
+                                self.closure_params.push(ident.clone());
+                            }
+                            Box::new(function_type)
+                        }
+                        Some(Err(e)) => {
+                            emit_error!(e.span(), "{}", e;
+                                help = "take a `robusta_jni::convert::JavaFunction` parameter with a custom `FunctionalInterface` instead");
+                            Box::new(ty)
+                        }
+                        None => Box::new(ty),
+                    },
+                };
+
+                let jni_conversion_type: Type = match self.call_type {
+                    CallType::Safe(_) => parse_quote_spanned! { original_input_type.span() => <#original_input_type as ::robusta_jni::convert::TryFromJavaValue<'env, 'borrow>>::Source },
+                    CallType::Unchecked { .. } => parse_quote_spanned! { original_input_type.span() => <#original_input_type as ::robusta_jni::convert::FromJavaValue<'env, 'borrow>>::Source },
+                };
+
+                if let Pat::Ident(PatIdent { mutability, .. }) = t.pat.as_mut() {
+                    *mutability = None
+                }
+
+                FnArg::Typed(PatType {
+                    attrs: t.attrs.into_iter().filter(|a| !a.path().is_ident("critical") && !a.path().is_ident("convert")).collect(),
+                    pat: t.pat,
+                    colon_token: t.colon_token,
+                    ty: Box::new(jni_conversion_type),
+                })
+            }
+        }
+    }
+
+    fn fold_return_type(&mut self, return_type: ReturnType) -> ReturnType {
+        let return_type = self
+            .struct_freestanding_transformer
+            .fold_return_type(return_type);
+
+        match return_type {
+            ReturnType::Default => return_type,
+            ReturnType::Type(ref arrow, ref rtype) => match (&**rtype, self.call_type.clone()) {
+                (Type::Path(p), CallType::Unchecked { .. }) => ReturnType::Type(
+                    *arrow,
+                    parse_quote_spanned! { p.span() => <#p as ::robusta_jni::convert::IntoJavaValue<'env>>::Target },
+                ),
+
+                // Errors of the method's own type are thrown before the value is converted
+                (Type::Path(p), CallType::Safe(_)) => match result_value_type(rtype) {
+                    Some(value) => ReturnType::Type(
+                        *arrow,
+                        parse_quote_spanned! { p.span() => <#value as ::robusta_jni::convert::TryIntoJavaValue<'env>>::Target },
+                    ),
+                    None => ReturnType::Type(
+                        *arrow,
+                        parse_quote_spanned! { p.span() => <#p as ::robusta_jni::convert::TryIntoJavaValue<'env>>::Target },
+                    ),
+                },
+
+                (Type::Reference(r), CallType::Unchecked { .. }) => {
+                    let r = fill_elided_lifetime(r);
+                    ReturnType::Type(
+                        *arrow,
+                        parse_quote_spanned! { r.span() => <#r as ::robusta_jni::convert::IntoJavaValue<'env>>::Target },
+                    )
+                }
+
+                (Type::Reference(r), CallType::Safe(_)) => {
+                    let r = fill_elided_lifetime(r);
+                    ReturnType::Type(
+                        *arrow,
+                        parse_quote_spanned! { r.span() => <#r as ::robusta_jni::convert::TryIntoJavaValue<'env>>::Target },
+                    )
+                }
+                (Type::Tuple(TypeTuple { elems, .. }), _) if elems.is_empty() => {
+                    ReturnType::Default
+                }
+                (Type::Tuple(t), CallType::Unchecked { .. }) => ReturnType::Type(
+                    *arrow,
+                    parse_quote_spanned! { t.span() => <#t as ::robusta_jni::convert::IntoJavaValue<'env>>::Target },
+                ),
+                (Type::Tuple(t), CallType::Safe(_)) => ReturnType::Type(
+                    *arrow,
+                    parse_quote_spanned! { t.span() => <#t as ::robusta_jni::convert::TryIntoJavaValue<'env>>::Target },
+                ),
+                _ => {
+                    emit_error!(return_type, "only type paths, references and tuples are permitted as return types of exported methods");
+                    return_type
+                }
+            },
+        }
+    }
+
+    fn fold_signature(&mut self, node: Signature) -> Signature {
+        Signature {
+            abi: node.abi.map(|a| self.fold_abi(a)),
+            ident: self.fold_ident(node.ident),
+            generics: self.transform_generics(node.generics),
+            inputs: node
+                .inputs
+                .into_iter()
+                .map(|f| self.fold_fn_arg(f))
+                .collect(),
+            variadic: node.variadic.map(|v| self.fold_variadic(v)),
+            output: self.fold_return_type(node.output),
+            ..node
+        }
+    }
+}
+
+struct JNISignature {
+    transformed_signature: Signature,
+    /// `impl Fn(...)` parameters, which are passed as closures
+    closure_params: Vec<Ident>,
+    /// `#[critical]` parameters, which are passed as slices borrowing Java arrays, with their slice type
+    critical_params: Vec<(Ident, Type)>,
+    /// `#[convert]` parameters, which are converted with their converter, with their type
+    converted_params: Vec<(Ident, Type)>,
+    call_type: CallType,
+    struct_type: Path,
+    struct_name: String,
+    self_method: bool,
+    /// `&self` methods, whose receiver is converted to an owned value and borrowed for the call
+    ref_receiver: bool,
+    mut_receiver: bool,
+    env_arg: Option<FnArg>,
+    /// Parameter receiving the process-wide `Vm` handle
+    vm_arg: Option<FnArg>,
+    /// Parameter receiving the class passed by JNI to static methods
+    class_arg: Option<FnArg>,
+    /// Whether the call is timed, see `robusta_jni::instrument`
+    instrument: bool,
+}
+
+impl JNISignature {
+    fn new(
+        signature: Signature,
+        struct_context: &StructContext,
+        call_type: CallType,
+    ) -> JNISignature {
+        let freestanding_transformer =
+            FreestandingTransformer::new(struct_context.struct_type.clone());
+        let mut jni_signature_transformer = JNISignatureTransformer::new(
+            freestanding_transformer,
+            struct_context.struct_lifetimes.clone(),
+            call_type.clone(),
+        );
+
+        let self_method = is_self_method(&signature);
+        let ref_receiver = signature.inputs.iter().any(|i| {
+            matches!(i, FnArg::Receiver(Receiver { reference: Some(_), mutability: None, .. }))
+        });
+        let mut_receiver = signature.inputs.iter().any(|i| {
+            matches!(i, FnArg::Receiver(Receiver { reference: Some(_), mutability: Some(_), .. }))
+        });
+        let (transformed_signature, env_arg) = get_env_arg(signature, &struct_context.env_aliases);
+        let (transformed_signature, vm_arg) = get_vm_arg(transformed_signature);
+        let (transformed_signature, class_arg) = get_jclass_arg(transformed_signature);
+
+        let transformed_signature = jni_signature_transformer.fold_signature(transformed_signature);
+
+        // The method body must not call JNI functions while Java arrays are borrowed, see `robusta_jni::convert::critical`
+        if !jni_signature_transformer.critical_params.is_empty() {
+            let forbidden = env_arg
+                .as_ref()
+                .map(|e| (e.span(), "a `JNIEnv`"))
+                .into_iter()
+                .chain(vm_arg.as_ref().map(|v| (v.span(), "the `Vm`")))
+                .chain(class_arg.as_ref().map(|c| (c.span(), "a `JClass`")))
+                .chain(jni_signature_transformer.closure_params.iter().map(|c| (c.span(), "closures")));
+            for (span, parameter) in forbidden {
+                emit_error!(span, "methods with `#[critical]` parameters can't take {}", parameter;
+                    note = "no JNI function may be called while `#[critical]` parameters borrow Java arrays");
+            }
+        }
+
+        JNISignature {
+            transformed_signature,
+            closure_params: jni_signature_transformer.closure_params,
+            critical_params: jni_signature_transformer.critical_params,
+            converted_params: jni_signature_transformer.converted_params,
+            call_type,
+            struct_type: struct_context.struct_type.clone(),
+            struct_name: struct_context.struct_name.clone(),
+            self_method,
+            ref_receiver,
+            mut_receiver,
+            env_arg,
+            vm_arg,
+            class_arg,
+            instrument: false,
+        }
+    }
+
+    fn args_iter(&self) -> impl Iterator<Item = &PatType> {
+        self.transformed_signature.inputs.iter()
+            .map(|a| match a {
+                FnArg::Receiver(_) => panic!("Bug -- please report to library author. Found receiver type in freestanding signature!"),
+                FnArg::Typed(p) => p
+            })
+    }
+
+    fn signature_call(&self) -> Expr {
+        let mut bindings: Vec<Stmt> = Vec::new();
+        let method_call_inputs: Punctuated<Expr, Token![,]> = {
+            let mut result: Vec<_> = self.args_iter()
+                .enumerate()
+                .map(|(index, p)| {
+                    match p.pat.as_ref() {
+                        Pat::Ident(PatIdent { ident, .. }) if self.mut_receiver && index == 0 => {
+                            parse_quote_spanned! { ident.span() => &mut #ident }
+                        }
+                        Pat::Ident(PatIdent { ident, .. }) => {
+                            let input_param: Expr = {
+                                match self.call_type {
+                                    CallType::Safe(_) => {
+                                        let parameter_error = self.parameter_error(index, ident, &p.ty);
+                                        parse_quote_spanned! { ident.span() => ::robusta_jni::convert::TryFromJavaValue::try_from(#ident, &env).map_err(#parameter_error)? }
+                                    }
+                                    CallType::Unchecked { .. } => parse_quote_spanned! { ident.span() => ::robusta_jni::convert::FromJavaValue::from(#ident, &env) }
+                                }
+                            };
+
+                            if self.closure_params.contains(ident) {
+                                // The `JavaFunction` type can't be inferred from the `impl Fn(...)` parameter
+                                let function_type = match p.ty.as_ref() {
+                                    Type::Path(TypePath { qself: Some(qself), .. }) => &qself.ty,
+                                    ty => ty,
+                                };
+                                parse_quote_spanned! { ident.span() => {
+                                    let function: #function_type = #input_param;
+                                    function.into_fn()
+                                }}
+                            } else if self.converted_params.iter().any(|(i, _)| i == ident) {
+                                let converted_type = match p.ty.as_ref() {
+                                    Type::Path(TypePath { qself: Some(qself), .. }) => &qself.ty,
+                                    ty => ty,
+                                };
+                                parse_quote_spanned! { ident.span() => {
+                                    let value: #converted_type = #input_param;
+                                    value.into_inner()
+                                }}
+                            } else if self.ref_receiver && index == 0 {
+                                parse_quote_spanned! { ident.span() => &#input_param }
+                            } else {
+                                input_param
+                            }
+                        }
+                        _ => panic!("Bug -- please report to library author. Found non-ident FnArg pattern")
+                    }
+            }).collect();
+
+            if !self.critical_params.is_empty() || self.instrument {
+                // Other parameters are converted before entering the critical region, in which no JNI function may be called.
+                // Instrumented methods convert all parameters before the call is timed.
+                // Critical regions are entered once all arrays are converted, as looking up their lengths calls into JNI.
+                let mut critical_bindings: Vec<Stmt> = Vec::new();
+                let mut critical_entries: Vec<Stmt> = Vec::new();
+                for (index, (p, input)) in self.args_iter().zip(result.iter_mut()).enumerate() {
+                    let ident = match p.pat.as_ref() {
+                        Pat::Ident(PatIdent { ident, .. }) => ident,
+                        _ => continue,
+                    };
+                    match self.critical_params.iter().find(|(i, _)| i == ident) {
+                        Some((_, slice_type)) => {
+                            // Parameters have type `<Critical<'env, 'borrow, T> as TryFromJavaValue<'env, 'borrow>>::Source`
+                            let critical_type = match p.ty.as_ref() {
+                                Type::Path(TypePath { qself: Some(qself), .. }) => &qself.ty,
+                                ty => ty,
+                            };
+                            critical_bindings.push(parse_quote_spanned! { ident.span() => let mut #ident: #critical_type = #input; });
+                            // Shared slices can't be changed, so their elements don't need to be copied back
+                            let mutable = matches!(slice_type, Type::Reference(TypeReference { mutability: Some(_), .. }));
+                            let mode: Expr = if mutable {
+                                parse_quote!(::robusta_jni::jni::objects::ReleaseMode::CopyBack)
+                            } else {
+                                parse_quote!(::robusta_jni::jni::objects::ReleaseMode::NoCopyBack)
+                            };
+                            critical_entries.push(match self.call_type {
+                                CallType::Safe(_) => {
+                                    let parameter_error = self.parameter_error(index, ident, &p.ty);
+                                    parse_quote_spanned! { ident.span() => ::robusta_jni::convert::Critical::enter(&mut #ident, #mode).map_err(#parameter_error)?; }
+                                }
+                                CallType::Unchecked { .. } => parse_quote_spanned! { ident.span() => ::robusta_jni::convert::Critical::enter(&mut #ident, #mode).unwrap(); }
+                            });
+                            if mutable {
+                                *input = parse_quote_spanned! { ident.span() => &mut #ident };
+                            } else {
+                                *input = parse_quote_spanned! { ident.span() => &#ident };
+                            }
+                        }
+                        None => {
+                            bindings.push(parse_quote_spanned! { ident.span() => let #ident = #input; });
+                            *input = parse_quote_spanned! { ident.span() => #ident };
+                        }
+                    }
+                }
+                bindings.extend(critical_bindings);
+                bindings.extend(critical_entries);
+            }
+
+            if let Some(ref e) = self.env_arg {
+                // because `self` is kept in the transformed JNI signature, if this is a `self` method we put `env` *after* self, otherwise the env parameter must be first
+                let idx = if self.self_method { 1 } else { 0 };
+                let env_span = e.span();
+                if is_mut_env_arg(e) {
+                    // `JNIEnv` is `Copy`, so a mutable reference to a copy can be handed out
+                    result.insert(idx, parse_quote_spanned!(env_span => &mut ::robusta_jni::jni::JNIEnv::clone(&env)));
+                } else {
+                    result.insert(idx, parse_quote_spanned!(env_span => &env));
+                }
+            }
+
+            if let Some(ref v) = self.vm_arg {
+                let idx = usize::from(self.self_method) + usize::from(self.env_arg.is_some());
+                let vm: Expr = match self.call_type {
+                    CallType::Safe(_) => parse_quote_spanned!(v.span() => ::robusta_jni::Vm::from_env(&env)?),
+                    CallType::Unchecked { .. } => parse_quote_spanned!(v.span() => ::robusta_jni::Vm::from_env(&env).unwrap()),
+                };
+                result.insert(idx, vm);
+            }
+
+            if let Some(ref c) = self.class_arg {
+                // The class reference is only valid during the call, like the objects obtained from `env`
+                let idx = usize::from(self.env_arg.is_some()) + usize::from(self.vm_arg.is_some());
+                result.insert(idx, parse_quote_spanned!(c.span() => unsafe { ::robusta_jni::jni::objects::JClass::from_raw(class.into_raw()) }));
+            }
+
+            Punctuated::from_iter(result)
+        };
+
+        let signature_span = self.transformed_signature.span();
+        // Types declared outside of the bridged module are named by their path
+        let struct_segments = self.struct_name.split("::").map(|s| Ident::new(s, signature_span));
+        let struct_name = quote! { #(#struct_segments)::* };
+        let method_name = self.transformed_signature.ident.clone();
+
+        let method_call: Expr = if self.instrument {
+            parse_quote_spanned! { signature_span => {
+                #(#bindings)*
+                ::robusta_jni::instrument::call_started();
+                #[allow(clippy::let_unit_value)]
+                let result = #struct_name::#method_name(#method_call_inputs);
+                ::robusta_jni::instrument::call_finished();
+                result
+            }}
+        } else if bindings.is_empty() {
+            parse_quote_spanned! { signature_span => #struct_name::#method_name(#method_call_inputs) }
+        } else {
+            // Borrowed arrays are released when the block ends, before the return value is converted
+            parse_quote_spanned! { signature_span => {
+                #(#bindings)*
+                #struct_name::#method_name(#method_call_inputs)
+            }}
+        };
+
+        if self.mut_receiver {
+            // `&mut self` methods get a receiver converted beforehand, which is written back to the Java instance afterwards
+            let struct_type = &self.struct_type;
+            let (receiver, receiver_type) = self.receiver().expect("`&mut self` methods have a receiver");
+            let (receiver_conversion, receiver_writeback): (Expr, Expr) = match self.call_type {
+                CallType::Safe(_) => {
+                    let parameter_error = self.parameter_error(0, receiver, receiver_type);
+                    (
+                        parse_quote_spanned! { signature_span => ::robusta_jni::convert::TryFromJavaValue::try_from(#receiver, &env).map_err(#parameter_error)? },
+                        parse_quote_spanned! { signature_span => ::robusta_jni::convert::TryWriteBack::try_write_back(#receiver, &env)? },
+                    )
+                }
+                CallType::Unchecked { .. } => (
+                    parse_quote_spanned! { signature_span => ::robusta_jni::convert::FromJavaValue::from(#receiver, &env) },
+                    parse_quote_spanned! { signature_span => ::robusta_jni::convert::WriteBack::write_back(#receiver, &env) },
+                ),
+            };
+
+            parse_quote_spanned! { signature_span => {
+                let mut #receiver: #struct_type = #receiver_conversion;
+                #[allow(clippy::let_unit_value)]
+                let result = #method_call;
+                #receiver_writeback;
+                result
+            }}
+        } else {
+            method_call
+        }
+    }
+
+    /// Parameter `self` was turned into, with its type, for `self` methods
+    fn receiver(&self) -> Option<(&Ident, &Type)> {
+        if !self.self_method {
+            return None;
+        }
+
+        self.args_iter().next().and_then(|p| match p.pat.as_ref() {
+            Pat::Ident(PatIdent { ident, .. }) => Some((ident, p.ty.as_ref())),
+            _ => None,
+        })
+    }
+
+    /// Closure adding the context of the parameter at `index` to a failed conversion of its Java value
+    fn parameter_error(&self, index: usize, ident: &Ident, ty: &Type) -> Expr {
+        // Parameters have type `<T as TryFromJavaValue<'env, 'borrow>>::Source`
+        let rust_type = match ty {
+            Type::Path(TypePath { qself: Some(qself), .. }) => &*qself.ty,
+            _ => ty,
+        };
+        let name = if self.self_method && index == 0 { "self".to_string() } else { ident.to_string() };
+        let rust_type_name = self.rust_type_name(ident, rust_type);
+
+        parse_quote_spanned! { ident.span() => |e| ::robusta_jni::convert::ConversionError::parameter(
+            #index,
+            #name,
+            #rust_type_name,
+            <#rust_type as ::robusta_jni::convert::TryFromJavaValue<'_, '_>>::SIG_TYPE,
+            e,
+        )}
+    }
+
+    /// Name of the Rust type of parameter `ident` as shown in runtime diagnostics
+    fn rust_type_name(&self, ident: &Ident, rust_type: &Type) -> String {
+        // `#[critical]` parameters are shown with the slice type of the method signature, and `#[convert]` parameters
+        // with their type
+        match self.critical_params.iter().chain(&self.converted_params).find(|(i, _)| i == ident) {
+            Some((_, ty)) => type_name(ty),
+            None => type_name(rust_type),
+        }
+    }
+
+    fn transformed_signature(&self) -> &Signature {
+        &self.transformed_signature
+    }
+
+    /// Name of the method as shown in runtime diagnostics, e.g. `User::getName`
+    fn method_name(&self) -> String {
+        format!("{}::{}", self.struct_name, self.transformed_signature.ident)
+    }
+
+    /// Statement returning early from the JNI function if object parameters are aliased, see `robusta_jni::aliasing`
+    fn aliasing_check(&self, policy: Ident) -> Stmt {
+        let method_name = self.method_name();
+        let params: Vec<TokenStream> = self
+            .args_iter()
+            .enumerate()
+            .filter_map(|(index, p)| match p.pat.as_ref() {
+                Pat::Ident(PatIdent { ident, .. }) => {
+                    let name = if self.self_method && index == 0 {
+                        "self".to_string()
+                    } else {
+                        ident.to_string()
+                    };
+                    Some(quote! { (#name, ::robusta_jni::convert::JavaValue::as_object(&#ident)) })
+                }
+                _ => None,
+            })
+            .collect();
+
+        parse_quote! {
+            if cfg!(debug_assertions)
+                && !::robusta_jni::aliasing::check_parameters(&env, #method_name, ::robusta_jni::aliasing::AliasingPolicy::#policy, &[#(#params),*])
+            {
+                return unsafe { ::std::mem::zeroed() };
+            }
+        }
+    }
+
+    /// Statement returning early from the JNI function if a parameter that can't be `null` is, see `robusta_jni::nulls`
+    fn null_check(&self) -> Option<Stmt> {
+        let method_name = self.method_name();
+        let params: Vec<TokenStream> = self
+            .args_iter()
+            .enumerate()
+            .filter_map(|(index, p)| match p.pat.as_ref() {
+                // Java never passes a null receiver
+                Pat::Ident(PatIdent { ident, .. }) if !(self.self_method && index == 0) => {
+                    // Parameters have type `<T as TryFromJavaValue<'env, 'borrow>>::Source`
+                    let rust_type = match p.ty.as_ref() {
+                        Type::Path(TypePath { qself: Some(qself), .. }) => &*qself.ty,
+                        ty => ty,
+                    };
+                    if is_nullable_type(rust_type) {
+                        return None;
+                    }
+
+                    let name = ident.to_string();
+                    let rust_type_name = self.rust_type_name(ident, rust_type);
+                    Some(quote! { (#name, #rust_type_name, ::robusta_jni::convert::JniValue::as_object(&#ident)) })
+                }
+                _ => None,
+            })
+            .collect();
+
+        if params.is_empty() {
+            return None;
+        }
+
+        Some(parse_quote! {
+            if !::robusta_jni::nulls::check_parameters(&env, #method_name, &[#(#params),*]) {
+                return unsafe { ::std::mem::zeroed() };
+            }
+        })
+    }
+
+    /// Statement entering the monitor of the receiver (or of the class for static methods) until the end of the
+    /// enclosing block, like a Java `synchronized` method. Safe methods throw if the monitor can't be entered, and
+    /// receivers whose Java type is primitive, which have no monitor, are rejected at compile time
+    fn monitor_enter(&self) -> Stmt {
+        let monitor = if let Some((receiver, _)) = self.receiver() {
+            let struct_type = LifetimeEraser.fold_path(self.struct_type.clone());
+            // The receiver reference is deleted when the converted `self` is dropped, before the monitor is exited, so
+            // the monitor is held through a new reference
+            quote! {
+                {
+                    const {
+                        assert!(
+                            ::robusta_jni::convert::is_reference(<#struct_type as ::robusta_jni::convert::Signature>::SIG_TYPE),
+                            "`#[synchronized]` methods need a receiver with a monitor, whose Java type is a class"
+                        )
+                    };
+                    ::robusta_jni::convert::JniValue::as_object(&#receiver)
+                        .ok_or(::robusta_jni::jni::errors::Error::NullPtr("receiver"))
+                        .and_then(|obj| env.new_local_ref::<::robusta_jni::jni::objects::JObject>(obj))
+                        .and_then(|obj| env.lock_obj(obj))
+                }
+            }
+        } else {
+            quote! { env.lock_obj(class) }
+        };
+
+        match self.call_type {
+            CallType::Safe(_) => {
+                let method_name = self.method_name();
+                parse_quote! {
+                    let _monitor = match #monitor {
+                        Ok(monitor) => monitor,
+                        Err(e) => {
+                            if !env.exception_check().unwrap_or(false) {
+                                let message = format!("`{}`: couldn't enter the monitor: {}", #method_name, e);
+                                if let Err(e) = env.throw_new("java/lang/IllegalMonitorStateException", message) {
+                                    println!("Error while throwing Java exception: {}", e);
+                                }
+                            }
+                            return unsafe { ::std::mem::zeroed() };
+                        }
+                    };
+                }
+            }
+            CallType::Unchecked(_) => parse_quote! {
+                let _monitor = #monitor.unwrap();
+            },
+        }
+    }
+}
+
+/// Escape `name` for use in a JNI symbol name, as described in the JNI specification:
+/// `_` becomes `_1`, `;` becomes `_2`, `[` becomes `_3`, and any other character that is not an ASCII letter or digit
+/// becomes `_0xxxx`, with `xxxx` the lowercase hexadecimal UTF-16 code unit (e.g. `$` becomes `_00024`).
+pub(crate) fn mangle_jni_name(name: &str) -> String {
+    let mut mangled = String::with_capacity(name.len());
+    for c in name.chars() {
+        match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' => mangled.push(c),
+            '_' => mangled.push_str("_1"),
+            ';' => mangled.push_str("_2"),
+            '[' => mangled.push_str("_3"),
+            _ => {
+                let mut units = [0; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    mangled.push_str(&format!("_0{:04x}", unit));
+                }
+            }
+        }
+    }
+
+    mangled
+}
+
+/// Mangle JNI parameter descriptors such as `(Ljava/lang/String;[I)` for the long name of an overloaded method
+fn mangle_jni_descriptor(descriptor: &str) -> String {
+    let params = descriptor.trim_start_matches('(').trim_end_matches(')');
+    params.split('/').map(mangle_jni_name).collect::<Vec<_>>().join("_")
+}
+
+/// Parameter descriptors (e.g. `(ILjava/lang/String;)`) of a method marked with `#[overload]`: given as argument, or
+/// inferred from the parameter types with a bare `#[overload]`. Returns `None` for methods that aren't overloaded.
+fn get_overload(node: &ImplItemFn, struct_context: &StructContext) -> Option<String> {
+    let attr = node.attrs.iter().find(|a| a.path().is_ident("overload"))?;
+
+    if let Meta::List(_) = &attr.meta {
+        let descriptor = match attr.parse_args::<LitStr>() {
+            Ok(descriptor) => descriptor,
+            Err(_) => {
+                emit_error!(attr, "expected string literal argument for `#[overload]`";
+                    help = "use e.g. `#[overload(\"(ILjava/lang/String;)\")]`");
+                return None;
+            }
+        };
+        let value = descriptor.value();
+        if !value.ends_with(')') || descriptor_params(&format!("{}V", value)).is_none() {
+            emit_error!(descriptor, "invalid parameter descriptors `{}`", value;
+                help = "use the parameter part of the JNI method signature, e.g. `(ILjava/lang/String;)`");
+            return None;
+        }
+        return Some(value);
+    }
+
+    let (signature, _) = get_env_arg(node.sig.clone(), &struct_context.env_aliases);
+    let (signature, _) = get_vm_arg(signature);
+    let (signature, _) = get_jclass_arg(signature);
+    let mut descriptor = String::from("(");
+    for input in &signature.inputs {
+        let ty = match input {
+            FnArg::Typed(t) if !matches!(&*t.pat, Pat::Ident(PatIdent { ident, .. }) if ident == "self") => &t.ty,
+            _ => continue,
+        };
+        match type_descriptor(ty) {
+            Some(d) => descriptor.push_str(&d),
+            None => {
+                emit_error!(ty, "can't infer the Java type of `{}` for the name of an overloaded method", ty.to_token_stream();
+                    help = "give the parameter descriptors of the Java method, e.g. `#[overload(\"(ILjava/lang/String;)\")]`");
+                return None;
+            }
+        }
+    }
+    descriptor.push(')');
+
+    Some(descriptor)
+}
+
+/// JNI type descriptor of `ty`, for primitives, strings and arrays of them, whose Java types are fixed
+fn type_descriptor(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Reference(TypeReference { elem, .. }) => type_descriptor(elem),
+        // Byte arrays are handled as unsigned bytes
+        Type::Slice(TypeSlice { elem, .. }) if matches!(&**elem, Type::Path(p) if p.path.is_ident("u8")) => {
+            Some("[B".to_string())
+        }
+        Type::Slice(TypeSlice { elem, .. }) => type_descriptor(elem).map(|d| format!("[{}", d)),
+        Type::Path(TypePath { qself: None, path }) => {
+            let segment = path.segments.last()?;
+            match &segment.arguments {
+                PathArguments::None => {
+                    let descriptor = match segment.ident.to_string().as_str() {
+                        "bool" | "u8" => "Z",
+                        "i8" => "B",
+                        "char" | "u16" => "C",
+                        "i16" => "S",
+                        "i32" | "u32" => "I",
+                        "i64" | "u64" => "J",
+                        "f32" => "F",
+                        "f64" => "D",
+                        "String" | "str" => "Ljava/lang/String;",
+                        _ => return None,
+                    };
+                    Some(descriptor.to_string())
+                }
+                PathArguments::AngleBracketed(a) if segment.ident == "Box" && a.args.len() == 1 => match a.args.first() {
+                    Some(GenericArgument::Type(elem @ Type::Slice(_))) => type_descriptor(elem),
+                    _ => None,
+                },
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Whether parameters are checked for null references: by default for `#[call_type(safe)]` methods only, or as given
+/// with `#[null_check]` (or `#[null_check(on)]`) and `#[null_check(off)]`
+fn get_null_check(node: &ImplItemFn, call_type: &CallType) -> bool {
+    let default = matches!(call_type, CallType::Safe(_));
+    let attr = match node.attrs.iter().find(|a| a.path().is_ident("null_check")) {
+        Some(attr) => attr,
+        None => return default,
+    };
+
+    match &attr.meta {
+        Meta::Path(_) => true,
+        Meta::List(_) => match attr.parse_args::<Ident>() {
+            Ok(i) if i == "on" => true,
+            Ok(i) if i == "off" => false,
+            _ => {
+                emit_error!(attr, "invalid `null_check` attribute";
+                    help = "use `#[null_check]`, `#[null_check(on)]` or `#[null_check(off)]`");
+                default
+            }
+        },
+        Meta::NameValue(_) => {
+            emit_error!(attr, "invalid `null_check` attribute";
+                help = "use `#[null_check]`, `#[null_check(on)]` or `#[null_check(off)]`");
+            default
+        }
+    }
+}
+
+/// Whether parameters of type `ty` accept `null`: `Option`, `Opaque` and raw JNI references are not null-checked
+fn is_nullable_type(ty: &Type) -> bool {
+    const NULLABLE_TYPES: &[&str] =
+        &["Option", "Opaque", "JObject", "JString", "JClass", "JThrowable", "JByteBuffer", "JList", "JMap", "JValue"];
+
+    match ty {
+        Type::Path(TypePath { path, .. }) => path.segments.last().is_some_and(|s| {
+            let name = s.ident.to_string();
+            // `jobject`, `jstring`, ... from `jni::sys`
+            NULLABLE_TYPES.contains(&name.as_str()) || name.starts_with('j')
+        }),
+        _ => false,
+    }
+}
+
+/// Policy given with the `#[check_aliasing]` attribute, if present: `Error` by default, or `Warn` with `#[check_aliasing(warn)]`
+fn get_aliasing_policy(node: &ImplItemFn) -> Option<Ident> {
+    let attr = node.attrs.iter().find(|a| a.path().is_ident("check_aliasing"))?;
+
+    let policy = match &attr.meta {
+        Meta::Path(_) => "Error",
+        Meta::List(_) => match attr.parse_args::<Ident>() {
+            Ok(i) if i == "warn" => "Warn",
+            Ok(i) if i == "error" => "Error",
+            _ => {
+                emit_error!(attr, "invalid `check_aliasing` attribute";
+                    help = "use `#[check_aliasing]`, `#[check_aliasing(error)]` or `#[check_aliasing(warn)]`");
+                "Error"
+            }
+        },
+        Meta::NameValue(_) => {
+            emit_error!(attr, "invalid `check_aliasing` attribute";
+                help = "use `#[check_aliasing]`, `#[check_aliasing(error)]` or `#[check_aliasing(warn)]`");
+            "Error"
+        }
+    };
+
+    Some(Ident::new(policy, attr.span()))
+}
+
+/// Exception class given with `#[throws("java.io.IOException")]`, thrown when the method returns an `Err`
+fn throws_class(attrs: &[Attribute]) -> Option<syn::Result<JavaPath>> {
+    let attr = attrs.iter().find(|a| a.path().is_ident("throws"))?;
+
+    Some(attr.parse_args::<LitStr>().and_then(|class| {
+        JavaPath::from_str(&class.value()).map_err(|e| syn::Error::new(class.span(), e))
+    }))
+}
+
+/// Action taken when an exported method panics
+enum PanicPolicy {
+    /// Throw an exception of the given class (in JNI format)
+    Throw(String),
+    Abort,
+}
+
+/// Policy given with the `#[on_panic]` attribute: by default a `java.lang.RuntimeException` is thrown
+fn get_panic_policy(node: &ImplItemFn) -> PanicPolicy {
+    let default_policy = PanicPolicy::Throw("java/lang/RuntimeException".to_string());
+    let attr = match node.attrs.iter().find(|a| a.path().is_ident("on_panic")) {
+        Some(attr) => attr,
+        None => return default_policy,
+    };
+
+    let mut policy = None;
+    let result = attr.parse_nested_meta(|meta| {
+        if policy.is_some() {
+            return Err(meta.error("only one panic policy can be given"));
+        }
+
+        if meta.path.is_ident("abort") {
+            policy = Some(PanicPolicy::Abort);
+            Ok(())
+        } else if meta.path.is_ident("throw") {
+            let exception_class: LitStr = meta.value()?.parse()?;
+            let exception_class = JavaPath::from_str(&exception_class.value())
+                .map_err(|e| syn::Error::new(exception_class.span(), e))?;
+
+            policy = Some(PanicPolicy::Throw(exception_class.to_classpath_path()));
+            Ok(())
+        } else {
+            Err(meta.error("unknown panic policy"))
+        }
+    });
+
+    match (result, policy) {
+        (Ok(()), Some(policy)) => policy,
+        (Err(e), _) => {
+            emit_error!(e.span(), "invalid `on_panic` attribute: {}", e;
+                help = "use `#[on_panic(throw = \"java.lang.RuntimeException\")]` or `#[on_panic(abort)]`");
+            default_policy
+        }
+        (Ok(()), None) => {
+            emit_error!(attr, "invalid `on_panic` attribute";
+                help = "use `#[on_panic(throw = \"java.lang.RuntimeException\")]` or `#[on_panic(abort)]`");
+            default_policy
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use proc_macro2::TokenStream;
+
+    use super::*;
+    use crate::transformation::{JavaPath, NameFormat};
+    use darling::FromMeta;
+
+    fn setup_package(
         package: Option<JavaPath>,
         struct_name: String,
         method_name: String,
@@ -287,16 +1668,22 @@ mod test {
             parse_quote! { pub extern "jni" fn #method_name_token_stream() {} };
         let struct_context = StructContext {
             struct_type: parse_quote! { #struct_name_token_stream },
+            class_name: struct_name.clone(),
             struct_name,
             struct_lifetimes: vec![],
             package,
+            kotlin_object: false,
+            rename_all: Default::default(),
+            java_name_format: None,
+            env_aliases: Default::default(),
         };
         let mut transformer = ExternJNIMethodTransformer {
             struct_context: &struct_context,
             call_type: CallType::Safe(None),
+            register_natives: false,
         };
 
-        transformer.fold_impl_item_method(method)
+        transformer.fold_impl_item_fn(method)
     }
 
     #[test]
@@ -324,6 +1711,50 @@ mod test {
         );
     }
 
+    #[test]
+    fn jni_method_names_are_escaped() {
+        let output = setup_package(
+            Some(JavaPath::from_str("com.my_app").unwrap()),
+            "Foo".into(),
+            "get_name".into(),
+        );
+        assert_eq!(
+            output.sig.ident.to_string(),
+            format!("Java_com_my_1app_Foo_get_1name")
+        );
+
+        assert_eq!(mangle_jni_name("Outer$Inner"), "Outer_00024Inner");
+        assert_eq!(mangle_jni_name("caffè"), "caff_000e8");
+    }
+
+    #[test]
+    fn nested_class_method_names() {
+        let method: ImplItemFn = parse_quote! { pub extern "jni" fn foo() {} };
+        let struct_context = StructContext {
+            struct_type: parse_quote! { OuterInner },
+            struct_name: "OuterInner".into(),
+            class_name: "Outer$Inner".into(),
+            struct_lifetimes: vec![],
+            package: Some(JavaPath::from_str("com.example").unwrap()),
+            kotlin_object: false,
+            rename_all: Default::default(),
+            java_name_format: None,
+            env_aliases: Default::default(),
+        };
+        let mut transformer = ExternJNIMethodTransformer {
+            struct_context: &struct_context,
+            call_type: CallType::Safe(None),
+            register_natives: false,
+        };
+
+        let output = transformer.fold_impl_item_fn(method);
+        assert_eq!(
+            output.sig.ident.to_string(),
+            "Java_com_example_Outer_00024Inner_foo"
+        );
+        assert_eq!(struct_context.class_path(), "com/example/Outer$Inner");
+    }
+
     #[test]
     fn jni_method_has_no_mangle() {
         let output = setup_package(None, "Foo".into(), "foo".into());
@@ -349,16 +1780,22 @@ mod test {
 
         let struct_context = StructContext {
             struct_type: parse_quote! { #struct_name_token_stream },
+            class_name: struct_name.clone(),
             struct_name,
             struct_lifetimes: vec![],
             package,
+            kotlin_object: false,
+            rename_all: Default::default(),
+            java_name_format: None,
+            env_aliases: Default::default(),
         };
         let mut transformer = ExternJNIMethodTransformer {
             struct_context: &struct_context,
             call_type: CallType::Safe(None),
+            register_natives: false,
         };
 
-        transformer.fold_impl_item_method(method)
+        transformer.fold_impl_item_fn(method)
     }
 
     #[test]
@@ -399,10 +1836,132 @@ mod test {
                 );
             }
 
-            _ => assert!(false),
+            _ => panic!("unexpected JNI signature inputs"),
         }
     }
 
+    #[test]
+    fn class_param_is_taken_from_jni() {
+        use quote::quote;
+
+        let output = setup_with_params(quote! { class: JClass<'env>, _1: i32 }, "Foo".to_string());
+        let params: Vec<String> = output
+            .sig
+            .inputs
+            .iter()
+            .map(|i| match i {
+                FnArg::Typed(PatType { pat, .. }) => pat.to_token_stream().to_string(),
+                FnArg::Receiver(_) => panic!("unexpected receiver"),
+            })
+            .collect();
+
+        assert_eq!(params, ["env", "class", "_1"]);
+    }
+
+    #[test]
+    fn vm_param_is_taken_from_env() {
+        use quote::quote;
+
+        let output = setup_with_params(quote! { vm: &'static Vm, _1: i32 }, "Foo".to_string());
+        let params: Vec<String> = output
+            .sig
+            .inputs
+            .iter()
+            .map(|i| match i {
+                FnArg::Typed(PatType { pat, .. }) => pat.to_token_stream().to_string(),
+                FnArg::Receiver(_) => panic!("unexpected receiver"),
+            })
+            .collect();
+        let block = output.block.to_token_stream().to_string();
+
+        assert_eq!(params, ["env", "class", "_1"]);
+        assert!(block.contains("Foo :: foo (:: robusta_jni :: Vm :: from_env (& env) ?"));
+    }
+
+    #[test]
+    fn parameter_errors_have_context() {
+        use quote::quote;
+
+        let output = setup_with_params(quote! { self, count: u32, names: Vec<String> }, "Foo".to_string());
+        let block = output.block.to_token_stream().to_string();
+
+        assert!(block.contains("ConversionError :: parameter (0usize , \"self\" , \"Foo\""));
+        assert!(block.contains("ConversionError :: parameter (1usize , \"count\" , \"u32\""));
+        assert!(block.contains("ConversionError :: parameter (2usize , \"names\" , \"Vec<String>\""));
+    }
+
+    #[test]
+    fn critical_params_are_converted_last() {
+        use quote::quote;
+
+        let output = setup_with_params(quote! { #[critical] values: &mut [i32], name: String }, "Foo".to_string());
+        let block = output.block.to_token_stream().to_string();
+        let signature = output.sig.to_token_stream().to_string();
+
+        assert!(!signature.contains("critical"));
+        assert!(signature.contains("values : < :: robusta_jni :: convert :: Critical < 'env , 'borrow , i32 >"));
+
+        let name_conversion = block.find("let name =").unwrap();
+        let values_conversion = block.find("let mut values : :: robusta_jni :: convert :: Critical").unwrap();
+        assert!(name_conversion < values_conversion);
+        assert!(block.contains("Foo :: foo (& mut values , name)"));
+        assert!(block.contains("Critical :: enter (& mut values , :: robusta_jni :: jni :: objects :: ReleaseMode :: CopyBack)"));
+        assert!(block.contains("ConversionError :: parameter (0usize , \"values\" , \"&mut [i32]\""));
+    }
+
+    #[test]
+    fn critical_regions_are_entered_after_all_lengths() {
+        use quote::quote;
+
+        let output = setup_with_params(quote! { #[critical] values: &[i32], #[critical] weights: &[f64] }, "Foo".to_string());
+        let block = output.block.to_token_stream().to_string();
+
+        // Looking up the length of `weights` calls into JNI, which is not allowed once `values` is entered
+        let weights_conversion = block.find("let mut weights : :: robusta_jni :: convert :: Critical").unwrap();
+        // Shared slices are released without copying back their elements
+        let values_entry = block.find("Critical :: enter (& mut values , :: robusta_jni :: jni :: objects :: ReleaseMode :: NoCopyBack)").unwrap();
+        let weights_entry = block.find("Critical :: enter (& mut weights , :: robusta_jni :: jni :: objects :: ReleaseMode :: NoCopyBack)").unwrap();
+        assert!(weights_conversion < values_entry);
+        assert!(values_entry < weights_entry);
+        assert!(block.contains("Foo :: foo (& values , & weights)"));
+    }
+
+    #[test]
+    fn null_checks_skip_nullable_params() {
+        use quote::quote;
+
+        let output = setup_with_params(
+            quote! { self, name: String, count: i32, alias: Option<String>, raw: JObject<'env> },
+            "Foo".to_string(),
+        );
+        let block = output.block.to_token_stream().to_string();
+
+        assert!(block.contains("nulls :: check_parameters (& env , \"Foo::foo\" , & [(\"name\" , \"String\" , :: robusta_jni :: convert :: JniValue :: as_object (& name)) , (\"count\" , \"i32\" , :: robusta_jni :: convert :: JniValue :: as_object (& count))])"));
+    }
+
+    #[test]
+    fn closure_params_take_functional_interfaces() {
+        let interface = |ty: Type| {
+            let function_type = java_function_type(&ty).unwrap().unwrap();
+            let tokens = function_type.to_token_stream().to_string();
+            let start = tokens.find("function ::").unwrap() + "function :: ".len();
+            tokens[start..].split(' ').next().unwrap().to_string()
+        };
+
+        assert_eq!(interface(parse_quote! { impl Fn() }), "Runnable");
+        assert_eq!(interface(parse_quote! { impl Fn() -> String }), "Supplier");
+        assert_eq!(interface(parse_quote! { impl Fn(i32) -> i32 }), "IntUnaryOperator");
+        assert_eq!(interface(parse_quote! { impl FnMut(f64, f64) -> f64 }), "DoubleBinaryOperator");
+        assert_eq!(interface(parse_quote! { impl Fn(i64) -> bool }), "LongPredicate");
+        assert_eq!(interface(parse_quote! { impl FnOnce(i32) + Send }), "IntConsumer");
+        assert_eq!(interface(parse_quote! { impl Fn(i32) -> i64 }), "Function");
+        assert_eq!(interface(parse_quote! { impl Fn(String, i32) -> bool }), "BiPredicate");
+        assert_eq!(interface(parse_quote! { impl Fn(String, String) }), "BiConsumer");
+
+        assert!(java_function_type(&parse_quote! { impl Fn(i32, i32, i32) -> i32 }).unwrap().is_err());
+        assert!(java_function_type(&parse_quote! { impl Iterator<Item = i32> }).is_none());
+    }
+
     #[test]
     fn self_method_params() {
         use quote::quote;
@@ -444,272 +2003,485 @@ mod test {
                 );
             }
 
-            _ => assert!(false),
+            _ => panic!("unexpected JNI signature inputs"),
         }
     }
-}
 
-struct JNISignatureTransformer {
-    struct_freestanding_transformer: FreestandingTransformer,
-    struct_lifetimes: Vec<LifetimeParam>,
-    call_type: CallType,
-}
+    #[test]
+    fn mut_self_method_params() {
+        use quote::quote;
 
-impl JNISignatureTransformer {
-    fn new(
-        struct_freestanding_transformer: FreestandingTransformer,
-        struct_lifetimes: Vec<LifetimeParam>,
-        call_type: CallType,
-    ) -> Self {
-        JNISignatureTransformer {
-            struct_freestanding_transformer,
-            struct_lifetimes,
-            call_type,
+        let struct_name = "Foo".to_string();
+        let struct_name_toks = TokenStream::from_str(&struct_name).unwrap();
+
+        let param_type_1: TokenStream = parse_quote! { i32 };
+        let output = setup_with_params(
+            quote! { &mut self, _1: #param_type_1 },
+            struct_name.clone(),
+        );
+
+        let self_conv_type: Type = parse_quote! { <#struct_name_toks as ::robusta_jni::convert::TryFromJavaValue<'env, 'borrow>>::Source };
+
+        let args: &[FnArg] = &output.sig.inputs.into_iter().collect::<Vec<_>>();
+        match args {
+            [FnArg::Typed(_), FnArg::Typed(PatType { ty: ty_self, .. }), FnArg::Typed(_)] => {
+                assert_eq!(
+                    ty_self.to_token_stream().to_string(),
+                    self_conv_type.to_token_stream().to_string()
+                );
+            }
+
+            _ => panic!("unexpected JNI signature inputs"),
         }
+
+        let block = output.block.to_token_stream().to_string();
+        assert!(block.contains("TryWriteBack :: try_write_back"));
     }
 
-    fn transform_generics(&mut self, mut generics: Generics) -> Generics {
-        let generics_span = generics.span();
-        generics.params.extend(
-            self.struct_lifetimes
+    #[test]
+    fn ref_self_method_borrows_receiver() {
+        use quote::quote;
+
+        let output = setup_with_params(quote! { &self, _1: i32 }, "Foo".to_string());
+
+        let block = output.block.to_token_stream().to_string();
+        assert!(block.contains("& :: robusta_jni :: convert :: TryFromJavaValue :: try_from (__robusta_receiver"));
+        assert!(!block.contains("try_write_back"));
+    }
+
+    #[test]
+    fn self_methods_allow_receiver_param() {
+        use quote::quote;
+
+        for receiver in [quote! { &self }, quote! { &mut self }] {
+            let output = setup_with_params(quote! { #receiver, receiver: i32 }, "Foo".to_string());
+
+            let params: Vec<String> = output
+                .sig
+                .inputs
                 .iter()
-                .cloned()
-                .map(GenericParam::Lifetime),
+                .filter_map(|i| match i {
+                    FnArg::Typed(PatType { pat, .. }) => Some(pat.to_token_stream().to_string()),
+                    FnArg::Receiver(_) => None,
+                })
+                .collect();
+            assert_eq!(params, ["env", "__robusta_receiver", "receiver"]);
+
+            // The `receiver` parameter is converted like any other parameter, not borrowed as `self`
+            let block = output.block.to_token_stream().to_string();
+            assert!(block.contains("TryFromJavaValue :: try_from (receiver , & env) . map_err (| e | :: robusta_jni :: convert :: ConversionError :: parameter (1usize , \"receiver\""));
+            assert!(block.contains("ConversionError :: parameter (0usize , \"self\" , \"Foo\""));
+        }
+    }
+
+    fn setup_with_attribute(attribute: TokenStream) -> ImplItemFn {
+        let method: ImplItemFn = parse_quote! {
+            #attribute
+            pub extern "jni" fn foo() -> i32 {}
+        };
+
+        let struct_context = StructContext {
+            struct_type: parse_quote! { Foo },
+            struct_name: "Foo".into(),
+            class_name: "Foo".into(),
+            struct_lifetimes: vec![],
+            package: None,
+            kotlin_object: false,
+            rename_all: Default::default(),
+            java_name_format: None,
+            env_aliases: Default::default(),
+        };
+        let mut transformer = ExternJNIMethodTransformer {
+            struct_context: &struct_context,
+            call_type: CallType::Safe(None),
+            register_natives: false,
+        };
+
+        transformer.fold_impl_item_fn(method)
+    }
+
+    #[test]
+    fn export_name_replaces_no_mangle() {
+        use quote::quote;
+
+        let output = setup_with_attribute(quote! { #[export_name = "Java_Outer_00024Inner_foo"] });
+        let no_mangle = parse_quote! { #[no_mangle] };
+        assert!(!output.attrs.contains(&no_mangle));
+        assert!(output.attrs.iter().any(|a| a.path().is_ident("export_name")));
+    }
+
+    #[test]
+    fn manifest_symbols() {
+        let struct_context = StructContext {
+            struct_type: parse_quote! { Foo },
+            struct_name: "Foo".into(),
+            class_name: "Foo".into(),
+            struct_lifetimes: vec![],
+            package: None,
+            kotlin_object: false,
+            rename_all: Default::default(),
+            java_name_format: None,
+            env_aliases: Default::default(),
+        };
+        let methods: Vec<ImplItemFn> = vec![
+            parse_quote! { pub extern "jni" fn foo(self) -> i32 {} },
+            parse_quote! { #[export_name = "Java_Outer_00024Inner_bar"] pub extern "jni" fn bar() -> i32 {} },
+        ];
+
+        let symbols = |register_natives| {
+            let mut transformer = ExportedMethodTransformer {
+                struct_context: &struct_context,
+                register_natives,
+                native_methods: Vec::new(),
+                method_constants: Vec::new(),
+            };
+            for method in methods.clone() {
+                transformer.fold_impl_item_fn(method);
+            }
+            transformer.native_methods.into_iter().map(|m| (m.symbol, m.is_static)).collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            symbols(false),
+            [(Some("Java_Foo_foo".to_string()), false), (Some("Java_Outer_00024Inner_bar".to_string()), true)]
         );
+        assert_eq!(symbols(true), [(None, false), (None, true)]);
+    }
 
-        let (env_lifetime, borrow_lifetime) = generics.params.iter_mut().fold((None, None), |acc, l| {
-            match l {
-                GenericParam::Lifetime(l) => {
-                    if l.lifetime.ident == "env" {
-                        if l.bounds.iter().any(|b| b.ident != "borrow") {
-                            emit_warning!(l, "using JNI-reserved `'env` lifetime with non `'borrow` bounds";
-                                note = "If you need to access to the lifetime of the `JNIEnv`, please use `'borrow` instead")
-                        }
+    #[test]
+    fn method_constants_use_rust_and_java_names() {
+        use quote::quote;
+
+        let struct_context = StructContext {
+            struct_type: parse_quote! { Foo },
+            struct_name: "Foo".into(),
+            class_name: "Foo".into(),
+            struct_lifetimes: vec![],
+            package: None,
+            kotlin_object: false,
+            rename_all: Default::default(),
+            java_name_format: None,
+            env_aliases: Default::default(),
+        };
+        let mut transformer = ExportedMethodTransformer {
+            struct_context: &struct_context,
+            register_natives: false,
+            native_methods: Vec::new(),
+            method_constants: Vec::new(),
+        };
+        transformer.fold_impl_item_fn(parse_quote! { #[java_name("nativeFoo")] pub extern "jni" fn foo(x: i32) -> i32 {} });
+
+        let constants = &transformer.method_constants;
+        assert_eq!(constants.len(), 1);
+        assert_eq!(constants[0].rust_name, "foo");
+        assert_eq!(constants[0].java_name, "nativeFoo");
+
+        let parts = &constants[0].signature_parts;
+        assert_eq!(
+            quote! { #(#parts),* }.to_string(),
+            quote! { "(", <i32 as ::robusta_jni::convert::TryFromJavaValue<'_, '_>>::SIG_TYPE, ")", <i32 as ::robusta_jni::convert::TryIntoJavaValue<'_>>::SIG_TYPE }.to_string()
+        );
+    }
+
+    #[test]
+    fn overloads_use_long_names() {
+        use quote::quote;
+
+        let output = setup_with_attribute(quote! { #[overload] });
+        assert_eq!(output.sig.ident, "Java_Foo_foo__");
+        assert!(!output.attrs.iter().any(|a| a.path().is_ident("overload")));
+
+        let output = setup_with_attribute(quote! { #[overload("(I[BLjava/lang/String;)")] });
+        assert_eq!(output.sig.ident, "Java_Foo_foo__I_3BLjava_lang_String_2");
+
+        let descriptor = |ty: Type| type_descriptor(&ty);
+        assert_eq!(descriptor(parse_quote! { &str }).as_deref(), Some("Ljava/lang/String;"));
+        assert_eq!(descriptor(parse_quote! { Box<[u8]> }).as_deref(), Some("[B"));
+        assert_eq!(descriptor(parse_quote! { &[i64] }).as_deref(), Some("[J"));
+        assert_eq!(descriptor(parse_quote! { Box<[String]> }).as_deref(), Some("[Ljava/lang/String;"));
+        assert_eq!(descriptor(parse_quote! { Vec<i32> }), None);
+    }
 
-                        (Some(l), acc.1)
-                    } else if l.lifetime.ident == "borrow" {
-                        (acc.0, Some(l))
-                    } else {
-                        acc
-                    }
-                },
-                _ => acc
-            }
-        });
+    #[test]
+    fn java_name_changes_symbol() {
+        use quote::quote;
 
-        match (env_lifetime, borrow_lifetime) {
-            (Some(_), Some(_)) => {}
-            (Some(e), None) => {
-                let borrow_lifetime_value = Lifetime {
-                    apostrophe: generics_span,
-                    ident: Ident::new("borrow", generics_span),
-                };
+        let output = setup_with_attribute(quote! { #[java_name("native_foo")] });
+        assert_eq!(output.sig.ident, "Java_Foo_native_1foo");
+        assert!(!output.attrs.iter().any(|a| a.path().is_ident("java_name")));
 
-                e.bounds.push(borrow_lifetime_value.clone());
+        let format = NameFormat::from_string("native{PascalCase}").unwrap();
+        assert_eq!(format.apply("get_value"), "nativeGetValue");
+        assert_eq!(NameFormat::from_string("{name}_{snake_case}").unwrap().apply("getValue"), "getValue_get_value");
+        assert!(NameFormat::from_string("native").is_err());
+        assert!(NameFormat::from_string("native{Upper}").is_err());
+    }
 
-                generics.params.push(GenericParam::Lifetime(LifetimeParam {
-                    attrs: vec![],
-                    lifetime: borrow_lifetime_value,
-                    colon_token: None,
-                    bounds: Default::default(),
-                }))
-            }
-            (None, Some(l)) => {
-                emit_error!(l, "Can't use JNI-reserved `'borrow` lifetime without accompanying `'env: 'borrow` lifetime";
-                    help = "Add `'env: 'borrow` lifetime here")
-            }
-            (None, None) => {
-                let borrow_lifetime_value = Lifetime {
-                    apostrophe: generics_span,
-                    ident: Ident::new("borrow", generics_span),
-                };
+    #[test]
+    fn synchronized_static_methods_lock_the_class() {
+        use quote::quote;
 
-                generics.params.push(GenericParam::Lifetime(LifetimeParam {
-                    attrs: vec![],
-                    lifetime: Lifetime {
-                        apostrophe: generics_span,
-                        ident: Ident::new("env", generics_span),
-                    },
-                    colon_token: None,
-                    bounds: {
-                        let mut p = Punctuated::new();
-                        p.push(borrow_lifetime_value.clone());
-                        p
-                    },
-                }));
+        let output = setup_with_attribute(quote! { #[synchronized] });
+        let block = output.block.to_token_stream().to_string();
+        assert!(block.contains("env . lock_obj (class)"));
+        assert!(block.contains("IllegalMonitorStateException"));
+        assert!(!output.attrs.iter().any(|a| a.path().is_ident("synchronized")));
 
-                generics.params.push(GenericParam::Lifetime(LifetimeParam {
-                    attrs: vec![],
-                    lifetime: borrow_lifetime_value,
-                    colon_token: None,
-                    bounds: Default::default(),
-                }))
-            }
-        }
+        let output = setup_with_attribute(TokenStream::new());
+        assert!(!output.block.to_token_stream().to_string().contains("lock_obj"));
+    }
 
-        generics
+    #[test]
+    fn local_frame_wraps_conversions() {
+        use quote::quote;
+
+        let output = setup_with_attribute(quote! { #[local_frame(capacity = 512)] });
+        let block = output.block.to_token_stream().to_string();
+        assert!(block.contains(":: robusta_jni :: frame :: with_local_frame (env , 512i32"));
+        assert!(!output.attrs.iter().any(|a| a.path().is_ident("local_frame")));
+
+        let output = setup_with_attribute(quote! { #[local_frame] });
+        assert!(output.block.to_token_stream().to_string().contains(":: robusta_jni :: frame :: DEFAULT_CAPACITY"));
+
+        let output = setup_with_attribute(TokenStream::new());
+        assert!(!output.block.to_token_stream().to_string().contains("with_local_frame"));
     }
-}
 
-impl Fold for JNISignatureTransformer {
-    fn fold_fn_arg(&mut self, arg: FnArg) -> FnArg {
-        match self.struct_freestanding_transformer.fold_fn_arg(arg) {
-            FnArg::Receiver(_) => panic!("Bug -- please report to library author. Found receiver input after freestanding conversion"),
-            FnArg::Typed(mut t) => {
-                let original_input_type = t.ty;
+    #[test]
+    fn panics_are_thrown_by_default() {
+        let output = setup_with_attribute(TokenStream::new());
+        let block = output.block.to_token_stream().to_string();
 
-                let jni_conversion_type: Type = match self.call_type {
-                    CallType::Safe(_) => parse_quote_spanned! { original_input_type.span() => <#original_input_type as ::robusta_jni::convert::TryFromJavaValue<'env, 'borrow>>::Source },
-                    CallType::Unchecked { .. } => parse_quote_spanned! { original_input_type.span() => <#original_input_type as ::robusta_jni::convert::FromJavaValue<'env, 'borrow>>::Source },
-                };
+        assert!(block.contains("catch_unwind"));
+        assert!(block.contains("\"java/lang/RuntimeException\""));
+    }
 
-                if let Pat::Ident(PatIdent { mutability, .. }) = t.pat.as_mut() {
-                    *mutability = None
-                }
+    #[test]
+    fn panic_policy_attribute() {
+        use quote::quote;
 
-                FnArg::Typed(PatType {
-                    attrs: t.attrs,
-                    pat: t.pat,
-                    colon_token: t.colon_token,
-                    ty: Box::new(jni_conversion_type),
-                })
-            }
-        }
+        let output = setup_with_attribute(quote! { #[on_panic(throw = "java.lang.IllegalStateException")] });
+        let block = output.block.to_token_stream().to_string();
+        assert!(block.contains("\"java/lang/IllegalStateException\""));
+        assert!(!output.attrs.iter().any(|a| a.path().is_ident("on_panic")));
+
+        let output = setup_with_attribute(quote! { #[on_panic(abort)] });
+        let block = output.block.to_token_stream().to_string();
+        assert!(block.contains("robusta_jni :: panic :: abort"));
     }
 
-    fn fold_return_type(&mut self, return_type: ReturnType) -> ReturnType {
-        match return_type {
-            ReturnType::Default => return_type,
-            ReturnType::Type(ref arrow, ref rtype) => match (&**rtype, self.call_type.clone()) {
-                (Type::Path(p), CallType::Unchecked { .. }) => ReturnType::Type(
-                    *arrow,
-                    parse_quote_spanned! { p.span() => <#p as ::robusta_jni::convert::IntoJavaValue<'env>>::Target },
-                ),
+    #[test]
+    fn self_return_type_is_replaced() {
+        let method: ImplItemFn = parse_quote! {
+            pub extern "jni" fn foo() -> Box<[Self]> {}
+        };
 
-                (Type::Path(p), CallType::Safe(_)) => ReturnType::Type(
-                    *arrow,
-                    parse_quote_spanned! { p.span() => <#p as ::robusta_jni::convert::TryIntoJavaValue<'env>>::Target },
-                ),
+        let struct_context = StructContext {
+            struct_type: parse_quote! { Foo },
+            struct_name: "Foo".into(),
+            class_name: "Foo".into(),
+            struct_lifetimes: vec![],
+            package: None,
+            kotlin_object: false,
+            rename_all: Default::default(),
+            java_name_format: None,
+            env_aliases: Default::default(),
+        };
+        let mut transformer = ExternJNIMethodTransformer {
+            struct_context: &struct_context,
+            call_type: CallType::Safe(None),
+            register_natives: false,
+        };
 
-                (Type::Reference(r), CallType::Unchecked { .. }) => ReturnType::Type(
-                    *arrow,
-                    parse_quote_spanned! { r.span() => <#r as ::robusta_jni::convert::IntoJavaValue<'env>>::Target },
-                ),
+        let output = transformer.fold_impl_item_fn(method);
+        let return_type: Type = parse_quote! { <Box<[Foo]> as ::robusta_jni::convert::TryIntoJavaValue<'env>>::Target };
 
-                (Type::Reference(r), CallType::Safe(_)) => ReturnType::Type(
-                    *arrow,
-                    parse_quote_spanned! { r.span() => <#r as ::robusta_jni::convert::TryIntoJavaValue<'env>>::Target },
-                ),
-                (Type::Tuple(TypeTuple { elems, .. }), _) if elems.is_empty() => {
-                    ReturnType::Default
-                }
-                _ => {
-                    emit_error!(return_type, "Only type or type paths are permitted as type ascriptions in function params");
-                    return_type
-                }
-            },
+        match output.sig.output {
+            ReturnType::Type(_, ty) => assert_eq!(
+                ty.to_token_stream().to_string(),
+                return_type.to_token_stream().to_string()
+            ),
+            ReturnType::Default => panic!("unexpected JNI signature output"),
         }
     }
 
-    fn fold_signature(&mut self, node: Signature) -> Signature {
-        Signature {
-            abi: node.abi.map(|a| self.fold_abi(a)),
-            ident: self.fold_ident(node.ident),
-            generics: self.transform_generics(node.generics),
-            inputs: node
-                .inputs
-                .into_iter()
-                .map(|f| self.fold_fn_arg(f))
-                .collect(),
-            variadic: node.variadic.map(|v| self.fold_variadic(v)),
-            output: self.fold_return_type(node.output),
-            ..node
+    #[test]
+    fn errors_of_their_own_type_are_thrown() {
+        let method: ImplItemFn = parse_quote! {
+            pub extern "jni" fn foo() -> Result<i32, ConfigError> {}
+        };
+
+        let struct_context = StructContext {
+            struct_type: parse_quote! { Foo },
+            struct_name: "Foo".into(),
+            class_name: "Foo".into(),
+            struct_lifetimes: vec![],
+            package: None,
+            kotlin_object: false,
+            rename_all: Default::default(),
+            java_name_format: None,
+            env_aliases: Default::default(),
+        };
+        let mut transformer = ExternJNIMethodTransformer {
+            struct_context: &struct_context,
+            call_type: CallType::Safe(None),
+            register_natives: false,
+        };
+
+        let output = transformer.fold_impl_item_fn(method);
+        let return_type: Type = parse_quote! { <i32 as ::robusta_jni::convert::TryIntoJavaValue<'env>>::Target };
+        match &output.sig.output {
+            ReturnType::Type(_, ty) => assert_eq!(
+                ty.to_token_stream().to_string(),
+                return_type.to_token_stream().to_string()
+            ),
+            ReturnType::Default => panic!("unexpected JNI signature output"),
         }
+
+        let block = output.block.to_token_stream().to_string();
+        assert!(block.contains("(Foo :: foo ()) . map_err (:: robusta_jni :: convert :: ConversionError :: method) ?"));
     }
-}
 
-struct JNISignature {
-    transformed_signature: Signature,
-    call_type: CallType,
-    struct_name: String,
-    self_method: bool,
-    env_arg: Option<FnArg>,
-}
+    #[test]
+    fn converted_params_and_return_use_converter() {
+        use quote::quote;
 
-impl JNISignature {
-    fn new(
-        signature: Signature,
-        struct_context: &StructContext,
-        call_type: CallType,
-    ) -> JNISignature {
-        let freestanding_transformer =
-            FreestandingTransformer::new(struct_context.struct_type.clone());
-        let mut jni_signature_transformer = JNISignatureTransformer::new(
-            freestanding_transformer,
-            struct_context.struct_lifetimes.clone(),
-            call_type.clone(),
-        );
+        let method: ImplItemFn = parse_quote! {
+            #[convert(with = "IntIp")]
+            pub extern "jni" fn foo(#[convert(with = "DottedIp")] ip: Ipv4Addr) -> Ipv4Addr {}
+        };
 
-        let self_method = is_self_method(&signature);
-        let (transformed_signature, env_arg) = get_env_arg(signature);
+        let struct_context = StructContext {
+            struct_type: parse_quote! { Foo },
+            struct_name: "Foo".into(),
+            class_name: "Foo".into(),
+            struct_lifetimes: vec![],
+            package: None,
+            kotlin_object: false,
+            rename_all: Default::default(),
+            java_name_format: None,
+            env_aliases: Default::default(),
+        };
+        let mut transformer = ExternJNIMethodTransformer {
+            struct_context: &struct_context,
+            call_type: CallType::Safe(None),
+            register_natives: false,
+        };
 
-        let transformed_signature = jni_signature_transformer.fold_signature(transformed_signature);
+        let output = transformer.fold_impl_item_fn(method);
+        let inputs = output.sig.inputs.to_token_stream().to_string();
+        let block = output.block.to_token_stream().to_string();
 
-        JNISignature {
-            transformed_signature,
-            call_type,
-            struct_name: struct_context.struct_name.clone(),
-            self_method,
-            env_arg,
-        }
+        let param_type = quote! { Converted<DottedIp, Ipv4Addr> }.to_string();
+        assert!(inputs.contains(&param_type), "{}", inputs);
+        assert!(block.contains("into_inner"));
+        assert!(block.contains(&quote! { Converted::<IntIp, _>::new }.to_string()), "{}", block);
+        assert!(!output.attrs.iter().any(|a| a.path().is_ident("convert")));
     }
 
-    fn args_iter(&self) -> impl Iterator<Item = &PatType> {
-        self.transformed_signature.inputs.iter()
-            .map(|a| match a {
-                FnArg::Receiver(_) => panic!("Bug -- please report to library author. Found receiver type in freestanding signature!"),
-                FnArg::Typed(p) => p
-            })
-    }
+    #[test]
+    fn instrumented_methods_time_the_call() {
+        let method: ImplItemFn = parse_quote! {
+            #[instrument]
+            pub extern "jni" fn foo(self, count: i32, name: String) -> i32 {}
+        };
 
-    fn signature_call(&self) -> Expr {
-        let method_call_inputs: Punctuated<Expr, Token![,]> = {
-            let mut result: Vec<_> = self.args_iter()
-                .map(|p| {
-                    match p.pat.as_ref() {
-                        Pat::Ident(PatIdent { ident, .. }) => {
-                            let input_param: Expr = {
-                                match self.call_type {
-                                    CallType::Safe(_) => parse_quote_spanned! { ident.span() => ::robusta_jni::convert::TryFromJavaValue::try_from(#ident, &env)? },
-                                    CallType::Unchecked { .. } => parse_quote_spanned! { ident.span() => ::robusta_jni::convert::FromJavaValue::from(#ident, &env) }
-                                }
-                            };
-                            input_param
-                        }
-                        _ => panic!("Bug -- please report to library author. Found non-ident FnArg pattern")
-                    }
-            }).collect();
+        let struct_context = StructContext {
+            struct_type: parse_quote! { Foo },
+            struct_name: "Foo".into(),
+            class_name: "Foo".into(),
+            struct_lifetimes: vec![],
+            package: None,
+            kotlin_object: false,
+            rename_all: Default::default(),
+            java_name_format: None,
+            env_aliases: Default::default(),
+        };
+        let mut transformer = ExternJNIMethodTransformer {
+            struct_context: &struct_context,
+            call_type: CallType::Safe(None),
+            register_natives: false,
+        };
 
-            if let Some(ref e) = self.env_arg {
-                // because `self` is kept in the transformed JNI signature, if this is a `self` method we put `env` *after* self, otherwise the env parameter must be first
-                let idx = if self.self_method { 1 } else { 0 };
-                let env_span = e.span();
-                result.insert(idx, parse_quote_spanned!(env_span => &env));
-            }
+        let output = transformer.fold_impl_item_fn(method);
+        let block = output.block.to_token_stream().to_string();
+
+        // The span covers the whole method, and the call starts once the parameters are converted
+        let span = block.find("Span :: enter").expect("missing span");
+        let conversion = block.find("TryFromJavaValue :: try_from (name").expect("missing conversion");
+        let call_started = block.find("call_started").expect("missing call start");
+        let call = block.find("Foo :: foo").expect("missing call");
+        let call_finished = block.find("call_finished").expect("missing call end");
+        assert!(span < conversion && conversion < call_started && call_started < call && call < call_finished, "{}", block);
+        assert!(block.contains("\"Foo::foo\" , 2usize"), "{}", block);
+        assert!(!output.attrs.iter().any(|a| a.path().is_ident("instrument")));
+    }
 
-            Punctuated::from_iter(result.into_iter())
+    #[test]
+    fn declared_exception_is_thrown_on_errors() {
+        let method: ImplItemFn = parse_quote! {
+            #[throws("java.io.IOException")]
+            pub extern "jni" fn foo() -> JniResult<i32> {}
         };
 
-        let signature_span = self.transformed_signature.span();
-        let struct_name = Ident::new(&self.struct_name, signature_span);
-        let method_name = self.transformed_signature.ident.clone();
+        let struct_context = StructContext {
+            struct_type: parse_quote! { Foo },
+            struct_name: "Foo".into(),
+            class_name: "Foo".into(),
+            struct_lifetimes: vec![],
+            package: None,
+            kotlin_object: false,
+            rename_all: Default::default(),
+            java_name_format: None,
+            env_aliases: Default::default(),
+        };
+        let mut transformer = ExternJNIMethodTransformer {
+            struct_context: &struct_context,
+            call_type: CallType::Safe(Some(SafeParams {
+                exception_class: Some("java.lang.IllegalStateException".parse().unwrap()),
+                message: None,
+                cause_class: None,
+            })),
+            register_natives: false,
+        };
 
-        parse_quote_spanned! { signature_span =>
-            #struct_name::#method_name(#method_call_inputs)
-        }
+        let output = transformer.fold_impl_item_fn(method);
+        let block = output.block.to_token_stream().to_string();
+
+        assert!(block.contains("throw_chained (& env , \"java/io/IOException\""), "{}", block);
+        assert!(!block.contains("IllegalStateException"), "{}", block);
+        assert!(!output.attrs.iter().any(|a| a.path().is_ident("throws")));
     }
 
-    fn transformed_signature(&self) -> &Signature {
-        &self.transformed_signature
+    #[test]
+    fn tuple_return_types_are_converted() {
+        let method: ImplItemFn = parse_quote! {
+            pub extern "jni" fn foo() -> (i32, String) {}
+        };
+
+        let struct_context = StructContext {
+            struct_type: parse_quote! { Foo },
+            struct_name: "Foo".into(),
+            class_name: "Foo".into(),
+            struct_lifetimes: vec![],
+            package: None,
+            kotlin_object: false,
+            rename_all: Default::default(),
+            java_name_format: None,
+            env_aliases: Default::default(),
+        };
+        let mut transformer = ExternJNIMethodTransformer {
+            struct_context: &struct_context,
+            call_type: CallType::Safe(None),
+            register_natives: false,
+        };
+
+        let output = transformer.fold_impl_item_fn(method);
+        let return_type = output.sig.output.to_token_stream().to_string();
+
+        assert_eq!(
+            return_type,
+            "-> < (i32 , String) as :: robusta_jni :: convert :: TryIntoJavaValue < 'env > > :: Target"
+        );
     }
 }
@@ -1,20 +1,269 @@
-use inflector::cases::camelcase::to_camel_case;
+use darling::FromMeta;
 use proc_macro2::{TokenStream, TokenTree};
-use proc_macro_error::{abort, emit_error, emit_warning};
-use quote::{quote_spanned, ToTokens};
+use proc_macro_error::{abort, emit_error};
+use quote::{quote, quote_spanned, ToTokens};
 use syn::fold::Fold;
 use syn::spanned::Spanned;
 use syn::{parse_quote, GenericArgument, PathArguments, Type, TypePath};
-use syn::{FnArg, ImplItemFn, Lit, Pat, PatIdent, ReturnType, Signature};
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Expr, ExprLit, FnArg, Ident, ImplItemFn, Lit, LitStr, Local, Meta, MetaNameValue, Pat, PatIdent, ReturnType, Signature, Stmt, Token};
 
+use crate::transformation::builder::BuilderConstructor;
+use crate::transformation::constants::MethodConstants;
 use crate::transformation::context::StructContext;
+use crate::transformation::descriptors::{CheckedMethod, Descriptors};
 use crate::transformation::utils::get_call_type;
-use crate::transformation::{CallType, CallTypeAttribute, SafeParams};
-use crate::utils::{get_abi, get_class_arg_if_any, get_env_arg, is_self_method};
+use crate::transformation::exported::LifetimeEraser;
+use crate::transformation::{CallType, CallTypeAttribute, FreestandingTransformer, JavaPath, SafeParams};
+use crate::utils::{
+    check_convert_attributes, check_helper_attributes, check_misplaced_env_args, converted_return_type, converted_type, converter_type,
+    get_abi, get_class_arg_if_any, get_env_arg, is_instrumented, is_self_method, java_name_override, local_frame_capacity,
+    IMPORTED_METHOD_ATTRIBUTES,
+};
 use std::collections::HashSet;
 
 pub struct ImportedMethodTransformer<'ctx> {
     pub(crate) struct_context: &'ctx StructContext,
+    /// Expressions evaluating to a `robusta_jni::verify::ImportedMethod` for each transformed method
+    pub(crate) verified_methods: Vec<TokenStream>,
+    /// Constructors annotated with `#[constructor(builder)]`
+    pub(crate) builder_constructors: Vec<BuilderConstructor>,
+    /// Descriptors to check imported methods against, see `#[bridge(check_against = "...")]`
+    pub(crate) descriptors: Option<&'ctx Descriptors>,
+    /// Items checking signatures against `descriptors` at compile time
+    pub(crate) signature_checks: Vec<TokenStream>,
+    /// Names and signatures of the transformed methods, exposed as constants of the struct
+    pub(crate) method_constants: Vec<MethodConstants>,
+    /// Whether the struct has an `#[on_first_use]` function, called before each method (see `robusta_jni::first_use`)
+    pub(crate) first_use: bool,
+}
+
+impl<'ctx> ImportedMethodTransformer<'ctx> {
+    /// Expression evaluating to the JNI signature of an imported method, usable outside of the impl block
+    fn verification_signature(&self, signature: &Signature, call_type: &CallType, is_constructor: bool, is_suspend: bool) -> TokenStream {
+        let parts = self.verification_signature_parts(signature, call_type, is_constructor, is_suspend);
+        quote! { [#(#parts),*].join("") }
+    }
+
+    /// Constant expressions whose concatenation is the JNI signature of an imported method, usable outside of the impl block
+    fn verification_signature_parts(&self, signature: &Signature, call_type: &CallType, is_constructor: bool, is_suspend: bool) -> Vec<TokenStream> {
+        // Types are used outside of the impl block, so `Self` must be replaced and lifetimes must be inferred
+        let mut type_transformer = FreestandingTransformer::new(self.struct_context.struct_type.clone());
+        let mut lifetime_eraser = LifetimeEraser;
+        let mut erase = |ty: &Type| lifetime_eraser.fold_type(type_transformer.fold_type(ty.clone()));
+
+        let input_signatures: Vec<TokenStream> = signature
+            .inputs
+            .iter()
+            .filter_map(|i| match i {
+                FnArg::Typed(t) => match &*t.pat {
+                    Pat::Ident(PatIdent { ident, .. }) if ident == "self" => None,
+                    _ => Some(t),
+                },
+                FnArg::Receiver(_) => None,
+            })
+            .map(|t| {
+                let override_input_type = t
+                    .attrs
+                    .iter()
+                    .find(|a| a.path().is_ident("input_type"))
+                    .and_then(|a| a.parse_args::<LitStr>().ok());
+                let ty = erase(&t.ty);
+
+                match (override_input_type, call_type) {
+                    (Some(input_type), _) => quote! { #input_type },
+                    (None, CallType::Safe(_)) => quote! { <#ty as ::robusta_jni::convert::TryIntoJavaValue<'_>>::SIG_TYPE },
+                    (None, CallType::Unchecked(_)) => quote! { <#ty as ::robusta_jni::convert::IntoJavaValue<'_>>::SIG_TYPE },
+                }
+            })
+            .collect();
+
+        // Suspend functions take a continuation, and return either their result or `COROUTINE_SUSPENDED`
+        if is_suspend {
+            return std::iter::once(quote! { "(" })
+                .chain(input_signatures)
+                .chain(std::iter::once(quote! { "Lkotlin/coroutines/Continuation;)Ljava/lang/Object;" }))
+                .collect();
+        }
+
+        let output_signature = match (&signature.output, call_type) {
+            (ReturnType::Default, _) => quote! { "V" },
+            _ if is_constructor => quote! { "V" },
+            (ReturnType::Type(_, ty), CallType::Safe(_)) => {
+                let inner_result_ty = match &**ty {
+                    Type::Path(TypePath { path, .. }) => path.segments.last().and_then(|s| match &s.arguments {
+                        PathArguments::AngleBracketed(a) => match a.args.first() {
+                            Some(GenericArgument::Type(t)) => Some(erase(t)),
+                            _ => None,
+                        },
+                        _ => None,
+                    }),
+                    _ => None,
+                };
+
+                // Invalid return types are already reported by the method transformation
+                match inner_result_ty {
+                    Some(ty) => quote! { <#ty as ::robusta_jni::convert::TryIntoJavaValue<'_>>::SIG_TYPE },
+                    None => quote! { "V" },
+                }
+            }
+            (ReturnType::Type(_, ty), CallType::Unchecked(_)) => {
+                let ty = erase(ty);
+                quote! { <#ty as ::robusta_jni::convert::IntoJavaValue<'_>>::SIG_TYPE }
+            }
+        };
+
+        std::iter::once(quote! { "(" })
+            .chain(input_signatures)
+            .chain(vec![quote! { ")" }, output_signature])
+            .collect()
+    }
+
+    /// Compile-time checks that the parameters of a constructor match the signature given with `#[constructor(signature = "...")]`
+    fn constructor_signature_checks(&self, signature: &Signature, constructor_signature: &LitStr, call_type: &CallType) -> TokenStream {
+        let descriptor = constructor_signature.value();
+        let (param_descriptors, return_descriptor) = match descriptor_params(&descriptor) {
+            Some(parsed) => parsed,
+            None => {
+                emit_error!(constructor_signature, "invalid constructor signature"; help = "use e.g. `#[constructor(signature = \"(Ljava/lang/String;I)V\")]`");
+                return TokenStream::new();
+            }
+        };
+
+        if return_descriptor != "V" {
+            emit_error!(constructor_signature, "constructor signatures must have a `V` return type");
+        }
+
+        let params: Vec<_> = signature
+            .inputs
+            .iter()
+            .filter_map(|i| match i {
+                FnArg::Typed(t) => match &*t.pat {
+                    Pat::Ident(PatIdent { ident, .. }) if ident == "self" => None,
+                    _ => Some(t),
+                },
+                FnArg::Receiver(_) => None,
+            })
+            .collect();
+
+        if params.len() != param_descriptors.len() {
+            emit_error!(constructor_signature, "constructor signature has {} parameters, but the constructor takes {}", param_descriptors.len(), params.len());
+            return TokenStream::new();
+        }
+
+        // Types are used in constants, so `Self` must be replaced and lifetimes must be inferred
+        let mut type_transformer = FreestandingTransformer::new(self.struct_context.struct_type.clone());
+        let mut lifetime_eraser = LifetimeEraser;
+
+        params
+            .iter()
+            .zip(param_descriptors)
+            .filter_map(|(param, expected)| {
+                let param_name = param.pat.to_token_stream().to_string();
+                let override_input_type = param
+                    .attrs
+                    .iter()
+                    .find(|a| a.path().is_ident("input_type"))
+                    .and_then(|a| a.parse_args::<LitStr>().ok());
+
+                if let Some(input_type) = override_input_type {
+                    if input_type.value() != expected {
+                        emit_error!(input_type, "input type of parameter `{}` doesn't match constructor signature, expected `{}`", param_name, expected);
+                    }
+                    return None;
+                }
+
+                let ty = lifetime_eraser.fold_type(type_transformer.fold_type((*param.ty).clone()));
+                let sig_type = match call_type {
+                    CallType::Safe(_) => quote! { <#ty as ::robusta_jni::convert::TryIntoJavaValue<'_>>::SIG_TYPE },
+                    CallType::Unchecked(_) => quote! { <#ty as ::robusta_jni::convert::IntoJavaValue<'_>>::SIG_TYPE },
+                };
+                let message = format!("type of parameter `{}` doesn't match constructor signature `{}`, expected `{}`", param_name, descriptor, expected);
+
+                Some(quote_spanned! { param.ty.span() =>
+                    const _: () = ::std::assert!(::robusta_jni::convert::signatures_eq(#sig_type, #expected), #message);
+                })
+            })
+            .collect()
+    }
+}
+
+/// Dispatch of an imported self method, selected with `#[call(...)]`
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Dispatch {
+    Virtual,
+    Nonvirtual,
+    Interface,
+}
+
+/// Parse the argument of a `#[call]` attribute
+fn parse_dispatch(attribute: &Attribute) -> Option<Dispatch> {
+    match attribute.parse_args::<Ident>() {
+        Ok(kind) if kind == "virtual" => Some(Dispatch::Virtual),
+        Ok(kind) if kind == "nonvirtual" => Some(Dispatch::Nonvirtual),
+        Ok(kind) if kind == "interface" => Some(Dispatch::Interface),
+        _ => {
+            emit_error!(attribute, "invalid `#[call]` argument"; help = "use `#[call(virtual)]`, `#[call(nonvirtual)]` or `#[call(interface)]`");
+            None
+        }
+    }
+}
+
+/// Parse the argument of a `#[class]` attribute on an imported method into a class path in the format expected by
+/// `JNIEnv::find_class`, from either a string literal (`#[class("java.lang.System")]`) or a path (`#[class(java.lang.System)]`)
+fn parse_class_override(attribute: &Attribute) -> Option<String> {
+    let path = match attribute.parse_args::<LitStr>() {
+        Ok(literal) => match JavaPath::from_string(&literal.value()) {
+            Ok(path) => path,
+            Err(e) => {
+                emit_error!(literal, "{}", e);
+                return None;
+            }
+        },
+        Err(_) => match attribute.parse_args::<JavaPath>() {
+            Ok(path) => path,
+            Err(e) => {
+                emit_error!(e.span(), "{}", e; help = "use e.g. `#[class(\"java.lang.System\")]`");
+                return None;
+            }
+        },
+    };
+
+    Some(path.to_classpath_path())
+}
+
+/// Parse the arguments of a `#[constructor]` attribute: whether a builder is requested, and the explicit signature if any
+fn parse_constructor_args(attribute: &Attribute) -> (bool, Option<LitStr>) {
+    let args = match &attribute.meta {
+        Meta::List(meta_list) => match meta_list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated) {
+            Ok(args) => args,
+            Err(e) => {
+                emit_error!(e.span(), "invalid `#[constructor]` arguments: {}", e; help = "use e.g. `#[constructor(builder, signature = \"(I)V\")]`");
+                return (false, None);
+            }
+        },
+        _ => return (false, None),
+    };
+
+    let mut builder = false;
+    let mut signature = None;
+    for arg in args {
+        match arg {
+            Meta::Path(path) if path.is_ident("builder") => builder = true,
+            Meta::NameValue(MetaNameValue {
+                path,
+                value: Expr::Lit(ExprLit { lit: Lit::Str(s), .. }),
+                ..
+            }) if path.is_ident("signature") => signature = Some(s),
+            other => emit_lint!(
+                other,
+                "unknown `#[constructor]` parameter";
+                help = "`#[constructor]` takes `builder` and `signature = \"...\"` parameters"
+            ),
+        }
+    }
+
+    (builder, signature)
 }
 
 impl<'ctx> Fold for ImportedMethodTransformer<'ctx> {
@@ -24,24 +273,50 @@ impl<'ctx> Fold for ImportedMethodTransformer<'ctx> {
             (_, Some("java")) => {
                 let constructor_attribute =
                     node.attrs.iter().find(|a| a.path().is_ident("constructor"));
-                let is_constructor = {
-                    match constructor_attribute {
-                        Some(a) => {
-                            if a.meta
-                                .require_list()
-                                .is_ok_and(|meta_list| !meta_list.tokens.is_empty())
-                            {
-                                emit_warning!(
-                                    a.to_token_stream(),
-                                    "#[constructor] attribute does not take parameters"
-                                )
-                            }
-                            true
-                        }
-                        None => false,
+                let (is_constructor, is_builder_constructor, constructor_signature) = match constructor_attribute {
+                    Some(a) => {
+                        let (builder, signature) = parse_constructor_args(a);
+                        (true, builder, signature)
                     }
+                    None => (false, false, None),
                 };
 
+                let java_name_attribute =
+                    node.attrs.iter().find(|a| a.path().is_ident("java_name"));
+                let java_name_override = java_name_override(&node.attrs);
+
+                let fallback_signatures: Vec<(LitStr, usize)> = node
+                    .attrs
+                    .iter()
+                    .filter(|a| a.path().is_ident("fallback_signature"))
+                    .filter_map(|a| match a.parse_args::<LitStr>() {
+                        Ok(signature) => match descriptor_arg_count(&signature.value()) {
+                            Some(count) => Some((signature, count)),
+                            None => {
+                                emit_error!(signature, "invalid method signature"; help = "use e.g. `#[fallback_signature(\"(I)V\")]`");
+                                None
+                            }
+                        },
+                        Err(_) => {
+                            emit_error!(a, "expected string literal argument for `#[fallback_signature]`"; help = "use e.g. `#[fallback_signature(\"(I)V\")]`");
+                            None
+                        }
+                    })
+                    .collect();
+
+                let companion_attribute =
+                    node.attrs.iter().find(|a| a.path().is_ident("companion"));
+                let is_companion = companion_attribute.is_some();
+
+                let class_attribute = node.attrs.iter().find(|a| a.path().is_ident("class"));
+                let class_override = class_attribute.and_then(parse_class_override);
+
+                let dispatch_attribute = node.attrs.iter().find(|a| a.path().is_ident("call"));
+                let dispatch = dispatch_attribute.and_then(parse_dispatch).unwrap_or(Dispatch::Virtual);
+
+                let suspend_attribute = node.attrs.iter().find(|a| a.path().is_ident("kotlin_suspend"));
+                let is_suspend = suspend_attribute.is_some();
+
                 if !node.block.stmts.is_empty() {
                     emit_error!(
                         node.block,
@@ -51,17 +326,51 @@ impl<'ctx> Fold for ImportedMethodTransformer<'ctx> {
 
                 let mut original_signature = node.sig.clone();
                 let self_method = is_self_method(&node.sig);
-                let (signature, env_arg) = get_env_arg(node.sig.clone());
+                check_misplaced_env_args(&node.sig, &self.struct_context.env_aliases);
+                check_helper_attributes(&node.attrs, IMPORTED_METHOD_ATTRIBUTES, "`extern \"java\"` methods");
+                check_convert_attributes(&node.attrs, &node.sig);
+                let (signature, env_arg) =
+                    get_env_arg(node.sig.clone(), &self.struct_context.env_aliases);
                 let (mut signature, class_ref_arg) = get_class_arg_if_any(signature.clone());
 
+                // Parameters and return values marked with `#[convert]` are converted as `Converted` values
+                let unconverted_inputs = signature.inputs.clone();
+                for input in signature.inputs.iter_mut() {
+                    if let FnArg::Typed(t) = input {
+                        if let Some(converter) = converter_type(&t.attrs) {
+                            *t.ty = converted_type(&converter, &t.ty);
+                        }
+                    }
+                }
+                let return_converter = converter_type(&node.attrs);
+                if let Some(converter) = &return_converter {
+                    signature.output = converted_return_type(converter, signature.output);
+                }
+
                 let impl_item_attributes: Vec<_> = {
                     let discarded_known_attributes: HashSet<&str> = {
                         let mut h = HashSet::new();
                         h.insert("call_type");
+                        h.insert("convert");
 
                         if is_constructor {
                             h.insert("constructor");
                         }
+
+                        if is_companion {
+                            h.insert("companion");
+                        }
+
+                        if java_name_attribute.is_some() {
+                            h.insert("java_name");
+                        }
+
+                        h.insert("fallback_signature");
+                        h.insert("call");
+                        h.insert("class");
+                        h.insert("kotlin_suspend");
+                        h.insert("local_frame");
+                        h.insert("instrument");
                         h
                     };
 
@@ -96,6 +405,94 @@ impl<'ctx> Fold for ImportedMethodTransformer<'ctx> {
                     return dummy;
                 }
 
+                if let Some(convert_attribute) = node.attrs.iter().find(|a| a.path().is_ident("convert")) {
+                    if is_constructor || matches!(original_signature.output, ReturnType::Default) {
+                        emit_error!(
+                            convert_attribute,
+                            "`#[convert]` can only be used on methods with a return value";
+                            help = "constructors always return an instance of the struct, use `#[convert(with = \"...\")]` on parameters to convert them"
+                        );
+
+                        return dummy;
+                    }
+                }
+
+                if let Some(companion_attribute) = companion_attribute {
+                    if self_method || is_constructor {
+                        emit_error!(
+                            companion_attribute,
+                            "`#[companion]` can only be used on static methods"
+                        );
+
+                        return dummy;
+                    }
+                }
+
+                if let Some(dispatch_attribute) = dispatch_attribute {
+                    if !self_method {
+                        emit_error!(
+                            dispatch_attribute,
+                            "`#[call]` can only be used on self methods";
+                            help = "static methods and constructors are always called on the class of the struct"
+                        );
+
+                        return dummy;
+                    }
+                }
+
+                if let Some(class_attribute) = class_attribute {
+                    if self_method || is_constructor || is_companion || self.struct_context.kotlin_object {
+                        emit_error!(
+                            class_attribute,
+                            "`#[class]` can only be used on static methods";
+                            help = "self methods are called on their receiver, and constructors always create an instance of the struct"
+                        );
+
+                        return dummy;
+                    }
+
+                    if class_ref_arg.is_some() {
+                        emit_error!(
+                            class_attribute,
+                            "`#[class]` can't be used on methods with a class parameter";
+                            help = "the class parameter already selects the class the method is called on"
+                        );
+
+                        return dummy;
+                    }
+                }
+
+                if let Some(suspend_attribute) = suspend_attribute {
+                    if is_constructor {
+                        emit_error!(
+                            suspend_attribute,
+                            "`#[kotlin_suspend]` can't be used on constructors";
+                            help = "Kotlin constructors can't be suspend functions"
+                        );
+
+                        return dummy;
+                    }
+
+                    if !fallback_signatures.is_empty() {
+                        emit_error!(
+                            suspend_attribute,
+                            "`#[kotlin_suspend]` can't be used with `#[fallback_signature]`"
+                        );
+
+                        return dummy;
+                    }
+                }
+
+                if is_constructor && self.struct_context.kotlin_object {
+                    emit_error!(
+                        original_signature,
+                        "cannot declare constructors on `#[kotlin_object]` structs";
+                        help = "Kotlin objects are instantiated once, use methods without `self` to call them"
+                    );
+
+                    return dummy;
+                }
+
                 if env_arg.is_none() {
                     if !self_method {
                         emit_error!(
@@ -118,36 +515,41 @@ impl<'ctx> Fold for ImportedMethodTransformer<'ctx> {
                     .unwrap_or(&CallType::Safe(None));
 
                 if let Some(CallTypeAttribute { attr, .. }) = &call_type_attribute {
-                    if let CallType::Safe(Some(params)) = call_type {
-                        if let SafeParams {
+                    if let CallType::Safe(Some(
+                        SafeParams {
                             message: Some(_), ..
                         }
                         | SafeParams {
                             exception_class: Some(_),
                             ..
-                        } = params
-                        {
-                            abort!(attr, "can't have exception message or exception class for imported methods")
                         }
+                        | SafeParams {
+                            cause_class: Some(_), ..
+                        },
+                    )) = call_type
+                    {
+                        abort!(attr, "can't have exception message, exception class or cause class for imported methods")
                     }
                 }
 
-                let jni_package_path = self
-                    .struct_context
-                    .package
-                    .as_ref()
-                    .map(|p| p.to_string())
-                    .filter(|s| !s.is_empty())
-                    .unwrap_or_else(|| "".into())
-                    .replace('.', "/");
+                let java_class_path = class_override.clone().unwrap_or_else(|| self.struct_context.class_path());
+                let java_method_name = match java_name_override {
+                    Some(java_name) => java_name,
+                    None => self
+                        .struct_context
+                        .rename_all
+                        .apply(&signature.ident.to_string()),
+                };
 
-                let java_class_path = [jni_package_path, self.struct_context.struct_name.clone()]
-                    .iter()
-                    .filter(|s| !s.is_empty())
-                    .map(|s| s.to_owned())
-                    .collect::<Vec<_>>()
-                    .join("/");
-                let java_method_name = to_camel_case(&signature.ident.to_string());
+                // Static methods of Kotlin `object`s and companion objects are instance methods of a singleton,
+                // which is stored in a static field of the class.
+                let singleton_field = if is_companion {
+                    Some(("Companion", format!("L{}$Companion;", java_class_path)))
+                } else if self.struct_context.kotlin_object && !self_method {
+                    Some(("INSTANCE", format!("L{};", java_class_path)))
+                } else {
+                    None
+                };
 
                 let input_types_conversions = signature
                     .inputs
@@ -243,7 +645,7 @@ impl<'ctx> Fold for ImportedMethodTransformer<'ctx> {
                                                     "maybe you meant `#[call_type(safe)]`?"
                                                 });
 
-                                                emit_warning!(ty, "using a `Result` type in a `#[call_type(unchecked)]` method";
+                                                emit_lint!(ty, "using a `Result` type in a `#[call_type(unchecked)]` method";
                                             hint =? call_type_span.unwrap() => call_type_hint)
                                             }
                                         }
@@ -255,7 +657,20 @@ impl<'ctx> Fold for ImportedMethodTransformer<'ctx> {
                     }
                 };
 
-                let java_signature = quote_spanned! { signature.span() => ["(", #input_types_conversions ")", #output_conversion].join("") };
+                let java_signature = match &constructor_signature {
+                    Some(constructor_signature) => {
+                        let checks = self.constructor_signature_checks(&signature, constructor_signature, call_type);
+                        quote_spanned! { constructor_signature.span() => {
+                            #checks
+                            ::std::string::ToString::to_string(#constructor_signature)
+                        }}
+                    }
+                    // Suspend functions take a continuation, and return either their result or `COROUTINE_SUSPENDED`
+                    None if is_suspend => quote_spanned! { signature.span() =>
+                        ["(", #input_types_conversions "Lkotlin/coroutines/Continuation;)Ljava/lang/Object;"].join("")
+                    },
+                    None => quote_spanned! { signature.span() => ["(", #input_types_conversions ")", #output_conversion].join("") },
+                };
 
                 let input_conversions = signature.inputs.iter().fold(TokenStream::new(), |mut tok, input| {
                     match input {
@@ -271,10 +686,16 @@ impl<'ctx> Fold for ImportedMethodTransformer<'ctx> {
                                 p.into()
                             };
 
+                            // `#[convert]` parameters have a `Converted` type
+                            let pat = match converter_type(&t.attrs) {
+                                Some(_) => quote_spanned! { ty.span() => ::robusta_jni::convert::converter::Converted::new(#pat) },
+                                None => pat,
+                            };
+
                             let conversion: TokenStream = if let CallType::Safe(_) = call_type {
                                 quote_spanned! { ty.span() => ::std::convert::Into::into(<#ty as ::robusta_jni::convert::TryIntoJavaValue>::try_into(#pat, &env)?), }
                             } else {
-                                quote_spanned! { ty.span() => ::std::convert::Into::into(<#ty as ::robusta_jni::convert::IntoJavaValue>::into(#pat, &env)), }
+                                quote_spanned! { ty.span() => ::robusta_jni::convert::JavaValue::into_jvalue(<#ty as ::robusta_jni::convert::IntoJavaValue>::into(#pat, &env), &env), }
                             };
                             conversion.to_tokens(&mut tok);
                             tok
@@ -282,34 +703,159 @@ impl<'ctx> Fold for ImportedMethodTransformer<'ctx> {
                     }
                 });
 
+                let arg_count = signature
+                    .inputs
+                    .iter()
+                    .filter(|i| match i {
+                        FnArg::Typed(t) => !matches!(&*t.pat, Pat::Ident(PatIdent { ident, .. }) if ident == "self"),
+                        FnArg::Receiver(_) => false,
+                    })
+                    .count();
+                for (fallback, count) in &fallback_signatures {
+                    if *count > arg_count {
+                        emit_error!(fallback, "fallback signature has more parameters than the method";
+                            note = "parameters of fallback signatures must be a prefix of the method parameters");
+                    }
+                }
+
+                // Suspend functions take a continuation as last argument
+                let arg_values = if is_suspend {
+                    quote! { #input_conversions ::robusta_jni::jni::objects::JValue::Object(continuation) }
+                } else {
+                    input_conversions
+                };
+                // Instrumented methods time the call once its arguments are converted, see `robusta_jni::instrument`
+                let instrument = is_instrumented(&node.attrs);
+                let arg_array = if instrument {
+                    quote! { {
+                        let args = [#arg_values];
+                        ::robusta_jni::instrument::call_started();
+                        args
+                    } }
+                } else {
+                    quote! { [#arg_values] }
+                };
+
+                // With fallback signatures, the signature and the number of arguments to pass are selected at runtime
+                let (java_signature, call_args, resolve_signature) = if is_suspend || fallback_signatures.is_empty() {
+                    (java_signature, quote! { &#arg_array }, None)
+                } else {
+                    let fallbacks: Vec<_> = fallback_signatures.iter().map(|(s, count)| quote! { (#s, #count) }).collect();
+                    let signature_span = signature.span();
+                    let resolve_error = match call_type {
+                        CallType::Safe(_) => quote! { ? },
+                        CallType::Unchecked(_) => quote! { .unwrap() },
+                    };
+                    let method_name = if is_constructor { "<init>".to_string() } else { java_method_name.clone() };
+                    let resolve_signature = move |class: TokenStream, is_static: bool| -> TokenStream {
+                        quote_spanned! { signature_span =>
+                            static SIGNATURE_CACHE: ::robusta_jni::fallback::SignatureCache = ::robusta_jni::fallback::SignatureCache::new();
+                            let primary_signature = #java_signature;
+                            let (signature, arg_count) = SIGNATURE_CACHE
+                                .resolve(env, #class, #method_name, #is_static, &[(primary_signature.as_str(), #arg_count), #(#fallbacks),*])
+                                #resolve_error;
+                        }
+                    };
+
+                    (quote! { signature }, quote! { &#arg_array[..arg_count] }, Some(resolve_signature))
+                };
+                let resolve_signature = |class: TokenStream, is_static: bool| -> TokenStream {
+                    resolve_signature.as_ref().map(|r| r(class, is_static)).unwrap_or_default()
+                };
+
+                // Suspend functions are called with a continuation, and their result is unboxed once they complete
+                let suspend_return_sig = match &signature.output {
+                    ReturnType::Default => quote! { "V" },
+                    ReturnType::Type(..) => output_conversion.clone(),
+                };
+                let suspend = |call: TokenStream| -> TokenStream {
+                    if is_suspend {
+                        quote! { ::robusta_jni::coroutines::call_suspend(env, &#suspend_return_sig, |continuation| #call) }
+                    } else {
+                        call
+                    }
+                };
+
+                // Arguments are converted in the local frame, and only the result is kept, see `robusta_jni::frame`
+                let local_frame = local_frame_capacity(&node.attrs);
+                let in_local_frame = |call: TokenStream| -> TokenStream {
+                    match &local_frame {
+                        Some(capacity) => quote! { ::robusta_jni::frame::with_local_frame(env, #capacity, || #call) },
+                        None => call,
+                    }
+                };
+
                 let return_expr = match call_type {
                     CallType::Safe(_) => {
                         if is_constructor {
                             quote_spanned! { output_type_span =>
-                                res.and_then(|v| ::robusta_jni::convert::TryFromJavaValue::try_from(v, &env))
+                                res.and_then(|v| ::robusta_jni::convert::try_from_returned(v, &env))
                             }
                         } else {
                             quote_spanned! { output_type_span =>
-                                res.and_then(|v| ::std::convert::TryInto::try_into(::robusta_jni::convert::JValueWrapper::from(v)))
-                                   .and_then(|v| ::robusta_jni::convert::TryFromJavaValue::try_from(v, &env))
+                                res.and_then(|v| ::robusta_jni::convert::JniValue::from_jvalue(v, &env))
+                                   .and_then(|v| ::robusta_jni::convert::try_from_returned(v, &env))
                             }
                         }
                     }
                     CallType::Unchecked(_) => {
                         if is_constructor {
                             quote_spanned! { output_type_span =>
-                                ::robusta_jni::convert::FromJavaValue::from(res, &env)
+                                ::robusta_jni::convert::from_returned(res, &env)
                             }
                         } else {
                             quote_spanned! { output_type_span =>
-                                ::std::convert::TryInto::try_into(::robusta_jni::convert::JValueWrapper::from(res))
-                                    .map(|v| ::robusta_jni::convert::FromJavaValue::from(v, &env))
+                                ::robusta_jni::convert::JniValue::from_jvalue(res, &env)
+                                    .map(|v| ::robusta_jni::convert::from_returned(v, &env))
                                     .unwrap()
                             }
                         }
                     }
                 };
 
+                // The converted return value is unwrapped, see `robusta_jni::convert::converter`
+                let return_expr = match (&return_converter, call_type) {
+                    (Some(converter), CallType::Safe(_)) => quote_spanned! { output_type_span =>
+                        (#return_expr).map(::robusta_jni::convert::converter::Converted::<#converter, _>::into_inner)
+                    },
+                    (Some(converter), CallType::Unchecked(_)) => quote_spanned! { output_type_span =>
+                        ::robusta_jni::convert::converter::Converted::<#converter, _>::into_inner(#return_expr)
+                    },
+                    (None, _) => return_expr,
+                };
+
+                if is_builder_constructor {
+                    if class_ref_arg.is_some() {
+                        emit_error!(
+                            original_signature,
+                            "`#[constructor(builder)]` can't be used on constructors with a class parameter"
+                        );
+                    } else if let Some(FnArg::Typed(env_arg)) = &env_arg {
+                        // The builder passes values of the parameter types to the constructor
+                        let params = unconverted_inputs
+                            .iter()
+                            .filter_map(|i| match i {
+                                FnArg::Typed(t) => match &*t.pat {
+                                    Pat::Ident(PatIdent { ident, .. }) => Some((ident.clone(), (*t.ty).clone())),
+                                    _ => {
+                                        emit_error!(t.pat, "`#[constructor(builder)]` parameters must be identifiers");
+                                        None
+                                    }
+                                },
+                                FnArg::Receiver(_) => None,
+                            })
+                            .collect();
+
+                        self.builder_constructors.push(BuilderConstructor {
+                            vis: node.vis.clone(),
+                            ident: signature.ident.clone(),
+                            env_type: (*env_arg.ty).clone(),
+                            params,
+                            safe: matches!(call_type, CallType::Safe(_)),
+                        });
+                    }
+                }
+
                 let env_ident = match env_arg.unwrap() {
                     FnArg::Typed(t) => {
                         match *t.pat {
@@ -323,6 +869,7 @@ impl<'ctx> Fold for ImportedMethodTransformer<'ctx> {
                 let sig_discarded_known_attributes: HashSet<&str> = {
                     let mut h = HashSet::new();
                     h.insert("input_type");
+                    h.insert("convert");
 
                     h
                 };
@@ -350,15 +897,11 @@ impl<'ctx> Fold for ImportedMethodTransformer<'ctx> {
                                 .clone()
                                 .into_iter()
                                 .filter(|a| {
-                                    !a.path()
-                                        .segments
-                                        .iter()
-                                        .find(|s| {
-                                            sig_discarded_known_attributes
-                                                .iter()
-                                                .any(|d| s.ident.to_string().contains(d))
-                                        })
-                                        .is_some()
+                                    !a.path().segments.iter().any(|s| {
+                                        sig_discarded_known_attributes
+                                            .iter()
+                                            .any(|d| s.ident.to_string().contains(d))
+                                    })
                                 })
                                 .collect()
                         }
@@ -366,25 +909,151 @@ impl<'ctx> Fold for ImportedMethodTransformer<'ctx> {
                     FnArg::Receiver(_) => {}
                 });
 
-                ImplItemFn {
+                // Classes are looked up once and cached, see `robusta_jni::convert::JavaClass`
+                let java_class = match (&class_override, call_type) {
+                    // Classes given with `#[class]` are not cached, they are looked up by name on each call
+                    (Some(class_override), CallType::Safe(_)) => quote! { ::robusta_jni::loader::find_class(env, #class_override)? },
+                    (Some(class_override), CallType::Unchecked(_)) => quote! { ::robusta_jni::loader::find_class(env, #class_override).unwrap() },
+                    (None, CallType::Safe(_)) => quote! { <Self as ::robusta_jni::convert::JavaClass>::class(env)? },
+                    (None, CallType::Unchecked(_)) => quote! { <Self as ::robusta_jni::convert::JavaClass>::class(env).unwrap() },
+                };
+                // Lookup of the class as a `JniResult` of a new local reference, for caches calling it on first use
+                let java_class_lookup = match &class_override {
+                    Some(class_override) => quote! { ::robusta_jni::loader::find_class(env, #class_override) },
+                    None => quote! { <Self as ::robusta_jni::convert::JavaClass>::class(env) },
+                };
+                let class_expr = match &class_arg_ident {
+                    Some(class_arg_ident) => class_arg_ident.to_token_stream(),
+                    None => java_class.clone(),
+                };
+
+                let (verified_class_path, is_static) = match (&singleton_field, is_companion) {
+                    (Some(_), true) => (format!("{}$Companion", java_class_path), false),
+                    (Some(_), false) => (java_class_path.clone(), false),
+                    (None, _) => (java_class_path.clone(), !self_method && !is_constructor),
+                };
+                let verified_signature = match &constructor_signature {
+                    Some(constructor_signature) => quote! { ::std::string::ToString::to_string(#constructor_signature) },
+                    None => self.verification_signature(&signature, call_type, is_constructor, is_suspend),
+                };
+                let verified_fallbacks = fallback_signatures.iter().map(|(s, _)| s);
+                if let Some(descriptors) = self.descriptors {
+                    let check = descriptors.check(CheckedMethod {
+                        rust_name: &format!("{}::{}", self.struct_context.struct_name, signature.ident),
+                        class_path: &verified_class_path,
+                        java_name: if is_constructor { "<init>" } else { &java_method_name },
+                        signature_parts: match &constructor_signature {
+                            Some(_) => None,
+                            None => Some(self.verification_signature_parts(&signature, call_type, is_constructor, is_suspend)),
+                        },
+                        literal_signatures: constructor_signature.iter().chain(fallback_signatures.iter().map(|(s, _)| s)).collect(),
+                        span: original_signature.ident.span(),
+                    });
+                    if !check.is_empty() {
+                        self.signature_checks.push(check);
+                    }
+                }
+                let rust_name = format!("{}::{}", self.struct_context.struct_name, signature.ident);
+                let verified_name = if is_constructor { "<init>".to_string() } else { java_method_name.clone() };
+                self.method_constants.push(MethodConstants {
+                    rust_name: original_signature.ident.clone(),
+                    java_name: verified_name.clone(),
+                    signature_parts: match &constructor_signature {
+                        Some(constructor_signature) => vec![constructor_signature.to_token_stream()],
+                        None => self.verification_signature_parts(&signature, call_type, is_constructor, is_suspend),
+                    },
+                });
+                self.verified_methods.push(quote! {
+                    ::robusta_jni::verify::ImportedMethod {
+                        rust_name: #rust_name,
+                        class_path: #verified_class_path,
+                        java_name: #verified_name,
+                        signatures: vec![#verified_signature, #(#verified_fallbacks.to_string()),*],
+                        is_static: #is_static,
+                    }
+                });
+
+                let call_on_singleton =
+                    in_local_frame(suspend(quote! { env.call_method(receiver, #java_method_name, #java_signature, #call_args) }));
+                // Static methods are looked up once and cached, see `robusta_jni::convert::StaticMethodCache`
+                let call_on_class_path_static = in_local_frame(suspend(quote! { {
+                    static METHOD_CACHE: ::robusta_jni::convert::StaticMethodCache = ::robusta_jni::convert::StaticMethodCache::new();
+                    METHOD_CACHE.call(
+                        env,
+                        || #java_class_lookup,
+                        #java_method_name,
+                        || ::std::string::ToString::to_string(&#java_signature),
+                        #call_args,
+                    )
+                } }));
+                let call_on_class_arg_static = in_local_frame(suspend(
+                    quote! { env.call_static_method(#class_arg_ident, #java_method_name, #java_signature, #call_args) },
+                ));
+                let new_on_class_path = in_local_frame(quote! { env.new_object(#java_class, #java_signature, #call_args) });
+                let new_on_class_arg = in_local_frame(quote! { env.new_object(#class_arg_ident, #java_signature, #call_args) });
+
+                let resolve_on_receiver = resolve_signature(quote! { receiver }, false);
+                let resolve_on_class_path_constructor = resolve_signature(java_class.clone(), false);
+                let resolve_on_class_path_static = resolve_signature(java_class.clone(), true);
+                let resolve_on_class_arg_constructor = resolve_signature(class_arg_ident.to_token_stream(), false);
+                let resolve_on_class_arg_static = resolve_signature(class_arg_ident.to_token_stream(), true);
+
+                let mut transformed = ImplItemFn {
                     sig: Signature {
                         abi: None,
                         ..original_signature
                     },
-                    block: if self_method {
+                    block: if let Some((field_name, field_sig)) = singleton_field {
+                        match call_type {
+                            CallType::Safe(_) => {
+                                parse_quote! {{
+                                    let env: &'_ ::robusta_jni::jni::JNIEnv<'_> = #env_ident;
+                                    // The singleton reference is deleted once the method returns
+                                    let singleton = env.auto_local(env.get_static_field(#class_expr, #field_name, #field_sig).and_then(|v| v.l())?);
+                                    let receiver = singleton.as_obj();
+                                    #resolve_on_receiver
+                                    let res = #call_on_singleton;
+                                    #return_expr
+                                }}
+                            }
+                            CallType::Unchecked(_) => {
+                                parse_quote! {{
+                                    let env: &'_ ::robusta_jni::jni::JNIEnv<'_> = #env_ident;
+                                    let singleton = env.auto_local(env.get_static_field(#class_expr, #field_name, #field_sig).and_then(|v| v.l()).unwrap());
+                                    let receiver = singleton.as_obj();
+                                    #resolve_on_receiver
+                                    let res = #call_on_singleton.unwrap();
+                                    #return_expr
+                                }}
+                            }
+                        }
+                    } else if self_method {
                         let self_span = node.sig.inputs.iter().next().unwrap().span();
+                        let call_on_receiver = in_local_frame(suspend(match dispatch {
+                            Dispatch::Virtual => quote! { env.call_method(receiver, #java_method_name, #java_signature, #call_args) },
+                            Dispatch::Nonvirtual => quote! {
+                                ::robusta_jni::dispatch::call_nonvirtual(env, receiver, #java_class, #java_method_name, &#java_signature, #call_args)
+                            },
+                            Dispatch::Interface => quote! {
+                                ::robusta_jni::dispatch::call_interface(env, receiver, #java_class, #java_method_name, &#java_signature, #call_args)
+                            },
+                        }));
                         match call_type {
                             CallType::Safe(_) => {
                                 parse_quote_spanned! { self_span => {
                                     let env: &'_ ::robusta_jni::jni::JNIEnv<'_> = #env_ident;
-                                    let res = env.call_method(::robusta_jni::convert::JavaValue::autobox(::robusta_jni::convert::TryIntoJavaValue::try_into(self, &env)?, &env), #java_method_name, #java_signature, &[#input_conversions]);
+                                    let receiver = ::robusta_jni::convert::JavaValue::autobox(::robusta_jni::convert::TryIntoJavaValue::try_into(self, &env)?, &env);
+                                    #resolve_on_receiver
+                                    let res = #call_on_receiver;
                                     #return_expr
                                 }}
                             }
                             CallType::Unchecked(_) => {
                                 parse_quote_spanned! { self_span => {
                                     let env: &'_ ::robusta_jni::jni::JNIEnv<'_> = #env_ident;
-                                    let res = env.call_method(::robusta_jni::convert::JavaValue::autobox(::robusta_jni::convert::IntoJavaValue::into(self, &env), &env), #java_method_name, #java_signature, &[#input_conversions]).unwrap();
+                                    let receiver = ::robusta_jni::convert::JavaValue::autobox(::robusta_jni::convert::IntoJavaValue::into(self, &env), &env);
+                                    #resolve_on_receiver
+                                    let res = #call_on_receiver.unwrap();
                                     #return_expr
                                 }}
                             }
@@ -393,30 +1062,34 @@ impl<'ctx> Fold for ImportedMethodTransformer<'ctx> {
                         match call_type {
                             CallType::Safe(_) => {
                                 if is_constructor {
-                                    if let Some(class_arg_ident) = class_arg_ident {
+                                    if class_arg_ident.is_some() {
                                         parse_quote! {{
                                             let env: &'_ ::robusta_jni::jni::JNIEnv<'_> = #env_ident;
-                                            let res = env.new_object(#class_arg_ident, #java_signature, &[#input_conversions]);
+                                            #resolve_on_class_arg_constructor
+                                            let res = #new_on_class_arg;
                                             #return_expr
                                         }}
                                     } else {
                                         parse_quote! {{
                                             let env: &'_ ::robusta_jni::jni::JNIEnv<'_> = #env_ident;
-                                            let res = env.new_object(#java_class_path, #java_signature, &[#input_conversions]);
+                                            #resolve_on_class_path_constructor
+                                            let res = #new_on_class_path;
                                             #return_expr
                                         }}
                                     }
                                 } else {
-                                    if let Some(class_arg_ident) = class_arg_ident {
+                                    if class_arg_ident.is_some() {
                                         parse_quote! {{
                                             let env: &'_ ::robusta_jni::jni::JNIEnv<'_> = #env_ident;
-                                            let res = env.call_static_method(#class_arg_ident, #java_method_name, #java_signature, &[#input_conversions]);
+                                            #resolve_on_class_arg_static
+                                            let res = #call_on_class_arg_static;
                                             #return_expr
                                         }}
                                     } else {
                                         parse_quote! {{
                                             let env: &'_ ::robusta_jni::jni::JNIEnv<'_> = #env_ident;
-                                            let res = env.call_static_method(#java_class_path, #java_method_name, #java_signature, &[#input_conversions]);
+                                            #resolve_on_class_path_static
+                                            let res = #call_on_class_path_static;
                                             #return_expr
                                         }}
                                     }
@@ -424,30 +1097,34 @@ impl<'ctx> Fold for ImportedMethodTransformer<'ctx> {
                             }
                             CallType::Unchecked(_) => {
                                 if is_constructor {
-                                    if let Some(class_arg_ident) = class_arg_ident {
+                                    if class_arg_ident.is_some() {
                                         parse_quote! {{
                                             let env: &'_ ::robusta_jni::jni::JNIEnv<'_> = #env_ident;
-                                            let res = env.new_object(#class_arg_ident, #java_signature, &[#input_conversions]).unwrap();
+                                            #resolve_on_class_arg_constructor
+                                            let res = #new_on_class_arg.unwrap();
                                             #return_expr
                                         }}
                                     } else {
                                         parse_quote! {{
                                             let env: &'_ ::robusta_jni::jni::JNIEnv<'_> = #env_ident;
-                                            let res = env.new_object(#java_class_path, #java_signature, &[#input_conversions]).unwrap();
+                                            #resolve_on_class_path_constructor
+                                            let res = #new_on_class_path.unwrap();
                                             #return_expr
                                         }}
                                     }
                                 } else {
-                                    if let Some(class_arg_ident) = class_arg_ident {
+                                    if class_arg_ident.is_some() {
                                         parse_quote! {{
                                             let env: &'_ ::robusta_jni::jni::JNIEnv<'_> = #env_ident;
-                                            let res = env.call_static_method(#class_arg_ident, #java_method_name, #java_signature, &[#input_conversions]).unwrap();
+                                            #resolve_on_class_arg_static
+                                            let res = #call_on_class_arg_static.unwrap();
                                             #return_expr
                                         }}
                                     } else {
                                         parse_quote! {{
                                             let env: &'_ ::robusta_jni::jni::JNIEnv<'_> = #env_ident;
-                                            let res = env.call_static_method(#java_class_path, #java_method_name, #java_signature, &[#input_conversions]).unwrap();
+                                            #resolve_on_class_path_static
+                                            let res = #call_on_class_path_static.unwrap();
                                             #return_expr
                                         }}
                                     }
@@ -457,10 +1134,68 @@ impl<'ctx> Fold for ImportedMethodTransformer<'ctx> {
                     },
                     attrs: impl_item_attributes,
                     ..node
+                };
+
+                // Right after binding `env`, see `robusta_jni::first_use`
+                if self.first_use {
+                    let first_use = match call_type {
+                        CallType::Safe(_) => quote! { Self::robusta_first_use(env)?; },
+                        CallType::Unchecked(_) => quote! { Self::robusta_first_use(env).unwrap(); },
+                    };
+                    transformed.block.stmts.insert(1, parse_quote! { #first_use });
+                }
+
+                // The call ends once `res` is bound, before the result is converted
+                if instrument {
+                    let res = transformed.block.stmts.iter().position(|s| {
+                        matches!(s, Stmt::Local(Local { pat: Pat::Ident(PatIdent { ident, .. }), .. }) if ident == "res")
+                    });
+                    if let Some(idx) = res {
+                        transformed.block.stmts.insert(idx + 1, parse_quote! { ::robusta_jni::instrument::call_finished(); });
+                    }
+                    transformed.block.stmts.insert(1, parse_quote! {
+                        let _robusta_span = ::robusta_jni::instrument::Span::enter(
+                            ::robusta_jni::instrument::CallDirection::Imported,
+                            #rust_name,
+                            #arg_count,
+                        );
+                    });
                 }
+
+                transformed
             }
 
             _ => node,
         }
     }
 }
+
+/// Number of parameters of a method descriptor such as `(ILjava/lang/String;[J)V`, or `None` if it is malformed
+fn descriptor_arg_count(descriptor: &str) -> Option<usize> {
+    descriptor_params(descriptor).map(|(params, _)| params.len())
+}
+
+/// Parameter and return type descriptors of a method descriptor, or `None` if it is malformed
+pub(crate) fn descriptor_params(descriptor: &str) -> Option<(Vec<&str>, &str)> {
+    let (params, ret) = descriptor.strip_prefix('(')?.split_once(')')?;
+    if ret.is_empty() {
+        return None;
+    }
+
+    let mut descriptors = Vec::new();
+    let mut rest = params;
+    while !rest.is_empty() {
+        let dimensions = rest.len() - rest.trim_start_matches('[').len();
+        let len = match rest[dimensions..].chars().next()? {
+            'Z' | 'B' | 'C' | 'S' | 'I' | 'J' | 'F' | 'D' => dimensions + 1,
+            'L' => dimensions + rest[dimensions..].find(';')? + 1,
+            _ => return None,
+        };
+
+        let (param, tail) = rest.split_at(len);
+        descriptors.push(param);
+        rest = tail;
+    }
+
+    Some((descriptors, ret))
+}
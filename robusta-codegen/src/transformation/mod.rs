@@ -1,35 +1,44 @@
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
-use darling::util::Flag;
+use darling::util::{Flag, Override};
 use darling::FromMeta;
-use proc_macro2::{Ident, TokenStream};
-use proc_macro_error::{emit_error, emit_warning};
-use quote::ToTokens;
+use inflector::cases::camelcase::to_camel_case;
+use inflector::cases::pascalcase::to_pascal_case;
+use inflector::cases::snakecase::to_snake_case;
+use proc_macro2::{Ident, Span, TokenStream};
+use proc_macro_error::emit_error;
+use quote::{format_ident, quote, quote_spanned, ToTokens};
 use syn::fold::Fold;
 use syn::parse::{Parse, ParseBuffer, ParseStream, Parser};
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::visit::Visit;
 use syn::{
-    parse_quote, Attribute, FnArg, GenericArgument, GenericParam, ImplItemFn, Item, ItemImpl,
-    ItemMod, ItemStruct, Lit, Pat, PatIdent, PatType, Path, PathArguments, PathSegment, Type,
+    parse_quote, Attribute, Field, Fields, FnArg, GenericArgument, GenericParam, Generics, ImplItemFn, Item, ItemEnum, ItemImpl,
+    ItemMod, ItemStruct, Lifetime, Lit, Meta, Pat, PatIdent, PatType, Path, PathArguments, PathSegment, Type,
     TypePath, TypeReference, Visibility,
 };
 use syn::{Error, ImplItem, Token};
 
+use builder::builder_items;
+use constants::method_constant_items;
+use descriptors::Descriptors;
 use imported::ImportedMethodTransformer;
 
 use crate::transformation::context::StructContext;
-use crate::transformation::exported::ExportedMethodTransformer;
-use crate::utils::{canonicalize_path, get_abi};
+use crate::transformation::exported::{mangle_jni_name, ExportedMethodTransformer, NativeMethodRegistration};
+use crate::utils::{canonicalize_path, get_abi, get_env_arg, is_strict, strip_param_attributes};
 use crate::validation::JNIBridgeModule;
 use std::fmt;
 
 #[macro_use]
 mod utils;
+mod builder;
+mod constants;
 mod context;
+mod descriptors;
 mod exported;
 mod imported;
 
@@ -42,24 +51,339 @@ pub(crate) enum ImplItemType {
 
 pub(crate) struct ModTransformer {
     module: JNIBridgeModule,
+    args: BridgeArgs,
+    /// Default `#[call_type]` attribute for all methods of the module
+    default_call_type: Option<Attribute>,
+    /// Descriptors to check imported methods against, from `#[bridge(check_against = "...")]`
+    descriptors: Option<Descriptors>,
+    native_methods: Vec<NativeMethodRegistration>,
+    verified_methods: Vec<TokenStream>,
+    signature_checks: Vec<TokenStream>,
+    /// Accessor methods replacing `#[field(lazy)]` fields
+    lazy_field_accessors: Vec<Item>,
 }
 
 impl ModTransformer {
-    pub(crate) fn new(module: JNIBridgeModule) -> Self {
-        ModTransformer { module }
+    pub(crate) fn new(module: JNIBridgeModule, args: BridgeArgs) -> Self {
+        let default_call_type = match (&module.call_type, args.default_call_type) {
+            (Some(attribute), Some(_)) => {
+                emit_error!(attribute, "the default call type is already set with `#[bridge(default_call_type = ...)]`");
+                None
+            }
+            (Some(attribute), None) => Some(attribute.clone()),
+            (None, default) => default.map(|d| d.attribute()),
+        };
+
+        let descriptors = args.check_against.as_deref().and_then(Descriptors::load);
+
+        ModTransformer {
+            module,
+            args,
+            default_call_type,
+            descriptors,
+            native_methods: Vec::new(),
+            verified_methods: Vec::new(),
+            signature_checks: Vec::new(),
+            lazy_field_accessors: Vec::new(),
+        }
     }
 
     pub(crate) fn transform_module(&mut self) -> TokenStream {
         let module_decl = self.module.module_decl.clone();
-        self.fold_item_mod(module_decl).into_token_stream()
+        let mut module = self.fold_item_mod(module_decl);
+
+        if let Some((_, items)) = &mut module.content {
+            let java_class_impls = self.java_class_impls(items);
+            items.extend(java_class_impls);
+            items.append(&mut self.lazy_field_accessors);
+        }
+
+        if self.args.register_natives.is_present() || self.args.helper_classes.is_present() {
+            let on_load = self.jni_on_load();
+            if let Some((_, items)) = &mut module.content {
+                items.push(on_load);
+            }
+        }
+
+        if let Some(on_unload) = &self.args.on_unload {
+            let on_unload = self.jni_on_unload(on_unload);
+            if let Some((_, items)) = &mut module.content {
+                items.push(on_unload);
+            }
+        }
+
+        if self.args.verify_bindings.is_present() {
+            let verify_bindings = self.verify_bindings();
+            if let Some((_, items)) = &mut module.content {
+                items.push(verify_bindings);
+            }
+        }
+
+        if self.args.symbol_manifest.is_present() {
+            let symbol_manifest = self.symbol_manifest();
+            if let Some((_, items)) = &mut module.content {
+                items.push(symbol_manifest);
+            }
+        }
+
+        if let Some(descriptors) = &self.descriptors {
+            let dependency = descriptors.dependency_item();
+            let checks = &self.signature_checks;
+            if let Some((_, items)) = &mut module.content {
+                items.push(parse_quote! { #dependency });
+                items.extend(checks.iter().map(|c| parse_quote! { #c }));
+            }
+        }
+
+        module.into_token_stream()
+    }
+
+    /// Generate `JavaClass` implementations for bridged structs, unless already provided by the `Signature` or `JavaClass` derives
+    fn java_class_impls(&self, items: &[Item]) -> Vec<Item> {
+        items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Struct(s) if !derives_trait(&s.attrs, "Signature") && !derives_trait(&s.attrs, "JavaClass") => {
+                    let package = self.module.package_map.get(&s.ident.to_string())?.as_ref()?;
+                    let class_name = self
+                        .module
+                        .class_names
+                        .get(&s.ident.to_string())
+                        .cloned()
+                        .unwrap_or_else(|| s.ident.to_string());
+                    let class_path = [package.to_classpath_path(), class_name]
+                        .iter()
+                        .filter(|p| !p.is_empty())
+                        .cloned()
+                        .collect::<Vec<_>>()
+                        .join("/");
+
+                    let struct_name = &s.ident;
+                    let (impl_generics, ty_generics, where_clause) = s.generics.split_for_impl();
+
+                    Some(parse_quote! {
+                        #[automatically_derived]
+                        impl #impl_generics ::robusta_jni::convert::JavaClass for #struct_name #ty_generics #where_clause {
+                            const CLASS_PATH: &'static str = #class_path;
+
+                            fn class_cache() -> &'static ::robusta_jni::convert::ClassCache {
+                                static CLASS_CACHE: ::robusta_jni::convert::ClassCache = ::robusta_jni::convert::ClassCache::new();
+                                &CLASS_CACHE
+                            }
+                        }
+                    })
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Generate a `robusta_verify_bindings` function checking that all imported methods exist on the Java side
+    fn verify_bindings(&self) -> Item {
+        let methods = &self.verified_methods;
+
+        parse_quote! {
+            /// Check that every imported method declared in this module has a matching Java method.
+            #[allow(dead_code)]
+            pub fn robusta_verify_bindings(
+                env: &::robusta_jni::jni::JNIEnv,
+            ) -> ::robusta_jni::jni::errors::Result<::robusta_jni::verify::VerificationReport> {
+                ::robusta_jni::verify::verify_methods(env, vec![#(#methods),*])
+            }
+        }
+    }
+
+    /// Generate a `robusta_symbol_manifest` function listing the exported methods of the module
+    fn symbol_manifest(&self) -> Item {
+        let functions = self.native_methods.iter().map(|m| {
+            let NativeMethodRegistration {
+                class_path,
+                java_name,
+                symbol,
+                is_static,
+                throws,
+                ..
+            } = m;
+            let signature = m.signature();
+            let symbol = match symbol {
+                Some(symbol) => quote! { ::std::option::Option::Some(#symbol) },
+                None => quote! { ::std::option::Option::None },
+            };
+            let throws = match throws {
+                Some(throws) => quote! { ::std::option::Option::Some(#throws) },
+                None => quote! { ::std::option::Option::None },
+            };
+
+            quote! {
+                ::robusta_jni::symbols::NativeFunction {
+                    symbol: #symbol,
+                    class_path: #class_path,
+                    java_name: #java_name,
+                    signature: #signature,
+                    is_static: #is_static,
+                    throws: #throws,
+                }
+            }
+        });
+        let on_load = self.args.register_natives.is_present() || self.args.helper_classes.is_present();
+        let on_unload = self.args.on_unload.is_some();
+
+        parse_quote! {
+            /// Native methods exported by this module.
+            #[allow(dead_code)]
+            pub fn robusta_symbol_manifest() -> ::robusta_jni::symbols::SymbolManifest {
+                ::robusta_jni::symbols::SymbolManifest {
+                    functions: vec![#(#functions),*],
+                    on_load: #on_load,
+                    on_unload: #on_unload,
+                }
+            }
+        }
+    }
+
+    /// Generate a `JNI_OnLoad` function storing the VM for `robusta_jni::attach`, defining the embedded helper classes
+    /// of `robusta_jni` (with `#[bridge(helper_classes)]`), and registering all exported methods via `RegisterNatives`
+    /// (with `#[bridge(register_natives)]`)
+    fn jni_on_load(&self) -> Item {
+        let helper_classes = self.args.helper_classes.is_present().then(|| {
+            quote! { ::robusta_jni::helpers::define_helper_classes(&env)?; }
+        });
+
+        let mut classes: BTreeMap<&str, Vec<&NativeMethodRegistration>> = BTreeMap::new();
+        let registered_methods = if self.args.register_natives.is_present() {
+            self.native_methods.as_slice()
+        } else {
+            &[]
+        };
+        for method in registered_methods {
+            classes
+                .entry(method.class_path.as_str())
+                .or_default()
+                .push(method);
+        }
+
+        let registrations = classes.into_iter().map(|(class_path, methods)| {
+            let native_methods = methods.iter().map(|m| {
+                let NativeMethodRegistration {
+                    java_name,
+                    function,
+                    ..
+                } = m;
+                let signature = m.signature();
+
+                quote! {
+                    ::robusta_jni::jni::NativeMethod {
+                        name: #java_name.into(),
+                        sig: #signature.into(),
+                        fn_ptr: #function as *mut ::std::os::raw::c_void,
+                    }
+                }
+            });
+
+            quote! {
+                env.register_native_methods(#class_path, &[#(#native_methods),*])?;
+            }
+        });
+
+        parse_quote! {
+            #[no_mangle]
+            pub extern "system" fn JNI_OnLoad(
+                vm: *mut ::robusta_jni::jni::sys::JavaVM,
+                _reserved: *mut ::std::os::raw::c_void,
+            ) -> ::robusta_jni::jni::sys::jint {
+                fn load(vm: *mut ::robusta_jni::jni::sys::JavaVM) -> ::robusta_jni::jni::errors::Result<()> {
+                    let vm = ::robusta_jni::Vm::init(unsafe { ::robusta_jni::jni::JavaVM::from_raw(vm) }?);
+                    let env = vm.get_env()?;
+                    #helper_classes
+                    #(#registrations)*
+                    Ok(())
+                }
+
+                match load(vm) {
+                    Ok(()) => ::robusta_jni::jni::sys::JNI_VERSION_1_6,
+                    Err(e) => {
+                        println!("Error while loading library: {}", e);
+                        ::robusta_jni::jni::sys::JNI_ERR
+                    }
+                }
+            }
+        }
+    }
+
+    /// Generate a `JNI_OnUnload` function running the unload hooks, after registering the function given in
+    /// `#[bridge(on_unload = "...")]` as the last one
+    fn jni_on_unload(&self, on_unload: &Override<String>) -> Item {
+        let hook = match on_unload {
+            Override::Inherit => None,
+            Override::Explicit(path) => match syn::parse_str::<Path>(path) {
+                Ok(path) => Some(quote! { ::robusta_jni::unload::on_unload(#path); }),
+                Err(_) => {
+                    emit_error!(self.module.module_decl, "`on_unload` must be the path of a function, found `{}`", path);
+                    None
+                }
+            },
+        };
+
+        parse_quote! {
+            #[no_mangle]
+            pub extern "system" fn JNI_OnUnload(
+                vm: *mut ::robusta_jni::jni::sys::JavaVM,
+                _reserved: *mut ::std::os::raw::c_void,
+            ) {
+                fn unload(vm: *mut ::robusta_jni::jni::sys::JavaVM) -> ::robusta_jni::jni::errors::Result<()> {
+                    let vm = unsafe { ::robusta_jni::jni::JavaVM::from_raw(vm) }?;
+                    #hook
+                    ::robusta_jni::unload::unload(&vm);
+                    Ok(())
+                }
+
+                if let Err(e) = unload(vm) {
+                    println!("Error while unloading library: {}", e);
+                }
+            }
+        }
     }
 
     /// If the impl block is a standard impl block for a type, makes every exported fn a freestanding one
     fn transform_item_impl(&mut self, node: ItemImpl) -> TokenStream {
+        let node = skip_no_bridge_methods(node);
+        let node = expand_exported_consts(node);
+        let struct_name = match &*node.self_ty {
+            Type::Path(p) => Some(
+                canonicalize_path(&p.path)
+                    .to_token_stream()
+                    .to_string()
+                    .replace(" ", ""),
+            ),
+            _ => None,
+        };
+        let delegate = struct_name
+            .as_ref()
+            .and_then(|n| self.module.delegates.get(n))
+            .cloned();
+        let struct_default_call_type = struct_name
+            .as_ref()
+            .and_then(|n| self.module.call_types.get(n))
+            .or(self.default_call_type.as_ref())
+            .cloned();
+        let node = match delegate {
+            Some(delegate) => expand_delegated_methods(node, &delegate, &self.module.env_aliases),
+            None => node,
+        };
+        let node = apply_default_call_type(node, struct_default_call_type);
+        let node = if self.args.instrument.is_present() { instrument_all(node) } else { node };
+        let first_use_hook = struct_name
+            .as_ref()
+            .and_then(|n| self.module.first_use_hooks.get(n))
+            .cloned();
+        let node = match &first_use_hook {
+            Some(hook) => expand_first_use_hook(node, hook),
+            None => node,
+        };
         let mut impl_export_visitor = ImplExportVisitor::default();
         impl_export_visitor.visit_item_impl(&node);
 
-        let (preserved_items, transformed_items) = if let Type::Path(p) = &*node.self_ty {
+        let (preserved_items, transformed_items, extra_items) = if let Type::Path(p) = &*node.self_ty {
             let canonical_path = canonicalize_path(&p.path);
             let struct_name = canonical_path
                 .to_token_stream()
@@ -103,18 +427,39 @@ impl ModTransformer {
                 })
                 .collect();
 
+            let kotlin_object = self.module.kotlin_objects.contains(&struct_name);
+            let class_name = self
+                .module
+                .class_names
+                .get(&struct_name)
+                .cloned()
+                .unwrap_or_else(|| struct_name.clone());
             let context = StructContext {
                 struct_type: p.path.clone(),
                 struct_name,
+                class_name,
                 struct_lifetimes,
                 package: struct_package,
+                kotlin_object,
+                rename_all: self.args.rename_all,
+                java_name_format: self.args.java_name_format.clone(),
+                env_aliases: self.module.env_aliases.clone(),
             };
 
             let mut exported_fns_transformer = ExportedMethodTransformer {
                 struct_context: &context,
+                register_natives: self.args.register_natives.is_present(),
+                native_methods: Vec::new(),
+                method_constants: Vec::new(),
             };
             let mut imported_fns_transformer = ImportedMethodTransformer {
                 struct_context: &context,
+                verified_methods: Vec::new(),
+                builder_constructors: Vec::new(),
+                descriptors: self.descriptors.as_ref(),
+                signature_checks: Vec::new(),
+                method_constants: Vec::new(),
+                first_use: first_use_hook.is_some(),
             };
             let mut impl_cleaner = ImplCleaner;
 
@@ -143,15 +488,34 @@ impl ModTransformer {
                 .map(|i| exported_fns_transformer.fold_impl_item(i))
                 .collect();
 
-            (preserved, transformed)
+            self.native_methods
+                .append(&mut exported_fns_transformer.native_methods);
+            self.verified_methods
+                .append(&mut imported_fns_transformer.verified_methods);
+            self.signature_checks
+                .append(&mut imported_fns_transformer.signature_checks);
+
+            let mut extra_items = builder_items(
+                &context,
+                &node.generics,
+                &node.self_ty,
+                &imported_fns_transformer.builder_constructors,
+            );
+            let mut method_constants = imported_fns_transformer.method_constants;
+            method_constants.append(&mut exported_fns_transformer.method_constants);
+            extra_items.extend(method_constant_items(&node.generics, &node.self_ty, &method_constants));
+
+            (preserved, transformed, extra_items)
         } else {
-            (node.items, Vec::new())
+            (node.items, Vec::new(), TokenStream::new())
         };
 
         let preserved_impl = ItemImpl {
+            // The Java class of types declared outside of the module is given on their impl blocks
             attrs: node
                 .attrs
                 .into_iter()
+                .filter(|a| !a.path().is_ident("package") && !a.path().is_ident("class"))
                 .map(|a| self.fold_attribute(a))
                 .collect(),
             generics: self.fold_generics(node.generics),
@@ -163,7 +527,7 @@ impl ModTransformer {
             ..node
         };
 
-        transformed_items.iter().map(|i| i.to_token_stream()).fold(
+        transformed_items.iter().map(|i| i.to_token_stream()).chain(Some(extra_items)).fold(
             preserved_impl.into_token_stream(),
             |item, mut stream| {
                 item.to_tokens(&mut stream);
@@ -173,6 +537,144 @@ impl ModTransformer {
     }
 }
 
+impl ModTransformer {
+    /// Turn every free `extern "jni"` function annotated with `#[class(...)]` into a plain Rust function,
+    /// and add an exported method forwarding to it on a hidden struct named after the Java class.
+    fn expand_free_functions(&mut self, items: Vec<Item>) -> Vec<Item> {
+        let mut classes: BTreeMap<String, (Ident, Vec<ImplItemFn>)> = BTreeMap::new();
+
+        let mut items: Vec<Item> = items
+            .into_iter()
+            .map(|item| match item {
+                Item::Fn(mut f) => {
+                    if take_no_bridge_attribute(&mut f.attrs).is_some() {
+                        f.attrs.retain(|a| {
+                            !a.path().is_ident("class")
+                                && !a.path().is_ident("call_type")
+                                && !a.path().is_ident("convert")
+                                && !a.path().is_ident("check_aliasing")
+                                && !a.path().is_ident("null_check")
+                                && !a.path().is_ident("on_panic")
+                                && !a.path().is_ident("overload")
+                                && !a.path().is_ident("synchronized")
+                                && !a.path().is_ident("local_frame")
+                                && !a.path().is_ident("instrument")
+                                && !a.path().is_ident("throws")
+                        });
+                        f.sig.abi = None;
+                        return Item::Fn(f);
+                    }
+
+                    let class_attribute = match f.attrs.iter().position(|a| a.path().is_ident("class")) {
+                        Some(idx) => f.attrs.remove(idx),
+                        None => {
+                            if is_strict() && get_abi(&f.sig).as_deref() == Some("jni") {
+                                emit_error!(f.sig, "free `extern \"jni\"` functions need a `#[class(...)]` attribute to be exported";
+                                    help = "add e.g. `#[class(com.example.Functions)]`, or move the function to an `impl` block");
+                            }
+                            return Item::Fn(f);
+                        }
+                    };
+
+                    if get_abi(&f.sig).as_deref() != Some("jni") || !matches!(f.vis, Visibility::Public(_)) {
+                        emit_error!(class_attribute, "`class` attribute can only be used on `pub extern \"jni\"` functions");
+                        return Item::Fn(f);
+                    }
+
+                    let class_path = match class_attribute.parse_args::<JavaPath>() {
+                        Ok(path) => path,
+                        Err(e) => {
+                            emit_error!(e.span(), "{}", e);
+                            return Item::Fn(f);
+                        }
+                    };
+                    let (package, class_name) = match class_path.0.rsplit_once('.') {
+                        Some((package, class_name)) => (package.to_string(), class_name.to_string()),
+                        None => (String::new(), class_path.0.clone()),
+                    };
+
+                    if !classes.contains_key(&class_name) && self.module.package_map.contains_key(&class_name) {
+                        emit_error!(class_attribute, "a struct named `{}` is already declared in this module", class_name;
+                            help = "declare the function inside an `impl {}` block instead", class_name);
+                        return Item::Fn(f);
+                    }
+                    self.module.package_map.insert(class_name.clone(), Some(JavaPath(package)));
+
+                    let args: Vec<_> = f
+                        .sig
+                        .inputs
+                        .iter()
+                        .filter_map(|arg| match arg {
+                            FnArg::Typed(PatType { pat, .. }) => match &**pat {
+                                Pat::Ident(PatIdent { ident, .. }) => Some(ident.clone()),
+                                _ => {
+                                    emit_error!(pat, "exported function parameters must be plain identifiers");
+                                    None
+                                }
+                            },
+                            FnArg::Receiver(_) => None,
+                        })
+                        .collect();
+
+                    let fn_ident = &f.sig.ident;
+                    let exported: ImplItemFn = ImplItemFn {
+                        attrs: f.attrs.clone(),
+                        vis: f.vis.clone(),
+                        defaultness: None,
+                        sig: f.sig.clone(),
+                        block: parse_quote_spanned! { f.block.span() =>
+                            {
+                                #fn_ident(#(#args),*)
+                            }
+                        },
+                    };
+
+                    classes
+                        .entry(class_name.clone())
+                        .or_insert_with(|| (Ident::new(&class_name, class_attribute.span()), Vec::new()))
+                        .1
+                        .push(exported);
+
+                    // `#[export_name]` belongs to the exported method only
+                    f.attrs.retain(|a| {
+                        !a.path().is_ident("call_type")
+                            && !a.path().is_ident("convert")
+                            && !a.path().is_ident("check_aliasing")
+                            && !a.path().is_ident("null_check")
+                            && !a.path().is_ident("on_panic")
+                            && !a.path().is_ident("overload")
+                            && !a.path().is_ident("synchronized")
+                            && !a.path().is_ident("local_frame")
+                            && !a.path().is_ident("instrument")
+                            && !a.path().is_ident("throws")
+                            && !a.path().is_ident("export_name")
+                            && !a.path().is_ident("java_name")
+                    });
+                    f.sig.abi = None;
+                    strip_param_attributes(&mut f.sig);
+                    Item::Fn(f)
+                }
+                item => item,
+            })
+            .collect();
+
+        for (class_ident, methods) in classes.into_values() {
+            items.push(parse_quote! {
+                #[doc(hidden)]
+                #[allow(dead_code)]
+                pub struct #class_ident;
+            });
+            items.push(parse_quote! {
+                impl #class_ident {
+                    #(#methods)*
+                }
+            });
+        }
+
+        items
+    }
+}
+
 impl Fold for ModTransformer {
     fn fold_item(&mut self, node: Item) -> Item {
         match node {
@@ -210,7 +712,10 @@ impl Fold for ModTransformer {
             content: node.content.map(|(brace, items)| {
                 (
                     brace,
-                    items.into_iter().map(|i| self.fold_item(i)).collect(),
+                    self.expand_free_functions(items)
+                        .into_iter()
+                        .map(|i| self.fold_item(i))
+                        .collect(),
                 )
             }),
             semi: node.semi,
@@ -218,50 +723,28 @@ impl Fold for ModTransformer {
     }
 
     fn fold_item_struct(&mut self, node: ItemStruct) -> ItemStruct {
-        let struct_attributes = {
-            /* The `#[bridge]` attribute macro has to discard `#[package()]` attributes, because they don't exists in standard Rust
-             * and currently there is no way for attribute macros to automatically introduce inert attributes (see: https://doc.rust-lang.org/reference/attributes.html#active-and-inert-attributes
-             * and rust-lang/issues/#65823).
-             * However, we want `#[package()]` to also be used in combination with auto-derive, and conversion traits (i.e. `Signature`, `(Try)IntoJavaValue`, `(Try)FromJavaValue`) *need* a `#[package]` attribute on the struct they are applied on.
-             * If we remove the package attribute blindly the traits cannot see it, and if we keep it the auto-derived traits cannot remove it (auto-derive macros cannot modify the existing token stream as proc macros).
-             * Here we check wether the struct has a `#[derive(TRAIT)]` (crudely with a string comparison and hoping the user never writes `#[derive(::robusta_jni::convert::TRAIT)]`)
-             * if it is present we don't remove `#[package]`, otherwise we remove it.
-             * This works because all conversion traits auto-derive macros also declare `#[package]` as a helper attribute
-             */
-            let attributes = node.attrs.clone();
-            let traits_with_package_attr = HashSet::from([
-                "Signature",
-                "FromJavaValue",
-                "TryFromJavaValue",
-                "IntoJavaValue",
-                "TryIntoJavaValue",
-            ]);
-
-            let has_package_trait = node.attrs.iter().any(|a| {
-                let is_derive =
-                    a.path().get_ident().map(ToString::to_string).as_deref() == Some("derive");
-                let derived_traits = a
-                    .parse_args_with(Punctuated::<Ident, Token![,]>::parse_terminated)
-                    .iter()
-                    .flat_map(|p: &syn::punctuated::Punctuated<Ident, Token![,]>| p)
-                    .map(|i| i.to_string())
-                    .collect::<HashSet<String>>();
-                let needs_package_attr = derived_traits
-                    .iter()
-                    .any(|t| traits_with_package_attr.contains(t.as_str()));
+        let struct_attributes = strip_package_attribute(node.attrs);
+
+        // `#[kotlin_object]`, `#[delegate_to]` and `#[call_type]` are only used by `#[bridge]` itself, so it's always safe to discard them
+        let struct_attributes = struct_attributes
+            .into_iter()
+            .filter(|a| {
+                !a.path().is_ident("kotlin_object")
+                    && !a.path().is_ident("delegate_to")
+                    && !a.path().is_ident("call_type")
+            })
+            .collect();
 
-                is_derive && needs_package_attr
-            });
+        // Derived conversions call the `#[on_first_use]` function of the struct, see `robusta_jni::first_use`
+        let mut struct_attributes: Vec<Attribute> = struct_attributes;
+        if self.module.first_use_hooks.contains_key(&node.ident.to_string())
+            && CONVERSION_DERIVES.iter().any(|t| derives_trait(&struct_attributes, t))
+        {
+            struct_attributes.push(parse_quote! { #[on_first_use] });
+        }
 
-            if !has_package_trait {
-                attributes
-                    .into_iter()
-                    .filter(|a| a.path().to_token_stream().to_string().as_str() != "package")
-                    .collect()
-            } else {
-                attributes
-            }
-        };
+        let (fields, accessors) = take_lazy_fields(&node.ident, &node.generics, node.fields);
+        self.lazy_field_accessors.extend(accessors);
 
         ItemStruct {
             attrs: struct_attributes,
@@ -269,10 +752,151 @@ impl Fold for ModTransformer {
             struct_token: node.struct_token,
             ident: node.ident,
             generics: self.fold_generics(node.generics),
-            fields: self.fold_fields(node.fields),
+            fields: self.fold_fields(fields),
             semi_token: node.semi_token,
         }
     }
+
+    fn fold_item_enum(&mut self, node: ItemEnum) -> ItemEnum {
+        let mut node = syn::fold::fold_item_enum(self, node);
+        node.attrs = strip_package_attribute(node.attrs);
+        node
+    }
+}
+
+/// Remove the fields marked with `#[field(lazy)]` from a struct, and generate an accessor method for each of them,
+/// returning a [`Field`](robusta_jni::convert::Field) handle whose field ID is looked up when the method is called
+fn take_lazy_fields(struct_name: &Ident, generics: &Generics, fields: Fields) -> (Fields, Option<Item>) {
+    let is_lazy = |f: &Field| {
+        f.attrs.iter().any(|a| {
+            a.path().is_ident("field")
+                && a.parse_args::<Ident>().is_ok_and(|i| i == "lazy")
+        })
+    };
+
+    let mut fields = match fields {
+        Fields::Named(fields) if fields.named.iter().any(is_lazy) => fields,
+        fields => return (fields, None),
+    };
+
+    let instance_ident = fields
+        .named
+        .iter()
+        .find(|f| f.attrs.iter().any(|a| a.path().is_ident("instance")))
+        .and_then(|f| f.ident.clone());
+    let instance_ident = match instance_ident {
+        Some(ident) => ident,
+        None => {
+            emit_error!(struct_name, "`#[field(lazy)]` fields require an `#[instance]` field");
+            return (Fields::Named(fields), None);
+        }
+    };
+
+    let (lazy, kept): (Vec<Field>, Vec<Field>) = fields.named.into_iter().partition(is_lazy);
+    fields.named = kept.into_iter().collect();
+
+    let accessors = lazy.into_iter().map(|f| {
+        let Field { attrs, vis, ident, ty, .. } = f;
+        let attrs = attrs.into_iter().filter(|a| !a.path().is_ident("field"));
+        let field_name = ident.as_ref().map(ToString::to_string);
+
+        quote_spanned! { ty.span() =>
+            #(#attrs)*
+            #vis fn #ident(&self, env: &'borrow ::robusta_jni::jni::JNIEnv<'env>) -> ::robusta_jni::jni::errors::Result<::robusta_jni::convert::Field<'env, 'borrow, #ty>>
+            where
+                #ty: ::robusta_jni::convert::Signature + ::robusta_jni::convert::TryIntoJavaValue<'env> + ::robusta_jni::convert::TryFromJavaValue<'env, 'borrow>,
+                ::robusta_jni::jni::objects::JValue<'env>: ::core::convert::From<<#ty as ::robusta_jni::convert::TryIntoJavaValue<'env>>::Target>,
+            {
+                ::robusta_jni::convert::Field::field_try_from(
+                    self.#instance_ident.as_obj(),
+                    &env.auto_local(<Self as ::robusta_jni::convert::JavaClass>::class(env)?),
+                    #field_name,
+                    env,
+                )
+            }
+        }
+    });
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let accessors_impl = parse_quote! {
+        #[automatically_derived]
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            #(#accessors)*
+        }
+    };
+
+    (Fields::Named(fields), Some(accessors_impl))
+}
+
+/// Derive macros of conversions, which accept the `#[on_first_use]` marker on structs
+const CONVERSION_DERIVES: &[&str] = &["IntoJavaValue", "TryIntoJavaValue", "FromJavaValue", "TryFromJavaValue", "JavaClass"];
+
+/// Whether `attributes` contain a `#[derive]` of `trait_name`
+fn derives_trait(attributes: &[Attribute], trait_name: &str) -> bool {
+    attributes.iter().filter(|a| a.path().is_ident("derive")).any(|a| {
+        a.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)
+            .is_ok_and(|traits| {
+                traits
+                    .iter()
+                    .any(|t| t.segments.last().is_some_and(|s| s.ident == trait_name))
+            })
+    })
+}
+
+fn strip_package_attribute(attributes: Vec<Attribute>) -> Vec<Attribute> {
+    /* The `#[bridge]` attribute macro has to discard `#[package()]` attributes, because they don't exists in standard Rust
+     * and currently there is no way for attribute macros to automatically introduce inert attributes (see: https://doc.rust-lang.org/reference/attributes.html#active-and-inert-attributes
+     * and rust-lang/issues/#65823).
+     * However, we want `#[package()]` to also be used in combination with auto-derive, and conversion traits (i.e. `Signature`, `(Try)IntoJavaValue`, `(Try)FromJavaValue`) *need* a `#[package]` attribute on the type they are applied on.
+     * If we remove the package attribute blindly the traits cannot see it, and if we keep it the auto-derived traits cannot remove it (auto-derive macros cannot modify the existing token stream as proc macros).
+     * Here we check wether the type has a `#[derive(TRAIT)]` (crudely with a string comparison and hoping the user never writes `#[derive(::robusta_jni::convert::TRAIT)]`)
+     * if it is present we don't remove `#[package]`, otherwise we remove it.
+     * This works because all conversion traits auto-derive macros also declare `#[package]` as a helper attribute
+     * The same goes for `#[class()]`, which gives the name of nested classes.
+     */
+    let traits_with_package_attr = HashSet::from([
+        "Signature",
+        "FromJavaValue",
+        "TryFromJavaValue",
+        "IntoJavaValue",
+        "TryIntoJavaValue",
+        "JavaClass",
+    ]);
+
+    let has_package_trait = attributes.iter().any(|a| {
+        let is_derive = a.path().get_ident().map(ToString::to_string).as_deref() == Some("derive");
+        let derived_traits = a
+            .parse_args_with(Punctuated::<Ident, Token![,]>::parse_terminated)
+            .iter()
+            .flat_map(|p: &syn::punctuated::Punctuated<Ident, Token![,]>| p)
+            .map(|i| i.to_string())
+            .collect::<HashSet<String>>();
+        let needs_package_attr = derived_traits
+            .iter()
+            .any(|t| traits_with_package_attr.contains(t.as_str()));
+
+        is_derive && needs_package_attr
+    });
+
+    if !has_package_trait {
+        attributes
+            .into_iter()
+            .filter(|a| !a.path().is_ident("package") && !a.path().is_ident("class"))
+            .collect()
+    } else {
+        attributes
+    }
+}
+
+/// Java class name of a bridged type in JNI format: the argument of its `#[class(...)]` attribute for nested classes
+/// (e.g. `Outer$Inner` for `#[class(Outer.Inner)]`), or the type identifier otherwise.
+pub(crate) fn java_class_name(attributes: &[Attribute], ident: &Ident) -> syn::Result<String> {
+    match attributes.iter().find(|a| a.path().is_ident("class")) {
+        Some(attribute) => attribute
+            .parse_args::<JavaPath>()
+            .map(|path| path.0.replace('.', "$")),
+        None => Ok(ident.to_string()),
+    }
 }
 
 #[derive(Default)]
@@ -320,8 +944,13 @@ impl FromStr for JavaPath {
 }
 
 impl JavaPath {
-    pub fn to_snake_case(&self) -> String {
-        self.0.replace('.', "_")
+    /// Path in the format of JNI symbol names, with each component escaped (e.g. `com_my_1app` for `com.my_app`)
+    pub fn to_jni_symbol(&self) -> String {
+        self.0
+            .split('.')
+            .map(mangle_jni_name)
+            .collect::<Vec<_>>()
+            .join("_")
     }
 
     pub fn to_classpath_path(&self) -> String {
@@ -357,7 +986,7 @@ impl FromMeta for JavaPath {
                 "invalid path: packages and classes cannot contain dashes",
             ))
         } else {
-            let tokens = TokenStream::from_str(&path)
+            let tokens = TokenStream::from_str(path)
                 .map_err(|_| Error::custom("cannot create token stream for java path parsing"))?;
             let _parsed: Punctuated<Ident, Token![.]> =
                 Punctuated::<Ident, Token![.]>::parse_separated_nonempty
@@ -385,12 +1014,257 @@ impl<'ast> AttributeFilter<'ast> {
 
 impl<'ast> Visit<'ast> for AttributeFilter<'ast> {
     fn visit_attribute(&mut self, attribute: &'ast Attribute) {
-        if self.whitelist.contains(&attribute.path()) {
+        if self.whitelist.contains(attribute.path()) {
             self.filtered_attributes.push(attribute);
         }
     }
 }
 
+/// Adds a static `extern "jni"` getter for every associated constant marked with `#[export]`,
+/// named after the constant (e.g. `MAX_USERS` is exported as `getMaxUsers`)
+fn expand_exported_consts(mut node: ItemImpl) -> ItemImpl {
+    let mut getters = Vec::new();
+
+    for item in node.items.iter_mut() {
+        if let ImplItem::Const(c) = item {
+            let export_attribute = c.attrs.iter().position(|a| a.path().is_ident("export"));
+
+            if let Some(idx) = export_attribute {
+                let attribute = c.attrs.remove(idx);
+
+                if !matches!(c.vis, Visibility::Public(_)) {
+                    emit_error!(attribute, "only public constants can be exported";
+                        help = "add `pub` to the constant declaration");
+                    continue;
+                }
+
+                c.ty = StaticLifetimes.fold_type(c.ty.clone());
+                let const_ident = &c.ident;
+                let const_type = &c.ty;
+                let getter_ident = Ident::new(
+                    &to_camel_case(&format!("get_{}", const_ident.to_string().to_lowercase())),
+                    const_ident.span(),
+                );
+
+                getters.push(parse_quote_spanned! { c.span() =>
+                    #[doc(hidden)]
+                    #[allow(non_snake_case)]
+                    pub extern "jni" fn #getter_ident() -> #const_type {
+                        Self::#const_ident
+                    }
+                });
+            }
+        }
+    }
+
+    node.items.extend(getters);
+    node
+}
+
+/// Gives elided lifetimes in the type of an exported constant (e.g. `&str`) the `'static` lifetime, both in the constant
+/// (where they can't be elided in impls with lifetime parameters) and in its getter (which has no parameters to elide
+/// them from).
+struct StaticLifetimes;
+
+impl Fold for StaticLifetimes {
+    fn fold_type_reference(&mut self, node: TypeReference) -> TypeReference {
+        let mut node = syn::fold::fold_type_reference(self, node);
+        if node.lifetime.is_none() {
+            node.lifetime = Some(Lifetime::new("'static", node.and_token.span));
+        }
+
+        node
+    }
+
+    fn fold_lifetime(&mut self, node: Lifetime) -> Lifetime {
+        if node.ident == "_" {
+            Lifetime::new("'static", node.span())
+        } else {
+            node
+        }
+    }
+}
+
+/// Turn methods annotated with `#[no_bridge]` into plain Rust methods, so that they are neither exported nor transformed.
+fn skip_no_bridge_methods(mut node: ItemImpl) -> ItemImpl {
+    for item in node.items.iter_mut() {
+        if let ImplItem::Fn(f) = item {
+            if let Some(attribute) = take_no_bridge_attribute(&mut f.attrs) {
+                if get_abi(&f.sig).as_deref() == Some("java") {
+                    emit_error!(attribute, "`#[no_bridge]` can't be used on `extern \"java\"` methods";
+                        help = "imported methods have no body to call, comment out the method instead");
+                }
+
+                f.sig.abi = None;
+                f.attrs.retain(|a| {
+                    !a.path().is_ident("call_type")
+                        && !a.path().is_ident("convert")
+                        && !a.path().is_ident("check_aliasing")
+                        && !a.path().is_ident("null_check")
+                        && !a.path().is_ident("on_panic")
+                        && !a.path().is_ident("overload")
+                        && !a.path().is_ident("synchronized")
+                        && !a.path().is_ident("local_frame")
+                        && !a.path().is_ident("instrument")
+                        && !a.path().is_ident("throws")
+                        && !a.path().is_ident("call")
+                        && !a.path().is_ident("kotlin_suspend")
+                });
+            }
+        }
+    }
+
+    node
+}
+
+/// Remove the `#[on_first_use]` attribute from function `hook`, and add a `robusta_first_use` function calling it once,
+/// used by imported methods and derived conversions
+fn expand_first_use_hook(mut node: ItemImpl, hook: &Ident) -> ItemImpl {
+    let mut declared = false;
+    for item in node.items.iter_mut() {
+        if let ImplItem::Fn(f) = item {
+            if &f.sig.ident == hook {
+                f.attrs.retain(|a| !a.path().is_ident("on_first_use"));
+                declared = true;
+            }
+        }
+    }
+
+    // Other impl blocks of the struct call the function declared with the hook
+    if declared {
+        node.items.push(parse_quote_spanned! { hook.span() =>
+            #[doc(hidden)]
+            pub fn robusta_first_use(env: &::robusta_jni::jni::JNIEnv) -> ::robusta_jni::jni::errors::Result<()> {
+                static FIRST_USE: ::robusta_jni::first_use::FirstUse = ::robusta_jni::first_use::FirstUse::new();
+                FIRST_USE.call(env, Self::#hook)
+            }
+        });
+    }
+
+    node
+}
+
+/// Remove the `#[no_bridge]` attribute from `attributes`, if any
+fn take_no_bridge_attribute(attributes: &mut Vec<Attribute>) -> Option<Attribute> {
+    let idx = attributes.iter().position(|a| a.path().is_ident("no_bridge"))?;
+    let attribute = attributes.remove(idx);
+    if !matches!(attribute.meta, Meta::Path(_)) {
+        emit_error!(attribute, "`#[no_bridge]` attribute does not take parameters");
+    }
+
+    Some(attribute)
+}
+
+/// Fill the empty bodies of exported methods with a call to the method with the same (snake_case) name on `delegate`,
+/// forwarding all arguments but the environment.
+fn expand_delegated_methods(
+    mut node: ItemImpl,
+    delegate: &Path,
+    env_aliases: &BTreeSet<String>,
+) -> ItemImpl {
+    for item in node.items.iter_mut() {
+        if let ImplItem::Fn(f) = item {
+            let is_exported = matches!(f.vis, Visibility::Public(_))
+                && get_abi(&f.sig).as_deref() == Some("jni");
+            if !is_exported || !f.block.stmts.is_empty() {
+                continue;
+            }
+
+            if let Some(receiver) = f.sig.receiver() {
+                emit_error!(receiver, "delegated methods cannot take `self`";
+                    help = "add a body to the method, or remove the receiver");
+                continue;
+            }
+
+            let (signature, _) = get_env_arg(f.sig.clone(), env_aliases);
+            let args: Vec<_> = signature
+                .inputs
+                .iter()
+                .filter_map(|arg| match arg {
+                    FnArg::Typed(PatType { pat, .. }) => match &**pat {
+                        Pat::Ident(PatIdent { ident, .. }) => Some(ident.clone()),
+                        _ => {
+                            emit_error!(pat, "delegated method parameters must be plain identifiers");
+                            None
+                        }
+                    },
+                    FnArg::Receiver(_) => None,
+                })
+                .collect();
+
+            let method_ident = Ident::new(&to_snake_case(&f.sig.ident.to_string()), f.sig.ident.span());
+            f.block = parse_quote_spanned! { f.block.span() =>
+                {
+                    <#delegate>::#method_ident(#(#args),*)
+                }
+            };
+        }
+    }
+
+    node
+}
+
+/// Copy the default `#[call_type]` attribute, given on the impl block or on the struct (with the former taking precedence),
+/// to every exported and imported method without its own `#[call_type]` attribute.
+fn apply_default_call_type(mut node: ItemImpl, struct_default: Option<Attribute>) -> ItemImpl {
+    let impl_default = node
+        .attrs
+        .iter()
+        .position(|a| a.path().is_ident("call_type"))
+        .map(|idx| node.attrs.remove(idx));
+
+    let default_attribute = match impl_default.or(struct_default) {
+        Some(a) => a,
+        None => return node,
+    };
+
+    let default_call_type: Option<CallTypeAttribute> =
+        syn::parse2(default_attribute.to_token_stream()).ok();
+    // Exception class and message only make sense for exported methods
+    let has_exception_params = matches!(
+        default_call_type,
+        Some(CallTypeAttribute {
+            call_type: CallType::Safe(Some(_)),
+            ..
+        })
+    );
+
+    for item in node.items.iter_mut() {
+        if let ImplItem::Fn(f) = item {
+            let applies = match get_abi(&f.sig).as_deref() {
+                Some("jni") => true,
+                Some("java") => !has_exception_params,
+                _ => false,
+            };
+
+            if applies
+                && matches!(f.vis, Visibility::Public(_))
+                && !f.attrs.iter().any(|a| a.path().is_ident("call_type"))
+            {
+                f.attrs.push(default_attribute.clone());
+            }
+        }
+    }
+
+    node
+}
+
+/// Add `#[instrument]` to every exported and imported method, for `#[bridge(instrument)]`
+fn instrument_all(mut node: ItemImpl) -> ItemImpl {
+    for item in node.items.iter_mut() {
+        if let ImplItem::Fn(f) = item {
+            if matches!(get_abi(&f.sig).as_deref(), Some("jni") | Some("java"))
+                && matches!(f.vis, Visibility::Public(_))
+                && !f.attrs.iter().any(|a| a.path().is_ident("instrument"))
+            {
+                f.attrs.push(parse_quote! { #[instrument] });
+            }
+        }
+    }
+
+    node
+}
+
 struct ImplCleaner;
 
 impl Fold for ImplCleaner {
@@ -404,11 +1278,25 @@ impl Fold for ImplCleaner {
         match (&node.vis, &abi.as_deref()) {
             (Visibility::Public(_), Some("jni")) => {
                 node.sig.abi = None;
-                node.attrs = node
-                    .attrs
-                    .into_iter()
-                    .filter(|a| a.path().get_ident().map_or(false, |i| i != "call_type"))
-                    .collect();
+                strip_param_attributes(&mut node.sig);
+                node.attrs.retain(|a| {
+                    a.path()
+                        .get_ident()
+                        .is_some_and(|i| {
+                            i != "call_type"
+                                && i != "convert"
+                                && i != "check_aliasing"
+                                && i != "null_check"
+                                && i != "on_panic"
+                                && i != "overload"
+                                && i != "synchronized"
+                                && i != "local_frame"
+                                && i != "instrument"
+                                && i != "throws"
+                                && i != "export_name"
+                                && i != "java_name"
+                        })
+                });
 
                 node
             }
@@ -417,6 +1305,12 @@ impl Fold for ImplCleaner {
     }
 }
 
+/// Name of the parameter `self` is turned into by [`FreestandingTransformer`], which can't collide with the names of
+/// other parameters
+fn receiver_ident(span: Span) -> Ident {
+    format_ident!("__robusta_receiver", span = span)
+}
+
 struct FreestandingTransformer {
     struct_type: Path,
 }
@@ -448,7 +1342,7 @@ impl Fold for FreestandingTransformer {
                 });
 
                 if needs_env_lifetime {
-                    emit_warning!(self.struct_type, "must have one `'env` lifetime in impl to support self methods when using lifetime-parametrized struct");
+                    emit_lint!(self.struct_type, "must have one `'env` lifetime in impl to support self methods when using lifetime-parametrized struct");
                 }
 
                 let self_type = match r.reference.clone() {
@@ -474,7 +1368,7 @@ impl Fold for FreestandingTransformer {
                         attrs: vec![],
                         by_ref: None,
                         mutability: None,
-                        ident: Ident::new("receiver", receiver_span),
+                        ident: receiver_ident(receiver_span),
                         subpat: None,
                     })),
                     colon_token: Token![:](receiver_span),
@@ -492,17 +1386,133 @@ impl Fold for FreestandingTransformer {
                             attrs: ident.attrs.clone(),
                             by_ref: ident.by_ref,
                             mutability: ident.mutability,
-                            ident: Ident::new("receiver", pat_span),
+                            ident: receiver_ident(pat_span),
                             subpat: ident.subpat.clone(),
                         })),
                         colon_token: t.colon_token,
-                        ty: Box::new(parse_quote! { #self_type }),
+                        ty: Box::new(self.fold_type(parse_quote! { #self_type })),
                     })
                 }
-                _ => FnArg::Typed(t),
+                _ => FnArg::Typed(self.fold_pat_type(t)),
             },
         }
     }
+
+    /// Replace `Self` with the struct type, as freestanding functions have no `Self` type in scope
+    fn fold_type_path(&mut self, node: TypePath) -> TypePath {
+        if node.qself.is_none() && node.path.is_ident("Self") {
+            TypePath {
+                qself: None,
+                path: self.struct_type.clone(),
+            }
+        } else {
+            syn::fold::fold_type_path(self, node)
+        }
+    }
+}
+
+/// Arguments of the `#[bridge]` attribute
+#[derive(Clone, Default, FromMeta)]
+#[darling(default)]
+pub struct BridgeArgs {
+    pub(crate) rename_all: RenameRule,
+    pub(crate) java_name_format: Option<NameFormat>,
+    pub(crate) register_natives: Flag,
+    pub(crate) helper_classes: Flag,
+    pub(crate) verify_bindings: Flag,
+    pub(crate) symbol_manifest: Flag,
+    pub(crate) on_unload: Option<Override<String>>,
+    pub(crate) default_call_type: Option<DefaultCallType>,
+    pub(crate) check_against: Option<String>,
+    pub(crate) instrument: Flag,
+    /// Emit all warnings as errors, and reject items that would be ignored
+    pub(crate) strict: Flag,
+}
+
+/// Call type of methods of the module without a `#[call_type]` attribute (on the method, its impl block or its struct)
+#[derive(Clone, Copy, FromMeta)]
+pub enum DefaultCallType {
+    #[darling(rename = "safe")]
+    Safe,
+    #[darling(rename = "unchecked")]
+    Unchecked,
+}
+
+impl DefaultCallType {
+    fn attribute(&self) -> Attribute {
+        match self {
+            DefaultCallType::Safe => parse_quote! { #[call_type(safe)] },
+            DefaultCallType::Unchecked => parse_quote! { #[call_type(unchecked)] },
+        }
+    }
+}
+
+/// Mapping strategy from Rust to Java names of imported methods
+#[derive(Clone, Copy, Default, FromMeta)]
+pub enum RenameRule {
+    #[default]
+    #[darling(rename = "camelCase")]
+    CamelCase,
+    #[darling(rename = "snake_case")]
+    SnakeCase,
+    #[darling(rename = "none")]
+    None,
+}
+
+impl RenameRule {
+    pub(crate) fn apply(&self, name: &str) -> String {
+        match self {
+            RenameRule::CamelCase => to_camel_case(name),
+            RenameRule::SnakeCase => to_snake_case(name),
+            RenameRule::None => name.to_string(),
+        }
+    }
+}
+
+/// Java name of exported methods as a function of their Rust name, e.g. `native{PascalCase}`
+#[derive(Clone)]
+pub struct NameFormat(String);
+
+impl NameFormat {
+    const PLACEHOLDERS: &'static [&'static str] = &["name", "camelCase", "PascalCase", "snake_case"];
+
+    pub(crate) fn apply(&self, name: &str) -> String {
+        self.0
+            .replace("{name}", name)
+            .replace("{camelCase}", &to_camel_case(name))
+            .replace("{PascalCase}", &to_pascal_case(name))
+            .replace("{snake_case}", &to_snake_case(name))
+    }
+}
+
+impl FromMeta for NameFormat {
+    fn from_string(format: &str) -> darling::Result<Self> {
+        use darling::Error;
+
+        let mut placeholders = 0;
+        let mut rest = format;
+        while let Some(start) = rest.find('{') {
+            let end = rest[start..]
+                .find('}')
+                .ok_or_else(|| Error::custom("unclosed `{` in `java_name_format`"))?
+                + start;
+            let placeholder = &rest[start + 1..end];
+            if !Self::PLACEHOLDERS.contains(&placeholder) {
+                return Err(Error::custom(format!(
+                    "unknown placeholder `{{{}}}` in `java_name_format`, expected one of `{{name}}`, `{{camelCase}}`, `{{PascalCase}}` or `{{snake_case}}`",
+                    placeholder
+                )));
+            }
+            placeholders += 1;
+            rest = &rest[end + 1..];
+        }
+
+        if placeholders == 0 {
+            Err(Error::custom("`java_name_format` must contain a placeholder for the method name, e.g. `native{PascalCase}`"))
+        } else {
+            Ok(NameFormat(format.to_string()))
+        }
+    }
 }
 
 #[derive(Clone, Default, FromMeta)]
@@ -510,12 +1520,14 @@ impl Fold for FreestandingTransformer {
 pub struct SafeParams {
     pub(crate) exception_class: Option<JavaPath>,
     pub(crate) message: Option<String>,
+    /// Class of the exceptions wrapping the source chain of the error, set as cause of the thrown exception
+    pub(crate) cause_class: Option<JavaPath>,
 }
 
 #[derive(Clone, FromMeta)]
 pub enum CallType {
     Safe(Option<SafeParams>),
-    Unchecked(Flag),
+    Unchecked(#[allow(dead_code)] Flag),
 }
 
 pub struct CallTypeAttribute {
@@ -2,7 +2,6 @@ use std::collections::HashSet;
 use std::str::FromStr;
 
 use proc_macro2::TokenStream;
-use proc_macro_error::emit_warning;
 use quote::ToTokens;
 use syn::visit::Visit;
 use syn::ImplItemFn;
@@ -17,11 +16,11 @@ pub(crate) fn get_call_type(node: &ImplItemFn) -> Option<CallTypeAttribute> {
     };
 
     let mut attributes_collector = AttributeFilter::with_whitelist(whitelist);
-    attributes_collector.visit_impl_item_fn(&node);
+    attributes_collector.visit_impl_item_fn(node);
 
     let call_type_attribute = attributes_collector.filtered_attributes.first().and_then(|call_type_attr| {
         syn::parse2(call_type_attr.to_token_stream()).map_err(|e| {
-            emit_warning!(e.span(), format!("invalid parsing of `call_type` attribute, defaulting to #[call_type(safe)]. {}", e));
+            emit_lint!(e.span(), format!("invalid parsing of `call_type` attribute, defaulting to #[call_type(safe)]. {}", e));
             e
         }).ok()
     });
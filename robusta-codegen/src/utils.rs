@@ -1,10 +1,233 @@
+use std::cell::Cell;
+use std::collections::BTreeSet;
 use std::iter;
 
+use proc_macro2::TokenStream;
 use proc_macro_error::emit_error;
+use quote::{quote, ToTokens};
+use syn::spanned::Spanned;
 use syn::{
-    parse_quote, FnArg, Pat, PatIdent, PatType, Path, PathArguments, Signature, Type, TypeReference,
+    parse_quote, parse_quote_spanned, Attribute, FnArg, GenericArgument, LitInt, LitStr, Meta, Pat, PatIdent, PatType, Path,
+    PathArguments, ReturnType, Signature, Type, TypePath, TypeReference,
 };
 
+thread_local! {
+    /// Whether the `#[bridge]` being expanded has the `strict` flag
+    static STRICT: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Whether warnings are emitted as errors, see `emit_lint!`
+pub fn is_strict() -> bool {
+    STRICT.with(Cell::get)
+}
+
+/// Strict mode of the current `#[bridge]` expansion, reset when dropped (also when the expansion aborts)
+pub struct StrictMode;
+
+impl StrictMode {
+    pub fn enable(strict: bool) -> Self {
+        STRICT.with(|s| s.set(strict));
+        StrictMode
+    }
+}
+
+impl Drop for StrictMode {
+    fn drop(&mut self) {
+        STRICT.with(|s| s.set(false));
+    }
+}
+
+/// Helper attributes of `extern "jni"` methods
+pub const EXPORTED_METHOD_ATTRIBUTES: &[&str] = &[
+    "call_type",
+    "convert",
+    "check_aliasing",
+    "null_check",
+    "on_panic",
+    "overload",
+    "synchronized",
+    "export_name",
+    "java_name",
+    "local_frame",
+    "instrument",
+    "throws",
+];
+
+/// Helper attributes of `extern "java"` methods
+pub const IMPORTED_METHOD_ATTRIBUTES: &[&str] = &[
+    "call_type",
+    "convert",
+    "constructor",
+    "companion",
+    "java_name",
+    "fallback_signature",
+    "call",
+    "class",
+    "kotlin_suspend",
+    "local_frame",
+    "instrument",
+];
+
+/// Helper attributes of fields of structs deriving the conversion traits
+pub const FIELD_ATTRIBUTES: &[&str] = &["instance", "field", "writeback"];
+
+/// Argument of the `#[java_name("...")]` attribute of a method, if present
+pub fn java_name_override(attributes: &[Attribute]) -> Option<String> {
+    let attribute = attributes.iter().find(|a| a.path().is_ident("java_name"))?;
+
+    match attribute.parse_args::<LitStr>() {
+        Ok(name) => Some(name.value()),
+        Err(_) => {
+            emit_error!(attribute, "expected string literal argument for `#[java_name]`"; help = "use e.g. `#[java_name(\"methodName\")]`");
+            None
+        }
+    }
+}
+
+/// Capacity of the local frame of a method marked with `#[local_frame]` or `#[local_frame(capacity = 512)]`, see
+/// `robusta_jni::frame`. Returns `None` for methods without a local frame.
+pub fn local_frame_capacity(attributes: &[Attribute]) -> Option<TokenStream> {
+    let attribute = attributes.iter().find(|a| a.path().is_ident("local_frame"))?;
+
+    if let Meta::Path(_) = attribute.meta {
+        return Some(quote! { ::robusta_jni::frame::DEFAULT_CAPACITY });
+    }
+
+    let mut capacity = None;
+    let parsed = attribute.parse_nested_meta(|meta| {
+        if meta.path.is_ident("capacity") {
+            capacity = Some(meta.value()?.parse::<LitInt>()?.base10_parse::<i32>()?);
+            Ok(())
+        } else {
+            Err(meta.error("unknown `local_frame` parameter"))
+        }
+    });
+
+    match (parsed, capacity) {
+        (Ok(()), Some(capacity)) if capacity > 0 => Some(quote! { #capacity }),
+        _ => {
+            emit_error!(attribute, "invalid `local_frame` attribute";
+                help = "use `#[local_frame]` or `#[local_frame(capacity = 512)]`, with a positive capacity");
+            None
+        }
+    }
+}
+
+/// Whether a parameter of an `extern "jni"` method is marked with `#[critical]`, see `robusta_jni::convert::critical`
+pub fn is_critical_param(attributes: &[Attribute]) -> bool {
+    attributes.iter().any(|a| a.path().is_ident("critical"))
+}
+
+/// Whether a method is marked with `#[instrument]`, see `robusta_jni::instrument`
+pub fn is_instrumented(attributes: &[Attribute]) -> bool {
+    let attribute = match attributes.iter().find(|a| a.path().is_ident("instrument")) {
+        Some(attribute) => attribute,
+        None => return false,
+    };
+
+    if !matches!(attribute.meta, Meta::Path(_)) {
+        emit_error!(attribute, "`instrument` takes no arguments");
+    }
+    true
+}
+
+/// Converter type given with `#[convert(with = "...")]`, see `robusta_jni::convert::converter`. Invalid attributes are
+/// reported by `check_convert_attributes`.
+pub fn converter_type(attributes: &[Attribute]) -> Option<Type> {
+    let attribute = attributes.iter().find(|a| a.path().is_ident("convert"))?;
+    parse_converter(attribute).ok()
+}
+
+fn parse_converter(attribute: &Attribute) -> syn::Result<Type> {
+    let mut converter = None;
+    attribute.parse_nested_meta(|meta| {
+        if meta.path.is_ident("with") {
+            converter = Some(meta.value()?.parse::<LitStr>()?.parse::<Type>()?);
+            Ok(())
+        } else {
+            Err(meta.error("unknown `convert` parameter"))
+        }
+    })?;
+
+    converter.ok_or_else(|| syn::Error::new(attribute.span(), "missing converter"))
+}
+
+/// Emit an error for every invalid `#[convert]` attribute of a method or of its parameters
+pub fn check_convert_attributes(attributes: &[Attribute], signature: &Signature) {
+    let param_attributes = signature.inputs.iter().flat_map(|i| match i {
+        FnArg::Typed(t) => t.attrs.as_slice(),
+        FnArg::Receiver(r) => r.attrs.as_slice(),
+    });
+
+    for attribute in attributes.iter().chain(param_attributes).filter(|a| a.path().is_ident("convert")) {
+        if parse_converter(attribute).is_err() {
+            emit_error!(attribute, "invalid `convert` attribute";
+                help = "use e.g. `#[convert(with = \"my_module::MyConverter\")]`, with a type implementing `robusta_jni::convert::Converter`");
+        }
+    }
+}
+
+/// `Converted` type of values of type `ty` converted with `converter`
+pub fn converted_type(converter: &Type, ty: &Type) -> Type {
+    parse_quote_spanned! { ty.span() => ::robusta_jni::convert::converter::Converted<#converter, #ty> }
+}
+
+/// Value type of `ty` if it's a `Result<T, E>` with an error type of its own, as returned by methods with errors of
+/// their own type (aliases with a fixed error type such as `JniResult<T>` are converted as a whole)
+pub fn result_value_type(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Path(TypePath { path, .. }) => path.segments.last().and_then(|s| match &s.arguments {
+            PathArguments::AngleBracketed(a) if s.ident == "Result" && a.args.len() == 2 => match a.args.first() {
+                Some(GenericArgument::Type(value)) => Some(value),
+                _ => None,
+            },
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+/// Whether `ty` is a `Result<T>`, or an alias such as `JniResult<T>`
+pub fn is_result_type(ty: &Type) -> bool {
+    match ty {
+        Type::Path(TypePath { path, .. }) => path.segments.last().is_some_and(|s| {
+            s.ident.to_string().ends_with("Result") && matches!(s.arguments, PathArguments::AngleBracketed(_))
+        }),
+        _ => false,
+    }
+}
+
+/// Return type `output` with the returned value converted with `converter`: `T` becomes `Converted<C, T>`, and `Result<T>`
+/// becomes `Result<Converted<C, T>>`
+pub fn converted_return_type(converter: &Type, output: ReturnType) -> ReturnType {
+    match output {
+        ReturnType::Type(arrow, mut ty) => {
+            if is_result_type(&ty) {
+                if let Type::Path(TypePath { path, .. }) = ty.as_mut() {
+                    if let Some(PathArguments::AngleBracketed(a)) = path.segments.last_mut().map(|s| &mut s.arguments) {
+                        if let Some(GenericArgument::Type(inner)) = a.args.first_mut() {
+                            *inner = converted_type(converter, inner);
+                        }
+                    }
+                }
+                ReturnType::Type(arrow, ty)
+            } else {
+                ReturnType::Type(arrow, Box::new(converted_type(converter, &ty)))
+            }
+        }
+        ReturnType::Default => output,
+    }
+}
+
+/// Remove `#[critical]` and `#[convert]` attributes from the parameters of `signature`, which are only known to `#[bridge]`
+pub fn strip_param_attributes(signature: &mut Signature) {
+    for input in signature.inputs.iter_mut() {
+        if let FnArg::Typed(t) = input {
+            t.attrs.retain(|a| !a.path().is_ident("critical") && !a.path().is_ident("convert"));
+        }
+    }
+}
+
 pub fn canonicalize_path(path: &Path) -> Path {
     let mut result = path.clone();
     result.segments = result
@@ -29,7 +252,35 @@ pub fn is_self_method(signature: &Signature) -> bool {
     })
 }
 
-pub fn get_env_arg(signature: Signature) -> (Signature, Option<FnArg>) {
+/// Whether `path` refers to `JNIEnv`, either by name, by full path or by an alias declared in the bridged module
+pub fn is_env_path(path: &Path, env_aliases: &BTreeSet<String>) -> bool {
+    let canonicalized_type_path = canonicalize_path(path);
+
+    match canonicalized_type_path.segments.last() {
+        Some(segment) if segment.ident == "JNIEnv" => true,
+        Some(segment) => {
+            canonicalized_type_path.segments.len() == 1
+                && env_aliases.contains(&segment.ident.to_string())
+        }
+        None => false,
+    }
+}
+
+/// Whether `ty` is `JNIEnv`, or a reference to it
+fn is_env_type(ty: &Type, env_aliases: &BTreeSet<String>) -> bool {
+    match ty {
+        Type::Reference(TypeReference { elem, .. }) => is_env_type(elem, env_aliases),
+        Type::Path(t) => is_env_path(&t.path, env_aliases),
+        _ => false,
+    }
+}
+
+/// Whether the environment parameter is a `&mut JNIEnv`
+pub fn is_mut_env_arg(env_arg: &FnArg) -> bool {
+    matches!(env_arg, FnArg::Typed(PatType { ty, .. }) if matches!(&**ty, Type::Reference(TypeReference { mutability: Some(_), .. })))
+}
+
+pub fn get_env_arg(signature: Signature, env_aliases: &BTreeSet<String>) -> (Signature, Option<FnArg>) {
     let self_method = is_self_method(&signature);
 
     // Check whether second argument (first exluding self) is of type &JNIEnv, if so we take it out from the signature
@@ -42,21 +293,13 @@ pub fn get_env_arg(signature: Signature) -> (Signature, Option<FnArg>) {
     let has_explicit_env_arg = if let Some(FnArg::Typed(PatType { ty, .. })) = possible_env_arg {
         if let Type::Reference(TypeReference { elem, .. }) = &**ty {
             if let Type::Path(t) = &**elem {
-                let full_path: Path = parse_quote! { ::robusta_jni::jni::JNIEnv };
-                let imported_path: Path = parse_quote! { JNIEnv };
-                let canonicalized_type_path = canonicalize_path(&t.path);
-
-                canonicalized_type_path == imported_path || canonicalized_type_path == full_path
+                is_env_path(&t.path, env_aliases)
             } else {
                 false
             }
         } else if let Type::Path(t) = &**ty {
             /* If the user has input `env: JNIEnv` instead of `env: &JNIEnv`, we let her know. */
-            let full_path: Path = parse_quote! { ::robusta_jni::jni::JNIEnv };
-            let imported_path: Path = parse_quote! { JNIEnv };
-            let canonicalized_type_path = canonicalize_path(&t.path);
-
-            if canonicalized_type_path == imported_path || canonicalized_type_path == full_path {
+            if is_env_path(&t.path, env_aliases) {
                 emit_error!(
                     t,
                     "explicit environment parameter must be of type `&JNIEnv`"
@@ -95,6 +338,93 @@ pub fn get_env_arg(signature: Signature) -> (Signature, Option<FnArg>) {
     (transformed_signature, env_arg)
 }
 
+/// Emit an error for every `JNIEnv` parameter which is not in the position expected by [`get_env_arg`]
+pub fn check_misplaced_env_args(signature: &Signature, env_aliases: &BTreeSet<String>) {
+    let env_position = if is_self_method(signature) { 1 } else { 0 };
+
+    signature
+        .inputs
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| *idx != env_position)
+        .for_each(|(_, input)| {
+            if let FnArg::Typed(PatType { ty, .. }) = input {
+                if is_env_type(ty, env_aliases) {
+                    emit_error!(ty, "environment parameter must be the first parameter (after `self` if present)");
+                }
+            }
+        });
+}
+
+/// Emit an error for every robusta helper attribute in `attributes` which is not one of `allowed`, and for every unknown
+/// attribute close enough to a helper attribute to be a misspelling of it. `position` describes the annotated item
+/// in error messages (e.g. "`extern \"jni\"` methods").
+pub fn check_helper_attributes(attributes: &[Attribute], allowed: &[&str], position: &str) {
+    let known = EXPORTED_METHOD_ATTRIBUTES
+        .iter()
+        .chain(IMPORTED_METHOD_ATTRIBUTES)
+        .chain(FIELD_ATTRIBUTES);
+
+    for attribute in attributes {
+        let name = match attribute.path().get_ident() {
+            Some(ident) => ident.to_string(),
+            None => continue,
+        };
+
+        if allowed.contains(&name.as_str()) {
+            continue;
+        }
+
+        if known.clone().any(|k| *k == name) {
+            let allowed_list = allowed.iter().map(|a| format!("`#[{}]`", a)).collect::<Vec<_>>().join(", ");
+            emit_error!(attribute, "`#[{}]` attribute cannot be used on {}", name, position;
+                help = "{} only accept {}", position, allowed_list);
+            continue;
+        }
+
+        // Prefer suggestions valid in this position
+        let max_distance = std::cmp::max(1, name.len() / 3);
+        let suggestion = allowed
+            .iter()
+            .chain(known.clone())
+            .map(|k| (edit_distance(&name, k), *k))
+            .filter(|(distance, _)| *distance <= max_distance)
+            .min_by_key(|(distance, _)| *distance);
+
+        if let Some((_, suggestion)) = suggestion {
+            emit_error!(attribute, "unknown attribute `#[{}]`", name;
+                help = "did you mean `#[{}]`?", suggestion);
+        }
+    }
+}
+
+/// Edit distance between `a` and `b`, counting transpositions of adjacent characters as a single edit
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            d[i][j] = (d[i - 1][j] + 1).min(d[i][j - 1] + 1).min(d[i - 1][j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    d[a.len()][b.len()]
+}
+
 pub fn get_class_arg_if_any(signature: Signature) -> (Signature, Option<FnArg>) {
     let has_explicit_class_ref_arg = if let Some(FnArg::Typed(PatType { ty, .. })) = signature.inputs.iter().next() {
         if let Type::Reference(TypeReference { elem, .. }) = &**ty {
@@ -139,6 +469,87 @@ pub fn get_class_arg_if_any(signature: Signature) -> (Signature, Option<FnArg>)
     }
 }
 
+/// Take out the `JClass` parameter of a static exported method, which must come first (after the environment parameter,
+/// if [`get_env_arg`] was already applied). Its argument is the class passed by JNI to static native methods.
+pub fn get_jclass_arg(signature: Signature) -> (Signature, Option<FnArg>) {
+    let is_jclass = |input: Option<&FnArg>| match input {
+        Some(FnArg::Typed(PatType { ty, .. })) => match &**ty {
+            Type::Path(t) => t.path.segments.last().is_some_and(|s| s.ident == "JClass"),
+            _ => false,
+        },
+        _ => false,
+    };
+
+    if is_self_method(&signature) {
+        if let Some(input) = signature.inputs.iter().skip(1).find(|i| is_jclass(Some(i))) {
+            emit_error!(input, "only static methods can take a `JClass` parameter");
+        }
+
+        return (signature, None);
+    }
+
+    if !is_jclass(signature.inputs.first()) {
+        return (signature, None);
+    }
+
+    let mut inner_signature = signature;
+    let mut iter = inner_signature.inputs.into_iter();
+    let class_arg = iter.next();
+    inner_signature.inputs = iter.collect();
+
+    (inner_signature, class_arg)
+}
+
+/// Take out the `&Vm` parameter of an exported method, which must come first (after `self` and the environment parameter,
+/// if [`get_env_arg`] was already applied). Its argument is the process-wide `robusta_jni::Vm` handle.
+pub fn get_vm_arg(signature: Signature) -> (Signature, Option<FnArg>) {
+    let is_vm = |input: &FnArg| match input {
+        FnArg::Typed(PatType { ty, .. }) => match &**ty {
+            Type::Reference(TypeReference { elem, mutability: None, .. }) => {
+                matches!(&**elem, Type::Path(t) if t.path.segments.last().is_some_and(|s| s.ident == "Vm"))
+            }
+            _ => false,
+        },
+        _ => false,
+    };
+
+    let position = if is_self_method(&signature) { 1 } else { 0 };
+    if !signature.inputs.iter().nth(position).is_some_and(is_vm) {
+        if let Some(input) = signature.inputs.iter().skip(position + 1).find(|i| is_vm(i)) {
+            emit_error!(input, "`Vm` parameter must be the first parameter (after `self` and `env` if present)");
+        }
+        return (signature, None);
+    }
+
+    let mut inner_signature = signature;
+    let mut inputs: Vec<FnArg> = inner_signature.inputs.into_iter().collect();
+    let vm_arg = inputs.remove(position);
+    inner_signature.inputs = inputs.into_iter().collect();
+
+    (inner_signature, Some(vm_arg))
+}
+
+/// Type as written in source code, without the spaces added by `ToTokens` (e.g. `Vec<String>` instead of `Vec < String >`)
+pub fn type_name(ty: &Type) -> String {
+    let tokens = ty.to_token_stream().to_string();
+    let chars: Vec<char> = tokens.chars().collect();
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+
+    chars
+        .iter()
+        .enumerate()
+        .filter(|&(i, &c)| {
+            // Spaces are only needed between words, e.g. in `&'a str` or `dyn Trait`, and before slices in `&mut [T]`
+            c != ' '
+                || (i > 0
+                    && i + 1 < chars.len()
+                    && is_word(chars[i - 1])
+                    && (is_word(chars[i + 1]) || chars[i + 1] == '['))
+        })
+        .map(|(_, &c)| c)
+        .collect()
+}
+
 pub fn get_abi(sig: &Signature) -> Option<String> {
     sig.abi
         .as_ref()
@@ -1,15 +1,19 @@
 use core::option::Option::{None, Some};
 use core::result::Result::{Err, Ok};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
-use proc_macro_error::{emit_error, emit_warning};
+use proc_macro_error::emit_error;
 use quote::ToTokens;
 use syn::parse::{Parse, ParseBuffer};
 use syn::spanned::Spanned;
 use syn::visit::Visit;
-use syn::{Attribute, Error, GenericParam, Item, ItemImpl, ItemMod, ItemStruct, Result, Type};
+use syn::{
+    Attribute, Error, GenericParam, Ident, ImplItem, Item, ItemImpl, ItemMod, ItemStruct, ItemType, Path,
+    Result, Type, UseRename,
+};
 
-use crate::transformation::JavaPath;
+use crate::transformation::{java_class_name, JavaPath};
+use crate::utils::canonicalize_path;
 
 struct AttribItemChecker {
     valid: bool,
@@ -31,10 +35,7 @@ impl<'ast> Visit<'ast> for AttribItemChecker {
                 emit_error!(i.span(), "`package` attribute used on non-struct type");
                 self.valid = false;
             }
-            Item::Enum(i) if i.attrs.iter().any(has_package_attribute) => {
-                emit_error!(i.span(), "`package` attribute used on non-struct type"; help = i.enum_token.span() => "replace `enum` with `struct`");
-                self.valid = false;
-            }
+            Item::Enum(_) => {}
             Item::ExternCrate(i) if i.attrs.iter().any(has_package_attribute) => {
                 emit_error!(i.span(), "`package` attribute used on non-struct type");
                 self.valid = false;
@@ -47,10 +48,8 @@ impl<'ast> Visit<'ast> for AttribItemChecker {
                 emit_error!(i.span(), "`package` attribute used on non-struct type");
                 self.valid = false;
             }
-            Item::Impl(i) if i.attrs.iter().any(has_package_attribute) => {
-                emit_error!(i.span(), "`package` attribute used on non-struct type");
-                self.valid = false;
-            }
+            // Impl blocks of types declared outside of the module, see `external_impls`
+            Item::Impl(_) => {}
             Item::Macro(i) if i.attrs.iter().any(has_package_attribute) => {
                 emit_error!(i.span(), "`package` attribute used on non-struct type");
                 self.valid = false;
@@ -100,6 +99,28 @@ impl<'ast> Visit<'ast> for ImplAccumulator<'ast> {
     }
 }
 
+/// Collects names under which `JNIEnv` is available in the module, i.e. `use ... JNIEnv as Alias` and `type Alias = JNIEnv`
+#[derive(Default)]
+struct EnvAliasVisitor {
+    aliases: BTreeSet<String>,
+}
+
+impl<'ast> Visit<'ast> for EnvAliasVisitor {
+    fn visit_use_rename(&mut self, node: &'ast UseRename) {
+        if node.ident == "JNIEnv" {
+            self.aliases.insert(node.rename.to_string());
+        }
+    }
+
+    fn visit_item_type(&mut self, node: &'ast ItemType) {
+        if let Type::Path(p) = &*node.ty {
+            if p.path.segments.last().is_some_and(|s| s.ident == "JNIEnv") {
+                self.aliases.insert(node.ident.to_string());
+            }
+        }
+    }
+}
+
 enum StructDeclarationKind {
     // structs with `package` attrib and impl
     Bridged,
@@ -156,12 +177,26 @@ impl<'ast> Visit<'ast> for StructDeclVisitor<'ast> {
 pub(crate) struct JNIBridgeModule {
     pub(crate) module_decl: ItemMod,
     pub(crate) package_map: BTreeMap<String, Option<JavaPath>>,
+    /// Java class names of structs mapped to nested classes (i.e. annotated with `#[class(...)]`), by struct name
+    pub(crate) class_names: BTreeMap<String, String>,
+    /// Names of structs mapped to Kotlin `object` declarations (i.e. annotated with `#[kotlin_object]`)
+    pub(crate) kotlin_objects: BTreeSet<String>,
+    /// Aliases of `JNIEnv` declared in the module
+    pub(crate) env_aliases: BTreeSet<String>,
+    /// Types that exported methods of a struct forward to (i.e. argument of `#[delegate_to]`), by struct name
+    pub(crate) delegates: BTreeMap<String, Path>,
+    /// Default `#[call_type]` attributes for methods of a struct, by struct name
+    pub(crate) call_types: BTreeMap<String, Attribute>,
+    /// Default `#[call_type]` attribute for all methods of the module, declared on the module itself
+    pub(crate) call_type: Option<Attribute>,
+    /// Functions of a struct marked with `#[on_first_use]`, by struct name
+    pub(crate) first_use_hooks: BTreeMap<String, Ident>,
 }
 
 impl Parse for JNIBridgeModule {
     fn parse(input: &ParseBuffer) -> Result<Self> {
         let mut valid_input;
-        let module_decl: ItemMod = input.parse().map_err(|e| {
+        let mut module_decl: ItemMod = input.parse().map_err(|e| {
             Error::new(
                 e.span(),
                 "`bridge` attribute is supported on mod items only",
@@ -172,18 +207,33 @@ impl Parse for JNIBridgeModule {
         attribute_checker.visit_item_mod(&module_decl);
         valid_input = attribute_checker.valid;
 
+        let mut env_alias_visitor = EnvAliasVisitor::default();
+        env_alias_visitor.visit_item_mod(&module_decl);
+
         let mut impl_visitor = ImplAccumulator::default();
         impl_visitor.visit_item_mod(&module_decl);
 
         let mut mod_visitor = StructDeclVisitor::new(impl_visitor.impls);
         mod_visitor.visit_item_mod(&module_decl);
 
+        let declared_structs: BTreeSet<String> = mod_visitor
+            .module_structs
+            .iter()
+            .map(|(s, _)| s.ident.to_string())
+            .collect();
+        let external_impls: Vec<ItemImpl> = mod_visitor
+            .module_impls
+            .iter()
+            .filter(|i| i.attrs.iter().any(|a| a.path().is_ident("package")))
+            .map(|i| (*i).clone())
+            .collect();
+
         let bridged_structs: Vec<_> = mod_visitor.module_structs.into_iter()
             .filter_map(|(struct_item, decl_kind)| {
                 match decl_kind {
                     StructDeclarationKind::Bridged => Some(struct_item),
                     StructDeclarationKind::UnImpl => {
-                        emit_warning!(struct_item, "ignoring struct without declared methods"; help = "add methods using an `impl` block");
+                        emit_lint!(struct_item, "ignoring struct without declared methods"; help = "add methods using an `impl` block");
                         None
                     }
                     StructDeclarationKind::UnAttrib => {
@@ -192,7 +242,7 @@ impl Parse for JNIBridgeModule {
                         None
                     }
                     StructDeclarationKind::Bare => {
-                        emit_warning!(struct_item, "ignoring struct with no `package` attribute and no implementation";
+                        emit_lint!(struct_item, "ignoring struct with no `package` attribute and no implementation";
                             help = struct_item.span() => "add a #[package(...)] attribute";
                             note = "structs with declared methods require package attribute for correct translation");
                         None
@@ -215,10 +265,48 @@ impl Parse for JNIBridgeModule {
             .map(|(s, i)| (s.clone(), i.clone()))
             .collect();
 
+        let mut first_use_hooks: BTreeMap<String, Ident> = BTreeMap::new();
+        for item_impl in &mod_visitor.module_impls {
+            let struct_name = match &*item_impl.self_ty {
+                Type::Path(p) => canonicalize_path(&p.path).to_token_stream().to_string().replace(' ', ""),
+                _ => continue,
+            };
+
+            for f in item_impl.items.iter().filter_map(|i| match i {
+                ImplItem::Fn(f) => Some(f),
+                _ => None,
+            }) {
+                let attr = match f.attrs.iter().find(|a| a.path().is_ident("on_first_use")) {
+                    Some(attr) => attr,
+                    None => continue,
+                };
+
+                if attr.meta.require_path_only().is_err() {
+                    emit_error!(attr, "`#[on_first_use]` attribute doesn't have any arguments");
+                    valid_input = false;
+                }
+                if let Some(abi) = &f.sig.abi {
+                    emit_error!(abi, "`#[on_first_use]` functions can't be exported or imported";
+                        help = "use a plain function, with signature `fn(&JNIEnv) -> jni::errors::Result<()>`");
+                    valid_input = false;
+                    continue;
+                }
+                if let Some(other) = first_use_hooks.insert(struct_name.clone(), f.sig.ident.clone()) {
+                    emit_error!(f.sig.ident, "`{}` already has an `#[on_first_use]` function", struct_name;
+                        note = other.span() => "first declared here");
+                    valid_input = false;
+                }
+            }
+        }
+
         mod_visitor
             .module_impls
             .into_iter()
             .filter(|i| {
+                if i.attrs.iter().any(|a| a.path().is_ident("package")) {
+                    return false;
+                }
+
                 if let Type::Path(p) = &*i.self_ty {
                     let impl_struct_name = p.path.segments.last().unwrap().ident.to_string();
                     let has_generics = i
@@ -263,7 +351,7 @@ impl Parse for JNIBridgeModule {
                 valid_input = false;
             });
 
-        let package_map: BTreeMap<String, Option<JavaPath>> = bridged_structs
+        let mut package_map: BTreeMap<String, Option<JavaPath>> = bridged_structs
             .iter()
             .map(|s| {
                 let name = s.ident.to_string();
@@ -281,6 +369,86 @@ impl Parse for JNIBridgeModule {
             })
             .collect();
 
+        let mut class_names: BTreeMap<String, String> = bridged_structs
+            .iter()
+            .filter(|s| s.attrs.iter().any(|a| a.path().is_ident("class")))
+            .filter_map(|s| match java_class_name(&s.attrs, &s.ident) {
+                Ok(class_name) => Some((s.ident.to_string(), class_name)),
+                Err(e) => {
+                    emit_error!(e.span(), "invalid `class` attribute: {}", e;
+                        help = "use the name of the class relative to its package, e.g. `#[class(Outer.Inner)]`");
+                    valid_input = false;
+                    None
+                }
+            })
+            .collect();
+
+        for item_impl in &external_impls {
+            let (name, package, class_name) = match external_class(item_impl, &declared_structs) {
+                Some(class) => class,
+                None => {
+                    valid_input = false;
+                    continue;
+                }
+            };
+
+            match package_map.get(&name) {
+                Some(Some(other)) if other.to_string() != package.to_string() || class_names.get(&name) != Some(&class_name) => {
+                    emit_error!(item_impl.self_ty, "conflicting Java classes given for `{}`", name;
+                        note = "all the impl blocks of a type must map it to the same Java class");
+                    valid_input = false;
+                }
+                _ => {
+                    package_map.insert(name.clone(), Some(package));
+                    class_names.insert(name, class_name);
+                }
+            }
+        }
+
+        let kotlin_objects: BTreeSet<String> = bridged_structs
+            .iter()
+            .filter(|s| {
+                s.attrs
+                    .iter()
+                    .any(|a| a.path().is_ident("kotlin_object"))
+            })
+            .map(|s| s.ident.to_string())
+            .collect();
+
+        let delegates: BTreeMap<String, Path> = bridged_structs
+            .iter()
+            .filter_map(|s| {
+                let attr = s.attrs.iter().find(|a| a.path().is_ident("delegate_to"))?;
+
+                match attr.parse_args::<Path>() {
+                    Ok(path) => Some((s.ident.to_string(), path)),
+                    Err(_) => {
+                        emit_error!(attr, "expected type path as `delegate_to` argument";
+                            help = "use e.g. `#[delegate_to(Calculator)]`");
+                        valid_input = false;
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        let call_types: BTreeMap<String, Attribute> = bridged_structs
+            .iter()
+            .filter_map(|s| {
+                s.attrs
+                    .iter()
+                    .find(|a| a.path().is_ident("call_type"))
+                    .map(|a| (s.ident.to_string(), a.clone()))
+            })
+            .collect();
+
+        // The attribute is only used by `#[bridge]`, either as an outer (`#[call_type]`) or inner (`#![call_type]`) attribute
+        let call_type = module_decl
+            .attrs
+            .iter()
+            .position(|a| a.path().is_ident("call_type"))
+            .map(|idx| module_decl.attrs.remove(idx));
+
         if !valid_input {
             Err(Error::new(
                 module_decl.span(),
@@ -290,7 +458,53 @@ impl Parse for JNIBridgeModule {
             Ok(JNIBridgeModule {
                 module_decl,
                 package_map,
+                class_names,
+                kotlin_objects,
+                env_aliases: env_alias_visitor.aliases,
+                delegates,
+                call_types,
+                call_type,
+                first_use_hooks,
             })
         }
     }
 }
+
+/// Name (as a canonical path), package and Java class name of a type declared outside of the module, from the `#[package]`
+/// and `#[class]` attributes of one of its impl blocks, or `None` if they are invalid (errors are reported).
+fn external_class(item_impl: &ItemImpl, declared_structs: &BTreeSet<String>) -> Option<(String, JavaPath, String)> {
+    let package_attr = item_impl.attrs.iter().find(|a| a.path().is_ident("package"))?;
+    let path = match &*item_impl.self_ty {
+        Type::Path(p) if p.qself.is_none() => &p.path,
+        _ => {
+            emit_error!(package_attr, "`package` attribute can only be used on impl blocks of named types");
+            return None;
+        }
+    };
+    let ident = &path.segments.last()?.ident;
+
+    if path.segments.len() == 1 && declared_structs.contains(&ident.to_string()) {
+        emit_error!(package_attr, "`package` attribute used on the impl block of a struct declared in this module";
+            help = "move the attribute to the declaration of `{}`", ident);
+        return None;
+    }
+
+    let package = match package_attr.parse_args::<JavaPath>() {
+        Ok(package) => package,
+        Err(e) => {
+            emit_error!(e.span(), "invalid Java class path: {}", e);
+            return None;
+        }
+    };
+    let class_name = match java_class_name(&item_impl.attrs, ident) {
+        Ok(class_name) => class_name,
+        Err(e) => {
+            emit_error!(e.span(), "invalid `class` attribute: {}", e;
+                help = "use the name of the class relative to its package, e.g. `#[class(Outer.Inner)]`");
+            return None;
+        }
+    };
+    let name = canonicalize_path(path).to_token_stream().to_string().replace(' ', "");
+
+    Some((name, package, class_name))
+}
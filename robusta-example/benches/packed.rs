@@ -0,0 +1,68 @@
+//! Compare conversions of numeric lists as `Vec<i32>`, boxing each element from Rust, with conversions as
+//! `PackedList<i32>`, copying the elements through a primitive array.
+//!
+//! Run with:
+//! ```text
+//! cargo bench -p robusta-example --bench packed
+//! ```
+//!
+//! The JVM library must be found by the dynamic loader, e.g. with `LD_LIBRARY_PATH=$JAVA_HOME/lib/server` on Linux.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use robusta_jni::convert::{PackedList, TryFromJavaValue, TryIntoJavaValue};
+use robusta_jni::embed::VmBuilder;
+use robusta_jni::jni::objects::JObject;
+use robusta_jni::jni::JNIEnv;
+
+const LENGTHS: [i32; 4] = [1, 16, 256, 4096];
+
+/// Run `f` in a local frame, so that local references created by the call don't pile up.
+fn in_frame<R>(env: &JNIEnv, f: impl FnOnce() -> R) -> R {
+    env.push_local_frame(16).unwrap();
+    let result = f();
+    env.pop_local_frame(JObject::null()).unwrap();
+    result
+}
+
+fn to_java(c: &mut Criterion, env: &JNIEnv) {
+    let mut group = c.benchmark_group("list of i32 to Java");
+    for len in LENGTHS {
+        let values: Vec<i32> = (0..len).collect();
+        group.bench_with_input(BenchmarkId::new("Vec", len), &values, |b, values| {
+            b.iter(|| in_frame(env, || TryIntoJavaValue::try_into(values.clone(), env).unwrap()))
+        });
+        group.bench_with_input(BenchmarkId::new("PackedList", len), &values, |b, values| {
+            b.iter(|| in_frame(env, || TryIntoJavaValue::try_into(PackedList(values.clone()), env).unwrap()))
+        });
+    }
+    group.finish();
+}
+
+fn from_java(c: &mut Criterion, env: &JNIEnv) {
+    let mut group = c.benchmark_group("list of i32 from Java");
+    for len in LENGTHS {
+        let values: Vec<i32> = (0..len).collect();
+        let list = TryIntoJavaValue::try_into(values, env).unwrap();
+        let list = env.new_global_ref(unsafe { JObject::from_raw(list) }).unwrap();
+
+        group.bench_with_input(BenchmarkId::new("Vec", len), &list, |b, list| {
+            b.iter(|| in_frame(env, || -> Vec<i32> { TryFromJavaValue::try_from(list.as_obj(), env).unwrap() }))
+        });
+        group.bench_with_input(BenchmarkId::new("PackedList", len), &list, |b, list| {
+            b.iter(|| in_frame(env, || -> PackedList<i32> { TryFromJavaValue::try_from(list.as_obj(), env).unwrap() }))
+        });
+    }
+    group.finish();
+}
+
+fn lists(c: &mut Criterion) {
+    // A process can only create one JVM
+    let vm = VmBuilder::new().build().unwrap();
+    let env = vm.attach_current_thread().unwrap();
+
+    to_java(c, &env);
+    from_java(c, &env);
+}
+
+criterion_group!(benches, lists);
+criterion_main!(benches);
@@ -0,0 +1,44 @@
+//! Compare conversions of a `Vec<i32>` to a new `java.util.ArrayList` with conversions reusing lists through
+//! `pool::reuse_lists`.
+//!
+//! Run with:
+//! ```text
+//! cargo bench -p robusta-example --bench pool
+//! ```
+//!
+//! The JVM library must be found by the dynamic loader, e.g. with `LD_LIBRARY_PATH=$JAVA_HOME/lib/server` on Linux.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use robusta_jni::convert::pool::reuse_lists;
+use robusta_jni::convert::TryIntoJavaValue;
+use robusta_jni::embed::VmBuilder;
+use robusta_jni::jni::objects::JObject;
+use robusta_jni::jni::JNIEnv;
+
+/// Run `f` in a local frame, so that local references created by the call don't pile up.
+fn in_frame<R>(env: &JNIEnv, f: impl FnOnce() -> R) -> R {
+    env.push_local_frame(4).unwrap();
+    let result = f();
+    env.pop_local_frame(JObject::null()).unwrap();
+    result
+}
+
+fn lists(c: &mut Criterion) {
+    let vm = VmBuilder::new().build().unwrap();
+    let env = vm.attach_current_thread().unwrap();
+
+    let mut group = c.benchmark_group("Vec<i32> to ArrayList");
+    for len in [16, 1024] {
+        let values: Vec<i32> = (0..len).collect();
+        group.bench_with_input(BenchmarkId::new("new list", len), &values, |b, values| {
+            b.iter(|| in_frame(&env, || TryIntoJavaValue::try_into(values.clone(), &env).unwrap()))
+        });
+        group.bench_with_input(BenchmarkId::new("reused list", len), &values, |b, values| {
+            b.iter(|| in_frame(&env, || reuse_lists(|| TryIntoJavaValue::try_into(values.clone(), &env).unwrap())))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, lists);
+criterion_main!(benches);
@@ -0,0 +1,75 @@
+//! Compare calls to a static Java method through `JNIEnv::call_static_method`, which looks up the method (and the class,
+//! if given by name) on each call, with calls through an imported method, which caches both.
+//!
+//! Run with:
+//! ```text
+//! cargo bench -p robusta-example --bench static_calls
+//! ```
+//!
+//! The JVM library must be found by the dynamic loader, e.g. with `LD_LIBRARY_PATH=$JAVA_HOME/lib/server` on Linux.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use robusta_jni::bridge;
+use robusta_jni::convert::JavaClass;
+use robusta_jni::embed::VmBuilder;
+use robusta_jni::jni::objects::{JObject, JValue};
+use robusta_jni::jni::JNIEnv;
+
+#[bridge]
+mod jdk {
+    use robusta_jni::convert::Signature;
+    use robusta_jni::jni::errors::Result as JniResult;
+    use robusta_jni::jni::JNIEnv;
+
+    #[derive(Signature)]
+    #[package(java.lang)]
+    pub struct Integer;
+
+    impl Integer {
+        pub extern "java" fn max(env: &JNIEnv, a: i32, b: i32) -> JniResult<i32> {}
+    }
+}
+
+use jdk::Integer;
+
+/// Run `f` in a local frame, so that local references created by the call don't pile up.
+fn in_frame<R>(env: &JNIEnv, f: impl FnOnce() -> R) -> R {
+    env.push_local_frame(4).unwrap();
+    let result = f();
+    env.pop_local_frame(JObject::null()).unwrap();
+    result
+}
+
+fn static_calls(c: &mut Criterion) {
+    let vm = VmBuilder::new().build().unwrap();
+    let env = vm.attach_current_thread().unwrap();
+    let args = [JValue::Int(3), JValue::Int(7)];
+
+    let mut group = c.benchmark_group("Integer.max(int, int)");
+    group.bench_function("call_static_method, class by name", |b| {
+        b.iter(|| {
+            in_frame(&env, || {
+                env.call_static_method("java/lang/Integer", "max", "(II)I", &args)
+                    .and_then(|v| v.i())
+                    .unwrap()
+            })
+        })
+    });
+    group.bench_function("call_static_method, cached class", |b| {
+        let class = Integer::class(&env).unwrap();
+        b.iter(|| {
+            in_frame(&env, || {
+                env.call_static_method(class, "max", "(II)I", &args)
+                    .and_then(|v| v.i())
+                    .unwrap()
+            })
+        })
+    });
+    group.bench_function("imported method", |b| {
+        b.iter(|| in_frame(&env, || Integer::max(&env, 3, 7).unwrap()))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, static_calls);
+criterion_main!(benches);
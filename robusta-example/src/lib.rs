@@ -1,6 +1,7 @@
 use robusta_jni::bridge;
 
 #[bridge]
+#[allow(clippy::disallowed_names)]
 mod jni {
     use robusta_jni::convert::{
         Field, IntoJavaValue, Signature, TryFromJavaValue, TryIntoJavaValue,
@@ -52,7 +53,7 @@ mod jni {
 
         pub extern "jni" fn setStringHelloWorld(mut self) -> JniResult<()> {
             println!("[rust]: self.foo: \"{}\"", self.foo.get()?);
-            self.foo.set("hello world".into())?;
+            self.foo.set("hello world".to_string())?;
             Ok(())
         }
     }
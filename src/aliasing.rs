@@ -0,0 +1,128 @@
+//! Debug check for Java objects passed more than once to an exported method.
+//!
+//! When the same Java object is given for two parameters (or for a parameter and `self`), both Rust values refer
+//! to the same instance: updates through one of them (e.g. with [`Field`](crate::convert::Field) handles or
+//! `&mut self` write-back) are silently visible through, or overwritten by, the other.
+//!
+//! Exported methods annotated with `#[check_aliasing]` compare their object parameters with `IsSameObject` before
+//! converting them, and throw an `IllegalArgumentException` if two of them are the same object.
+//! With `#[check_aliasing(warn)]`, a warning is printed to standard error and the call proceeds.
+//! The check is only performed in debug builds (i.e. with `debug_assertions` enabled).
+//!
+//! ```ignore
+//! #[check_aliasing]
+//! pub extern "jni" fn transfer(from: Account<'env, 'borrow>, to: Account<'env, 'borrow>, amount: i64) -> JniResult<()> {
+//!     // ...
+//! }
+//! ```
+//!
+//! Objects of immutable classes (strings, boxed primitives, enum constants and classes) are never reported,
+//! since sharing them is harmless and common (e.g. with interned string literals).
+
+use jni::errors::Result as JniResult;
+use jni::objects::JObject;
+use jni::JNIEnv;
+
+const ILLEGAL_ARGUMENT_EXCEPTION_CLASS: &str = "java/lang/IllegalArgumentException";
+
+/// Classes whose instances can be shared between parameters without consequences.
+const IMMUTABLE_CLASSES: &[&str] = &[
+    "java/lang/String",
+    "java/lang/Boolean",
+    "java/lang/Character",
+    "java/lang/Byte",
+    "java/lang/Short",
+    "java/lang/Integer",
+    "java/lang/Long",
+    "java/lang/Float",
+    "java/lang/Double",
+    "java/lang/Enum",
+    "java/lang/Class",
+];
+
+/// Action taken when aliased parameters are found.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AliasingPolicy {
+    /// Print a warning to standard error
+    Warn,
+    /// Throw an `IllegalArgumentException` and skip the call
+    Error,
+}
+
+/// Pairs of parameter names in `params` referring to the same (mutable) Java object.
+///
+/// Parameters with a `None` or null object are ignored.
+pub fn find_aliases<'p, 'env>(
+    env: &JNIEnv<'env>,
+    params: &[(&'p str, Option<JObject<'env>>)],
+) -> JniResult<Vec<(&'p str, &'p str)>> {
+    let mut candidates = Vec::new();
+    for &(name, object) in params {
+        let object = match object {
+            Some(object) if !object.is_null() => object,
+            _ => continue,
+        };
+
+        let mut immutable = false;
+        for class in IMMUTABLE_CLASSES {
+            if env.is_instance_of(object, *class)? {
+                immutable = true;
+                break;
+            }
+        }
+
+        if !immutable {
+            candidates.push((name, object));
+        }
+    }
+
+    let mut aliases = Vec::new();
+    for (idx, &(name, object)) in candidates.iter().enumerate() {
+        for &(other_name, other_object) in &candidates[idx + 1..] {
+            if env.is_same_object(object, other_object)? {
+                aliases.push((name, other_name));
+            }
+        }
+    }
+
+    Ok(aliases)
+}
+
+/// Check the parameters of exported method `method` for aliasing, applying `policy` if any is found.
+///
+/// Returns `false` if the method must not be called, in which case a Java exception is pending.
+/// Failures of the check itself are ignored.
+pub fn check_parameters<'env>(
+    env: &JNIEnv<'env>,
+    method: &str,
+    policy: AliasingPolicy,
+    params: &[(&str, Option<JObject<'env>>)],
+) -> bool {
+    let aliases = match find_aliases(env, params) {
+        Ok(aliases) if !aliases.is_empty() => aliases,
+        _ => return true,
+    };
+
+    let message = format!(
+        "`{}`: {} refer to the same Java object",
+        method,
+        aliases
+            .iter()
+            .map(|(a, b)| format!("parameters `{}` and `{}`", a, b))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    match policy {
+        AliasingPolicy::Warn => {
+            eprintln!("warning: {}", message);
+            true
+        }
+        AliasingPolicy::Error => {
+            if let Err(e) = env.throw_new(ILLEGAL_ARGUMENT_EXCEPTION_CLASS, message) {
+                println!("Error while throwing Java exception: {}", e);
+            }
+            false
+        }
+    }
+}
@@ -0,0 +1,89 @@
+//! Access to a JNI environment from any thread.
+//!
+//! Generated code stores the Java VM process-wide (see [`Vm`]) on the first call of an exported method, and in the
+//! `JNI_OnLoad` function generated with `#[bridge(register_natives)]` or `#[bridge(helper_classes)]`. Threads started
+//! by native code can then get an environment without passing a `JavaVM` around:
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! # #[bridge]
+//! # mod jni {
+//!     # use robusta_jni::convert::Signature;
+//!     # use robusta_jni::jni::errors::Result as JniResult;
+//!     # use robusta_jni::jni::JNIEnv;
+//! #[derive(Signature)]
+//! #[package(com.example)]
+//! struct Worker;
+//!
+//! impl Worker {
+//!     // Java: `static native void start()`
+//!     pub extern "jni" fn start() {
+//!         std::thread::spawn(|| {
+//!             robusta_jni::attach::with_env(|env| {
+//!                 Worker::done(env)
+//!             })
+//!         });
+//!     }
+//!
+//!     pub extern "java" fn done(env: &JNIEnv) -> JniResult<()> {}
+//! }
+//! # }
+//! ```
+//!
+//! [`with_env`] and [`attach`] attach the current thread for a scope: it is detached when the returned guard is dropped,
+//! unless it was already attached. Attaching is costly, so threads that call into Java often (e.g. worker threads of a
+//! pool) should rather use [`with_env_permanently`] or [`attach_permanently`], which leave the thread attached until it
+//! exits.
+//!
+//! All functions fail with [`Error::NullPtr`] if no VM was stored yet, e.g. if the library wasn't called from Java and
+//! has no `JNI_OnLoad` function: the VM can then be stored explicitly with [`Vm::init`] or [`Vm::from_env`].
+
+use jni::errors::{Error, Result as JniResult};
+use jni::{AttachGuard, JNIEnv};
+
+use crate::Vm;
+
+/// The Java VM stored process-wide.
+pub fn vm() -> JniResult<&'static Vm> {
+    Vm::get().ok_or(Error::NullPtr("robusta_jni::attach (no Java VM was stored yet)"))
+}
+
+/// Attach the current thread to the VM, until the returned guard is dropped.
+///
+/// Threads that were already attached are left attached.
+pub fn attach() -> JniResult<AttachGuard<'static>> {
+    vm()?.attach()
+}
+
+/// Attach the current thread to the VM until it exits, and return its environment.
+pub fn attach_permanently() -> JniResult<JNIEnv<'static>> {
+    vm()?.attach_current_thread_permanently()
+}
+
+/// Run `f` with an environment of the current thread, attaching it to the VM for the duration of the call if needed.
+///
+/// Errors of `f` are flattened with those of attaching the thread.
+pub fn with_env<F, R>(f: F) -> JniResult<R>
+where
+    F: FnOnce(&JNIEnv) -> JniResult<R>,
+{
+    let guard = attach()?;
+    f(&guard)
+}
+
+/// Run `f` with an environment of the current thread, which is attached to the VM until it exits.
+///
+/// Errors of `f` are flattened with those of attaching the thread.
+pub fn with_env_permanently<F, R>(f: F) -> JniResult<R>
+where
+    F: FnOnce(&JNIEnv) -> JniResult<R>,
+{
+    let env = attach_permanently()?;
+    f(&env)
+}
+
+/// Store the VM running `env`, if none was stored yet. Called on entry of generated native methods.
+#[doc(hidden)]
+pub fn register(env: &JNIEnv) {
+    let _ = Vm::from_env(env);
+}
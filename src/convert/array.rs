@@ -0,0 +1,112 @@
+use jni::errors::Result as JniResult;
+use jni::JNIEnv;
+
+use crate::convert::{
+    ArrayElement, FromJavaValue, IntoJavaValue, Signature, TryFromJavaValue, TryIntoJavaValue,
+};
+
+/// Wrapper around a [`Vec<T>`] that is converted to and from a Java array `T[]`, instead of a `java.util.ArrayList`.
+///
+/// Conversions are the ones of `Box<[T]>`, so `JArray<T>` can be used wherever `Box<[T]>` can (including nested arrays,
+/// e.g. `JArray<JArray<String>>` for `String[][]`), while keeping a growable vector on the Rust side.
+///
+/// Example:
+/// ```rust
+/// # use robusta_jni::bridge;
+/// #
+/// # #[bridge]
+/// # mod jni {
+///     # use robusta_jni::convert::{JArray, Signature};
+///     # #[derive(Signature)]
+///     # #[package(com.example)]
+///     # struct Names;
+/// impl Names {
+///     pub extern "jni" fn withDefault(mut names: JArray<String>) -> JArray<String> {
+///         names.0.push("default".to_string());
+///         names
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct JArray<T>(pub Vec<T>);
+
+impl<T> JArray<T> {
+    /// Unwrap into the inner [`Vec`].
+    pub fn into_vec(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<T> From<Vec<T>> for JArray<T> {
+    fn from(v: Vec<T>) -> Self {
+        JArray(v)
+    }
+}
+
+impl<T> From<JArray<T>> for Vec<T> {
+    fn from(a: JArray<T>) -> Self {
+        a.0
+    }
+}
+
+impl<T> Signature for JArray<T>
+where
+    Box<[T]>: Signature,
+{
+    const SIG_TYPE: &'static str = <Box<[T]> as Signature>::SIG_TYPE;
+}
+
+impl<T> ArrayElement for JArray<T>
+where
+    Box<[T]>: ArrayElement,
+{
+    const ARRAY_SIG_TYPE: &'static str = <Box<[T]> as ArrayElement>::ARRAY_SIG_TYPE;
+    const NESTED_ARRAY_SIG_TYPES: &'static [&'static str] = <Box<[T]> as ArrayElement>::NESTED_ARRAY_SIG_TYPES;
+}
+
+impl<'env, T> TryIntoJavaValue<'env> for JArray<T>
+where
+    Box<[T]>: TryIntoJavaValue<'env>,
+{
+    type Target = <Box<[T]> as TryIntoJavaValue<'env>>::Target;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+        TryIntoJavaValue::try_into(self.0.into_boxed_slice(), env)
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T> TryFromJavaValue<'env, 'borrow> for JArray<T>
+where
+    Box<[T]>: TryFromJavaValue<'env, 'borrow>,
+{
+    type Source = <Box<[T]> as TryFromJavaValue<'env, 'borrow>>::Source;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+        let array: Box<[T]> = TryFromJavaValue::try_from(s, env)?;
+        Ok(JArray(array.into_vec()))
+    }
+}
+
+impl<'env, T> IntoJavaValue<'env> for JArray<T>
+where
+    Box<[T]>: IntoJavaValue<'env>,
+{
+    type Target = <Box<[T]> as IntoJavaValue<'env>>::Target;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        IntoJavaValue::into(self.0.into_boxed_slice(), env)
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T> FromJavaValue<'env, 'borrow> for JArray<T>
+where
+    Box<[T]>: FromJavaValue<'env, 'borrow>,
+{
+    type Source = <Box<[T]> as FromJavaValue<'env, 'borrow>>::Source;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        let array: Box<[T]> = FromJavaValue::from(s, env);
+        JArray(array.into_vec())
+    }
+}
@@ -0,0 +1,156 @@
+//! Conversions between 128-bit Rust integers and `java.math.BigInteger`.
+//!
+//! | **Rust** | **Java**               |
+//! |----------|------------------------|
+//! | `i128`   | `java.math.BigInteger` |
+//! | `u128`   | `java.math.BigInteger` |
+//!
+//! Values are exchanged as big-endian byte arrays (`BigInteger(byte[])` / `BigInteger.toByteArray()`), so no precision is
+//! lost. `BigInteger` values that don't fit the Rust type (e.g. a negative value converted to `u128`) fail conversion.
+//!
+//! Example:
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! # #[bridge]
+//! # mod jni {
+//!     # use robusta_jni::convert::Signature;
+//!     # use robusta_jni::jni::errors::Result as JniResult;
+//! #[derive(Signature)]
+//! #[package(com.example)]
+//! struct Ids;
+//!
+//! impl Ids {
+//!     // Java: `static native BigInteger next(BigInteger id)`
+//!     pub extern "jni" fn next(id: u128) -> JniResult<u128> {
+//!         Ok(id.wrapping_add(1))
+//!     }
+//! }
+//! # }
+//! ```
+
+use std::convert::TryFrom;
+
+use jni::errors::{Error, Result as JniResult};
+use jni::objects::{JObject, JValue};
+use jni::JNIEnv;
+
+use crate::convert::{FromJavaValue, IntoJavaValue, Signature, TryFromJavaValue, TryIntoJavaValue};
+
+const BIG_INTEGER_CLASS: &str = "java/math/BigInteger";
+
+/// Create a `java.math.BigInteger` from its big-endian two's complement representation.
+fn new_big_integer<'env>(env: &JNIEnv<'env>, bytes: &[u8]) -> JniResult<JObject<'env>> {
+    let array = unsafe { JObject::from_raw(env.byte_array_from_slice(bytes)?) };
+    let value = env.new_object(BIG_INTEGER_CLASS, "([B)V", &[JValue::Object(array)]);
+    env.delete_local_ref(array)?;
+    value
+}
+
+/// Big-endian two's complement representation of a `java.math.BigInteger`, in the minimum number of bytes.
+fn big_integer_bytes(env: &JNIEnv, value: JObject) -> JniResult<Vec<u8>> {
+    let array = env.call_method(value, "toByteArray", "()[B", &[])?.l()?;
+    let bytes = env.convert_byte_array(array.into_raw())?;
+    env.delete_local_ref(array)?;
+    Ok(bytes)
+}
+
+/// Sign-extend big-endian two's complement `bytes` to 17 bytes, the size needed to represent any `i128` or `u128`.
+///
+/// Returns `None` if `bytes` doesn't fit.
+fn sign_extend(bytes: &[u8]) -> Option<[u8; 17]> {
+    if bytes.len() > 17 {
+        return None;
+    }
+
+    let negative = bytes.first().is_some_and(|b| b & 0x80 != 0);
+    let mut extended = [if negative { 0xff } else { 0 }; 17];
+    extended[17 - bytes.len()..].copy_from_slice(bytes);
+    Some(extended)
+}
+
+fn to_i128(bytes: &[u8]) -> Option<i128> {
+    let extended = sign_extend(bytes)?;
+    let value = i128::from_be_bytes(<[u8; 16]>::try_from(&extended[1..]).unwrap());
+    // The extra byte must only repeat the sign bit
+    let sign = if value < 0 { 0xff } else { 0 };
+    (extended[0] == sign).then_some(value)
+}
+
+fn to_u128(bytes: &[u8]) -> Option<u128> {
+    let extended = sign_extend(bytes)?;
+    (extended[0] == 0).then(|| u128::from_be_bytes(<[u8; 16]>::try_from(&extended[1..]).unwrap()))
+}
+
+macro_rules! unchecked_via_safe {
+    ($type:ty) => {
+        impl<'env> IntoJavaValue<'env> for $type {
+            type Target = JObject<'env>;
+            const NEW_LOCAL_REF: bool = true;
+
+            fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+                TryIntoJavaValue::try_into(self, env).unwrap()
+            }
+        }
+
+        impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for $type {
+            type Source = JObject<'env>;
+            const KEEPS_SOURCE_REF: bool = false;
+
+            fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+                TryFromJavaValue::try_from(s, env).unwrap()
+            }
+        }
+    };
+}
+
+impl Signature for i128 {
+    const SIG_TYPE: &'static str = "Ljava/math/BigInteger;";
+}
+
+impl<'env> TryIntoJavaValue<'env> for i128 {
+    type Target = JObject<'env>;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+        new_big_integer(env, &self.to_be_bytes())
+    }
+}
+
+impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for i128 {
+    type Source = JObject<'env>;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+        to_i128(&big_integer_bytes(env, s)?).ok_or(Error::WrongJValueType("i128", "java.math.BigInteger"))
+    }
+}
+
+unchecked_via_safe!(i128);
+
+impl Signature for u128 {
+    const SIG_TYPE: &'static str = "Ljava/math/BigInteger;";
+}
+
+impl<'env> TryIntoJavaValue<'env> for u128 {
+    type Target = JObject<'env>;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+        // Leading zero byte, so that values with the high bit set aren't negative
+        let mut bytes = [0; 17];
+        bytes[1..].copy_from_slice(&self.to_be_bytes());
+        new_big_integer(env, &bytes)
+    }
+}
+
+impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for u128 {
+    type Source = JObject<'env>;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+        to_u128(&big_integer_bytes(env, s)?).ok_or(Error::WrongJValueType("u128", "java.math.BigInteger"))
+    }
+}
+
+unchecked_via_safe!(u128);
@@ -0,0 +1,159 @@
+use jni::errors::Result as JniResult;
+use jni::objects::{JObject, JValue};
+use jni::sys::jlong;
+use jni::JNIEnv;
+
+use crate::convert::metrics;
+use crate::convert::{FromJavaValue, IntoJavaValue, Signature, TryFromJavaValue, TryIntoJavaValue};
+
+const BITSET_CLASS: &str = "java/util/BitSet";
+
+/// Wrapper around a boolean vector that is converted to and from a `java.util.BitSet`.
+///
+/// Bits are packed into the `long[]` backing the `BitSet`, so transfers are 64 times smaller than with `boolean[]`
+/// (i.e. `Box<[bool]>`), which matters for large masks.
+///
+/// Note that a `BitSet` doesn't store its length: vectors converted from Java end at the highest set bit
+/// (see `BitSet.length()`), so trailing `false` values are not preserved in a round trip.
+///
+/// With the `bitvec` feature, `JBitSet` can also be converted from and into a `bitvec::vec::BitVec<u64, Lsb0>`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct JBitSet(pub Box<[bool]>);
+
+impl JBitSet {
+    /// Unwrap into the inner boolean slice.
+    pub fn into_inner(self) -> Box<[bool]> {
+        self.0
+    }
+
+    /// Pack bits in 64-bit words, least significant bit first (the layout of `BitSet.valueOf(long[])`).
+    fn to_words(&self) -> Vec<jlong> {
+        self.0
+            .chunks(64)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0u64, |word, (i, &bit)| word | ((bit as u64) << i)) as jlong
+            })
+            .collect()
+    }
+
+    /// Unpack the first `len` bits of `words`, least significant bit first.
+    fn from_words(words: &[jlong], len: usize) -> Self {
+        JBitSet(
+            (0..len)
+                .map(|i| (words[i / 64] as u64 >> (i % 64)) & 1 == 1)
+                .collect(),
+        )
+    }
+}
+
+impl From<Box<[bool]>> for JBitSet {
+    fn from(bits: Box<[bool]>) -> Self {
+        JBitSet(bits)
+    }
+}
+
+impl From<Vec<bool>> for JBitSet {
+    fn from(bits: Vec<bool>) -> Self {
+        JBitSet(bits.into_boxed_slice())
+    }
+}
+
+impl From<JBitSet> for Box<[bool]> {
+    fn from(bits: JBitSet) -> Self {
+        bits.0
+    }
+}
+
+impl From<JBitSet> for Vec<bool> {
+    fn from(bits: JBitSet) -> Self {
+        bits.0.into_vec()
+    }
+}
+
+impl Signature for JBitSet {
+    const SIG_TYPE: &'static str = "Ljava/util/BitSet;";
+}
+
+impl<'env> TryIntoJavaValue<'env> for JBitSet {
+    type Target = JObject<'env>;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+        let words = self.to_words();
+        let array = env.new_long_array(words.len() as i32)?;
+        env.set_long_array_region(array, 0, &words)?;
+        metrics::record_to_java(
+            <Self as Signature>::SIG_TYPE,
+            self.0.len(),
+            words.len() * std::mem::size_of::<jlong>(),
+        );
+
+        env.call_static_method(
+            BITSET_CLASS,
+            "valueOf",
+            "([J)Ljava/util/BitSet;",
+            &[JValue::Object(unsafe { JObject::from_raw(array) })],
+        )?
+        .l()
+    }
+}
+
+impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for JBitSet {
+    type Source = JObject<'env>;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+        let len = env.call_method(s, "length", "()I", &[])?.i()? as usize;
+        let array = env.call_method(s, "toLongArray", "()[J", &[])?.l()?.into_raw();
+        let mut words = vec![0; env.get_array_length(array)? as usize];
+        env.get_long_array_region(array, 0, &mut words)?;
+        metrics::record_from_java(
+            <Self as Signature>::SIG_TYPE,
+            len,
+            words.len() * std::mem::size_of::<jlong>(),
+        );
+
+        Ok(JBitSet::from_words(&words, len))
+    }
+}
+
+impl<'env> IntoJavaValue<'env> for JBitSet {
+    type Target = JObject<'env>;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        TryIntoJavaValue::try_into(self, env).unwrap()
+    }
+}
+
+impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for JBitSet {
+    type Source = JObject<'env>;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        TryFromJavaValue::try_from(s, env).unwrap()
+    }
+}
+
+#[cfg(feature = "bitvec")]
+mod bitvec_conversions {
+    use bitvec::order::Lsb0;
+    use bitvec::vec::BitVec;
+
+    use super::JBitSet;
+
+    impl From<BitVec<u64, Lsb0>> for JBitSet {
+        fn from(bits: BitVec<u64, Lsb0>) -> Self {
+            JBitSet(bits.iter().by_vals().collect())
+        }
+    }
+
+    impl From<JBitSet> for BitVec<u64, Lsb0> {
+        fn from(bits: JBitSet) -> Self {
+            bits.0.iter().copied().collect()
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use std::slice;
+
+use jni::errors::{Error, Result as JniResult};
+use jni::objects::{JByteBuffer, JObject, JValue};
+use jni::JNIEnv;
+
+use crate::convert::{
+    FromJavaValue, IntoJavaValue, Signature, TryFromJavaValue, TryIntoJavaValue,
+};
+
+/// A direct `java.nio.ByteBuffer`, whose memory can be accessed from Rust without copying.
+///
+/// The backing memory is obtained via `GetDirectBufferAddress`, so conversion fails for non-direct (heap) buffers.
+///
+/// Note that the memory is shared with the JVM: writes from Java code are visible through [`as_slice`](DirectByteBuffer::as_slice)
+/// and vice versa. Converting a buffer only looks up its address and capacity, so accessing the memory is `unsafe`: it
+/// may be written by Java code meanwhile, or may have been freed if the buffer was created with `NewDirectByteBuffer`
+/// over memory managed by another native library.
+pub struct DirectByteBuffer<'env> {
+    buffer: JByteBuffer<'env>,
+    data: *mut u8,
+    len: usize,
+}
+
+impl<'env> DirectByteBuffer<'env> {
+    /// Wrap an existing direct `ByteBuffer`.
+    ///
+    /// Fails for non-direct buffers.
+    pub fn from_buffer(env: &JNIEnv<'env>, buffer: JByteBuffer<'env>) -> JniResult<Self> {
+        let data = env.get_direct_buffer_address(buffer)?;
+        let len = env.get_direct_buffer_capacity(buffer)?;
+
+        Ok(DirectByteBuffer { buffer, data, len })
+    }
+
+    /// Expose Rust memory to Java as a direct `ByteBuffer`, without copying.
+    ///
+    /// # Safety
+    /// The JVM may keep references to the buffer after `'env` ends (e.g. by storing it in a field), when `data` may be
+    /// freed: Java code must not access the buffer after `'env` ends.
+    pub unsafe fn new(env: &JNIEnv<'env>, data: &'env mut [u8]) -> JniResult<Self> {
+        let len = data.len();
+        let data = data.as_mut_ptr();
+        let buffer = unsafe { env.new_direct_byte_buffer(data, len) }?;
+
+        Ok(DirectByteBuffer { buffer, data, len })
+    }
+
+    /// Allocate a new direct `ByteBuffer` of the given capacity, with memory owned by the JVM.
+    ///
+    /// Fails with a pending `IllegalArgumentException` if `capacity` doesn't fit in a Java `int`.
+    pub fn allocate(env: &JNIEnv<'env>, capacity: usize) -> JniResult<Self> {
+        let capacity: i32 = match std::convert::TryFrom::try_from(capacity) {
+            Ok(capacity) => capacity,
+            Err(_) => {
+                env.throw_new(
+                    "java/lang/IllegalArgumentException",
+                    format!("direct buffer capacity {} exceeds the maximum of {}", capacity, i32::MAX),
+                )?;
+                return Err(Error::JavaException);
+            }
+        };
+        let buffer = env
+            .call_static_method(
+                "java/nio/ByteBuffer",
+                "allocateDirect",
+                "(I)Ljava/nio/ByteBuffer;",
+                &[JValue::Int(capacity)],
+            )?
+            .l()?;
+
+        DirectByteBuffer::from_buffer(env, JByteBuffer::from(buffer))
+    }
+
+    /// Allocate a new direct `ByteBuffer` with memory owned by the JVM, and copy `data` into it.
+    pub fn from_slice(env: &JNIEnv<'env>, data: &[u8]) -> JniResult<Self> {
+        let mut buffer = DirectByteBuffer::allocate(env, data.len())?;
+        // The buffer was just allocated by the JVM, and isn't reachable from other Java code yet
+        unsafe { buffer.as_mut_slice() }.copy_from_slice(data);
+
+        Ok(buffer)
+    }
+
+    /// Underlying `ByteBuffer` object.
+    pub fn buffer(&self) -> JByteBuffer<'env> {
+        self.buffer
+    }
+
+    /// Buffer capacity in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The buffer memory.
+    ///
+    /// # Safety
+    /// The memory must still be allocated. This holds for buffers allocated by the JVM (e.g. with
+    /// `ByteBuffer.allocateDirect`), which are kept alive by the reference, but not for buffers created with
+    /// `NewDirectByteBuffer` over memory that may have been freed (e.g. by another native library).
+    ///
+    /// The memory must not be written by Java code (e.g. by another thread holding the `ByteBuffer`) while the returned
+    /// slice is alive, as that would be a data race.
+    pub unsafe fn as_slice(&self) -> &[u8] {
+        if self.len == 0 {
+            return &[];
+        }
+
+        unsafe { slice::from_raw_parts(self.data, self.len) }
+    }
+
+    /// The buffer memory, for writing.
+    ///
+    /// # Safety
+    /// Same as [`as_slice`](DirectByteBuffer::as_slice), and the memory must not be read by Java code either while the
+    /// returned slice is alive.
+    pub unsafe fn as_mut_slice(&mut self) -> &mut [u8] {
+        if self.len == 0 {
+            return &mut [];
+        }
+
+        unsafe { slice::from_raw_parts_mut(self.data, self.len) }
+    }
+
+    /// Copy the buffer contents into a new `Vec`.
+    ///
+    /// # Safety
+    /// Same as [`as_slice`](DirectByteBuffer::as_slice).
+    pub unsafe fn to_vec(&self) -> Vec<u8> {
+        unsafe { self.as_slice() }.to_vec()
+    }
+}
+
+impl<'env> Signature for DirectByteBuffer<'env> {
+    const SIG_TYPE: &'static str = "Ljava/nio/ByteBuffer;";
+}
+
+impl<'env> TryIntoJavaValue<'env> for DirectByteBuffer<'env> {
+    type Target = JObject<'env>;
+
+    fn try_into(self, _env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+        Ok(self.buffer.into())
+    }
+}
+
+impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for DirectByteBuffer<'env> {
+    type Source = JObject<'env>;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+        DirectByteBuffer::from_buffer(env, JByteBuffer::from(s))
+    }
+}
+
+impl<'env> IntoJavaValue<'env> for DirectByteBuffer<'env> {
+    type Target = JObject<'env>;
+
+    fn into(self, _env: &JNIEnv<'env>) -> Self::Target {
+        self.buffer.into()
+    }
+}
+
+impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for DirectByteBuffer<'env> {
+    type Source = JObject<'env>;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        DirectByteBuffer::from_buffer(env, JByteBuffer::from(s)).unwrap()
+    }
+}
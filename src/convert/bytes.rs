@@ -0,0 +1,88 @@
+//! Conversions between [`Bytes`] and Java `byte[]` (behind the `bytes` feature).
+//!
+//! Byte arrays are copied into owned buffers, like `Vec<u8>` (which `Bytes` takes ownership of without another copy),
+//! with bytes reinterpreted as unsigned. `Vec<u8>`, `Box<[u8]>` and `&[u8]` are converted to `byte[]` without this
+//! feature, so `Bytes` is only needed to share buffers with other code using it.
+//!
+//! Example:
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! # #[bridge]
+//! # mod jni {
+//!     # use robusta_jni::convert::Signature;
+//!     # use bytes::Bytes;
+//! #[derive(Signature)]
+//! #[package(com.example)]
+//! struct Codec;
+//!
+//! impl Codec {
+//!     // Java: `static native byte[] reversed(byte[] data)`
+//!     pub extern "jni" fn reversed(data: Bytes) -> Bytes {
+//!         let mut buf = data.to_vec();
+//!         buf.reverse();
+//!         Bytes::from(buf)
+//!     }
+
+//!     // Java: `static native byte[] prefixed(byte[] data)`
+//!     pub extern "jni" fn prefixed(data: Vec<u8>) -> Vec<u8> {
+//!         let mut buf = vec![data.len() as u8];
+//!         buf.extend(data);
+//!         buf
+//!     }
+//! }
+//! # }
+//! ```
+
+use ::bytes::Bytes;
+use jni::errors::Result as JniResult;
+use jni::sys::jbyteArray;
+use jni::JNIEnv;
+
+use crate::convert::{ArrayElement, FromJavaValue, IntoJavaValue, Signature, TryFromJavaValue, TryIntoJavaValue};
+
+impl Signature for Bytes {
+    const SIG_TYPE: &'static str = "[B";
+}
+
+impl ArrayElement for Bytes {
+    const ARRAY_SIG_TYPE: &'static str = "[[B";
+    const NESTED_ARRAY_SIG_TYPES: &'static [&'static str] = &["[[[B"];
+}
+
+impl<'env> TryIntoJavaValue<'env> for Bytes {
+    type Target = jbyteArray;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+        TryIntoJavaValue::try_into(self.as_ref(), env)
+    }
+}
+
+/// The copied array is moved into the returned buffer, without another copy.
+impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for Bytes {
+    type Source = jbyteArray;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+        <Vec<u8> as TryFromJavaValue>::try_from(s, env).map(<Bytes as From<Vec<u8>>>::from)
+    }
+}
+
+impl<'env> IntoJavaValue<'env> for Bytes {
+    type Target = jbyteArray;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        IntoJavaValue::into(self.as_ref(), env)
+    }
+}
+
+impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for Bytes {
+    type Source = jbyteArray;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        <Bytes as From<Vec<u8>>>::from(<Vec<u8> as FromJavaValue>::from(s, env))
+    }
+}
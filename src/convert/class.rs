@@ -0,0 +1,239 @@
+use std::sync::{Arc, Mutex, RwLock};
+
+use jni::errors::{Error, Result as JniResult};
+use jni::objects::{GlobalRef, JClass, JObject, JStaticMethodID, JValue};
+use jni::signature::TypeSignature;
+use jni::sys::jvalue;
+use jni::JNIEnv;
+
+pub use robusta_codegen::JavaClass;
+
+/// Types mapped to a Java class, with a cached reference to the class.
+///
+/// Looking up a class with `FindClass` on every call is comparatively slow, and from threads attached by native code
+/// only finds classes of the system class loader. The class is instead looked up once, on the first call to [`class`](JavaClass::class),
+/// and kept alive in a global reference afterwards. The lookup uses the class loader registered with
+/// [`set_class_loader`](crate::loader::set_class_loader), if any.
+///
+/// This trait is implemented by the [`Signature`](crate::convert::Signature) and `JavaClass` derive macros, and by `#[bridge]` for bridged structs
+/// that don't derive `Signature`. Code generated for imported constructors, static methods and `#[field]` fields uses it
+/// instead of looking up the class by name.
+///
+/// Example:
+/// ```rust
+/// # use robusta_jni::bridge;
+/// #
+/// # #[bridge]
+/// # mod jni {
+///     # use robusta_jni::convert::{JavaClass, Signature};
+///     # use robusta_jni::jni::errors::Result as JniResult;
+///     # use robusta_jni::jni::JNIEnv;
+/// #[derive(Signature)]
+/// #[package(com.example)]
+/// pub struct Counter;
+///
+/// impl<'env> Counter {
+///     pub extern "jni" fn isCounter(env: &JNIEnv, obj: ::robusta_jni::jni::objects::JObject<'env>) -> JniResult<bool> {
+///         env.is_instance_of(obj, Counter::class(env)?)
+///     }
+/// }
+/// # }
+/// ```
+pub trait JavaClass {
+    /// Class path of the class in JNI format (e.g. `com/example/Counter`)
+    const CLASS_PATH: &'static str;
+
+    /// Cache of the class reference, usually a `static` declared by the implementation.
+    fn class_cache() -> &'static ClassCache;
+
+    /// New local reference to the class, looked up on first use.
+    ///
+    /// The reference stays valid even if [`unload::release_caches`](crate::unload::release_caches) is called
+    /// meanwhile, and can be deleted once it's no longer used (e.g. with `JNIEnv::auto_local`).
+    fn class<'env>(env: &JNIEnv<'env>) -> JniResult<JClass<'env>> {
+        Self::class_cache().get(env, Self::CLASS_PATH)
+    }
+}
+
+/// Global reference to a class, initialized once.
+///
+/// Cached classes are released by [`unload::release_caches`](crate::unload::release_caches), and looked up again on
+/// next use.
+pub struct ClassCache {
+    class: RwLock<Option<GlobalRef>>,
+}
+
+/// Caches holding a class, to be released when the library is unloaded.
+static CACHED: Mutex<Vec<&'static ClassCache>> = Mutex::new(Vec::new());
+
+impl ClassCache {
+    pub const fn new() -> Self {
+        ClassCache {
+            class: RwLock::new(None),
+        }
+    }
+
+    /// New local reference to class `class_path`, looked up with `env` if it wasn't cached yet.
+    pub fn get<'env>(&'static self, env: &JNIEnv<'env>, class_path: &str) -> JniResult<JClass<'env>> {
+        if let Some(class) = &*self.class.read().unwrap() {
+            return local_class(env, class);
+        }
+
+        let class = crate::loader::find_class(env, class_path)?;
+        let global = env.new_global_ref(class)?;
+
+        // Another thread may have cached the class in the meantime, in which case `global` is just dropped
+        let mut cached = self.class.write().unwrap();
+        if cached.is_none() {
+            *cached = Some(global);
+            CACHED.lock().unwrap().push(self);
+        }
+
+        Ok(class)
+    }
+
+    /// Whether the class was already looked up.
+    pub fn is_cached(&self) -> bool {
+        self.class.read().unwrap().is_some()
+    }
+
+    /// Release the cached class, if any.
+    pub fn clear(&self) {
+        self.class.write().unwrap().take();
+    }
+}
+
+/// New local reference to the class of global reference `class`, which stays valid once the lock guarding it is
+/// released, even if the cache is cleared meanwhile.
+fn local_class<'env>(env: &JNIEnv<'env>, class: &GlobalRef) -> JniResult<JClass<'env>> {
+    env.new_local_ref::<JObject>(class.as_obj()).map(JClass::from)
+}
+
+/// Release the classes of all caches, and the static methods cached along with them.
+pub(crate) fn clear_class_caches() {
+    for cache in CACHED.lock().unwrap().drain(..) {
+        cache.clear();
+    }
+    for cache in CACHED_METHODS.lock().unwrap().drain(..) {
+        cache.clear();
+    }
+}
+
+impl Default for ClassCache {
+    fn default() -> Self {
+        ClassCache::new()
+    }
+}
+
+/// ID of a static method, looked up once along with a global reference to its class.
+///
+/// Code generated for imported static methods calls them through a cache of their own, so that each call doesn't look
+/// up the class and the method with `FindClass` and `GetStaticMethodID`. The global reference keeps the class from
+/// being unloaded, which would invalidate the method ID. Cached methods are released by
+/// [`unload::release_caches`](crate::unload::release_caches) along with cached classes, and looked up again on next
+/// use.
+pub struct StaticMethodCache {
+    method: RwLock<Option<Arc<StaticMethod>>>,
+}
+
+struct StaticMethod {
+    class: GlobalRef,
+    id: JStaticMethodID,
+    signature: TypeSignature,
+}
+
+impl StaticMethod {
+    /// Static method `name` of `class` with signature `sig`.
+    fn resolve(env: &JNIEnv, class: JClass, name: &str, sig: &str) -> JniResult<Self> {
+        Ok(StaticMethod {
+            class: env.new_global_ref(class)?,
+            id: env.get_static_method_id(class, name, sig)?,
+            signature: TypeSignature::from_str(sig)?,
+        })
+    }
+
+    /// Call the method with `args`.
+    fn call<'env>(&self, env: &JNIEnv<'env>, args: &[JValue]) -> JniResult<JValue<'env>> {
+        if self.signature.args.len() != args.len() {
+            return Err(Error::InvalidArgList(self.signature.clone()));
+        }
+        let args: Vec<jvalue> = args.iter().map(|a| a.to_jni()).collect();
+
+        // `self` holds the global reference until the call returns, even if the cache is cleared meanwhile
+        let class = JClass::from(unsafe { JObject::from_raw(self.class.as_obj().into_raw()) });
+        env.call_static_method_unchecked(class, self.id, self.signature.ret.clone(), &args)
+    }
+}
+
+/// Caches holding a static method, to be released when the library is unloaded.
+static CACHED_METHODS: Mutex<Vec<&'static StaticMethodCache>> = Mutex::new(Vec::new());
+
+impl StaticMethodCache {
+    pub const fn new() -> Self {
+        StaticMethodCache {
+            method: RwLock::new(None),
+        }
+    }
+
+    /// Call static method `name` with `args`.
+    ///
+    /// Its class and signature are only computed, by calling `class` and `sig`, when the method isn't cached yet. `class`
+    /// must return a new local reference, which is deleted once the method is cached.
+    pub fn call<'env, 'c, C, S>(
+        &'static self,
+        env: &JNIEnv<'env>,
+        class: C,
+        name: &str,
+        sig: S,
+        args: &[JValue],
+    ) -> JniResult<JValue<'env>>
+    where
+        C: FnOnce() -> JniResult<JClass<'c>>,
+        S: FnOnce() -> String,
+    {
+        // The method is shared out of the lock, so that the cache can be cleared while it runs
+        let cached = self.method.read().unwrap().clone();
+        let method = match cached {
+            Some(method) => method,
+            None => self.lookup(env, class, name, sig)?,
+        };
+
+        method.call(env, args)
+    }
+
+    fn lookup<'c, C, S>(&'static self, env: &JNIEnv, class: C, name: &str, sig: S) -> JniResult<Arc<StaticMethod>>
+    where
+        C: FnOnce() -> JniResult<JClass<'c>>,
+        S: FnOnce() -> String,
+    {
+        let class = class()?;
+        let method = StaticMethod::resolve(env, class, name, &sig());
+        env.delete_local_ref(class.into())?;
+        let method = Arc::new(method?);
+
+        // Another thread may have cached the method in the meantime, in which case `method` is just dropped
+        let mut cached = self.method.write().unwrap();
+        if cached.is_none() {
+            *cached = Some(method);
+            CACHED_METHODS.lock().unwrap().push(self);
+        }
+
+        Ok(cached.as_ref().unwrap().clone())
+    }
+
+    /// Whether the method was already looked up.
+    pub fn is_cached(&self) -> bool {
+        self.method.read().unwrap().is_some()
+    }
+
+    /// Release the cached method and its class, if any.
+    pub fn clear(&self) {
+        self.method.write().unwrap().take();
+    }
+}
+
+impl Default for StaticMethodCache {
+    fn default() -> Self {
+        StaticMethodCache::new()
+    }
+}
@@ -0,0 +1,154 @@
+//! Custom conversions of single parameters and return values.
+//!
+//! Some types can't implement the conversion traits: the orphan rule forbids implementing them for types of other
+//! crates, and a type can only have one Java representation. A converter is a type implementing [`Converter`] and
+//! [`ConvertFromJava`] and/or [`ConvertIntoJava`] for the Rust type. Parameters marked with
+//! `#[convert(with = "...")]` are then converted with the given converter instead of the conversion traits of their type,
+//! and methods marked with `#[convert(with = "...")]` convert their return value with it (the value inside the `Result`
+//! for methods returning one). This works for both `extern "jni"` and `extern "java"` methods, with any `call_type`:
+//! with `#[call_type(unchecked)]` a failed conversion panics.
+//!
+//! Converters can't be declared in the bridged module, which only holds Java classes. Example, with two Java
+//! representations of [`Ipv4Addr`](std::net::Ipv4Addr):
+//! ```rust
+//! use std::net::Ipv4Addr;
+//!
+//! use robusta_jni::bridge;
+//! use robusta_jni::convert::{ConvertFromJava, ConvertIntoJava, Converter, TryFromJavaValue};
+//! use robusta_jni::jni::errors::{Error, Result as JniResult};
+//! use robusta_jni::jni::objects::JString;
+//! use robusta_jni::jni::sys::jint;
+//! use robusta_jni::jni::JNIEnv;
+//!
+//! /// `Ipv4Addr` as a dotted string, e.g. `"127.0.0.1"`
+//! pub struct DottedIp;
+//!
+//! impl Converter for DottedIp {
+//!     const SIG_TYPE: &'static str = "Ljava/lang/String;";
+//! }
+//!
+//! impl<'env: 'borrow, 'borrow> ConvertFromJava<'env, 'borrow, Ipv4Addr> for DottedIp {
+//!     type Source = JString<'env>;
+//!
+//!     fn from_java(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Ipv4Addr> {
+//!         let s: String = TryFromJavaValue::try_from(s, env)?;
+//!         s.parse().map_err(|_| Error::WrongJValueType("Ipv4Addr", "dotted IPv4 address"))
+//!     }
+//! }
+//!
+//! /// `Ipv4Addr` as a big-endian `int`
+//! pub struct IntIp;
+//!
+//! impl Converter for IntIp {
+//!     const SIG_TYPE: &'static str = "I";
+//! }
+//!
+//! impl<'env> ConvertIntoJava<'env, Ipv4Addr> for IntIp {
+//!     type Target = jint;
+//!
+//!     fn into_java(value: Ipv4Addr, _env: &JNIEnv<'env>) -> JniResult<jint> {
+//!         Ok(u32::from(value) as jint)
+//!     }
+//! }
+//!
+//! #[bridge]
+//! mod jni {
+//!     # use std::net::Ipv4Addr;
+//!     # use robusta_jni::convert::Signature;
+//!     use super::{DottedIp, IntIp};
+//!
+//!     #[derive(Signature)]
+//!     #[package(com.example)]
+//!     struct Network;
+//!
+//!     impl Network {
+//!         // Java: `static native int parseIp(String ip)`
+//!         #[convert(with = "IntIp")]
+//!         pub extern "jni" fn parseIp(#[convert(with = "DottedIp")] ip: Ipv4Addr) -> Ipv4Addr {
+//!             ip
+//!         }
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+
+use std::marker::PhantomData;
+
+use jni::errors::Result as JniResult;
+use jni::JNIEnv;
+
+use crate::convert::{FromJavaValue, IntoJavaValue, JniValue, Signature, TryFromJavaValue, TryIntoJavaValue};
+
+/// Converter of values to and from Java, used with `#[convert(with = "...")]` (see the [module documentation](self)).
+pub trait Converter {
+    /// [Signature](https://docs.oracle.com/en/java/javase/15/docs/specs/jni/types.html#type-signatures) of the Java representation.
+    const SIG_TYPE: &'static str;
+}
+
+/// Conversion of Java values to values of type `T` with a [`Converter`].
+pub trait ConvertFromJava<'env: 'borrow, 'borrow, T>: Converter {
+    /// Conversion source type.
+    type Source: JniValue<'env>;
+
+    /// Perform the conversion.
+    fn from_java(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<T>;
+}
+
+/// Conversion of values of type `T` to Java values with a [`Converter`].
+pub trait ConvertIntoJava<'env, T>: Converter {
+    /// Conversion target type.
+    type Target: JniValue<'env>;
+
+    /// Perform the conversion.
+    fn into_java(value: T, env: &JNIEnv<'env>) -> JniResult<Self::Target>;
+}
+
+/// Value of type `T` converted with converter `C`, implementing the conversion traits for generated code.
+#[doc(hidden)]
+pub struct Converted<C, T>(pub T, PhantomData<C>);
+
+impl<C, T> Converted<C, T> {
+    pub fn new(value: T) -> Self {
+        Converted(value, PhantomData)
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<C: Converter, T> Signature for Converted<C, T> {
+    const SIG_TYPE: &'static str = C::SIG_TYPE;
+}
+
+impl<'env, C: ConvertIntoJava<'env, T>, T> TryIntoJavaValue<'env> for Converted<C, T> {
+    type Target = C::Target;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+        C::into_java(self.0, env)
+    }
+}
+
+impl<'env: 'borrow, 'borrow, C: ConvertFromJava<'env, 'borrow, T>, T> TryFromJavaValue<'env, 'borrow> for Converted<C, T> {
+    type Source = C::Source;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+        C::from_java(s, env).map(Converted::new)
+    }
+}
+
+impl<'env, C: ConvertIntoJava<'env, T>, T> IntoJavaValue<'env> for Converted<C, T> {
+    type Target = C::Target;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        C::into_java(self.0, env).unwrap()
+    }
+}
+
+impl<'env: 'borrow, 'borrow, C: ConvertFromJava<'env, 'borrow, T>, T> FromJavaValue<'env, 'borrow> for Converted<C, T> {
+    type Source = C::Source;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        Converted::new(C::from_java(s, env).unwrap())
+    }
+}
@@ -0,0 +1,196 @@
+//! Access to primitive arrays without copying, with `GetPrimitiveArrayCritical`.
+//!
+//! Converting a Java `int[]` to a `Box<[i32]>` copies its elements. Parameters of exported methods typed as slices of
+//! primitives (`&[i32]`, `&mut [u8]`, ...) and marked with `#[critical]` instead borrow the memory of the Java array,
+//! obtained with `GetPrimitiveArrayCritical`. Changes made through `&mut` slices are visible from Java after the call.
+//!
+//! No JNI function may be called between `GetPrimitiveArrayCritical` and `ReleasePrimitiveArrayCritical`, and the JVM
+//! may block the garbage collector in the meantime. The generated glue code converts all other parameters first, then
+//! looks up the lengths of all `#[critical]` arrays, and only then borrows their elements, so that methods can take
+//! several `#[critical]` parameters. The arrays are released as soon as the method returns, before its return value
+//! is converted. For the method body,
+//! the restriction is enforced by its shape: methods with `#[critical]` parameters can't take a `JNIEnv`, the class
+//! of static methods, the `Vm` or closures (which call into Java). The method must not call into Java in any other
+//! way (e.g. through a `JavaVM`), and should return quickly. Arrays borrowed as `&[T]` are released without copying
+//! their elements back.
+//!
+//! Example:
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! # #[bridge]
+//! # mod jni {
+//!     # use robusta_jni::convert::Signature;
+//!     # #[derive(Signature)]
+//!     # #[package(com.example)]
+//!     # struct Pixels;
+//! impl Pixels {
+//!     // Java: `static native void invert(byte[] pixels)`
+//!     pub extern "jni" fn invert(#[critical] pixels: &mut [u8]) {
+//!         for p in pixels.iter_mut() {
+//!             *p = !*p;
+//!         }
+//!     }
+//!
+//!     // Java: `static native long sum(int[] values)`
+//!     pub extern "jni" fn sum(#[critical] values: &[i32]) -> i64 {
+//!         values.iter().map(|&v| v as i64).sum()
+//!     }
+//! }
+//! # }
+//! ```
+
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::slice;
+
+use jni::errors::{Error, Result as JniResult};
+use jni::objects::{AutoPrimitiveArray, JObject, ReleaseMode};
+use jni::JNIEnv;
+
+use crate::convert::{FromJavaValue, Signature, TryFromJavaValue};
+
+/// Primitive types whose Java arrays can be accessed with [`Critical`].
+pub trait CriticalElement: Copy {
+    /// Signature of the Java array type, e.g. `[I` for `i32`
+    const ARRAY_SIG_TYPE: &'static str;
+}
+
+macro_rules! critical_elements {
+    ($($type:ty: $sig:literal),+) => {
+        $(
+            impl CriticalElement for $type {
+                const ARRAY_SIG_TYPE: &'static str = $sig;
+            }
+        )+
+    };
+}
+
+critical_elements! {
+    i8: "[B",
+    u8: "[B",
+    i16: "[S",
+    u16: "[C",
+    i32: "[I",
+    i64: "[J",
+    f32: "[F",
+    f64: "[D"
+}
+
+/// Elements of a Java primitive array, borrowed with `GetPrimitiveArrayCritical` from [`enter`](Critical::enter)
+/// until dropped.
+///
+/// This is the type `#[critical]` parameters are converted from, see the [module documentation](self).
+/// No JNI function may be called once a `Critical` is entered, including creating another `Critical`: create all of
+/// them first, then enter them.
+pub struct Critical<'env: 'borrow, 'borrow, T> {
+    env: &'borrow JNIEnv<'env>,
+    array: JObject<'env>,
+    len: usize,
+    elements: Option<AutoPrimitiveArray<'borrow, 'borrow>>,
+    element: PhantomData<T>,
+}
+
+impl<'env: 'borrow, 'borrow, T: CriticalElement> Critical<'env, 'borrow, T> {
+    /// Prepare a critical region for `array`, looking up its length.
+    ///
+    /// Fails if `array` isn't a Java array of `T` (e.g. an `int[]` for `i32`), whose elements would otherwise be read
+    /// out of bounds.
+    pub fn new(env: &'borrow JNIEnv<'env>, array: JObject<'env>) -> JniResult<Self> {
+        if !env.is_instance_of(array, T::ARRAY_SIG_TYPE)? {
+            return Err(Error::WrongJValueType(T::ARRAY_SIG_TYPE, "array of another type"));
+        }
+        // The length can't be queried once in the critical region
+        let len = env.get_array_length(array.into_raw())? as usize;
+
+        Ok(Critical {
+            env,
+            array,
+            len,
+            elements: None,
+            element: PhantomData,
+        })
+    }
+
+    /// Enter the critical region, borrowing the elements of the array. Does nothing if it was already entered.
+    ///
+    /// With [`ReleaseMode::NoCopyBack`], changes made through [`as_mut_slice`](Critical::as_mut_slice) may be lost
+    /// when the region is left, if the JVM handed out a copy of the elements.
+    pub fn enter(&mut self, mode: ReleaseMode) -> JniResult<()> {
+        if self.elements.is_none() {
+            self.elements = Some(self.env.get_primitive_array_critical(self.array.into_raw(), mode)?);
+        }
+
+        Ok(())
+    }
+
+    fn elements_ptr(&self) -> *mut T {
+        self.elements
+            .as_ref()
+            .expect("critical region of a Java array accessed before `Critical::enter`")
+            .as_ptr() as *mut T
+    }
+
+    /// Elements of the array.
+    ///
+    /// # Panics
+    ///
+    /// If the critical region wasn't [entered](Critical::enter).
+    pub fn as_slice(&self) -> &[T] {
+        let ptr = self.elements_ptr();
+        if self.len == 0 {
+            return &[];
+        }
+
+        unsafe { slice::from_raw_parts(ptr, self.len) }
+    }
+
+    /// Mutable elements of the array, copied back to it when the critical region is left with
+    /// [`ReleaseMode::CopyBack`].
+    ///
+    /// # Panics
+    ///
+    /// If the critical region wasn't [entered](Critical::enter).
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        let ptr = self.elements_ptr();
+        if self.len == 0 {
+            return &mut [];
+        }
+
+        unsafe { slice::from_raw_parts_mut(ptr, self.len) }
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T: CriticalElement> Deref for Critical<'env, 'borrow, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T: CriticalElement> DerefMut for Critical<'env, 'borrow, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.as_mut_slice()
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T: CriticalElement> Signature for Critical<'env, 'borrow, T> {
+    const SIG_TYPE: &'static str = T::ARRAY_SIG_TYPE;
+}
+
+impl<'env: 'borrow, 'borrow, T: CriticalElement> TryFromJavaValue<'env, 'borrow> for Critical<'env, 'borrow, T> {
+    type Source = JObject<'env>;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+        Critical::new(env, s)
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T: CriticalElement> FromJavaValue<'env, 'borrow> for Critical<'env, 'borrow, T> {
+    type Source = JObject<'env>;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        Critical::new(env, s).unwrap()
+    }
+}
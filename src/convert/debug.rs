@@ -0,0 +1,92 @@
+//! Descriptions of Java objects for debugging output.
+//!
+//! [`describe`] formats an object as `<class name>@<identity hash code>{<toString()>}`, for example
+//! `com.example.User@1b6d3586{User(alice)}`, and can be called anywhere native code has a [`JNIEnv`]:
+//! a pending Java exception is set aside while the object is described and thrown again afterwards,
+//! and exceptions thrown while describing the object (e.g. by a faulty `toString()`) are replaced by a placeholder.
+//!
+//! Structs deriving [`TryFromJavaValue`](crate::convert::TryFromJavaValue) get a `describe(env)` method for their
+//! underlying object, which can be used to implement [`Debug`](std::fmt::Debug) by hand or to log an instance:
+//! ```ignore
+//! log::debug!("created {}", user.describe(env));
+//! ```
+
+use jni::errors::Result as JniResult;
+use jni::objects::{JObject, JString, JValue};
+use jni::JNIEnv;
+
+/// Binary name of the class of `obj` (e.g. `com.example.Outer$Inner`), as returned by `Class.getName()`.
+pub fn class_name(env: &JNIEnv, obj: JObject) -> JniResult<String> {
+    let class = env.auto_local(env.get_object_class(obj)?);
+    let name = env.auto_local(env.call_method(class.as_obj(), "getName", "()Ljava/lang/String;", &[])?.l()?);
+
+    Ok(env.get_string(JString::from(name.as_obj()))?.into())
+}
+
+/// Description of `obj` in the format `com.example.User@1b6d3586{toString()}`, or `null` for null references.
+///
+/// This function never fails, and leaves the exception state of `env` as it was: see the [module documentation](self).
+pub fn describe(env: &JNIEnv, obj: JObject) -> String {
+    if obj.is_null() {
+        return "null".to_string();
+    }
+
+    // No other JNI function can be called while an exception is pending
+    let pending = match env.exception_occurred() {
+        Ok(exception) if !exception.is_null() => {
+            let _ = env.exception_clear();
+            Some(exception)
+        }
+        _ => None,
+    };
+
+    let class_name = or_placeholder(env, "getClass().getName()", class_name(env, obj));
+    let hash_code = or_placeholder(env, "identityHashCode()", identity_hash_code(env, obj));
+    let string = or_placeholder(env, "toString()", to_string(env, obj));
+
+    if let Some(exception) = pending {
+        let _ = env.throw(exception);
+        let _ = env.delete_local_ref(exception.into());
+    }
+
+    format!("{}@{}{{{}}}", class_name, hash_code, string)
+}
+
+/// `System.identityHashCode(obj)` in hexadecimal, as used by `Object.toString()`.
+fn identity_hash_code(env: &JNIEnv, obj: JObject) -> JniResult<String> {
+    let hash_code = env
+        .call_static_method("java/lang/System", "identityHashCode", "(Ljava/lang/Object;)I", &[JValue::Object(obj)])?
+        .i()?;
+
+    Ok(format!("{:x}", hash_code))
+}
+
+fn to_string(env: &JNIEnv, obj: JObject) -> JniResult<String> {
+    let string = env.auto_local(env.call_method(obj, "toString", "()Ljava/lang/String;", &[])?.l()?);
+    if string.as_obj().is_null() {
+        return Ok("null".to_string());
+    }
+
+    Ok(env.get_string(JString::from(string.as_obj()))?.into())
+}
+
+/// Value of `result`, or a placeholder naming the exception thrown by `what` (which is cleared).
+fn or_placeholder(env: &JNIEnv, what: &str, result: JniResult<String>) -> String {
+    if let Ok(value) = result {
+        return value;
+    }
+
+    match env.exception_occurred() {
+        Ok(exception) if !exception.is_null() => {
+            let _ = env.exception_clear();
+            let exception_class = class_name(env, exception.into()).unwrap_or_else(|_| {
+                let _ = env.exception_clear();
+                "an exception".to_string()
+            });
+            let _ = env.delete_local_ref(exception.into());
+
+            format!("<{} threw {}>", what, exception_class)
+        }
+        _ => format!("<{} failed>", what),
+    }
+}
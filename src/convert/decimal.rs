@@ -0,0 +1,91 @@
+//! Conversions between [`rust_decimal::Decimal`] and `java.math.BigDecimal` (behind the `rust_decimal` feature).
+//!
+//! Values are exchanged through their decimal string representation (`new BigDecimal(String)` /
+//! `BigDecimal.toPlainString()`), never through `f64`, so both the digits and the scale are kept: `1.50` stays `1.50`.
+//! `BigDecimal` values that don't fit in a `Decimal` (more than 28 significant digits or fractional digits) fail
+//! conversion instead of being rounded.
+//!
+//! Example:
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! # #[bridge]
+//! # mod jni {
+//!     # use robusta_jni::convert::Signature;
+//!     # use robusta_jni::jni::errors::Result as JniResult;
+//!     # use rust_decimal::Decimal;
+//! #[derive(Signature)]
+//! #[package(com.example)]
+//! struct Invoice;
+//!
+//! impl Invoice {
+//!     // Java: `static native BigDecimal total(BigDecimal price, int quantity)`
+//!     pub extern "jni" fn total(price: Decimal, quantity: i32) -> JniResult<Decimal> {
+//!         Ok(price * Decimal::from(quantity))
+//!     }
+//! }
+//! # }
+//! ```
+
+use jni::errors::{Error, Result as JniResult};
+use jni::objects::{JObject, JString, JValue};
+use jni::JNIEnv;
+use rust_decimal::Decimal;
+
+use crate::convert::{FromJavaValue, IntoJavaValue, Signature, TryFromJavaValue, TryIntoJavaValue};
+
+const BIG_DECIMAL_CLASS: &str = "java/math/BigDecimal";
+
+impl Signature for Decimal {
+    const SIG_TYPE: &'static str = "Ljava/math/BigDecimal;";
+}
+
+impl<'env> TryIntoJavaValue<'env> for Decimal {
+    type Target = JObject<'env>;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+        let digits = env.new_string(self.to_string())?;
+        let value = env.new_object(
+            BIG_DECIMAL_CLASS,
+            "(Ljava/lang/String;)V",
+            &[JValue::Object(*digits)],
+        );
+        env.delete_local_ref(*digits)?;
+        value
+    }
+}
+
+impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for Decimal {
+    type Source = JObject<'env>;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+        let digits = env
+            .call_method(s, "toPlainString", "()Ljava/lang/String;", &[])?
+            .l()?;
+        let plain = <String as TryFromJavaValue>::try_from(<JString as From<JObject>>::from(digits), env)?;
+        env.delete_local_ref(digits)?;
+
+        Decimal::from_str_exact(&plain)
+            .map_err(|_| Error::WrongJValueType("rust_decimal::Decimal", "java.math.BigDecimal"))
+    }
+}
+
+impl<'env> IntoJavaValue<'env> for Decimal {
+    type Target = JObject<'env>;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        TryIntoJavaValue::try_into(self, env).unwrap()
+    }
+}
+
+impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for Decimal {
+    type Source = JObject<'env>;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        TryFromJavaValue::try_from(s, env).unwrap()
+    }
+}
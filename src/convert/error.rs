@@ -0,0 +1,190 @@
+//! Errors raised by the glue code of exported methods.
+//!
+//! Safe exported methods convert their parameters with [`TryFromJavaValue`](crate::convert::TryFromJavaValue). When a
+//! conversion fails, the thrown exception mentions which parameter couldn't be converted, e.g.
+//! `JNI call error!. Cause: can't convert parameter 1 (`name`) from Java type `Ljava/lang/String;` to Rust type `String`: ...`
+//!
+//! Safe exported methods may return errors of their own type, as a `Result<T, E>` where `E` implements
+//! [`std::error::Error`]. They are thrown with the message of the error, e.g. `JNI call error!. Cause: invalid config`.
+//!
+//! The thrown exception also keeps the [source](std::error::Error::source) chain of the error: each level is wrapped in
+//! an exception of the `cause_class` of `#[call_type(safe)]` (`java.lang.RuntimeException` by default), set as the
+//! cause of the level above, so that Java stack traces show the whole chain. A Java exception pending when the error
+//! was returned (e.g. thrown by a Java method called by the native method) is rethrown unchanged instead.
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+use jni::errors::{Error as JniError, Result as JniResult};
+use jni::objects::{JClass, JObject, JThrowable, JValue};
+use jni::JNIEnv;
+
+use crate::loader::find_class;
+
+/// Error of the glue code of a safe exported method.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ConversionError {
+    /// A parameter couldn't be converted from its Java value
+    Parameter {
+        /// Position of the parameter in the Rust signature, not counting `JNIEnv` and `JClass` parameters
+        /// (`self` is parameter 0 of methods)
+        index: usize,
+        /// Name of the parameter (`self` for the receiver)
+        name: &'static str,
+        /// Rust type of the parameter
+        rust_type: &'static str,
+        /// Java type of the parameter, as a type descriptor
+        java_type: &'static str,
+        /// The underlying error
+        source: JniError,
+    },
+    /// Any other JNI error, e.g. raised by the method itself or when converting its return value
+    Jni(JniError),
+    /// An error of another type returned by the method
+    Method(Box<dyn Error + 'static>),
+}
+
+impl ConversionError {
+    /// Error for a parameter whose conversion failed with `source`.
+    pub fn parameter(
+        index: usize,
+        name: &'static str,
+        rust_type: &'static str,
+        java_type: &'static str,
+        source: JniError,
+    ) -> Self {
+        ConversionError::Parameter {
+            index,
+            name,
+            rust_type,
+            java_type,
+            source,
+        }
+    }
+
+    /// Error `error` returned by the method. JNI errors are kept as [`ConversionError::Jni`].
+    pub fn method<E: Error + 'static>(error: E) -> Self {
+        let error: Box<dyn Error + 'static> = Box::new(error);
+        match error.downcast::<JniError>() {
+            Ok(e) => ConversionError::Jni(*e),
+            Err(e) => ConversionError::Method(e),
+        }
+    }
+
+    /// The underlying JNI error, unless the method returned an error of another type.
+    pub fn jni_error(&self) -> Option<&JniError> {
+        match self {
+            ConversionError::Parameter { source, .. } => Some(source),
+            ConversionError::Jni(e) => Some(e),
+            ConversionError::Method(_) => None,
+        }
+    }
+
+    /// Unwrap into the underlying JNI error, dropping the context, unless the method returned an error of another type.
+    pub fn into_jni_error(self) -> Option<JniError> {
+        match self {
+            ConversionError::Parameter { source, .. } => Some(source),
+            ConversionError::Jni(e) => Some(e),
+            ConversionError::Method(_) => None,
+        }
+    }
+}
+
+impl Display for ConversionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ConversionError::Parameter {
+                index,
+                name,
+                rust_type,
+                java_type,
+                source,
+            } => write!(
+                f,
+                "can't convert parameter {} (`{}`) from Java type `{}` to Rust type `{}`: {}",
+                index, name, java_type, rust_type, source
+            ),
+            ConversionError::Jni(e) => write!(f, "{}", e),
+            ConversionError::Method(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for ConversionError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConversionError::Parameter { source, .. } => Some(source),
+            // Displayed as the error itself
+            ConversionError::Jni(e) => e.source(),
+            ConversionError::Method(e) => e.source(),
+        }
+    }
+}
+
+impl From<JniError> for ConversionError {
+    fn from(e: JniError) -> Self {
+        ConversionError::Jni(e)
+    }
+}
+
+/// Throw a new exception of class `class_path` (in JNI format) with message `message`, caused by the source chain of
+/// `error`. Each level of the chain is wrapped in an exception of class `cause_class_path`.
+///
+/// Nothing is thrown if a Java exception is already pending.
+///
+/// Exception classes are looked up with [`find_class`], and must have a constructor taking the message.
+pub fn throw_chained(
+    env: &JNIEnv,
+    class_path: &str,
+    message: &str,
+    error: &dyn Error,
+    cause_class_path: &str,
+) -> JniResult<()> {
+    if env.exception_check()? {
+        return Ok(());
+    }
+
+    let mut sources = Vec::new();
+    let mut source = error.source();
+    while let Some(e) = source {
+        sources.push(e.to_string());
+        source = e.source();
+    }
+
+    let mut cause = JObject::null();
+    if !sources.is_empty() {
+        let cause_class = find_class(env, cause_class_path)?;
+        for source in sources.iter().rev() {
+            cause = new_exception(env, cause_class, source, cause)?;
+        }
+    }
+
+    let class = find_class(env, class_path)?;
+    let exception = new_exception(env, class, message, cause)?;
+    env.throw(JThrowable::from(exception))
+}
+
+/// New exception of class `class` with message `message` and cause `cause`, unless it's null.
+fn new_exception<'env>(
+    env: &JNIEnv<'env>,
+    class: JClass<'env>,
+    message: &str,
+    cause: JObject<'env>,
+) -> JniResult<JObject<'env>> {
+    let message = env.new_string(message)?;
+    let exception = env.new_object(class, "(Ljava/lang/String;)V", &[JValue::Object(*message)])?;
+    env.delete_local_ref(*message)?;
+
+    if !cause.is_null() {
+        env.call_method(
+            exception,
+            "initCause",
+            "(Ljava/lang/Throwable;)Ljava/lang/Throwable;",
+            &[JValue::Object(cause)],
+        )?;
+        env.delete_local_ref(cause)?;
+    }
+
+    Ok(exception)
+}
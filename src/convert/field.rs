@@ -1,31 +1,157 @@
 use std::marker::PhantomData;
 use std::str::FromStr;
 
-use std::convert::{TryFrom, TryInto};
-
 use jni::errors::Error as JniError;
 use jni::errors::Result as JniResult;
-use jni::objects::{JFieldID, JObject};
-use jni::signature::ReturnType;
+use jni::descriptors::Desc;
+use jni::objects::{JClass, JFieldID, JMethodID, JObject};
+use jni::signature::{Primitive, ReturnType};
 use jni::JNIEnv;
 
 use crate::convert::{
-    FromJavaValue, IntoJavaValue, JValueWrapper, JavaValue, Signature, TryFromJavaValue,
-    TryIntoJavaValue,
+    FromJavaValue, IntoJavaValue, JavaValue, JniValue, Signature, TryFromJavaValue, TryIntoJavaValue,
 };
 use crate::jni::objects::JValue;
 
+/// Handle to a field of a Java object, to read and write it directly.
+///
+/// Fields of derived structs marked with `#[field]` hold a handle to the Java field with the same name, looked up when
+/// the struct is converted. In a `#[bridge]` module, fields marked with `#[field(lazy)]` are replaced by accessor methods
+/// instead, which look up the field only when called:
+/// ```rust
+/// # use robusta_jni::bridge;
+/// #
+/// # #[bridge]
+/// # mod jni {
+///     # use robusta_jni::convert::{Field, Signature, TryFromJavaValue};
+///     # use robusta_jni::jni::errors::Result as JniResult;
+///     # use robusta_jni::jni::objects::AutoLocal;
+///     # use robusta_jni::jni::JNIEnv;
+/// #[derive(Signature, TryFromJavaValue)]
+/// #[package()]
+/// struct Counter<'env: 'borrow, 'borrow> {
+///     #[instance]
+///     raw: AutoLocal<'env, 'borrow>,
+///     // becomes `fn count(&self, env) -> JniResult<Field<i32>>`
+///     #[field(lazy)]
+///     count: i32,
+/// }
+///
+/// impl<'env: 'borrow, 'borrow> Counter<'env, 'borrow> {
+///     pub extern "jni" fn increment(self, env: &'borrow JNIEnv<'env>) -> JniResult<()> {
+///         let mut count = self.count(env)?;
+///         count.set(count.get()? + 1)
+///     }
+/// }
+/// # }
+/// ```
+///
+/// The value type `T` can be any type implementing the conversion traits, e.g. `Vec<String>` for an
+/// `ArrayList<String>` field, or `Box<[T]>` for an array of bridged structs.
+///
+/// Java classes that only expose their state through accessor methods can be bridged with
+/// `#[field(getter = "getCount", setter = "setCount")]`: [`get`](Field::get) and [`set`](Field::set) then call the
+/// accessors instead of reading and writing the field. Either accessor can be left out for read-only or write-only
+/// properties, in which case the corresponding method fails with `Error::MethodNotFound`. On fields that aren't `Field`
+/// handles, the attribute makes the derived conversions read the value with the getter (and write it back with the
+/// setter, for `#[writeback]` fields):
+/// ```rust
+/// # use robusta_jni::bridge;
+/// #
+/// # #[bridge]
+/// # mod jni {
+///     # use robusta_jni::convert::{Field, Signature, TryFromJavaValue};
+///     # use robusta_jni::jni::objects::AutoLocal;
+/// #[derive(Signature, TryFromJavaValue)]
+/// #[package(com.example)]
+/// struct Account<'env: 'borrow, 'borrow> {
+///     #[instance]
+///     raw: AutoLocal<'env, 'borrow>,
+///     // read with `getOwner()` during the conversion
+///     #[field(getter = "getOwner")]
+///     owner: String,
+///     // `get` calls `getBalance()`, `set` calls `setBalance(long)`
+///     #[field(getter = "getBalance", setter = "setBalance")]
+///     balance: Field<'env, 'borrow, i64>,
+/// }
+/// # }
+/// ```
 #[derive(Clone)]
 pub struct Field<'env: 'borrow, 'borrow, T>
 where
     T: Signature,
 {
     env: &'borrow JNIEnv<'env>,
-    field_id: JFieldID,
+    access: Access,
     obj: JObject<'env>,
     marker: PhantomData<T>,
 }
 
+/// How the value of a [`Field`] is read and written
+#[derive(Clone, Copy)]
+enum Access {
+    /// Directly, through the Java field
+    Field(JFieldID),
+    /// Through accessor methods
+    Accessors {
+        getter: Option<JMethodID>,
+        setter: Option<JMethodID>,
+    },
+}
+
+impl<'env: 'borrow, 'borrow, T> Field<'env, 'borrow, T>
+where
+    T: Signature,
+{
+    fn read(&self) -> JniResult<JValue<'env>> {
+        let ty = ReturnType::from_str(<T as Signature>::SIG_TYPE).unwrap();
+
+        match self.access {
+            Access::Field(field_id) => self.env.get_field_unchecked(self.obj, field_id, ty),
+            Access::Accessors { getter: Some(getter), .. } => self.env.call_method_unchecked(self.obj, getter, ty, &[]),
+            Access::Accessors { getter: None, .. } => Err(JniError::MethodNotFound {
+                name: "getter".to_string(),
+                sig: format!("(){}", <T as Signature>::SIG_TYPE),
+            }),
+        }
+    }
+
+    fn write(&self, value: JValue) -> JniResult<()> {
+        match self.access {
+            Access::Field(field_id) => self.env.set_field_unchecked(self.obj, field_id, value),
+            Access::Accessors { setter: Some(setter), .. } => self
+                .env
+                .call_method_unchecked(self.obj, setter, ReturnType::Primitive(Primitive::Void), &[value.to_jni()])
+                .map(drop),
+            Access::Accessors { setter: None, .. } => Err(JniError::MethodNotFound {
+                name: "setter".to_string(),
+                sig: format!("({})V", <T as Signature>::SIG_TYPE),
+            }),
+        }
+    }
+
+    /// Look up the accessor methods `getter` (returning `T`) and `setter` (taking `T`) of `class`.
+    fn lookup_accessors<'c, C>(
+        class: C,
+        getter: Option<&str>,
+        setter: Option<&str>,
+        env: &'borrow JNIEnv<'env>,
+    ) -> JniResult<Access>
+    where
+        C: Desc<'env, JClass<'c>>,
+    {
+        let class = class.lookup(env)?;
+        let getter = getter
+            .map(|getter| env.get_method_id(class, getter, format!("(){}", <T as Signature>::SIG_TYPE)))
+            .transpose()?;
+        let setter = setter
+            .map(|setter| env.get_method_id(class, setter, format!("({})V", <T as Signature>::SIG_TYPE)))
+            .transpose()?;
+
+        Ok(Access::Accessors { getter, setter })
+    }
+}
+
 impl<'env: 'borrow, 'borrow, T> Field<'env, 'borrow, T>
 where
     T: Signature,
@@ -42,7 +168,7 @@ where
 
         Some(Field {
             env,
-            field_id,
+            access: Access::Field(field_id),
             obj,
             marker: Default::default(),
         })
@@ -52,27 +178,18 @@ where
 impl<'env: 'borrow, 'borrow, T> Field<'env, 'borrow, T>
 where
     T: Signature + TryIntoJavaValue<'env> + TryFromJavaValue<'env, 'borrow>,
-    <T as TryFromJavaValue<'env, 'borrow>>::Source: TryFrom<JValueWrapper<'env>, Error = JniError>,
-    JValue<'env>: From<<T as TryIntoJavaValue<'env>>::Target>,
 {
     pub fn set(&mut self, value: T) -> JniResult<()> {
         let v = TryIntoJavaValue::try_into(value, self.env)?;
-        let jvalue: JValue = JValue::from(v);
+        let jvalue = JniValue::into_jvalue(v, self.env);
 
-        self.env
-            .set_field_unchecked(self.obj, self.field_id, jvalue)?;
-        Ok(())
+        self.write(jvalue)
     }
 
     pub fn get(&self) -> JniResult<T> {
-        let res: JValue = self.env.get_field_unchecked(
-            self.obj,
-            self.field_id,
-            ReturnType::from_str(<T as Signature>::SIG_TYPE).unwrap(),
-        )?;
-
-        let f = JValueWrapper::from(res);
-        TryInto::try_into(f).and_then(|v| TryFromJavaValue::try_from(v, &self.env))
+        let res: JValue = self.read()?;
+
+        JniValue::from_jvalue(res, self.env).and_then(|v| TryFromJavaValue::try_from(v, self.env))
     }
 
     // Java object is not sufficient to retrieve parent object / field owner
@@ -81,18 +198,40 @@ where
     // A nicer solution would be to have a `const CLASS_PATH: &str` and a `const FIELD_NAME: &str` const parameters and use those instead,
     // but full const generics are required for that.
     // FIXME: use const generics to parametrize `Field` by class path and field name, and implement `(Try)FromJavaValue`
-    pub fn field_try_from(
+    pub fn field_try_from<'c, C>(
         source: JObject<'env>,
-        classpath_path: &str,
+        class: C,
         field_name: &str,
         env: &'borrow JNIEnv<'env>,
-    ) -> JniResult<Self> {
-        let class = env.find_class(classpath_path)?;
+    ) -> JniResult<Self>
+    where
+        C: Desc<'env, JClass<'c>>,
+    {
+        let class = class.lookup(env)?;
         let field_id = env.get_field_id(class, field_name, <T as Signature>::SIG_TYPE)?;
 
         Ok(Self {
             env,
-            field_id,
+            access: Access::Field(field_id),
+            obj: source.autobox(env),
+            marker: Default::default(),
+        })
+    }
+
+    /// Handle to a property of `source` read with the `getter` method and written with the `setter` method of `class`.
+    pub fn accessors_try_from<'c, C>(
+        source: JObject<'env>,
+        class: C,
+        getter: Option<&str>,
+        setter: Option<&str>,
+        env: &'borrow JNIEnv<'env>,
+    ) -> JniResult<Self>
+    where
+        C: Desc<'env, JClass<'c>>,
+    {
+        Ok(Self {
+            env,
+            access: Self::lookup_accessors(class, getter, setter, env)?,
             obj: source.autobox(env),
             marker: Default::default(),
         })
@@ -102,47 +241,58 @@ where
 impl<'env: 'borrow, 'borrow, T> Field<'env, 'borrow, T>
 where
     T: Signature + IntoJavaValue<'env> + FromJavaValue<'env, 'borrow>,
-    <T as FromJavaValue<'env, 'borrow>>::Source: TryFrom<JValueWrapper<'env>, Error = JniError>,
-    JValue<'env>: From<<T as IntoJavaValue<'env>>::Target>,
 {
     pub fn set_unchecked(&mut self, value: T) {
         let v = IntoJavaValue::into(value, self.env);
-        let jvalue = JValue::from(v);
+        let jvalue = JniValue::into_jvalue(v, self.env);
 
-        self.env
-            .set_field_unchecked(self.obj, self.field_id, jvalue)
-            .unwrap();
+        self.write(jvalue).unwrap();
     }
 
     pub fn get_unchecked(&self) -> T {
-        let res = self
-            .env
-            .get_field_unchecked(
-                self.obj,
-                self.field_id,
-                ReturnType::from_str(<T as Signature>::SIG_TYPE).unwrap(),
-            )
-            .unwrap();
+        let res = self.read().unwrap();
 
-        TryInto::try_into(JValueWrapper::from(res))
-            .map(|v| FromJavaValue::from(v, &self.env))
+        JniValue::from_jvalue(res, self.env)
+            .map(|v| FromJavaValue::from(v, self.env))
             .unwrap()
     }
 
-    pub fn field_from(
+    pub fn field_from<'c, C>(
         source: JObject<'env>,
-        classpath_path: &str,
+        class: C,
         field_name: &str,
         env: &'borrow JNIEnv<'env>,
-    ) -> Self {
-        let class = env.find_class(classpath_path).unwrap();
+    ) -> Self
+    where
+        C: Desc<'env, JClass<'c>>,
+    {
+        let class = class.lookup(env).unwrap();
         let field_id = env
             .get_field_id(class, field_name, <T as Signature>::SIG_TYPE)
             .unwrap();
 
         Self {
             env,
-            field_id,
+            access: Access::Field(field_id),
+            obj: source.autobox(env),
+            marker: Default::default(),
+        }
+    }
+
+    /// Handle to a property of `source` read with the `getter` method and written with the `setter` method of `class`.
+    pub fn accessors_from<'c, C>(
+        source: JObject<'env>,
+        class: C,
+        getter: Option<&str>,
+        setter: Option<&str>,
+        env: &'borrow JNIEnv<'env>,
+    ) -> Self
+    where
+        C: Desc<'env, JClass<'c>>,
+    {
+        Self {
+            env,
+            access: Self::lookup_accessors(class, getter, setter, env).unwrap(),
             obj: source.autobox(env),
             marker: Default::default(),
         }
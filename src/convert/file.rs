@@ -0,0 +1,165 @@
+//! Conversions between Rust paths and Java file types *(`file` feature)*.
+//!
+//! | **Rust**                        | **Java**              |
+//! |---------------------------------|-----------------------|
+//! | [`PathBuf`], [`&Path`](Path)§   | `java.io.File`        |
+//! | [`JPath`]                       | `java.nio.file.Path`  |
+//!
+//! § Only as output type, like other references.
+//!
+//! Java files are created from the string form of the path (`new File(String)` and `Paths.get(String)`), so paths
+//! that aren't valid UTF-8 fail conversion. Converting back uses `File.getAbsolutePath()` (relative files are resolved
+//! against the working directory of the JVM) and `Path.toString()`.
+
+use std::path::{Path, PathBuf};
+
+use jni::errors::{Error, Result as JniResult};
+use jni::objects::{JObject, JString, JValue};
+use jni::JNIEnv;
+
+use crate::convert::{FromJavaValue, IntoJavaValue, Signature, TryFromJavaValue, TryIntoJavaValue};
+
+const FILE_CLASS: &str = "java/io/File";
+const PATHS_CLASS: &str = "java/nio/file/Paths";
+
+/// Wrapper around a [`PathBuf`] that is converted to and from a `java.nio.file.Path` (plain `PathBuf`s are converted
+/// to `java.io.File`).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct JPath(pub PathBuf);
+
+impl JPath {
+    /// Unwrap into the inner path.
+    pub fn into_inner(self) -> PathBuf {
+        self.0
+    }
+}
+
+impl From<PathBuf> for JPath {
+    fn from(path: PathBuf) -> Self {
+        JPath(path)
+    }
+}
+
+impl From<JPath> for PathBuf {
+    fn from(path: JPath) -> Self {
+        path.0
+    }
+}
+
+impl AsRef<Path> for JPath {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+/// UTF-8 form of `path`, as Java strings can't represent arbitrary OS strings.
+fn path_string<'env>(env: &JNIEnv<'env>, path: &Path, java_type: &'static str) -> JniResult<JString<'env>> {
+    let s = path.to_str().ok_or(Error::WrongJValueType(java_type, "Path"))?;
+    env.new_string(s)
+}
+
+/// Rust path of a Java string returned by `method`.
+fn path_from<'env>(env: &JNIEnv<'env>, obj: JObject<'env>, method: &str) -> JniResult<PathBuf> {
+    let s = env.call_method(obj, method, "()Ljava/lang/String;", &[])?.l()?;
+    let s: String = env.get_string(<JString as From<_>>::from(s))?.into();
+
+    Ok(<PathBuf as From<_>>::from(s))
+}
+
+macro_rules! unchecked_via_safe {
+    ($type:ty) => {
+        impl<'env> IntoJavaValue<'env> for $type {
+            type Target = JObject<'env>;
+            const NEW_LOCAL_REF: bool = true;
+
+            fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+                TryIntoJavaValue::try_into(self, env).unwrap()
+            }
+        }
+    };
+    ($type:ty, from) => {
+        unchecked_via_safe!($type);
+
+        impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for $type {
+            type Source = JObject<'env>;
+            const KEEPS_SOURCE_REF: bool = false;
+
+            fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+                TryFromJavaValue::try_from(s, env).unwrap()
+            }
+        }
+    };
+}
+
+impl Signature for PathBuf {
+    const SIG_TYPE: &'static str = "Ljava/io/File;";
+}
+
+impl Signature for &Path {
+    const SIG_TYPE: &'static str = <PathBuf as Signature>::SIG_TYPE;
+}
+
+impl<'env> TryIntoJavaValue<'env> for &Path {
+    type Target = JObject<'env>;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+        let path = path_string(env, self, "java.io.File")?;
+        env.new_object(FILE_CLASS, "(Ljava/lang/String;)V", &[JValue::Object(*path)])
+    }
+}
+
+impl<'env> TryIntoJavaValue<'env> for PathBuf {
+    type Target = JObject<'env>;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+        TryIntoJavaValue::try_into(self.as_path(), env)
+    }
+}
+
+impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for PathBuf {
+    type Source = JObject<'env>;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+        path_from(env, s, "getAbsolutePath")
+    }
+}
+
+unchecked_via_safe!(&Path);
+unchecked_via_safe!(PathBuf, from);
+
+impl Signature for JPath {
+    const SIG_TYPE: &'static str = "Ljava/nio/file/Path;";
+}
+
+impl<'env> TryIntoJavaValue<'env> for JPath {
+    type Target = JObject<'env>;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+        let path = path_string(env, &self.0, "java.nio.file.Path")?;
+        let more = env.new_object_array(0, "java/lang/String", JObject::null())?;
+        let more = unsafe { JObject::from_raw(more) };
+
+        env.call_static_method(
+            PATHS_CLASS,
+            "get",
+            "(Ljava/lang/String;[Ljava/lang/String;)Ljava/nio/file/Path;",
+            &[JValue::Object(*path), JValue::Object(more)],
+        )?
+        .l()
+    }
+}
+
+impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for JPath {
+    type Source = JObject<'env>;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+        path_from(env, s, "toString").map(JPath)
+    }
+}
+
+unchecked_via_safe!(JPath, from);
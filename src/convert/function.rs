@@ -0,0 +1,552 @@
+//! Bridging between Rust closures and Java functional interfaces.
+//!
+//! Parameters of exported methods typed as `impl Fn(...)` (or `FnMut`/`FnOnce`) take a Java object implementing the
+//! matching interface of `java.util.function` (or `java.lang.Runnable`), and call it when invoked:
+//!
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! # #[bridge]
+//! # mod jni {
+//!     # use robusta_jni::convert::Signature;
+//! #[derive(Signature)]
+//! #[package()]
+//! struct Calculator;
+//!
+//! impl Calculator {
+//!     // Java: `static native int applyTwice(IntUnaryOperator f, int x)`
+//!     pub extern "jni" fn applyTwice(f: impl Fn(i32) -> i32, x: i32) -> i32 {
+//!         f(f(x))
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! The interface is chosen from the closure signature:
+//!
+//! | **Rust**                                          | **Java**                                     |
+//! |---------------------------------------------------|----------------------------------------------|
+//! | `Fn()`                                            | `Runnable`                                   |
+//! | `Fn() -> R`                                       | `Supplier<R>`                                |
+//! | `Fn(i32) -> i32`, `Fn(i64) -> i64`, `Fn(f64) -> f64` | `IntUnaryOperator`, `LongUnaryOperator`, `DoubleUnaryOperator` |
+//! | `Fn(i32, i32) -> i32` (and `i64`, `f64`)          | `IntBinaryOperator` (and `Long`, `Double`)   |
+//! | `Fn(i32) -> bool` (and `i64`, `f64`)              | `IntPredicate` (and `Long`, `Double`)        |
+//! | `Fn(i32)` (and `i64`, `f64`)                      | `IntConsumer` (and `Long`, `Double`)         |
+//! | `Fn(T) -> bool`, `Fn(T, U) -> bool`               | `Predicate<T>`, `BiPredicate<T, U>`          |
+//! | `Fn(T)`, `Fn(T, U)`                               | `Consumer<T>`, `BiConsumer<T, U>`            |
+//! | `Fn(T) -> R`, `Fn(T, U) -> R`                     | `Function<T, R>`, `BiFunction<T, U, R>`      |
+//!
+//! Parameters and return values must implement [`FunctionValue`], which is provided for primitives, `String` and `()`.
+//! Any other single-method interface can be used by implementing [`FunctionalInterface`] for a marker type, and taking
+//! a [`JavaFunction`] parameter directly:
+//!
+//! ```rust
+//! # use robusta_jni::bridge;
+//! use robusta_jni::convert::{FunctionalInterface, Signature};
+//!
+//! /// `com.example.Formatter`, with method `String format(int value)`
+//! pub struct Formatter;
+//!
+//! impl Signature for Formatter {
+//!     const SIG_TYPE: &'static str = "Lcom/example/Formatter;";
+//! }
+//!
+//! impl FunctionalInterface for Formatter {
+//!     const METHOD: &'static str = "format";
+//! }
+//!
+//! #[bridge]
+//! mod jni {
+//!     # use robusta_jni::convert::{JavaFunction, Signature};
+//!     # use robusta_jni::jni::errors::Result as JniResult;
+//!     # use super::Formatter;
+//! #[derive(Signature)]
+//! #[package()]
+//! struct Report;
+//!
+//! impl Report {
+//!     pub extern "jni" fn formatAll(formatter: JavaFunction<Formatter, fn(i32) -> String>, count: i32) -> JniResult<String> {
+//!         (0..count).map(|i| formatter.call(i)).collect()
+//!     }
+//! }
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! A [`JavaFunction`] holds a global reference to the Java object, so it can be kept and called from any thread.
+//!
+//! In the other direction, [`RustFunction`] wraps a Rust closure into a Java object implementing a functional interface
+//! (through a `java.lang.reflect.Proxy`), e.g. to return a lambda from an exported method:
+//!
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! # #[bridge]
+//! # mod jni {
+//!     # use robusta_jni::convert::{RustFunction, Signature};
+//!     # use robusta_jni::convert::function::IntUnaryOperator;
+//!     # #[derive(Signature)]
+//!     # #[package()]
+//!     # struct Calculator;
+//! impl Calculator {
+//!     // Java: `static native IntUnaryOperator adder(int n)`
+//!     pub extern "jni" fn adder(n: i32) -> RustFunction<IntUnaryOperator> {
+//!         RustFunction::new(move |x: i32| x + n)
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! This requires the `robusta.RustFunction` class, found in the `java` directory of this crate, to be available to the
+//! JVM (e.g. by adding that directory to the Java sources of the project, or embedded in the library with the
+//! `helper_classes` feature, see [`helpers`](crate::helpers)), and Java 16 or later. The closure is dropped
+//! when the Java object is garbage collected.
+
+use std::any::Any;
+use std::ffi::c_void;
+use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use jni::errors::{Error, Result as JniResult};
+use jni::objects::{GlobalRef, JClass, JObject, JValue};
+use jni::strings::JNIString;
+use jni::sys::{jlong, jobject, jobjectArray};
+use jni::{JNIEnv, JavaVM, NativeMethod};
+
+use crate::convert::{
+    FromJavaValue, IntoJavaValue, JavaValue, JniValue, Signature, TryFromJavaValue, TryIntoJavaValue,
+};
+
+const OBJECT_SIG: &str = "Ljava/lang/Object;";
+const RUST_FUNCTION_CLASS: &str = "robusta/RustFunction";
+
+/// A Java interface with a single abstract method, implemented by [`JavaFunction`] and [`RustFunction`] values.
+///
+/// The [`Signature`] of the implementing type is the signature of the interface.
+pub trait FunctionalInterface: Signature {
+    /// Name of the abstract method.
+    const METHOD: &'static str;
+
+    /// Whether parameters of the method are erased to `Object` (e.g. `T` in `java.util.function.Function<T, R>`),
+    /// so that values are passed boxed.
+    const GENERIC_PARAMETERS: bool = false;
+
+    /// Whether the return value of the method is erased to `Object`, so that values are returned boxed.
+    const GENERIC_RETURN: bool = false;
+}
+
+macro_rules! functional_interfaces {
+    ($($(#[$doc:meta])* $name:ident($class:literal, $method:literal $(, $generic:ident)*);)*) => {
+        $(
+            $(#[$doc])*
+            pub struct $name;
+
+            impl Signature for $name {
+                const SIG_TYPE: &'static str = concat!("L", $class, ";");
+            }
+
+            impl FunctionalInterface for $name {
+                const METHOD: &'static str = $method;
+                $(const $generic: bool = true;)*
+            }
+        )*
+    };
+}
+
+functional_interfaces! {
+    /// `java.lang.Runnable`
+    Runnable("java/lang/Runnable", "run");
+    /// `java.util.function.Supplier<R>`
+    Supplier("java/util/function/Supplier", "get", GENERIC_RETURN);
+    /// `java.util.function.IntUnaryOperator`
+    IntUnaryOperator("java/util/function/IntUnaryOperator", "applyAsInt");
+    /// `java.util.function.LongUnaryOperator`
+    LongUnaryOperator("java/util/function/LongUnaryOperator", "applyAsLong");
+    /// `java.util.function.DoubleUnaryOperator`
+    DoubleUnaryOperator("java/util/function/DoubleUnaryOperator", "applyAsDouble");
+    /// `java.util.function.IntBinaryOperator`
+    IntBinaryOperator("java/util/function/IntBinaryOperator", "applyAsInt");
+    /// `java.util.function.LongBinaryOperator`
+    LongBinaryOperator("java/util/function/LongBinaryOperator", "applyAsLong");
+    /// `java.util.function.DoubleBinaryOperator`
+    DoubleBinaryOperator("java/util/function/DoubleBinaryOperator", "applyAsDouble");
+    /// `java.util.function.IntPredicate`
+    IntPredicate("java/util/function/IntPredicate", "test");
+    /// `java.util.function.LongPredicate`
+    LongPredicate("java/util/function/LongPredicate", "test");
+    /// `java.util.function.DoublePredicate`
+    DoublePredicate("java/util/function/DoublePredicate", "test");
+    /// `java.util.function.IntConsumer`
+    IntConsumer("java/util/function/IntConsumer", "accept");
+    /// `java.util.function.LongConsumer`
+    LongConsumer("java/util/function/LongConsumer", "accept");
+    /// `java.util.function.DoubleConsumer`
+    DoubleConsumer("java/util/function/DoubleConsumer", "accept");
+    /// `java.util.function.Predicate<T>`
+    Predicate("java/util/function/Predicate", "test", GENERIC_PARAMETERS);
+    /// `java.util.function.BiPredicate<T, U>`
+    BiPredicate("java/util/function/BiPredicate", "test", GENERIC_PARAMETERS);
+    /// `java.util.function.Consumer<T>`
+    Consumer("java/util/function/Consumer", "accept", GENERIC_PARAMETERS);
+    /// `java.util.function.BiConsumer<T, U>`
+    BiConsumer("java/util/function/BiConsumer", "accept", GENERIC_PARAMETERS);
+    /// `java.util.function.Function<T, R>`
+    Function("java/util/function/Function", "apply", GENERIC_PARAMETERS, GENERIC_RETURN);
+    /// `java.util.function.BiFunction<T, U, R>`
+    BiFunction("java/util/function/BiFunction", "apply", GENERIC_PARAMETERS, GENERIC_RETURN);
+}
+
+/// Values passed to and returned from functional interfaces.
+///
+/// Unlike the conversion traits, conversions aren't tied to a `JNIEnv` lifetime, so that closures can be called with
+/// any `JNIEnv` (e.g. from a thread other than the one they were created on).
+pub trait FunctionValue: Signature + Sized {
+    /// Convert into a JNI value, boxed into an object if `boxed`.
+    fn into_java<'env>(self, env: &JNIEnv<'env>, boxed: bool) -> JniResult<JValue<'env>>;
+
+    /// Convert from a JNI value, boxed into an object if `boxed`.
+    fn from_java<'env>(value: JValue<'env>, env: &JNIEnv<'env>, boxed: bool) -> JniResult<Self>;
+}
+
+macro_rules! function_values {
+    ($($type:ty),*) => {
+        $(
+            impl FunctionValue for $type {
+                fn into_java<'env>(self, env: &JNIEnv<'env>, boxed: bool) -> JniResult<JValue<'env>> {
+                    let value = TryIntoJavaValue::try_into(self, env)?;
+
+                    Ok(if boxed {
+                        JValue::Object(JavaValue::autobox(value, env))
+                    } else {
+                        JavaValue::into_jvalue(value, env)
+                    })
+                }
+
+                fn from_java<'env>(value: JValue<'env>, env: &JNIEnv<'env>, boxed: bool) -> JniResult<Self> {
+                    let source = if boxed {
+                        let object = value.l()?;
+                        if object.is_null() {
+                            return Err(Error::NullPtr("boxed functional interface value"));
+                        }
+                        JavaValue::unbox(object, env)
+                    } else {
+                        JniValue::from_jvalue(value, env)?
+                    };
+
+                    TryFromJavaValue::try_from(source, env)
+                }
+            }
+        )*
+    };
+}
+
+function_values!(bool, char, i8, i16, i32, i64, f32, f64, String);
+
+impl FunctionValue for () {
+    fn into_java<'env>(self, _env: &JNIEnv<'env>, boxed: bool) -> JniResult<JValue<'env>> {
+        Ok(if boxed { JValue::Object(JObject::null()) } else { JValue::Void })
+    }
+
+    fn from_java<'env>(_value: JValue<'env>, _env: &JNIEnv<'env>, _boxed: bool) -> JniResult<Self> {
+        Ok(())
+    }
+}
+
+/// Descriptor of a method of `I` with the given parameter and return signatures.
+fn method_descriptor<I: FunctionalInterface>(parameters: &[&str], return_type: &str) -> String {
+    let parameters: String = parameters
+        .iter()
+        .map(|p| if I::GENERIC_PARAMETERS { OBJECT_SIG } else { p })
+        .collect();
+    let return_type = if I::GENERIC_RETURN && return_type != "V" { OBJECT_SIG } else { return_type };
+
+    format!("({}){}", parameters, return_type)
+}
+
+/// A Java object implementing the functional interface `I`, called as a Rust function of type `F` (e.g. `fn(i32) -> i32`).
+///
+/// Values can be called with `call`, or turned into a closure with `into_fn`, which panics if the Java method throws.
+pub struct JavaFunction<I, F> {
+    vm: JavaVM,
+    object: ManuallyDrop<GlobalRef>,
+    marker: PhantomData<(fn() -> I, F)>,
+}
+
+impl<I, F> Drop for JavaFunction<I, F> {
+    fn drop(&mut self) {
+        // Threads spawned from Rust are usually not attached to the JVM, but deleting the reference requires it
+        let _env = self.vm.attach_current_thread();
+        unsafe { ManuallyDrop::drop(&mut self.object) };
+    }
+}
+
+impl<I, F> JavaFunction<I, F> {
+    /// The Java object.
+    pub fn as_obj(&self) -> JObject<'_> {
+        self.object.as_obj()
+    }
+}
+
+impl<I: FunctionalInterface, F> JavaFunction<I, F> {
+    fn invoke<R: FunctionValue>(&self, parameters: &[&str], arguments: Vec<ArgumentFn>) -> JniResult<R> {
+        let env = self.vm.attach_current_thread()?;
+        let descriptor = method_descriptor::<I>(parameters, R::SIG_TYPE);
+
+        // Arguments and the result are local references, released when the call returns
+        env.push_local_frame(arguments.len() as i32 + 1)?;
+        let result = arguments
+            .into_iter()
+            .map(|a| a(&env, I::GENERIC_PARAMETERS))
+            .collect::<JniResult<Vec<_>>>()
+            .and_then(|a| env.call_method(self.object.as_obj(), I::METHOD, descriptor, &a))
+            .and_then(|r| R::from_java(r, &env, I::GENERIC_RETURN && R::SIG_TYPE != "V"));
+        env.pop_local_frame(JObject::null())?;
+
+        result
+    }
+}
+
+type ArgumentFn = Box<dyn for<'env> FnOnce(&JNIEnv<'env>, bool) -> JniResult<JValue<'env>>>;
+
+macro_rules! java_function_arities {
+    ($(($($arg:ident: $type:ident),*);)*) => {
+        $(
+            impl<I, $($type,)* R> JavaFunction<I, fn($($type),*) -> R>
+            where
+                I: FunctionalInterface,
+                $($type: FunctionValue + 'static,)*
+                R: FunctionValue,
+            {
+                /// Call the Java method.
+                pub fn call(&self, $($arg: $type),*) -> JniResult<R> {
+                    let arguments: Vec<ArgumentFn> = vec![$(Box::new(move |env, boxed| $arg.into_java(env, boxed))),*];
+                    self.invoke(&[$($type::SIG_TYPE),*], arguments)
+                }
+
+                /// Closure calling the Java method, which panics if the call fails (leaving a thrown exception pending).
+                pub fn into_fn(self) -> impl Fn($($type),*) -> R {
+                    move |$($arg),*| match self.call($($arg),*) {
+                        Ok(result) => result,
+                        Err(e) => panic!("call to `{}` failed: {}", I::METHOD, e),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+java_function_arities! {
+    ();
+    (a: A);
+    (a: A, b: B);
+    (a: A, b: B, c: C);
+}
+
+impl<I: FunctionalInterface, F> Signature for JavaFunction<I, F> {
+    const SIG_TYPE: &'static str = <I as Signature>::SIG_TYPE;
+}
+
+impl<'env: 'borrow, 'borrow, I: FunctionalInterface, F> TryFromJavaValue<'env, 'borrow> for JavaFunction<I, F> {
+    type Source = JObject<'env>;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+        if s.is_null() {
+            return Err(Error::NullPtr("functional interface argument"));
+        }
+
+        Ok(JavaFunction {
+            vm: env.get_java_vm()?,
+            object: ManuallyDrop::new(env.new_global_ref(s)?),
+            marker: PhantomData,
+        })
+    }
+}
+
+impl<'env: 'borrow, 'borrow, I: FunctionalInterface, F> FromJavaValue<'env, 'borrow> for JavaFunction<I, F> {
+    type Source = JObject<'env>;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        TryFromJavaValue::try_from(s, env).unwrap()
+    }
+}
+
+/// Closure called with the JNI environment and the (boxed) arguments of the Java call, returning the boxed result.
+type ErasedFn = dyn for<'env> Fn(&JNIEnv<'env>, &[JObject<'env>]) -> JniResult<JObject<'env>> + Send + Sync;
+
+/// A Rust closure converted to a Java object implementing the functional interface `I`.
+pub struct RustFunction<I> {
+    function: Box<ErasedFn>,
+    marker: PhantomData<fn() -> I>,
+}
+
+/// Closures that can be wrapped in a [`RustFunction`], with parameters `Args` (as a tuple).
+pub trait RustClosure<Args>: Send + Sync + 'static {
+    /// Erase the types of the closure.
+    #[doc(hidden)]
+    fn erase(self) -> Box<ErasedFn>;
+}
+
+macro_rules! rust_closure_arities {
+    ($(($($arg:ident: $type:ident),*);)*) => {
+        $(
+            impl<F, $($type,)* R> RustClosure<($($type,)*)> for F
+            where
+                F: Fn($($type),*) -> R + Send + Sync + 'static,
+                $($type: FunctionValue,)*
+                R: FunctionValue,
+            {
+                #[allow(unused_variables, unused_mut)]
+                fn erase(self) -> Box<ErasedFn> {
+                    Box::new(move |env, arguments| {
+                        let mut arguments = arguments.iter();
+                        $(
+                            let $arg = match arguments.next() {
+                                Some(&a) => $type::from_java(JValue::Object(a), env, true)?,
+                                None => return Err(Error::WrongJValueType("argument", "nothing")),
+                            };
+                        )*
+
+                        self($($arg),*).into_java(env, true)?.l()
+                    })
+                }
+            }
+        )*
+    };
+}
+
+rust_closure_arities! {
+    ();
+    (a: A);
+    (a: A, b: B);
+    (a: A, b: B, c: C);
+}
+
+impl<I: FunctionalInterface> RustFunction<I> {
+    /// Wrap `function`, which must have the parameters and return type of the method of `I`.
+    pub fn new<F: RustClosure<Args>, Args>(function: F) -> Self {
+        RustFunction {
+            function: function.erase(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<I: FunctionalInterface> Signature for RustFunction<I> {
+    const SIG_TYPE: &'static str = <I as Signature>::SIG_TYPE;
+}
+
+impl<'env, I: FunctionalInterface> TryIntoJavaValue<'env> for RustFunction<I> {
+    type Target = JObject<'env>;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+        register_natives(env)?;
+
+        let sig = <I as Signature>::SIG_TYPE;
+        let interface = crate::loader::find_class(env, &sig[1..sig.len() - 1])?;
+        let loader = env
+            .call_method(interface, "getClassLoader", "()Ljava/lang/ClassLoader;", &[])?
+            .l()?;
+        let interfaces = env.new_object_array(1, "java/lang/Class", interface)?;
+
+        let class = crate::helpers::helper_class(env, RUST_FUNCTION_CLASS)?;
+        let handle = Box::into_raw(Box::new(self.function)) as jlong;
+        let handler = match env.new_object(class, "(J)V", &[JValue::Long(handle)]) {
+            Ok(handler) => handler,
+            Err(e) => {
+                drop(unsafe { Box::from_raw(handle as *mut Box<ErasedFn>) });
+                return Err(e);
+            }
+        };
+
+        env.call_static_method(
+            "java/lang/reflect/Proxy",
+            "newProxyInstance",
+            "(Ljava/lang/ClassLoader;[Ljava/lang/Class;Ljava/lang/reflect/InvocationHandler;)Ljava/lang/Object;",
+            &[
+                JValue::Object(loader),
+                JValue::Object(unsafe { JObject::from_raw(interfaces) }),
+                JValue::Object(handler),
+            ],
+        )?
+        .l()
+    }
+}
+
+impl<'env, I: FunctionalInterface> IntoJavaValue<'env> for RustFunction<I> {
+    type Target = JObject<'env>;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        TryIntoJavaValue::try_into(self, env).unwrap()
+    }
+}
+
+static NATIVES_REGISTERED: AtomicBool = AtomicBool::new(false);
+
+/// Register the native methods of `robusta.RustFunction`, which are looked up dynamically so that they don't need to
+/// be exported by the library.
+fn register_natives(env: &JNIEnv) -> JniResult<()> {
+    if NATIVES_REGISTERED.load(Ordering::Acquire) {
+        return Ok(());
+    }
+
+    env.register_native_methods(
+        crate::helpers::helper_class(env, RUST_FUNCTION_CLASS)?,
+        &[
+            NativeMethod {
+                name: JNIString::from("call"),
+                sig: JNIString::from("(J[Ljava/lang/Object;)Ljava/lang/Object;"),
+                fn_ptr: rust_function_call as *mut c_void,
+            },
+            NativeMethod {
+                name: JNIString::from("drop"),
+                sig: JNIString::from("(J)V"),
+                fn_ptr: rust_function_drop as *mut c_void,
+            },
+        ],
+    )?;
+    NATIVES_REGISTERED.store(true, Ordering::Release);
+
+    Ok(())
+}
+
+fn call_erased<'env>(env: &JNIEnv<'env>, handle: jlong, arguments: jobjectArray) -> JniResult<JObject<'env>> {
+    let function = unsafe { &*(handle as *const Box<ErasedFn>) };
+    let arguments = if arguments.is_null() {
+        Vec::new()
+    } else {
+        (0..env.get_array_length(arguments)?)
+            .map(|i| env.get_object_array_element(arguments, i))
+            .collect::<JniResult<Vec<_>>>()?
+    };
+
+    function(env, &arguments)
+}
+
+extern "system" fn rust_function_call(env: JNIEnv, _class: JClass, handle: jlong, arguments: jobjectArray) -> jobject {
+    let result: Result<JniResult<JObject>, Box<dyn Any + Send>> =
+        catch_unwind(AssertUnwindSafe(|| call_erased(&env, handle, arguments)));
+
+    match result {
+        Ok(Ok(result)) => result.into_raw(),
+        Ok(Err(e)) => {
+            if !env.exception_check().unwrap_or(true) {
+                let _ = env.throw_new("java/lang/RuntimeException", format!("Rust function failed: {}", e));
+            }
+            std::ptr::null_mut()
+        }
+        Err(payload) => {
+            crate::panic::throw(&env, "RustFunction.call", "java/lang/RuntimeException", payload);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+extern "system" fn rust_function_drop(_env: JNIEnv, _class: JClass, handle: jlong) {
+    drop(unsafe { Box::from_raw(handle as *mut Box<ErasedFn>) });
+}
@@ -0,0 +1,83 @@
+use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
+
+use jni::errors::Result as JniResult;
+use jni::objects::{GlobalRef, JObject};
+use jni::{JNIEnv, JavaVM};
+
+use crate::convert::TryFromJavaValue;
+
+/// Types that can be rebuilt from a [`Global`] handle.
+///
+/// This trait is implemented by the [`TryFromJavaValue`] derive macro on the `'static` instantiation of the deriving struct
+/// (e.g. `User<'static, 'static>`), with [`Local`](GlobalInstance::Local) being the struct itself.
+pub trait GlobalInstance {
+    /// Type used to access the Java object while the current thread is attached.
+    type Local<'env: 'borrow, 'borrow>: TryFromJavaValue<'env, 'borrow, Source = JObject<'env>>;
+}
+
+/// A handle to a Java object that is not bound to a `JNIEnv`, and can therefore be stored and sent to other threads.
+///
+/// The handle owns a [`GlobalRef`] to the object, so the object is kept alive until the handle is dropped.
+/// Derived structs get a `to_global(env)` method to create a handle. Structs deriving [`IntoGlobal`](crate::convert::IntoGlobal)
+/// can also be converted into an owned type holding a handle along with a copy of their data fields.
+///
+/// Example:
+/// ```ignore
+/// let user = user.to_global(env)?;
+///
+/// std::thread::spawn(move || {
+///     user.with(|env, user| user.getPassword(env)).unwrap()
+/// });
+/// ```
+pub struct Global<T> {
+    vm: JavaVM,
+    object: ManuallyDrop<GlobalRef>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Global<T> {
+    /// Create a handle to `object`.
+    pub fn new(env: &JNIEnv, object: JObject) -> JniResult<Self> {
+        Ok(Global {
+            vm: env.get_java_vm()?,
+            object: ManuallyDrop::new(env.new_global_ref(object)?),
+            _marker: PhantomData,
+        })
+    }
+
+    /// Global reference to the Java object.
+    pub fn global_ref(&self) -> &GlobalRef {
+        &self.object
+    }
+
+    /// Java VM the object belongs to.
+    pub fn vm(&self) -> &JavaVM {
+        &self.vm
+    }
+}
+
+impl<T: GlobalInstance> Global<T> {
+    /// Run `f` with a local instance of the object, attaching the current thread to the JVM if needed.
+    ///
+    /// If the thread was not already attached, it's detached when `f` returns.
+    pub fn with<F, R>(&self, f: F) -> JniResult<R>
+    where
+        F: for<'env> FnOnce(&'env JNIEnv<'env>, T::Local<'env, 'env>) -> R,
+    {
+        let guard = self.vm.attach_current_thread()?;
+        let env: &JNIEnv = &guard;
+        let local = env.new_local_ref(self.object.as_obj())?;
+        let instance = TryFromJavaValue::try_from(local, env)?;
+
+        Ok(f(env, instance))
+    }
+}
+
+impl<T> Drop for Global<T> {
+    fn drop(&mut self) {
+        // Attach while releasing the reference, so that handles can be dropped from any thread
+        let _guard = self.vm.attach_current_thread();
+        unsafe { ManuallyDrop::drop(&mut self.object) }
+    }
+}
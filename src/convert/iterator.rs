@@ -0,0 +1,322 @@
+//! Streaming conversions between Rust iterators and `java.util.Iterator`.
+//!
+//! [`JIterator<'env, T>`](JIterator) takes a Java `Iterator` (or `Iterable`, whose `iterator()` is used) and implements
+//! [`Iterator`], converting each element to `T` when it is reached. Unlike `Vec<T>`, the collection is never
+//! materialized, so large or unbounded collections can be processed element by element:
+//!
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! # #[bridge]
+//! # mod jni {
+//!     # use robusta_jni::convert::{JIterator, Signature};
+//!     # use robusta_jni::jni::errors::Result as JniResult;
+//! #[derive(Signature)]
+//! #[package(com.example)]
+//! struct Stats;
+//!
+//! impl<'env> Stats {
+//!     // Java: `static native long sum(Iterator<Integer> values)`
+//!     pub extern "jni" fn sum(values: JIterator<'env, i32>) -> JniResult<i64> {
+//!         values.map(|v| v.map(i64::from)).sum()
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! Elements must be converted to types that don't borrow the `JNIEnv` (e.g. primitives, `String`, `i128`): use
+//! [`JIterator::next_object`] to get elements as `JObject`s. Boxed primitives are released once unboxed; other elements
+//! are local references, released when the native method returns.
+//!
+//! In the other direction, [`RustIterator`] is converted to a `java.util.Iterator` producing the items of a Rust
+//! iterator lazily, as Java calls `next()`:
+//!
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! # #[bridge]
+//! # mod jni {
+//!     # use robusta_jni::convert::{RustIterator, Signature};
+//!     # #[derive(Signature)]
+//!     # #[package(com.example)]
+//!     # struct Stats;
+//! impl Stats {
+//!     // Java: `static native Iterator<Long> squares(int count)`
+//!     pub extern "jni" fn squares(count: i32) -> RustIterator {
+//!         RustIterator::new((0..count as i64).map(|i| i * i))
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! Items must implement [`FunctionValue`], and are boxed (e.g. `i64` as `Long`). This requires the
+//! `robusta.RustIterator` class, found in the `java` directory of this crate, to be available to the JVM (or embedded in
+//! the library with the `helper_classes` feature, see [`helpers`](crate::helpers)). The Rust iterator is dropped when the Java object is garbage collected.
+
+use std::any::Any;
+use std::ffi::c_void;
+use std::iter::Peekable;
+use std::marker::PhantomData;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use jni::errors::{Error, Result as JniResult};
+use jni::objects::{JClass, JObject, JValue};
+use jni::strings::JNIString;
+use jni::sys::{jboolean, jlong, jobject, JNI_FALSE, JNI_TRUE};
+use jni::{JNIEnv, NativeMethod};
+
+use crate::convert::{
+    FromJavaValue, FunctionValue, IntoJavaValue, JavaValue, Signature, TryFromJavaValue, TryIntoJavaValue,
+};
+
+const ITERABLE_CLASS: &str = "java/lang/Iterable";
+const RUST_ITERATOR_CLASS: &str = "robusta/RustIterator";
+
+/// A Java `java.util.Iterator`, iterated as a Rust [`Iterator`] of `JniResult<T>`.
+///
+/// Converted from a Java `Iterator` or `Iterable`, and only usable as an input type.
+pub struct JIterator<'env, T> {
+    env: JNIEnv<'env>,
+    iterator: JObject<'env>,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<'env, T> JIterator<'env, T> {
+    /// Iterate over `object`, a Java `Iterator` or `Iterable`.
+    pub fn new(env: &JNIEnv<'env>, object: JObject<'env>) -> JniResult<Self> {
+        if object.is_null() {
+            return Err(Error::NullPtr("java.util.Iterator"));
+        }
+
+        let iterator = if env.is_instance_of(object, ITERABLE_CLASS)? {
+            env.call_method(object, "iterator", "()Ljava/util/Iterator;", &[])?.l()?
+        } else {
+            object
+        };
+
+        Ok(JIterator {
+            env: *env,
+            iterator,
+            marker: PhantomData,
+        })
+    }
+
+    /// The underlying `java.util.Iterator`.
+    pub fn as_obj(&self) -> JObject<'env> {
+        self.iterator
+    }
+
+    /// Next element of the iterator as a `JObject`, without converting it.
+    pub fn next_object(&mut self) -> JniResult<Option<JObject<'env>>> {
+        if !self.env.call_method(self.iterator, "hasNext", "()Z", &[])?.z()? {
+            return Ok(None);
+        }
+
+        self.env
+            .call_method(self.iterator, "next", "()Ljava/lang/Object;", &[])?
+            .l()
+            .map(Some)
+    }
+}
+
+impl<'env, T> Iterator for JIterator<'env, T>
+where
+    T: for<'a> TryFromJavaValue<'a, 'a>,
+    for<'a> <T as TryFromJavaValue<'a, 'a>>::Source: JavaValue<'a>,
+{
+    type Item = JniResult<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let element = match self.next_object() {
+            Ok(Some(element)) => element,
+            Ok(None) => return None,
+            Err(e) => return Some(Err(e)),
+        };
+
+        let env = &self.env;
+        let source = <T as TryFromJavaValue>::Source::unbox(element, env);
+        let owned = !T::KEEPS_SOURCE_REF || JavaValue::as_object(&source).is_none();
+        let value = T::try_from(source, env);
+
+        // Boxed primitives aren't needed once unboxed, nor are elements not kept by their converted value
+        if owned && !element.is_null() {
+            if let Err(e) = env.delete_local_ref(element) {
+                return Some(Err(e));
+            }
+        }
+
+        Some(value)
+    }
+}
+
+impl<T> Signature for JIterator<'_, T> {
+    const SIG_TYPE: &'static str = "Ljava/util/Iterator;";
+}
+
+impl<'env: 'borrow, 'borrow, T> TryFromJavaValue<'env, 'borrow> for JIterator<'env, T> {
+    type Source = JObject<'env>;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+        JIterator::new(env, s)
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T> FromJavaValue<'env, 'borrow> for JIterator<'env, T> {
+    type Source = JObject<'env>;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        JIterator::new(env, s).unwrap()
+    }
+}
+
+/// Type-erased Rust iterator, used by the native methods of `robusta.RustIterator`.
+trait ErasedIterator: Send {
+    fn has_next(&mut self) -> bool;
+
+    fn next<'env>(&mut self, env: &JNIEnv<'env>) -> JniResult<Option<JObject<'env>>>;
+}
+
+impl<I> ErasedIterator for Peekable<I>
+where
+    I: Iterator + Send,
+    I::Item: FunctionValue + Send,
+{
+    fn has_next(&mut self) -> bool {
+        self.peek().is_some()
+    }
+
+    fn next<'env>(&mut self, env: &JNIEnv<'env>) -> JniResult<Option<JObject<'env>>> {
+        match Iterator::next(self) {
+            Some(item) => item.into_java(env, true)?.l().map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// A Rust iterator, converted to a Java `java.util.Iterator` producing its items.
+///
+/// Only usable as an output type.
+pub struct RustIterator {
+    iterator: Box<dyn ErasedIterator>,
+}
+
+impl RustIterator {
+    /// Wrap `iterator`, whose items are converted to Java when they are requested by `next()`.
+    pub fn new<I>(iterator: I) -> Self
+    where
+        I: IntoIterator,
+        I::IntoIter: Send + 'static,
+        I::Item: FunctionValue + Send + 'static,
+    {
+        RustIterator {
+            iterator: Box::new(iterator.into_iter().peekable()),
+        }
+    }
+}
+
+impl Signature for RustIterator {
+    const SIG_TYPE: &'static str = "Ljava/util/Iterator;";
+}
+
+impl<'env> TryIntoJavaValue<'env> for RustIterator {
+    type Target = JObject<'env>;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+        register_natives(env)?;
+
+        let class = crate::helpers::helper_class(env, RUST_ITERATOR_CLASS)?;
+        let handle = Box::into_raw(Box::new(self.iterator)) as jlong;
+        match env.new_object(class, "(J)V", &[JValue::Long(handle)]) {
+            Ok(iterator) => Ok(iterator),
+            Err(e) => {
+                drop(unsafe { Box::from_raw(handle as *mut Box<dyn ErasedIterator>) });
+                Err(e)
+            }
+        }
+    }
+}
+
+impl<'env> IntoJavaValue<'env> for RustIterator {
+    type Target = JObject<'env>;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        TryIntoJavaValue::try_into(self, env).unwrap()
+    }
+}
+
+static NATIVES_REGISTERED: AtomicBool = AtomicBool::new(false);
+
+/// Register the native methods of `robusta.RustIterator`, which are looked up dynamically so that they don't need to
+/// be exported by the library.
+fn register_natives(env: &JNIEnv) -> JniResult<()> {
+    if NATIVES_REGISTERED.load(Ordering::Acquire) {
+        return Ok(());
+    }
+
+    env.register_native_methods(
+        crate::helpers::helper_class(env, RUST_ITERATOR_CLASS)?,
+        &[
+            NativeMethod {
+                name: JNIString::from("hasItem"),
+                sig: JNIString::from("(J)Z"),
+                fn_ptr: rust_iterator_has_item as *mut c_void,
+            },
+            NativeMethod {
+                name: JNIString::from("nextItem"),
+                sig: JNIString::from("(J)Ljava/lang/Object;"),
+                fn_ptr: rust_iterator_next_item as *mut c_void,
+            },
+            NativeMethod {
+                name: JNIString::from("drop"),
+                sig: JNIString::from("(J)V"),
+                fn_ptr: rust_iterator_drop as *mut c_void,
+            },
+        ],
+    )?;
+    NATIVES_REGISTERED.store(true, Ordering::Release);
+
+    Ok(())
+}
+
+/// The iterator of `handle`. Calls are serialized by the `synchronized` methods of `robusta.RustIterator`.
+#[allow(clippy::mut_from_ref)]
+unsafe fn iterator<'a>(handle: jlong) -> &'a mut Box<dyn ErasedIterator> {
+    &mut *(handle as *mut Box<dyn ErasedIterator>)
+}
+
+extern "system" fn rust_iterator_has_item(env: JNIEnv, _class: JClass, handle: jlong) -> jboolean {
+    match catch_unwind(AssertUnwindSafe(|| unsafe { iterator(handle) }.has_next())) {
+        Ok(true) => JNI_TRUE,
+        Ok(false) => JNI_FALSE,
+        Err(payload) => {
+            crate::panic::throw(&env, "RustIterator.hasNext", "java/lang/RuntimeException", payload);
+            JNI_FALSE
+        }
+    }
+}
+
+extern "system" fn rust_iterator_next_item(env: JNIEnv, _class: JClass, handle: jlong) -> jobject {
+    let result: Result<JniResult<Option<JObject>>, Box<dyn Any + Send>> =
+        catch_unwind(AssertUnwindSafe(|| unsafe { iterator(handle) }.next(&env)));
+
+    match result {
+        Ok(Ok(item)) => item.unwrap_or_else(JObject::null).into_raw(),
+        Ok(Err(e)) => {
+            if !env.exception_check().unwrap_or(true) {
+                let _ = env.throw_new("java/lang/RuntimeException", format!("Rust iterator failed: {}", e));
+            }
+            std::ptr::null_mut()
+        }
+        Err(payload) => {
+            crate::panic::throw(&env, "RustIterator.next", "java/lang/RuntimeException", payload);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+extern "system" fn rust_iterator_drop(_env: JNIEnv, _class: JClass, handle: jlong) {
+    drop(unsafe { Box::from_raw(handle as *mut Box<dyn ErasedIterator>) });
+}
@@ -0,0 +1,122 @@
+use std::cell::OnceCell;
+
+use jni::errors::Result as JniResult;
+use jni::objects::{JObject, JValue};
+use jni::JNIEnv;
+
+use crate::convert::{FromJavaValue, JniValue, Signature, TryFromJavaValue};
+
+/// Data field of a derived struct that is read from the Java instance only when first accessed.
+///
+/// The conversion derive macros read regular data fields when the struct is converted, even if they are never used.
+/// A `Lazy` field defers reading and converting the Java field until [`get`](Lazy::get) (or [`get_unchecked`](Lazy::get_unchecked))
+/// is called, and caches the converted value afterwards. This avoids copying heavy fields (e.g. large strings or lists) which
+/// are only needed by some methods.
+///
+/// Note that a `Lazy` field refers to the instance held by the `#[instance]` field, and must not outlive it.
+///
+/// Example:
+/// ```rust
+/// # use robusta_jni::bridge;
+/// #
+/// # #[bridge]
+/// # mod jni {
+///     # use robusta_jni::convert::{Lazy, Signature, TryFromJavaValue};
+///     # use robusta_jni::jni::errors::Result as JniResult;
+///     # use robusta_jni::jni::objects::AutoLocal;
+/// #[derive(Signature, TryFromJavaValue)]
+/// #[package()]
+/// struct Document<'env: 'borrow, 'borrow> {
+///     #[instance]
+///     raw: AutoLocal<'env, 'borrow>,
+///     title: String,
+///     body: Lazy<'env, 'borrow, String>,
+/// }
+///
+/// impl<'env: 'borrow, 'borrow> Document<'env, 'borrow> {
+///     // `body` is only copied from Java if `full` is true
+///     pub extern "jni" fn wordCount(self, full: bool) -> JniResult<i32> {
+///         let text = if full { self.body.get()? } else { &self.title };
+///         Ok(text.split_whitespace().count() as i32)
+///     }
+/// }
+/// # }
+/// ```
+pub struct Lazy<'env: 'borrow, 'borrow, T>
+where
+    T: Signature,
+{
+    env: &'borrow JNIEnv<'env>,
+    obj: JObject<'env>,
+    field_name: &'static str,
+    value: OnceCell<T>,
+}
+
+impl<'env: 'borrow, 'borrow, T> Lazy<'env, 'borrow, T>
+where
+    T: Signature,
+{
+    /// Create a handle to field `field_name` of `obj`, without reading it.
+    pub fn new(obj: JObject<'env>, field_name: &'static str, env: &'borrow JNIEnv<'env>) -> Self {
+        Lazy {
+            env,
+            obj,
+            field_name,
+            value: OnceCell::new(),
+        }
+    }
+
+    /// Whether the field was already read.
+    pub fn is_loaded(&self) -> bool {
+        self.value.get().is_some()
+    }
+
+    /// Consume the handle, returning the field value if it was already read.
+    pub fn into_inner(self) -> Option<T> {
+        self.value.into_inner()
+    }
+
+    fn read_field(&self) -> JniResult<JValue<'env>> {
+        self.env.get_field(self.obj, self.field_name, <T as Signature>::SIG_TYPE)
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T> Lazy<'env, 'borrow, T>
+where
+    T: Signature + TryFromJavaValue<'env, 'borrow>,
+{
+    /// Value of the field, reading and converting it on first access.
+    pub fn get(&self) -> JniResult<&T> {
+        if let Some(value) = self.value.get() {
+            return Ok(value);
+        }
+
+        let value = JniValue::from_jvalue(self.read_field()?, self.env)
+            .and_then(|v| TryFromJavaValue::try_from(v, self.env))?;
+        Ok(self.value.get_or_init(|| value))
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T> Lazy<'env, 'borrow, T>
+where
+    T: Signature + FromJavaValue<'env, 'borrow>,
+{
+    /// Value of the field, reading and converting it on first access.
+    ///
+    /// # Panics
+    /// Panics if the field can't be read.
+    pub fn get_unchecked(&self) -> &T {
+        self.value.get_or_init(|| {
+            JniValue::from_jvalue(self.read_field().unwrap(), self.env)
+                .map(|v| FromJavaValue::from(v, self.env))
+                .unwrap()
+        })
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T> Signature for Lazy<'env, 'borrow, T>
+where
+    T: Signature,
+{
+    const SIG_TYPE: &'static str = <T as Signature>::SIG_TYPE;
+}
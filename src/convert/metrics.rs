@@ -0,0 +1,113 @@
+//! Data volume metrics for conversions that copy strings and arrays across the JNI boundary.
+//!
+//! Requires the `metrics` feature. When enabled, every string and primitive array conversion reports
+//! a [`CopyEvent`] to the callback registered with [`set_copy_callback`].
+//! When the feature is disabled, reporting compiles down to nothing.
+//!
+//! Example:
+//! ```ignore
+//! use std::sync::atomic::{AtomicUsize, Ordering};
+//! use robusta_jni::convert::metrics::{set_copy_callback, CopyEvent};
+//!
+//! static BYTES: AtomicUsize = AtomicUsize::new(0);
+//!
+//! set_copy_callback(|event: &CopyEvent| {
+//!     BYTES.fetch_add(event.bytes, Ordering::Relaxed);
+//! });
+//! ```
+
+#[cfg(feature = "metrics")]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "metrics")]
+use std::sync::RwLock;
+
+/// Direction of a copy across the JNI boundary.
+#[cfg(feature = "metrics")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CopyDirection {
+    /// Rust value copied into a new Java object.
+    ToJava,
+    /// Java object copied into a new Rust value.
+    FromJava,
+}
+
+/// A single copy of string or array data across the JNI boundary.
+#[cfg(feature = "metrics")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CopyEvent {
+    /// [Java type signature](https://docs.oracle.com/en/java/javase/15/docs/specs/jni/types.html#type-signatures) of the copied value, e.g. `[B`.
+    pub type_signature: &'static str,
+    pub direction: CopyDirection,
+    /// Number of copied elements. For strings, this is the length of the UTF-8 representation.
+    pub elements: usize,
+    /// Number of copied bytes.
+    pub bytes: usize,
+}
+
+#[cfg(feature = "metrics")]
+type CopyCallback = Box<dyn Fn(&CopyEvent) + Send + Sync>;
+
+#[cfg(feature = "metrics")]
+static CALLBACK_SET: AtomicBool = AtomicBool::new(false);
+
+#[cfg(feature = "metrics")]
+static CALLBACK: RwLock<Option<CopyCallback>> = RwLock::new(None);
+
+/// Register `callback` to be called on every copy, replacing any previously registered callback.
+///
+/// The callback is invoked synchronously on the thread performing the conversion, so it should be cheap.
+#[cfg(feature = "metrics")]
+pub fn set_copy_callback<F>(callback: F)
+where
+    F: Fn(&CopyEvent) + Send + Sync + 'static,
+{
+    let mut guard = CALLBACK.write().unwrap_or_else(|e| e.into_inner());
+    *guard = Some(Box::new(callback));
+    CALLBACK_SET.store(true, Ordering::Release);
+}
+
+/// Remove the registered callback, if any.
+#[cfg(feature = "metrics")]
+pub fn clear_copy_callback() {
+    let mut guard = CALLBACK.write().unwrap_or_else(|e| e.into_inner());
+    CALLBACK_SET.store(false, Ordering::Release);
+    *guard = None;
+}
+
+#[cfg(feature = "metrics")]
+#[inline]
+pub(crate) fn record_to_java(type_signature: &'static str, elements: usize, bytes: usize) {
+    record(type_signature, CopyDirection::ToJava, elements, bytes)
+}
+
+#[cfg(feature = "metrics")]
+#[inline]
+pub(crate) fn record_from_java(type_signature: &'static str, elements: usize, bytes: usize) {
+    record(type_signature, CopyDirection::FromJava, elements, bytes)
+}
+
+#[cfg(feature = "metrics")]
+#[inline]
+fn record(type_signature: &'static str, direction: CopyDirection, elements: usize, bytes: usize) {
+    if !CALLBACK_SET.load(Ordering::Acquire) {
+        return;
+    }
+
+    let guard = CALLBACK.read().unwrap_or_else(|e| e.into_inner());
+    if let Some(callback) = guard.as_ref() {
+        callback(&CopyEvent {
+            type_signature,
+            direction,
+            elements,
+            bytes,
+        });
+    }
+}
+
+#[cfg(not(feature = "metrics"))]
+#[inline(always)]
+pub(crate) fn record_to_java(_type_signature: &'static str, _elements: usize, _bytes: usize) {}
+
+#[cfg(not(feature = "metrics"))]
+#[inline(always)]
+pub(crate) fn record_from_java(_type_signature: &'static str, _elements: usize, _bytes: usize) {}
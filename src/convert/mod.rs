@@ -3,7 +3,10 @@
 //! similar to the ones found in the standard library.
 //!
 //! The `call_type` attribute controls which of the two conversion families is selected during code generation.
-//! `call_type` is a per-function attribute.
+//! `call_type` is a per-function attribute, which can also be placed on an impl block or on a bridged struct to set a default for all of its methods.
+//! A default for the whole module can be set with `#[bridge(default_call_type = "unchecked")]` (or `"safe"`), or with a
+//! `#[call_type]` attribute on the module itself.
+//! Method-level attributes override the impl block default, which in turn overrides the struct default, then the module default.
 //! Specific parameters that can be given to `call_type` can be found in the module documentation relative to the trait family ([safe] module for fallible conversions and [unchecked] module for infallible conversions)
 //!
 //! **If the `call_type` attribute is omitted, the fallible conversion trait family is chosen.**
@@ -30,6 +33,26 @@
 //! }
 //! ```
 //!
+//! With a module default:
+//! ```
+//! use robusta_jni::bridge;
+//!
+//! #[bridge(default_call_type = "unchecked")]
+//! mod jni {
+//!     #[package(com.example.robusta)]
+//!     struct Numbers;
+//!
+//!     impl Numbers {
+//!         pub extern "jni" fn twice(v: i32) -> i32 { v * 2 }
+//!
+//!         pub extern "java" fn parse(env: &::robusta_jni::jni::JNIEnv, s: String) -> i32 {}
+//!
+//!         #[call_type(safe)]
+//!         pub extern "jni" fn half(v: i32) -> ::robusta_jni::jni::errors::Result<i32> { Ok(v / 2) }
+//!     }
+//! }
+//! ```
+//!
 //! # Raising exceptions from native code
 //! If you want to have the option of throwing a Java exception from native code (conversion errors aside), you can
 //! annotate your function signature with a [`jni::errors::Result<T>`] return type.
@@ -48,23 +71,127 @@ use jni::sys::{jboolean, jbyte, jchar, jdouble, jfloat, jint, jlong, jobject, js
 use jni::JNIEnv;
 use paste::paste;
 
+pub use array::*;
+pub use bitset::*;
+pub use buffer::*;
+pub use class::*;
+pub use converter::{ConvertFromJava, ConvertIntoJava, Converter};
+pub use critical::{Critical, CriticalElement};
+pub use error::ConversionError;
 pub use field::*;
-pub use robusta_codegen::Signature;
+#[cfg(feature = "file")]
+pub use file::JPath;
+pub use function::{FunctionValue, FunctionalInterface, JavaFunction, RustFunction};
+pub use global::*;
+pub use iterator::{JIterator, RustIterator};
+pub use lazy::*;
+pub use opaque::Opaque;
+pub use optional::*;
+pub use packed::{PackedElement, PackedList};
+pub use robusta_codegen::{IntoData, IntoGlobal, JavaDelegate, Signature};
 pub use safe::*;
+#[cfg(feature = "cbor")]
+pub use serialized::Cbor;
+#[cfg(feature = "json")]
+pub use serialized::Json;
 pub use unchecked::*;
-
+pub use unsigned::Checked;
+pub use weak::WeakGlobal;
+
+pub mod array;
+pub mod bigint;
+pub mod bitset;
+pub mod buffer;
+#[cfg(feature = "bytes")]
+pub mod bytes;
+pub mod class;
+pub mod converter;
+pub mod critical;
+pub mod debug;
+#[cfg(feature = "rust_decimal")]
+pub mod decimal;
+pub mod error;
 pub mod field;
+#[cfg(feature = "file")]
+pub mod file;
+pub mod function;
+pub mod global;
+pub mod iterator;
+pub mod lazy;
+pub mod metrics;
+#[cfg(feature = "net")]
+pub mod net;
+pub mod nullable;
+pub mod opaque;
+pub mod optional;
+pub mod packed;
+pub mod pool;
 pub mod safe;
+#[cfg(any(feature = "json", feature = "cbor"))]
+pub mod serialized;
+pub mod time;
+pub mod tuple;
 pub mod unchecked;
+pub mod unsigned;
+pub mod weak;
 
 /// A trait for types that are ffi-safe to use with JNI. It is implemented for primitives, [JObject](jni::objects::JObject) and [jobject](jni::sys::jobject).
 /// Users that want automatic conversion should instead implement [FromJavaValue], [IntoJavaValue] and/or [TryFromJavaValue], [TryIntoJavaValue]
+///
+/// Java values can be boxed into objects, e.g. to be stored in collections. `()` (Java `void`) is not a Java value,
+/// so it can't be used where a value must be boxed:
+/// ```compile_fail
+/// # use robusta_jni::bridge;
+/// #
+/// # #[bridge]
+/// # mod jni {
+///     # use robusta_jni::convert::Signature;
+///     # #[derive(Signature)]
+///     # #[package()]
+///     # struct A;
+///     #
+/// impl A {
+///     pub extern "jni" fn nothing() -> Vec<()> {
+///         vec![()]
+///     }
+/// }
+/// # }
+/// ```
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not a Java value",
+    note = "Java values are primitives and objects: `()` maps to `void`, and can only be returned by methods"
+)]
 pub trait JavaValue<'env> {
     /// Convert instance to a [`JObject`].
     fn autobox(self, env: &JNIEnv<'env>) -> JObject<'env>;
 
     /// Convert [`JObject`] to the implementing type.
     fn unbox(s: JObject<'env>, env: &JNIEnv<'env>) -> Self;
+
+    /// Convert instance to a [`JValue`], e.g. for use as a method argument or as a field value.
+    ///
+    /// By default the instance is autoboxed and passed as an object value.
+    fn into_jvalue(self, env: &JNIEnv<'env>) -> JValue<'env>
+    where
+        Self: Sized,
+    {
+        JValue::Object(self.autobox(env))
+    }
+
+    /// The instance as an object reference, or `None` for primitive values.
+    fn as_object(&self) -> Option<JObject<'env>> {
+        None
+    }
+
+    /// Convert a [`JValue`] (e.g. a field value or the result of a method call) to the implementing type.
+    ///
+    /// By default the value must be an object, which is unboxed.
+    fn from_jvalue(value: JValue<'env>, env: &JNIEnv<'env>) -> jni::errors::Result<Self>
+    where
+        Self: Sized,
+    {
+        value.l().map(|object| Self::unbox(object, env))
+    }
 }
 
 /// This trait provides [type signatures](https://docs.oracle.com/en/java/javase/15/docs/specs/jni/types.html#type-signatures) for types.
@@ -79,18 +206,211 @@ pub trait Signature {
     const SIG_TYPE: &'static str;
 }
 
-macro_rules! jvalue_types {
-    ($type:ty: $boxed:ident ($sig:ident) [$unbox_method:ident]) => {
-        impl Signature for $type {
-            const SIG_TYPE: &'static str = stringify!($sig);
+/// Whether a value of Java type `sig` is a reference, which can be `null`. Usable in constants, e.g. by code generated
+/// for `#[synchronized]` methods.
+#[doc(hidden)]
+pub const fn is_reference(sig: &str) -> bool {
+    matches!(sig.as_bytes().first(), Some(b'L') | Some(b'['))
+}
+
+/// Whether two type signatures are equal. Usable in constants, e.g. by code generated for `#[constructor(signature = "...")]`.
+#[doc(hidden)]
+pub const fn signatures_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+
+    true
+}
+
+/// Length of the concatenation of `parts`. Used with [`concat_signature`] by the signature constants of bridged methods.
+#[doc(hidden)]
+pub const fn signature_len(parts: &[&str]) -> usize {
+    let mut len = 0;
+    let mut i = 0;
+    while i < parts.len() {
+        len += parts[i].len();
+        i += 1;
+    }
+
+    len
+}
+
+/// Concatenation of `parts`, whose length must be `N`. Used by the signature constants of bridged methods.
+#[doc(hidden)]
+pub const fn concat_signature<const N: usize>(parts: &[&str]) -> [u8; N] {
+    let mut bytes = [0; N];
+    let mut pos = 0;
+    let mut i = 0;
+    while i < parts.len() {
+        let part = parts[i].as_bytes();
+        let mut j = 0;
+        while j < part.len() {
+            bytes[pos] = part[j];
+            pos += 1;
+            j += 1;
         }
+        i += 1;
+    }
+
+    bytes
+}
+
+/// `bytes` as a string, for signatures concatenated with [`concat_signature`].
+#[doc(hidden)]
+pub const fn signature_str(bytes: &'static [u8]) -> &'static str {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => s,
+        Err(_) => panic!("signature isn't valid UTF-8"),
+    }
+}
 
+/// Object types that can be stored in Java arrays, enabling conversions between `Box<[T]>` and Java `T[]`.
+///
+/// Arrays are themselves array elements, so that multi-dimensional arrays (e.g. `Box<[Box<[T]>]>` and Java `T[][]`)
+/// are supported up to `1 + NESTED_ARRAY_SIG_TYPES.len()` dimensions.
+///
+/// The [`Signature`] derive macro (and the derives implying it, like `JavaClass`) implements this trait for the deriving
+/// struct or enum, so that arrays of bridged classes are converted in both directions, as parameters and return values of
+/// exported and imported methods alike. Types implementing [`Signature`] manually must implement this trait as well.
+///
+/// `Option<T>` is an array element when `T` is, with `None` stored as `null`. Primitive types aren't: Java arrays of
+/// primitives can't contain `null`, so `Box<[Option<i64>]>` doesn't compile. Use `Vec<Option<i64>>`, which is a
+/// `java.util.ArrayList<Long>`, or `Option<Box<[i64]>>` for a nullable `long[]`:
+/// ```compile_fail
+/// # use robusta_jni::bridge;
+/// #
+/// # #[bridge]
+/// # mod jni {
+///     # use robusta_jni::convert::Signature;
+///     # #[derive(Signature)]
+///     # #[package()]
+///     # struct A;
+///     #
+/// impl A {
+///     pub extern "jni" fn sum(values: Box<[Option<i64>]>) -> i64 {
+///         values.iter().flatten().sum()
+///     }
+/// }
+/// # }
+/// ```
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` can't be stored in a Java object array",
+    note = "arrays of primitives are `Box<[T]>` of the primitive type, and can't contain `null`: for nullable elements use `Vec<Option<T>>`"
+)]
+pub trait ArrayElement: Signature {
+    /// [Java type signature](https://docs.oracle.com/en/java/javase/15/docs/specs/jni/types.html#type-signatures) of an array of the implementing type.
+    const ARRAY_SIG_TYPE: &'static str;
+
+    /// Type signatures of arrays with two or more dimensions of the implementing type, in increasing order of dimensions
+    /// (e.g. `["[[Ljava/lang/String;", "[[[Ljava/lang/String;"]`).
+    const NESTED_ARRAY_SIG_TYPES: &'static [&'static str] = &[];
+
+    /// Class of the array elements, in the format expected by [`JNIEnv::find_class`] and [`crate::loader::find_class`].
+    fn element_class() -> &'static str {
+        Self::SIG_TYPE
+            .strip_prefix('L')
+            .and_then(|s| s.strip_suffix(';'))
+            .unwrap_or(Self::SIG_TYPE)
+    }
+}
+
+impl<T: ArrayElement> Signature for Box<[T]> {
+    const SIG_TYPE: &'static str = T::ARRAY_SIG_TYPE;
+}
+
+impl<T: ArrayElement> ArrayElement for Box<[T]> {
+    const ARRAY_SIG_TYPE: &'static str = T::NESTED_ARRAY_SIG_TYPES[0];
+    const NESTED_ARRAY_SIG_TYPES: &'static [&'static str] =
+        match T::NESTED_ARRAY_SIG_TYPES.split_first() {
+            Some((_, rest)) => rest,
+            None => &[],
+        };
+}
+
+impl<T: ArrayElement> ArrayElement for Option<T> {
+    const ARRAY_SIG_TYPE: &'static str = T::ARRAY_SIG_TYPE;
+    const NESTED_ARRAY_SIG_TYPES: &'static [&'static str] = T::NESTED_ARRAY_SIG_TYPES;
+
+    fn element_class() -> &'static str {
+        T::element_class()
+    }
+}
+
+impl ArrayElement for String {
+    const ARRAY_SIG_TYPE: &'static str = "[Ljava/lang/String;";
+    const NESTED_ARRAY_SIG_TYPES: &'static [&'static str] =
+        &["[[Ljava/lang/String;", "[[[Ljava/lang/String;"];
+}
+
+impl ArrayElement for Box<[bool]> {
+    const ARRAY_SIG_TYPE: &'static str = "[[Z";
+    const NESTED_ARRAY_SIG_TYPES: &'static [&'static str] = &["[[[Z"];
+}
+
+impl ArrayElement for Box<[u8]> {
+    const ARRAY_SIG_TYPE: &'static str = "[[B";
+    const NESTED_ARRAY_SIG_TYPES: &'static [&'static str] = &["[[[B"];
+}
+
+impl ArrayElement for Vec<u8> {
+    const ARRAY_SIG_TYPE: &'static str = "[[B";
+    const NESTED_ARRAY_SIG_TYPES: &'static [&'static str] = &["[[[B"];
+}
+
+impl ArrayElement for Box<[i8]> {
+    const ARRAY_SIG_TYPE: &'static str = "[[B";
+    const NESTED_ARRAY_SIG_TYPES: &'static [&'static str] = &["[[[B"];
+}
+
+/// Signatures of arrays of primitive types other than `boolean` and `byte`, with their nested arrays.
+macro_rules! primitive_arrays {
+    ($($type:ty: $sig:literal),+) => {
+        $(
+            impl Signature for &[$type] {
+                const SIG_TYPE: &'static str = concat!("[", $sig);
+            }
+
+            impl Signature for Box<[$type]> {
+                const SIG_TYPE: &'static str = concat!("[", $sig);
+            }
+
+            impl ArrayElement for Box<[$type]> {
+                const ARRAY_SIG_TYPE: &'static str = concat!("[[", $sig);
+                const NESTED_ARRAY_SIG_TYPES: &'static [&'static str] = &[concat!("[[[", $sig)];
+            }
+        )+
+    };
+}
+
+primitive_arrays! {
+    jchar: "C",
+    jshort: "S",
+    jint: "I",
+    jlong: "J",
+    jfloat: "F",
+    jdouble: "D"
+}
+
+macro_rules! jvalue_types {
+    ($type:ty: $boxed:ident ($sig:ident) [$unbox_method:ident]) => {
         impl<'env> JavaValue<'env> for $type {
             fn autobox(self, env: &JNIEnv<'env>) -> JObject<'env> {
-                env.call_static_method_unchecked(concat!("java/lang/", stringify!($boxed)),
-                    (concat!("java/lang/", stringify!($boxed)), "valueOf", concat!(stringify!(($sig)), "Ljava/lang/", stringify!($boxed), ";")),
-                    ReturnType::from_str(concat!("Ljava/lang/", stringify!($boxed), ";")).unwrap(),
-                    &[JValue::from(self).to_jni()]).unwrap().l().unwrap()
+                pool::autobox(env, self, |value: Self| {
+                    env.call_static_method_unchecked(concat!("java/lang/", stringify!($boxed)),
+                        (concat!("java/lang/", stringify!($boxed)), "valueOf", concat!(stringify!(($sig)), "Ljava/lang/", stringify!($boxed), ";")),
+                        ReturnType::from_str(concat!("Ljava/lang/", stringify!($boxed), ";")).unwrap(),
+                        &[JValue::from(value).to_jni()]).unwrap().l().unwrap()
+                })
             }
 
             fn unbox(s: JObject<'env>, env: &JNIEnv<'env>) -> Self {
@@ -98,6 +418,14 @@ macro_rules! jvalue_types {
                     .unwrap().[<$sig:lower>]()
                     .unwrap()))
             }
+
+            fn into_jvalue(self, _env: &JNIEnv<'env>) -> JValue<'env> {
+                JValue::from(self)
+            }
+
+            fn from_jvalue(value: JValue<'env>, _env: &JNIEnv<'env>) -> jni::errors::Result<Self> {
+                TryFrom::try_from(JValueWrapper::from(value))
+            }
         }
     };
 
@@ -119,16 +447,75 @@ jvalue_types! {
     jshort: Short (S) [shortValue]
 }
 
+macro_rules! primitive_signatures {
+    ($($type:ty: $sig:literal),+) => {
+        $(
+            impl Signature for $type {
+                const SIG_TYPE: &'static str = $sig;
+            }
+        )+
+    };
+}
+
+// `jboolean` is `u8`, which is only converted in byte containers (e.g. `Vec<u8>` and `Box<[u8]>` to `byte[]`): as a
+// value it's the JNI representation of `bool`, so it has no signature (and no conversions) of its own.
+primitive_signatures! {
+    jbyte: "B",
+    jchar: "C",
+    jdouble: "D",
+    jfloat: "F",
+    jint: "I",
+    jlong: "J",
+    jshort: "S"
+}
+
+/// Types of values passed to and returned from JNI functions: [`JavaValue`]s, and `()` for `void`.
+///
+/// This is the bound of the source and target types of the conversion traits, so that `()` can be converted
+/// like other return types. Code that needs to box values (e.g. to add them to a collection) requires a [`JavaValue`] instead.
+pub trait JniValue<'env> {
+    /// Convert instance to a [`JValue`], see [`JavaValue::into_jvalue`].
+    fn into_jvalue(self, env: &JNIEnv<'env>) -> JValue<'env>;
+
+    /// The instance as an object reference, or `None` for primitive values and `void`.
+    fn as_object(&self) -> Option<JObject<'env>>;
+
+    /// Convert a [`JValue`] to the implementing type, see [`JavaValue::from_jvalue`].
+    fn from_jvalue(value: JValue<'env>, env: &JNIEnv<'env>) -> jni::errors::Result<Self>
+    where
+        Self: Sized;
+}
+
+impl<'env, T: JavaValue<'env>> JniValue<'env> for T {
+    fn into_jvalue(self, env: &JNIEnv<'env>) -> JValue<'env> {
+        JavaValue::into_jvalue(self, env)
+    }
+
+    fn as_object(&self) -> Option<JObject<'env>> {
+        JavaValue::as_object(self)
+    }
+
+    fn from_jvalue(value: JValue<'env>, env: &JNIEnv<'env>) -> jni::errors::Result<Self> {
+        JavaValue::from_jvalue(value, env)
+    }
+}
+
 impl Signature for () {
     const SIG_TYPE: &'static str = "V";
 }
 
-impl<'env> JavaValue<'env> for () {
-    fn autobox(self, _env: &JNIEnv<'env>) -> JObject<'env> {
-        panic!("called `JavaValue::autobox` on unit value")
+impl<'env> JniValue<'env> for () {
+    fn into_jvalue(self, _env: &JNIEnv<'env>) -> JValue<'env> {
+        JValue::Void
     }
 
-    fn unbox(_s: JObject<'env>, _env: &JNIEnv<'env>) -> Self {}
+    fn as_object(&self) -> Option<JObject<'env>> {
+        None
+    }
+
+    fn from_jvalue(value: JValue<'env>, _env: &JNIEnv<'env>) -> jni::errors::Result<Self> {
+        TryFrom::try_from(JValueWrapper::from(value))
+    }
 }
 
 impl<'env> Signature for JObject<'env> {
@@ -143,9 +530,14 @@ impl<'env> JavaValue<'env> for JObject<'env> {
     fn unbox(s: JObject<'env>, _env: &JNIEnv<'env>) -> Self {
         s
     }
+
+    fn as_object(&self) -> Option<JObject<'env>> {
+        Some(*self)
+    }
 }
 
 impl<'env> JavaValue<'env> for jobject {
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
     fn autobox(self, _env: &JNIEnv<'env>) -> JObject<'env> {
         unsafe { JObject::from_raw(self) }
     }
@@ -153,6 +545,10 @@ impl<'env> JavaValue<'env> for jobject {
     fn unbox(s: JObject<'env>, _env: &JNIEnv<'env>) -> Self {
         s.into_raw()
     }
+
+    fn as_object(&self) -> Option<JObject<'env>> {
+        Some(unsafe { JObject::from_raw(*self) })
+    }
 }
 
 impl<'env> Signature for JString<'env> {
@@ -167,9 +563,14 @@ impl<'env> JavaValue<'env> for JString<'env> {
     fn unbox(s: JObject<'env>, _env: &JNIEnv<'env>) -> Self {
         From::from(s)
     }
+
+    fn as_object(&self) -> Option<JObject<'env>> {
+        Some(Into::into(*self))
+    }
 }
 
-impl<T: Signature> Signature for jni::errors::Result<T> {
+/// Results returned by exported methods, whose error is thrown, see [`error`]
+impl<T: Signature, E> Signature for std::result::Result<T, E> {
     const SIG_TYPE: &'static str = <T as Signature>::SIG_TYPE;
 }
 
@@ -193,7 +594,7 @@ impl<'a> TryFrom<JValueWrapper<'a>> for jboolean {
     fn try_from(value: JValueWrapper<'a>) -> Result<Self, Self::Error> {
         match value.0 {
             JValue::Bool(b) => Ok(b),
-            _ => Err(Error::WrongJValueType("bool", value.0.type_name()).into()),
+            _ => Err(Error::WrongJValueType("bool", value.0.type_name())),
         }
     }
 }
@@ -204,7 +605,7 @@ impl<'a> TryFrom<JValueWrapper<'a>> for jbyte {
     fn try_from(value: JValueWrapper<'a>) -> Result<Self, Self::Error> {
         match value.0 {
             JValue::Byte(b) => Ok(b),
-            _ => Err(Error::WrongJValueType("byte", value.0.type_name()).into()),
+            _ => Err(Error::WrongJValueType("byte", value.0.type_name())),
         }
     }
 }
@@ -215,7 +616,7 @@ impl<'a> TryFrom<JValueWrapper<'a>> for jchar {
     fn try_from(value: JValueWrapper<'a>) -> Result<Self, Self::Error> {
         match value.0 {
             JValue::Char(c) => Ok(c),
-            _ => Err(Error::WrongJValueType("char", value.0.type_name()).into()),
+            _ => Err(Error::WrongJValueType("char", value.0.type_name())),
         }
     }
 }
@@ -226,7 +627,7 @@ impl<'a> TryFrom<JValueWrapper<'a>> for jdouble {
     fn try_from(value: JValueWrapper<'a>) -> Result<Self, Self::Error> {
         match value.0 {
             JValue::Double(d) => Ok(d),
-            _ => Err(Error::WrongJValueType("double", value.0.type_name()).into()),
+            _ => Err(Error::WrongJValueType("double", value.0.type_name())),
         }
     }
 }
@@ -237,7 +638,7 @@ impl<'a> TryFrom<JValueWrapper<'a>> for jfloat {
     fn try_from(value: JValueWrapper<'a>) -> Result<Self, Self::Error> {
         match value.0 {
             JValue::Float(f) => Ok(f),
-            _ => Err(Error::WrongJValueType("float", value.0.type_name()).into()),
+            _ => Err(Error::WrongJValueType("float", value.0.type_name())),
         }
     }
 }
@@ -248,7 +649,7 @@ impl<'a> TryFrom<JValueWrapper<'a>> for jint {
     fn try_from(value: JValueWrapper<'a>) -> Result<Self, Self::Error> {
         match value.0 {
             JValue::Int(i) => Ok(i),
-            _ => Err(Error::WrongJValueType("int", value.0.type_name()).into()),
+            _ => Err(Error::WrongJValueType("int", value.0.type_name())),
         }
     }
 }
@@ -259,7 +660,7 @@ impl<'a> TryFrom<JValueWrapper<'a>> for jshort {
     fn try_from(value: JValueWrapper<'a>) -> Result<Self, Self::Error> {
         match value.0 {
             JValue::Short(s) => Ok(s),
-            _ => Err(Error::WrongJValueType("short", value.0.type_name()).into()),
+            _ => Err(Error::WrongJValueType("short", value.0.type_name())),
         }
     }
 }
@@ -270,7 +671,7 @@ impl<'a> TryFrom<JValueWrapper<'a>> for jlong {
     fn try_from(value: JValueWrapper<'a>) -> Result<Self, Self::Error> {
         match value.0 {
             JValue::Long(l) => Ok(l),
-            _ => Err(Error::WrongJValueType("long", value.0.type_name()).into()),
+            _ => Err(Error::WrongJValueType("long", value.0.type_name())),
         }
     }
 }
@@ -281,7 +682,7 @@ impl<'a> TryFrom<JValueWrapper<'a>> for () {
     fn try_from(value: JValueWrapper<'a>) -> Result<Self, Self::Error> {
         match value.0 {
             JValue::Void => Ok(()),
-            _ => Err(Error::WrongJValueType("void", value.0.type_name()).into()),
+            _ => Err(Error::WrongJValueType("void", value.0.type_name())),
         }
     }
 }
@@ -292,7 +693,7 @@ impl<'a> TryFrom<JValueWrapper<'a>> for JObject<'a> {
     fn try_from(value: JValueWrapper<'a>) -> Result<Self, Self::Error> {
         match value.0 {
             JValue::Object(o) => Ok(o),
-            _ => Err(Error::WrongJValueType("object", value.0.type_name()).into()),
+            _ => Err(Error::WrongJValueType("object", value.0.type_name())),
         }
     }
 }
@@ -303,7 +704,7 @@ impl<'a> TryFrom<JValueWrapper<'a>> for JString<'a> {
     fn try_from(value: JValueWrapper<'a>) -> Result<Self, Self::Error> {
         match value.0 {
             JValue::Object(o) => Ok(From::from(o)),
-            _ => Err(Error::WrongJValueType("string", value.0.type_name()).into()),
+            _ => Err(Error::WrongJValueType("string", value.0.type_name())),
         }
     }
 }
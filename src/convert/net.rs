@@ -0,0 +1,157 @@
+//! Conversions between Rust and Java network addresses *(`net` feature)*.
+//!
+//! | **Rust**                  | **Java**                        |
+//! |---------------------------|---------------------------------|
+//! | [`IpAddr`]                | `java.net.InetAddress`          |
+//! | [`SocketAddr`]            | `java.net.InetSocketAddress`    |
+//!
+//! Addresses are created with `InetAddress.getByAddress(byte[])` and `new InetSocketAddress(InetAddress, int)`, without
+//! any name lookup, and converted back from `InetAddress.getAddress()` and `InetSocketAddress.getPort()`. The scope ID
+//! of IPv6 socket addresses is kept, their flow info is not.
+//!
+//! Java turns IPv4-mapped IPv6 addresses (e.g. `::ffff:127.0.0.1`) into `Inet4Address`es, which are converted back
+//! to [`IpAddr::V4`]. Unresolved socket addresses (from `InetSocketAddress.createUnresolved`) fail conversion.
+
+use std::convert::TryFrom;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+use jni::errors::{Error, Result as JniResult};
+use jni::objects::{JObject, JValue};
+use jni::JNIEnv;
+
+use crate::convert::{FromJavaValue, IntoJavaValue, Signature, TryFromJavaValue, TryIntoJavaValue};
+
+const INET_ADDRESS_CLASS: &str = "java/net/InetAddress";
+const INET6_ADDRESS_CLASS: &str = "java/net/Inet6Address";
+const INET_SOCKET_ADDRESS_CLASS: &str = "java/net/InetSocketAddress";
+
+/// `InetAddress` of `octets`, with `scope_id` for IPv6 addresses (0 for none).
+fn inet_address<'env>(env: &JNIEnv<'env>, octets: &[u8], scope_id: u32) -> JniResult<JObject<'env>> {
+    let bytes = env.byte_array_from_slice(octets)?;
+    let bytes = unsafe { JObject::from_raw(bytes) };
+
+    if scope_id == 0 {
+        env.call_static_method(INET_ADDRESS_CLASS, "getByAddress", "([B)Ljava/net/InetAddress;", &[JValue::Object(bytes)])?
+            .l()
+    } else {
+        env.call_static_method(
+            INET6_ADDRESS_CLASS,
+            "getByAddress",
+            "(Ljava/lang/String;[BI)Ljava/net/Inet6Address;",
+            &[JValue::Object(JObject::null()), JValue::Object(bytes), JValue::Int(scope_id as i32)],
+        )?
+        .l()
+    }
+}
+
+/// Rust address of an `InetAddress`.
+fn ip_addr(env: &JNIEnv, address: JObject) -> JniResult<IpAddr> {
+    let bytes = env.call_method(address, "getAddress", "()[B", &[])?.l()?;
+    let bytes = env.convert_byte_array(bytes.into_raw())?;
+
+    match bytes.len() {
+        4 => Ok(IpAddr::V4(Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3]))),
+        16 => {
+            let mut octets = [0; 16];
+            octets.copy_from_slice(&bytes);
+            Ok(IpAddr::V6(Ipv6Addr::from(octets)))
+        }
+        _ => Err(Error::WrongJValueType("java.net.InetAddress", "IpAddr")),
+    }
+}
+
+macro_rules! unchecked_via_safe {
+    ($type:ty) => {
+        impl<'env> IntoJavaValue<'env> for $type {
+            type Target = JObject<'env>;
+            const NEW_LOCAL_REF: bool = true;
+
+            fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+                TryIntoJavaValue::try_into(self, env).unwrap()
+            }
+        }
+
+        impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for $type {
+            type Source = JObject<'env>;
+            const KEEPS_SOURCE_REF: bool = false;
+
+            fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+                TryFromJavaValue::try_from(s, env).unwrap()
+            }
+        }
+    };
+}
+
+impl Signature for IpAddr {
+    const SIG_TYPE: &'static str = "Ljava/net/InetAddress;";
+}
+
+impl<'env> TryIntoJavaValue<'env> for IpAddr {
+    type Target = JObject<'env>;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+        match self {
+            IpAddr::V4(ip) => inet_address(env, &ip.octets(), 0),
+            IpAddr::V6(ip) => inet_address(env, &ip.octets(), 0),
+        }
+    }
+}
+
+impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for IpAddr {
+    type Source = JObject<'env>;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+        ip_addr(env, s)
+    }
+}
+
+unchecked_via_safe!(IpAddr);
+
+impl Signature for SocketAddr {
+    const SIG_TYPE: &'static str = "Ljava/net/InetSocketAddress;";
+}
+
+impl<'env> TryIntoJavaValue<'env> for SocketAddr {
+    type Target = JObject<'env>;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+        let address = match self {
+            SocketAddr::V4(addr) => inet_address(env, &addr.ip().octets(), 0)?,
+            SocketAddr::V6(addr) => inet_address(env, &addr.ip().octets(), addr.scope_id())?,
+        };
+
+        env.new_object(
+            INET_SOCKET_ADDRESS_CLASS,
+            "(Ljava/net/InetAddress;I)V",
+            &[JValue::Object(address), JValue::Int(<i32 as From<u16>>::from(self.port()))],
+        )
+    }
+}
+
+impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for SocketAddr {
+    type Source = JObject<'env>;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+        let address = env.call_method(s, "getAddress", "()Ljava/net/InetAddress;", &[])?.l()?;
+        if address.is_null() {
+            return Err(Error::WrongJValueType("unresolved java.net.InetSocketAddress", "SocketAddr"));
+        }
+        let port = env.call_method(s, "getPort", "()I", &[])?.i()?;
+        let port = <u16 as TryFrom<i32>>::try_from(port)
+            .map_err(|_| Error::WrongJValueType("java.net.InetSocketAddress", "SocketAddr"))?;
+
+        match ip_addr(env, address)? {
+            IpAddr::V4(ip) => Ok(SocketAddr::V4(SocketAddrV4::new(ip, port))),
+            IpAddr::V6(ip) => {
+                let scope_id = env.call_method(address, "getScopeId", "()I", &[])?.i()?;
+                Ok(SocketAddr::V6(SocketAddrV6::new(ip, port, 0, scope_id as u32)))
+            }
+        }
+    }
+}
+
+unchecked_via_safe!(SocketAddr);
@@ -0,0 +1,138 @@
+//! Conversions of [`Option<T>`] to and from nullable Java references.
+//!
+//! `Option<T>` has the Java type of `T`: `None` is converted to and from `null`, and `Some(value)` like `value`. This is
+//! how parameters of exported methods accept `null`, which is rejected with a `NullPointerException` for other types
+//! (see [`nulls`](crate::nulls)). Only reference types can be `null`: converting `None` to a primitive type (e.g. an
+//! `Option<i32>` returned as Java `int`) fails.
+//!
+//! Elements of collections are objects, so `None` is `null` there for primitive types too: `Vec<Option<i64>>` is a
+//! `java.util.ArrayList<Long>` which may contain `null`. Arrays of nullable references (e.g. `Box<[Option<String>]>`)
+//! are supported as well, while arrays of primitives can't contain `null` (see [`ArrayElement`](crate::convert::ArrayElement)).
+//!
+//! For `java.util.Optional`, use [`JOptional<T>`](crate::convert::JOptional) instead.
+
+use jni::errors::{Error, Result as JniResult};
+use jni::objects::JObject;
+use jni::JNIEnv;
+
+use crate::convert::{
+    is_reference, FromJavaValue, IntoJavaValue, JavaValue, JniValue, Signature, TryFromJavaValue, TryIntoJavaValue,
+};
+
+impl<T: Signature> Signature for Option<T> {
+    const SIG_TYPE: &'static str = T::SIG_TYPE;
+}
+
+/// Java `null` as a value of conversion target type `U`, for `T` of Java type `sig`.
+fn null<'env, U: JavaValue<'env>>(env: &JNIEnv<'env>, sig: &str) -> JniResult<U> {
+    if is_reference(sig) {
+        Ok(U::unbox(JObject::null(), env))
+    } else {
+        Err(Error::WrongJValueType("reference type", "primitive type"))
+    }
+}
+
+/// Whether `value` is a null reference.
+fn is_null<'env, S: JniValue<'env>>(value: &S) -> bool {
+    value.as_object().is_some_and(|o| o.is_null())
+}
+
+impl<'env, T> TryIntoJavaValue<'env> for Option<T>
+where
+    T: TryIntoJavaValue<'env>,
+    T::Target: JavaValue<'env>,
+{
+    type Target = T::Target;
+    const NEW_LOCAL_REF: bool = T::NEW_LOCAL_REF;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+        match self {
+            Some(value) => TryIntoJavaValue::try_into(value, env),
+            None => null(env, <T as Signature>::SIG_TYPE),
+        }
+    }
+
+    fn try_into_object(self, env: &JNIEnv<'env>) -> JniResult<JObject<'env>> {
+        match self {
+            Some(value) => value.try_into_object(env),
+            None => Ok(JObject::null()),
+        }
+    }
+}
+
+impl<'env, T> IntoJavaValue<'env> for Option<T>
+where
+    T: IntoJavaValue<'env>,
+    T::Target: JavaValue<'env>,
+{
+    type Target = T::Target;
+    const NEW_LOCAL_REF: bool = T::NEW_LOCAL_REF;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        match self {
+            Some(value) => IntoJavaValue::into(value, env),
+            None => null(env, <T as Signature>::SIG_TYPE).unwrap(),
+        }
+    }
+
+    fn into_object(self, env: &JNIEnv<'env>) -> JObject<'env> {
+        match self {
+            Some(value) => value.into_object(env),
+            None => JObject::null(),
+        }
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T> TryFromJavaValue<'env, 'borrow> for Option<T>
+where
+    T: TryFromJavaValue<'env, 'borrow>,
+{
+    type Source = T::Source;
+    const KEEPS_SOURCE_REF: bool = T::KEEPS_SOURCE_REF;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+        if is_null(&s) {
+            Ok(None)
+        } else {
+            TryFromJavaValue::try_from(s, env).map(Some)
+        }
+    }
+
+    fn try_from_object(s: JObject<'env>, env: &'borrow JNIEnv<'env>) -> JniResult<Self>
+    where
+        T::Source: JavaValue<'env>,
+    {
+        if s.is_null() {
+            Ok(None)
+        } else {
+            T::try_from_object(s, env).map(Some)
+        }
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T> FromJavaValue<'env, 'borrow> for Option<T>
+where
+    T: FromJavaValue<'env, 'borrow>,
+{
+    type Source = T::Source;
+    const KEEPS_SOURCE_REF: bool = T::KEEPS_SOURCE_REF;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        if is_null(&s) {
+            None
+        } else {
+            Some(FromJavaValue::from(s, env))
+        }
+    }
+
+    fn from_object(s: JObject<'env>, env: &'borrow JNIEnv<'env>) -> Self
+    where
+        T::Source: JavaValue<'env>,
+    {
+        if s.is_null() {
+            None
+        } else {
+            Some(T::from_object(s, env))
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! Java objects passed through methods as opaque references.
+//!
+//! Binding a Java type usually means deriving (or implementing) the four conversion traits for a struct.
+//! When native code only needs to hold on to an object, or to hand it back to Java, [`Opaque<T>`] can be used instead:
+//! it only requires `T` to implement [`Signature`], which gives the Java type of the reference.
+//! `Opaque` values are converted with all conversion trait families, in both directions, and keep the object as a plain [`JObject`].
+//!
+//! Example:
+//! ```rust
+//! # use robusta_jni::bridge;
+//! use robusta_jni::convert::Signature;
+//!
+//! pub struct Locale;
+//!
+//! impl Signature for Locale {
+//!     const SIG_TYPE: &'static str = "Ljava/util/Locale;";
+//! }
+//!
+//! #[bridge]
+//! mod jni {
+//!     # use robusta_jni::convert::{Opaque, Signature};
+//!     # use robusta_jni::jni::errors::Result as JniResult;
+//!     # use robusta_jni::jni::JNIEnv;
+//!     # use super::Locale;
+//!     #[derive(Signature)]
+//!     #[package(com.example)]
+//!     struct Formatter;
+//!
+//!     impl<'env> Formatter {
+//!         pub extern "jni" fn languageTag(env: &JNIEnv, locale: Opaque<'env, Locale>) -> JniResult<String> {
+//!             let tag = env.call_method(*locale, "toLanguageTag", "()Ljava/lang/String;", &[])?.l()?;
+//!             Ok(env.get_string(tag.into())?.into())
+//!         }
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! Java `null` references are converted to (and from) `Opaque` values wrapping a null [`JObject`], see [`Opaque::is_null`].
+
+use std::fmt::{self, Debug, Formatter};
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+use jni::objects::JObject;
+use jni::JNIEnv;
+
+use crate::convert::{JavaValue, Signature};
+
+/// Reference to a Java object of the type given by `T`'s [`Signature`], without any conversion
+/// (see the [module documentation](self)).
+///
+/// `Opaque` has the same representation as [`JObject`], so it can be used directly in native function signatures.
+#[repr(transparent)]
+pub struct Opaque<'env, T> {
+    object: JObject<'env>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<'env, T> Opaque<'env, T> {
+    /// Wrap `object`, which must be an instance of the Java type of `T` (or null).
+    pub fn new(object: JObject<'env>) -> Self {
+        Opaque {
+            object,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The wrapped object.
+    pub fn as_obj(&self) -> JObject<'env> {
+        self.object
+    }
+
+    /// Unwrap the object.
+    pub fn into_inner(self) -> JObject<'env> {
+        self.object
+    }
+
+    /// Whether the reference is `null`.
+    pub fn is_null(&self) -> bool {
+        self.object.is_null()
+    }
+}
+
+impl<'env, T> Clone for Opaque<'env, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'env, T> Copy for Opaque<'env, T> {}
+
+impl<'env, T> Debug for Opaque<'env, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Opaque").field(&self.object).finish()
+    }
+}
+
+impl<'env, T> Deref for Opaque<'env, T> {
+    type Target = JObject<'env>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.object
+    }
+}
+
+impl<'env, T> From<JObject<'env>> for Opaque<'env, T> {
+    fn from(object: JObject<'env>) -> Self {
+        Opaque::new(object)
+    }
+}
+
+impl<'env, T> From<Opaque<'env, T>> for JObject<'env> {
+    fn from(opaque: Opaque<'env, T>) -> Self {
+        opaque.object
+    }
+}
+
+impl<'env, T: Signature> Signature for Opaque<'env, T> {
+    const SIG_TYPE: &'static str = T::SIG_TYPE;
+}
+
+/// Conversion traits are implemented through the [`JavaValue`] blanket implementations, with `Opaque` itself as source and target type.
+impl<'env, T: Signature> JavaValue<'env> for Opaque<'env, T> {
+    fn autobox(self, _env: &JNIEnv<'env>) -> JObject<'env> {
+        self.object
+    }
+
+    fn unbox(s: JObject<'env>, _env: &JNIEnv<'env>) -> Self {
+        Opaque::new(s)
+    }
+
+    fn as_object(&self) -> Option<JObject<'env>> {
+        Some(self.object)
+    }
+}
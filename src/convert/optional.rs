@@ -0,0 +1,134 @@
+use jni::errors::Result as JniResult;
+use jni::objects::{JObject, JValue};
+use jni::JNIEnv;
+
+use crate::convert::{
+    FromJavaValue, IntoJavaValue, JavaValue, Signature, TryFromJavaValue, TryIntoJavaValue,
+};
+
+const OPTIONAL_CLASS: &str = "java/util/Optional";
+
+/// Wrapper around [`Option<T>`] that is converted to and from a `java.util.Optional<T>`.
+///
+/// `Some(value)` is converted with `Optional.of(value)` (primitives are autoboxed), while `None` is converted with `Optional.empty()`.
+/// A `null` reference is not a valid `Optional` and fails conversion.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct JOptional<T>(pub Option<T>);
+
+impl<T> JOptional<T> {
+    /// Unwrap into the inner [`Option`].
+    pub fn into_option(self) -> Option<T> {
+        self.0
+    }
+}
+
+impl<T> From<Option<T>> for JOptional<T> {
+    fn from(o: Option<T>) -> Self {
+        JOptional(o)
+    }
+}
+
+impl<T> From<JOptional<T>> for Option<T> {
+    fn from(o: JOptional<T>) -> Self {
+        o.0
+    }
+}
+
+impl<T> Signature for JOptional<T> {
+    const SIG_TYPE: &'static str = "Ljava/util/Optional;";
+}
+
+fn optional_of<'env>(env: &JNIEnv<'env>, value: Option<JObject<'env>>) -> JniResult<JObject<'env>> {
+    match value {
+        Some(value) => env
+            .call_static_method(
+                OPTIONAL_CLASS,
+                "of",
+                "(Ljava/lang/Object;)Ljava/util/Optional;",
+                &[JValue::Object(value)],
+            )?
+            .l(),
+        None => env
+            .call_static_method(OPTIONAL_CLASS, "empty", "()Ljava/util/Optional;", &[])?
+            .l(),
+    }
+}
+
+fn optional_get<'env>(env: &JNIEnv<'env>, optional: JObject<'env>) -> JniResult<Option<JObject<'env>>> {
+    if env.call_method(optional, "isPresent", "()Z", &[])?.z()? {
+        Ok(Some(
+            env.call_method(optional, "get", "()Ljava/lang/Object;", &[])?
+                .l()?,
+        ))
+    } else {
+        Ok(None)
+    }
+}
+
+impl<'env, T> TryIntoJavaValue<'env> for JOptional<T>
+where
+    T: TryIntoJavaValue<'env>,
+    T::Target: JavaValue<'env>,
+{
+    type Target = JObject<'env>;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+        let value = match self.0 {
+            Some(v) => Some(JavaValue::autobox(TryIntoJavaValue::try_into(v, env)?, env)),
+            None => None,
+        };
+
+        optional_of(env, value)
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T, U> TryFromJavaValue<'env, 'borrow> for JOptional<T>
+where
+    T: TryFromJavaValue<'env, 'borrow, Source = U>,
+    U: JavaValue<'env>,
+{
+    type Source = JObject<'env>;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+        match optional_get(env, s)? {
+            Some(v) => Ok(JOptional(Some(T::try_from(U::unbox(v, env), env)?))),
+            None => Ok(JOptional(None)),
+        }
+    }
+}
+
+impl<'env, T> IntoJavaValue<'env> for JOptional<T>
+where
+    T: IntoJavaValue<'env>,
+    T::Target: JavaValue<'env>,
+{
+    type Target = JObject<'env>;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        let value = self
+            .0
+            .map(|v| JavaValue::autobox(IntoJavaValue::into(v, env), env));
+
+        optional_of(env, value).unwrap()
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T, U> FromJavaValue<'env, 'borrow> for JOptional<T>
+where
+    T: FromJavaValue<'env, 'borrow, Source = U>,
+    U: JavaValue<'env>,
+{
+    type Source = JObject<'env>;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        JOptional(
+            optional_get(env, s)
+                .unwrap()
+                .map(|v| T::from(U::unbox(v, env), env)),
+        )
+    }
+}
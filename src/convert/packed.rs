@@ -0,0 +1,253 @@
+//! Numeric lists converted without boxing each element from Rust.
+//!
+//! A `Vec<i32>` is converted to a `java.util.ArrayList<Integer>` one element at a time: each element is boxed with a
+//! call to `Integer.valueOf` and added with a call to `add`, so the number of JNI calls grows with the length of the list.
+//! [`PackedList<T>`] converts to and from the same Java type, but copies the elements through a primitive array in a
+//! single call, and lets Java code box and unbox them (with `Arrays.stream(...).boxed()` and
+//! `List.stream().mapToInt(...)`), so that the number of JNI calls is constant.
+//!
+//! `PackedList<T>` is available for `i32`, `i64` and `f64` elements (the primitive types with a Java stream type).
+//! It is slower than a plain `Vec` for very short lists, as it always makes a few calls; see the `packed` benchmark of
+//! `robusta-example`:
+//! ```text
+//! cargo bench -p robusta-example --bench packed
+//! ```
+//!
+//! If the Java side can use a primitive array (`int[]`) instead of a list, [`JArray<T>`](crate::convert::JArray) avoids
+//! boxing altogether.
+
+use std::ops::{Deref, DerefMut};
+use std::sync::OnceLock;
+
+use jni::errors::Result as JniResult;
+use jni::objects::{GlobalRef, JObject, JValue};
+use jni::sys::jobject;
+use jni::JNIEnv;
+
+use crate::convert::{FromJavaValue, IntoJavaValue, Signature, TryFromJavaValue, TryIntoJavaValue};
+
+/// Wrapper around a [`Vec<T>`] of numbers converted to and from a `java.util.ArrayList` with a constant number of JNI
+/// calls, see the [module documentation](self).
+///
+/// Example:
+/// ```rust
+/// # use robusta_jni::bridge;
+/// #
+/// # #[bridge]
+/// # mod jni {
+///     # use robusta_jni::convert::{PackedList, Signature};
+///     # #[derive(Signature)]
+///     # #[package(com.example)]
+///     # struct Stats;
+/// impl Stats {
+///     // Java: `static native ArrayList<Integer> cumulative(ArrayList<Integer> values)`
+///     pub extern "jni" fn cumulative(mut values: PackedList<i32>) -> PackedList<i32> {
+///         for i in 1..values.len() {
+///             values[i] += values[i - 1];
+///         }
+///         values
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PackedList<T>(pub Vec<T>);
+
+impl<T> PackedList<T> {
+    /// Unwrap into the inner [`Vec`].
+    pub fn into_vec(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<T> From<Vec<T>> for PackedList<T> {
+    fn from(v: Vec<T>) -> Self {
+        PackedList(v)
+    }
+}
+
+impl<T> From<PackedList<T>> for Vec<T> {
+    fn from(l: PackedList<T>) -> Self {
+        l.0
+    }
+}
+
+impl<T> Deref for PackedList<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for PackedList<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Elements of a [`PackedList`].
+pub trait PackedElement: Sized {
+    /// Boxed Java array of the elements: `Integer[]` for `i32`.
+    #[doc(hidden)]
+    fn to_boxed_array<'env>(values: &[Self], env: &JNIEnv<'env>) -> JniResult<JObject<'env>>;
+
+    /// Unboxed elements of a `java.util.List`.
+    #[doc(hidden)]
+    fn from_list(list: JObject, env: &JNIEnv) -> JniResult<Vec<Self>>;
+}
+
+/// Global reference to an instance of functional interface `interface` calling method `method` of `class`, which returns
+/// the primitive type of boxed class `return_type` (e.g. `Integer.intValue` as a `ToIntFunction`).
+fn method_function(
+    env: &JNIEnv,
+    interface: &str,
+    class: &str,
+    method: &str,
+    return_type: &str,
+) -> JniResult<GlobalRef> {
+    let lookup = env
+        .call_static_method(
+            "java/lang/invoke/MethodHandles",
+            "publicLookup",
+            "()Ljava/lang/invoke/MethodHandles$Lookup;",
+            &[],
+        )?
+        .l()?;
+    let return_type = env.get_static_field(return_type, "TYPE", "Ljava/lang/Class;")?.l()?;
+    let method_type = env
+        .call_static_method(
+            "java/lang/invoke/MethodType",
+            "methodType",
+            "(Ljava/lang/Class;)Ljava/lang/invoke/MethodType;",
+            &[JValue::Object(return_type)],
+        )?
+        .l()?;
+    let class = env.find_class(class)?;
+    let method = env.new_string(method)?;
+    let handle = env
+        .call_method(
+            lookup,
+            "findVirtual",
+            "(Ljava/lang/Class;Ljava/lang/String;Ljava/lang/invoke/MethodType;)Ljava/lang/invoke/MethodHandle;",
+            &[JValue::Object(*class), JValue::Object(*method), JValue::Object(method_type)],
+        )?
+        .l()?;
+    let interface = env.find_class(interface)?;
+    let function = env
+        .call_static_method(
+            "java/lang/invoke/MethodHandleProxies",
+            "asInterfaceInstance",
+            "(Ljava/lang/Class;Ljava/lang/invoke/MethodHandle;)Ljava/lang/Object;",
+            &[JValue::Object(*interface), JValue::Object(handle)],
+        )?
+        .l()?;
+
+    env.new_global_ref(function)
+}
+
+macro_rules! packed_elements {
+    ($($type:ty: $boxed:literal, $stream:literal, $function:literal, $map:literal, $unbox:literal, $sig:literal;)*) => {
+        $(
+            impl PackedElement for $type {
+                fn to_boxed_array<'env>(values: &[Self], env: &JNIEnv<'env>) -> JniResult<JObject<'env>> {
+                    let array = TryIntoJavaValue::try_into(values, env)?;
+                    let stream = env
+                        .call_static_method(
+                            "java/util/Arrays",
+                            "stream",
+                            concat!("([", $sig, ")L", $stream, ";"),
+                            &[JValue::Object(unsafe { JObject::from_raw(array) })],
+                        )?
+                        .l()?;
+                    let boxed = env.call_method(stream, "boxed", "()Ljava/util/stream/Stream;", &[])?.l()?;
+
+                    env.call_method(boxed, "toArray", "()[Ljava/lang/Object;", &[])?.l()
+                }
+
+                fn from_list(list: JObject, env: &JNIEnv) -> JniResult<Vec<Self>> {
+                    static UNBOX: OnceLock<GlobalRef> = OnceLock::new();
+                    let unbox = match UNBOX.get() {
+                        Some(unbox) => unbox,
+                        None => {
+                            let unbox = method_function(env, $function, $boxed, $unbox, $boxed)?;
+                            UNBOX.get_or_init(|| unbox)
+                        }
+                    };
+
+                    let stream = env.call_method(list, "stream", "()Ljava/util/stream/Stream;", &[])?.l()?;
+                    let mapped = env
+                        .call_method(
+                            stream,
+                            $map,
+                            concat!("(L", $function, ";)L", $stream, ";"),
+                            &[JValue::Object(unbox.as_obj())],
+                        )?
+                        .l()?;
+                    let array = env.call_method(mapped, "toArray", concat!("()[", $sig), &[])?.l()?;
+                    let values: Box<[$type]> = TryFromJavaValue::try_from(array.into_raw(), env)?;
+
+                    Ok(values.into_vec())
+                }
+            }
+        )*
+    };
+}
+
+packed_elements! {
+    i32: "java/lang/Integer", "java/util/stream/IntStream", "java/util/function/ToIntFunction", "mapToInt", "intValue", "I";
+    i64: "java/lang/Long", "java/util/stream/LongStream", "java/util/function/ToLongFunction", "mapToLong", "longValue", "J";
+    f64: "java/lang/Double", "java/util/stream/DoubleStream", "java/util/function/ToDoubleFunction", "mapToDouble", "doubleValue", "D";
+}
+
+impl<T> Signature for PackedList<T> {
+    const SIG_TYPE: &'static str = "Ljava/util/ArrayList;";
+}
+
+impl<'env, T: PackedElement> TryIntoJavaValue<'env> for PackedList<T> {
+    type Target = jobject;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+        let array = T::to_boxed_array(&self.0, env)?;
+        let list = env
+            .call_static_method(
+                "java/util/Arrays",
+                "asList",
+                "([Ljava/lang/Object;)Ljava/util/List;",
+                &[JValue::Object(array)],
+            )?
+            .l()?;
+
+        // `Arrays.asList` is backed by the array, copy it so that elements can be added like with `Vec`
+        env.new_object("java/util/ArrayList", "(Ljava/util/Collection;)V", &[JValue::Object(list)])
+            .map(JObject::into_raw)
+    }
+}
+
+impl<'env, T: PackedElement> IntoJavaValue<'env> for PackedList<T> {
+    type Target = jobject;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        TryIntoJavaValue::try_into(self, env).unwrap()
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T: PackedElement> TryFromJavaValue<'env, 'borrow> for PackedList<T> {
+    type Source = JObject<'env>;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+        T::from_list(s, env).map(PackedList)
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T: PackedElement> FromJavaValue<'env, 'borrow> for PackedList<T> {
+    type Source = JObject<'env>;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        TryFromJavaValue::try_from(s, env).unwrap()
+    }
+}
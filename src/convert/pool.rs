@@ -0,0 +1,269 @@
+//! Caching of Java objects and classes created by conversions.
+//!
+//! Requires the `pool` feature. When enabled:
+//!  * autoboxing a `byte`, `short`, `int`, `long` or `char` value interned by `valueOf` (`-128..=127`, `0..=127` for `char`),
+//!    or a `boolean`, returns a new local reference to a cached object instead of calling `valueOf`
+//!  * `Vec` conversions create their `java.util.ArrayList` with a cached class reference, instead of looking the class up by name
+//!
+//! Cached boxes are the instances returned by `valueOf`, so identity comparisons on the Java side are unaffected.
+//! They are kept in global references, which are valid on every thread, so the cache is shared by all threads
+//! and filled on first use of each value. It is never released.
+//!
+//! Lists are only reused when asked for, with [`reuse_lists`]: a list handed to Java code can be retained by it, so
+//! clearing it for another conversion would change data the application still holds. Lists created by `Vec`
+//! conversions in the closure given to `reuse_lists` are taken from a pool, and given back to it when the closure
+//! returns, so they must not be used by Java code afterwards. This suits lists passed to Java methods which only read
+//! them during the call, e.g. in a loop of calls:
+//! ```rust,no_run
+//! # use robusta_jni::convert::pool::reuse_lists;
+//! # use robusta_jni::convert::TryIntoJavaValue;
+//! # use robusta_jni::jni::JNIEnv;
+//! # fn process(env: &JNIEnv, batches: Vec<Vec<i32>>) -> robusta_jni::jni::errors::Result<()> {
+//! for batch in batches {
+//!     reuse_lists(|| -> robusta_jni::jni::errors::Result<()> {
+//!         let list = TryIntoJavaValue::try_into(batch, env)?;
+//!         // ... pass `list` to a Java method which doesn't keep it
+//!         # let _ = list;
+//!         Ok(())
+//!     })?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! When the feature is disabled, conversions are unchanged, and `reuse_lists` just calls the closure.
+//! The `pool` benchmark of `robusta-example` measures the difference:
+//! ```text
+//! cargo bench -p robusta-example --bench pool
+//! ```
+
+#[cfg(feature = "pool")]
+use std::cell::RefCell;
+#[cfg(feature = "pool")]
+use std::sync::{Mutex, OnceLock};
+
+use jni::errors::Result as JniResult;
+#[cfg(feature = "pool")]
+use jni::objects::GlobalRef;
+use jni::objects::{JObject, JValue};
+#[cfg(feature = "pool")]
+use jni::sys::{jboolean, jbyte, jchar, jdouble, jfloat, jint, jlong, jshort};
+use jni::JNIEnv;
+
+#[cfg(feature = "pool")]
+use crate::convert::ClassCache;
+
+/// Boxed objects of the 256 values of a primitive type that can be interned, indexed by [`Interned::cache_index`].
+#[cfg(feature = "pool")]
+pub(crate) struct BoxCache {
+    values: [OnceLock<GlobalRef>; 256],
+}
+
+#[cfg(feature = "pool")]
+impl BoxCache {
+    const fn new() -> Self {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const EMPTY: OnceLock<GlobalRef> = OnceLock::new();
+
+        BoxCache { values: [EMPTY; 256] }
+    }
+}
+
+/// Primitive types whose boxed objects are cached.
+#[cfg(feature = "pool")]
+pub(crate) trait Interned: Copy {
+    /// Index of the value in [`Interned::cache`], if `valueOf` interns it.
+    fn cache_index(self) -> Option<usize>;
+
+    fn cache() -> &'static BoxCache;
+}
+
+#[cfg(feature = "pool")]
+macro_rules! interned_types {
+    ($($type:ty),+) => {
+        $(
+            impl Interned for $type {
+                fn cache_index(self) -> Option<usize> {
+                    let value = self as i64;
+                    if (-128..=127).contains(&value) {
+                        Some((value + 128) as usize)
+                    } else {
+                        None
+                    }
+                }
+
+                fn cache() -> &'static BoxCache {
+                    static CACHE: BoxCache = BoxCache::new();
+                    &CACHE
+                }
+            }
+        )+
+    };
+}
+
+#[cfg(feature = "pool")]
+interned_types!(jboolean, jbyte, jchar, jint, jlong, jshort);
+
+// `valueOf` doesn't intern floating point values
+#[cfg(feature = "pool")]
+macro_rules! uninterned_types {
+    ($($type:ty),+) => {
+        $(
+            impl Interned for $type {
+                fn cache_index(self) -> Option<usize> {
+                    None
+                }
+
+                fn cache() -> &'static BoxCache {
+                    unreachable!("values of {} are never cached", stringify!($type))
+                }
+            }
+        )+
+    };
+}
+
+#[cfg(feature = "pool")]
+uninterned_types!(jdouble, jfloat);
+
+/// Boxed `value`, from the cache if possible. `create` boxes the value with `valueOf`.
+#[cfg(feature = "pool")]
+pub(crate) fn autobox<'env, T, F>(env: &JNIEnv<'env>, value: T, create: F) -> JObject<'env>
+where
+    T: Interned,
+    F: FnOnce(T) -> JObject<'env>,
+{
+    let entry = match value.cache_index() {
+        Some(index) => &T::cache().values[index],
+        None => return create(value),
+    };
+
+    if let Some(global) = entry.get() {
+        if let Ok(local) = env.new_local_ref(global.as_obj()) {
+            return local;
+        }
+    }
+
+    let boxed = create(value);
+    if entry.get().is_none() {
+        // Another thread may have cached the value in the meantime, in which case the new reference is just dropped
+        if let Ok(global) = env.new_global_ref(boxed) {
+            let _ = entry.set(global);
+        }
+    }
+
+    boxed
+}
+
+#[cfg(not(feature = "pool"))]
+#[inline(always)]
+pub(crate) fn autobox<'env, T, F>(_env: &JNIEnv<'env>, value: T, create: F) -> JObject<'env>
+where
+    F: FnOnce(T) -> JObject<'env>,
+{
+    create(value)
+}
+
+/// New `java.util.ArrayList` with initial capacity `capacity`, or an empty list from the pool if lists are reused.
+#[cfg(feature = "pool")]
+pub(crate) fn new_array_list<'env>(env: &JNIEnv<'env>, capacity: usize) -> JniResult<JObject<'env>> {
+    match reused_list(env, capacity)? {
+        Some(list) => Ok(list),
+        None => create_array_list(env, capacity),
+    }
+}
+
+#[cfg(feature = "pool")]
+fn create_array_list<'env>(env: &JNIEnv<'env>, capacity: usize) -> JniResult<JObject<'env>> {
+    static ARRAY_LIST: ClassCache = ClassCache::new();
+
+    let class = env.auto_local(ARRAY_LIST.get(env, "java/util/ArrayList")?);
+    env.new_object(&class, "(I)V", &[JValue::Int(capacity as i32)])
+}
+
+#[cfg(not(feature = "pool"))]
+#[inline(always)]
+pub(crate) fn new_array_list<'env>(env: &JNIEnv<'env>, capacity: usize) -> JniResult<JObject<'env>> {
+    env.new_object("java/util/ArrayList", "(I)V", &[JValue::Int(capacity as i32)])
+}
+
+/// Lists given back to the pool by [`reuse_lists`], at most [`MAX_POOLED_LISTS`].
+#[cfg(feature = "pool")]
+static POOLED_LISTS: Mutex<Vec<GlobalRef>> = Mutex::new(Vec::new());
+
+#[cfg(feature = "pool")]
+const MAX_POOLED_LISTS: usize = 64;
+
+#[cfg(feature = "pool")]
+thread_local! {
+    /// Lists taken from the pool by the outermost [`reuse_lists`] call of the thread, if any
+    static REUSING_LISTS: RefCell<Option<Vec<GlobalRef>>> = const { RefCell::new(None) };
+}
+
+/// Call `f`, reusing the `java.util.ArrayList`s created by `Vec` conversions in it, see the
+/// [module documentation](self). The lists must not be used by Java code once `f` returns.
+///
+/// Nested calls reuse lists until the outermost one returns.
+#[cfg(feature = "pool")]
+pub fn reuse_lists<R>(f: impl FnOnce() -> R) -> R {
+    let outermost = REUSING_LISTS.with(|lists| {
+        let mut lists = lists.borrow_mut();
+        let outermost = lists.is_none();
+        if outermost {
+            *lists = Some(Vec::new());
+        }
+        outermost
+    });
+    if !outermost {
+        return f();
+    }
+
+    /// Gives the lists back to the pool, even if `f` panics
+    struct Scope;
+    impl Drop for Scope {
+        fn drop(&mut self) {
+            let lists = REUSING_LISTS.with(|lists| lists.borrow_mut().take()).unwrap_or_default();
+            let mut pooled = POOLED_LISTS.lock().unwrap();
+            let free = MAX_POOLED_LISTS.saturating_sub(pooled.len());
+            pooled.extend(lists.into_iter().take(free));
+        }
+    }
+
+    let _scope = Scope;
+    f()
+}
+
+#[cfg(not(feature = "pool"))]
+#[inline(always)]
+pub fn reuse_lists<R>(f: impl FnOnce() -> R) -> R {
+    f()
+}
+
+/// Empty list from the pool, if lists are reused.
+#[cfg(feature = "pool")]
+fn reused_list<'env>(env: &JNIEnv<'env>, capacity: usize) -> JniResult<Option<JObject<'env>>> {
+    if REUSING_LISTS.with(|lists| lists.borrow().is_none()) {
+        return Ok(None);
+    }
+
+    let pooled = POOLED_LISTS.lock().unwrap().pop();
+    let list = match pooled {
+        Some(list) => {
+            env.call_method(list.as_obj(), "clear", "()V", &[])?;
+            list
+        }
+        None => {
+            let list = env.auto_local(create_array_list(env, capacity)?);
+            env.new_global_ref(&list)?
+        }
+    };
+    let local = env.new_local_ref(list.as_obj())?;
+    REUSING_LISTS.with(|lists| lists.borrow_mut().get_or_insert_with(Vec::new).push(list));
+
+    Ok(Some(local))
+}
+
+/// Release the pooled lists.
+#[cfg(feature = "pool")]
+pub(crate) fn clear_pooled_lists() {
+    POOLED_LISTS.lock().unwrap().clear();
+}
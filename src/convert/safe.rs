@@ -14,15 +14,41 @@
 //! ```
 //!
 //! Both of these parameters are optional. By default, the exception class is `java.lang.RuntimeException`.
+//! The cause appended to the message is a [`ConversionError`](crate::convert::ConversionError), which names the parameter
+//! that failed conversion along with its Rust and Java types.
 //!
+//! The source chain of the error is kept as the cause of the exception (see [`error`](crate::convert::error)), with each
+//! level wrapped in a `java.lang.RuntimeException`, or in the class given with the `cause_class` parameter:
+//!
+//! ```ignore
+//! #[call_type(safe(cause_class = "com.example.RustException"))]
+//! ```
+//!
+//! Exported methods modeling a Java checked exception can declare it with `#[throws]`, which takes precedence over
+//! `exception_class`. The class is also listed in the [symbol manifest](crate::symbols) of the method, so that the
+//! `throws` clause of the Java declaration can be checked or generated:
+//!
+//! ```ignore
+//! // Java: `static native String readConfig(String path) throws IOException`
+//! #[throws("java.io.IOException")]
+//! pub extern "jni" fn readConfig(path: String) -> JniResult<String> { ... }
+//! ```
+//!
+
+use std::borrow::Cow;
 
 use jni::errors::{Error, Result};
-use jni::objects::{JList, JObject, JString, JValue};
-use jni::sys::{jboolean, jbooleanArray, jbyteArray, jchar, jobject};
+use jni::objects::{JList, JObject, JString};
+use jni::sys::{
+    jboolean, jbooleanArray, jbyteArray, jchar, jcharArray, jdouble, jdoubleArray, jfloat, jfloatArray, jint, jintArray,
+    jlong, jlongArray, jobject, jobjectArray, jshort, jshortArray,
+};
 use jni::JNIEnv;
+use paste::paste;
 
+use crate::convert::{metrics, pool};
 use crate::convert::unchecked::{FromJavaValue, IntoJavaValue};
-use crate::convert::{JavaValue, Signature};
+use crate::convert::{is_reference, ArrayElement, JavaValue, JniValue, Signature};
 
 pub use robusta_codegen::{TryFromJavaValue, TryIntoJavaValue};
 
@@ -37,14 +63,32 @@ pub use robusta_codegen::{TryFromJavaValue, TryIntoJavaValue};
 ///
 pub trait TryIntoJavaValue<'env>: Signature {
     /// Conversion target type.
-    type Target: JavaValue<'env>;
+    type Target: JniValue<'env>;
 
     /// [Signature](https://docs.oracle.com/en/java/javase/15/docs/specs/jni/types.html#type-signatures) of the source type.
     /// By default, use the one defined on the [`Signature`] trait for the implementing type.
     const SIG_TYPE: &'static str = <Self as Signature>::SIG_TYPE;
 
+    /// Whether the converted value is a new local reference owned by the caller (e.g. the `java.lang.String` created
+    /// from a Rust `String`), rather than one held elsewhere (e.g. the [`JObject`] being converted, or a global reference).
+    ///
+    /// Collections delete new local references to their elements once stored, so that converting large collections
+    /// doesn't exhaust the local reference table. By default they're kept until the native method returns.
+    const NEW_LOCAL_REF: bool = false;
+
     /// Perform the conversion.
     fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target>;
+
+    /// Convert to an object, as stored in collections: primitive values are boxed, and `None` is `null` even for
+    /// primitive types (e.g. `Vec<Option<i64>>` is a `java.util.ArrayList<Long>` which may contain `null`).
+    #[doc(hidden)]
+    fn try_into_object(self, env: &JNIEnv<'env>) -> Result<JObject<'env>>
+    where
+        Self: Sized,
+        Self::Target: JavaValue<'env>,
+    {
+        TryIntoJavaValue::try_into(self, env).map(|value| JavaValue::autobox(value, env))
+    }
 }
 
 /// Conversion trait from Java values to Rust values, analogous to [TryFrom](std::convert::TryInto). Used when converting types that are input to JNI-available functions.
@@ -79,6 +123,27 @@ pub trait TryIntoJavaValue<'env>: Signature {
 /// # }
 /// ```
 ///
+/// Structs without an `#[instance]` field and without lifetime parameters are converted by value instead: their fields
+/// are read from the Java object during conversion, and the local reference to it is dropped along with the source value,
+/// so the struct doesn't keep the Java object alive. This suits plain data types, such as:
+///
+/// ```rust
+/// # use robusta_jni::bridge;
+/// #
+/// # #[bridge]
+/// # mod jni {
+///     # use robusta_jni::convert::{Signature, TryFromJavaValue};
+/// #[derive(Signature, TryFromJavaValue)]
+/// #[package(com.example)]
+/// struct Point {
+///     x: i32,
+///     y: i32
+/// }
+/// # }
+/// ```
+///
+/// Such structs can't have `#[field]`, `Lazy` or `#[writeback]` fields, and can't be used as `&mut self` receivers.
+///
 /// [`AutoLocal`]: jni::objects::AutoLocal
 ///
 pub trait TryFromJavaValue<'env: 'borrow, 'borrow>
@@ -86,14 +151,92 @@ where
     Self: Sized + Signature,
 {
     /// Conversion source type.
-    type Source: JavaValue<'env>;
+    type Source: JniValue<'env>;
 
     /// [Signature](https://docs.oracle.com/en/java/javase/15/docs/specs/jni/types.html#type-signatures) of the target type.
     /// By default, use the one defined on the [`Signature`] trait for the implementing type.
     const SIG_TYPE: &'static str = <Self as Signature>::SIG_TYPE;
 
+    /// Whether the converted value keeps using the local reference it was converted from, like a [`JObject`] or a
+    /// struct with an `#[instance]` field.
+    ///
+    /// Collections delete the local references to their elements once converted, unless they're kept, so that
+    /// converting large collections doesn't exhaust the local reference table. By default they're kept until the native
+    /// method returns.
+    const KEEPS_SOURCE_REF: bool = true;
+
     /// Perform the conversion.
     fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Result<Self>;
+
+    /// Convert from an object, as stored in collections: boxed primitive values are unboxed, see
+    /// [`TryIntoJavaValue::try_into_object`].
+    #[doc(hidden)]
+    fn try_from_object(s: JObject<'env>, env: &'borrow JNIEnv<'env>) -> Result<Self>
+    where
+        Self::Source: JavaValue<'env>,
+    {
+        TryFromJavaValue::try_from(JavaValue::unbox(s, env), env)
+    }
+}
+
+/// Convert the value returned by a call to a Java method, deleting its local reference once converted unless `T` keeps
+/// it (see [`TryFromJavaValue::KEEPS_SOURCE_REF`]). Used by `extern "java"` methods.
+#[doc(hidden)]
+pub fn try_from_returned<'env: 'borrow, 'borrow, T>(value: T::Source, env: &'borrow JNIEnv<'env>) -> Result<T>
+where
+    T: TryFromJavaValue<'env, 'borrow>,
+{
+    let owned = (!T::KEEPS_SOURCE_REF).then(|| JniValue::as_object(&value)).flatten();
+    let converted = T::try_from(value, env);
+    if let Some(object) = owned.filter(|o| !o.is_null()) {
+        env.delete_local_ref(object)?;
+    }
+    converted
+}
+
+/// Write-back trait for Rust values converted from a Java instance. Used when an exported method takes a `&mut self` receiver.
+///
+/// After the method body completes, the (possibly modified) receiver is written back to the Java object it was converted from,
+/// so that changes made from Rust are visible on the Java side.
+///
+/// # Notes on the derive macro
+/// An implementation is generated by the [`TryFromJavaValue`] derive macro.
+/// Data fields annotated with `#[writeback]` are written to the Java instance field with the same name, while all other fields are left untouched.
+/// `#[field]` fields don't need the attribute, as [`Field`] handles already read and write the Java field directly.
+/// [`Lazy`] fields are never written back.
+///
+/// Example:
+///
+/// ```rust
+/// # use robusta_jni::bridge;
+/// #
+/// # #[bridge]
+/// # mod jni {
+///     # use robusta_jni::convert::{Signature, TryFromJavaValue};
+///     # use robusta_jni::jni::objects::AutoLocal;
+/// #[derive(Signature, TryFromJavaValue)]
+/// #[package()]
+/// struct A<'env: 'borrow, 'borrow> {
+///     #[instance]
+///     raw: AutoLocal<'env, 'borrow>,
+///     #[writeback]
+///     counter: i32
+/// }
+///
+/// impl<'env: 'borrow, 'borrow> A<'env, 'borrow> {
+///     pub extern "jni" fn increment(&mut self) {
+///         self.counter += 1; // visible from Java after the call
+///     }
+/// }
+/// # }
+/// ```
+///
+/// [`Field`]: crate::convert::Field
+/// [`Lazy`]: crate::convert::Lazy
+///
+pub trait TryWriteBack<'env> {
+    /// Write the value back to the Java object it was converted from.
+    fn try_write_back(self, env: &JNIEnv<'env>) -> Result<()>;
 }
 
 impl<'env, T> TryIntoJavaValue<'env> for T
@@ -118,20 +261,72 @@ where
     }
 }
 
+impl<'env> TryIntoJavaValue<'env> for () {
+    type Target = ();
+
+    fn try_into(self, _env: &JNIEnv<'env>) -> Result<Self::Target> {
+        Ok(())
+    }
+}
+
+impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for () {
+    type Source = ();
+
+    fn try_from(_s: Self::Source, _env: &'borrow JNIEnv<'env>) -> Result<Self> {
+        Ok(())
+    }
+}
+
 impl<'env> TryIntoJavaValue<'env> for String {
     type Target = JString<'env>;
+    const NEW_LOCAL_REF: bool = true;
     const SIG_TYPE: &'static str = "Ljava/lang/String;";
 
     fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
-        env.new_string(self)
+        TryIntoJavaValue::try_into(self.as_str(), env)
+    }
+}
+
+/// String slices are converted without an intermediate `String`, e.g. when passed to `extern "java"` methods.
+impl<'env> TryIntoJavaValue<'env> for &str {
+    type Target = JString<'env>;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        let s = env.new_string(self)?;
+        metrics::record_to_java(<String as Signature>::SIG_TYPE, self.len(), self.len());
+        Ok(s)
+    }
+}
+
+impl<'env> TryIntoJavaValue<'env> for &String {
+    type Target = JString<'env>;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        TryIntoJavaValue::try_into(self.as_str(), env)
+    }
+}
+
+/// Borrowed strings are copied straight into the Java string, so returning e.g. `Cow::Borrowed` from a `&'static str`
+/// doesn't allocate a Rust `String` first.
+impl<'env> TryIntoJavaValue<'env> for Cow<'_, str> {
+    type Target = JString<'env>;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        TryIntoJavaValue::try_into(self.as_ref(), env)
     }
 }
 
 impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for String {
     type Source = JString<'env>;
+    const KEEPS_SOURCE_REF: bool = false;
 
     fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Result<Self> {
-        env.get_string(s).map(Into::into)
+        let s: String = env.get_string(s)?.into();
+        metrics::record_from_java(<String as Signature>::SIG_TYPE, s.len(), s.len());
+        Ok(s)
     }
 }
 
@@ -178,26 +373,30 @@ impl Signature for Box<[bool]> {
 
 impl<'env> TryIntoJavaValue<'env> for Box<[bool]> {
     type Target = jbooleanArray;
+    const NEW_LOCAL_REF: bool = true;
 
     fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
         let len = self.len();
         let buf: Vec<_> = self.iter().map(|&b| Into::into(b)).collect();
         let raw = env.new_boolean_array(len as i32)?;
         env.set_boolean_array_region(raw, 0, &buf)?;
+        metrics::record_to_java(<Self as Signature>::SIG_TYPE, len, len * std::mem::size_of::<jboolean>());
         Ok(raw)
     }
 }
 
 impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for Box<[bool]> {
     type Source = jbooleanArray;
+    const KEEPS_SOURCE_REF: bool = false;
 
     fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Result<Self> {
         let len = env.get_array_length(s)?;
-        let mut buf = Vec::with_capacity(len as usize).into_boxed_slice();
-        env.get_boolean_array_region(s, 0, &mut *buf)?;
+        let mut buf = vec![0; len as usize].into_boxed_slice();
+        env.get_boolean_array_region(s, 0, &mut buf)?;
+        metrics::record_from_java(<Self as Signature>::SIG_TYPE, buf.len(), buf.len() * std::mem::size_of::<jboolean>());
 
         buf.iter()
-            .map(|&b| TryFromJavaValue::try_from(b, &env))
+            .map(|&b| TryFromJavaValue::try_from(b, env))
             .collect()
     }
 }
@@ -205,27 +404,24 @@ impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for Box<[bool]> {
 impl<'env, T> TryIntoJavaValue<'env> for Vec<T>
 where
     T: TryIntoJavaValue<'env>,
+    T::Target: JavaValue<'env>,
 {
     type Target = jobject;
+    const NEW_LOCAL_REF: bool = true;
 
     fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
-        let obj = env.new_object(
-            "java/util/ArrayList",
-            "(I)V",
-            &[JValue::Int(self.len() as i32)],
-        )?;
-        let list = JList::from_env(&env, obj)?;
-
-        let _: Result<Vec<_>> = self
-            .into_iter()
-            .map::<Result<_>, _>(|el| {
-                Ok(JavaValue::autobox(
-                    TryIntoJavaValue::try_into(el, &env)?,
-                    &env,
-                ))
-            })
-            .map(|el| Ok(list.add(el?)))
-            .collect();
+        let obj = pool::new_array_list(env, self.len())?;
+        let list = JList::from_env(env, obj)?;
+
+        // Primitives are boxed into new objects, which aren't needed once added, like other new local references
+        let owned = <T as TryIntoJavaValue<'env>>::NEW_LOCAL_REF || !is_reference(<T as Signature>::SIG_TYPE);
+        for el in self {
+            let value = el.try_into_object(env)?;
+            list.add(value)?;
+            if owned && !value.is_null() {
+                env.delete_local_ref(value)?;
+            }
+        }
 
         Ok(list.into_raw())
     }
@@ -237,35 +433,240 @@ where
     U: JavaValue<'env>,
 {
     type Source = JObject<'env>;
+    const KEEPS_SOURCE_REF: bool = false;
 
     fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Result<Self> {
         let list = JList::from_env(env, s)?;
 
+        // Boxed primitives aren't needed once unboxed, nor are elements not kept by their converted value
+        let owned = !<T as TryFromJavaValue<'env, 'borrow>>::KEEPS_SOURCE_REF || !is_reference(<T as Signature>::SIG_TYPE);
         list.iter()?
-            .map(|el| T::try_from(U::unbox(el, env), env))
+            .map(|el| {
+                let value = T::try_from_object(el, env);
+                if owned && !el.is_null() {
+                    env.delete_local_ref(el)?;
+                }
+                value
+            })
             .collect()
     }
 }
 
+impl Signature for &[u8] {
+    const SIG_TYPE: &'static str = "[B";
+}
+
 impl Signature for Box<[u8]> {
     const SIG_TYPE: &'static str = "[B";
 }
 
+impl Signature for Cow<'_, [u8]> {
+    const SIG_TYPE: &'static str = "[B";
+}
+
+impl Signature for Vec<u8> {
+    const SIG_TYPE: &'static str = "[B";
+}
+
+impl Signature for &[i8] {
+    const SIG_TYPE: &'static str = "[B";
+}
+
+impl Signature for Box<[i8]> {
+    const SIG_TYPE: &'static str = "[B";
+}
+
+impl<'env> TryIntoJavaValue<'env> for &[u8] {
+    type Target = jbyteArray;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        let array = env.byte_array_from_slice(self)?;
+        metrics::record_to_java(<Self as Signature>::SIG_TYPE, self.len(), self.len());
+        Ok(array)
+    }
+}
+
 impl<'env> TryIntoJavaValue<'env> for Box<[u8]> {
     type Target = jbyteArray;
+    const NEW_LOCAL_REF: bool = true;
 
     fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
-        env.byte_array_from_slice(self.as_ref())
+        TryIntoJavaValue::try_into(self.as_ref(), env)
+    }
+}
+
+impl<'env> TryIntoJavaValue<'env> for Cow<'_, [u8]> {
+    type Target = jbyteArray;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        TryIntoJavaValue::try_into(self.as_ref(), env)
+    }
+}
+
+impl<'env> TryIntoJavaValue<'env> for Vec<u8> {
+    type Target = jbyteArray;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        TryIntoJavaValue::try_into(self.as_slice(), env)
+    }
+}
+
+impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for Vec<u8> {
+    type Source = jbyteArray;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Result<Vec<u8>> {
+        let buf = env.convert_byte_array(s)?;
+        metrics::record_from_java(<Self as Signature>::SIG_TYPE, buf.len(), buf.len());
+        Ok(buf)
     }
 }
 
 impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for Box<[u8]> {
     type Source = jbyteArray;
+    const KEEPS_SOURCE_REF: bool = false;
 
     fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Result<Box<[u8]>> {
-        let buf = env.convert_byte_array(s)?;
-        let boxed_slice = buf.into_boxed_slice();
-        Ok(boxed_slice)
+        <Vec<u8> as TryFromJavaValue>::try_from(s, env).map(Vec::into_boxed_slice)
+    }
+}
+
+/// Signed byte arrays are converted through their unsigned counterpart, reinterpreting each byte as `u8`.
+impl<'env> TryIntoJavaValue<'env> for &[i8] {
+    type Target = jbyteArray;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        let buf: Vec<u8> = self.iter().map(|&b| b as u8).collect();
+        TryIntoJavaValue::try_into(buf.as_slice(), env)
+    }
+}
+
+impl<'env> TryIntoJavaValue<'env> for Box<[i8]> {
+    type Target = jbyteArray;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        TryIntoJavaValue::try_into(self.as_ref(), env)
+    }
+}
+
+impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for Box<[i8]> {
+    type Source = jbyteArray;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Result<Box<[i8]>> {
+        let buf: Box<[u8]> = TryFromJavaValue::try_from(s, env)?;
+        Ok(buf.iter().map(|&b| b as i8).collect())
+    }
+}
+
+/// Conversions of arrays of primitive types other than `boolean` and `byte`, which are copied in a single JNI call.
+macro_rules! primitive_arrays {
+    ($($type:ty: $name:ident),+) => {
+        $(
+            paste! {
+                impl<'env> TryIntoJavaValue<'env> for &[$type] {
+                    type Target = [<j $name Array>];
+                    const NEW_LOCAL_REF: bool = true;
+
+                    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+                        let array = env.[<new_ $name _array>](self.len() as i32)?;
+                        env.[<set_ $name _array_region>](array, 0, self)?;
+                        metrics::record_to_java(<Self as Signature>::SIG_TYPE, self.len(), std::mem::size_of_val(self));
+                        Ok(array)
+                    }
+                }
+
+                impl<'env> TryIntoJavaValue<'env> for Box<[$type]> {
+                    type Target = [<j $name Array>];
+                    const NEW_LOCAL_REF: bool = true;
+
+                    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+                        TryIntoJavaValue::try_into(self.as_ref(), env)
+                    }
+                }
+
+                impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for Box<[$type]> {
+                    type Source = [<j $name Array>];
+                    const KEEPS_SOURCE_REF: bool = false;
+
+                    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Result<Self> {
+                        let len = env.get_array_length(s)?;
+                        let mut buf: Box<[$type]> = vec![Default::default(); len as usize].into_boxed_slice();
+                        env.[<get_ $name _array_region>](s, 0, &mut buf)?;
+                        metrics::record_from_java(<Self as Signature>::SIG_TYPE, buf.len(), std::mem::size_of_val(buf.as_ref()));
+                        Ok(buf)
+                    }
+                }
+            }
+        )+
+    };
+}
+
+primitive_arrays! {
+    jchar: char,
+    jshort: short,
+    jint: int,
+    jlong: long,
+    jfloat: float,
+    jdouble: double
+}
+
+impl<'env, T> TryIntoJavaValue<'env> for Box<[T]>
+where
+    T: ArrayElement + TryIntoJavaValue<'env>,
+    T::Target: JavaValue<'env>,
+{
+    type Target = jobjectArray;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        let element_class = crate::loader::find_class(env, T::element_class())?;
+        let array = env.new_object_array(self.len() as i32, element_class, JObject::null())?;
+
+        for (i, el) in self.into_vec().into_iter().enumerate() {
+            let value = TryIntoJavaValue::try_into(el, env)?;
+            // Primitives are boxed into new objects, which aren't needed once stored, like other new local references
+            let owned = <T as TryIntoJavaValue<'env>>::NEW_LOCAL_REF || JavaValue::as_object(&value).is_none();
+            let value = JavaValue::autobox(value, env);
+            env.set_object_array_element(array, i as i32, value)?;
+            if owned && !value.is_null() {
+                env.delete_local_ref(value)?;
+            }
+        }
+
+        Ok(array)
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T, U> TryFromJavaValue<'env, 'borrow> for Box<[T]>
+where
+    T: ArrayElement + TryFromJavaValue<'env, 'borrow, Source = U>,
+    U: JavaValue<'env>,
+{
+    type Source = JObject<'env>;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Result<Self> {
+        let len = env.get_array_length(s.into_raw())?;
+
+        (0..len)
+            .map(|i| {
+                let el = env.get_object_array_element(s.into_raw(), i)?;
+                let source = U::unbox(el, env);
+                // Boxed primitives aren't needed once unboxed, nor are elements not kept by their converted value
+                let owned = !<T as TryFromJavaValue<'env, 'borrow>>::KEEPS_SOURCE_REF || JavaValue::as_object(&source).is_none();
+                let value = T::try_from(source, env);
+                if owned && !el.is_null() {
+                    env.delete_local_ref(el)?;
+                }
+                value
+            })
+            .collect()
     }
 }
 
@@ -0,0 +1,223 @@
+//! Conversions of `serde` types through a serialized form (behind the `json` and `cbor` features).
+//!
+//! [`Json<T>`] is converted to and from a Java `String` holding the JSON representation of the wrapped value, and
+//! [`Cbor<T>`] to and from a `byte[]` holding its CBOR representation. Any type implementing [`Serialize`] and
+//! [`DeserializeOwned`] can then cross the JNI boundary in a single call, without declaring its fields on both sides:
+//! for large nested structures this is often simpler (and faster) than converting field by field.
+//!
+//! Values that can't be (de)serialized fail conversion with [`Error::WrongJValueType`], naming the Rust type.
+//!
+//! Example:
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! # #[bridge]
+//! # mod jni {
+//!     # use robusta_jni::convert::Signature;
+//!     # use robusta_jni::convert::Json;
+//!     # use robusta_jni::jni::errors::Result as JniResult;
+//!     # use serde::{Deserialize, Serialize};
+//! #[derive(Serialize, Deserialize)]
+//! pub struct Order {
+//!     items: Vec<String>,
+//!     total: f64,
+//! }
+//!
+//! #[derive(Signature)]
+//! #[package(com.example)]
+//! struct Orders;
+//!
+//! impl Orders {
+//!     // Java: `static native String discounted(String order, double rate)`
+//!     pub extern "jni" fn discounted(order: Json<Order>, rate: f64) -> JniResult<Json<Order>> {
+//!         let Json(mut order) = order;
+//!         order.total *= 1.0 - rate;
+//!         Ok(Json(order))
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! On the Java side, the serialized values are read and written with any JSON or CBOR library, e.g. with Jackson
+//! (`jackson-databind`, plus `jackson-dataformat-cbor` for CBOR) or Gson:
+//! ```java
+//! final class Serialized {
+//!     private static final ObjectMapper JSON = new ObjectMapper();
+//!     private static final ObjectMapper CBOR = new CBORMapper();
+//!     private static final Gson GSON = new Gson();
+//!
+//!     static <T> T fromJson(String json, Class<T> type) throws IOException {
+//!         return JSON.readValue(json, type); // or GSON.fromJson(json, type)
+//!     }
+//!
+//!     static String toJson(Object value) throws IOException {
+//!         return JSON.writeValueAsString(value); // or GSON.toJson(value)
+//!     }
+//!
+//!     static <T> T fromCbor(byte[] cbor, Class<T> type) throws IOException {
+//!         return CBOR.readValue(cbor, type);
+//!     }
+//!
+//!     static byte[] toCbor(Object value) throws IOException {
+//!         return CBOR.writeValueAsBytes(value);
+//!     }
+//! }
+//! ```
+//! so that `Orders.discounted(Serialized.toJson(order), 0.1)` is read back with
+//! `Serialized.fromJson(..., Order.class)`. Field names are matched as-is: use `#[serde(rename_all = "camelCase")]`
+//! on the Rust side to follow Java naming conventions.
+
+use std::any::type_name;
+
+use jni::errors::{Error, Result as JniResult};
+#[cfg(feature = "json")]
+use jni::objects::JString;
+#[cfg(feature = "cbor")]
+use jni::sys::jbyteArray;
+use jni::JNIEnv;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::convert::{FromJavaValue, IntoJavaValue, Signature, TryFromJavaValue, TryIntoJavaValue};
+
+/// Wrapper around a `serde` value that is converted to and from a Java `String` with its JSON representation.
+#[cfg(feature = "json")]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Json<T>(pub T);
+
+#[cfg(feature = "json")]
+impl<T> Json<T> {
+    /// Unwrap into the inner value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T> From<T> for Json<T> {
+    fn from(value: T) -> Self {
+        Json(value)
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T> Signature for Json<T> {
+    const SIG_TYPE: &'static str = "Ljava/lang/String;";
+}
+
+#[cfg(feature = "json")]
+impl<'env, T: Serialize> TryIntoJavaValue<'env> for Json<T> {
+    type Target = JString<'env>;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+        let json = serde_json::to_string(&self.0).map_err(|_| Error::WrongJValueType(type_name::<T>(), "JSON string"))?;
+        TryIntoJavaValue::try_into(json, env)
+    }
+}
+
+#[cfg(feature = "json")]
+impl<'env: 'borrow, 'borrow, T: DeserializeOwned> TryFromJavaValue<'env, 'borrow> for Json<T> {
+    type Source = JString<'env>;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+        let json = <String as TryFromJavaValue>::try_from(s, env)?;
+        serde_json::from_str(&json)
+            .map(Json)
+            .map_err(|_| Error::WrongJValueType(type_name::<T>(), "JSON string"))
+    }
+}
+
+#[cfg(feature = "json")]
+impl<'env, T: Serialize> IntoJavaValue<'env> for Json<T> {
+    type Target = JString<'env>;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        TryIntoJavaValue::try_into(self, env).unwrap()
+    }
+}
+
+#[cfg(feature = "json")]
+impl<'env: 'borrow, 'borrow, T: DeserializeOwned> FromJavaValue<'env, 'borrow> for Json<T> {
+    type Source = JString<'env>;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        TryFromJavaValue::try_from(s, env).unwrap()
+    }
+}
+
+/// Wrapper around a `serde` value that is converted to and from a Java `byte[]` with its CBOR representation.
+///
+/// CBOR is more compact than JSON and faster to parse, and keeps byte strings as they are instead of encoding them.
+#[cfg(feature = "cbor")]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Cbor<T>(pub T);
+
+#[cfg(feature = "cbor")]
+impl<T> Cbor<T> {
+    /// Unwrap into the inner value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+#[cfg(feature = "cbor")]
+impl<T> From<T> for Cbor<T> {
+    fn from(value: T) -> Self {
+        Cbor(value)
+    }
+}
+
+#[cfg(feature = "cbor")]
+impl<T> Signature for Cbor<T> {
+    const SIG_TYPE: &'static str = "[B";
+}
+
+#[cfg(feature = "cbor")]
+impl<'env, T: Serialize> TryIntoJavaValue<'env> for Cbor<T> {
+    type Target = jbyteArray;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(&self.0, &mut bytes)
+            .map_err(|_| Error::WrongJValueType(type_name::<T>(), "CBOR byte array"))?;
+        TryIntoJavaValue::try_into(bytes.as_slice(), env)
+    }
+}
+
+#[cfg(feature = "cbor")]
+impl<'env: 'borrow, 'borrow, T: DeserializeOwned> TryFromJavaValue<'env, 'borrow> for Cbor<T> {
+    type Source = jbyteArray;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+        let bytes = <Box<[u8]> as TryFromJavaValue>::try_from(s, env)?;
+        ciborium::de::from_reader(bytes.as_ref())
+            .map(Cbor)
+            .map_err(|_| Error::WrongJValueType(type_name::<T>(), "CBOR byte array"))
+    }
+}
+
+#[cfg(feature = "cbor")]
+impl<'env, T: Serialize> IntoJavaValue<'env> for Cbor<T> {
+    type Target = jbyteArray;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        TryIntoJavaValue::try_into(self, env).unwrap()
+    }
+}
+
+#[cfg(feature = "cbor")]
+impl<'env: 'borrow, 'borrow, T: DeserializeOwned> FromJavaValue<'env, 'borrow> for Cbor<T> {
+    type Source = jbyteArray;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        TryFromJavaValue::try_from(s, env).unwrap()
+    }
+}
@@ -0,0 +1,285 @@
+//! Conversions between Rust time types and `java.time` types.
+//!
+//! | **Rust**                                   | **Java**               |
+//! |--------------------------------------------|------------------------|
+//! | [`std::time::SystemTime`]                  | `java.time.Instant`    |
+//! | [`std::time::Duration`]                    | `java.time.Duration`   |
+//! | `chrono::DateTime<Utc>` *(`chrono` feature)* | `java.time.Instant`    |
+//! | `chrono::Duration` *(`chrono` feature)*      | `java.time.Duration`   |
+//! | `time::OffsetDateTime` *(`time` feature)*    | `java.time.Instant`    |
+//! | `time::Duration` *(`time` feature)*          | `java.time.Duration`   |
+//!
+//! Values that cannot be represented on the target side (e.g. a negative `java.time.Duration` converted to
+//! a [`std::time::Duration`]) fail conversion.
+
+use std::convert::TryFrom;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use jni::errors::{Error, Result as JniResult};
+use jni::objects::{JObject, JValue};
+use jni::JNIEnv;
+
+use crate::convert::{FromJavaValue, IntoJavaValue, Signature, TryFromJavaValue, TryIntoJavaValue};
+
+const INSTANT_CLASS: &str = "java/time/Instant";
+const DURATION_CLASS: &str = "java/time/Duration";
+
+/// Create a `java.time.Instant` from seconds since the Unix epoch and a (possibly negative) nanosecond adjustment.
+fn new_instant<'env>(env: &JNIEnv<'env>, seconds: i64, nanos: i64) -> JniResult<JObject<'env>> {
+    env.call_static_method(
+        INSTANT_CLASS,
+        "ofEpochSecond",
+        "(JJ)Ljava/time/Instant;",
+        &[JValue::Long(seconds), JValue::Long(nanos)],
+    )?
+    .l()
+}
+
+/// Seconds since the Unix epoch and nanoseconds of second (always positive) of a `java.time.Instant`.
+fn instant_parts(env: &JNIEnv, instant: JObject) -> JniResult<(i64, u32)> {
+    let seconds = env
+        .call_method(instant, "getEpochSecond", "()J", &[])?
+        .j()?;
+    let nanos = env.call_method(instant, "getNano", "()I", &[])?.i()?;
+
+    Ok((seconds, nanos as u32))
+}
+
+/// Create a `java.time.Duration` from seconds and a (possibly negative) nanosecond adjustment.
+fn new_duration<'env>(env: &JNIEnv<'env>, seconds: i64, nanos: i64) -> JniResult<JObject<'env>> {
+    env.call_static_method(
+        DURATION_CLASS,
+        "ofSeconds",
+        "(JJ)Ljava/time/Duration;",
+        &[JValue::Long(seconds), JValue::Long(nanos)],
+    )?
+    .l()
+}
+
+/// Seconds and nanoseconds of second (always positive) of a `java.time.Duration`.
+fn duration_parts(env: &JNIEnv, duration: JObject) -> JniResult<(i64, u32)> {
+    let seconds = env.call_method(duration, "getSeconds", "()J", &[])?.j()?;
+    let nanos = env.call_method(duration, "getNano", "()I", &[])?.i()?;
+
+    Ok((seconds, nanos as u32))
+}
+
+macro_rules! unchecked_via_safe {
+    ($type:ty) => {
+        impl<'env> IntoJavaValue<'env> for $type {
+            type Target = JObject<'env>;
+            const NEW_LOCAL_REF: bool = true;
+
+            fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+                TryIntoJavaValue::try_into(self, env).unwrap()
+            }
+        }
+
+        impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for $type {
+            type Source = JObject<'env>;
+            const KEEPS_SOURCE_REF: bool = false;
+
+            fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+                TryFromJavaValue::try_from(s, env).unwrap()
+            }
+        }
+    };
+}
+
+impl Signature for SystemTime {
+    const SIG_TYPE: &'static str = "Ljava/time/Instant;";
+}
+
+impl<'env> TryIntoJavaValue<'env> for SystemTime {
+    type Target = JObject<'env>;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+        let (seconds, nanos) = match self.duration_since(UNIX_EPOCH) {
+            Ok(d) => (
+                <i64 as TryFrom<u64>>::try_from(d.as_secs()),
+                d.subsec_nanos() as i64,
+            ),
+            Err(e) => (
+                <i64 as TryFrom<u64>>::try_from(e.duration().as_secs()).map(|s| -s),
+                -(e.duration().subsec_nanos() as i64),
+            ),
+        };
+        let seconds =
+            seconds.map_err(|_| Error::WrongJValueType("java.time.Instant", "SystemTime"))?;
+
+        new_instant(env, seconds, nanos)
+    }
+}
+
+impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for SystemTime {
+    type Source = JObject<'env>;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+        let (seconds, nanos) = instant_parts(env, s)?;
+        let time = if seconds >= 0 {
+            UNIX_EPOCH.checked_add(Duration::from_secs(seconds as u64))
+        } else {
+            UNIX_EPOCH.checked_sub(Duration::from_secs(seconds.unsigned_abs()))
+        };
+
+        time.and_then(|t| t.checked_add(Duration::from_nanos(nanos as u64)))
+            .ok_or(Error::WrongJValueType("SystemTime", "java.time.Instant"))
+    }
+}
+
+unchecked_via_safe!(SystemTime);
+
+impl Signature for Duration {
+    const SIG_TYPE: &'static str = "Ljava/time/Duration;";
+}
+
+impl<'env> TryIntoJavaValue<'env> for Duration {
+    type Target = JObject<'env>;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+        let seconds = <i64 as TryFrom<u64>>::try_from(self.as_secs())
+            .map_err(|_| Error::WrongJValueType("java.time.Duration", "Duration"))?;
+
+        new_duration(env, seconds, self.subsec_nanos() as i64)
+    }
+}
+
+impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for Duration {
+    type Source = JObject<'env>;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+        let (seconds, nanos) = duration_parts(env, s)?;
+        let seconds = <u64 as TryFrom<i64>>::try_from(seconds)
+            .map_err(|_| Error::WrongJValueType("Duration", "java.time.Duration"))?;
+
+        Ok(Duration::new(seconds, nanos))
+    }
+}
+
+unchecked_via_safe!(Duration);
+
+#[cfg(feature = "chrono")]
+mod chrono_impls {
+    use chrono::{DateTime, TimeDelta, Utc};
+
+    use super::*;
+
+    impl Signature for DateTime<Utc> {
+        const SIG_TYPE: &'static str = "Ljava/time/Instant;";
+    }
+
+    impl<'env> TryIntoJavaValue<'env> for DateTime<Utc> {
+        type Target = JObject<'env>;
+        const NEW_LOCAL_REF: bool = true;
+
+        fn try_into(self, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+            new_instant(env, self.timestamp(), self.timestamp_subsec_nanos() as i64)
+        }
+    }
+
+    impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for DateTime<Utc> {
+        type Source = JObject<'env>;
+        const KEEPS_SOURCE_REF: bool = false;
+
+        fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+            let (seconds, nanos) = instant_parts(env, s)?;
+
+            DateTime::from_timestamp(seconds, nanos)
+                .ok_or(Error::WrongJValueType("DateTime<Utc>", "java.time.Instant"))
+        }
+    }
+
+    unchecked_via_safe!(DateTime<Utc>);
+
+    impl Signature for TimeDelta {
+        const SIG_TYPE: &'static str = "Ljava/time/Duration;";
+    }
+
+    impl<'env> TryIntoJavaValue<'env> for TimeDelta {
+        type Target = JObject<'env>;
+        const NEW_LOCAL_REF: bool = true;
+
+        fn try_into(self, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+            new_duration(env, self.num_seconds(), self.subsec_nanos() as i64)
+        }
+    }
+
+    impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for TimeDelta {
+        type Source = JObject<'env>;
+        const KEEPS_SOURCE_REF: bool = false;
+
+        fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+            let (seconds, nanos) = duration_parts(env, s)?;
+
+            TimeDelta::new(seconds, nanos)
+                .ok_or(Error::WrongJValueType("chrono::Duration", "java.time.Duration"))
+        }
+    }
+
+    unchecked_via_safe!(TimeDelta);
+}
+
+#[cfg(feature = "time")]
+mod time_impls {
+    use ::time::OffsetDateTime;
+
+    use super::*;
+
+    impl Signature for OffsetDateTime {
+        const SIG_TYPE: &'static str = "Ljava/time/Instant;";
+    }
+
+    impl<'env> TryIntoJavaValue<'env> for OffsetDateTime {
+        type Target = JObject<'env>;
+        const NEW_LOCAL_REF: bool = true;
+
+        fn try_into(self, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+            new_instant(env, self.unix_timestamp(), self.nanosecond() as i64)
+        }
+    }
+
+    impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for OffsetDateTime {
+        type Source = JObject<'env>;
+        const KEEPS_SOURCE_REF: bool = false;
+
+        fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+            let (seconds, nanos) = instant_parts(env, s)?;
+            let nanos = seconds as i128 * 1_000_000_000 + nanos as i128;
+
+            OffsetDateTime::from_unix_timestamp_nanos(nanos)
+                .map_err(|_| Error::WrongJValueType("OffsetDateTime", "java.time.Instant"))
+        }
+    }
+
+    unchecked_via_safe!(OffsetDateTime);
+
+    impl Signature for ::time::Duration {
+        const SIG_TYPE: &'static str = "Ljava/time/Duration;";
+    }
+
+    impl<'env> TryIntoJavaValue<'env> for ::time::Duration {
+        type Target = JObject<'env>;
+        const NEW_LOCAL_REF: bool = true;
+
+        fn try_into(self, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+            new_duration(env, self.whole_seconds(), self.subsec_nanoseconds() as i64)
+        }
+    }
+
+    impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for ::time::Duration {
+        type Source = JObject<'env>;
+        const KEEPS_SOURCE_REF: bool = false;
+
+        fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+            let (seconds, nanos) = duration_parts(env, s)?;
+
+            Ok(::time::Duration::new(seconds, nanos as i32))
+        }
+    }
+
+    unchecked_via_safe!(::time::Duration);
+}
@@ -0,0 +1,172 @@
+//! Conversions between Rust tuples and the `robusta.Tuple2` and `robusta.Tuple3` Java classes.
+//!
+//! | **Rust**       | **Java**                 |
+//! |----------------|--------------------------|
+//! | `(A, B)`       | `robusta.Tuple2<A, B>`    |
+//! | `(A, B, C)`    | `robusta.Tuple3<A, B, C>` |
+//!
+//! Methods can return several values without declaring a class for them:
+//!
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! # #[bridge]
+//! # mod jni {
+//!     # use robusta_jni::convert::Signature;
+//! #[derive(Signature)]
+//! #[package(com.example)]
+//! struct Stats;
+//!
+//! impl Stats {
+//!     // Java: `static native robusta.Tuple2<Integer, Integer> minMax(ArrayList<Integer> values)`
+//!     pub extern "jni" fn minMax(values: Vec<i32>) -> (i32, i32) {
+//!         let min = values.iter().copied().min().unwrap_or_default();
+//!         let max = values.iter().copied().max().unwrap_or_default();
+//!         (min, max)
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! Elements are converted like the elements of a `Vec<T>`, with primitives boxed (e.g. `i32` as `Integer`), and read
+//! back with the `first()`, `second()` and `third()` accessors of the Java class. Both classes implement `equals`,
+//! `hashCode` and `toString` on their elements.
+//!
+//! The classes are found in the `java` directory of this crate, and must be available to the JVM (or embedded in the
+//! library with the `helper_classes` feature, see [`helpers`](crate::helpers)). For larger tuples, or to name the
+//! elements, declare a struct deriving the conversion traits instead.
+
+use jni::errors::{Error, Result as JniResult};
+use jni::objects::{JObject, JValue};
+use jni::JNIEnv;
+
+use crate::convert::{FromJavaValue, IntoJavaValue, JavaValue, Signature, TryFromJavaValue, TryIntoJavaValue};
+
+/// Element of a tuple being converted to Java, as an object, and whether it's a new local reference: either boxed, or
+/// `new_local_ref` as reported by its conversion.
+fn boxed_element<'env, T: JavaValue<'env>>(value: T, new_local_ref: bool, env: &JNIEnv<'env>) -> (JObject<'env>, bool) {
+    let owned = new_local_ref || value.as_object().is_none();
+    (value.autobox(env), owned)
+}
+
+/// Instance of tuple class `class_path` holding `elements`.
+fn new_tuple<'env>(
+    env: &JNIEnv<'env>,
+    class_path: &str,
+    constructor: &str,
+    elements: &[(JObject<'env>, bool)],
+) -> JniResult<JObject<'env>> {
+    let class = crate::helpers::helper_class(env, class_path)?;
+    let args: Vec<JValue> = elements.iter().map(|(element, _)| JValue::Object(*element)).collect();
+    let tuple = env.new_object(class, constructor, &args)?;
+
+    // Primitives are boxed into new objects, which aren't needed once stored, like other new local references
+    for (element, owned) in elements {
+        if *owned && !element.is_null() {
+            env.delete_local_ref(*element)?;
+        }
+    }
+
+    Ok(tuple)
+}
+
+/// Element of a Java tuple, read with its `getter`.
+fn element<'env>(env: &JNIEnv<'env>, tuple: JObject<'env>, getter: &str) -> JniResult<JObject<'env>> {
+    env.call_method(tuple, getter, "()Ljava/lang/Object;", &[])?.l()
+}
+
+macro_rules! tuple_conversions {
+    ($class:literal, $java_name:literal, $constructor:literal; $(($type:ident, $source:ident, $index:tt, $getter:literal)),+) => {
+        impl<$($type),+> Signature for ($($type,)+) {
+            const SIG_TYPE: &'static str = concat!("L", $class, ";");
+        }
+
+        impl<'env, $($type),+> TryIntoJavaValue<'env> for ($($type,)+)
+        where
+            $($type: TryIntoJavaValue<'env>, <$type as TryIntoJavaValue<'env>>::Target: JavaValue<'env>,)+
+        {
+            type Target = JObject<'env>;
+            const NEW_LOCAL_REF: bool = true;
+
+            fn try_into(self, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+                let elements = [$(boxed_element(
+                    TryIntoJavaValue::try_into(self.$index, env)?,
+                    <$type as TryIntoJavaValue<'env>>::NEW_LOCAL_REF,
+                    env,
+                )),+];
+                new_tuple(env, $class, $constructor, &elements)
+            }
+        }
+
+        impl<'env, $($type),+> IntoJavaValue<'env> for ($($type,)+)
+        where
+            $($type: IntoJavaValue<'env>, <$type as IntoJavaValue<'env>>::Target: JavaValue<'env>,)+
+        {
+            type Target = JObject<'env>;
+            const NEW_LOCAL_REF: bool = true;
+
+            fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+                let elements = [$(boxed_element(
+                    IntoJavaValue::into(self.$index, env),
+                    <$type as IntoJavaValue<'env>>::NEW_LOCAL_REF,
+                    env,
+                )),+];
+                new_tuple(env, $class, $constructor, &elements).unwrap()
+            }
+        }
+
+        impl<'env: 'borrow, 'borrow, $($type, $source),+> TryFromJavaValue<'env, 'borrow> for ($($type,)+)
+        where
+            $($type: TryFromJavaValue<'env, 'borrow, Source = $source>, $source: JavaValue<'env>,)+
+        {
+            type Source = JObject<'env>;
+            const KEEPS_SOURCE_REF: bool = false;
+
+            fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+                if s.is_null() {
+                    return Err(Error::NullPtr($java_name));
+                }
+
+                Ok(($({
+                    let element = element(env, s, $getter)?;
+                    let source = <$source as JavaValue<'env>>::unbox(element, env);
+                    // Boxed primitives aren't needed once unboxed, nor are elements not kept by their converted value
+                    let owned = !<$type as TryFromJavaValue<'env, 'borrow>>::KEEPS_SOURCE_REF
+                        || JavaValue::as_object(&source).is_none();
+                    let value = <$type as TryFromJavaValue<'env, 'borrow>>::try_from(source, env)?;
+                    if owned && !element.is_null() {
+                        env.delete_local_ref(element)?;
+                    }
+                    value
+                },)+))
+            }
+        }
+
+        impl<'env: 'borrow, 'borrow, $($type, $source),+> FromJavaValue<'env, 'borrow> for ($($type,)+)
+        where
+            $($type: FromJavaValue<'env, 'borrow, Source = $source>, $source: JavaValue<'env>,)+
+        {
+            type Source = JObject<'env>;
+            const KEEPS_SOURCE_REF: bool = false;
+
+            fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+                ($({
+                    let element = element(env, s, $getter).unwrap();
+                    let source = <$source as JavaValue<'env>>::unbox(element, env);
+                    let owned = !<$type as FromJavaValue<'env, 'borrow>>::KEEPS_SOURCE_REF
+                        || JavaValue::as_object(&source).is_none();
+                    let value = <$type as FromJavaValue<'env, 'borrow>>::from(source, env);
+                    if owned && !element.is_null() {
+                        env.delete_local_ref(element).unwrap();
+                    }
+                    value
+                },)+)
+            }
+        }
+    };
+}
+
+tuple_conversions!("robusta/Tuple2", "robusta.Tuple2", "(Ljava/lang/Object;Ljava/lang/Object;)V";
+    (A, UA, 0, "first"), (B, UB, 1, "second"));
+tuple_conversions!("robusta/Tuple3", "robusta.Tuple3", "(Ljava/lang/Object;Ljava/lang/Object;Ljava/lang/Object;)V";
+    (A, UA, 0, "first"), (B, UB, 1, "second"), (C, UC, 2, "third"));
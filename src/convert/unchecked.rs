@@ -11,11 +11,16 @@
 //! **These functions *will* panic should any conversion fail.**
 //!
 
-use jni::objects::{JList, JObject, JString, JValue};
-use jni::sys::{jboolean, jbooleanArray, jchar, jobject, jstring};
+use std::borrow::Cow;
+
+use jni::objects::{JList, JObject, JString};
+use jni::sys::{
+    jboolean, jbooleanArray, jbyteArray, jchar, jcharArray, jdouble, jdoubleArray, jfloat, jfloatArray, jint, jintArray,
+    jlong, jlongArray, jobject, jobjectArray, jshort, jshortArray, jstring,
+};
 use jni::JNIEnv;
 
-use crate::convert::{JavaValue, Signature};
+use crate::convert::{is_reference, metrics, pool, ArrayElement, JavaValue, JniValue, Signature, TryFromJavaValue, TryIntoJavaValue};
 
 pub use robusta_codegen::{FromJavaValue, IntoJavaValue};
 
@@ -34,14 +39,28 @@ pub use robusta_codegen::{FromJavaValue, IntoJavaValue};
 ///
 pub trait IntoJavaValue<'env>: Signature {
     /// Conversion target type.
-    type Target: JavaValue<'env>;
+    type Target: JniValue<'env>;
 
     /// [Signature](https://docs.oracle.com/en/java/javase/15/docs/specs/jni/types.html#type-signatures) of the source type.
     /// By default, use the one defined on the [`Signature`] trait for the implementing type.
     const SIG_TYPE: &'static str = <Self as Signature>::SIG_TYPE;
 
+    /// Whether the converted value is a new local reference owned by the caller, see
+    /// [`TryIntoJavaValue::NEW_LOCAL_REF`].
+    const NEW_LOCAL_REF: bool = false;
+
     /// Perform the conversion.
     fn into(self, env: &JNIEnv<'env>) -> Self::Target;
+
+    /// Convert to an object, as stored in collections, see [`TryIntoJavaValue::try_into_object`].
+    #[doc(hidden)]
+    fn into_object(self, env: &JNIEnv<'env>) -> JObject<'env>
+    where
+        Self: Sized,
+        Self::Target: JavaValue<'env>,
+    {
+        JavaValue::autobox(IntoJavaValue::into(self, env), env)
+    }
 }
 
 /// Conversion trait from Java values to Rust values, analogous to [From]. Used when converting types that are input to JNI-available functions.
@@ -54,14 +73,58 @@ pub trait IntoJavaValue<'env>: Signature {
 ///
 pub trait FromJavaValue<'env: 'borrow, 'borrow>: Signature {
     /// Conversion source type.
-    type Source: JavaValue<'env>;
+    type Source: JniValue<'env>;
 
     /// [Signature](https://docs.oracle.com/en/java/javase/15/docs/specs/jni/types.html#type-signatures) of the target type.
     /// By default, use the one defined on the [`Signature`] trait for the implementing type.
     const SIG_TYPE: &'static str = <Self as Signature>::SIG_TYPE;
 
+    /// Whether the converted value keeps using the local reference it was converted from, see
+    /// [`TryFromJavaValue::KEEPS_SOURCE_REF`].
+    const KEEPS_SOURCE_REF: bool = true;
+
     /// Perform the conversion.
     fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self;
+
+    /// Convert from an object, as stored in collections, see [`TryFromJavaValue::try_from_object`].
+    #[doc(hidden)]
+    fn from_object(s: JObject<'env>, env: &'borrow JNIEnv<'env>) -> Self
+    where
+        Self: Sized,
+        Self::Source: JavaValue<'env>,
+    {
+        FromJavaValue::from(JavaValue::unbox(s, env), env)
+    }
+}
+
+/// Convert the value returned by a call to a Java method, analogous to [`try_from_returned`](crate::convert::try_from_returned).
+#[doc(hidden)]
+pub fn from_returned<'env: 'borrow, 'borrow, T>(value: T::Source, env: &'borrow JNIEnv<'env>) -> T
+where
+    T: FromJavaValue<'env, 'borrow>,
+{
+    let owned = (!T::KEEPS_SOURCE_REF).then(|| JniValue::as_object(&value)).flatten();
+    let converted = T::from(value, env);
+    if let Some(object) = owned.filter(|o| !o.is_null()) {
+        env.delete_local_ref(object).unwrap();
+    }
+    converted
+}
+
+/// Write-back trait for Rust values converted from a Java instance, analogous to [`TryWriteBack`]. Used when an exported method takes a `&mut self` receiver.
+///
+/// When using this trait the write-back is assumed to be infallible.
+/// Should it fail, a panic will be raised.
+///
+/// # Notes on the derive macro
+///
+/// An implementation is generated by the [`FromJavaValue`] derive macro. The same notes on [`TryWriteBack`] apply.
+///
+/// [`TryWriteBack`]: crate::convert::TryWriteBack
+///
+pub trait WriteBack<'env> {
+    /// Write the value back to the Java object it was converted from.
+    fn write_back(self, env: &JNIEnv<'env>);
 }
 
 impl<'env, T> IntoJavaValue<'env> for T
@@ -86,23 +149,81 @@ where
     }
 }
 
+impl<'env> IntoJavaValue<'env> for () {
+    type Target = ();
+
+    fn into(self, _env: &JNIEnv<'env>) -> Self::Target {}
+}
+
+impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for () {
+    type Source = ();
+
+    fn from(_s: Self::Source, _env: &'borrow JNIEnv<'env>) -> Self {}
+}
+
 impl Signature for String {
     const SIG_TYPE: &'static str = "Ljava/lang/String;";
 }
 
+impl Signature for &str {
+    const SIG_TYPE: &'static str = <String as Signature>::SIG_TYPE;
+}
+
+impl Signature for &String {
+    const SIG_TYPE: &'static str = <String as Signature>::SIG_TYPE;
+}
+
+impl Signature for Cow<'_, str> {
+    const SIG_TYPE: &'static str = <String as Signature>::SIG_TYPE;
+}
+
 impl<'env> IntoJavaValue<'env> for String {
     type Target = jstring;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        IntoJavaValue::into(self.as_str(), env)
+    }
+}
+
+/// String slices are converted without an intermediate `String`, e.g. when passed to `extern "java"` methods.
+impl<'env> IntoJavaValue<'env> for &str {
+    type Target = jstring;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        let s = env.new_string(self).unwrap().into_raw();
+        metrics::record_to_java(<String as Signature>::SIG_TYPE, self.len(), self.len());
+        s
+    }
+}
+
+impl<'env> IntoJavaValue<'env> for &String {
+    type Target = jstring;
+    const NEW_LOCAL_REF: bool = true;
 
     fn into(self, env: &JNIEnv<'env>) -> Self::Target {
-        env.new_string(self).unwrap().into_raw()
+        IntoJavaValue::into(self.as_str(), env)
+    }
+}
+
+impl<'env> IntoJavaValue<'env> for Cow<'_, str> {
+    type Target = jstring;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        IntoJavaValue::into(self.as_ref(), env)
     }
 }
 
 impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for String {
     type Source = JString<'env>;
+    const KEEPS_SOURCE_REF: bool = false;
 
     fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
-        env.get_string(s).unwrap().into()
+        let s: String = env.get_string(s).unwrap().into();
+        metrics::record_from_java(<String as Signature>::SIG_TYPE, s.len(), s.len());
+        s
     }
 }
 
@@ -119,7 +240,7 @@ impl<'env> IntoJavaValue<'env> for bool {
 }
 
 impl Signature for bool {
-    const SIG_TYPE: &'static str = <jboolean as Signature>::SIG_TYPE;
+    const SIG_TYPE: &'static str = "Z";
 }
 
 impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for bool {
@@ -155,53 +276,187 @@ impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for char {
 
 impl<'env> IntoJavaValue<'env> for Box<[bool]> {
     type Target = jbooleanArray;
+    const NEW_LOCAL_REF: bool = true;
 
     fn into(self, env: &JNIEnv<'env>) -> Self::Target {
         let len = self.len();
         let buf: Vec<_> = self.iter().map(|&b| Into::into(b)).collect();
         let raw = env.new_boolean_array(len as i32).unwrap();
         env.set_boolean_array_region(raw, 0, &buf).unwrap();
+        metrics::record_to_java(<Self as Signature>::SIG_TYPE, len, len * std::mem::size_of::<jboolean>());
         raw
     }
 }
 
 impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for Box<[bool]> {
     type Source = jbooleanArray;
+    const KEEPS_SOURCE_REF: bool = false;
 
     fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
         let len = env.get_array_length(s).unwrap();
-        let mut buf = Vec::with_capacity(len as usize).into_boxed_slice();
-        env.get_boolean_array_region(s, 0, &mut *buf).unwrap();
+        let mut buf = vec![0; len as usize].into_boxed_slice();
+        env.get_boolean_array_region(s, 0, &mut buf).unwrap();
+        metrics::record_from_java(<Self as Signature>::SIG_TYPE, buf.len(), buf.len() * std::mem::size_of::<jboolean>());
+
+        buf.iter().map(|&b| FromJavaValue::from(b, env)).collect()
+    }
+}
 
-        buf.iter().map(|&b| FromJavaValue::from(b, &env)).collect()
+impl<'env> IntoJavaValue<'env> for &[u8] {
+    type Target = jbyteArray;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        let array = env.byte_array_from_slice(self).unwrap();
+        metrics::record_to_java(<Self as Signature>::SIG_TYPE, self.len(), self.len());
+        array
     }
 }
 
-impl<T> Signature for Vec<T> {
+impl<'env> IntoJavaValue<'env> for Box<[u8]> {
+    type Target = jbyteArray;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        IntoJavaValue::into(self.as_ref(), env)
+    }
+}
+
+impl<'env> IntoJavaValue<'env> for Cow<'_, [u8]> {
+    type Target = jbyteArray;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        IntoJavaValue::into(self.as_ref(), env)
+    }
+}
+
+impl<'env> IntoJavaValue<'env> for Vec<u8> {
+    type Target = jbyteArray;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        IntoJavaValue::into(self.as_slice(), env)
+    }
+}
+
+impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for Vec<u8> {
+    type Source = jbyteArray;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        let buf = env.convert_byte_array(s).unwrap();
+        metrics::record_from_java(<Self as Signature>::SIG_TYPE, buf.len(), buf.len());
+        buf
+    }
+}
+
+impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for Box<[u8]> {
+    type Source = jbyteArray;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        <Vec<u8> as FromJavaValue>::from(s, env).into_boxed_slice()
+    }
+}
+
+impl<'env> IntoJavaValue<'env> for &[i8] {
+    type Target = jbyteArray;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        let buf: Vec<u8> = self.iter().map(|&b| b as u8).collect();
+        IntoJavaValue::into(buf.as_slice(), env)
+    }
+}
+
+impl<'env> IntoJavaValue<'env> for Box<[i8]> {
+    type Target = jbyteArray;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        IntoJavaValue::into(self.as_ref(), env)
+    }
+}
+
+impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for Box<[i8]> {
+    type Source = jbyteArray;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        let buf: Box<[u8]> = FromJavaValue::from(s, env);
+        buf.iter().map(|&b| b as i8).collect()
+    }
+}
+
+/// Conversions of arrays of primitive types other than `boolean` and `byte`, see their [`TryIntoJavaValue`](crate::convert::TryIntoJavaValue) implementations.
+macro_rules! primitive_arrays {
+    ($($type:ty: $array:ty),+) => {
+        $(
+            impl<'env> IntoJavaValue<'env> for &[$type] {
+                type Target = $array;
+                const NEW_LOCAL_REF: bool = true;
+
+                fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+                    TryIntoJavaValue::try_into(self, env).unwrap()
+                }
+            }
+
+            impl<'env> IntoJavaValue<'env> for Box<[$type]> {
+                type Target = $array;
+                const NEW_LOCAL_REF: bool = true;
+
+                fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+                    TryIntoJavaValue::try_into(self, env).unwrap()
+                }
+            }
+
+            impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for Box<[$type]> {
+                type Source = $array;
+                const KEEPS_SOURCE_REF: bool = false;
+
+                fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+                    TryFromJavaValue::try_from(s, env).unwrap()
+                }
+            }
+        )+
+    };
+}
+
+primitive_arrays! {
+    jchar: jcharArray,
+    jshort: jshortArray,
+    jint: jintArray,
+    jlong: jlongArray,
+    jfloat: jfloatArray,
+    jdouble: jdoubleArray
+}
+
+impl<T: Signature> Signature for Vec<T> {
     const SIG_TYPE: &'static str = "Ljava/util/ArrayList;";
 }
 
 impl<'env, T> IntoJavaValue<'env> for Vec<T>
 where
     T: IntoJavaValue<'env>,
+    T::Target: JavaValue<'env>,
 {
     type Target = jobject;
+    const NEW_LOCAL_REF: bool = true;
 
     fn into(self, env: &JNIEnv<'env>) -> Self::Target {
-        let obj = env
-            .new_object(
-                "java/util/ArrayList",
-                "(I)V",
-                &[JValue::Int(self.len() as i32)],
-            )
-            .unwrap();
-        let list = JList::from_env(&env, obj).unwrap();
-
-        self.into_iter()
-            .map(|el| JavaValue::autobox(IntoJavaValue::into(el, &env), &env))
-            .for_each(|el| {
-                list.add(el).unwrap();
-            });
+        let obj = pool::new_array_list(env, self.len()).unwrap();
+        let list = JList::from_env(env, obj).unwrap();
+
+        // Primitives are boxed into new objects, which aren't needed once added, like other new local references
+        let owned = <T as IntoJavaValue<'env>>::NEW_LOCAL_REF || !is_reference(<T as Signature>::SIG_TYPE);
+        for el in self {
+            let value = el.into_object(env);
+            list.add(value).unwrap();
+            if owned && !value.is_null() {
+                env.delete_local_ref(value).unwrap();
+            }
+        }
 
         list.into_raw()
     }
@@ -213,13 +468,78 @@ where
     U: JavaValue<'env>,
 {
     type Source = JObject<'env>;
+    const KEEPS_SOURCE_REF: bool = false;
 
     fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
         let list = JList::from_env(env, s).unwrap();
 
+        // Boxed primitives aren't needed once unboxed, nor are elements not kept by their converted value
+        let owned = !<T as FromJavaValue<'env, 'borrow>>::KEEPS_SOURCE_REF || !is_reference(<T as Signature>::SIG_TYPE);
         list.iter()
             .unwrap()
-            .map(|el| T::from(U::unbox(el, env), env))
+            .map(|el| {
+                let value = T::from_object(el, env);
+                if owned && !el.is_null() {
+                    env.delete_local_ref(el).unwrap();
+                }
+                value
+            })
+            .collect()
+    }
+}
+
+impl<'env, T> IntoJavaValue<'env> for Box<[T]>
+where
+    T: ArrayElement + IntoJavaValue<'env>,
+    T::Target: JavaValue<'env>,
+{
+    type Target = jobjectArray;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        let element_class = crate::loader::find_class(env, T::element_class()).unwrap();
+        let array = env
+            .new_object_array(self.len() as i32, element_class, JObject::null())
+            .unwrap();
+
+        for (i, el) in self.into_vec().into_iter().enumerate() {
+            let value = IntoJavaValue::into(el, env);
+            // Primitives are boxed into new objects, which aren't needed once stored, like other new local references
+            let owned = <T as IntoJavaValue<'env>>::NEW_LOCAL_REF || JavaValue::as_object(&value).is_none();
+            let value = JavaValue::autobox(value, env);
+            env.set_object_array_element(array, i as i32, value).unwrap();
+            if owned && !value.is_null() {
+                env.delete_local_ref(value).unwrap();
+            }
+        }
+
+        array
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T, U> FromJavaValue<'env, 'borrow> for Box<[T]>
+where
+    T: ArrayElement + FromJavaValue<'env, 'borrow, Source = U>,
+    U: JavaValue<'env>,
+{
+    type Source = JObject<'env>;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        let len = env.get_array_length(s.into_raw()).unwrap();
+
+        (0..len)
+            .map(|i| {
+                let el = env.get_object_array_element(s.into_raw(), i).unwrap();
+                let source = U::unbox(el, env);
+                // Boxed primitives aren't needed once unboxed, nor are elements not kept by their converted value
+                let owned = !<T as FromJavaValue<'env, 'borrow>>::KEEPS_SOURCE_REF || JavaValue::as_object(&source).is_none();
+                let value = T::from(source, env);
+                if owned && !el.is_null() {
+                    env.delete_local_ref(el).unwrap();
+                }
+                value
+            })
             .collect()
     }
 }
@@ -0,0 +1,194 @@
+//! Conversions between Rust unsigned integers and Java (signed) integer types.
+//!
+//! Java has no unsigned integer types, so unsigned values are mapped to the signed type of the same width.
+//! Values that don't fit in the signed type can either be reinterpreted bit by bit (as `Integer.toUnsignedLong` and
+//! friends do on the Java side) or rejected, and the choice is made explicit with a wrapper type:
+//!
+//! | **Rust**                                   | **Java** | **Out of range values**              |
+//! |--------------------------------------------|----------|--------------------------------------|
+//! | [`Wrapping<u8>`](std::num::Wrapping)       | byte     | reinterpreted (e.g. `255` ↔ `-1`)    |
+//! | [`Wrapping<u16>`](std::num::Wrapping)      | short    | reinterpreted                        |
+//! | [`Wrapping<u32>`](std::num::Wrapping)      | int      | reinterpreted                        |
+//! | [`Wrapping<u64>`](std::num::Wrapping)      | long     | reinterpreted                        |
+//! | [`Checked<u8>`]                            | byte     | rejected                             |
+//! | [`Checked<u16>`]                           | short    | rejected                             |
+//! | [`Checked<u32>`], `u32`                    | int      | rejected                             |
+//! | [`Checked<u64>`], `u64`                    | long     | rejected                             |
+//!
+//! Rejected values fail conversion: with `#[call_type(safe)]` a Java exception is thrown, while with
+//! `#[call_type(unchecked)]` the conversion panics. Negative Java values are out of range for all checked conversions.
+//!
+//! Plain `u8` and `u16` are the JNI representations of `boolean` and `char` respectively, so they must be wrapped to be
+//! converted to a Java `byte` or `short`. `u8` has no conversion of its own, so that byte buffers (`Vec<u8>`, `Box<[u8]>`
+//! and `&[u8]`) are converted to a Java `byte[]`.
+//!
+//! Example:
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! # #[bridge]
+//! # mod jni {
+//!     # use std::num::Wrapping;
+//!     # use robusta_jni::convert::Checked;
+//! #[package(com.example)]
+//! struct Hashes;
+//!
+//! impl Hashes {
+//!     pub extern "jni" fn fnv1a(bytes: Box<[u8]>) -> Wrapping<u32> {
+//!         bytes.iter().fold(Wrapping(0x811c9dc5), |hash, &b| (hash ^ Wrapping(b as u32)) * Wrapping(0x01000193))
+//!     }
+//!
+//!     pub extern "jni" fn bucket(hash: Wrapping<u32>, buckets: Checked<u16>) -> u32 {
+//!         hash.0 % buckets.0 as u32
+//!     }
+//! }
+//! # }
+//! ```
+
+use std::convert::TryFrom;
+use std::num::Wrapping;
+
+use jni::errors::{Error, Result as JniResult};
+use jni::sys::{jbyte, jint, jlong, jshort};
+use jni::JNIEnv;
+
+use crate::convert::{FromJavaValue, IntoJavaValue, Signature, TryFromJavaValue, TryIntoJavaValue};
+
+/// Unsigned integer converted to the Java signed integer type of the same width, failing conversion
+/// for values that don't fit (see the [module documentation](self)).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Checked<T>(pub T);
+
+macro_rules! unsigned_conversions {
+    ($($unsigned:ident: $signed:ident ($java:literal)),+) => {
+        $(
+            impl Signature for Wrapping<$unsigned> {
+                const SIG_TYPE: &'static str = <$signed as Signature>::SIG_TYPE;
+            }
+
+            impl<'env> IntoJavaValue<'env> for Wrapping<$unsigned> {
+                type Target = $signed;
+
+                fn into(self, _env: &JNIEnv<'env>) -> Self::Target {
+                    self.0 as $signed
+                }
+            }
+
+            impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for Wrapping<$unsigned> {
+                type Source = $signed;
+
+                fn from(s: Self::Source, _env: &'borrow JNIEnv<'env>) -> Self {
+                    Wrapping(s as $unsigned)
+                }
+            }
+
+            impl<'env> TryIntoJavaValue<'env> for Wrapping<$unsigned> {
+                type Target = $signed;
+
+                fn try_into(self, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+                    Ok(IntoJavaValue::into(self, env))
+                }
+            }
+
+            impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for Wrapping<$unsigned> {
+                type Source = $signed;
+
+                fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+                    Ok(FromJavaValue::from(s, env))
+                }
+            }
+
+            impl Signature for Checked<$unsigned> {
+                const SIG_TYPE: &'static str = <$signed as Signature>::SIG_TYPE;
+            }
+
+            impl<'env> TryIntoJavaValue<'env> for Checked<$unsigned> {
+                type Target = $signed;
+
+                fn try_into(self, _env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+                    <$signed as TryFrom<$unsigned>>::try_from(self.0)
+                        .map_err(|_| Error::WrongJValueType(stringify!($unsigned), $java))
+                }
+            }
+
+            impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for Checked<$unsigned> {
+                type Source = $signed;
+
+                fn try_from(s: Self::Source, _env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+                    <$unsigned as TryFrom<$signed>>::try_from(s)
+                        .map(Checked)
+                        .map_err(|_| Error::WrongJValueType($java, stringify!($unsigned)))
+                }
+            }
+
+            impl<'env> IntoJavaValue<'env> for Checked<$unsigned> {
+                type Target = $signed;
+
+                fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+                    TryIntoJavaValue::try_into(self, env)
+                        .unwrap_or_else(|_| panic!("{} is out of range for a Java {}", self.0, $java))
+                }
+            }
+
+            impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for Checked<$unsigned> {
+                type Source = $signed;
+
+                fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+                    TryFromJavaValue::try_from(s, env)
+                        .unwrap_or_else(|_| panic!("Java {} {} is out of range for {}", $java, s, stringify!($unsigned)))
+                }
+            }
+        )+
+    };
+}
+
+unsigned_conversions! {
+    u8: jbyte ("byte"),
+    u16: jshort ("short"),
+    u32: jint ("int"),
+    u64: jlong ("long")
+}
+
+macro_rules! checked_by_default {
+    ($($unsigned:ident),+) => {
+        $(
+            impl Signature for $unsigned {
+                const SIG_TYPE: &'static str = <Checked<$unsigned> as Signature>::SIG_TYPE;
+            }
+
+            impl<'env> IntoJavaValue<'env> for $unsigned {
+                type Target = <Checked<$unsigned> as IntoJavaValue<'env>>::Target;
+
+                fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+                    IntoJavaValue::into(Checked(self), env)
+                }
+            }
+
+            impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for $unsigned {
+                type Source = <Checked<$unsigned> as FromJavaValue<'env, 'borrow>>::Source;
+
+                fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+                    <Checked<$unsigned> as FromJavaValue>::from(s, env).0
+                }
+            }
+
+            impl<'env> TryIntoJavaValue<'env> for $unsigned {
+                type Target = <Checked<$unsigned> as TryIntoJavaValue<'env>>::Target;
+
+                fn try_into(self, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+                    TryIntoJavaValue::try_into(Checked(self), env)
+                }
+            }
+
+            impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for $unsigned {
+                type Source = <Checked<$unsigned> as TryFromJavaValue<'env, 'borrow>>::Source;
+
+                fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+                    <Checked<$unsigned> as TryFromJavaValue>::try_from(s, env).map(|c| c.0)
+                }
+            }
+        )+
+    };
+}
+
+checked_by_default!(u32, u64);
@@ -0,0 +1,173 @@
+use std::marker::PhantomData;
+
+use jni::errors::{Error, Result as JniResult};
+use jni::objects::JObject;
+use jni::sys::jweak;
+use jni::{JNIEnv, JavaVM};
+
+use crate::convert::{FromJavaValue, Global, GlobalInstance, IntoJavaValue, Signature, TryFromJavaValue, TryIntoJavaValue};
+
+/// A weak handle to a Java object, which doesn't prevent it from being garbage collected.
+///
+/// Like [`Global`], the handle is not bound to a `JNIEnv` and can be stored and sent to other threads. It owns a weak global
+/// reference (see `NewWeakGlobalRef`), so that e.g. listeners registered from Java can be referenced from Rust without
+/// being kept alive by native code. [`upgrade`](WeakGlobal::upgrade) returns the object while it's still reachable.
+///
+/// Derived structs get a `to_weak(env)` method to create a handle. Handles are also converted from and to Java objects,
+/// so they can be parameters of exported methods and fields of derived structs: they're converted to `null` once the
+/// object was collected.
+///
+/// Example:
+/// ```ignore
+/// let listener = listener.to_weak(env)?;
+///
+/// std::thread::spawn(move || {
+///     listener.with(|env, listener| listener.onEvent(env, "done")).unwrap()
+/// });
+/// ```
+pub struct WeakGlobal<T> {
+    vm: JavaVM,
+    object: jweak,
+    _marker: PhantomData<fn() -> T>,
+}
+
+// Weak global references are valid on any thread
+unsafe impl<T> Send for WeakGlobal<T> {}
+unsafe impl<T> Sync for WeakGlobal<T> {}
+
+impl<T> WeakGlobal<T> {
+    /// Create a weak handle to `object`.
+    pub fn new(env: &JNIEnv, object: JObject) -> JniResult<Self> {
+        let raw = env.get_native_interface();
+        let object = unsafe {
+            let new_weak_global_ref = (**raw)
+                .NewWeakGlobalRef
+                .ok_or(Error::JNIEnvMethodNotFound("NewWeakGlobalRef"))?;
+            new_weak_global_ref(raw, object.into_raw())
+        };
+        if env.exception_check()? {
+            return Err(Error::JavaException);
+        }
+        if object.is_null() {
+            return Err(Error::NullPtr("NewWeakGlobalRef result"));
+        }
+
+        Ok(WeakGlobal {
+            vm: env.get_java_vm()?,
+            object,
+            _marker: PhantomData,
+        })
+    }
+
+    /// New local reference to the Java object, or `None` if it was garbage collected.
+    pub fn local_ref<'env>(&self, env: &JNIEnv<'env>) -> JniResult<Option<JObject<'env>>> {
+        let local = env.new_local_ref(unsafe { JObject::from_raw(self.object) })?;
+        Ok(if local.is_null() { None } else { Some(local) })
+    }
+
+    /// Whether the Java object was garbage collected.
+    pub fn is_collected(&self, env: &JNIEnv) -> JniResult<bool> {
+        env.is_same_object(unsafe { JObject::from_raw(self.object) }, JObject::null())
+    }
+
+    /// Strong handle to the Java object, keeping it alive, or `None` if it was garbage collected.
+    pub fn upgrade_global(&self, env: &JNIEnv) -> JniResult<Option<Global<T>>> {
+        match self.local_ref(env)? {
+            Some(local) => {
+                let global = Global::new(env, local);
+                env.delete_local_ref(local)?;
+                global.map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Java VM the object belongs to.
+    pub fn vm(&self) -> &JavaVM {
+        &self.vm
+    }
+}
+
+impl<T: GlobalInstance> WeakGlobal<T> {
+    /// Local instance of the Java object, or `None` if it was garbage collected.
+    pub fn upgrade<'env: 'borrow, 'borrow>(&self, env: &'borrow JNIEnv<'env>) -> JniResult<Option<T::Local<'env, 'borrow>>> {
+        match self.local_ref(env)? {
+            Some(local) => TryFromJavaValue::try_from(local, env).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Run `f` with a local instance of the Java object, attaching the current thread to the JVM if needed. Returns
+    /// `None` if the object was garbage collected.
+    ///
+    /// If the thread was not already attached, it's detached when `f` returns.
+    pub fn with<F, R>(&self, f: F) -> JniResult<Option<R>>
+    where
+        F: for<'env> FnOnce(&'env JNIEnv<'env>, T::Local<'env, 'env>) -> R,
+    {
+        let guard = self.vm.attach_current_thread()?;
+        let env: &JNIEnv = &guard;
+
+        let result = self.upgrade(env)?.map(|instance| f(env, instance));
+
+        Ok(result)
+    }
+}
+
+impl<T> Drop for WeakGlobal<T> {
+    fn drop(&mut self) {
+        // Attach while releasing the reference, so that handles can be dropped from any thread
+        if let Ok(env) = self.vm.attach_current_thread() {
+            let raw = env.get_native_interface();
+            unsafe {
+                if let Some(delete_weak_global_ref) = (**raw).DeleteWeakGlobalRef {
+                    delete_weak_global_ref(raw, self.object);
+                }
+            }
+        }
+    }
+}
+
+impl<T: Signature> Signature for WeakGlobal<T> {
+    const SIG_TYPE: &'static str = T::SIG_TYPE;
+}
+
+impl<T: Signature> Signature for &WeakGlobal<T> {
+    const SIG_TYPE: &'static str = T::SIG_TYPE;
+}
+
+impl<'env: 'borrow, 'borrow, T: Signature> TryFromJavaValue<'env, 'borrow> for WeakGlobal<T> {
+    type Source = JObject<'env>;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+        WeakGlobal::new(env, s)
+    }
+}
+
+impl<'env, T: Signature> TryIntoJavaValue<'env> for &WeakGlobal<T> {
+    type Target = JObject<'env>;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+        Ok(self.local_ref(env)?.unwrap_or_else(JObject::null))
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T: Signature> FromJavaValue<'env, 'borrow> for WeakGlobal<T> {
+    type Source = JObject<'env>;
+    const KEEPS_SOURCE_REF: bool = false;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        WeakGlobal::new(env, s).unwrap()
+    }
+}
+
+impl<'env, T: Signature> IntoJavaValue<'env> for &WeakGlobal<T> {
+    type Target = JObject<'env>;
+    const NEW_LOCAL_REF: bool = true;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        self.local_ref(env).unwrap().unwrap_or_else(JObject::null)
+    }
+}
@@ -0,0 +1,320 @@
+//! Runtime support for `#[kotlin_suspend]` on imported (`extern "java"`) methods. **Experimental.**
+//!
+//! A Kotlin `suspend fun fetch(id: Int): String` is compiled to a method `fetch(int, kotlin.coroutines.Continuation)`
+//! returning an `Object`: either the result of the function, or the `COROUTINE_SUSPENDED` marker if the function
+//! suspended, in which case the result is passed to the continuation later, possibly from another thread.
+//!
+//! Methods marked with `#[kotlin_suspend]` are declared with the parameters and the return type of the Kotlin function,
+//! and calls block the current thread until the function completes:
+//! ```ignore
+//! #[kotlin_suspend]
+//! pub extern "java" fn fetch(env: &JNIEnv, id: i32) -> JniResult<String> {}
+//! ```
+//!
+//! The continuation passed to the function has an empty coroutine context, so the function resumes on the threads of
+//! the dispatchers it selects itself (e.g. with `withContext(Dispatchers.IO)`), and is not cancelled by any parent job.
+//! Exceptions thrown by the function are thrown by the call. Functions can't be called from Rust without blocking yet.
+//!
+//! The continuation is a `java.lang.reflect.Proxy` implemented with method handles, so no Java code has to be loaded,
+//! but the Kotlin standard library must be on the class path.
+
+use std::str::FromStr;
+
+use jni::errors::{Error, Result as JniResult};
+use jni::objects::{JClass, JObject, JThrowable, JValue};
+use jni::signature::{Primitive, ReturnType};
+use jni::JNIEnv;
+
+use crate::convert::JavaValue;
+use crate::frame;
+use crate::loader::find_class;
+
+const CONTINUATION_CLASS: &str = "kotlin/coroutines/Continuation";
+const EMPTY_CONTEXT_CLASS: &str = "kotlin/coroutines/EmptyCoroutineContext";
+const SINGLETONS_CLASS: &str = "kotlin/coroutines/intrinsics/CoroutineSingletons";
+const FAILURE_CLASS: &str = "kotlin/Result$Failure";
+const FUTURE_CLASS: &str = "java/util/concurrent/CompletableFuture";
+const METHOD_HANDLES_CLASS: &str = "java/lang/invoke/MethodHandles";
+const METHOD_HANDLE_SIG: &str = "Ljava/lang/invoke/MethodHandle;";
+
+/// Call a Kotlin suspend function and wait for its result.
+///
+/// `call` calls the function with the continuation it is given as last argument. The result is converted
+/// to a value of type `return_sig`, unboxing primitive values.
+pub fn call_suspend<'env, F>(env: &JNIEnv<'env>, return_sig: &str, call: F) -> JniResult<JValue<'env>>
+where
+    F: FnOnce(JObject<'env>) -> JniResult<JValue<'env>>,
+{
+    // Only the result is kept, so that calls in a loop don't exhaust the local reference table
+    frame::with_local_frame(env, 16, || {
+        let future = env.new_object(FUTURE_CLASS, "()V", &[])?;
+        let continuation = continuation(env, future)?;
+
+        let mut result = call(continuation)?.l()?;
+        let suspended = env
+            .get_static_field(find_class(env, SINGLETONS_CLASS)?, "COROUTINE_SUSPENDED", format!("L{};", SINGLETONS_CLASS))?
+            .l()?;
+        if env.is_same_object(result, suspended)? {
+            result = env.call_method(future, "get", "()Ljava/lang/Object;", &[])?.l()?;
+        }
+
+        // Results passed to the continuation are unboxed `kotlin.Result`s, which wrap exceptions in a `Result.Failure`
+        if env.is_instance_of(result, find_class(env, FAILURE_CLASS)?)? {
+            let exception = env.get_field(result, "exception", "Ljava/lang/Throwable;")?.l()?;
+            env.throw(JThrowable::from(exception))?;
+            return Err(Error::JavaException);
+        }
+
+        unbox(env, result, return_sig)
+    })
+}
+
+/// Value of the boxed `value` as a value of type `sig`.
+fn unbox<'env>(env: &JNIEnv<'env>, value: JObject<'env>, sig: &str) -> JniResult<JValue<'env>> {
+    let method = match ReturnType::from_str(sig)? {
+        ReturnType::Object | ReturnType::Array => return Ok(JValue::Object(value)),
+        // Kotlin functions without a return value return `Unit`
+        ReturnType::Primitive(Primitive::Void) => return Ok(JValue::Void),
+        ReturnType::Primitive(Primitive::Boolean) => "booleanValue",
+        ReturnType::Primitive(Primitive::Char) => "charValue",
+        ReturnType::Primitive(Primitive::Byte) => "byteValue",
+        ReturnType::Primitive(Primitive::Short) => "shortValue",
+        ReturnType::Primitive(Primitive::Int) => "intValue",
+        ReturnType::Primitive(Primitive::Long) => "longValue",
+        ReturnType::Primitive(Primitive::Float) => "floatValue",
+        ReturnType::Primitive(Primitive::Double) => "doubleValue",
+    };
+
+    env.call_method(value, method, format!("(){}", sig), &[])
+}
+
+/// Continuation completing `future` with its result.
+///
+/// It is a proxy whose invocation handler is built from method handles, dispatching on the name of the invoked method:
+///  * `resumeWith(result)` calls `future.complete(result)`
+///  * `getContext()` returns `EmptyCoroutineContext`
+///  * other methods (`toString`, `hashCode` and `equals`) are invoked on `future`
+fn continuation<'env>(env: &JNIEnv<'env>, future: JObject<'env>) -> JniResult<JObject<'env>> {
+    env.with_local_frame(64, || {
+        let object = env.find_class("java/lang/Object")?;
+        let object_array = env.find_class("[Ljava/lang/Object;")?;
+        let method = env.find_class("java/lang/reflect/Method")?;
+        let method_handle = env.find_class("java/lang/invoke/MethodHandle")?;
+        let boolean = JClass::from(env.get_static_field("java/lang/Boolean", "TYPE", "Ljava/lang/Class;")?.l()?);
+        let lookup = env
+            .call_static_method(METHOD_HANDLES_CLASS, "publicLookup", "()Ljava/lang/invoke/MethodHandles$Lookup;", &[])?
+            .l()?;
+
+        // (Object proxy, Method method, Object[] args) -> future.complete(args[0])
+        let complete = find_method(env, lookup, "findVirtual", FUTURE_CLASS, "complete", boolean, &[object])?;
+        let complete = bind_to(env, complete, future)?;
+        let first = call_handles(env, "arrayElementGetter", "(Ljava/lang/Class;)", &[object_array.into()])?;
+        let first = insert_argument(env, first, 1, JavaValue::autobox(0, env))?;
+        let resume = filter_arguments(env, complete, &[first])?;
+        let resume = as_type(env, resume, object, &[object_array])?;
+        let resume = drop_arguments(env, resume, &[object, method])?;
+
+        // (Object proxy, Method method, Object[] args) -> EmptyCoroutineContext.INSTANCE
+        let context = env
+            .get_static_field(find_class(env, EMPTY_CONTEXT_CLASS)?, "INSTANCE", format!("L{};", EMPTY_CONTEXT_CLASS))?
+            .l()?;
+        let get_context = call_handles(env, "constant", "(Ljava/lang/Class;Ljava/lang/Object;)", &[object.into(), context.into()])?;
+        let get_context = drop_arguments(env, get_context, &[object, method, object_array])?;
+
+        // (Object proxy, Method method, Object[] args) -> lookup.unreflect(method).bindTo(future).invokeWithArguments(args)
+        // `Method.invoke` is caller sensitive, so it can't be called through a public lookup
+        let unreflect = find_method(env, lookup, "findVirtual", "java/lang/invoke/MethodHandles$Lookup", "unreflect", method_handle, &[method])?;
+        let unreflect = bind_to(env, unreflect, lookup)?;
+        let bind = find_method(env, lookup, "findVirtual", "java/lang/invoke/MethodHandle", "bindTo", method_handle, &[object])?;
+        let bind = insert_argument(env, bind, 1, future)?;
+        let on_future = call_handles(env, "filterReturnValue", "(Ljava/lang/invoke/MethodHandle;Ljava/lang/invoke/MethodHandle;)", &[
+            unreflect.into(),
+            bind.into(),
+        ])?;
+        // Proxies are invoked with null arguments for methods without parameters
+        let non_null = find_method(env, lookup, "findStatic", "java/util/Objects", "requireNonNullElse", object, &[object, object])?;
+        let no_args = object_array_of(env, "java/lang/Object", &[])?;
+        let non_null = insert_argument(env, non_null, 1, no_args)?;
+        let non_null = as_type(env, non_null, object_array, &[object_array])?;
+        let invoke = find_method(env, lookup, "findVirtual", "java/lang/invoke/MethodHandle", "invokeWithArguments", object, &[object_array])?;
+        let invoke = filter_arguments(env, invoke, &[on_future, non_null])?;
+        let invoke = drop_arguments(env, invoke, &[object])?;
+
+        let handler = guard_method_name(env, lookup, "getContext", get_context, invoke)?;
+        let handler = guard_method_name(env, lookup, "resumeWith", resume, handler)?;
+
+        let invocation_handler = env.find_class("java/lang/reflect/InvocationHandler")?;
+        let invocation_handler = env
+            .call_static_method(
+                "java/lang/invoke/MethodHandleProxies",
+                "asInterfaceInstance",
+                format!("(Ljava/lang/Class;{})Ljava/lang/Object;", METHOD_HANDLE_SIG),
+                &[invocation_handler.into(), handler.into()],
+            )?
+            .l()?;
+
+        let continuation_class = find_class(env, CONTINUATION_CLASS)?;
+        let loader = env
+            .call_method(continuation_class, "getClassLoader", "()Ljava/lang/ClassLoader;", &[])?
+            .l()?;
+        let interfaces = object_array_of(env, "java/lang/Class", &[continuation_class.into()])?;
+
+        env.call_static_method(
+            "java/lang/reflect/Proxy",
+            "newProxyInstance",
+            "(Ljava/lang/ClassLoader;[Ljava/lang/Class;Ljava/lang/reflect/InvocationHandler;)Ljava/lang/Object;",
+            &[loader.into(), interfaces.into(), invocation_handler.into()],
+        )?
+        .l()
+    })
+}
+
+/// `MethodHandles.<name>(args)`, with `params` the parameter part of the method descriptor.
+fn call_handles<'env>(env: &JNIEnv<'env>, name: &str, params: &str, args: &[JValue]) -> JniResult<JObject<'env>> {
+    env.with_local_frame(8, || {
+        env.call_static_method(METHOD_HANDLES_CLASS, name, format!("{}{}", params, METHOD_HANDLE_SIG), args)?
+            .l()
+    })
+}
+
+/// `MethodType.methodType(ret, params)`.
+fn method_type<'env>(env: &JNIEnv<'env>, ret: JClass, params: &[JClass]) -> JniResult<JObject<'env>> {
+    env.with_local_frame(8, || {
+        let params: Vec<JObject> = params.iter().map(|&c| c.into()).collect();
+        let params = object_array_of(env, "java/lang/Class", &params)?;
+
+        env.call_static_method(
+            "java/lang/invoke/MethodType",
+            "methodType",
+            "(Ljava/lang/Class;[Ljava/lang/Class;)Ljava/lang/invoke/MethodType;",
+            &[ret.into(), params.into()],
+        )?
+        .l()
+    })
+}
+
+/// Handle of the public method `name` of `class`, with `finder` either `findVirtual` or `findStatic`.
+fn find_method<'env>(
+    env: &JNIEnv<'env>,
+    lookup: JObject<'env>,
+    finder: &str,
+    class: &str,
+    name: &str,
+    ret: JClass,
+    params: &[JClass],
+) -> JniResult<JObject<'env>> {
+    env.with_local_frame(16, || {
+        let class = env.find_class(class)?;
+        let name = env.new_string(name)?;
+        let method_type = method_type(env, ret, params)?;
+
+        env.call_method(
+            lookup,
+            finder,
+            format!("(Ljava/lang/Class;Ljava/lang/String;Ljava/lang/invoke/MethodType;){}", METHOD_HANDLE_SIG),
+            &[class.into(), name.into(), method_type.into()],
+        )?
+        .l()
+    })
+}
+
+fn bind_to<'env>(env: &JNIEnv<'env>, handle: JObject<'env>, value: JObject) -> JniResult<JObject<'env>> {
+    env.call_method(handle, "bindTo", format!("(Ljava/lang/Object;){}", METHOD_HANDLE_SIG), &[value.into()])?
+        .l()
+}
+
+fn as_type<'env>(env: &JNIEnv<'env>, handle: JObject<'env>, ret: JClass, params: &[JClass]) -> JniResult<JObject<'env>> {
+    env.with_local_frame(16, || {
+        let method_type = method_type(env, ret, params)?;
+
+        env.call_method(
+            handle,
+            "asType",
+            format!("(Ljava/lang/invoke/MethodType;){}", METHOD_HANDLE_SIG),
+            &[method_type.into()],
+        )?
+        .l()
+    })
+}
+
+/// `handle` with `value` bound to the parameter at `pos`.
+fn insert_argument<'env>(env: &JNIEnv<'env>, handle: JObject, pos: i32, value: JObject) -> JniResult<JObject<'env>> {
+    env.with_local_frame(16, || {
+        let values = object_array_of(env, "java/lang/Object", &[value])?;
+
+        call_handles(env, "insertArguments", "(Ljava/lang/invoke/MethodHandle;I[Ljava/lang/Object;)", &[
+            handle.into(),
+            JValue::Int(pos),
+            values.into(),
+        ])
+    })
+}
+
+/// `handle` with its leading arguments transformed by `filters`.
+fn filter_arguments<'env>(env: &JNIEnv<'env>, handle: JObject, filters: &[JObject]) -> JniResult<JObject<'env>> {
+    env.with_local_frame(16, || {
+        let filters = object_array_of(env, "java/lang/invoke/MethodHandle", filters)?;
+
+        call_handles(env, "filterArguments", "(Ljava/lang/invoke/MethodHandle;I[Ljava/lang/invoke/MethodHandle;)", &[
+            handle.into(),
+            JValue::Int(0),
+            filters.into(),
+        ])
+    })
+}
+
+/// `handle` with ignored leading parameters of types `types`.
+fn drop_arguments<'env>(env: &JNIEnv<'env>, handle: JObject, types: &[JClass]) -> JniResult<JObject<'env>> {
+    env.with_local_frame(16, || {
+        let types: Vec<JObject> = types.iter().map(|&c| c.into()).collect();
+        let types = object_array_of(env, "java/lang/Class", &types)?;
+
+        call_handles(env, "dropArguments", "(Ljava/lang/invoke/MethodHandle;I[Ljava/lang/Class;)", &[
+            handle.into(),
+            JValue::Int(0),
+            types.into(),
+        ])
+    })
+}
+
+/// Invocation handler calling `target` for methods named `name`, and `fallback` for other methods.
+fn guard_method_name<'env>(
+    env: &JNIEnv<'env>,
+    lookup: JObject<'env>,
+    name: &str,
+    target: JObject,
+    fallback: JObject,
+) -> JniResult<JObject<'env>> {
+    env.with_local_frame(64, || {
+        let object = env.find_class("java/lang/Object")?;
+        let string = env.find_class("java/lang/String")?;
+        let boolean = JClass::from(env.get_static_field("java/lang/Boolean", "TYPE", "Ljava/lang/Class;")?.l()?);
+
+        // (Object proxy, Method method) -> name.equals(method.getName())
+        let get_name = find_method(env, lookup, "findVirtual", "java/lang/reflect/Method", "getName", string, &[])?;
+        let equals = find_method(env, lookup, "findVirtual", "java/lang/String", "equals", boolean, &[object])?;
+        let equals = bind_to(env, equals, env.new_string(name)?.into())?;
+        let equals = as_type(env, equals, boolean, &[string])?;
+        let test = call_handles(env, "filterReturnValue", "(Ljava/lang/invoke/MethodHandle;Ljava/lang/invoke/MethodHandle;)", &[
+            get_name.into(),
+            equals.into(),
+        ])?;
+        let test = drop_arguments(env, test, &[object])?;
+
+        call_handles(env, "guardWithTest", "(Ljava/lang/invoke/MethodHandle;Ljava/lang/invoke/MethodHandle;Ljava/lang/invoke/MethodHandle;)", &[
+            test.into(),
+            target.into(),
+            fallback.into(),
+        ])
+    })
+}
+
+/// New `class[]` array with `elements`.
+fn object_array_of<'env>(env: &JNIEnv<'env>, class: &str, elements: &[JObject]) -> JniResult<JObject<'env>> {
+    let array = env.new_object_array(elements.len() as i32, class, JObject::null())?;
+    for (i, &element) in elements.iter().enumerate() {
+        env.set_object_array_element(array, i as i32, element)?;
+    }
+
+    Ok(unsafe { JObject::from_raw(array) })
+}
@@ -0,0 +1,140 @@
+//! Runtime support for `#[call(...)]` on imported (`extern "java"`) self methods.
+//!
+//! By default, imported self methods are called with virtual dispatch on the class of the receiver, like a method call
+//! in Java code. The `#[call]` attribute selects another kind of dispatch:
+//!  * `#[call(virtual)]`: the default
+//!  * `#[call(nonvirtual)]`: calls the implementation declared (or inherited) by the class of the bridged struct,
+//!    even if the receiver belongs to a subclass overriding it, like `super.method()` in Java code. See [`call_nonvirtual`]
+//!  * `#[call(interface)]`: the bridged struct is an interface, and the method is looked up on it instead of on the class
+//!    of the receiver. Calls are still dispatched to the implementation of the receiver (or to a default method).
+//!    See [`call_interface`]
+//!
+//! ```ignore
+//! // Calls `Base.kind()`, even on instances of subclasses overriding it
+//! #[call(nonvirtual)]
+//! pub extern "java" fn kind(&self, env: &JNIEnv) -> JniResult<String> {}
+//! ```
+
+use jni::descriptors::Desc;
+use jni::errors::{Error, Result as JniResult};
+use jni::objects::{JClass, JMethodID, JObject, JValue};
+use jni::signature::{Primitive, ReturnType, TypeSignature};
+use jni::sys::jvalue;
+use jni::JNIEnv;
+
+/// Call method `name` with signature `sig` of `class` on `obj`, without virtual dispatch.
+///
+/// The implementation called is the one `class` declares or inherits, even if the class of `obj` overrides it.
+/// `obj` must be an instance of `class`.
+pub fn call_nonvirtual<'env, 'c, O, C>(
+    env: &JNIEnv<'env>,
+    obj: O,
+    class: C,
+    name: &str,
+    sig: &str,
+    args: &[JValue],
+) -> JniResult<JValue<'env>>
+where
+    O: Into<JObject<'env>>,
+    C: Desc<'env, JClass<'c>>,
+{
+    let obj = obj.into();
+    if obj.is_null() {
+        return Err(Error::NullPtr("call_nonvirtual obj argument"));
+    }
+
+    let class = class.lookup(env)?;
+    let (method_id, ret, args) = lookup_method(env, class, name, sig, args)?;
+
+    let raw = env.get_native_interface();
+    let (obj, class, method_id, args) = (obj.into_raw(), class.into_raw(), method_id.into_raw(), args.as_ptr());
+
+    macro_rules! call {
+        ($function:ident) => {
+            unsafe {
+                let function = (**raw).$function.ok_or(Error::JNIEnvMethodNotFound(stringify!($function)))?;
+                function(raw, obj, class, method_id, args)
+            }
+        };
+    }
+
+    let value = match ret {
+        ReturnType::Object | ReturnType::Array => {
+            let obj = call!(CallNonvirtualObjectMethodA);
+            JValue::Object(unsafe { JObject::from_raw(obj) })
+        }
+        ReturnType::Primitive(Primitive::Boolean) => JValue::Bool(call!(CallNonvirtualBooleanMethodA)),
+        ReturnType::Primitive(Primitive::Byte) => JValue::Byte(call!(CallNonvirtualByteMethodA)),
+        ReturnType::Primitive(Primitive::Char) => JValue::Char(call!(CallNonvirtualCharMethodA)),
+        ReturnType::Primitive(Primitive::Short) => JValue::Short(call!(CallNonvirtualShortMethodA)),
+        ReturnType::Primitive(Primitive::Int) => JValue::Int(call!(CallNonvirtualIntMethodA)),
+        ReturnType::Primitive(Primitive::Long) => JValue::Long(call!(CallNonvirtualLongMethodA)),
+        ReturnType::Primitive(Primitive::Float) => JValue::Float(call!(CallNonvirtualFloatMethodA)),
+        ReturnType::Primitive(Primitive::Double) => JValue::Double(call!(CallNonvirtualDoubleMethodA)),
+        ReturnType::Primitive(Primitive::Void) => {
+            call!(CallNonvirtualVoidMethodA);
+            JValue::Void
+        }
+    };
+
+    if env.exception_check()? {
+        return Err(Error::JavaException);
+    }
+
+    Ok(value)
+}
+
+/// Call method `name` with signature `sig` of interface `interface` on `obj`.
+///
+/// The method is looked up on `interface` rather than on the class of `obj`, so that calls fail with a `NoSuchMethodError`
+/// if the interface doesn't declare it, and with an `IncompatibleClassChangeError` if `obj` doesn't implement the interface.
+/// The implementation called is the one of the class of `obj`, or the default method of the interface.
+pub fn call_interface<'env, 'c, O, C>(
+    env: &JNIEnv<'env>,
+    obj: O,
+    interface: C,
+    name: &str,
+    sig: &str,
+    args: &[JValue],
+) -> JniResult<JValue<'env>>
+where
+    O: Into<JObject<'env>>,
+    C: Desc<'env, JClass<'c>>,
+{
+    let obj = obj.into();
+    if obj.is_null() {
+        return Err(Error::NullPtr("call_interface obj argument"));
+    }
+
+    let interface = interface.lookup(env)?;
+    if !env.is_instance_of(obj, interface)? {
+        let class_name = crate::convert::debug::class_name(env, obj)?;
+        env.throw_new(
+            "java/lang/IncompatibleClassChangeError",
+            format!("{} does not implement the interface declaring {}{}", class_name, name, sig),
+        )?;
+        return Err(Error::JavaException);
+    }
+
+    let (method_id, ret, args) = lookup_method(env, interface, name, sig, args)?;
+    env.call_method_unchecked(obj, method_id, ret, &args)
+}
+
+/// Method ID and return type of method `name` with signature `sig` of `class`, along with the raw arguments to call it with.
+fn lookup_method<'env>(
+    env: &JNIEnv<'env>,
+    class: JClass<'_>,
+    name: &str,
+    sig: &str,
+    args: &[JValue],
+) -> JniResult<(JMethodID, ReturnType, Vec<jvalue>)> {
+    let parsed = TypeSignature::from_str(sig)?;
+    if parsed.args.len() != args.len() {
+        return Err(Error::InvalidArgList(parsed));
+    }
+
+    let method_id = env.get_method_id(class, name, sig)?;
+    let args = args.iter().map(|a| a.to_jni()).collect();
+
+    Ok((method_id, parsed.ret, args))
+}
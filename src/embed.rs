@@ -0,0 +1,238 @@
+//! Embedding a JVM in a Rust binary.
+//!
+//! Bridged modules are usually compiled into a library loaded by a Java application, but the same bindings can be used
+//! from a standalone Rust binary that starts its own JVM with the [Invocation API](https://docs.oracle.com/en/java/javase/17/docs/specs/jni/invocation.html).
+//! This module (enabled by the `invocation` feature) provides the pieces needed to do so:
+//!  * [`Classpath`], to assemble the class path from jars and class directories
+//!  * [`VmBuilder`], to start a [`JavaVM`] with the class path, the native library path and additional options
+//!  * [`describe_exception`] and [`check_exception`], to turn pending Java exceptions into readable errors
+//!
+//! Once the VM is started, bridged structs are constructed and used as in exported functions, using the `JNIEnv`
+//! of the attached thread:
+//! ```ignore
+//! use robusta_jni::embed::{check_exception, Classpath, VmBuilder};
+//!
+//! let classpath = Classpath::new()
+//!     .entry("build/classes/java/main")
+//!     .jars_in("libs")?;
+//! let vm = VmBuilder::new().classpath(classpath).build()?;
+//! let env = vm.attach_current_thread()?;
+//!
+//! // `User` is a struct of a `#[bridge]` module, with an imported constructor and imported methods
+//! let user = check_exception(&env, User::new(&env, "user".into(), "password".into()))?;
+//! let password = check_exception(&env, user.getPassword(&env))?;
+//! ```
+//!
+//! If the bridged module also exports functions, the library containing them must be loaded by the JVM (e.g. with
+//! `System.loadLibrary` in a static initializer) and its directory must be on the native library path,
+//! see [`VmBuilder::library_path`]. A complete example is in `examples/embed.rs`.
+//!
+//! Note that the JVM can only be created once per process, and that the binary links to the JVM library (`libjvm`),
+//! which must be found by the dynamic loader at startup.
+
+use std::ffi::OsString;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use jni::errors::{Error as JniError, Result as JniResult};
+use jni::objects::JString;
+use jni::{InitArgsBuilder, JNIEnv, JNIVersion, JavaVM, JvmError};
+
+use crate::convert::TryFromJavaValue;
+
+/// Errors raised while starting an embedded JVM or calling into it.
+#[derive(Debug)]
+pub enum EmbedError {
+    /// A class path or library path entry can't be passed to the JVM (e.g. it contains the path separator)
+    InvalidPath(OsString),
+    /// A JVM option is invalid
+    InvalidOption(JvmError),
+    /// A JNI call failed
+    Jni(JniError),
+    /// A Java exception was thrown, with its description (see [`describe_exception`])
+    JavaException(String),
+}
+
+impl Display for EmbedError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            EmbedError::InvalidPath(path) => write!(f, "invalid JVM path: {}", path.to_string_lossy()),
+            EmbedError::InvalidOption(e) => write!(f, "invalid JVM option: {}", e),
+            EmbedError::Jni(e) => write!(f, "JNI call failed: {}", e),
+            EmbedError::JavaException(description) => write!(f, "Java exception thrown: {}", description),
+        }
+    }
+}
+
+impl std::error::Error for EmbedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EmbedError::InvalidOption(e) => Some(e),
+            EmbedError::Jni(e) => Some(e),
+            EmbedError::InvalidPath(_) | EmbedError::JavaException(_) => None,
+        }
+    }
+}
+
+impl From<JniError> for EmbedError {
+    fn from(e: JniError) -> Self {
+        EmbedError::Jni(e)
+    }
+}
+
+impl From<JvmError> for EmbedError {
+    fn from(e: JvmError) -> Self {
+        EmbedError::InvalidOption(e)
+    }
+}
+
+/// Class path of an embedded JVM, made of jars and class directories.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Classpath {
+    entries: Vec<PathBuf>,
+}
+
+impl Classpath {
+    /// Empty class path.
+    pub fn new() -> Self {
+        Classpath::default()
+    }
+
+    /// Class path from the `CLASSPATH` environment variable, empty if it isn't set.
+    pub fn from_env() -> Self {
+        Classpath {
+            entries: std::env::var_os("CLASSPATH")
+                .map(|cp| std::env::split_paths(&cp).collect())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Append a jar or a directory of classes.
+    pub fn entry(mut self, path: impl Into<PathBuf>) -> Self {
+        self.entries.push(path.into());
+        self
+    }
+
+    /// Append all jars in directory `dir` (not recursively), sorted by file name.
+    pub fn jars_in(mut self, dir: impl AsRef<Path>) -> io::Result<Self> {
+        let mut jars = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_file() && path.extension().is_some_and(|ext| ext == "jar") {
+                jars.push(path);
+            }
+        }
+        jars.sort();
+
+        self.entries.extend(jars);
+        Ok(self)
+    }
+
+    /// Entries of the class path, in lookup order.
+    pub fn entries(&self) -> &[PathBuf] {
+        &self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// `-Djava.class.path` option for this class path.
+    pub fn to_option(&self) -> Result<String, EmbedError> {
+        Ok(format!("-Djava.class.path={}", join_paths(&self.entries)?))
+    }
+}
+
+/// Builder for an embedded [`JavaVM`].
+#[derive(Clone, Debug, Default)]
+pub struct VmBuilder {
+    classpath: Classpath,
+    library_path: Vec<PathBuf>,
+    options: Vec<String>,
+}
+
+impl VmBuilder {
+    pub fn new() -> Self {
+        VmBuilder::default()
+    }
+
+    /// Set the class path of the JVM.
+    pub fn classpath(mut self, classpath: Classpath) -> Self {
+        self.classpath = classpath;
+        self
+    }
+
+    /// Append a directory to `java.library.path`, where `System.loadLibrary` looks for native libraries.
+    pub fn library_path(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.library_path.push(dir.into());
+        self
+    }
+
+    /// Add a JVM option (e.g. `-Xcheck:jni` or `-Xmx512m`).
+    pub fn option(mut self, option: impl Into<String>) -> Self {
+        self.options.push(option.into());
+        self
+    }
+
+    /// Start the JVM.
+    pub fn build(self) -> Result<JavaVM, EmbedError> {
+        let mut args = InitArgsBuilder::new().version(JNIVersion::V8);
+
+        if !self.classpath.is_empty() {
+            args = args.option(&self.classpath.to_option()?);
+        }
+        if !self.library_path.is_empty() {
+            args = args.option(&format!("-Djava.library.path={}", join_paths(&self.library_path)?));
+        }
+        for option in &self.options {
+            args = args.option(option);
+        }
+
+        Ok(JavaVM::new(args.build()?)?)
+    }
+}
+
+/// Clear the pending Java exception, if any, and return its description (as given by `Throwable.toString()`).
+pub fn describe_exception(env: &JNIEnv) -> JniResult<Option<String>> {
+    if !env.exception_check()? {
+        return Ok(None);
+    }
+
+    // No other JNI function can be called while the exception is pending
+    let exception = env.exception_occurred()?;
+    env.exception_clear()?;
+    let description = env
+        .call_method(exception, "toString", "()Ljava/lang/String;", &[])?
+        .l()?;
+
+    <String as TryFromJavaValue>::try_from(JString::from(description), env).map(Some)
+}
+
+/// Turn a JNI error caused by a Java exception into [`EmbedError::JavaException`], clearing the exception.
+pub fn check_exception<T>(env: &JNIEnv, result: JniResult<T>) -> Result<T, EmbedError> {
+    match result {
+        Ok(v) => Ok(v),
+        Err(JniError::JavaException) => match describe_exception(env)? {
+            Some(description) => Err(EmbedError::JavaException(description)),
+            None => Err(EmbedError::Jni(JniError::JavaException)),
+        },
+        Err(e) => Err(EmbedError::Jni(e)),
+    }
+}
+
+/// Join `paths` with the platform path separator.
+fn join_paths(paths: &[PathBuf]) -> Result<String, EmbedError> {
+    let joined = std::env::join_paths(paths).map_err(|_| {
+        EmbedError::InvalidPath(
+            paths
+                .iter()
+                .find(|p| std::env::join_paths([p]).is_err())
+                .map(|p| p.clone().into_os_string())
+                .unwrap_or_default(),
+        )
+    })?;
+
+    joined.into_string().map_err(EmbedError::InvalidPath)
+}
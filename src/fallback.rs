@@ -0,0 +1,95 @@
+//! Runtime support for `#[fallback_signature]` on imported (`extern "java"`) methods.
+//!
+//! An imported method can declare alternative signatures, which are tried in order when the method is first called
+//! if the class doesn't declare a method with the primary signature (e.g. because an older version of a Java dependency
+//! is on the class path):
+//! ```ignore
+//! // Primary signature `(Ljava/lang/String;I)V`, falling back to `(Ljava/lang/String;)V`
+//! #[fallback_signature("(Ljava/lang/String;)V")]
+//! pub extern "java" fn log(env: &JNIEnv, message: String, level: i32) -> JniResult<()> {}
+//! ```
+//!
+//! Fallback signatures must have the same return type as the primary one, and their parameters must be a prefix of the
+//! declared parameters: when a fallback signature is selected, trailing arguments are dropped.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use jni::descriptors::Desc;
+use jni::errors::{Error, Result as JniResult};
+use jni::objects::JClass;
+use jni::JNIEnv;
+
+const NO_SUCH_METHOD_ERROR_CLASS: &str = "java/lang/NoSuchMethodError";
+
+/// Cache of the signature selected for an imported method.
+///
+/// The generated code declares one cache per method in a `static`.
+pub struct SignatureCache {
+    /// Index of the selected signature plus one, or zero if no signature was selected yet
+    resolved: AtomicUsize,
+}
+
+impl SignatureCache {
+    pub const fn new() -> Self {
+        SignatureCache {
+            resolved: AtomicUsize::new(0),
+        }
+    }
+
+    /// Select the first signature in `signatures` declared for method `name` of `class`, along with its argument count.
+    ///
+    /// The selection is cached after the first successful lookup. If no signature matches, the `NoSuchMethodError`
+    /// of the last lookup is left pending.
+    pub fn resolve<'env, 'c, 's, C>(
+        &self,
+        env: &JNIEnv<'env>,
+        class: C,
+        name: &str,
+        is_static: bool,
+        signatures: &[(&'s str, usize)],
+    ) -> JniResult<(&'s str, usize)>
+    where
+        C: Desc<'env, JClass<'c>>,
+    {
+        if let Some(idx) = self.resolved.load(Ordering::Relaxed).checked_sub(1) {
+            return Ok(signatures[idx]);
+        }
+
+        let class = class.lookup(env)?;
+        for (idx, &(signature, arg_count)) in signatures.iter().enumerate() {
+            let lookup = if is_static {
+                env.get_static_method_id(class, name, signature).map(|_| ())
+            } else {
+                env.get_method_id(class, name, signature).map(|_| ())
+            };
+
+            match lookup {
+                Ok(()) => {
+                    self.resolved.store(idx + 1, Ordering::Relaxed);
+                    return Ok((signature, arg_count));
+                }
+                Err(Error::JavaException) if idx + 1 < signatures.len() => {
+                    // No other JNI function can be called while the exception is pending
+                    let exception = env.exception_occurred()?;
+                    env.exception_clear()?;
+                    if !env.is_instance_of(exception, NO_SUCH_METHOD_ERROR_CLASS)? {
+                        env.throw(exception)?;
+                        return Err(Error::JavaException);
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(Error::MethodNotFound {
+            name: name.into(),
+            sig: signatures.first().map(|(s, _)| *s).unwrap_or_default().into(),
+        })
+    }
+}
+
+impl Default for SignatureCache {
+    fn default() -> Self {
+        SignatureCache::new()
+    }
+}
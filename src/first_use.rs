@@ -0,0 +1,118 @@
+//! Hooks run on first use of a bridged class.
+//!
+//! A function of a bridged struct marked with `#[on_first_use]` is called once, before the first imported method call
+//! of the struct and the first conversion of a Java object to it (or, for records, in either direction). It can cache
+//! field and method IDs, register loggers or check the Java side of the bridge, without having Java code call an
+//! initialization method first:
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! # #[bridge]
+//! # mod jni {
+//!     # use robusta_jni::convert::{Signature, TryFromJavaValue, TryIntoJavaValue};
+//!     # use robusta_jni::jni::errors::Result as JniResult;
+//!     # use robusta_jni::jni::objects::AutoLocal;
+//!     # use robusta_jni::jni::JNIEnv;
+//! #[derive(Signature, TryIntoJavaValue, TryFromJavaValue)]
+//! #[package(com.example)]
+//! struct Sensor<'env: 'borrow, 'borrow> {
+//!     #[instance]
+//!     raw: AutoLocal<'env, 'borrow>,
+//! }
+//!
+//! impl<'env: 'borrow, 'borrow> Sensor<'env, 'borrow> {
+//!     #[on_first_use]
+//!     fn check_version(env: &JNIEnv) -> JniResult<()> {
+//!         if Sensor::version(env)? < 2 {
+//!             panic!("unsupported sensor version");
+//!         }
+//!         Ok(())
+//!     }
+//!
+//!     pub extern "java" fn version(env: &JNIEnv) -> JniResult<i32> {}
+//!
+//!     pub extern "java" fn read(&self, env: &JNIEnv) -> JniResult<f64> {}
+//! }
+//! # }
+//! ```
+//!
+//! The hook must have the signature `fn(&JNIEnv) -> jni::errors::Result<()>`, and a struct has at most one. Calls from
+//! other threads wait for the hook to complete, while imported methods and conversions of the struct used by the hook
+//! itself don't call it again. If the hook fails, the error is returned by the call or conversion that triggered it
+//! (with `#[call_type(unchecked)]`, it panics), and the hook is called again on next use.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::thread::{self, ThreadId};
+
+use jni::errors::Result as JniResult;
+use jni::JNIEnv;
+
+/// State of a first use hook, usually a `static` declared by generated code.
+pub struct FirstUse {
+    done: AtomicBool,
+    /// Thread running the hook, which can use the struct without waiting for the hook to complete
+    running: Mutex<Option<ThreadId>>,
+    lock: Mutex<()>,
+}
+
+impl FirstUse {
+    pub const fn new() -> Self {
+        FirstUse {
+            done: AtomicBool::new(false),
+            running: Mutex::new(None),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Call `hook` with `env`, unless it already completed successfully or is being run by the current thread.
+    pub fn call<F>(&self, env: &JNIEnv, hook: F) -> JniResult<()>
+    where
+        F: FnOnce(&JNIEnv) -> JniResult<()>,
+    {
+        if self.done.load(Ordering::Acquire) {
+            return Ok(());
+        }
+
+        let current = thread::current().id();
+        if *self.running() == Some(current) {
+            return Ok(());
+        }
+
+        let _lock = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+        if self.done.load(Ordering::Acquire) {
+            return Ok(());
+        }
+
+        *self.running() = Some(current);
+        let _running = Running(self);
+        hook(env)?;
+        self.done.store(true, Ordering::Release);
+
+        Ok(())
+    }
+
+    /// Whether the hook completed successfully.
+    pub fn is_done(&self) -> bool {
+        self.done.load(Ordering::Acquire)
+    }
+
+    fn running(&self) -> std::sync::MutexGuard<'_, Option<ThreadId>> {
+        self.running.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Default for FirstUse {
+    fn default() -> Self {
+        FirstUse::new()
+    }
+}
+
+/// Marks the hook as no longer running when dropped, even if it panics.
+struct Running<'a>(&'a FirstUse);
+
+impl Drop for Running<'_> {
+    fn drop(&mut self) {
+        *self.0.running() = None;
+    }
+}
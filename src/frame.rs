@@ -0,0 +1,128 @@
+//! Local frames around the conversions of bridged methods.
+//!
+//! Every object obtained from JNI is a local reference, which stays alive until the native method returns. Converting
+//! large collections (e.g. a `Vec` of bridged structs) or calling Java methods in a loop from a native thread can exhaust
+//! the local reference table. With `#[local_frame]`, the generated glue of a method runs in a local frame, created with
+//! `PushLocalFrame` and released with `PopLocalFrame`: all the local references created by the conversions of the
+//! parameters and of the result (and, for exported methods, by the method body) are deleted when the call completes,
+//! except for the returned object.
+//!
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! # #[bridge]
+//! # mod jni {
+//!     # use robusta_jni::convert::Signature;
+//!     # use robusta_jni::jni::errors::Result as JniResult;
+//!     # use robusta_jni::jni::JNIEnv;
+//! #[derive(Signature)]
+//! #[package(com.example)]
+//! struct Catalog;
+//!
+//! impl Catalog {
+//!     #[local_frame(capacity = 512)]
+//!     pub extern "jni" fn longest(names: Vec<String>) -> Option<String> {
+//!         names.into_iter().max_by_key(|name| name.len())
+//!     }
+//!
+//!     #[local_frame]
+//!     pub extern "java" fn describe(env: &JNIEnv, name: String) -> JniResult<String> {}
+//! }
+//! # }
+//! ```
+//!
+//! The capacity is the number of local references the JVM guarantees to be available in the frame, and defaults to
+//! [`DEFAULT_CAPACITY`]. [`with_local_frame`] runs any code in a local frame.
+
+use jni::errors::Error;
+use jni::objects::{JObject, JValue};
+use jni::JNIEnv;
+
+use crate::convert::JavaValue;
+
+/// Capacity of the local frames of methods marked with `#[local_frame]` without an explicit capacity
+pub const DEFAULT_CAPACITY: i32 = 64;
+
+/// Values that can be returned from a local frame: their object reference, if any, is moved to the enclosing frame.
+pub trait FrameValue<'env>: Sized {
+    /// The object reference held by the value, or `None` for primitive values and `void`.
+    fn frame_object(&self) -> Option<JObject<'env>>;
+
+    /// The value holding `object` instead, the reference to the same object in the enclosing frame.
+    fn with_frame_object(self, object: JObject<'env>, env: &JNIEnv<'env>) -> Self;
+}
+
+impl<'env, T: JavaValue<'env>> FrameValue<'env> for T {
+    fn frame_object(&self) -> Option<JObject<'env>> {
+        JavaValue::as_object(self)
+    }
+
+    fn with_frame_object(self, object: JObject<'env>, env: &JNIEnv<'env>) -> Self {
+        match JavaValue::as_object(&self) {
+            Some(_) => T::unbox(object, env),
+            None => self,
+        }
+    }
+}
+
+impl<'env> FrameValue<'env> for () {
+    fn frame_object(&self) -> Option<JObject<'env>> {
+        None
+    }
+
+    fn with_frame_object(self, _object: JObject<'env>, _env: &JNIEnv<'env>) -> Self {}
+}
+
+impl<'env> FrameValue<'env> for JValue<'env> {
+    fn frame_object(&self) -> Option<JObject<'env>> {
+        match self {
+            JValue::Object(object) => Some(*object),
+            _ => None,
+        }
+    }
+
+    fn with_frame_object(self, object: JObject<'env>, _env: &JNIEnv<'env>) -> Self {
+        match self {
+            JValue::Object(_) => JValue::Object(object),
+            value => value,
+        }
+    }
+}
+
+/// Run `f` in a new local frame with room for at least `capacity` local references.
+///
+/// All the local references created by `f` are deleted when it returns, except for the object returned in `Ok`, which
+/// is returned as a new local reference of the current frame.
+pub fn with_local_frame<'env, T, E, F>(env: &JNIEnv<'env>, capacity: i32, f: F) -> Result<T, E>
+where
+    T: FrameValue<'env>,
+    E: From<Error>,
+    F: FnOnce() -> Result<T, E>,
+{
+    env.push_local_frame(capacity)?;
+
+    let guard = FrameGuard(env);
+    let result = f();
+    std::mem::forget(guard);
+    let object = result
+        .as_ref()
+        .ok()
+        .and_then(FrameValue::frame_object)
+        .unwrap_or_else(JObject::null);
+    let object = env.pop_local_frame(object);
+
+    let value = result?;
+    Ok(value.with_frame_object(object?, env))
+}
+
+/// Pops the local frame pushed by [`with_local_frame`] if its function panics, so that the panic is handled (e.g.
+/// thrown as a Java exception by an exported method) in the enclosing frame.
+struct FrameGuard<'a, 'env>(&'a JNIEnv<'env>);
+
+impl Drop for FrameGuard<'_, '_> {
+    fn drop(&mut self) {
+        if let Err(e) = self.0.pop_local_frame(JObject::null()) {
+            eprintln!("Error while popping local frame: {}", e);
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! Java helper classes of this crate, embedded in the library.
+//!
+//! Some conversions are backed by small Java classes, found in the `java` directory of this crate:
+//! `robusta.RustFunction` for [`RustFunction`](crate::convert::RustFunction), `robusta.RustIterator` for
+//! [`RustIterator`](crate::convert::RustIterator), and `robusta.Tuple2` and `robusta.Tuple3` for
+//! [tuples](crate::convert::tuple). By default, they must be compiled with the Java sources of the
+//! project.
+//!
+//! With the `helper_classes` feature, their compiled class files are embedded in the library instead, and
+//! `define_helper_classes` defines them at runtime with `DefineClass`, in a class loader of their own (a child of the
+//! class loader registered with [`set_class_loader`](crate::loader::set_class_loader), or of the system class
+//! loader). `#[bridge(helper_classes)]` generates a `JNI_OnLoad` function doing so when the library is loaded:
+//! ```ignore
+//! # use robusta_jni::bridge;
+//! #
+//! #[bridge(helper_classes)]
+//! mod jni {
+//!     # use robusta_jni::convert::{RustIterator, Signature};
+//!     #[derive(Signature)]
+//!     #[package(com.example)]
+//!     struct Numbers;
+//!
+//!     impl Numbers {
+//!         // Java: `static native Iterator<Integer> upTo(int n)`, with no `robusta` classes on the class path
+//!         pub extern "jni" fn upTo(n: i32) -> RustIterator {
+//!             RustIterator::new(0..n)
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! Once defined, the embedded classes are used by conversions even if classes with the same name are on the class
+//! path. `DefineClass` isn't supported on Android, where the helper classes must be compiled with the application.
+//!
+//! The class files are compiled for the oldest Java version each class supports, so that the helpers a library uses
+//! can be defined by the JVMs it targets:
+//! ```text
+//! javac --release 8 -d java/classes java/robusta/Tuple2.java java/robusta/Tuple3.java
+//! javac --release 9 -d java/classes java/robusta/RustIterator.java
+//! javac --release 16 -d java/classes java/robusta/RustFunction.java
+//! ```
+//! `RustIterator` needs Java 9 for `java.lang.ref.Cleaner`, and `RustFunction` needs Java 16 for
+//! `InvocationHandler.invokeDefault`, with which it forwards default methods of the interface.
+//! `define_helper_classes` skips the classes the JVM doesn't support.
+
+use std::sync::RwLock;
+
+use jni::errors::Result as JniResult;
+use jni::objects::{GlobalRef, JClass};
+#[cfg(feature = "helper_classes")]
+use jni::errors::Error;
+#[cfg(feature = "helper_classes")]
+use jni::objects::{JObject, JValue};
+use jni::JNIEnv;
+
+/// Helper classes defined by `define_helper_classes`, by class path.
+static HELPER_CLASSES: RwLock<Vec<(&'static str, GlobalRef)>> = RwLock::new(Vec::new());
+
+/// Class files embedded with the `helper_classes` feature, by class path.
+#[cfg(feature = "helper_classes")]
+const CLASS_FILES: &[(&str, &[u8])] = &[
+    (
+        "robusta/RustFunction",
+        include_bytes!("../java/classes/robusta/RustFunction.class"),
+    ),
+    (
+        "robusta/RustIterator",
+        include_bytes!("../java/classes/robusta/RustIterator.class"),
+    ),
+    ("robusta/Tuple2", include_bytes!("../java/classes/robusta/Tuple2.class")),
+    ("robusta/Tuple3", include_bytes!("../java/classes/robusta/Tuple3.class")),
+];
+
+/// Define the embedded helper classes in a new class loader, unless they were already defined.
+///
+/// This must be called before any conversion using them, usually in `JNI_OnLoad` (see the
+/// [module documentation](self)).
+#[cfg(feature = "helper_classes")]
+pub fn define_helper_classes(env: &JNIEnv) -> JniResult<()> {
+    let mut classes = HELPER_CLASSES.write().unwrap();
+    if !classes.is_empty() {
+        return Ok(());
+    }
+
+    let parent = match crate::loader::class_loader() {
+        Some(loader) => env.new_local_ref(loader.as_obj())?,
+        None => env
+            .call_static_method("java/lang/ClassLoader", "getSystemClassLoader", "()Ljava/lang/ClassLoader;", &[])?
+            .l()?,
+    };
+    let urls = env.new_object_array(0, "java/net/URL", JObject::null())?;
+    let loader = env.new_object(
+        "java/net/URLClassLoader",
+        "([Ljava/net/URL;Ljava/lang/ClassLoader;)V",
+        &[JValue::Object(unsafe { JObject::from_raw(urls) }), JValue::Object(parent)],
+    )?;
+
+    let mut defined = Vec::with_capacity(CLASS_FILES.len());
+    for (class_path, bytes) in CLASS_FILES {
+        let class = match env.define_class(*class_path, loader, bytes) {
+            Ok(class) => class,
+            // Classes needing a later Java version are skipped, conversions using them fail when they can't be found
+            Err(Error::JavaException) if unsupported_class_version(env)? => continue,
+            Err(e) => return Err(e),
+        };
+        defined.push((*class_path, env.new_global_ref(class)?));
+        env.delete_local_ref(*class)?;
+    }
+    *classes = defined;
+
+    Ok(())
+}
+
+/// Whether the pending exception is an `UnsupportedClassVersionError`, in which case it is cleared.
+#[cfg(feature = "helper_classes")]
+fn unsupported_class_version(env: &JNIEnv) -> JniResult<bool> {
+    // No JNI function but a few may be called while an exception is pending
+    let exception = env.exception_occurred()?;
+    env.exception_clear()?;
+    if !env.is_instance_of(exception, "java/lang/UnsupportedClassVersionError")? {
+        env.throw(exception)?;
+        return Ok(false);
+    }
+
+    env.delete_local_ref(*exception)?;
+    Ok(true)
+}
+
+/// Look up helper class `class_path`: the embedded one if it was defined with `define_helper_classes`, or else with
+/// [`find_class`](crate::loader::find_class).
+pub(crate) fn helper_class<'env>(env: &JNIEnv<'env>, class_path: &str) -> JniResult<JClass<'env>> {
+    let defined = HELPER_CLASSES
+        .read()
+        .unwrap()
+        .iter()
+        .find(|(path, _)| *path == class_path)
+        .map(|(_, class)| class.clone());
+
+    match defined {
+        Some(class) => env.new_local_ref(class.as_obj()).map(JClass::from),
+        None => crate::loader::find_class(env, class_path),
+    }
+}
+
+/// Forget the defined helper classes.
+pub(crate) fn clear_helper_classes() {
+    HELPER_CLASSES.write().unwrap().clear();
+}
@@ -0,0 +1,215 @@
+//! Timing of calls between Java and Rust.
+//!
+//! Exported and imported methods marked with `#[instrument]`, or all methods of a module with `#[bridge(instrument)]`,
+//! report a [`CallEvent`] to the callback registered with [`set_call_callback`] when they are entered and when they
+//! return. Exit events split the time spent in the method between the conversions of its parameters and return value
+//! and the call itself: the Rust method for `extern "jni"` methods, the Java method for `extern "java"` methods.
+//!
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! # #[bridge]
+//! # mod jni {
+//!     # use robusta_jni::convert::Signature;
+//! #[derive(Signature)]
+//! #[package(com.example)]
+//! struct Parser;
+//!
+//! impl Parser {
+//!     #[instrument]
+//!     pub extern "jni" fn countWords(text: String) -> i32 {
+//!         text.split_whitespace().count() as i32
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! and elsewhere, e.g. in `JNI_OnLoad`:
+//! ```rust
+//! use robusta_jni::instrument::{set_call_callback, CallEvent};
+//!
+//! set_call_callback(|event: &CallEvent| {
+//!     if let CallEvent::Exit(call, times) = event {
+//!         println!("{}: {:?} converting, {:?} in the call", call.method, times.conversion, times.call);
+//!     }
+//! });
+//! ```
+//!
+//! Without a registered callback, instrumented methods only keep track of their nesting, and don't read the clock.
+
+use std::cell::{Cell, RefCell};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+/// Side of the bridge implementing the called method.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CallDirection {
+    /// Java calling an `extern "jni"` method.
+    Exported,
+    /// Rust calling an `extern "java"` method.
+    Imported,
+}
+
+/// An instrumented method call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Call {
+    pub direction: CallDirection,
+    /// Rust name of the method, e.g. `Parser::countWords`.
+    pub method: &'static str,
+    /// Number of parameters, not counting the receiver, `JNIEnv` and class parameters.
+    pub arg_count: usize,
+}
+
+/// Time spent in a completed call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CallTimes {
+    /// Time spent outside of the called method: conversions of parameters and return value, and raising exceptions.
+    pub conversion: Duration,
+    /// Time spent in the called method: the Rust method for exported methods, the Java method for imported ones.
+    pub call: Duration,
+    pub total: Duration,
+}
+
+/// Entry in or exit from an instrumented method.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CallEvent {
+    Enter(Call),
+    /// Reported when the method returns, fails or panics.
+    Exit(Call, CallTimes),
+}
+
+type CallCallback = Box<dyn Fn(&CallEvent) + Send + Sync>;
+
+static CALLBACK_SET: AtomicBool = AtomicBool::new(false);
+
+static CALLBACK: RwLock<Option<CallCallback>> = RwLock::new(None);
+
+/// Register `callback` to be called on every entry in and exit from an instrumented method, replacing any previously
+/// registered callback.
+///
+/// The callback is invoked synchronously on the thread performing the call, so it should be cheap.
+pub fn set_call_callback<F>(callback: F)
+where
+    F: Fn(&CallEvent) + Send + Sync + 'static,
+{
+    let mut guard = CALLBACK.write().unwrap_or_else(|e| e.into_inner());
+    *guard = Some(Box::new(callback));
+    CALLBACK_SET.store(true, Ordering::Release);
+}
+
+/// Remove the registered callback, if any.
+pub fn clear_call_callback() {
+    let mut guard = CALLBACK.write().unwrap_or_else(|e| e.into_inner());
+    CALLBACK_SET.store(false, Ordering::Release);
+    *guard = None;
+}
+
+fn report(event: CallEvent) {
+    let guard = CALLBACK.read().unwrap_or_else(|e| e.into_inner());
+    if let Some(callback) = guard.as_ref() {
+        callback(&event);
+    }
+}
+
+/// Timing of an active span.
+struct Frame {
+    /// Nesting level of the span, so that only its own method marks the call
+    depth: usize,
+    call_start: Option<Instant>,
+    call: Duration,
+}
+
+thread_local! {
+    /// Number of instrumented methods being executed by the current thread, including those without a callback
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+    static FRAMES: RefCell<Vec<Frame>> = const { RefCell::new(Vec::new()) };
+}
+
+/// An instrumented method being executed, created by generated code. Dropping it reports the exit from the method.
+#[doc(hidden)]
+pub struct Span {
+    call: Call,
+    /// `None` if no callback was registered on entry
+    start: Option<Instant>,
+}
+
+impl Span {
+    pub fn enter(direction: CallDirection, method: &'static str, arg_count: usize) -> Self {
+        let depth = DEPTH.with(|d| {
+            d.set(d.get() + 1);
+            d.get()
+        });
+        let call = Call {
+            direction,
+            method,
+            arg_count,
+        };
+
+        if !CALLBACK_SET.load(Ordering::Acquire) {
+            return Span { call, start: None };
+        }
+
+        report(CallEvent::Enter(call));
+        FRAMES.with(|f| {
+            f.borrow_mut().push(Frame {
+                depth,
+                call_start: None,
+                call: Duration::ZERO,
+            })
+        });
+
+        Span {
+            call,
+            start: Some(Instant::now()),
+        }
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        DEPTH.with(|d| d.set(d.get() - 1));
+
+        if let Some(start) = self.start {
+            let total = start.elapsed();
+            let call = FRAMES
+                .with(|f| f.borrow_mut().pop())
+                .map(|frame| frame.call + frame.call_start.map(|s| s.elapsed()).unwrap_or_default())
+                .unwrap_or_default();
+
+            report(CallEvent::Exit(
+                self.call,
+                CallTimes {
+                    conversion: total.saturating_sub(call),
+                    call,
+                    total,
+                },
+            ));
+        }
+    }
+}
+
+/// Mark the start of the call of the innermost instrumented method, once its parameters are converted.
+#[doc(hidden)]
+pub fn call_started() {
+    with_current_frame(|frame| frame.call_start = Some(Instant::now()));
+}
+
+/// Mark the end of the call of the innermost instrumented method, before its return value is converted.
+#[doc(hidden)]
+pub fn call_finished() {
+    with_current_frame(|frame| {
+        if let Some(start) = frame.call_start.take() {
+            frame.call += start.elapsed();
+        }
+    });
+}
+
+fn with_current_frame<F: FnOnce(&mut Frame)>(f: F) {
+    let depth = DEPTH.with(Cell::get);
+    FRAMES.with(|frames| {
+        if let Some(frame) = frames.borrow_mut().last_mut().filter(|frame| frame.depth == depth) {
+            f(frame);
+        }
+    });
+}
@@ -41,6 +41,53 @@
 //! }
 //! ```
 //!
+//! Nested classes are declared with an additional `#[class(Outer.Inner)]` attribute, giving the name of the class
+//! relative to its package. The struct itself can have any name, while signatures, class lookups and exported functions
+//! use the binary name of the class (`my/awesome/package/Outer$Inner`):
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #[bridge]
+//! mod jni {
+//!     # use robusta_jni::convert::Signature;
+//!     #[derive(Signature)]
+//!     #[package(my.awesome.package)]
+//!     #[class(Outer.Inner)]
+//!     pub struct OuterInner;
+//!
+//!     impl OuterInner {
+//!         pub extern "jni" fn answer() -> i32 {
+//!             42
+//!         }
+//!     }
+//! }
+//! # use robusta_jni::convert::Signature;
+//! # assert_eq!(<jni::OuterInner as Signature>::SIG_TYPE, "Lmy/awesome/package/Outer$Inner;");
+//! ```
+//!
+//! Structs declared outside of the bridged module, e.g. in a shared model module, can have methods bridged by putting
+//! the `#[package]` (and `#[class]`) attribute on their impl block instead. The struct must still be declared in the
+//! same crate, and implement [`Signature`] and the conversion traits needed by its methods:
+//! ```rust
+//! # use robusta_jni::bridge;
+//! mod model {
+//!     # use robusta_jni::convert::Signature;
+//!     #[derive(Signature)]
+//!     #[package(com.example)]
+//!     pub struct Counter;
+//! }
+//!
+//! #[bridge]
+//! mod jni {
+//!     #[package(com.example)]
+//!     impl super::model::Counter {
+//!         pub extern "jni" fn start() -> i32 {
+//!             0
+//!         }
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+//!
 //! # Adding native methods
 //! JNI bindings are generated for every method implemented for `package`-annotated structs.
 //! Each method can optionally specify a `#[call_type]` attribute that will determine how conversions between Rust and Java types are performed.
@@ -50,6 +97,20 @@
 //! (input types must implement `(Try)FromJavaValue` and output types must implement `(Try)IntoJavaValue`)
 //!
 //! Native methods can optionally accept a [`JNIEnv`] parameter as first parameter (after `self` if present).
+//! The parameter can be either a `&JNIEnv` or a `&mut JNIEnv`, and `JNIEnv` can also be referred to with an alias
+//! declared in the bridged module (e.g. `use robusta_jni::jni::JNIEnv as Env;`).
+//! Exported methods can also take the process-wide [`Vm`] handle with a `&Vm` parameter right after the environment
+//! parameter, e.g. to attach threads started by the method to the JVM (see [`vm`], and [`attach`] to get an environment
+//! from any thread).
+//! Static methods can also take the class JNI passes to static native methods, with a [`JClass`](jni::objects::JClass)
+//! parameter right after these (e.g. `env: &JNIEnv, class: JClass<'env>`).
+//!
+//! Methods with a `&self` receiver borrow the converted struct, so that the body can make several calls on it
+//! (e.g. to `extern "java"` methods taking `&self`, or through [`Field`](convert::Field) handles).
+//! Methods with a `&mut self` receiver write the receiver back to the Java instance after the method body completes,
+//! so that changes to `#[writeback]` fields are visible from Java (see [`TryWriteBack`](convert::TryWriteBack)).
+//! Fields read with a getter method (`#[field(getter = "...", setter = "...")]`, see [`Field`](convert::Field)) are
+//! written back with their setter.
 //!
 //! Methods are declared as standard Rust functions with public visibility and "jni" ABI, and are matched by name with Java methods.
 //! No special handling is needed.
@@ -96,6 +157,310 @@
 //! # }
 //! ```
 //!
+//! ## Symbol names
+//! Exported functions are named as the JVM expects, i.e. `Java_<package>_<class>_<method>`, with package, class and method names
+//! escaped as described in the JNI specification (e.g. `get_name` in package `com.my_app` becomes `Java_com_my_1app_Foo_get_1name`).
+//! The generated name can be replaced with Rust's `#[export_name]` attribute, for functions implementing a native method
+//! of a class that isn't bridged: `#[export_name = "Java_com_example_Outer_00024Inner_run"]`.
+//!
+//! The Java name of an exported method is its Rust name by default. It can be changed for a single method with
+//! `#[java_name("...")]`, or for the whole module with the `java_name_format` argument of `#[bridge]`, in which
+//! `{name}`, `{camelCase}`, `{PascalCase}` and `{snake_case}` are replaced with the Rust name in the given case.
+//! Both also apply to methods registered with `register_natives`. Imported methods are named with `rename_all`
+//! instead, see [Method names](#method-names).
+//!
+//! Example:
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! #[bridge(java_name_format = "native{PascalCase}")]
+//! mod jni {
+//!     # use robusta_jni::convert::Signature;
+//!     # #[derive(Signature)]
+//!     # #[package(com.example)]
+//!     # struct Codec;
+//!     #
+//!     impl Codec {
+//!         // implements `static native int nativeEncodedLength(String s)`
+//!         pub extern "jni" fn encoded_length(s: String) -> i32 {
+//!             s.len() as i32
+//!         }
+//!
+//!         // implements `static native int version()`
+//!         #[java_name("version")]
+//!         pub extern "jni" fn codec_version() -> i32 {
+//!             2
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! ## Overloaded methods
+//! Overloaded native methods can't share the short name: methods marked with `#[overload]` get the long name of the JNI
+//! specification instead, with the descriptors of the parameters appended (e.g. `Java_com_example_Codec_encode__I`).
+//! The descriptors are inferred from primitive, string and array parameter types; for other types they must be given
+//! with e.g. `#[overload("(Ljava/util/ArrayList;I)")]`. Either way, compilation fails if they don't match the signatures
+//! of the conversion traits.
+//!
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! # #[bridge]
+//! # mod jni {
+//!     # use robusta_jni::convert::Signature;
+//!     # #[derive(Signature)]
+//!     # #[package(com.example)]
+//!     # struct Codec;
+//!     #
+//! impl Codec {
+//!     // implements `static native String encode(int value)`
+//!     #[overload]
+//!     #[java_name("encode")]
+//!     pub extern "jni" fn encode_int(value: i32) -> String {
+//!         value.to_string()
+//!     }
+//!
+//!     // implements `static native String encode(byte[] bytes)`
+//!     #[overload]
+//!     #[java_name("encode")]
+//!     pub extern "jni" fn encode_bytes(bytes: Box<[u8]>) -> String {
+//!         bytes.iter().map(|b| format!("{:02x}", b)).collect()
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! ## Registering native methods
+//! By default exported functions are looked up by the JVM through their (mangled) symbol names.
+//! With `#[bridge(register_natives)]` a `JNI_OnLoad` function is generated instead, which registers all exported methods
+//! of the module with `RegisterNatives`: exported functions are then not exposed as library symbols,
+//! and don't depend on JNI name mangling.
+//!
+//! Since registration matches methods by their full type signature, Java declarations must use the exact types
+//! given by the conversion traits (e.g. `ArrayList` for `Vec<T>`, instead of `List`).
+//! Only one module per library can use this option.
+//!
+//! ## Embedding helper classes
+//! With the `helper_classes` feature and `#[bridge(helper_classes)]`, the generated `JNI_OnLoad` function also defines
+//! the Java classes used by some conversions (e.g. `robusta.RustFunction`), embedded in the library, so that they don't
+//! need to be compiled with the Java sources of the project. It can be combined with `register_natives`, and only one
+//! module per library can use it. See [`helpers`] for details.
+//!
+//! ## Unloading the library
+//! With `#[bridge(on_unload)]` a `JNI_OnUnload` function is generated, which runs the hooks registered with
+//! [`unload::on_unload`] (e.g. to stop threads started by the library) and then releases the global references cached
+//! by this crate, such as classes of bridged structs. A function of the module can also be given directly, with
+//! `#[bridge(on_unload = "shutdown")]`. Only one module per library can use this option. See [`unload`] for details.
+//!
+//! ## Verifying imported methods
+//! Imported methods are looked up by the JVM only when they are first called, so a mismatch between a Rust declaration
+//! and the Java class is usually reported as a `NoSuchMethodError` deep inside application code.
+//! With `#[bridge(verify_bindings)]` a `robusta_verify_bindings(env)` function is generated in the module, which checks
+//! every imported method against its Java class and returns a [`verify::VerificationReport`] listing the mismatches.
+//! Calling it right after loading the library (or in a test) surfaces signature drift early. See [`verify`] for details.
+//!
+//! When the Java classes aren't available at runtime in tests, imported methods can instead be checked at compile time
+//! against a JSON file of method descriptors, with `#[bridge(check_against = "bindings.json")]`.
+//!
+//! ## Listing exported symbols
+//! With `#[bridge(symbol_manifest)]` a `robusta_symbol_manifest()` function is generated in the module, which returns
+//! the symbol names and JNI signatures of all exported methods. The [`symbols::SymbolManifest`] can be written out as a
+//! symbol list, a linker version script, JSON or a C header (e.g. from a test, when `ROBUSTA_SYMBOLS_DIR` is set),
+//! to restrict the symbols exported by the library or to check them in CI. See [`symbols`] for details.
+//!
+//! ## Strict mode
+//! Some mistakes in a bridged module only produce warnings, and the item is then left out of the bridge: structs
+//! without a `#[package]` attribute, unknown `#[call_type]` options, constructor parameters that can't be converted,
+//! and so on. These warnings are only shown by nightly compilers, so on stable the item silently goes missing.
+//! With `#[bridge(strict)]` they become errors, as do `extern "jni"` methods that aren't public and free
+//! `extern "jni"` functions without a `#[class]` attribute, which would otherwise not be exported:
+//!
+//! ```rust,compile_fail
+//! # use robusta_jni::bridge;
+//! #
+//! #[bridge(strict)]
+//! mod jni {
+//!     // error: ignoring struct with no `package` attribute and no implementation
+//!     struct Config;
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! ## Signature constants
+//! Every `extern "jni"` and `extern "java"` method gets two associated constants on its struct, named after the Rust
+//! method: `<METHOD>_NAME` with its Java name, and `<METHOD>_SIG` with its JNI signature (constructors are named
+//! `<init>`). Code calling Java through the `JNIEnv` directly, e.g. to cache method IDs, can use them instead of spelling
+//! out the signatures. Constants aren't generated for structs with type parameters.
+//!
+//! Example:
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! #[bridge]
+//! mod jni {
+//!     # use robusta_jni::convert::{Signature, TryFromJavaValue, TryIntoJavaValue};
+//!     # use robusta_jni::jni::errors::Result as JniResult;
+//!     # use robusta_jni::jni::objects::AutoLocal;
+//!     # use robusta_jni::jni::JNIEnv;
+//!     #[derive(Signature, TryIntoJavaValue, TryFromJavaValue)]
+//!     #[package(com.example)]
+//!     pub struct User<'env: 'borrow, 'borrow> {
+//!         #[instance]
+//!         raw: AutoLocal<'env, 'borrow>,
+//!     }
+//!
+//!     impl<'env: 'borrow, 'borrow> User<'env, 'borrow> {
+//!         pub extern "jni" fn hashPassword(password: String, rounds: i32) -> Box<[u8]> {
+//!             # /*
+//!             ...
+//!             # */ Box::new([])
+//!         }
+//!
+//!         pub extern "java" fn getPassword(&self, env: &JNIEnv) -> JniResult<String> {}
+//!     }
+//! }
+//!
+//! assert_eq!(jni::User::HASH_PASSWORD_NAME, "hashPassword");
+//! assert_eq!(jni::User::HASH_PASSWORD_SIG, "(Ljava/lang/String;I)[B");
+//! assert_eq!(jni::User::GET_PASSWORD_SIG, "()Ljava/lang/String;");
+//! ```
+//!
+//! ## Exported constants
+//! Public associated constants marked with `#[export]` get a static native getter, named after the constant
+//! (e.g. `MAX_USERS` can be read from Java with `public static native int getMaxUsers();`). References in the type of
+//! the constant are `'static`, as in the constant itself.
+//!
+//! Example:
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! # #[bridge]
+//! # mod jni {
+//!     # use robusta_jni::convert::Signature;
+//!     # #[derive(Signature)]
+//!     # #[package()]
+//!     # struct A;
+//!     #
+//! impl A {
+//!     #[export]
+//!     pub const MAX_USERS: i32 = 100;
+//!
+//!     #[export]
+//!     pub const DEFAULT_NAME: &str = "user";
+//! }
+//! # }
+//! ```
+//!
+//! ## Delegating to plain Rust types
+//! A struct marked with `#[delegate_to(Type)]` can declare exported methods with an empty body: their implementation
+//! forwards all arguments (except for the environment) to the associated function of `Type` with the same name in snake_case.
+//! This keeps business logic free of JNI types and lifetimes. Methods with a body are exported as usual.
+//!
+//! Example:
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! pub struct Calculator;
+//!
+//! impl Calculator {
+//!     pub fn add_all(values: Vec<i32>) -> i32 {
+//!         values.iter().sum()
+//!     }
+//! }
+//!
+//! #[bridge]
+//! mod jni {
+//!     # use robusta_jni::convert::Signature;
+//!     #[derive(Signature)]
+//!     #[package(com.example)]
+//!     #[delegate_to(super::Calculator)]
+//!     struct NativeCalculator;
+//!
+//!     impl NativeCalculator {
+//!         // calls `Calculator::add_all(values)`
+//!         pub extern "jni" fn addAll(values: Vec<i32>) -> i32 {}
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! ## Free functions
+//! Utility natives that don't belong to any bridged struct can be declared as free `pub extern "jni"` functions
+//! in the bridge module, with a `#[class(...)]` attribute naming the fully qualified Java class that declares them.
+//! The functions remain callable from Rust as plain functions.
+//!
+//! Example:
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! #[bridge]
+//! mod jni {
+//!     // exported as `com.example.Bindings.add`
+//!     #[class(com.example.Bindings)]
+//!     pub extern "jni" fn add(a: i32, b: i32) -> i32 {
+//!         a + b
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! ## Excluding methods
+//! Methods and free functions marked with `#[no_bridge]` are left out of the bridge: no native function is exported
+//! for them, and they are kept as plain Rust functions (without the `extern "jni"` ABI) callable from other methods.
+//! This is useful to temporarily disable an export, or to share helpers between exported methods.
+//!
+//! ## Panics
+//! Panics raised by exported methods (including failed conversions with `#[call_type(unchecked)]`) are caught before
+//! reaching the JVM, and by default thrown as a `java.lang.RuntimeException` with the panic message.
+//! The exception class can be changed with `#[on_panic(throw = "java.lang.IllegalStateException")]`,
+//! while `#[on_panic(abort)]` aborts the process instead. See [`panic`](mod@panic) for details.
+//!
+//! ## Synchronized methods
+//! Exported methods marked with `#[synchronized]` hold the monitor of their receiver (or of their class for static methods)
+//! while they run, like Java `synchronized` methods. This keeps the locking discipline of Java code ported to Rust
+//! a method at a time: native and Java `synchronized` methods of the same class exclude each other.
+//!
+//! Safe methods throw a `java.lang.IllegalMonitorStateException` if the monitor can't be entered, while unchecked methods
+//! panic. Receivers converted from a primitive Java type have no monitor, and are rejected at compile time:
+//! ```compile_fail
+//! # use robusta_jni::bridge;
+//! #
+//! # #[bridge]
+//! # mod jni {
+//!     # use robusta_jni::convert::{Signature, TryFromJavaValue};
+//!     # use robusta_jni::jni::errors::Result as JniResult;
+//!     # use robusta_jni::jni::sys::jint;
+//!     # use robusta_jni::jni::JNIEnv;
+//!     #
+//! #[package(com.example)]
+//! struct Id(i32);
+//!
+//! impl Signature for Id {
+//!     const SIG_TYPE: &'static str = "I";
+//! }
+//!
+//! impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for Id {
+//!     type Source = jint;
+//!
+//!     fn try_from(s: jint, _env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+//!         Ok(Id(s))
+//!     }
+//! }
+//!
+//! impl Id {
+//!     #[synchronized]
+//!     pub extern "jni" fn next(self) -> i32 {
+//!         self.0 + 1
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! ## Local frames
+//! Exported and imported methods marked with `#[local_frame]` (or `#[local_frame(capacity = 512)]`) run their
+//! conversions in a local frame, so that the local references they create are released when the call completes
+//! instead of piling up, e.g. when converting large collections. See [`frame`] for details.
+//!
 //! # Adding Java methods
 //! You can also declare Java methods and `robusta` will generate binding glue to convert types and call methods on the Java side.
 //! Again, **all input and output types must implement proper conversion traits**: in this case it's the reverse from the Java to Rust case
@@ -141,10 +506,19 @@
 //!         i: i32,
 //!         u: i32,
 //!     ) -> ::robusta_jni::jni::errors::Result<i32> {}
+//!
+//!     // calls `java.lang.System.currentTimeMillis()`
+//!     #[class("java.lang.System")]
+//!     pub extern "java" fn currentTimeMillis(env: &JNIEnv) -> ::robusta_jni::jni::errors::Result<i64> {}
 //! }
 //! # }
 //! ```
 //!
+//! Static methods of other classes can be declared with `#[class("...")]`, without a struct for their class.
+//!
+//! The class and the method ID of a static method are looked up on its first call, and cached for later calls
+//! (see [`StaticMethodCache`](convert::StaticMethodCache)).
+//!
 //! ## Non-static methods
 //!
 //! Example:
@@ -221,6 +595,388 @@
 //! # }
 //! ```
 //!
+//! The constructor is selected by the signature derived from the parameter types. When that isn't the intended overload
+//! (e.g. for a `Ljava/lang/Object;` parameter), the signature can be given explicitly with `#[constructor(signature = "...")]`:
+//! parameter types are checked against it at compile time.
+//! ```ignore
+//! #[constructor(signature = "(Ljava/lang/CharSequence;I)V")]
+//! pub extern "java" fn new(env: &JNIEnv, #[input_type("Ljava/lang/CharSequence;")] name: &str, size: i32) -> JniResult<Self> {}
+//! ```
+//!
+//! Constructors annotated with `#[constructor(builder)]` also get a `<Struct>Builder` type (created with `<Struct>::builder()`),
+//! with a setter for each of their parameters. Its `build(env)` method calls the constructor taking exactly the parameters that
+//! were set, so that overloads with optional parameters can be declared as separate constructors:
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! # #[bridge]
+//! # mod jni {
+//!     # use robusta_jni::convert::{FromJavaValue, Signature, TryFromJavaValue};
+//!     # use robusta_jni::jni::errors::Result as JniResult;
+//!     # use robusta_jni::jni::JNIEnv;
+//!     # use jni::objects::JObject;
+//!     # #[derive(Signature)]
+//!     # #[package(com.example)]
+//!     # pub struct Connection;
+//!     #
+//!     # impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for Connection {
+//!     #    type Source = JObject<'env>;
+//!     #
+//!     #    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {
+//!     #         Ok(Connection)
+//!     #     }
+//!     # }
+//!     #
+//! impl Connection {
+//!     #[constructor(builder)]
+//!     pub extern "java" fn new(env: &JNIEnv, host: String, port: i32, timeout_millis: i64) -> JniResult<Self> {}
+//!
+//!     #[constructor(builder)]
+//!     pub extern "java" fn with_default_timeout(env: &JNIEnv, host: String, port: i32) -> JniResult<Self> {}
+//!
+//!     pub fn connect_locally(env: &JNIEnv) -> JniResult<Self> {
+//!         // Calls `with_default_timeout`
+//!         Connection::builder().host("localhost".to_string()).port(8080).build(env)
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! ## Method names
+//! By default, names of imported methods are converted to camel case (so `get_value` calls `getValue`).
+//! The mapping can be changed for the whole module with the `rename_all` argument of `#[bridge]`
+//! (one of `"camelCase"`, `"snake_case"` and `"none"`), or for a single method with `#[java_name("...")]`.
+//!
+//! Example:
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! #[bridge(rename_all = "none")]
+//! mod jni {
+//!     # use robusta_jni::convert::Signature;
+//!     # use robusta_jni::jni::JNIEnv;
+//!     # #[derive(Signature)]
+//!     # #[package()]
+//!     # struct A;
+//!     #
+//!     impl A {
+//!         // calls `A.get_value()`
+//!         pub extern "java" fn get_value(env: &JNIEnv) -> ::robusta_jni::jni::errors::Result<i32> {}
+//!
+//!         // calls `A.computeValue()`
+//!         #[java_name("computeValue")]
+//!         pub extern "java" fn compute(env: &JNIEnv) -> ::robusta_jni::jni::errors::Result<i32> {}
+//!     }
+//! }
+//! ```
+//!
+//! ## Fallback signatures
+//! To support several versions of a Java dependency, imported methods can declare alternative signatures with
+//! `#[fallback_signature("...")]`, tried in order on the first call if the class doesn't declare the method with its
+//! primary signature. Parameters of fallback signatures must be a prefix of the declared ones (trailing arguments are
+//! dropped), and the return type must be the same. See [`fallback`] for details.
+//!
+//! Example:
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! #[bridge]
+//! mod jni {
+//!     # use robusta_jni::convert::Signature;
+//!     # use robusta_jni::jni::JNIEnv;
+//!     # #[derive(Signature)]
+//!     # #[package()]
+//!     # struct Logger;
+//!     #
+//!     impl Logger {
+//!         // calls `Logger.log(String)` on versions without `Logger.log(String, int)`
+//!         #[fallback_signature("(Ljava/lang/String;)V")]
+//!         pub extern "java" fn log(env: &JNIEnv, message: String, level: i32) -> ::robusta_jni::jni::errors::Result<()> {}
+//!     }
+//! }
+//! ```
+//!
+//! ## Method dispatch
+//! Imported self methods are called with virtual dispatch, like method calls in Java code. `#[call(nonvirtual)]` calls
+//! the implementation of the class of the struct even on instances of subclasses overriding it (like `super.method()`),
+//! while `#[call(interface)]` looks the method up on the interface bridged by the struct, e.g. to call a default method.
+//! See [`dispatch`] for details.
+//!
+//! Example:
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! #[bridge]
+//! mod jni {
+//!     # use robusta_jni::convert::{Signature, TryFromJavaValue, TryIntoJavaValue};
+//!     # use robusta_jni::jni::JNIEnv;
+//!     # use robusta_jni::jni::objects::AutoLocal;
+//!     # #[derive(Signature, TryIntoJavaValue, TryFromJavaValue)]
+//!     # #[package(com.example)]
+//!     # pub struct Shape<'env: 'borrow, 'borrow> {
+//!     #     #[instance]
+//!     #     raw: AutoLocal<'env, 'borrow>,
+//!     # }
+//!     #
+//!     impl<'env: 'borrow, 'borrow> Shape<'env, 'borrow> {
+//!         // calls `Shape.area()`, even if `self` is an instance of a subclass overriding it
+//!         #[call(nonvirtual)]
+//!         pub extern "java" fn area(&self, env: &JNIEnv) -> ::robusta_jni::jni::errors::Result<f64> {}
+//!     }
+//! }
+//! ```
+//!
+//! ## Kotlin objects and companion objects
+//! Members of Kotlin `object` declarations and companion objects are compiled to instance methods of a singleton
+//! stored in a static field (`INSTANCE` and `Companion` respectively).
+//!
+//! Marking a struct with `#[kotlin_object]` makes all its static `extern "java"` methods dispatch on the `INSTANCE` singleton,
+//! while marking a single static method with `#[companion]` makes it dispatch on the `Companion` singleton.
+//!
+//! Example:
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! # #[bridge]
+//! # mod jni {
+//!     # use robusta_jni::convert::Signature;
+//!     # use robusta_jni::jni::JNIEnv;
+//! #[derive(Signature)]
+//! #[package(com.example)]
+//! #[kotlin_object]
+//! struct Registry;
+//!
+//! impl Registry {
+//!     // calls `Registry.INSTANCE.lookup(key)`
+//!     pub extern "java" fn lookup(env: &JNIEnv, key: String) -> ::robusta_jni::jni::errors::Result<String> {}
+//! }
+//!
+//! #[derive(Signature)]
+//! #[package(com.example)]
+//! struct Config;
+//!
+//! impl Config {
+//!     // calls `Config.Companion.defaultName()`
+//!     #[companion]
+//!     pub extern "java" fn default_name(env: &JNIEnv) -> ::robusta_jni::jni::errors::Result<String> {}
+//! }
+//! # }
+//! ```
+//!
+//! ## Kotlin suspend functions
+//! **Experimental.** Kotlin `suspend` functions are compiled to methods taking an additional `kotlin.coroutines.Continuation`
+//! parameter and returning an `Object`. Marking an `extern "java"` method with `#[kotlin_suspend]` generates this signature
+//! from the parameters and the return type of the Kotlin function, and blocks the current thread until the function completes,
+//! even if it suspends and is resumed from another thread. Exceptions thrown by the function are thrown by the call.
+//! See [`coroutines`] for details.
+//!
+//! ```ignore
+//! impl<'env: 'borrow, 'borrow> Repository<'env, 'borrow> {
+//!     // calls `suspend fun fetch(id: Int): String`
+//!     #[kotlin_suspend]
+//!     pub extern "java" fn fetch(&self, env: &JNIEnv, id: i32) -> JniResult<String> {}
+//! }
+//! ```
+//!
+//! ## Deriving all conversions
+//! Deriving [`JavaClass`](convert::JavaClass) on a struct or an enum is equivalent to deriving `Signature`,
+//! `TryIntoJavaValue`, `IntoJavaValue`, `TryFromJavaValue` and `FromJavaValue`, and accepts the same helper attributes.
+//! A `#[java()]` attribute restricts the derived conversions: `only_into` derives conversions from Rust to Java,
+//! `only_from` conversions from Java to Rust, and `only_try` only the safe `Try*` traits.
+//!
+//! Example:
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! # #[bridge]
+//! # mod jni {
+//!     # use robusta_jni::convert::JavaClass;
+//!     # use robusta_jni::jni::objects::AutoLocal;
+//! // same as `#[derive(Signature, TryFromJavaValue)]`
+//! #[derive(JavaClass)]
+//! #[java(only_from, only_try)]
+//! #[package(com.example)]
+//! pub struct Point<'env: 'borrow, 'borrow> {
+//!     #[instance]
+//!     raw: AutoLocal<'env, 'borrow>,
+//!     x: i32,
+//!     y: i32,
+//! }
+//! # }
+//! ```
+//!
+//! ## Enums
+//! Fieldless Rust enums can be mapped to Java enums by deriving `Signature` and the conversion traits on them.
+//! Variants are matched to Java enum constants by name, which can be overridden with `#[java_name("...")]`.
+//!
+//! Example:
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! # #[bridge]
+//! # mod jni {
+//!     # use robusta_jni::convert::{Signature, TryIntoJavaValue, TryFromJavaValue};
+//! #[derive(Signature, TryIntoJavaValue, TryFromJavaValue)]
+//! #[package(com.example)]
+//! pub enum Color {
+//!     #[java_name("RED")]
+//!     Red,
+//!     #[java_name("DARK_BLUE")]
+//!     DarkBlue,
+//! }
+//! # }
+//! ```
+//!
+//! ## Records
+//! Structs marked with `#[record]` are mapped to Java records (Java 16+), and converted by value: conversions from Java
+//! read each component with its accessor method (e.g. `x()`), and conversions to Java create a new record with its
+//! canonical constructor, passing components in the order of the struct fields. Record structs don't have an
+//! `#[instance]` field nor lifetime parameters. Components are matched to fields by name, which can be overridden
+//! with `#[java_name("...")]`.
+//!
+//! Example:
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! # #[bridge]
+//! # mod jni {
+//!     # use robusta_jni::convert::{Signature, TryIntoJavaValue, TryFromJavaValue};
+//! // public record Range(int start, int end, String displayName) {}
+//! #[derive(Signature, TryIntoJavaValue, TryFromJavaValue)]
+//! #[package(com.example)]
+//! #[record]
+//! pub struct Range {
+//!     start: i32,
+//!     end: i32,
+//!     #[java_name("displayName")]
+//!     display_name: String,
+//! }
+//! # }
+//! ```
+//!
+//! ## Data structs
+//! Structs that derive [`TryFromJavaValue`](convert::TryFromJavaValue) or [`FromJavaValue`](convert::FromJavaValue)
+//! without an `#[instance]` field are converted by value, like records: each field is read from the Java field with the
+//! same name (or with its `#[field(getter = "...")]` accessor), and the struct holds no reference to the Java object.
+//! They don't have lifetime parameters, so they can be kept past the native call that received them:
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! # #[bridge]
+//! # mod jni {
+//!     # use robusta_jni::convert::{Signature, TryFromJavaValue};
+//! #[derive(Signature, TryFromJavaValue)]
+//! #[package(com.example)]
+//! pub struct Point {
+//!     x: i32,
+//!     y: i32,
+//! }
+//!
+//! # #[derive(Signature)]
+//! # #[package(com.example)]
+//! # struct Geometry;
+//! #
+//! impl Geometry {
+//!     // Java: `static native int manhattan(Point p)`
+//!     pub extern "jni" fn manhattan(p: Point) -> i32 {
+//!         p.x.abs() + p.y.abs()
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! ## Newtypes
+//! Single-field tuple structs can derive [`JavaDelegate`](convert::JavaDelegate), which implements `Signature`, `ArrayElement`
+//! (when the inner type is an array element) and all four conversion traits by delegating to the inner type.
+//! The newtype is then converted exactly as its inner type.
+//!
+//! Example:
+//! ```rust
+//! use robusta_jni::convert::JavaDelegate;
+//!
+//! // converted to and from a Java `String[]`
+//! #[derive(JavaDelegate)]
+//! pub struct Names(pub Box<[String]>);
+//! ```
+//!
+//! ## Custom converters
+//! Parameters and return values of types with no conversion traits (e.g. types of other crates), or that need a
+//! different Java representation, can be converted with a converter instead, by marking them with
+//! `#[convert(with = "...")]`. See the [`converter`](convert::converter) module for details.
+//!
+//! ## Generic structs
+//! Conversion derives also support structs with type parameters, mapped to a generic Java class. Bounds needed to convert
+//! fields depending on type parameters are added to the generated impls, so only `Signature` must be declared.
+//! Since Java erases type variables, fields typed with a bare type parameter are read and written as `Object`:
+//! type parameters must be instantiated with types converted to Java objects (e.g. `String`, not `i32`).
+//!
+//! Example:
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! # #[bridge]
+//! # mod jni {
+//!     # use robusta_jni::convert::{Signature, TryIntoJavaValue, TryFromJavaValue};
+//!     # use robusta_jni::jni::objects::AutoLocal;
+//! // public class Holder<T> { String label; T value; }
+//! #[derive(Signature, TryIntoJavaValue, TryFromJavaValue)]
+//! #[package(com.example)]
+//! pub struct Holder<'env: 'borrow, 'borrow, T: Signature> {
+//!     #[instance]
+//!     raw: AutoLocal<'env, 'borrow>,
+//!     label: String,
+//!     value: T,
+//! }
+//! # }
+//! ```
+//!
+//! ## First use hooks
+//! A plain function of a bridged struct marked with `#[on_first_use]` is called once, before the first imported method call
+//! or conversion from Java of the struct, e.g. to check the Java side of the bridge. See the [`first_use`] module.
+//!
+//! ## Calling Java from other threads
+//! Structs deriving `TryFromJavaValue` get a `to_global(env)` method, returning a [`Global`](convert::Global) handle
+//! that can be sent to other threads. [`Global::with`](convert::Global::with) attaches the current thread to the JVM
+//! and gives access to a local instance of the struct, on which `extern "java"` methods can be called.
+//!
+//! ```ignore
+//! let user = user.to_global(env)?;
+//!
+//! std::thread::spawn(move || {
+//!     user.with(|env, user| user.getPassword(env))
+//! });
+//! ```
+//!
+//! Deriving [`IntoGlobal`](convert::IntoGlobal) (on a struct also deriving `TryFromJavaValue`) generates an owned
+//! `<Struct>Global` type, holding a [`Global`](convert::Global) handle and the data fields of the struct that don't borrow
+//! from the environment (`Lazy` and `#[field]` fields are left out). It has no lifetimes, so it can be stored in a `static`:
+//! `user.into_global(env)?` converts a struct into its owned version, and `owned.into_local(env)?` converts it back,
+//! reading the remaining fields from the Java object again.
+//!
+//! `to_weak(env)` returns a [`WeakGlobal`](convert::WeakGlobal) handle instead, which doesn't keep the Java object alive
+//! (e.g. for listeners registered from Java): [`WeakGlobal::with`](convert::WeakGlobal::with) gives access to a local
+//! instance only while the object wasn't garbage collected.
+//!
+//! Deriving [`IntoData`](convert::IntoData) generates a plain `<Struct>Data` type without lifetimes, holding the values of
+//! the data, `Lazy` and `#[field]` fields (fields whose value borrows from the environment are left out) but no reference
+//! to the Java object, to hand data to code that doesn't use JNI. `user.into_data()?` (or `UserData::try_from(user)`)
+//! reads it from a struct, and `data.into_java(env)?` creates a new Java object holding the values, without running any
+//! constructor.
+//!
+//! Bridged structs implement [`JavaClass`](convert::JavaClass), which caches a global reference to their class on first use.
+//! Imported constructors and static methods look up the class through it, so they also work from threads attached
+//! by native code (where `FindClass` only sees the system class loader) once the class was used from a Java thread.
+//!
+//! ## Debugging output
+//! [`convert::debug::describe`] describes any Java object as `com.example.User@1b6d3586{toString()}`, without disturbing
+//! pending exceptions. Structs deriving `TryFromJavaValue` also get a `describe(env)` method doing the same for their instance.
+//!
+//! ## Interoperating with other JNI libraries
+//! Structs deriving `TryFromJavaValue` get `as_raw()` and `into_raw()` methods returning the `jobject` of their instance
+//! (the latter handing over the local reference), and an `unsafe` `from_raw(env, obj)` constructor taking ownership of a
+//! raw local reference, so that objects can be passed to and from other JNI libraries without going through the
+//! conversion traits.
+//!
 //! # Conversion details and special lifetimes
 //! The procedural macro handles two special lifetimes specially: `'env` and `'borrow`.
 //!
@@ -235,9 +991,53 @@
 //! When using `*FromJavaValue` derive macros your structs will be required to have both `'env` and `'borrow`,
 //! with the same bounds as in the trait definition. For more information, see the relevant traits documentation.
 //!
+//! ## Aliased parameters
+//! Passing the same Java object for two parameters of an exported method gives two Rust values referring to the same
+//! instance, whose updates (e.g. through `Field` handles or `&mut self` write-back) can silently conflict.
+//! Annotating the method with `#[check_aliasing]` makes debug builds throw an `IllegalArgumentException` when that happens
+//! (or print a warning with `#[check_aliasing(warn)]`). See [`aliasing`] for details.
+//!
+//! ## Null parameters
+//! Exported methods with `#[call_type(safe)]` throw a `NullPointerException` naming the parameter when Java passes `null`
+//! for a parameter that can't represent it (e.g. a `String`). Parameters typed as `Option<T>` receive `None` instead.
+//! The check is disabled with `#[null_check(off)]`, and enabled for `#[call_type(unchecked)]` methods with `#[null_check]`.
+//! See [`nulls`].
+//!
+//! ## Class loaders
+//! From threads attached by native code, `FindClass` only finds classes of the system class loader, which on Android
+//! doesn't know the classes of the application. [`set_class_loader`] (or [`set_context_class_loader`]) registers a class loader
+//! used instead by all class lookups of generated code. See [`loader`].
+//!
+//! ## Embedding a JVM
+//! Bridged structs can also be used from a Rust binary starting its own JVM: with the `invocation` feature,
+//! the [`embed`](crate::embed) module provides helpers to assemble the class path, start the VM and report Java exceptions.
+//!
+//! ## Unit testing without Gradle
+//! With both the `testing` and `invocation` features, bridged code can be unit-tested with `cargo test`: functions marked
+//! with [`#[robusta_test]`](crate::testing::robusta_test) are run as tests with the `JNIEnv` of a JVM shared by the
+//! test binary, whose class path is configured with a [`VmBuilder`](crate::embed::VmBuilder). See [`testing`](crate::testing).
+//!
+//! ## Testing with multiple Java versions
+//! With the `testing` feature, the [`testing`](crate::testing) module runs an integration test suite once for each
+//! configured Java installation (e.g. Java 8, 11, 17 and 21), skipping versions that lack features required by the suite.
+//!
+//! ## Logging
+//! With the `log` feature, [`logging::install`](crate::logging) forwards records of the [`log`](https://docs.rs/log) crate
+//! to `java.util.logging` or to `android.util.Log`, from any thread.
+//!
+//! ## Timing calls
+//! Exported and imported methods marked with `#[instrument]`, or all methods of a module with `#[bridge(instrument)]`,
+//! report their entry, exit and the time spent converting values and in the call to a callback. See [`instrument`].
+//!
+//! ## Caching boxed values
+//! With the `pool` feature, autoboxing small integers and booleans (e.g. when converting a `Vec<i32>`) reuses cached objects
+//! instead of calling `valueOf` through JNI, and `Vec` conversions use a cached `ArrayList` class. See [`convert::pool`].
+//!
 //! ## Raising exceptions
 //! You can make a Rust native method raise a Java exception simply by returning a [`jni::errors::Result`] with an `Err` variant.
-//! See the [`convert`] module documentation for more information.
+//! See the [`convert`] module documentation for more information. The source chain of the error is kept as the
+//! cause of the thrown exception, see [`convert::error`]. Methods can declare a checked exception with e.g.
+//! `#[throws("java.io.IOException")]`, which is then thrown for `Err` returns, see [`convert::safe`].
 //!
 //! ## Library-provided conversions
 //!
@@ -251,29 +1051,109 @@
 //! | f64                                                                                | double                            |
 //! | i64                                                                                | long                              |
 //! | i16                                                                                | short                             |
-//! | String                                                                             | String                            |
+//! | ()\*                                                                               | void                              |
+//! | u32, u64, [Checked\<T\>](convert::Checked), [Wrapping\<T\>](std::num::Wrapping)#      | byte, short, int, long            |
+//! | String, &str§, &String§, Cow<str>§                                                 | String                            |
 //! | Vec\<T\>†                                                                          | ArrayList\<T\>                    |
-//! | Box<[u8]>                                                                          | byte[]                            |
+//! | [PackedList\<T\>](convert::PackedList) *(`T` is i32, i64 or f64)*                   | ArrayList\<T\> *(boxed by Java)*  |
+//! | Box\<[T]\>†                                                                         | T[]                               |
+//! | [JArray\<T\>](convert::JArray)†                                                     | T[]                               |
+//! | Vec<u8>, Box<[u8]>, &[u8]§, Cow<[u8]>§, `bytes::Bytes`⌂                             | byte[]                            |
+//! | Box<[i8]>, &[i8]§                                                                  | byte[]                            |
+//! | Box<[i16]>, Box<[i32]>, Box<[i64]>, &[i16]§, &[i32]§, &[i64]§                       | short[], int[], long[]            |
+//! | Box<[f32]>, Box<[f64]>, Box<[u16]>, &[f32]§, &[f64]§, &[u16]§                       | float[], double[], char[]         |
+//! | [DirectByteBuffer<'env>](convert::DirectByteBuffer)                                ‡ | ByteBuffer *(direct only)*        |
+//! | [JOptional\<T\>](convert::JOptional)†                                               | Optional\<T\>                     |
+//! | [Option\<T\>](convert::nullable)†                                                   | T *(`null` for `None`)*           |
+//! | [SystemTime](std::time::SystemTime), [Duration](std::time::Duration)¶                | Instant, Duration                 |
+//! | [i128, u128](convert::bigint)                                                        | BigInteger                        |
+//! | `rust_decimal::Decimal`¤                                                             | BigDecimal                        |
+//! | `Json<T>`, `Cbor<T>`⌘                                                               | String, byte[]                    |
+//! | [PathBuf](std::path::PathBuf), &[Path](std::path::Path)§, `JPath`◊                   | File, java.nio.file.Path          |
+//! | [IpAddr](std::net::IpAddr), [SocketAddr](std::net::SocketAddr)⌖                       | InetAddress, InetSocketAddress    |
+//! | [(A, B), (A, B, C)](convert::tuple)†⌑                                                 | robusta.Tuple2, robusta.Tuple3    |
+//! | [JBitSet](convert::JBitSet)                                                          | BitSet                            |
+//! | `impl Fn(...)`, [JavaFunction](convert::JavaFunction), [RustFunction](convert::RustFunction)⁂ | *(functional interfaces)*         |
+//! | [JIterator<'env, T>](convert::JIterator)‡, [RustIterator](convert::RustIterator)∆     | Iterator\<T\>                     |
+//! | [Opaque<'env, T>](convert::Opaque)                                                 ‡ | *(the Java type of `T`)*          |
 //! | [jni::JObject<'env>](jni::objects::JObject)                                      ‡ | *(any Java object as input type)* |
 //! | [jni::jobject](jni::sys::jobject)                                                    | *(any Java object as output)*     |
 //!
-//! † Type parameter `T` must implement proper conversion types
+//! † Type parameter `T` must implement proper conversion types. Arrays additionally require `T` to implement [`ArrayElement`](convert::ArrayElement), which is provided by the [`Signature`] derive macro.
+//! Nested arrays such as `Box<[Box<[T]>]>` map to multi-dimensional Java arrays (up to three dimensions for derived types and `String`)
 //!
 //! ‡ The special `'env` lifetime **must** be used
 //!
+//! § Slices and references can only be used as output types (e.g. as parameters of `extern "java"` methods, or returned from exported methods
+//! such as `-> &'static str`, without an intermediate allocation). Byte arrays are handled as unsigned bytes, signed variants are converted bytewise.
+//! Parameters of exported methods marked with `#[critical]` can be primitive slices borrowing the Java array, see [`convert::critical`].
+//! Byte buffers are `u8`-first: `Vec<u8>` is converted to a `byte[]` like `Box<[u8]>`, while a plain `u8` (the JNI representation of `boolean`)
+//! has no conversion of its own, see [`convert::unsigned`]. `Vec<u8>` and `Box<[u8]>` can also be used for `byte[]` fields of derived structs,
+//! either as plain fields or as `Field` handles (e.g. `Field<Vec<u8>>`).
+//!
+//! \# Unsigned integers are mapped to the signed type of the same width. Out of range values fail conversion, except with `Wrapping`
+//! which reinterprets them bitwise. See [`convert::unsigned`]
+//!
+//! \* Only as a return type: `()` is not a Java value, so e.g. `Vec<()>` or `JOptional<()>` are rejected at compile time
+//!
+//! ¶ From the `java.time` package. `chrono` and `time` types are supported behind the respective features, see [`convert::time`]
+//!
+//! ◊ Behind the `file` feature, see `convert::file`. Paths that aren't valid UTF-8 fail conversion
+//!
+//! ⌖ Behind the `net` feature, see `convert::net`. Addresses are converted without name lookups
+//!
+//! ¤ Behind the `rust_decimal` feature, see `convert::decimal`. Values are converted through strings, without precision loss
+//!
+//! ⌂ Behind the `bytes` feature, see `convert::bytes`
+//!
+//! ⌘ Behind the `json` and `cbor` features, for any `serde` type `T`, see `convert::serialized`
+//!
+//! ⁂ `impl Fn(...)` and `JavaFunction` only as input types, `RustFunction` only as output type. See [`convert::function`]
+//!
+//! ∆ `JIterator` only as input type (an `Iterable` is also accepted), `RustIterator` only as output type. See [`convert::iterator`]
+//!
+//! ⌑ Backed by Java classes found in the `java` directory of this crate (or embedded with the `helper_classes` feature).
+//! See [`convert::tuple`]
+//!
 //! ## Limitations
 //!
 //! Currently there are some limitations in the conversion mechanism:
 //!  * Boxed types are supported only through the opaque `JObject`/`jobject` types
-//!  * Automatic type conversion is limited to the table outlined above, though easily extendable if needed.
+//!  * Automatic type conversion is limited to the table outlined above, though easily extendable if needed:
+//!    objects that only need to be passed around can be wrapped in [`Opaque<T>`](convert::Opaque), which only requires `T` to implement [`Signature`].
 //!
 //! [`Signature`]: convert::Signature
 //! [`JNIEnv`]: jni::JNIEnv
 //!
 
 pub use robusta_codegen::bridge;
+pub use loader::{set_class_loader, set_context_class_loader};
+pub use vm::Vm;
 
+pub mod aliasing;
+pub mod attach;
 pub mod convert;
+pub mod coroutines;
+pub mod dispatch;
+#[cfg(feature = "invocation")]
+pub mod embed;
+pub mod fallback;
+pub mod first_use;
+pub mod frame;
+pub mod helpers;
+pub mod instrument;
+pub mod loader;
+#[cfg(feature = "log")]
+pub mod logging;
+pub mod nulls;
+pub mod panic;
+pub mod path;
+pub mod symbols;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod unload;
+pub mod verify;
+pub mod vm;
 
 pub use jni;
 
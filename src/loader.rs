@@ -0,0 +1,115 @@
+//! Class lookups through an application class loader.
+//!
+//! `FindClass` looks up classes with the class loader of the Java method that called the current native method. On
+//! threads attached by native code there is no such method, and the system class loader is used instead: on Android,
+//! it only knows the classes of the platform, not those of the application (loaded by a `DexClassLoader`).
+//!
+//! A class loader registered with [`set_class_loader`] is instead used for all the class lookups of generated code
+//! (bridged classes, `#[class]` overrides, exception classes of `#[call_type(safe)]` methods and `#[on_panic]`, enum
+//! constants) and of the library (e.g. Kotlin coroutine classes), from any thread. It is usually registered once,
+//! from a Java thread, before any lookup is made, since classes of bridged structs are cached on first use
+//! (see [`JavaClass`](crate::convert::JavaClass)):
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! # #[bridge]
+//! # mod jni {
+//!     # use robusta_jni::convert::Signature;
+//!     # use robusta_jni::jni::errors::Result as JniResult;
+//!     # use robusta_jni::jni::JNIEnv;
+//!     # #[derive(Signature)]
+//!     # #[package(com.example)]
+//!     # struct Native;
+//! impl Native {
+//!     // Java: `static native void init()`, called from the main thread of the application
+//!     pub extern "jni" fn init(env: &JNIEnv) -> JniResult<()> {
+//!         robusta_jni::set_context_class_loader(env)
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! Without a registered class loader, classes are looked up with `FindClass`.
+
+use std::sync::RwLock;
+
+use jni::errors::{Error, Result as JniResult};
+use jni::objects::{GlobalRef, JClass, JObject, JValue};
+use jni::JNIEnv;
+
+static CLASS_LOADER: RwLock<Option<GlobalRef>> = RwLock::new(None);
+
+/// Use `loader` (an instance of `java.lang.ClassLoader`) for class lookups, from any thread.
+pub fn set_class_loader(env: &JNIEnv, loader: JObject) -> JniResult<()> {
+    if loader.is_null() {
+        return Err(Error::NullPtr("set_class_loader loader argument"));
+    }
+
+    let loader = env.new_global_ref(loader)?;
+    *CLASS_LOADER.write().unwrap() = Some(loader);
+    Ok(())
+}
+
+/// Use the context class loader of the current thread for class lookups, from any thread.
+pub fn set_context_class_loader(env: &JNIEnv) -> JniResult<()> {
+    let thread = env
+        .call_static_method("java/lang/Thread", "currentThread", "()Ljava/lang/Thread;", &[])?
+        .l()?;
+    let loader = env
+        .call_method(thread, "getContextClassLoader", "()Ljava/lang/ClassLoader;", &[])?
+        .l()?;
+
+    set_class_loader(env, loader)
+}
+
+/// Go back to looking up classes with `FindClass`.
+pub fn clear_class_loader() {
+    *CLASS_LOADER.write().unwrap() = None;
+}
+
+/// Class loader registered with [`set_class_loader`], if any.
+pub fn class_loader() -> Option<GlobalRef> {
+    CLASS_LOADER.read().unwrap().clone()
+}
+
+/// Look up class `class_path` (in JNI format, e.g. `com/example/Counter` or `[Ljava/lang/String;`) with the registered
+/// class loader, or with `FindClass` if there is none.
+///
+/// Like `FindClass`, the class is initialized, and a `NoClassDefFoundError` is thrown if it doesn't exist.
+pub fn find_class<'env>(env: &JNIEnv<'env>, class_path: &str) -> JniResult<JClass<'env>> {
+    let loader = match class_loader() {
+        Some(loader) => loader,
+        None => return env.find_class(class_path),
+    };
+
+    let name = env.new_string(class_path.replace('/', "."))?;
+    let result = env.call_static_method(
+        "java/lang/Class",
+        "forName",
+        "(Ljava/lang/String;ZLjava/lang/ClassLoader;)Ljava/lang/Class;",
+        &[JValue::Object(*name), JValue::Bool(1), JValue::Object(loader.as_obj())],
+    );
+    env.delete_local_ref(*name)?;
+
+    match result {
+        Ok(class) => class.l().map(JClass::from),
+        Err(Error::JavaException) => {
+            let exception = env.exception_occurred()?;
+            env.exception_clear()?;
+            if env.is_instance_of(exception, "java/lang/ClassNotFoundException")? {
+                env.throw_new("java/lang/NoClassDefFoundError", class_path)?;
+            } else {
+                env.throw(exception)?;
+            }
+            Err(Error::JavaException)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Throw a new exception of class `class_path` (in JNI format) with message `message`, looking up the class with
+/// [`find_class`].
+pub fn throw_new<S: AsRef<str>>(env: &JNIEnv, class_path: &str, message: S) -> JniResult<()> {
+    let class = find_class(env, class_path)?;
+    env.throw_new(class, message)
+}
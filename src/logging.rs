@@ -0,0 +1,280 @@
+//! Forwarding of Rust [`log`] records to Java loggers.
+//!
+//! With the `log` feature, [`install`] registers a global [`log`] logger that forwards every record to the Java side,
+//! so that messages logged by native code end up next to the ones logged by the application:
+//! ```ignore
+//! #[bridge]
+//! mod jni {
+//!     #[package(com.example)]
+//!     struct Native;
+//!
+//!     impl Native {
+//!         pub extern "jni" fn init(env: &JNIEnv) -> JniResult<()> {
+//!             robusta_jni::logging::install(env).map_err(|_| JniError::JavaException)
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! Records are sent to a `java.util.logging.Logger` (named `rust` by default), or to `android.util.Log` on Android
+//! (see [`Backend`]), with the following level mapping:
+//!
+//! | **Rust** | **`java.util.logging`** | **`android.util.Log`** |
+//! |----------|-------------------------|------------------------|
+//! | `Error`  | `SEVERE`                | `ERROR`                |
+//! | `Warn`   | `WARNING`               | `WARN`                 |
+//! | `Info`   | `INFO`                  | `INFO`                 |
+//! | `Debug`  | `FINE`                  | `DEBUG`                |
+//! | `Trace`  | `FINER`                 | `VERBOSE`              |
+//!
+//! Records can be logged from any thread: threads that aren't attached to the JVM are attached as daemon threads
+//! on their first record. Records logged while a Java exception is pending (or whose forwarding fails) are printed
+//! to standard error instead, leaving the exception untouched.
+//!
+//! `tracing` events can be forwarded as well, by enabling the `log` feature of the `tracing` crate.
+
+use std::fmt::{self, Display, Formatter};
+
+use jni::errors::{Error as JniError, Result as JniResult};
+use jni::objects::{GlobalRef, JClass, JObject, JValue};
+use jni::{JNIEnv, JavaVM};
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+
+const JUL_LOGGER_CLASS: &str = "java/util/logging/Logger";
+const JUL_LEVEL_CLASS: &str = "java/util/logging/Level";
+const ANDROID_LOG_CLASS: &str = "android/util/Log";
+
+/// Java logging facility receiving the records.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Backend {
+    /// `java.util.logging`, with the name of the logger
+    JavaUtilLogging(String),
+    /// `android.util.Log`, with the tag of the records
+    Android(String),
+}
+
+/// Configuration of the forwarding logger.
+#[derive(Clone, Debug)]
+pub struct Config {
+    backend: Backend,
+    max_level: LevelFilter,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            backend: Backend::JavaUtilLogging("rust".to_string()),
+            max_level: LevelFilter::Info,
+        }
+    }
+}
+
+impl Config {
+    /// Forward records up to `Info` to the `rust` logger of `java.util.logging`.
+    pub fn new() -> Self {
+        Config::default()
+    }
+
+    pub fn backend(mut self, backend: Backend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Maximum level of forwarded records. Records are also filtered on the Java side, according to its configuration.
+    pub fn max_level(mut self, max_level: LevelFilter) -> Self {
+        self.max_level = max_level;
+        self
+    }
+}
+
+/// Errors raised while installing the forwarding logger.
+#[derive(Debug)]
+pub enum LoggingError {
+    /// Lookup of the Java logger failed
+    Jni(JniError),
+    /// A global logger was already installed
+    AlreadyInstalled(SetLoggerError),
+}
+
+impl Display for LoggingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            LoggingError::Jni(e) => write!(f, "can't set up Java logger: {}", e),
+            LoggingError::AlreadyInstalled(e) => write!(f, "can't install logger: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for LoggingError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoggingError::Jni(e) => Some(e),
+            LoggingError::AlreadyInstalled(e) => Some(e),
+        }
+    }
+}
+
+impl From<JniError> for LoggingError {
+    fn from(e: JniError) -> Self {
+        LoggingError::Jni(e)
+    }
+}
+
+impl From<SetLoggerError> for LoggingError {
+    fn from(e: SetLoggerError) -> Self {
+        LoggingError::AlreadyInstalled(e)
+    }
+}
+
+/// Install the forwarding logger with the default [`Config`].
+pub fn install(env: &JNIEnv) -> Result<(), LoggingError> {
+    install_with(env, Config::default())
+}
+
+/// Install the forwarding logger with configuration `config`.
+pub fn install_with(env: &JNIEnv, config: Config) -> Result<(), LoggingError> {
+    let max_level = config.max_level;
+    let logger = JavaLogger::new(env, config)?;
+
+    log::set_boxed_logger(Box::new(logger))?;
+    log::set_max_level(max_level);
+    Ok(())
+}
+
+/// Java objects used to log records, looked up on installation
+enum Sink {
+    JavaUtilLogging {
+        logger: GlobalRef,
+        /// `java.util.logging.Level` instances, indexed by `log::Level as usize - 1`
+        levels: Vec<GlobalRef>,
+    },
+    Android {
+        class: GlobalRef,
+        tag: GlobalRef,
+    },
+}
+
+struct JavaLogger {
+    vm: JavaVM,
+    max_level: LevelFilter,
+    sink: Sink,
+}
+
+impl JavaLogger {
+    fn new(env: &JNIEnv, config: Config) -> JniResult<Self> {
+        let sink = match config.backend {
+            Backend::JavaUtilLogging(name) => {
+                let name = env.new_string(name)?;
+                let logger = env
+                    .call_static_method(
+                        JUL_LOGGER_CLASS,
+                        "getLogger",
+                        "(Ljava/lang/String;)Ljava/util/logging/Logger;",
+                        &[JValue::Object(name.into())],
+                    )?
+                    .l()?;
+
+                let levels = ["SEVERE", "WARNING", "INFO", "FINE", "FINER"]
+                    .iter()
+                    .map(|level| {
+                        let level = env
+                            .get_static_field(JUL_LEVEL_CLASS, *level, "Ljava/util/logging/Level;")?
+                            .l()?;
+                        env.new_global_ref(level)
+                    })
+                    .collect::<JniResult<_>>()?;
+
+                Sink::JavaUtilLogging {
+                    logger: env.new_global_ref(logger)?,
+                    levels,
+                }
+            }
+            Backend::Android(tag) => Sink::Android {
+                class: env.new_global_ref(env.find_class(ANDROID_LOG_CLASS)?)?,
+                tag: env.new_global_ref(env.new_string(tag)?)?,
+            },
+        };
+
+        Ok(JavaLogger {
+            vm: env.get_java_vm()?,
+            max_level: config.max_level,
+            sink,
+        })
+    }
+
+    fn forward(&self, env: &JNIEnv, record: &Record, message: &str) -> JniResult<()> {
+        let message = env.new_string(message)?;
+
+        match &self.sink {
+            Sink::JavaUtilLogging { logger, levels } => {
+                let level = &levels[record.level() as usize - 1];
+                let target = env.new_string(record.target())?;
+                env.call_method(
+                    logger.as_obj(),
+                    "logp",
+                    "(Ljava/util/logging/Level;Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;)V",
+                    &[
+                        JValue::Object(level.as_obj()),
+                        JValue::Object(target.into()),
+                        JValue::Object(JObject::null()),
+                        JValue::Object(message.into()),
+                    ],
+                )?;
+                env.delete_local_ref(target.into())?;
+            }
+            Sink::Android { class, tag } => {
+                let priority = match record.level() {
+                    Level::Error => 6,
+                    Level::Warn => 5,
+                    Level::Info => 4,
+                    Level::Debug => 3,
+                    Level::Trace => 2,
+                };
+                env.call_static_method(
+                    JClass::from(class.as_obj()),
+                    "println",
+                    "(ILjava/lang/String;Ljava/lang/String;)I",
+                    &[
+                        JValue::Int(priority),
+                        JValue::Object(tag.as_obj()),
+                        JValue::Object(message.into()),
+                    ],
+                )?;
+            }
+        }
+
+        // Records can be logged in long-running native methods, which would otherwise accumulate local references
+        env.delete_local_ref(message.into())
+    }
+}
+
+impl Log for JavaLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.max_level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let message = record.args().to_string();
+        let forwarded = match self.vm.attach_current_thread_as_daemon() {
+            // No other JNI function can be called while an exception is pending
+            Ok(env) if matches!(env.exception_check(), Ok(false)) => match self.forward(&env, record, &message) {
+                Ok(()) => true,
+                Err(_) => {
+                    let _ = env.exception_clear();
+                    false
+                }
+            },
+            _ => false,
+        };
+
+        if !forwarded {
+            eprintln!("[{} {}] {}", record.level(), record.target(), message);
+        }
+    }
+
+    fn flush(&self) {}
+}
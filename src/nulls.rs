@@ -0,0 +1,57 @@
+//! Null checks for the parameters of exported methods.
+//!
+//! Most parameter types can't represent a Java `null`: converting a null reference to a `String`, a bridged struct or
+//! an array fails deep inside the conversion, with an error (or, with `#[call_type(unchecked)]`, a panic) that doesn't
+//! say which parameter was null.
+//!
+//! With `#[call_type(safe)]` (the default), exported methods check their parameters before converting them, and throw a
+//! `NullPointerException` naming the first null parameter instead of calling the method. Parameters that accept `null`
+//! are not checked: `Option<T>` (converted to `None`), [`Opaque<T>`](crate::convert::Opaque) and raw JNI references
+//! (`JObject`, `JString`, `JClass`, ...). The check is disabled with `#[null_check(off)]`.
+//!
+//! With `#[call_type(unchecked)]`, parameters are not checked unless the method is annotated with `#[null_check]`:
+//! a null reference makes the conversion panic, and the panic is handled as configured with `#[on_panic]`
+//! (see [`panic`](crate::panic)), by default by throwing a `RuntimeException`.
+//!
+//! ```ignore
+//! // Throws `NullPointerException: `Greeter::greet`: parameter `name` (String) is null` for `Greeter.greet(null)`
+//! pub extern "jni" fn greet(name: String) -> JniResult<String> {
+//!     Ok(format!("Hello, {}", name))
+//! }
+//!
+//! // `Greeter.greetOrDefault(null)` returns "Hello, stranger"
+//! pub extern "jni" fn greetOrDefault(name: Option<String>) -> JniResult<String> {
+//!     Ok(format!("Hello, {}", name.as_deref().unwrap_or("stranger")))
+//! }
+//! ```
+
+use jni::objects::JObject;
+use jni::JNIEnv;
+
+const NULL_POINTER_EXCEPTION_CLASS: &str = "java/lang/NullPointerException";
+
+/// Name of the first parameter in `params` (given as name, Rust type and object) with a null object.
+///
+/// Parameters with a `None` object (i.e. primitive values) are ignored.
+pub fn find_null<'p>(params: &[(&'p str, &'p str, Option<JObject>)]) -> Option<(&'p str, &'p str)> {
+    params
+        .iter()
+        .find(|(_, _, object)| object.is_some_and(|o| o.is_null()))
+        .map(|&(name, rust_type, _)| (name, rust_type))
+}
+
+/// Check the parameters of exported method `method` for null references.
+///
+/// Returns `false` if the method must not be called, in which case a `NullPointerException` is pending.
+pub fn check_parameters(env: &JNIEnv, method: &str, params: &[(&str, &str, Option<JObject>)]) -> bool {
+    let (name, rust_type) = match find_null(params) {
+        Some(param) => param,
+        None => return true,
+    };
+
+    let message = format!("`{}`: parameter `{}` ({}) is null", method, name, rust_type);
+    if let Err(e) = env.throw_new(NULL_POINTER_EXCEPTION_CLASS, message) {
+        println!("Error while throwing Java exception: {}", e);
+    }
+    false
+}
@@ -0,0 +1,54 @@
+//! Handling of panics raised by exported methods.
+//!
+//! Unwinding out of a native method is undefined behaviour, so the body of every exported method is run with
+//! [`catch_unwind`](std::panic::catch_unwind). What happens to a caught panic is chosen with the `#[on_panic]` attribute:
+//!
+//! * `#[on_panic(throw = "java.lang.IllegalStateException")]` throws an exception of the given class, with the panic
+//!   message as exception message. This is the default, with `java.lang.RuntimeException`.
+//! * `#[on_panic(abort)]` aborts the process, for code that can't leave the JVM in a consistent state after a panic.
+//!
+//! ```ignore
+//! #[on_panic(throw = "java.lang.IllegalStateException")]
+//! pub extern "jni" fn checkedDivide(a: i32, b: i32) -> i32 {
+//!     a.checked_div(b).expect("division by zero")
+//! }
+//! ```
+//!
+//! If a Java exception is already pending when the panic is caught (e.g. a `#[call_type(unchecked)]` call to a Java method
+//! threw and then panicked), that exception is left untouched, since it is usually the root cause of the panic.
+
+use std::any::Any;
+
+use jni::JNIEnv;
+
+/// Message of a panic payload, as given to `panic!`.
+pub fn payload_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "Box<dyn Any>"
+    }
+}
+
+/// Throw an exception of class `exception_class` (in JNI format) for the panic with payload `payload`,
+/// caught in exported method `method`.
+pub fn throw(env: &JNIEnv, method: &str, exception_class: &str, payload: Box<dyn Any + Send>) {
+    let message = payload_message(&*payload);
+
+    if env.exception_check().unwrap_or(true) {
+        eprintln!("`{}` panicked with a pending Java exception: {}", method, message);
+        return;
+    }
+
+    if let Err(e) = crate::loader::throw_new(env, exception_class, message) {
+        eprintln!("Error while throwing Java exception: {}", e);
+    }
+}
+
+/// Abort the process for the panic with payload `payload`, caught in exported method `method`.
+pub fn abort(method: &str, payload: Box<dyn Any + Send>) -> ! {
+    eprintln!("`{}` panicked, aborting: {}", method, payload_message(&*payload));
+    std::process::abort()
+}
@@ -0,0 +1,111 @@
+//! Utilities for Java class names.
+//!
+//! The JVM uses several formats to refer to the same class:
+//!  * binary names, e.g. `com.example.Outer$Inner`
+//!  * internal names (class paths), used with [`JNIEnv::find_class`](jni::JNIEnv::find_class), e.g. `com/example/Outer$Inner`
+//!  * type descriptors, used in signatures, e.g. `Lcom/example/Outer$Inner;` or `[Lcom/example/Outer$Inner;` for arrays
+//!
+//! [`JavaPath`] converts between them:
+//! ```
+//! use robusta_jni::path::JavaPath;
+//!
+//! let path: JavaPath = "com.example.Outer".parse().unwrap();
+//! let inner = path.nested("Inner");
+//!
+//! assert_eq!(inner.to_classpath_path(), "com/example/Outer$Inner");
+//! assert_eq!(inner.to_descriptor(), "Lcom/example/Outer$Inner;");
+//! assert_eq!(inner.to_array_descriptor(2), "[[Lcom/example/Outer$Inner;");
+//! assert_eq!(JavaPath::from_descriptor("Lcom/example/Outer$Inner;"), Some(inner.clone()));
+//! assert_eq!(inner.package(), "com.example");
+//! assert_eq!(inner.class_name(), "Outer$Inner");
+//!
+//! assert_eq!(robusta_jni::path::array_descriptor("I", 1), "[I");
+//! assert!("com/example/Outer".parse::<JavaPath>().is_err());
+//! ```
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// Binary name of a Java class (e.g. `java.lang.String`, or `java.util.Map$Entry` for nested classes).
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct JavaPath(String);
+
+impl JavaPath {
+    /// Parse a class path (internal name) such as `java/lang/String`.
+    pub fn from_classpath_path(path: &str) -> Result<Self, String> {
+        JavaPath::from_str(&path.replace('/', "."))
+    }
+
+    /// Parse a class type descriptor such as `Ljava/lang/String;`.
+    ///
+    /// Returns `None` for primitive and array descriptors.
+    pub fn from_descriptor(descriptor: &str) -> Option<Self> {
+        descriptor
+            .strip_prefix('L')
+            .and_then(|d| d.strip_suffix(';'))
+            .and_then(|d| JavaPath::from_classpath_path(d).ok())
+    }
+
+    /// Class nested in this one, e.g. `java.util.Map` and `Entry` give `java.util.Map$Entry`.
+    pub fn nested(&self, name: &str) -> JavaPath {
+        JavaPath(format!("{}${}", self.0, name))
+    }
+
+    /// Package of the class, e.g. `java.util` for `java.util.Map$Entry`. Empty for classes in the default package.
+    pub fn package(&self) -> &str {
+        self.0.rsplit_once('.').map(|(p, _)| p).unwrap_or("")
+    }
+
+    /// Name of the class without its package, e.g. `Map$Entry` for `java.util.Map$Entry`.
+    pub fn class_name(&self) -> &str {
+        self.0.rsplit_once('.').map(|(_, c)| c).unwrap_or(&self.0)
+    }
+
+    /// Class path (internal name), e.g. `java/util/Map$Entry`.
+    pub fn to_classpath_path(&self) -> String {
+        self.0.replace('.', "/")
+    }
+
+    /// Type descriptor, e.g. `Ljava/util/Map$Entry;`.
+    pub fn to_descriptor(&self) -> String {
+        format!("L{};", self.to_classpath_path())
+    }
+
+    /// Type descriptor of an array with `dimensions` dimensions of this class, e.g. `[Ljava/lang/String;` for `String[]`.
+    ///
+    /// Note that this is also the name to use for array classes with [`JNIEnv::find_class`](jni::JNIEnv::find_class).
+    pub fn to_array_descriptor(&self, dimensions: usize) -> String {
+        array_descriptor(&self.to_descriptor(), dimensions)
+    }
+}
+
+/// Wrap a type descriptor (e.g. `I` or `Ljava/lang/String;`) in `dimensions` array dimensions.
+pub fn array_descriptor(element_descriptor: &str, dimensions: usize) -> String {
+    let mut descriptor = "[".repeat(dimensions);
+    descriptor.push_str(element_descriptor);
+    descriptor
+}
+
+impl FromStr for JavaPath {
+    type Err = String;
+
+    /// Parse a binary name, such as `java.lang.String`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = s.replace(' ', "");
+        if input.is_empty() || input.split('.').any(str::is_empty) {
+            Err(format!("invalid class name `{}`", s))
+        } else if input.contains('-') {
+            Err("package names can't contain dashes".into())
+        } else if input.contains(['/', ';', '[']) {
+            Err(format!("`{}` is not a binary class name", s))
+        } else {
+            Ok(JavaPath(input))
+        }
+    }
+}
+
+impl Display for JavaPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
@@ -0,0 +1,283 @@
+//! Manifests of the native functions exported by bridged modules.
+//!
+//! With `#[bridge(symbol_manifest)]`, the bridged module gets a `robusta_symbol_manifest()` function returning a
+//! [`SymbolManifest`] of its exported methods: their symbol names (e.g. `Java_com_example_User_getPassword`), Java
+//! classes, names and JNI signatures. A manifest can be rendered as a list of symbols, a linker version script, JSON, or a
+//! C header like the ones generated by `javac -h`.
+//!
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! #[bridge(symbol_manifest)]
+//! mod jni {
+//!     # use robusta_jni::convert::Signature;
+//!     #[derive(Signature)]
+//!     #[package(com.example)]
+//!     struct Codec;
+//!
+//!     impl Codec {
+//!         pub extern "jni" fn encode(input: String, level: i32) -> Box<[u8]> {
+//!             input.into_bytes().into_boxed_slice()
+//!         }
+//!     }
+//! }
+//!
+//! let manifest = jni::robusta_symbol_manifest();
+//! assert_eq!(manifest.symbols(), ["Java_com_example_Codec_encode"]);
+//! assert_eq!(manifest.functions[0].signature, "(Ljava/lang/String;I)[B");
+//! assert!(manifest.to_c_header("codec").contains(
+//!     "JNIEXPORT jbyteArray JNICALL Java_com_example_Codec_encode\n  (JNIEnv *, jclass, jstring, jint);"
+//! ));
+//! ```
+//!
+//! Methods of a module using `#[bridge(register_natives)]` aren't exported as symbols: they are listed without a symbol
+//! name, and the manifest exports `JNI_OnLoad` instead.
+//!
+//! Signatures are computed from the conversion traits of the parameter types, so manifests are built by running code
+//! of the crate, e.g. in a test. [`SymbolManifest::emit`] writes the manifest files to the directory given in the
+//! `ROBUSTA_SYMBOLS_DIR` environment variable, and does nothing if it isn't set:
+//! ```ignore
+//! #[test]
+//! fn symbol_manifest() {
+//!     let manifest = SymbolManifest::merge([jni::robusta_symbol_manifest(), other::robusta_symbol_manifest()]);
+//!     manifest.emit("native").unwrap();
+//! }
+//! ```
+//! Running `ROBUSTA_SYMBOLS_DIR=target/symbols cargo test symbol_manifest` then writes `native.symbols`,
+//! `native.map`, `native.json` and `native.h` to `target/symbols`.
+
+use std::fmt::Write as _;
+use std::io;
+use std::path::PathBuf;
+
+/// Environment variable with the directory to write manifests to, see [`SymbolManifest::emit`].
+pub const SYMBOLS_DIR_VAR: &str = "ROBUSTA_SYMBOLS_DIR";
+
+const ON_LOAD_SYMBOL: &str = "JNI_OnLoad";
+const ON_UNLOAD_SYMBOL: &str = "JNI_OnUnload";
+
+/// A native method exported by a bridged module.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NativeFunction {
+    /// Name of the exported symbol, or `None` for methods registered with `RegisterNatives`
+    pub symbol: Option<&'static str>,
+    /// Class path of the class declaring the method (e.g. `com/example/User`)
+    pub class_path: &'static str,
+    /// Java name of the method
+    pub java_name: &'static str,
+    /// JNI signature of the method (e.g. `(I)Ljava/lang/String;`)
+    pub signature: String,
+    /// Whether the method is a static method of the class
+    pub is_static: bool,
+    /// Checked exception declared with `#[throws]` (e.g. `java.io.IOException`), to be declared by the Java method
+    pub throws: Option<&'static str>,
+}
+
+/// Native methods exported by one or more bridged modules.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SymbolManifest {
+    pub functions: Vec<NativeFunction>,
+    /// Whether a `JNI_OnLoad` function registering native methods is exported
+    pub on_load: bool,
+    /// Whether a `JNI_OnUnload` function running the unload hooks is exported, see [`unload`](crate::unload)
+    pub on_unload: bool,
+}
+
+impl SymbolManifest {
+    /// Manifest of all the functions of `manifests`, e.g. of all the bridged modules of a library.
+    pub fn merge<I: IntoIterator<Item = SymbolManifest>>(manifests: I) -> Self {
+        manifests.into_iter().fold(SymbolManifest::default(), |mut merged, manifest| {
+            merged.functions.extend(manifest.functions);
+            merged.on_load |= manifest.on_load;
+            merged.on_unload |= manifest.on_unload;
+            merged
+        })
+    }
+
+    /// Names of the exported symbols, in declaration order.
+    pub fn symbols(&self) -> Vec<&'static str> {
+        let on_load = Some(ON_LOAD_SYMBOL).filter(|_| self.on_load);
+        let on_unload = Some(ON_UNLOAD_SYMBOL).filter(|_| self.on_unload);
+        self.functions.iter().filter_map(|f| f.symbol).chain(on_load).chain(on_unload).collect()
+    }
+
+    /// Exported symbols, one per line.
+    pub fn to_symbol_list(&self) -> String {
+        self.symbols().iter().map(|s| format!("{}\n", s)).collect()
+    }
+
+    /// GNU linker version script exporting the symbols of the manifest, and hiding all others.
+    pub fn to_version_script(&self) -> String {
+        let mut script = String::from("{\n  global:\n");
+        for symbol in self.symbols() {
+            let _ = writeln!(script, "    {};", symbol);
+        }
+        script.push_str("  local:\n    *;\n};\n");
+        script
+    }
+
+    /// JSON object with the exported functions and whether `JNI_OnLoad` and `JNI_OnUnload` are exported, e.g.
+    /// `{"on_load": false, "on_unload": false, "functions": [{"symbol": "Java_User_getInt", "class": "User", "method": "getInt",
+    /// "signature": "(I)I", "static": false, "throws": null}]}`.
+    pub fn to_json(&self) -> String {
+        let functions: Vec<String> = self
+            .functions
+            .iter()
+            .map(|f| {
+                format!(
+                    "    {{\"symbol\": {}, \"class\": {}, \"method\": {}, \"signature\": {}, \"static\": {}, \"throws\": {}}}",
+                    f.symbol.map(json_string).unwrap_or_else(|| "null".to_string()),
+                    json_string(f.class_path),
+                    json_string(f.java_name),
+                    json_string(&f.signature),
+                    f.is_static,
+                    f.throws.map(json_string).unwrap_or_else(|| "null".to_string())
+                )
+            })
+            .collect();
+
+        let functions = if functions.is_empty() { "[]".to_string() } else { format!("[\n{}\n  ]", functions.join(",\n")) };
+        format!(
+            "{{\n  \"on_load\": {},\n  \"on_unload\": {},\n  \"functions\": {}\n}}\n",
+            self.on_load, self.on_unload, functions
+        )
+    }
+
+    /// C header declaring the exported functions, in the format of `javac -h`, with `name` used for the include guard.
+    pub fn to_c_header(&self, name: &str) -> String {
+        let guard: String = name.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect();
+
+        let mut header = String::new();
+        let _ = writeln!(header, "/* DO NOT EDIT THIS FILE - it is machine generated */");
+        let _ = writeln!(header, "#include <jni.h>\n");
+        let _ = writeln!(header, "#ifndef _Included_{}\n#define _Included_{}", guard, guard);
+        let _ = writeln!(header, "#ifdef __cplusplus\nextern \"C\" {{\n#endif");
+
+        for function in &self.functions {
+            let symbol = match function.symbol {
+                Some(symbol) => symbol,
+                None => continue,
+            };
+            let (parameters, return_type) = c_types(&function.signature);
+            let receiver = if function.is_static { "jclass" } else { "jobject" };
+            let parameters: String = parameters.iter().map(|p| format!(", {}", p)).collect();
+
+            let _ = writeln!(
+                header,
+                "/*\n * Class:     {}\n * Method:    {}\n * Signature: {}\n */",
+                function.class_path.replace(['/', '$'], "_"),
+                function.java_name,
+                function.signature
+            );
+            let _ = writeln!(header, "JNIEXPORT {} JNICALL {}\n  (JNIEnv *, {}{});\n", return_type, symbol, receiver, parameters);
+        }
+
+        if self.on_load {
+            let _ = writeln!(header, "JNIEXPORT jint JNICALL {}\n  (JavaVM *, void *);\n", ON_LOAD_SYMBOL);
+        }
+
+        if self.on_unload {
+            let _ = writeln!(header, "JNIEXPORT void JNICALL {}\n  (JavaVM *, void *);\n", ON_UNLOAD_SYMBOL);
+        }
+
+        let _ = writeln!(header, "#ifdef __cplusplus\n}}\n#endif\n#endif");
+        header
+    }
+
+    /// Write the manifest to the directory given in the `ROBUSTA_SYMBOLS_DIR` environment variable, if set, as
+    /// `{name}.symbols` (symbol list), `{name}.map` (version script), `{name}.json` and `{name}.h`.
+    ///
+    /// Returns the directory the files were written to.
+    pub fn emit(&self, name: &str) -> io::Result<Option<PathBuf>> {
+        let dir = match std::env::var_os(SYMBOLS_DIR_VAR) {
+            Some(dir) => PathBuf::from(dir),
+            None => return Ok(None),
+        };
+
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(dir.join(format!("{}.symbols", name)), self.to_symbol_list())?;
+        std::fs::write(dir.join(format!("{}.map", name)), self.to_version_script())?;
+        std::fs::write(dir.join(format!("{}.json", name)), self.to_json())?;
+        std::fs::write(dir.join(format!("{}.h", name)), self.to_c_header(name))?;
+        Ok(Some(dir))
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+/// C types of the parameters and of the return value of a method with JNI signature `signature`.
+fn c_types(signature: &str) -> (Vec<&'static str>, &'static str) {
+    let (parameters, return_type) = signature
+        .strip_prefix('(')
+        .and_then(|s| s.split_once(')'))
+        .unwrap_or(("", signature));
+
+    let mut types = Vec::new();
+    let mut rest = parameters;
+    while !rest.is_empty() {
+        let (c_type, len) = c_type(rest);
+        types.push(c_type);
+        rest = &rest[len.min(rest.len())..];
+    }
+
+    (types, c_type(return_type).0)
+}
+
+/// C type of the first type of descriptor list `descriptors`, and the length of its descriptor.
+fn c_type(descriptors: &str) -> (&'static str, usize) {
+    let object_len = |s: &str| s.find(';').map_or(s.len(), |i| i + 1);
+
+    match descriptors.as_bytes().first() {
+        Some(b'Z') => ("jboolean", 1),
+        Some(b'B') => ("jbyte", 1),
+        Some(b'C') => ("jchar", 1),
+        Some(b'S') => ("jshort", 1),
+        Some(b'I') => ("jint", 1),
+        Some(b'J') => ("jlong", 1),
+        Some(b'F') => ("jfloat", 1),
+        Some(b'D') => ("jdouble", 1),
+        Some(b'V') => ("void", 1),
+        Some(b'L') => {
+            let len = object_len(descriptors);
+            let c_type = match &descriptors[..len] {
+                "Ljava/lang/String;" => "jstring",
+                "Ljava/lang/Class;" => "jclass",
+                "Ljava/lang/Throwable;" => "jthrowable",
+                _ => "jobject",
+            };
+            (c_type, len)
+        }
+        Some(b'[') => {
+            let (element, len) = c_type(&descriptors[1..]);
+            let c_type = match (element, descriptors.as_bytes().get(1)) {
+                (_, Some(b'[')) | (_, Some(b'L')) => "jobjectArray",
+                ("jboolean", _) => "jbooleanArray",
+                ("jbyte", _) => "jbyteArray",
+                ("jchar", _) => "jcharArray",
+                ("jshort", _) => "jshortArray",
+                ("jint", _) => "jintArray",
+                ("jlong", _) => "jlongArray",
+                ("jfloat", _) => "jfloatArray",
+                ("jdouble", _) => "jdoubleArray",
+                _ => "jarray",
+            };
+            (c_type, len + 1)
+        }
+        _ => ("jobject", descriptors.len().max(1)),
+    }
+}
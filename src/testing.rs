@@ -0,0 +1,375 @@
+//! Testing bridges: unit tests with an embedded JVM, and integration test suites run against multiple Java versions.
+//!
+//! ## Unit tests
+//! Integration tests run by Gradle are heavyweight. With the `invocation` feature as well as `testing`, bridged code can also be
+//! tested from Rust with `cargo test`, using a JVM shared by all tests of the test binary:
+//!  * [`jvm`] starts the JVM the first time it's called, with the builder given by [`default_vm`] (the class path is
+//!    read from the `CLASSPATH` environment variable), and [`jvm_with`] with another builder
+//!  * [`attach`], [`attach_permanently`] and [`detach`] attach test threads to the JVM
+//!  * [`with_env`] calls a closure with the `JNIEnv` of the current thread, failing if it leaves a Java exception pending
+//!  * [`robusta_test`] turns a function taking a `&JNIEnv` into a test, called through [`with_env`]
+//!
+//! ```ignore
+//! use robusta_jni::embed::{Classpath, VmBuilder};
+//! use robusta_jni::jni::errors::Result as JniResult;
+//! use robusta_jni::jni::JNIEnv;
+//! use robusta_jni::testing::{default_vm, robusta_test};
+//!
+//! #[robusta_test]
+//! fn password(env: &JNIEnv) {
+//!     // `User` is a struct of a `#[bridge]` module, with an imported constructor and imported methods
+//!     let user = User::new(env, "user".into(), "password".into()).unwrap();
+//!     assert_eq!(user.getPassword(env).unwrap(), "password");
+//! }
+//!
+//! // Classes and JVM options are configured with a `VmBuilder`
+//! fn driver_vm() -> VmBuilder {
+//!     default_vm().classpath(Classpath::new().entry("build/classes/java/main"))
+//! }
+//!
+//! #[robusta_test(jvm = driver_vm)]
+//! fn users_count(env: &JNIEnv) -> JniResult<()> {
+//!     assert!(User::getTotalUsersCount(env)? > 0);
+//!     Ok(())
+//! }
+//! ```
+//!
+//! Since a JVM can only be created once per process, the first test to run decides how the JVM is configured:
+//! all tests of a binary should use the same builder. Exported functions are called by Java code as usual, so their
+//! library must be loaded by the JVM (see [`VmBuilder::library_path`](crate::embed::VmBuilder::library_path)).
+//!
+//! ## Multiple Java versions
+//! Type signatures, default methods and available classes differ between Java versions, so bindings are best tested
+//! with every version they're expected to run on. Since a JVM can only be created once per process, each version is
+//! tested by a separate command (e.g. `./gradlew test`, or a test binary embedding a JVM), run with the `JAVA_HOME`
+//! of that version. This module (enabled by the `testing` feature) provides:
+//!  * [`JavaHome`], a Java installation with its major version
+//!  * [`JavaMatrix`], the installations to test against, usually discovered from the environment with [`JavaMatrix::from_env`]
+//!  * [`MatrixReport`], the outcome of running a command with each installation
+//!
+//! Example, in an integration test of a downstream crate:
+//! ```ignore
+//! use std::process::Command;
+//! use robusta_jni::testing::{JavaFeature, JavaMatrix};
+//!
+//! #[test]
+//! fn java_versions() {
+//!     let matrix = JavaMatrix::from_env().expect("invalid Java installation");
+//!     let report = matrix.run(|java| {
+//!         let mut gradle = Command::new("./gradlew");
+//!         gradle.arg("test");
+//!         if !java.supports(JavaFeature::Records) {
+//!             gradle.arg("-PexcludeTags=records");
+//!         }
+//!         gradle
+//!     });
+//!
+//!     report.assert_success();
+//! }
+//! ```
+//!
+//! Test commands are run with `JAVA_HOME` set to the installation, its `bin` directory first in `PATH`,
+//! and `ROBUSTA_JAVA_VERSION` set to its major version. Suites that can't run at all on some versions
+//! can be skipped with [`JavaMatrix::require`].
+
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus};
+
+#[cfg(feature = "invocation")]
+pub use robusta_codegen::robusta_test;
+
+#[cfg(feature = "invocation")]
+mod jvm;
+#[cfg(feature = "invocation")]
+pub use jvm::*;
+
+/// Environment variable listing the Java installations to test against, separated as `PATH` entries.
+pub const JAVA_HOMES_VAR: &str = "ROBUSTA_JAVA_HOMES";
+
+/// Environment variable set for test commands to the major version of the Java installation they're run with.
+pub const JAVA_VERSION_VAR: &str = "ROBUSTA_JAVA_VERSION";
+
+/// Long-term support versions, looked up in `JAVA_HOME_<version>` variables by [`JavaMatrix::from_env`].
+pub const LTS_VERSIONS: &[u32] = &[8, 11, 17, 21];
+
+/// Java language and platform features whose availability depends on the Java version.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JavaFeature {
+    DefaultMethods,
+    Modules,
+    Records,
+    SealedClasses,
+    VirtualThreads,
+}
+
+impl JavaFeature {
+    /// First major Java version with the feature (not counting preview releases).
+    pub fn since(self) -> u32 {
+        match self {
+            JavaFeature::DefaultMethods => 8,
+            JavaFeature::Modules => 9,
+            JavaFeature::Records => 16,
+            JavaFeature::SealedClasses => 17,
+            JavaFeature::VirtualThreads => 21,
+        }
+    }
+}
+
+/// Errors raised while discovering Java installations.
+#[derive(Debug)]
+pub enum MatrixError {
+    /// The `release` file of the installation can't be read
+    Io(PathBuf, io::Error),
+    /// The `release` file of the installation has no valid `JAVA_VERSION`
+    UnknownVersion(PathBuf),
+}
+
+impl Display for MatrixError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            MatrixError::Io(path, e) => write!(f, "can't read Java installation {}: {}", path.display(), e),
+            MatrixError::UnknownVersion(path) => write!(f, "unknown version of Java installation {}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for MatrixError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MatrixError::Io(_, e) => Some(e),
+            MatrixError::UnknownVersion(_) => None,
+        }
+    }
+}
+
+/// A Java installation, i.e. a directory that can be used as `JAVA_HOME`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JavaHome {
+    version: u32,
+    path: PathBuf,
+}
+
+impl JavaHome {
+    /// Installation at `path`, with major version `version`.
+    pub fn new(version: u32, path: impl Into<PathBuf>) -> Self {
+        JavaHome {
+            version,
+            path: path.into(),
+        }
+    }
+
+    /// Installation at `path`, with the version read from its `release` file.
+    pub fn detect(path: impl Into<PathBuf>) -> Result<Self, MatrixError> {
+        let path = path.into();
+        let release = match fs::read_to_string(path.join("release")) {
+            Ok(release) => release,
+            Err(e) => return Err(MatrixError::Io(path, e)),
+        };
+
+        match parse_release_version(&release) {
+            Some(version) => Ok(JavaHome { version, path }),
+            None => Err(MatrixError::UnknownVersion(path)),
+        }
+    }
+
+    /// Major version (e.g. `8` for Java 1.8).
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether `feature` is available in this version.
+    ///
+    /// ```
+    /// # use robusta_jni::testing::{JavaFeature, JavaHome};
+    /// let java = JavaHome::new(11, "/usr/lib/jvm/java-11-openjdk");
+    /// assert!(java.supports(JavaFeature::DefaultMethods));
+    /// assert!(!java.supports(JavaFeature::Records));
+    /// ```
+    pub fn supports(&self, feature: JavaFeature) -> bool {
+        self.version >= feature.since()
+    }
+
+    /// Make `command` run with this installation.
+    pub fn configure(&self, command: &mut Command) {
+        let bin = self.path.join("bin");
+        let path = std::env::var_os("PATH")
+            .map(|path| std::env::split_paths(&path).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let path = std::iter::once(bin).chain(path);
+
+        command.env("JAVA_HOME", &self.path);
+        if let Ok(path) = std::env::join_paths(path) {
+            command.env("PATH", path);
+        }
+        command.env(JAVA_VERSION_VAR, self.version.to_string());
+    }
+}
+
+/// Major version in the `JAVA_VERSION` entry of a `release` file, e.g. `JAVA_VERSION="1.8.0_392"` or `JAVA_VERSION="17.0.9"`.
+fn parse_release_version(release: &str) -> Option<u32> {
+    let version = release
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("JAVA_VERSION="))?
+        .trim_matches('"');
+
+    let mut components = version.split(|c: char| !c.is_ascii_digit());
+    match components.next()?.parse().ok()? {
+        1 => components.next()?.parse().ok(),
+        major => Some(major),
+    }
+}
+
+/// Java installations to run a test suite with.
+#[derive(Clone, Debug, Default)]
+pub struct JavaMatrix {
+    homes: Vec<JavaHome>,
+    required: Vec<JavaFeature>,
+}
+
+impl JavaMatrix {
+    /// Empty matrix.
+    pub fn new() -> Self {
+        JavaMatrix::default()
+    }
+
+    /// Installations configured in the environment, in order of version:
+    ///  * all entries of `ROBUSTA_JAVA_HOMES`, if set
+    ///  * otherwise, the `JAVA_HOME_<version>` variables of [`LTS_VERSIONS`] (also with the `_X64` and `_ARM64` suffixes set by CI runners)
+    ///  * otherwise, `JAVA_HOME`
+    ///
+    /// Versions are read from the `release` file of each installation.
+    pub fn from_env() -> Result<Self, MatrixError> {
+        let mut paths: Vec<PathBuf> = match std::env::var_os(JAVA_HOMES_VAR) {
+            Some(homes) => std::env::split_paths(&homes).filter(|p| !p.as_os_str().is_empty()).collect(),
+            None => LTS_VERSIONS
+                .iter()
+                .filter_map(|version| {
+                    ["", "_X64", "_ARM64"]
+                        .iter()
+                        .find_map(|suffix| std::env::var_os(format!("JAVA_HOME_{}{}", version, suffix)))
+                        .map(PathBuf::from)
+                })
+                .collect(),
+        };
+        if paths.is_empty() {
+            paths.extend(std::env::var_os("JAVA_HOME").map(PathBuf::from));
+        }
+
+        let mut matrix = JavaMatrix::new();
+        for path in paths {
+            if !matrix.homes.iter().any(|h| h.path == path) {
+                matrix = matrix.home(JavaHome::detect(path)?);
+            }
+        }
+
+        Ok(matrix)
+    }
+
+    /// Add installation `home`.
+    pub fn home(mut self, home: JavaHome) -> Self {
+        self.homes.push(home);
+        self.homes.sort_by_key(|h| h.version);
+        self
+    }
+
+    /// Skip installations without `feature`.
+    pub fn require(mut self, feature: JavaFeature) -> Self {
+        self.required.push(feature);
+        self
+    }
+
+    /// Installations, in order of version.
+    pub fn homes(&self) -> &[JavaHome] {
+        &self.homes
+    }
+
+    /// Run the command given by `command` once with each installation, waiting for it to complete.
+    ///
+    /// Standard output and error of the commands are inherited, so that test failures are shown with the output of the caller.
+    pub fn run<F>(&self, mut command: F) -> MatrixReport
+    where
+        F: FnMut(&JavaHome) -> Command,
+    {
+        let results = self
+            .homes
+            .iter()
+            .map(|home| {
+                if let Some(&feature) = self.required.iter().find(|f| !home.supports(**f)) {
+                    return (home.clone(), Outcome::Skipped(feature));
+                }
+
+                let mut command = command(home);
+                home.configure(&mut command);
+
+                let outcome = match command.status() {
+                    Ok(status) if status.success() => Outcome::Passed,
+                    Ok(status) => Outcome::Failed(status),
+                    Err(e) => Outcome::NotStarted(e.to_string()),
+                };
+                (home.clone(), outcome)
+            })
+            .collect();
+
+        MatrixReport { results }
+    }
+}
+
+/// Outcome of a test command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Passed,
+    /// The command exited with an error status
+    Failed(ExitStatus),
+    /// The command couldn't be started, with the reason
+    NotStarted(String),
+    /// The installation doesn't support a feature required with [`JavaMatrix::require`]
+    Skipped(JavaFeature),
+}
+
+/// Outcomes of running a command with each installation of a [`JavaMatrix`].
+#[derive(Clone, Debug)]
+pub struct MatrixReport {
+    results: Vec<(JavaHome, Outcome)>,
+}
+
+impl MatrixReport {
+    /// Outcome for each installation, in order of version.
+    pub fn results(&self) -> &[(JavaHome, Outcome)] {
+        &self.results
+    }
+
+    /// Whether no command failed. Skipped installations are not considered failures.
+    pub fn is_success(&self) -> bool {
+        self.results
+            .iter()
+            .all(|(_, outcome)| matches!(outcome, Outcome::Passed | Outcome::Skipped(_)))
+    }
+
+    /// Panic with a summary of the outcomes if any command failed.
+    pub fn assert_success(&self) {
+        assert!(self.is_success(), "tests failed with some Java versions:\n{}", self);
+    }
+}
+
+impl Display for MatrixReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (home, outcome) in &self.results {
+            write!(f, "Java {} ({}): ", home.version, home.path.display())?;
+            match outcome {
+                Outcome::Passed => writeln!(f, "passed")?,
+                Outcome::Failed(status) => writeln!(f, "failed ({})", status)?,
+                Outcome::NotStarted(reason) => writeln!(f, "not started ({})", reason)?,
+                Outcome::Skipped(feature) => writeln!(f, "skipped ({:?} requires Java {})", feature, feature.since())?,
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+//! Shared embedded JVM for unit tests, see the [`testing`](super) module documentation.
+
+use std::sync::OnceLock;
+
+use jni::objects::JObject;
+use jni::{AttachGuard, JNIEnv, JavaVM};
+
+use crate::embed::{describe_exception, Classpath, VmBuilder};
+
+/// Number of local references guaranteed to each call of [`with_env`].
+const LOCAL_FRAME_CAPACITY: i32 = 64;
+
+static VM: OnceLock<JavaVM> = OnceLock::new();
+
+/// Builder of the shared JVM used by [`jvm`]: the class path is read from the `CLASSPATH` environment variable,
+/// and JNI calls are checked with `-Xcheck:jni`.
+pub fn default_vm() -> VmBuilder {
+    VmBuilder::new()
+        .classpath(Classpath::from_env())
+        .option("-Xcheck:jni")
+}
+
+/// Shared JVM of the test binary, started with [`default_vm`] if no test started it yet.
+pub fn jvm() -> &'static JavaVM {
+    jvm_with(default_vm)
+}
+
+/// Shared JVM of the test binary, started with the builder returned by `builder` if no test started it yet.
+///
+/// Since a JVM can only be created once per process, the first test to run decides how the JVM is configured:
+/// all tests of a binary should use the same builder. Panics if the JVM can't be started.
+pub fn jvm_with<F>(builder: F) -> &'static JavaVM
+where
+    F: FnOnce() -> VmBuilder,
+{
+    VM.get_or_init(|| match builder().build() {
+        Ok(vm) => vm,
+        Err(e) => panic!("can't start the test JVM: {}", e),
+    })
+}
+
+/// Attach the current thread to the shared JVM until the returned guard is dropped.
+///
+/// Attaching a thread which is already attached is a no-op, and the returned guard won't detach it.
+pub fn attach() -> AttachGuard<'static> {
+    jvm()
+        .attach_current_thread()
+        .unwrap_or_else(|e| panic!("can't attach the current thread to the test JVM: {}", e))
+}
+
+/// Attach the current thread to the shared JVM until it exits or [`detach`] is called.
+pub fn attach_permanently() -> JNIEnv<'static> {
+    jvm()
+        .attach_current_thread_permanently()
+        .unwrap_or_else(|e| panic!("can't attach the current thread to the test JVM: {}", e))
+}
+
+/// Detach the current thread from the shared JVM, if it is attached.
+///
+/// Every `JNIEnv` and [`AttachGuard`] of the current thread is invalid afterwards.
+pub fn detach() {
+    if let Some(vm) = VM.get() {
+        vm.detach_current_thread();
+    }
+}
+
+/// Call `f` with the `JNIEnv` of the current thread, attached to the shared JVM for the duration of the call.
+///
+/// Local references created by `f` are deleted when it returns, and `with_env` panics if `f` leaves a Java exception pending,
+/// so that the exception fails the test instead of leaking into the next one.
+pub fn with_env<F, R>(f: F) -> R
+where
+    F: FnOnce(&JNIEnv) -> R,
+{
+    with_env_in(jvm(), f)
+}
+
+/// Like [`with_env`], with the shared JVM returned by [`jvm_with`] (or any other JVM).
+pub fn with_env_in<F, R>(vm: &JavaVM, f: F) -> R
+where
+    F: FnOnce(&JNIEnv) -> R,
+{
+    let env = vm
+        .attach_current_thread()
+        .unwrap_or_else(|e| panic!("can't attach the current thread to the test JVM: {}", e));
+
+    env.push_local_frame(LOCAL_FRAME_CAPACITY)
+        .expect("can't create a local reference frame");
+    let result = f(&env);
+
+    let exception = describe_exception(&env).expect("can't describe the pending Java exception");
+    env.pop_local_frame(JObject::null())
+        .expect("can't delete the local reference frame");
+
+    if let Some(exception) = exception {
+        panic!("Java exception left pending: {}", exception);
+    }
+    result
+}
@@ -0,0 +1,82 @@
+//! Teardown of the library when it is unloaded.
+//!
+//! The JVM calls `JNI_OnUnload` when the class loader that loaded the library is garbage collected. With
+//! `#[bridge(on_unload)]`, the module exports a `JNI_OnUnload` function calling [`unload`], which runs the hooks
+//! registered with [`on_unload`] (e.g. to shut down thread pools or flush buffers), and then releases the global
+//! references cached by this crate: classes of bridged structs (see [`JavaClass`](crate::convert::JavaClass)), embedded
+//! helper classes (see [`helpers`](crate::helpers)) and the class loader registered with
+//! [`set_class_loader`](crate::loader::set_class_loader).
+//!
+//! `#[bridge(on_unload = "path::to::function")]` additionally calls `function(&JavaVM)` before the registered hooks:
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! #[bridge(on_unload = "shutdown")]
+//! mod jni {
+//!     # use robusta_jni::convert::Signature;
+//!     # use robusta_jni::jni::JavaVM;
+//!     #[derive(Signature)]
+//!     #[package(com.example)]
+//!     struct Worker;
+//!
+//!     impl Worker {
+//!         pub extern "jni" fn start() {
+//!             // Threads, caches, ...
+//!         }
+//!     }
+//!
+//!     fn shutdown(_vm: &JavaVM) {
+//!         // Stop threads started by `start`
+//!     }
+//! }
+//! ```
+//!
+//! Only one module per library can use this option. Note that global references to classes keep their class loader,
+//! and so the library, from being unloaded: a library whose classes are loaded by a class loader meant to be discarded
+//! (e.g. a plugin) should call [`unload`] itself, from a native method called before the class loader is dropped.
+//! Hooks run only once, so `JNI_OnUnload` then does nothing.
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Mutex;
+
+use jni::JavaVM;
+
+type Hook = Box<dyn FnOnce(&JavaVM) + Send>;
+
+static HOOKS: Mutex<Vec<Hook>> = Mutex::new(Vec::new());
+
+/// Run `hook` when the library is unloaded, before the hooks registered earlier.
+pub fn on_unload<F: FnOnce(&JavaVM) + Send + 'static>(hook: F) {
+    HOOKS.lock().unwrap().push(Box::new(hook));
+}
+
+/// Run the hooks registered with [`on_unload`], in reverse order of registration, and forget them.
+///
+/// Panics of hooks are caught and printed, so that all hooks run. Returns the number of hooks that were run.
+pub fn run_hooks(vm: &JavaVM) -> usize {
+    let hooks = std::mem::take(&mut *HOOKS.lock().unwrap());
+    let count = hooks.len();
+
+    for hook in hooks.into_iter().rev() {
+        if let Err(payload) = catch_unwind(AssertUnwindSafe(|| hook(vm))) {
+            eprintln!("Unload hook panicked: {}", crate::panic::payload_message(&*payload));
+        }
+    }
+
+    count
+}
+
+/// Release the global references cached by this crate. They are created again if needed.
+pub fn release_caches() {
+    crate::convert::class::clear_class_caches();
+    crate::helpers::clear_helper_classes();
+    crate::loader::clear_class_loader();
+    #[cfg(feature = "pool")]
+    crate::convert::pool::clear_pooled_lists();
+}
+
+/// Run the unload hooks and release cached references, see the [module documentation](self).
+pub fn unload(vm: &JavaVM) {
+    run_hooks(vm);
+    release_caches();
+}
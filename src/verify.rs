@@ -0,0 +1,224 @@
+//! Runtime verification of imported (`extern "java"`) methods.
+//!
+//! With `#[bridge(verify_bindings)]`, the bridged module gets a `robusta_verify_bindings(env)` function, which checks
+//! that every imported method declared in the module has a matching method (by class, name and signature) on the Java side.
+//! Calling it early (e.g. right after loading the library) catches drift between Rust and Java declarations before
+//! a mismatch surfaces as a `NoSuchMethodError` deep inside application logic:
+//! ```ignore
+//! let report = jni::robusta_verify_bindings(&env)?;
+//! assert!(report.is_ok(), "{}", report);
+//! ```
+//!
+//! Methods declared with `#[fallback_signature]` match if any of their signatures is found.
+//! Methods taking an explicit class parameter are checked against the class of the bridged struct.
+//!
+//! Imported methods can also be checked at compile time against a list of Java method descriptors, with
+//! `#[bridge(check_against = "bindings.json")]` (the path is relative to the directory of `Cargo.toml`).
+//! The file maps class paths to method names to descriptors, e.g. as printed by `javap -s`:
+//! ```json
+//! {
+//!   "com/example/User": {
+//!     "<init>": ["(Ljava/lang/String;Ljava/lang/String;)V"],
+//!     "getPassword": ["()Ljava/lang/String;"]
+//!   }
+//! }
+//! ```
+//! Compilation fails if an imported method of a listed class has no method with the same name and a matching signature
+//! in the file. Classes that aren't listed are not checked, and static methods aren't told apart from instance methods.
+
+use std::fmt::{self, Display, Formatter};
+
+use jni::errors::{Error, Result as JniResult};
+use jni::JNIEnv;
+
+/// An imported method to verify.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImportedMethod {
+    /// Name of the method in Rust, including the struct name (e.g. `User::getPassword`)
+    pub rust_name: &'static str,
+    /// Class path of the class declaring the method (e.g. `com/example/User`)
+    pub class_path: &'static str,
+    /// Java name of the method, `<init>` for constructors
+    pub java_name: &'static str,
+    /// Accepted signatures, in order of preference
+    pub signatures: Vec<String>,
+    /// Whether the method is a static method of the class
+    pub is_static: bool,
+}
+
+/// Reason for a failed verification.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MismatchKind {
+    /// The declaring class can't be found
+    ClassNotFound,
+    /// The class doesn't declare a method with the expected name and any of the expected signatures
+    MethodNotFound,
+}
+
+/// An imported method without a matching Java method.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mismatch {
+    pub method: ImportedMethod,
+    pub kind: MismatchKind,
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let ImportedMethod {
+            rust_name,
+            class_path,
+            java_name,
+            signatures,
+            is_static,
+        } = &self.method;
+
+        match self.kind {
+            MismatchKind::ClassNotFound => write!(f, "{}: class `{}` not found", rust_name, class_path),
+            MismatchKind::MethodNotFound => write!(
+                f,
+                "{}: {}method `{}.{}` not found with signature {}",
+                rust_name,
+                if *is_static { "static " } else { "" },
+                class_path,
+                java_name,
+                signatures.join(" or ")
+            ),
+        }
+    }
+}
+
+/// Result of a verification.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VerificationReport {
+    /// Number of verified methods
+    pub checked: usize,
+    /// Methods without a matching Java method
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl VerificationReport {
+    /// Whether all methods have a matching Java method.
+    pub fn is_ok(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+impl Display for VerificationReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} of {} imported methods verified",
+            self.checked - self.mismatches.len(),
+            self.checked
+        )?;
+        for mismatch in &self.mismatches {
+            write!(f, "\n  {}", mismatch)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Check that every method in `methods` has a matching Java method.
+///
+/// Lookup failures are reported in the returned [`VerificationReport`], while other errors (e.g. a Java exception
+/// thrown by a static initializer) are returned as `Err`.
+pub fn verify_methods(env: &JNIEnv, methods: Vec<ImportedMethod>) -> JniResult<VerificationReport> {
+    let mut report = VerificationReport {
+        checked: methods.len(),
+        mismatches: Vec::new(),
+    };
+
+    for method in methods {
+        let kind = match lookup(env, crate::loader::find_class(env, method.class_path), "java/lang/NoClassDefFoundError")? {
+            None => Some(MismatchKind::ClassNotFound),
+            Some(class) => {
+                let mut found = false;
+                for signature in &method.signatures {
+                    let lookup_result = if method.is_static {
+                        env.get_static_method_id(class, method.java_name, signature.as_str())
+                            .map(|_| ())
+                    } else {
+                        env.get_method_id(class, method.java_name, signature.as_str())
+                            .map(|_| ())
+                    };
+
+                    if lookup(env, lookup_result, "java/lang/NoSuchMethodError")?.is_some() {
+                        found = true;
+                        break;
+                    }
+                }
+
+                env.delete_local_ref(class.into())?;
+                if found {
+                    None
+                } else {
+                    Some(MismatchKind::MethodNotFound)
+                }
+            }
+        };
+
+        if let Some(kind) = kind {
+            report.mismatches.push(Mismatch { method, kind });
+        }
+    }
+
+    Ok(report)
+}
+
+/// Whether the concatenation of `parts` is one of `signatures`. Used by compile-time checks of imported methods.
+pub const fn matches_any(parts: &[&str], signatures: &[&str]) -> bool {
+    let mut i = 0;
+    while i < signatures.len() {
+        if concat_eq(parts, signatures[i].as_bytes()) {
+            return true;
+        }
+        i += 1;
+    }
+
+    false
+}
+
+/// Whether the concatenation of `parts` is `expected`
+const fn concat_eq(parts: &[&str], expected: &[u8]) -> bool {
+    let mut pos = 0;
+    let mut i = 0;
+    while i < parts.len() {
+        let part = parts[i].as_bytes();
+        let mut j = 0;
+        while j < part.len() {
+            if pos >= expected.len() || expected[pos] != part[j] {
+                return false;
+            }
+            pos += 1;
+            j += 1;
+        }
+        i += 1;
+    }
+
+    pos == expected.len()
+}
+
+/// Turn a lookup failure with a pending exception of class `missing_exception_class` into `None`, clearing the exception.
+fn lookup<T>(env: &JNIEnv, result: JniResult<T>, missing_exception_class: &str) -> JniResult<Option<T>> {
+    match result {
+        Ok(v) => Ok(Some(v)),
+        Err(Error::JavaException) => {
+            // No other JNI function can be called while the exception is pending
+            let exception = env.exception_occurred()?;
+            env.exception_clear()?;
+            // Methods are looked up one by one, so the references used to check the exception are deleted right away
+            let class = env.find_class(missing_exception_class)?;
+            let missing = env.is_instance_of(exception, class)?;
+            env.delete_local_ref(class.into())?;
+            if missing {
+                env.delete_local_ref(exception.into())?;
+                Ok(None)
+            } else {
+                env.throw(exception)?;
+                Err(Error::JavaException)
+            }
+        }
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,99 @@
+//! Process-wide handle to the Java VM.
+//!
+//! Native code that calls back into Java from its own threads needs the [`JavaVM`] to attach them. [`Vm`] keeps the
+//! first `JavaVM` it is obtained from in a process-wide static, so that it can be retrieved anywhere with [`Vm::get`].
+//! Generated native methods store it on their first call, see [`attach`](crate::attach).
+//!
+//! Exported methods can take a `&Vm` (or `&'static Vm`) parameter, which must come first (after `self` and the
+//! environment parameter, if present). It isn't part of the Java signature:
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! # #[bridge]
+//! # mod jni {
+//!     # use robusta_jni::convert::Signature;
+//!     # use robusta_jni::Vm;
+//! #[derive(Signature)]
+//! #[package(com.example)]
+//! struct Worker;
+//!
+//! impl Worker {
+//!     // Java: `static native void initNative()`
+//!     pub extern "jni" fn initNative(vm: &'static Vm) {
+//!         std::thread::spawn(move || {
+//!             vm.with_env(|_env| {
+//!                 // Call Java methods with `_env`
+//!             })
+//!         });
+//!     }
+//! }
+//! # }
+//! ```
+//! Afterwards, [`Vm::get`] returns the same handle, e.g. in threads started by a library that doesn't know about the
+//! bridged code.
+
+use std::ops::Deref;
+use std::sync::OnceLock;
+
+use jni::errors::Result as JniResult;
+use jni::{AttachGuard, JNIEnv, JavaVM};
+
+static VM: OnceLock<Vm> = OnceLock::new();
+
+/// Handle to the Java VM of the process, usable from any thread.
+pub struct Vm {
+    vm: JavaVM,
+}
+
+impl Vm {
+    /// Handle to the Java VM running `env`, stored process-wide on first use.
+    pub fn from_env(env: &JNIEnv) -> JniResult<&'static Vm> {
+        if let Some(vm) = VM.get() {
+            return Ok(vm);
+        }
+
+        let vm = env.get_java_vm()?;
+        Ok(VM.get_or_init(|| Vm { vm }))
+    }
+
+    /// Store `vm` as the Java VM of the process, e.g. in `JNI_OnLoad` or after creating an embedded VM.
+    ///
+    /// Returns the stored handle, which is the one given earlier if a handle was already stored.
+    pub fn init(vm: JavaVM) -> &'static Vm {
+        VM.get_or_init(|| Vm { vm })
+    }
+
+    /// Handle stored by [`Vm::from_env`] or [`Vm::init`], if any.
+    pub fn get() -> Option<&'static Vm> {
+        VM.get()
+    }
+
+    /// The underlying [`JavaVM`].
+    pub fn java_vm(&self) -> &JavaVM {
+        &self.vm
+    }
+
+    /// Attach the current thread to the VM, until the returned guard is dropped.
+    ///
+    /// Threads that were already attached are left attached.
+    pub fn attach(&self) -> JniResult<AttachGuard<'_>> {
+        self.vm.attach_current_thread()
+    }
+
+    /// Run `f` with an environment of the current thread, attaching it to the VM for the duration of the call if needed.
+    pub fn with_env<F, R>(&self, f: F) -> JniResult<R>
+    where
+        F: FnOnce(&JNIEnv) -> R,
+    {
+        let guard = self.attach()?;
+        Ok(f(&guard))
+    }
+}
+
+impl Deref for Vm {
+    type Target = JavaVM;
+
+    fn deref(&self) -> &JavaVM {
+        &self.vm
+    }
+}
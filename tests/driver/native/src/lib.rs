@@ -1,35 +1,193 @@
 use robusta_jni::bridge;
 
-#[bridge]
+#[bridge(verify_bindings, symbol_manifest, check_against = "bindings.json")]
 pub mod jni {
+    use std::borrow::Cow;
     use std::convert::TryInto;
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+    use std::num::Wrapping;
+    use std::path::{Path, PathBuf};
+    use std::sync::Mutex;
+    use std::time::{Duration, SystemTime};
+
+    use bytes::Bytes;
+    use rust_decimal::Decimal;
+    use serde::{Deserialize, Serialize};
 
+    use robusta_jni::convert::function::{Function, IntUnaryOperator};
     use robusta_jni::convert::{
-        IntoJavaValue, JValueWrapper, Signature, TryFromJavaValue, TryIntoJavaValue,
+        Cbor, Checked, DirectByteBuffer, JArray, FromJavaValue, IntoData, IntoGlobal, IntoJavaValue, JBitSet, JIterator, JPath, JavaClass, PackedList, RustFunction, RustIterator, JavaDelegate, Field, Lazy, JOptional, JValueWrapper, Json, Opaque, Signature, TryFromJavaValue,
+        TryIntoJavaValue,
     };
-    use robusta_jni::jni::errors::Result as JniResult;
-    use robusta_jni::jni::objects::AutoLocal;
+    use log::LevelFilter;
+    use robusta_jni::jni::errors::{Error, JniError, Result as JniResult};
+    use robusta_jni::jni::objects::{AutoLocal, JClass, JString};
     use robusta_jni::jni::JNIEnv;
+    use robusta_jni::logging;
+    use robusta_jni::Vm;
+
+    use crate::ip::{DottedIp, IntIp};
+
+    type StringCube = Box<[Box<[Box<[String]>]>]>;
+
+    static STORED_USER: Mutex<Option<UserGlobal>> = Mutex::new(None);
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Order {
+        items: Vec<String>,
+        quantity: i32,
+    }
+
+    #[derive(JavaDelegate)]
+    pub struct Names(Box<[String]>);
+
+    #[derive(JavaDelegate)]
+    pub struct UserId(i32);
+
+    #[derive(Signature)]
+    #[package(java.util)]
+    pub struct Locale;
+
+    #[derive(Signature)]
+    #[package()]
+    #[class(User.Counter)]
+    pub struct UserCounter;
+
+    impl<'env> UserCounter {
+        pub extern "jni" fn previous(value: i32) -> i32 {
+            value - 1
+        }
 
-    #[derive(Signature, TryIntoJavaValue, IntoJavaValue, TryFromJavaValue)]
+        pub extern "jni" fn isCounter(env: &JNIEnv, obj: ::robusta_jni::jni::objects::JObject<'env>) -> JniResult<bool> {
+            env.is_instance_of(obj, UserCounter::class(env)?)
+        }
+    }
+
+    #[derive(Signature, TryIntoJavaValue, IntoJavaValue, TryFromJavaValue, IntoGlobal, IntoData)]
     #[package()]
     pub struct User<'env: 'borrow, 'borrow> {
         #[instance]
         raw: AutoLocal<'env, 'borrow>,
+        #[writeback]
         password: String,
+        pub username: Lazy<'env, 'borrow, String>,
+        /// Number of logins, only looked up by the methods using it
+        #[field(lazy)]
+        login_count: i32,
     }
 
     impl<'env: 'borrow, 'borrow> User<'env, 'borrow> {
-        pub extern "jni" fn initNative() {
-            std::env::var("RUST_LOG").unwrap_or_else(|_| {
-                std::env::set_var("RUST_LOG", "info");
-                "info".to_string()
-            });
-            println!(
-                "Initialized env logger with level: {}",
-                std::env::var("RUST_LOG").unwrap()
-            );
-            env_logger::init();
+        #[export]
+        pub const MAX_USERS: i32 = 100;
+
+        #[export]
+        pub const DEFAULT_GREETING: &str = "Hello";
+
+        pub extern "jni" fn initNative(env: &JNIEnv) {
+            let max_level = std::env::var("RUST_LOG")
+                .ok()
+                .and_then(|level| level.parse().ok())
+                .unwrap_or(LevelFilter::Info);
+
+            match logging::install_with(env, logging::Config::new().max_level(max_level)) {
+                Ok(()) => println!("Initialized Java logger with level: {}", max_level),
+                Err(e) => println!("Error while initializing Java logger: {}", e),
+            }
+        }
+
+        pub extern "jni" fn localeTag(env: &JNIEnv, locale: Opaque<'env, Locale>) -> JniResult<String> {
+            let tag: JString = JValueWrapper::from(env.call_method(*locale, "toLanguageTag", "()Ljava/lang/String;", &[])?).try_into()?;
+            env.get_string(tag).map(Into::into)
+        }
+
+        pub extern "jni" fn sameLocale(locale: Opaque<'env, Locale>) -> Opaque<'env, Locale> {
+            locale
+        }
+
+        pub extern "jni" fn panicWith(message: String) -> i32 {
+            panic!("{}", message)
+        }
+
+        #[on_panic(throw = "java.lang.IllegalStateException")]
+        pub extern "jni" fn checkedDivide(a: i32, b: i32) -> i32 {
+            a.checked_div(b).expect("division by zero")
+        }
+
+        #[no_bridge]
+        pub extern "jni" fn disabledExport(value: i32) -> i32 {
+            value * 2
+        }
+
+        pub extern "jni" fn callDisabledExport(value: i32) -> i32 {
+            User::disabledExport(value)
+        }
+
+        pub extern "jni" fn describeUser(env: &JNIEnv, user: User<'env, 'borrow>) -> String {
+            user.describe(env)
+        }
+
+        pub extern "jni" fn describeObject(env: &JNIEnv, obj: ::robusta_jni::jni::objects::JObject<'env>) -> String {
+            ::robusta_jni::convert::debug::describe(env, obj)
+        }
+
+        pub extern "jni" fn storeUser(env: &JNIEnv, user: User<'env, 'borrow>) -> JniResult<()> {
+            let user = user.into_global(env)?;
+            // Owned values can be moved across threads
+            let user = std::thread::spawn(move || user).join().unwrap();
+            *STORED_USER.lock().unwrap() = Some(user);
+            Ok(())
+        }
+
+        pub extern "jni" fn storedUserPassword() -> String {
+            STORED_USER.lock().unwrap().as_ref().map(|user| user.password.clone()).unwrap_or_default()
+        }
+
+        pub extern "jni" fn takeStoredUser(env: &'borrow JNIEnv<'env>) -> JniResult<User<'env, 'borrow>> {
+            match STORED_USER.lock().unwrap().take() {
+                Some(user) => user.into_local(env),
+                None => Err(robusta_jni::jni::errors::Error::NullPtr("no stored user")),
+            }
+        }
+
+        pub extern "jni" fn shoutedCopy(env: &'borrow JNIEnv<'env>, user: User<'env, 'borrow>) -> JniResult<User<'env, 'borrow>> {
+            let data = crate::shout(user.into_data()?);
+            data.into_java(env)
+        }
+
+        pub extern "jni" fn className(env: &JNIEnv, class: JClass<'env>) -> JniResult<String> {
+            let name = env.call_method(class, "getName", "()Ljava/lang/String;", &[])?.l()?;
+            env.get_string(From::from(name)).map(Into::into)
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn sameClassUnchecked(env: &JNIEnv, class: JClass<'env>, obj: ::robusta_jni::jni::objects::JObject<'env>) -> bool {
+            env.is_instance_of(obj, class).unwrap()
+        }
+
+        pub extern "jni" fn recordLogin(env: &'borrow JNIEnv<'env>, user: User<'env, 'borrow>) -> JniResult<i32> {
+            let mut login_count = user.login_count(env)?;
+            let count = login_count.get()? + 1;
+            login_count.set(count)?;
+            Ok(count)
+        }
+
+        pub extern "jni" fn add_one(value: i32) -> i32 {
+            value + 1
+        }
+
+        #[export_name = "Java_User_00024Counter_next"]
+        pub extern "jni" fn counterNext(value: i32) -> i32 {
+            value + 1
+        }
+
+        pub extern "jni" fn logWarning(message: String) {
+            log::warn!("{}", message);
+        }
+
+        pub extern "jni" fn logWarningFromThread(message: String) {
+            std::thread::spawn(move || log::warn!("{}", message))
+                .join()
+                .unwrap();
         }
 
         pub extern "jni" fn userCountStatus(env: &JNIEnv) -> String {
@@ -42,11 +200,260 @@ pub mod jni {
             users_count.to_string()
         }
 
+        pub extern "jni" fn lazyUsername(self, load: bool) -> JniResult<String> {
+            if !load {
+                return Ok(format!("loaded: {}", self.username.is_loaded()));
+            }
+
+            let username = self.username.get()?.clone();
+            Ok(format!("{} (loaded: {})", username, self.username.is_loaded()))
+        }
+
+        pub extern "jni" fn lazyUsernameUnchecked(self) -> String {
+            self.username.get_unchecked().to_uppercase()
+        }
+
         pub extern "jni" fn hashedPassword(self, _env: &JNIEnv, _seed: i32) -> String {
             let user_pw: String = self.password;
             user_pw + "_pass"
         }
 
+        pub extern "jni" fn profile(&self, env: &JNIEnv) -> JniResult<String> {
+            // The receiver is only borrowed, so it can be used by several calls
+            let name = self.name(env)?;
+            let password = self.getPassword(env)?;
+            Ok(format!("{} ({}, {} chars)", name, self.username.get()?, password.len()))
+        }
+
+        pub extern "jni" fn messageTo(&self, receiver: String) -> JniResult<String> {
+            Ok(format!("{} -> {}", self.username.get()?, receiver))
+        }
+
+        pub extern "jni" fn appendToPassword(&mut self, suffix: String) {
+            self.password.push_str(&suffix);
+        }
+
+        pub extern "jni" fn appendChecksum(&mut self, #[critical] bytes: &[i8]) {
+            let checksum: i32 = bytes.iter().map(|&b| b as i32).sum();
+            self.password.push_str(&checksum.to_string());
+        }
+
+        pub extern "jni" fn formatNameWithFallback(env: &JNIEnv, name: String) -> JniResult<String> {
+            User::formatName(env, name, true)
+        }
+
+        pub extern "jni" fn formatNameFromSlices(env: &JNIEnv, first: String, last: String) -> JniResult<String> {
+            let full_name = [first.as_str(), last.as_str()].join(" ");
+            let first_part = User::formatSlice(env, full_name.split(' ').next().unwrap_or_default())?;
+            let full = User::formatBorrowedString(env, &full_name);
+            Ok(format!("{} {}", first_part, full))
+        }
+
+        pub extern "jni" fn fallbackSelfMethod(self, env: &JNIEnv, s: String) -> JniResult<String> {
+            self.multipleParametersWithFlag(env, 0, s, true)
+        }
+
+        pub extern "jni" fn verifyBindings(env: &JNIEnv) -> JniResult<String> {
+            crate::jni::robusta_verify_bindings(env).map(|report| report.to_string())
+        }
+
+        pub extern "jni" fn isUser(env: &JNIEnv, obj: ::robusta_jni::jni::objects::JObject<'env>) -> JniResult<bool> {
+            env.is_instance_of(obj, User::class(env)?)
+        }
+
+        #[check_aliasing]
+        pub extern "jni" fn samePassword(self, other: Self) -> JniResult<bool> {
+            Ok(self.password == other.password)
+        }
+
+        #[check_aliasing(warn)]
+        pub extern "jni" fn samePasswordLenient(self, other: Self) -> JniResult<bool> {
+            Ok(self.password == other.password)
+        }
+
+        #[check_aliasing]
+        pub extern "jni" fn concatenate(first: String, second: String) -> JniResult<String> {
+            Ok(first + &second)
+        }
+
+        pub extern "jni" fn shapeKinds(env: &JNIEnv, shape: Shape<'env, 'borrow>) -> JniResult<String> {
+            Ok(format!("{}/{}/{}", shape.kind(env)?, shape.shapeKind(env)?, shape.shapeKindUnchecked(env)))
+        }
+
+        pub extern "jni" fn greet(env: &JNIEnv, named: Named<'env, 'borrow>) -> JniResult<String> {
+            named.greeting(env)
+        }
+
+        pub extern "jni" fn suspendCalls(env: &JNIEnv, fetcher: Fetcher<'env, 'borrow>) -> JniResult<String> {
+            Ok(format!(
+                "{}/{}/{}/{}",
+                Fetcher::length(env, "four".to_string())?,
+                Fetcher::greeting(env, "user".to_string())?,
+                Fetcher::hasEmptyContext(env),
+                fetcher.fetch(env, 7)?
+            ))
+        }
+
+        pub extern "jni" fn suspendFailure(env: &JNIEnv, message: String) -> JniResult<()> {
+            Fetcher::fail(env, message)
+        }
+
+        pub extern "jni" fn describeHolder(holder: Holder<'env, 'borrow, String>) -> JniResult<String> {
+            Ok(format!("{}: {}", holder.label, holder.value))
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn describeHolderUnchecked(holder: Holder<'env, 'borrow, String>) -> String {
+            format!("{}: {}", holder.label, holder.value)
+        }
+
+        pub extern "jni" fn nestedHolderLabel(holder: Holder<'env, 'borrow, Holder<'env, 'borrow, String>>) -> JniResult<String> {
+            Ok(format!("{}/{}", holder.label, holder.value.label))
+        }
+
+        pub extern "jni" fn echoHolder(holder: Holder<'env, 'borrow, String>) -> JniResult<Holder<'env, 'borrow, String>> {
+            Ok(holder)
+        }
+
+        pub extern "jni" fn rawUserPassword(env: &JNIEnv, obj: ::robusta_jni::jni::objects::JObject<'env>) -> JniResult<String> {
+            // Simulates a reference handed over by another JNI library
+            let raw = env.new_local_ref(obj)?.into_raw();
+            let user = unsafe { User::from_raw(env, raw)? };
+            Ok(user.password)
+        }
+
+        pub extern "jni" fn passwordBySignature(env: &JNIEnv, user: User<'env, 'borrow>) -> JniResult<String> {
+            // Manual call reusing the name and signature computed for the imported `getPassword`
+            let password = env.call_method(user.raw.as_obj(), Self::GET_PASSWORD_NAME, Self::GET_PASSWORD_SIG, &[])?.l()?;
+            let password: String = env.get_string(From::from(password))?.into();
+            Ok(format!("{} {}", password, Self::PASSWORD_BY_SIGNATURE_SIG))
+        }
+
+        pub extern "jni" fn rawUser(user: User<'env, 'borrow>) -> ::robusta_jni::jni::objects::JObject<'env> {
+            unsafe { ::robusta_jni::jni::objects::JObject::from_raw(user.into_raw()) }
+        }
+
+        pub extern "jni" fn absHex(env: &JNIEnv, value: i32) -> JniResult<String> {
+            User::toHexString(env, User::abs(env, value as i64) as i32)
+        }
+
+        pub extern "jni" fn greetDefaultUser(env: &JNIEnv) -> JniResult<String> {
+            let username = User::default_username(env)?;
+            Greeter::greet(env, username)
+        }
+
+        pub extern "jni" fn usersList(
+            env: &'borrow JNIEnv<'env>,
+            count: i32,
+        ) -> JniResult<Vec<Self>> {
+            (0..count)
+                .map(|i| User::new(env, format!("user{}", i), format!("pass{}", i)))
+                .collect()
+        }
+
+        pub extern "jni" fn passwordOfBuiltUser(
+            env: &'borrow JNIEnv<'env>,
+            username: String,
+            password: String,
+        ) -> JniResult<String> {
+            let mut builder = User::builder();
+            if !username.is_empty() {
+                builder = builder.username(username);
+            }
+            if !password.is_empty() {
+                builder = builder.password(password);
+            }
+
+            builder.build(env)?.getPassword(env)
+        }
+
+        pub extern "jni" fn passwordOfNamedUser(env: &'borrow JNIEnv<'env>, username: String) -> JniResult<String> {
+            User::named(env, &username)?.getPassword(env)
+        }
+
+        pub extern "jni" fn usersArray(
+            env: &'borrow JNIEnv<'env>,
+            count: i32,
+        ) -> JniResult<Box<[Self]>> {
+            (0..count)
+                .map(|i| User::new(env, format!("user{}", i), format!("pass{}", i)))
+                .collect()
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn usersListUnchecked(env: &'borrow JNIEnv<'env>, count: i32) -> Vec<Self> {
+            (0..count)
+                .map(|i| User::new(env, format!("user{}", i), format!("pass{}", i)).unwrap())
+                .collect()
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn usersArrayUnchecked(
+            env: &'borrow JNIEnv<'env>,
+            count: i32,
+        ) -> Box<[Self]> {
+            (0..count)
+                .map(|i| User::new(env, format!("user{}", i), format!("pass{}", i)).unwrap())
+                .collect()
+        }
+
+        pub extern "jni" fn incrementBuffer(mut buffer: DirectByteBuffer<'env>) {
+            // The buffer is allocated with `ByteBuffer.allocateDirect`, and isn't shared with other Java threads
+            unsafe { buffer.as_mut_slice() }
+                .iter_mut()
+                .for_each(|b| *b = b.wrapping_add(1));
+        }
+
+        pub extern "jni" fn bufferFromBytes(
+            env: &'borrow JNIEnv<'env>,
+            bytes: Box<[u8]>,
+        ) -> JniResult<DirectByteBuffer<'env>> {
+            DirectByteBuffer::from_slice(env, &bytes)
+        }
+
+        pub extern "jni" fn passwordFromThread(self, env: &JNIEnv) -> JniResult<String> {
+            let user = self.to_global(env)?;
+
+            std::thread::spawn(move || user.with(|env, user| user.getPassword(env)))
+                .join()
+                .unwrap()?
+        }
+
+        pub extern "jni" fn classNameFromThread(env: &JNIEnv) -> JniResult<String> {
+            // Threads attached by native code look up classes with the context class loader of the caller
+            robusta_jni::set_context_class_loader(env)?;
+            let vm = env.get_java_vm()?;
+
+            let name = std::thread::spawn(move || -> JniResult<String> {
+                let env = vm.attach_current_thread()?;
+                let class = robusta_jni::loader::find_class(&env, "User")?;
+                let name = env.call_method(class, "getName", "()Ljava/lang/String;", &[])?.l()?;
+                <String as TryFromJavaValue>::try_from(From::from(name), &env)
+            })
+            .join()
+            .unwrap();
+
+            robusta_jni::loader::clear_class_loader();
+            name
+        }
+
+        #[synchronized]
+        pub extern "jni" fn holdsLock(self, env: &JNIEnv) -> JniResult<bool> {
+            let obj = ::robusta_jni::jni::objects::JValue::Object(self.raw.as_obj());
+            env.call_static_method("java/lang/Thread", "holdsLock", "(Ljava/lang/Object;)Z", &[obj])?.z()
+        }
+
+        #[synchronized]
+        #[call_type(unchecked)]
+        pub extern "jni" fn holdsClassLock(env: &JNIEnv) -> bool {
+            let class = ::robusta_jni::jni::objects::JValue::Object(<User as JavaClass>::class(env).unwrap().into());
+            env.call_static_method("java/lang/Thread", "holdsLock", "(Ljava/lang/Object;)Z", &[class]).unwrap().z().unwrap()
+        }
+
+        pub extern "jni" fn displayNameFromNative(self, env: &JNIEnv) -> JniResult<String> {
+            self.name(env)
+        }
+
         pub extern "jni" fn getInt(self, v: i32) -> i32 {
             v
         }
@@ -79,6 +486,26 @@ pub mod jni {
             v
         }
 
+        pub extern "jni" fn getUnsignedInt(self, v: u32) -> u32 {
+            v
+        }
+
+        pub extern "jni" fn unsignedIntToString(self, v: Wrapping<u32>) -> String {
+            v.to_string()
+        }
+
+        pub extern "jni" fn unsignedByteToString(self, v: Wrapping<u8>) -> String {
+            v.to_string()
+        }
+
+        pub extern "jni" fn maxUnsignedInt(self) -> Wrapping<u32> {
+            Wrapping(u32::MAX)
+        }
+
+        pub extern "jni" fn maxCheckedUnsignedShort(self) -> Checked<u16> {
+            Checked(u16::MAX)
+        }
+
         pub extern "jni" fn getString(self, v: String) -> String {
             v
         }
@@ -95,6 +522,27 @@ pub mod jni {
             v
         }
 
+        pub extern "jni" fn getByteVec(self, v: Vec<u8>) -> Vec<u8> {
+            v
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn getByteVecUnchecked(v: Vec<u8>) -> Vec<u8> {
+            v
+        }
+
+        pub extern "jni" fn byteVecToString(self, v: Vec<u8>) -> String {
+            format!("{:?}", v)
+        }
+
+        pub extern "jni" fn getSignedByteArray(self, v: Box<[i8]>) -> Box<[i8]> {
+            v
+        }
+
+        pub extern "jni" fn signedByteArrayToString(self, v: Box<[i8]>) -> String {
+            format!("{:?}", v)
+        }
+
         pub extern "jni" fn intToString(self, v: i32) -> String {
             format!("{}", v)
         }
@@ -139,31 +587,1218 @@ pub mod jni {
             format!("{:?}", v)
         }
 
-        pub extern "java" fn getPassword(
-            &self,
-            env: &JNIEnv,
-        ) -> ::robusta_jni::jni::errors::Result<String> {
+        pub extern "jni" fn transposeStrings(
+            v: Box<[Box<[String]>]>,
+        ) -> Box<[Box<[String]>]> {
+            let cols = v.first().map(|r| r.len()).unwrap_or(0);
+            (0..cols)
+                .map(|c| v.iter().map(|r| r[c].clone()).collect())
+                .collect()
         }
 
-        pub extern "java" fn getTotalUsersCount(
-            env: &JNIEnv,
-        ) -> ::robusta_jni::jni::errors::Result<i32> {
+        pub extern "jni" fn byteMatrix(rows: i32, cols: i32) -> Box<[Box<[u8]>]> {
+            (0..rows)
+                .map(|r| (0..cols).map(|c| (r * cols + c) as u8).collect())
+                .collect()
         }
 
-        pub extern "java" fn multipleParameters(
-            &self,
-            env: &JNIEnv,
-            v: i32,
-            s: String,
-        ) -> ::robusta_jni::jni::errors::Result<String> {
+        pub extern "jni" fn stringCube(n: i32) -> StringCube {
+            (0..n)
+                .map(|i| {
+                    (0..n)
+                        .map(|j| (0..n).map(|k| format!("{}{}{}", i, j, k)).collect())
+                        .collect()
+                })
+                .collect()
         }
 
-        #[constructor]
-        pub extern "java" fn new(
-            env: &'borrow JNIEnv<'env>,
-            username: String,
-            password: String,
-        ) -> JniResult<Self> {
+        pub extern "jni" fn sortNames(mut names: Names) -> Names {
+            names.0.sort();
+            names
+        }
+
+        pub extern "jni" fn reverseInts(values: Box<[i32]>) -> Box<[i32]> {
+            values.into_vec().into_iter().rev().collect()
+        }
+
+        pub extern "jni" fn sumLongs(values: JArray<i64>) -> i64 {
+            values.0.iter().sum()
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn scaleDoubles(values: Box<[f64]>, factor: f64) -> Box<[f64]> {
+            values.into_vec().into_iter().map(|v| v * factor).collect()
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn halveFloats(values: JArray<f32>) -> JArray<f32> {
+            JArray(values.0.iter().map(|v| v / 2.0).collect())
+        }
+
+        pub extern "jni" fn negateShorts(values: Box<[i16]>) -> Box<[i16]> {
+            values.into_vec().into_iter().map(|v| -v).collect()
+        }
+
+        pub extern "jni" fn upperChars(values: Box<[u16]>) -> JniResult<Box<[u16]>> {
+            Ok(String::from_utf16_lossy(&values).to_uppercase().encode_utf16().collect())
+        }
+
+        pub extern "jni" fn transposeInts(matrix: Box<[Box<[i32]>]>) -> Box<[Box<[i32]>]> {
+            let columns = matrix.first().map_or(0, |row| row.len());
+            (0..columns).map(|c| matrix.iter().map(|row| row[c]).collect()).collect()
+        }
+
+        pub extern "jni" fn appendName(mut names: JArray<String>, name: String) -> JArray<String> {
+            names.0.push(name);
+            names
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn appendNameUnchecked(mut names: JArray<String>, name: String) -> JArray<String> {
+            names.0.push(name);
+            names
+        }
+
+        pub extern "jni" fn appendRow(mut rows: JArray<JArray<String>>, row: JArray<String>) -> JArray<JArray<String>> {
+            rows.0.push(row);
+            rows
+        }
+
+        pub extern "jni" fn nameGroupSizes(groups: Box<[Names]>) -> Vec<i32> {
+            groups.into_vec().into_iter().map(|g| g.0.len() as i32).collect()
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn nextUserId(id: UserId) -> UserId {
+            UserId(id.0 + 1)
+        }
+
+        pub extern "jni" fn nestedListSums(v: Vec<Vec<i32>>) -> Vec<i32> {
+            v.iter().map(|r| r.iter().sum()).collect()
+        }
+
+        pub extern "jni" fn nextColor(color: Color) -> Color {
+            match color {
+                Color::Red => Color::Green,
+                Color::Green => Color::DarkBlue,
+                Color::DarkBlue => Color::Red,
+            }
+        }
+
+        pub extern "jni" fn colorToString(color: Color) -> String {
+            format!("{:?}", color)
+        }
+
+        pub extern "jni" fn reverseColors(colors: Box<[Color]>) -> Box<[Color]> {
+            colors.into_vec().into_iter().rev().collect()
+        }
+
+        pub extern "jni" fn translatePoint(point: Point, dx: i32, dy: i32) -> Point {
+            Point {
+                x: point.x + dx,
+                y: point.y + dy,
+                label: format!("{} moved", point.label),
+            }
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn pointLabels(points: Box<[Point]>) -> Box<[String]> {
+            points.into_vec().into_iter().map(|p| format!("{} ({}, {})", p.label, p.x, p.y)).collect()
+        }
+
+        #[convert(with = "IntIp")]
+        pub extern "jni" fn parseIp(#[convert(with = "DottedIp")] ip: Ipv4Addr) -> Ipv4Addr {
+            ip
+        }
+
+        #[call_type(unchecked)]
+        #[convert(with = "DottedIp")]
+        pub extern "jni" fn nextIp(#[convert(with = "IntIp")] ip: Ipv4Addr) -> Ipv4Addr {
+            <Ipv4Addr as From<u32>>::from(<u32 as From<Ipv4Addr>>::from(ip) + 1)
+        }
+
+        #[convert(with = "DottedIp")]
+        pub extern "jni" fn gatewayOf(#[convert(with = "DottedIp")] ip: Ipv4Addr) -> JniResult<Ipv4Addr> {
+            let [a, b, c, _] = ip.octets();
+            Ok(Ipv4Addr::new(a, b, c, 1))
+        }
+
+        pub extern "jni" fn describeIp(env: &JNIEnv, #[convert(with = "DottedIp")] ip: Ipv4Addr) -> JniResult<String> {
+            User::formatIp(env, ip)
+        }
+
+        pub extern "jni" fn defaultIpIsPrivate(env: &JNIEnv) -> JniResult<bool> {
+            Ok(User::defaultIp(env)?.is_private())
+        }
+
+        pub extern "jni" fn describeDimensions(dimensions: Dimensions) -> String {
+            format!("{}x{} {}", dimensions.width, dimensions.height, dimensions.unit)
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn dimensionsArea(dimensions: Dimensions) -> i32 {
+            dimensions.width * dimensions.height
+        }
+
+        #[call_type(safe(cause_class = "java.lang.IllegalStateException"))]
+        pub extern "jni" fn failWithCause() -> JniResult<()> {
+            Err(Error::JniCall(JniError::NoMemory))
+        }
+
+        pub extern "jni" fn configuredPort(port: String) -> Result<i32, crate::config::ConfigError> {
+            crate::config::parse_port(&port).map(Into::into)
+        }
+
+        pub extern "jni" fn allShapeKinds(env: &'borrow JNIEnv<'env>) -> JniResult<Box<[String]>> {
+            Shape::all(env)?.iter().map(|shape| shape.kind(env)).collect()
+        }
+
+        pub extern "jni" fn firstShape(shapes: Box<[Shape<'env, 'borrow>]>) -> Shape<'env, 'borrow> {
+            shapes.into_vec().into_iter().next().unwrap()
+        }
+
+        pub extern "jni" fn optionalLength(s: JOptional<String>) -> JOptional<i32> {
+            JOptional(s.0.map(|s| s.len() as i32))
+        }
+
+        pub extern "jni" fn plusOneSecond(t: SystemTime) -> SystemTime {
+            t + Duration::from_secs(1)
+        }
+
+        pub extern "jni" fn doubleDuration(d: Duration) -> Duration {
+            d * 2
+        }
+
+        #[overload]
+        #[java_name("overloaded")]
+        pub extern "jni" fn overloadedInt(value: i32) -> String {
+            format!("int {}", value)
+        }
+
+        #[overload]
+        #[java_name("overloaded")]
+        pub extern "jni" fn overloadedStrings(first: String, rest: Box<[String]>) -> String {
+            format!("strings {} {}", first, rest.into_vec().join(" "))
+        }
+
+        #[overload("(Ljava/util/ArrayList;)")]
+        #[java_name("overloaded")]
+        pub extern "jni" fn overloadedList(values: Vec<i32>) -> String {
+            format!("list {:?}", values)
+        }
+
+        pub extern "jni" fn negateWide(x: i128) -> i128 {
+            x.wrapping_neg()
+        }
+
+        pub extern "jni" fn maxUnsignedWide() -> u128 {
+            u128::MAX
+        }
+
+        pub extern "jni" fn halveUnsignedWide(x: u128) -> JniResult<u128> {
+            Ok(x / 2)
+        }
+
+        pub extern "jni" fn lineTotal(price: Decimal, quantity: i32) -> JniResult<Decimal> {
+            Ok(price * Decimal::new(quantity as i64, 0))
+        }
+
+        pub extern "jni" fn oneTenth() -> Decimal {
+            Decimal::new(1, 1)
+        }
+
+        pub extern "jni" fn lengthOnAttachedThread(s: String) -> JniResult<i32> {
+            std::thread::spawn(move || {
+                robusta_jni::attach::with_env(|env| {
+                    let js = env.new_string(&s)?;
+                    env.call_method(js, "length", "()I", &[])?.i()
+                })
+            })
+            .join()
+            .unwrap()
+        }
+
+        pub extern "jni" fn reversedBytes(data: Bytes) -> Bytes {
+            data.iter().rev().copied().collect()
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn bytesLength(data: Bytes) -> i32 {
+            data.len() as i32
+        }
+
+        pub extern "jni" fn concatBytes(parts: Vec<Vec<u8>>) -> Vec<u8> {
+            parts.concat()
+        }
+
+        pub extern "jni" fn doubledOrder(order: Json<Order>) -> JniResult<Json<Order>> {
+            let Json(mut order) = order;
+            order.items.extend(order.items.clone());
+            order.quantity *= 2;
+            Ok(Json(order))
+        }
+
+        pub extern "jni" fn orderToCbor(order: Json<Order>) -> JniResult<Cbor<Order>> {
+            Ok(Cbor(order.into_inner()))
+        }
+
+        pub extern "jni" fn orderFromCbor(order: Cbor<Order>) -> JniResult<Json<Order>> {
+            Ok(Json(order.into_inner()))
+        }
+
+        pub extern "jni" fn fileName(file: PathBuf) -> String {
+            file.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default()
+        }
+
+        pub extern "jni" fn siblingPath(path: JPath, name: String) -> JPath {
+            JPath(path.0.with_file_name(name))
+        }
+
+        pub extern "jni" fn tempFile() -> &'static Path {
+            Path::new("/tmp/robusta.tmp")
+        }
+
+        #[throws("java.io.IOException")]
+        pub extern "jni" fn readConfig(path: String) -> JniResult<String> {
+            if path.is_empty() {
+                return Err(Error::WrongJValueType("path", "empty string"));
+            }
+            Ok(format!("config of {}", path))
+        }
+
+        pub extern "jni" fn isLoopback(address: IpAddr) -> bool {
+            address.is_loopback()
+        }
+
+        pub extern "jni" fn nextPort(address: SocketAddr) -> JniResult<SocketAddr> {
+            let port = address.port().checked_add(1).ok_or(Error::WrongJValueType("port", "u16"))?;
+            Ok(SocketAddr::new(address.ip(), port))
+        }
+
+        pub extern "jni" fn localhost(v6: bool) -> IpAddr {
+            if v6 {
+                IpAddr::V6(Ipv6Addr::LOCALHOST)
+            } else {
+                IpAddr::V4(Ipv4Addr::LOCALHOST)
+            }
+        }
+
+        pub extern "jni" fn minMax(values: Vec<i32>) -> JniResult<(i32, i32)> {
+            let min = values.iter().copied().min().ok_or(Error::WrongJValueType("values", "empty list"))?;
+            let max = values.iter().copied().max().ok_or(Error::WrongJValueType("values", "empty list"))?;
+            Ok((min, max))
+        }
+
+        pub extern "jni" fn swapPair(pair: (i32, String)) -> (String, i32) {
+            (pair.1, pair.0)
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn rotateTriple(triple: (String, i64, bool)) -> (i64, bool, String) {
+            (triple.1, triple.2, triple.0)
+        }
+
+        pub extern "jni" fn applyTwice(f: impl Fn(i32) -> i32, x: i32) -> i32 {
+            f(f(x))
+        }
+
+        pub extern "jni" fn mapBoth(f: impl Fn(String) -> String, a: String, b: String) -> String {
+            format!("{}{}", f(a), f(b))
+        }
+
+        pub extern "jni" fn supplyOnThread(f: impl Fn() -> i32 + Send + 'static) -> i32 {
+            std::thread::spawn(f).join().unwrap()
+        }
+
+        pub extern "jni" fn adder(n: i32) -> RustFunction<IntUnaryOperator> {
+            RustFunction::new(move |x: i32| x + n)
+        }
+
+        pub extern "jni" fn prefixer(prefix: String) -> RustFunction<Function> {
+            RustFunction::new(move |s: String| format!("{}{}", prefix, s))
+        }
+
+        pub extern "jni" fn totalLength(words: JIterator<'env, String>) -> JniResult<i64> {
+            words.map(|w| w.map(|w| w.len() as i64)).sum()
+        }
+
+        pub extern "jni" fn range(start: i32, end: i32) -> RustIterator {
+            RustIterator::new(start..end)
+        }
+
+        pub extern "jni" fn lengthOnThread(_env: &JNIEnv, vm: &'static Vm, s: String) -> JniResult<i32> {
+            assert!(std::ptr::eq(vm, Vm::get().unwrap()));
+            std::thread::spawn(move || {
+                vm.with_env(|env| {
+                    let js = env.new_string(&s)?;
+                    env.call_method(js, "length", "()I", &[])?.i()
+                })?
+            })
+            .join()
+            .unwrap()
+        }
+
+        pub extern "jni" fn invertBytes(#[critical] bytes: &mut [u8]) {
+            for b in bytes.iter_mut() {
+                *b = !*b;
+            }
+        }
+
+        pub extern "jni" fn greetOrDefault(name: Option<String>) -> String {
+            format!("Hello, {}", name.as_deref().unwrap_or("stranger"))
+        }
+
+        pub extern "jni" fn nonEmpty(s: String) -> Option<String> {
+            Some(s).filter(|s| !s.is_empty())
+        }
+
+        pub extern "jni" fn nullTerminatedBooleans(values: Option<Box<[bool]>>) -> Option<Vec<Option<bool>>> {
+            values.map(|v| v.iter().copied().map(Some).chain([None]).collect())
+        }
+
+        pub extern "jni" fn compactBooleans(values: Option<Vec<Option<bool>>>) -> Option<Box<[bool]>> {
+            values.map(|v| v.into_iter().flatten().collect())
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn nullTerminatedBytes(values: Option<Box<[i8]>>) -> Option<Vec<Option<i8>>> {
+            values.map(|v| v.iter().copied().map(Some).chain([None]).collect())
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn compactBytes(values: Option<Vec<Option<i8>>>) -> Option<Box<[i8]>> {
+            values.map(|v| v.into_iter().flatten().collect())
+        }
+
+        pub extern "jni" fn nullTerminatedChars(values: Option<Box<[u16]>>) -> Option<Vec<Option<u16>>> {
+            values.map(|v| v.iter().copied().map(Some).chain([None]).collect())
+        }
+
+        pub extern "jni" fn compactChars(values: Option<Vec<Option<u16>>>) -> Option<Box<[u16]>> {
+            values.map(|v| v.into_iter().flatten().collect())
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn nullTerminatedShorts(values: Option<Box<[i16]>>) -> Option<Vec<Option<i16>>> {
+            values.map(|v| v.iter().copied().map(Some).chain([None]).collect())
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn compactShorts(values: Option<Vec<Option<i16>>>) -> Option<Box<[i16]>> {
+            values.map(|v| v.into_iter().flatten().collect())
+        }
+
+        pub extern "jni" fn nullTerminatedInts(values: Option<Box<[i32]>>) -> Option<Vec<Option<i32>>> {
+            values.map(|v| v.iter().copied().map(Some).chain([None]).collect())
+        }
+
+        pub extern "jni" fn compactInts(values: Option<Vec<Option<i32>>>) -> Option<Box<[i32]>> {
+            values.map(|v| v.into_iter().flatten().collect())
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn nullTerminatedLongs(values: Option<Box<[i64]>>) -> Option<Vec<Option<i64>>> {
+            values.map(|v| v.iter().copied().map(Some).chain([None]).collect())
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn compactLongs(values: Option<Vec<Option<i64>>>) -> Option<Box<[i64]>> {
+            values.map(|v| v.into_iter().flatten().collect())
+        }
+
+        pub extern "jni" fn nullTerminatedFloats(values: Option<Box<[f32]>>) -> Option<Vec<Option<f32>>> {
+            values.map(|v| v.iter().copied().map(Some).chain([None]).collect())
+        }
+
+        pub extern "jni" fn compactFloats(values: Option<Vec<Option<f32>>>) -> Option<Box<[f32]>> {
+            values.map(|v| v.into_iter().flatten().collect())
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn nullTerminatedDoubles(values: Option<Box<[f64]>>) -> Option<Vec<Option<f64>>> {
+            values.map(|v| v.iter().copied().map(Some).chain([None]).collect())
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn compactDoubles(values: Option<Vec<Option<f64>>>) -> Option<Box<[f64]>> {
+            values.map(|v| v.into_iter().flatten().collect())
+        }
+
+        pub extern "jni" fn blankToNull(names: Box<[Option<String>]>) -> Box<[Option<String>]> {
+            names.into_vec().into_iter().map(|n| n.filter(|n| !n.trim().is_empty())).collect()
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn countNulls(names: Box<[Box<[Option<String>]>]>) -> i32 {
+            names.into_vec().into_iter().flat_map(|row| row.into_vec()).filter(Option::is_none).count() as i32
+        }
+
+        #[call_type(unchecked)]
+        #[null_check]
+        pub extern "jni" fn checkedUpper(s: String) -> String {
+            s.to_uppercase()
+        }
+
+        #[null_check(off)]
+        pub extern "jni" fn uncheckedLength(s: String) -> i32 {
+            s.len() as i32
+        }
+
+        pub extern "jni" fn weightedSum(label: String, #[critical] values: &[i32], #[critical] weights: &[f64]) -> String {
+            let sum: f64 = values.iter().zip(weights).map(|(&v, w)| v as f64 * w).sum();
+            format!("{}: {}", label, sum)
+        }
+
+        #[local_frame(capacity = 16)]
+        pub extern "jni" fn formatNamesInFrame(env: &JNIEnv, names: Vec<String>) -> JniResult<Vec<String>> {
+            names.into_iter().map(|name| User::formatNameInFrame(env, name)).collect()
+        }
+
+        #[local_frame]
+        pub extern "jni" fn panicInFrame(names: Vec<String>) -> String {
+            panic!("{} names", names.len())
+        }
+
+        pub extern "jni" fn cumulativeSums(mut values: PackedList<i64>) -> PackedList<i64> {
+            for i in 1..values.len() {
+                values[i] += values[i - 1];
+            }
+            values
+        }
+
+        pub extern "jni" fn scaled(values: PackedList<f64>, factor: f64) -> PackedList<f64> {
+            PackedList(values.iter().map(|v| v * factor).collect())
+        }
+
+        pub extern "jni" fn invertBits(bits: JBitSet, len: i32) -> JBitSet {
+            let mut bits = bits.into_inner().into_vec();
+            bits.resize(len as usize, false);
+            JBitSet(bits.iter().map(|b| !b).collect())
+        }
+
+        pub extern "jni" fn staticGreeting() -> &'static str {
+            "Hello from Rust"
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn staticGreetingUnchecked() -> &'static str {
+            "Hello from Rust"
+        }
+
+        pub extern "jni" fn greetingOrName(name: String) -> JniResult<Cow<'static, str>> {
+            Ok(if name.is_empty() { Cow::Borrowed("Hello from Rust") } else { Cow::Owned(format!("Hello, {}", name)) })
+        }
+
+        pub extern "jni" fn magicBytes() -> &'static [u8] {
+            b"\x7fELF"
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn magicBytesUnchecked() -> Cow<'static, [u8]> {
+            Cow::Borrowed(b"\x7fELF")
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn optionalLengthUnchecked(s: JOptional<String>) -> JOptional<i32> {
+            JOptional(s.0.map(|s| s.len() as i32))
+        }
+
+        pub extern "java" fn getPassword(
+            &self,
+            env: &JNIEnv,
+        ) -> ::robusta_jni::jni::errors::Result<String> {
+        }
+
+        pub extern "java" fn getTotalUsersCount(
+            env: &JNIEnv,
+        ) -> ::robusta_jni::jni::errors::Result<i32> {
+        }
+
+        pub extern "java" fn multipleParameters(
+            &self,
+            env: &JNIEnv,
+            v: i32,
+            s: String,
+        ) -> ::robusta_jni::jni::errors::Result<String> {
+        }
+
+        #[java_name("display_name")]
+        pub extern "java" fn name(&self, env: &JNIEnv) -> JniResult<String> {}
+
+        pub extern "java" fn formatIp(env: &JNIEnv, #[convert(with = "IntIp")] ip: Ipv4Addr) -> JniResult<String> {}
+
+        #[convert(with = "DottedIp")]
+        pub extern "java" fn defaultIp(env: &JNIEnv) -> JniResult<Ipv4Addr> {}
+
+        // `User.java` only declares the signatures without the trailing parameters
+        #[fallback_signature("(Ljava/lang/String;)Ljava/lang/String;")]
+        pub extern "java" fn formatName(env: &JNIEnv, name: String, uppercase: bool) -> JniResult<String> {}
+
+        #[java_name("formatName")]
+        pub extern "java" fn formatSlice(env: &JNIEnv, name: &str) -> JniResult<String> {}
+
+        #[java_name("formatName")]
+        #[local_frame]
+        pub extern "java" fn formatNameInFrame(env: &JNIEnv, name: String) -> JniResult<String> {}
+
+        #[java_name("formatName")]
+        #[call_type(unchecked)]
+        pub extern "java" fn formatBorrowedString(env: &JNIEnv, name: &String) -> String {}
+
+        #[java_name("multipleParameters")]
+        #[fallback_signature("(ILjava/lang/String;)Ljava/lang/String;")]
+        pub extern "java" fn multipleParametersWithFlag(
+            &self,
+            env: &JNIEnv,
+            v: i32,
+            s: String,
+            flag: bool,
+        ) -> JniResult<String> {
+        }
+
+        #[class("java.lang.Integer")]
+        pub extern "java" fn toHexString(env: &JNIEnv, value: i32) -> JniResult<String> {}
+
+        #[class(java.lang.Math)]
+        #[call_type(unchecked)]
+        pub extern "java" fn abs(env: &JNIEnv, value: i64) -> i64 {}
+
+        #[companion]
+        pub extern "java" fn default_username(env: &JNIEnv) -> JniResult<String> {}
+
+        #[constructor(builder)]
+        pub extern "java" fn new(
+            env: &'borrow JNIEnv<'env>,
+            username: String,
+            password: String,
+        ) -> JniResult<Self> {
+        }
+
+        #[constructor(builder)]
+        pub extern "java" fn with_username(env: &'borrow JNIEnv<'env>, username: String) -> JniResult<Self> {}
+
+        #[constructor(signature = "(Ljava/lang/String;)V")]
+        pub extern "java" fn named(env: &'borrow JNIEnv<'env>, username: &str) -> JniResult<Self> {}
+    }
+
+    #[derive(Signature, TryIntoJavaValue, IntoJavaValue, TryFromJavaValue, FromJavaValue)]
+    #[package()]
+    pub struct Shape<'env: 'borrow, 'borrow> {
+        #[instance]
+        raw: AutoLocal<'env, 'borrow>,
+    }
+
+    impl<'env: 'borrow, 'borrow> Shape<'env, 'borrow> {
+        pub extern "java" fn kind(&self, env: &JNIEnv) -> JniResult<String> {}
+
+        pub extern "java" fn all(env: &'borrow JNIEnv<'env>) -> JniResult<Box<[Self]>> {}
+
+        #[call(nonvirtual)]
+        #[java_name("kind")]
+        pub extern "java" fn shapeKind(&self, env: &JNIEnv) -> JniResult<String> {}
+
+        #[call(nonvirtual)]
+        #[call_type(unchecked)]
+        #[java_name("kind")]
+        pub extern "java" fn shapeKindUnchecked(&self, env: &JNIEnv) -> String {}
+    }
+
+    #[derive(JavaClass)]
+    #[package()]
+    pub struct Named<'env: 'borrow, 'borrow> {
+        #[instance]
+        raw: AutoLocal<'env, 'borrow>,
+    }
+
+    impl<'env: 'borrow, 'borrow> Named<'env, 'borrow> {
+        #[call(interface)]
+        pub extern "java" fn greeting(&self, env: &JNIEnv) -> JniResult<String> {}
+    }
+
+    #[derive(Signature, TryIntoJavaValue, IntoJavaValue, TryFromJavaValue, FromJavaValue)]
+    #[package()]
+    pub struct Fetcher<'env: 'borrow, 'borrow> {
+        #[instance]
+        raw: AutoLocal<'env, 'borrow>,
+    }
+
+    impl<'env: 'borrow, 'borrow> Fetcher<'env, 'borrow> {
+        #[kotlin_suspend]
+        pub extern "java" fn length(env: &JNIEnv, s: String) -> JniResult<i32> {}
+
+        #[kotlin_suspend]
+        pub extern "java" fn greeting(env: &JNIEnv, name: String) -> JniResult<String> {}
+
+        #[kotlin_suspend]
+        #[call_type(unchecked)]
+        pub extern "java" fn hasEmptyContext(env: &JNIEnv) -> bool {}
+
+        #[kotlin_suspend]
+        pub extern "java" fn fail(env: &JNIEnv, message: String) -> JniResult<()> {}
+
+        #[kotlin_suspend]
+        pub extern "java" fn fetch(&self, env: &JNIEnv, id: i32) -> JniResult<String> {}
+    }
+
+    #[derive(Signature, TryIntoJavaValue, IntoJavaValue, TryFromJavaValue, FromJavaValue)]
+    #[package()]
+    pub struct Holder<'env: 'borrow, 'borrow, T: Signature> {
+        #[instance]
+        raw: AutoLocal<'env, 'borrow>,
+        label: String,
+        value: T,
+    }
+
+    #[derive(Signature, TryIntoJavaValue, IntoJavaValue, TryFromJavaValue, FromJavaValue)]
+    #[package()]
+    pub struct Account<'env: 'borrow, 'borrow> {
+        #[instance]
+        raw: AutoLocal<'env, 'borrow>,
+        #[field(getter = "getOwner")]
+        owner: String,
+        #[writeback]
+        #[field(getter = "getNote", setter = "setNote")]
+        note: String,
+        #[field(getter = "getBalance", setter = "setBalance")]
+        balance: Field<'env, 'borrow, i64>,
+        #[field]
+        history: Field<'env, 'borrow, Vec<String>>,
+        #[field]
+        checkpoints: Field<'env, 'borrow, Box<[Point]>>,
+    }
+
+    impl<'env: 'borrow, 'borrow> Account<'env, 'borrow> {
+        pub extern "jni" fn deposit(&mut self, amount: i64) -> JniResult<String> {
+            let balance = self.balance.get()? + amount;
+            self.balance.set(balance)?;
+            self.note = format!("deposited {}", amount);
+
+            let mut history = self.history.get()?;
+            history.push(format!("+{}", amount));
+            self.history.set(history)?;
+
+            let mut checkpoints = self.checkpoints.get()?.into_vec();
+            checkpoints.push(Point {
+                x: balance as i32,
+                y: amount as i32,
+                label: self.owner.clone(),
+            });
+            self.checkpoints.set(checkpoints.into_boxed_slice())?;
+
+            Ok(self.owner.clone())
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn balanceUnchecked(self) -> i64 {
+            self.balance.get_unchecked()
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn clearHistoryUnchecked(mut self) -> Vec<String> {
+            let history = self.history.get_unchecked();
+            self.history.set_unchecked(Vec::new());
+            history
+        }
+    }
+
+    #[derive(Signature, TryIntoJavaValue, IntoJavaValue, TryFromJavaValue, FromJavaValue)]
+    #[package()]
+    pub struct Packet<'env: 'borrow, 'borrow> {
+        #[instance]
+        raw: AutoLocal<'env, 'borrow>,
+        header: Box<[u8]>,
+        #[field]
+        payload: Field<'env, 'borrow, Box<[u8]>>,
+        trailer: Vec<u8>,
+        #[field]
+        signature: Field<'env, 'borrow, Vec<u8>>,
+    }
+
+    impl<'env: 'borrow, 'borrow> Packet<'env, 'borrow> {
+        pub extern "jni" fn seal(mut self) -> JniResult<i32> {
+            let mut payload = self.header.to_vec();
+            payload.extend_from_slice(&self.payload.get()?);
+            let len = payload.len() as i32;
+            self.payload.set(payload.into_boxed_slice())?;
+            Ok(len)
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn checksumUnchecked(self) -> i32 {
+            self.payload.get_unchecked().iter().map(|&b| b as i32).sum()
+        }
+
+        pub extern "jni" fn sign(mut self) -> JniResult<i32> {
+            let mut signature = self.signature.get()?;
+            signature.extend(self.trailer.iter().rev());
+            let len = signature.len() as i32;
+            self.signature.set(signature)?;
+            Ok(len)
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn signatureSumUnchecked(self) -> i32 {
+            self.signature.get_unchecked().iter().map(|&b| b as i32).sum()
+        }
+    }
+
+    #[derive(Signature, TryIntoJavaValue, IntoJavaValue, TryFromJavaValue, FromJavaValue)]
+    #[package()]
+    #[record]
+    pub struct Point {
+        x: i32,
+        y: i32,
+        #[java_name("displayLabel")]
+        label: String,
+    }
+
+    #[derive(Signature, TryFromJavaValue, FromJavaValue)]
+    #[package()]
+    pub struct Dimensions {
+        width: i32,
+        height: i32,
+        #[field(getter = "getUnit")]
+        unit: String,
+    }
+
+    #[derive(JavaClass, Debug)]
+    #[package()]
+    pub enum Color {
+        #[java_name("RED")]
+        Red,
+        #[java_name("GREEN")]
+        Green,
+        #[java_name("DARK_BLUE")]
+        DarkBlue,
+    }
+
+    #[derive(Signature)]
+    #[package()]
+    #[call_type(unchecked)]
+    pub struct UncheckedDefaults;
+
+    impl UncheckedDefaults {
+        pub extern "jni" fn join(v: Vec<String>) -> String {
+            v.join(",")
+        }
+
+        pub extern "jni" fn negate(v: bool) -> bool {
+            !v
+        }
+
+        #[call_type(safe(exception_class = "java.lang.IllegalArgumentException"))]
+        pub extern "jni" fn parse(s: String) -> JniResult<i32> {
+            s.parse()
+                .map_err(|_| robusta_jni::jni::errors::Error::WrongJValueType("i32", "String"))
+        }
+    }
+
+    #[derive(Signature)]
+    #[package()]
+    #[kotlin_object]
+    pub struct Greeter;
+
+    impl Greeter {
+        pub extern "java" fn greet(env: &JNIEnv, name: String) -> JniResult<String> {}
+    }
+}
+
+#[bridge]
+#[call_type(unchecked)]
+pub mod module_defaults {
+    use robusta_jni::convert::Signature;
+    use robusta_jni::jni::errors::Result as JniResult;
+
+    #[derive(Signature)]
+    #[package()]
+    pub struct ModuleDefaults;
+
+    impl ModuleDefaults {
+        pub extern "jni" fn twice(v: i32) -> i32 {
+            v * 2
+        }
+
+        pub extern "jni" fn shout(s: String) -> String {
+            s.to_uppercase()
+        }
+
+        #[call_type(safe(exception_class = "java.lang.IllegalArgumentException"))]
+        pub extern "jni" fn parse(s: String) -> JniResult<i32> {
+            s.parse()
+                .map_err(|_| robusta_jni::jni::errors::Error::WrongJValueType("i32", "String"))
+        }
+    }
+}
+
+#[bridge(register_natives, helper_classes, symbol_manifest, on_unload)]
+pub mod registered {
+    use std::sync::Mutex;
+
+    use robusta_jni::convert::{RustIterator, Signature};
+    use robusta_jni::jni::errors::Result as JniResult;
+    use robusta_jni::jni::objects::JClass;
+    use robusta_jni::jni::JNIEnv;
+    use robusta_jni::jni::JNIEnv as Env;
+
+    static UNLOADED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    #[derive(Signature)]
+    #[package()]
+    pub struct Registered;
+
+    impl Registered {
+        pub extern "jni" fn add(a: i32, b: i32) -> i32 {
+            a + b
+        }
+
+        pub extern "jni" fn greet(_env: &JNIEnv, name: String) -> JniResult<String> {
+            Ok(format!("Hello, {}", name))
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn negate(v: bool) -> bool {
+            !v
+        }
+
+        pub extern "jni" fn noop() {}
+
+        #[java_name("subtract")]
+        pub extern "jni" fn minus(a: i32, b: i32) -> i32 {
+            a - b
+        }
+
+        pub extern "jni" fn aliasedEnv(env: &Env, s: String) -> JniResult<i32> {
+            let js = env.new_string(&s)?;
+            Ok(String::from(env.get_string(js)?).len() as i32)
+        }
+
+        pub extern "jni" fn mutEnv(env: &mut JNIEnv, s: String) -> JniResult<String> {
+            let js = env.new_string(s.to_uppercase())?;
+            env.get_string(js).map(Into::into)
+        }
+
+        pub extern "jni" fn isRegisteredClass(env: &JNIEnv, class: JClass) -> JniResult<bool> {
+            env.is_same_object(class, env.find_class("Registered")?)
+        }
+
+        pub extern "jni" fn addUnloadHook(name: String) {
+            robusta_jni::unload::on_unload(move |_| UNLOADED.lock().unwrap().push(name));
+        }
+
+        pub extern "jni" fn runUnloadHooks(env: &JNIEnv) -> JniResult<String> {
+            robusta_jni::unload::run_hooks(&env.get_java_vm()?);
+            Ok(UNLOADED.lock().unwrap().drain(..).collect::<Vec<_>>().join(","))
+        }
+
+        pub extern "jni" fn countdown(n: i32) -> RustIterator {
+            RustIterator::new((0..n).rev())
+        }
+    }
+}
+
+#[bridge(java_name_format = "native{PascalCase}")]
+pub mod prefixed {
+    use robusta_jni::convert::Signature;
+    use robusta_jni::jni::errors::Result as JniResult;
+    use robusta_jni::jni::JNIEnv;
+
+    #[derive(Signature)]
+    #[package()]
+    pub struct Prefixed;
+
+    impl Prefixed {
+        pub extern "jni" fn add(a: i32, b: i32) -> i32 {
+            a + b
+        }
+
+        pub extern "jni" fn describe_twice(env: &JNIEnv, value: i32) -> JniResult<String> {
+            let description = Prefixed::describe(env, value)?;
+            Ok(format!("{} {}", description, description))
+        }
+
+        #[java_name("checksum")]
+        pub extern "jni" fn compute_checksum(s: String) -> i32 {
+            s.bytes().map(i32::from).sum()
+        }
+
+        pub extern "java" fn describe(env: &JNIEnv, value: i32) -> JniResult<String> {}
+    }
+}
+
+/// Plain function on the data of a `User`, free of JNI types and lifetimes
+fn shout(mut user: jni::UserData) -> jni::UserData {
+    user.username = user.username.to_uppercase();
+    user
+}
+
+/// Plain business logic, free of JNI types and lifetimes
+pub struct TextOps;
+
+impl TextOps {
+    pub fn repeat(s: String, times: i32) -> String {
+        s.repeat(times as usize)
+    }
+
+    pub fn count_words(s: String) -> i32 {
+        s.split_whitespace().count() as i32
+    }
+}
+
+/// Converters of `Ipv4Addr`, which can't implement the conversion traits of this crate
+pub mod ip {
+    use std::net::Ipv4Addr;
+
+    use robusta_jni::convert::{ConvertFromJava, ConvertIntoJava, Converter, TryFromJavaValue, TryIntoJavaValue};
+    use robusta_jni::jni::errors::{Error, Result as JniResult};
+    use robusta_jni::jni::objects::JString;
+    use robusta_jni::jni::sys::jint;
+    use robusta_jni::jni::JNIEnv;
+
+    /// `Ipv4Addr` as a dotted string, e.g. `"127.0.0.1"`
+    pub struct DottedIp;
+
+    impl Converter for DottedIp {
+        const SIG_TYPE: &'static str = "Ljava/lang/String;";
+    }
+
+    impl<'env: 'borrow, 'borrow> ConvertFromJava<'env, 'borrow, Ipv4Addr> for DottedIp {
+        type Source = JString<'env>;
+
+        fn from_java(s: Self::Source, env: &'borrow JNIEnv<'env>) -> JniResult<Ipv4Addr> {
+            let s: String = TryFromJavaValue::try_from(s, env)?;
+            s.parse().map_err(|_| Error::WrongJValueType("Ipv4Addr", "dotted IPv4 address"))
+        }
+    }
+
+    impl<'env> ConvertIntoJava<'env, Ipv4Addr> for DottedIp {
+        type Target = JString<'env>;
+
+        fn into_java(value: Ipv4Addr, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+            TryIntoJavaValue::try_into(value.to_string(), env)
+        }
+    }
+
+    /// `Ipv4Addr` as a big-endian `int`
+    pub struct IntIp;
+
+    impl Converter for IntIp {
+        const SIG_TYPE: &'static str = "I";
+    }
+
+    impl<'env: 'borrow, 'borrow> ConvertFromJava<'env, 'borrow, Ipv4Addr> for IntIp {
+        type Source = jint;
+
+        fn from_java(s: Self::Source, _env: &'borrow JNIEnv<'env>) -> JniResult<Ipv4Addr> {
+            Ok(<Ipv4Addr as From<u32>>::from(s as u32))
+        }
+    }
+
+    impl<'env> ConvertIntoJava<'env, Ipv4Addr> for IntIp {
+        type Target = jint;
+
+        fn into_java(value: Ipv4Addr, _env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+            Ok(u32::from(value) as jint)
+        }
+    }
+}
+
+/// Errors of their own type, returned by exported methods
+pub mod config {
+    use std::error::Error;
+    use std::fmt::{self, Display, Formatter};
+    use std::num::ParseIntError;
+
+    #[derive(Debug)]
+    pub struct PortError {
+        port: String,
+        source: ParseIntError,
+    }
+
+    impl Display for PortError {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            write!(f, "invalid port `{}`", self.port)
+        }
+    }
+
+    impl Error for PortError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.source)
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct ConfigError(PortError);
+
+    impl Display for ConfigError {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            write!(f, "invalid configuration")
+        }
+    }
+
+    impl Error for ConfigError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    pub fn parse_port(port: &str) -> Result<u16, ConfigError> {
+        port.parse().map_err(|source| ConfigError(PortError { port: port.to_string(), source }))
+    }
+}
+
+/// Types declared outside of bridged modules, with their own derives
+pub mod model {
+    use robusta_jni::convert::{Signature, TryFromJavaValue, TryIntoJavaValue};
+    use robusta_jni::jni::objects::AutoLocal;
+
+    #[derive(Signature, TryIntoJavaValue, TryFromJavaValue)]
+    #[package()]
+    pub struct Tally<'env: 'borrow, 'borrow> {
+        #[instance]
+        raw: AutoLocal<'env, 'borrow>,
+        pub count: i32,
+    }
+}
+
+#[bridge]
+pub mod external {
+    use robusta_jni::jni::errors::Result as JniResult;
+    use robusta_jni::jni::JNIEnv;
+
+    #[package()]
+    impl<'env: 'borrow, 'borrow> super::model::Tally<'env, 'borrow> {
+        pub extern "jni" fn incremented(self) -> i32 {
+            self.count + 1
+        }
+
+        pub extern "jni" fn summary(&self, env: &JNIEnv) -> JniResult<String> {
+            Ok(format!("{} = {}", self.label(env)?, self.count))
+        }
+
+        pub extern "java" fn label(&self, env: &JNIEnv) -> JniResult<String> {}
+    }
+}
+
+#[bridge]
+pub mod delegated {
+    use robusta_jni::convert::Signature;
+    use robusta_jni::jni::JNIEnv;
+
+    #[derive(Signature)]
+    #[package()]
+    #[delegate_to(super::TextOps)]
+    pub struct Delegated;
+
+    impl Delegated {
+        pub extern "jni" fn repeat(s: String, times: i32) -> String {}
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn countWords(_env: &JNIEnv, s: String) -> i32 {}
+
+        pub extern "jni" fn shout(s: String) -> String {
+            s.to_uppercase()
+        }
+    }
+}
+
+#[bridge]
+pub mod bindings {
+    use robusta_jni::jni::errors::Result as JniResult;
+    use robusta_jni::jni::JNIEnv;
+
+    #[class(Bindings)]
+    pub extern "jni" fn add(a: i32, b: i32) -> i32 {
+        a + b
+    }
+
+    #[class(Bindings)]
+    pub extern "jni" fn joinAll(_env: &JNIEnv, parts: Vec<String>, separator: String) -> JniResult<String> {
+        Ok(parts.join(&separator))
+    }
+
+    #[class(Bindings)]
+    #[call_type(unchecked)]
+    pub extern "jni" fn isBlank(s: String) -> bool {
+        s.trim().is_empty()
+    }
+}
+
+#[bridge(strict)]
+pub mod sensors {
+    use std::sync::atomic::{AtomicI32, Ordering};
+    use std::sync::Mutex;
+
+    use robusta_jni::convert::{Signature, TryFromJavaValue, TryIntoJavaValue, WeakGlobal};
+    use robusta_jni::instrument::{self, CallEvent};
+    use robusta_jni::jni::errors::{Error, Result as JniResult};
+    use robusta_jni::jni::objects::AutoLocal;
+    use robusta_jni::jni::JNIEnv;
+
+    static FIRST_USES: AtomicI32 = AtomicI32::new(0);
+    static WATCHED: Mutex<Option<WeakGlobal<Sensor<'static, 'static>>>> = Mutex::new(None);
+    static TRACE: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    #[derive(Signature, TryIntoJavaValue, TryFromJavaValue)]
+    #[package()]
+    pub struct Sensor<'env: 'borrow, 'borrow> {
+        #[instance]
+        raw: AutoLocal<'env, 'borrow>,
+    }
+
+    impl<'env: 'borrow, 'borrow> Sensor<'env, 'borrow> {
+        #[on_first_use]
+        fn check_version(env: &JNIEnv) -> JniResult<()> {
+            FIRST_USES.fetch_add(1, Ordering::SeqCst);
+            // Imported methods of the struct don't call the hook again
+            if Sensor::version(env)? < 2 {
+                return Err(Error::WrongJValueType("Sensor", "unsupported sensor version"));
+            }
+            Ok(())
+        }
+
+        pub extern "java" fn version(env: &JNIEnv) -> JniResult<i32> {}
+
+        #[instrument]
+        pub extern "java" fn read(&self, env: &JNIEnv) -> JniResult<f64> {}
+
+        pub extern "jni" fn firstUseCount() -> i32 {
+            FIRST_USES.load(Ordering::SeqCst)
+        }
+
+        #[instrument]
+        pub extern "jni" fn readTwice(env: &'borrow JNIEnv<'env>, sensor: Sensor<'env, 'borrow>) -> JniResult<f64> {
+            Ok(sensor.read(env)? * 2.0)
+        }
+
+        pub extern "jni" fn watch(sensor: WeakGlobal<Sensor<'static, 'static>>) {
+            *WATCHED.lock().unwrap() = Some(sensor);
+        }
+
+        pub extern "jni" fn watchedValue(env: &JNIEnv) -> JniResult<f64> {
+            let watched = WATCHED.lock().unwrap();
+            match watched.as_ref().map(|sensor| sensor.upgrade(env)).transpose()?.flatten() {
+                Some(sensor) => sensor.read(env),
+                None => Ok(f64::NAN),
+            }
+        }
+
+        pub extern "jni" fn startTracing() {
+            TRACE.lock().unwrap().clear();
+            instrument::set_call_callback(|event: &CallEvent| {
+                let entry = match event {
+                    CallEvent::Enter(call) => format!("enter {}({})", call.method, call.arg_count),
+                    CallEvent::Exit(call, times) => {
+                        assert!(times.call <= times.total);
+                        format!("exit {}", call.method)
+                    }
+                };
+                TRACE.lock().unwrap().push(entry);
+            });
+        }
+
+        pub extern "jni" fn stopTracing() -> String {
+            instrument::clear_call_callback();
+            TRACE.lock().unwrap().join(", ")
         }
     }
 }
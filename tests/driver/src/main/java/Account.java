@@ -0,0 +1,48 @@
+import java.util.ArrayList;
+
+public class Account {
+    private final String owner;
+    private long balance;
+    private String note = "";
+    private ArrayList<String> history = new ArrayList<>();
+    private Point[] checkpoints = new Point[0];
+
+    public Account(String owner, long balance) {
+        this.owner = owner;
+        this.balance = balance;
+    }
+
+    public String getOwner() {
+        return owner;
+    }
+
+    public long getBalance() {
+        return balance;
+    }
+
+    public void setBalance(long balance) {
+        this.balance = balance;
+    }
+
+    public String getNote() {
+        return note;
+    }
+
+    public void setNote(String note) {
+        this.note = note;
+    }
+
+    public ArrayList<String> getHistory() {
+        return history;
+    }
+
+    public Point[] getCheckpoints() {
+        return checkpoints;
+    }
+
+    public native String deposit(long amount);
+
+    public native long balanceUnchecked();
+
+    public native ArrayList<String> clearHistoryUnchecked();
+}
@@ -0,0 +1,13 @@
+import java.util.ArrayList;
+
+public class Bindings {
+    static {
+        System.loadLibrary("native");
+    }
+
+    public static native int add(int a, int b);
+
+    public static native String joinAll(ArrayList<String> parts, String separator);
+
+    public static native boolean isBlank(String s);
+}
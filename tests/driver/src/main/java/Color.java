@@ -0,0 +1,7 @@
+public enum Color {
+    RED,
+    GREEN,
+    DARK_BLUE,
+    // No Rust counterpart
+    TRANSPARENT
+}
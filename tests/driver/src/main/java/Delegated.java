@@ -0,0 +1,11 @@
+public class Delegated {
+    static {
+        System.loadLibrary("native");
+    }
+
+    public static native String repeat(String s, int times);
+
+    public static native int countWords(String s);
+
+    public static native String shout(String s);
+}
@@ -0,0 +1,15 @@
+public class Dimensions {
+    private final int width;
+    private final int height;
+    private final String unit;
+
+    public Dimensions(int width, int height, String unit) {
+        this.width = width;
+        this.height = height;
+        this.unit = unit;
+    }
+
+    public String getUnit() {
+        return unit;
+    }
+}
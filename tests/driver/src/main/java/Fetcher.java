@@ -0,0 +1,53 @@
+import kotlin.Result;
+import kotlin.coroutines.Continuation;
+import kotlin.coroutines.EmptyCoroutineContext;
+import kotlin.coroutines.intrinsics.CoroutineSingletons;
+
+// Mirrors the bytecode layout of Kotlin suspend functions in a file `Fetcher.kt`
+public final class Fetcher {
+    private final String prefix;
+
+    public Fetcher(String prefix) {
+        this.prefix = prefix;
+    }
+
+    // suspend fun length(s: String): Int, completing without suspending
+    public static Object length(String s, Continuation<? super Integer> continuation) {
+        return s.length();
+    }
+
+    // suspend fun greeting(name: String): String, resumed from another thread
+    public static Object greeting(String name, Continuation<? super String> continuation) {
+        resumeLater(continuation, "Hello, " + name);
+        return CoroutineSingletons.COROUTINE_SUSPENDED;
+    }
+
+    // suspend fun hasEmptyContext(): Boolean
+    public static Object hasEmptyContext(Continuation<? super Boolean> continuation) {
+        resumeLater(continuation, continuation.getContext() == EmptyCoroutineContext.INSTANCE);
+        return CoroutineSingletons.COROUTINE_SUSPENDED;
+    }
+
+    // suspend fun fail(message: String): Unit, throwing after suspending
+    public static Object fail(String message, Continuation<? super Object> continuation) {
+        resumeLater(continuation, new Result.Failure(new IllegalStateException(message)));
+        return CoroutineSingletons.COROUTINE_SUSPENDED;
+    }
+
+    // suspend fun fetch(id: Int): String
+    public Object fetch(int id, Continuation<? super String> continuation) {
+        resumeLater(continuation, prefix + id);
+        return CoroutineSingletons.COROUTINE_SUSPENDED;
+    }
+
+    private static void resumeLater(Continuation<?> continuation, Object result) {
+        new Thread(() -> {
+            try {
+                Thread.sleep(10);
+            } catch (InterruptedException e) {
+                Thread.currentThread().interrupt();
+            }
+            continuation.resumeWith(result);
+        }).start();
+    }
+}
@@ -0,0 +1,11 @@
+// Mirrors the bytecode layout of a Kotlin `object Greeter`
+public final class Greeter {
+    public static final Greeter INSTANCE = new Greeter();
+
+    private Greeter() {
+    }
+
+    public String greet(String name) {
+        return "Hello, " + name;
+    }
+}
@@ -0,0 +1,9 @@
+public class Holder<T> {
+    public final String label;
+    public final T value;
+
+    public Holder(String label, T value) {
+        this.label = label;
+        this.value = value;
+    }
+}
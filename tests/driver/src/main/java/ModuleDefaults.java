@@ -0,0 +1,11 @@
+public class ModuleDefaults {
+    static {
+        System.loadLibrary("native");
+    }
+
+    public static native int twice(int v);
+
+    public static native String shout(String s);
+
+    public static native int parse(String s);
+}
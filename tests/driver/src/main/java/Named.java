@@ -0,0 +1,7 @@
+public interface Named {
+    String name();
+
+    default String greeting() {
+        return "Hello, " + name();
+    }
+}
@@ -0,0 +1,29 @@
+public class Packet {
+    private final byte[] header;
+    private byte[] payload;
+    private final byte[] trailer;
+    private byte[] signature;
+
+    public Packet(byte[] header, byte[] payload, byte[] trailer, byte[] signature) {
+        this.header = header;
+        this.payload = payload;
+        this.trailer = trailer;
+        this.signature = signature;
+    }
+
+    public byte[] getPayload() {
+        return payload;
+    }
+
+    public byte[] getSignature() {
+        return signature;
+    }
+
+    public native int seal();
+
+    public native int checksumUnchecked();
+
+    public native int sign();
+
+    public native int signatureSumUnchecked();
+}
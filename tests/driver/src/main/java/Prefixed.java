@@ -0,0 +1,15 @@
+public class Prefixed {
+    static {
+        System.loadLibrary("native");
+    }
+
+    public static native int nativeAdd(int a, int b);
+
+    public static native String nativeDescribeTwice(int value);
+
+    public static native int checksum(String s);
+
+    public static String describe(int value) {
+        return "#" + value;
+    }
+}
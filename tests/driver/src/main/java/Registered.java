@@ -0,0 +1,29 @@
+import java.util.Iterator;
+
+public class Registered {
+    static {
+        System.loadLibrary("native");
+    }
+
+    public static native int add(int a, int b);
+
+    public static native String greet(String name);
+
+    public static native boolean negate(boolean v);
+
+    public static native void noop();
+
+    public static native int subtract(int a, int b);
+
+    public static native int aliasedEnv(String s);
+
+    public static native String mutEnv(String s);
+
+    public static native boolean isRegisteredClass();
+
+    public static native void addUnloadHook(String name);
+
+    public static native String runUnloadHooks();
+
+    public static native Iterator<Integer> countdown(int n);
+}
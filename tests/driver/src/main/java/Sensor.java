@@ -0,0 +1,31 @@
+public class Sensor {
+    static {
+        System.loadLibrary("native");
+    }
+
+    private final double value;
+
+    public Sensor(double value) {
+        this.value = value;
+    }
+
+    public static int version() {
+        return 2;
+    }
+
+    public double read() {
+        return value;
+    }
+
+    public static native int firstUseCount();
+
+    public static native double readTwice(Sensor sensor);
+
+    public static native void watch(Sensor sensor);
+
+    public static native double watchedValue();
+
+    public static native void startTracing();
+
+    public static native String stopTracing();
+}
@@ -0,0 +1,14 @@
+public class Shape implements Named {
+    public static Shape[] all() {
+        return new Shape[] { new Shape(), new Square() };
+    }
+
+    public String kind() {
+        return "shape";
+    }
+
+    @Override
+    public String name() {
+        return "shape";
+    }
+}
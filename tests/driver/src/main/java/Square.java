@@ -0,0 +1,11 @@
+public class Square extends Shape {
+    @Override
+    public String kind() {
+        return "square";
+    }
+
+    @Override
+    public String name() {
+        return "square";
+    }
+}
@@ -0,0 +1,19 @@
+public class Tally {
+    static {
+        System.loadLibrary("native");
+    }
+
+    public int count;
+
+    public Tally(int count) {
+        this.count = count;
+    }
+
+    public String label() {
+        return "tally";
+    }
+
+    public native int incremented();
+
+    public native String summary();
+}
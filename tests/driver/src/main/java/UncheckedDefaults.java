@@ -0,0 +1,13 @@
+import java.util.ArrayList;
+
+public class UncheckedDefaults {
+    static {
+        System.loadLibrary("native");
+    }
+
+    public static native String join(ArrayList<String> v);
+
+    public static native boolean negate(boolean v);
+
+    public static native int parse(String s);
+}
@@ -1,4 +1,22 @@
+import java.io.File;
+import java.io.IOException;
+import java.math.BigDecimal;
+import java.math.BigInteger;
+import java.net.InetAddress;
+import java.net.InetSocketAddress;
+import java.nio.ByteBuffer;
+import java.nio.file.Path;
+import java.time.Duration;
+import java.time.Instant;
+import java.util.ArrayList;
+import java.util.BitSet;
+import java.util.Iterator;
 import java.util.List;
+import java.util.Locale;
+import java.util.Optional;
+import java.util.function.Function;
+import java.util.function.IntUnaryOperator;
+import java.util.function.Supplier;
 
 public class User {
     static {
@@ -8,8 +26,21 @@ public class User {
 
     private static int TOTAL_USERS_COUNT = 0;
 
+    // Mirrors the bytecode layout of a Kotlin `companion object`
+    public static final Companion Companion = new Companion();
+
+    public static final class Companion {
+        private Companion() {
+        }
+
+        public String defaultUsername() {
+            return "guest";
+        }
+    }
+
     private String username;
     private String password;
+    private int login_count;
 
     public native int getInt(int x);
 
@@ -27,6 +58,16 @@ public class User {
 
     public native short getShort(short x);
 
+    public native int getUnsignedInt(int x);
+
+    public native String unsignedIntToString(int x);
+
+    public native String unsignedByteToString(byte x);
+
+    public native int maxUnsignedInt();
+
+    public native short maxCheckedUnsignedShort();
+
     public native String getString(String x);
 
     public native List<Integer> getIntArray(List<Integer> x);
@@ -35,6 +76,16 @@ public class User {
 
     public native byte[] getByteArray(byte[] x);
 
+    public native byte[] getByteVec(byte[] x);
+
+    public static native byte[] getByteVecUnchecked(byte[] x);
+
+    public native String byteVecToString(byte[] x);
+
+    public native byte[] getSignedByteArray(byte[] x);
+
+    public native String signedByteArrayToString(byte[] x);
+
     public native String intToString(int x);
 
     public native String boolToString(boolean x);
@@ -59,10 +110,355 @@ public class User {
 
     private native static void initNative();
 
+    public native static String localeTag(Locale locale);
+
+    public native static Locale sameLocale(Locale locale);
+
+    public native static int panicWith(String message);
+
+    public native static int checkedDivide(int a, int b);
+
+    public native static int disabledExport(int value);
+
+    public native static int callDisabledExport(int value);
+
+    public native static String describeUser(User user);
+
+    public native static String describeObject(Object obj);
+
+    public native static void storeUser(User user);
+
+    public native static String storedUserPassword();
+
+    public native static User takeStoredUser();
+
+    public native static User shoutedCopy(User user);
+
+    public native static String className();
+
+    public native static boolean sameClassUnchecked(Object obj);
+
+    public native static int recordLogin(User user);
+
+    public native static int add_one(int value);
+
+    public static class Counter {
+        public native static int next(int value);
+
+        public native static int previous(int value);
+
+        public native static boolean isCounter(Object obj);
+    }
+
+    public native static void logWarning(String message);
+
+    public native static void logWarningFromThread(String message);
+
     public native static String userCountStatus();
 
     public native String hashedPassword(int seed);
 
+    public native String profile();
+
+    public native String messageTo(String receiver);
+
+    public native void appendToPassword(String suffix);
+
+    public native void appendChecksum(byte[] bytes);
+
+    public native static String greetDefaultUser();
+
+    public native String lazyUsername(boolean load);
+
+    public native String lazyUsernameUnchecked();
+
+    public native static String formatNameWithFallback(String name);
+
+    public native String fallbackSelfMethod(String s);
+
+    public native static String verifyBindings();
+
+    public native static boolean isUser(Object obj);
+
+    public native boolean samePassword(User other);
+
+    public native boolean samePasswordLenient(User other);
+
+    public native static String concatenate(String first, String second);
+
+    public native static String shapeKinds(Shape shape);
+
+    public native static String greet(Named named);
+
+    public native static String absHex(int value);
+
+    public native static String suspendCalls(Fetcher fetcher);
+
+    public native static void suspendFailure(String message);
+
+    public native static String rawUserPassword(User user);
+
+    public native static String passwordBySignature(User user);
+
+    public native static User rawUser(User user);
+
+    public native static String describeHolder(Holder<String> holder);
+
+    public native static String describeHolderUnchecked(Holder<String> holder);
+
+    public native static String nestedHolderLabel(Holder<Holder<String>> holder);
+
+    public native static Holder<String> echoHolder(Holder<String> holder);
+
+    public native static List<User> usersList(int count);
+
+    public native static User[] usersArray(int count);
+
+    public native static List<User> usersListUnchecked(int count);
+
+    public native static User[] usersArrayUnchecked(int count);
+
+    public native String displayNameFromNative();
+
+    public native String passwordFromThread();
+
+    public native static String classNameFromThread();
+
+    public native boolean holdsLock();
+
+    public native static boolean holdsClassLock();
+
+    public native static int getMaxUsers();
+
+    public native static String getDefaultGreeting();
+
+    public native static void incrementBuffer(ByteBuffer buffer);
+
+    public native static ByteBuffer bufferFromBytes(byte[] bytes);
+
+    public native static String[][] transposeStrings(String[][] v);
+
+    public native static byte[][] byteMatrix(int rows, int cols);
+
+    public native static String[][][] stringCube(int n);
+
+    public native static String[] sortNames(String[] names);
+
+    public native static int[] reverseInts(int[] values);
+
+    public native static long sumLongs(long[] values);
+
+    public native static double[] scaleDoubles(double[] values, double factor);
+
+    public native static float[] halveFloats(float[] values);
+
+    public native static short[] negateShorts(short[] values);
+
+    public native static char[] upperChars(char[] values);
+
+    public native static int[][] transposeInts(int[][] matrix);
+
+    public native static String[] appendName(String[] names, String name);
+
+    public native static String[] appendNameUnchecked(String[] names, String name);
+
+    public native static String[][] appendRow(String[][] rows, String[] row);
+
+    public native static ArrayList<Integer> nameGroupSizes(String[][] groups);
+
+    public native static int nextUserId(int id);
+
+    public native static ArrayList<Integer> nestedListSums(ArrayList<ArrayList<Integer>> v);
+
+    public native static Color nextColor(Color color);
+
+    public native static String colorToString(Color color);
+
+    public native static Color[] reverseColors(Color[] colors);
+
+    public native static Point translatePoint(Point point, int dx, int dy);
+
+    public native static String[] pointLabels(Point[] points);
+
+    public native static int parseIp(String ip);
+
+    public native static String nextIp(int ip);
+
+    public native static String gatewayOf(String ip);
+
+    public native static String describeIp(String ip);
+
+    public native static boolean defaultIpIsPrivate();
+
+    public static String formatIp(int ip) {
+        return "ip:" + ip;
+    }
+
+    public static String defaultIp() {
+        return "192.168.1.20";
+    }
+
+    public native static String describeDimensions(Dimensions dimensions);
+
+    public native static int dimensionsArea(Dimensions dimensions);
+
+    public native static void failWithCause();
+
+    public native static int configuredPort(String port);
+
+    public native static String greetOrDefault(String name);
+
+    public native static String nonEmpty(String s);
+
+    public native static ArrayList<Boolean> nullTerminatedBooleans(boolean[] values);
+
+    public native static boolean[] compactBooleans(ArrayList<Boolean> values);
+
+    public native static ArrayList<Byte> nullTerminatedBytes(byte[] values);
+
+    public native static byte[] compactBytes(ArrayList<Byte> values);
+
+    public native static ArrayList<Character> nullTerminatedChars(char[] values);
+
+    public native static char[] compactChars(ArrayList<Character> values);
+
+    public native static ArrayList<Short> nullTerminatedShorts(short[] values);
+
+    public native static short[] compactShorts(ArrayList<Short> values);
+
+    public native static ArrayList<Integer> nullTerminatedInts(int[] values);
+
+    public native static int[] compactInts(ArrayList<Integer> values);
+
+    public native static ArrayList<Long> nullTerminatedLongs(long[] values);
+
+    public native static long[] compactLongs(ArrayList<Long> values);
+
+    public native static ArrayList<Float> nullTerminatedFloats(float[] values);
+
+    public native static float[] compactFloats(ArrayList<Float> values);
+
+    public native static ArrayList<Double> nullTerminatedDoubles(double[] values);
+
+    public native static double[] compactDoubles(ArrayList<Double> values);
+
+    public native static String[] blankToNull(String[] names);
+
+    public native static int countNulls(String[][] names);
+
+    public native static String checkedUpper(String s);
+
+    public native static int uncheckedLength(String s);
+
+    public native static String[] allShapeKinds();
+
+    public native static Shape firstShape(Shape[] shapes);
+
+    public native static Optional<Integer> optionalLength(Optional<String> s);
+
+    public native static String staticGreeting();
+
+    public native static String staticGreetingUnchecked();
+
+    public native static String greetingOrName(String name);
+
+    public native static byte[] magicBytes();
+
+    public native static byte[] magicBytesUnchecked();
+
+    public native static Optional<Integer> optionalLengthUnchecked(Optional<String> s);
+
+    public native static Instant plusOneSecond(Instant t);
+
+    public native static Duration doubleDuration(Duration d);
+
+    public native static String overloaded(int value);
+
+    public native static String overloaded(String first, String[] rest);
+
+    public native static String overloaded(ArrayList<Integer> values);
+
+    public native static BigInteger negateWide(BigInteger x);
+
+    public native static BigInteger maxUnsignedWide();
+
+    public native static BigInteger halveUnsignedWide(BigInteger x);
+
+    public native static BigDecimal lineTotal(BigDecimal price, int quantity);
+
+    public native static BigDecimal oneTenth();
+
+    public native static int lengthOnAttachedThread(String s);
+
+    public native static byte[] reversedBytes(byte[] data);
+
+    public native static int bytesLength(byte[] data);
+
+    public native static byte[] concatBytes(List<byte[]> parts);
+
+    public native static String doubledOrder(String order);
+
+    public native static byte[] orderToCbor(String order);
+
+    public native static String orderFromCbor(byte[] order);
+
+    public native static String fileName(File file);
+
+    public native static Path siblingPath(Path path, String name);
+
+    public native static File tempFile();
+
+    public native static String readConfig(String path) throws IOException;
+
+    public native static boolean isLoopback(InetAddress address);
+
+    public native static InetSocketAddress nextPort(InetSocketAddress address);
+
+    public native static InetAddress localhost(boolean v6);
+
+    // `robusta.Tuple2` and `robusta.Tuple3` are embedded in the library, see `helper_classes`
+    public native static Object minMax(ArrayList<Integer> values);
+
+    public native static Object swapPair(Object pair);
+
+    public native static Object rotateTriple(Object triple);
+
+    public native static int applyTwice(IntUnaryOperator f, int x);
+
+    public native static String mapBoth(Function<String, String> f, String a, String b);
+
+    public native static int supplyOnThread(Supplier<Integer> f);
+
+    public native static IntUnaryOperator adder(int n);
+
+    public native static Function<String, String> prefixer(String prefix);
+
+    public native static long totalLength(Iterator<String> words);
+
+    public native static Iterator<Integer> range(int start, int end);
+
+    public native static int lengthOnThread(String s);
+
+    public native static void invertBytes(byte[] bytes);
+
+    public native static String weightedSum(String label, int[] values, double[] weights);
+
+    public native static ArrayList<String> formatNamesInFrame(ArrayList<String> names);
+
+    public native static String panicInFrame(ArrayList<String> names);
+
+    public native static ArrayList<Long> cumulativeSums(ArrayList<Long> values);
+
+    public native static ArrayList<Double> scaled(ArrayList<Double> values, double factor);
+
+    public native static BitSet invertBits(BitSet bits, int len);
+
+    public native static String passwordOfBuiltUser(String username, String password);
+
+    public native static String passwordOfNamedUser(String username);
+
+    public native static String formatNameFromSlices(String first, String last);
+
     public User(String username, String password) {
         User.TOTAL_USERS_COUNT += 1;
 
@@ -70,6 +466,10 @@ public class User {
         this.password = password;
     }
 
+    public User(String username) {
+        this(username, "default");
+    }
+
     public static int getTotalUsersCount() {
         return TOTAL_USERS_COUNT;
     }
@@ -81,4 +481,12 @@ public class User {
     public String multipleParameters(int i, String s) {
         return s;
     }
+
+    public static String formatName(String name) {
+        return "<" + name + ">";
+    }
+
+    public String display_name() {
+        return "@" + username;
+    }
 }
@@ -0,0 +1,15 @@
+package kotlin;
+
+// Mirrors the bytecode layout of `kotlin.Result` from the Kotlin standard library
+public final class Result {
+    private Result() {
+    }
+
+    public static final class Failure {
+        public final Throwable exception;
+
+        public Failure(Throwable exception) {
+            this.exception = exception;
+        }
+    }
+}
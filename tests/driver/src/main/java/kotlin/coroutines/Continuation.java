@@ -0,0 +1,8 @@
+package kotlin.coroutines;
+
+// Mirrors `kotlin.coroutines.Continuation` from the Kotlin standard library
+public interface Continuation<T> {
+    CoroutineContext getContext();
+
+    void resumeWith(Object result);
+}
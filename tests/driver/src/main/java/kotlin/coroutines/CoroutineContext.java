@@ -0,0 +1,5 @@
+package kotlin.coroutines;
+
+// Mirrors `kotlin.coroutines.CoroutineContext` from the Kotlin standard library
+public interface CoroutineContext {
+}
@@ -0,0 +1,9 @@
+package kotlin.coroutines;
+
+// Mirrors `kotlin.coroutines.EmptyCoroutineContext` from the Kotlin standard library
+public final class EmptyCoroutineContext implements CoroutineContext {
+    public static final EmptyCoroutineContext INSTANCE = new EmptyCoroutineContext();
+
+    private EmptyCoroutineContext() {
+    }
+}
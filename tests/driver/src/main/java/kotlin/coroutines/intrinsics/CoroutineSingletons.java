@@ -0,0 +1,8 @@
+package kotlin.coroutines.intrinsics;
+
+// Mirrors `kotlin.coroutines.intrinsics.CoroutineSingletons` from the Kotlin standard library
+public enum CoroutineSingletons {
+    COROUTINE_SUSPENDED,
+    UNDECIDED,
+    RESUMED
+}
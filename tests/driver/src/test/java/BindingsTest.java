@@ -0,0 +1,18 @@
+import org.junit.jupiter.api.Test;
+
+import java.util.ArrayList;
+import java.util.Arrays;
+
+import static org.junit.jupiter.api.Assertions.assertEquals;
+import static org.junit.jupiter.api.Assertions.assertFalse;
+import static org.junit.jupiter.api.Assertions.assertTrue;
+
+public class BindingsTest {
+    @Test
+    public void freeFunctions() {
+        assertEquals(5, Bindings.add(2, 3));
+        assertEquals("a, b, c", Bindings.joinAll(new ArrayList<>(Arrays.asList("a", "b", "c")), ", "));
+        assertTrue(Bindings.isBlank("  "));
+        assertFalse(Bindings.isBlank(" x "));
+    }
+}
@@ -0,0 +1,12 @@
+import org.junit.jupiter.api.Test;
+
+import static org.junit.jupiter.api.Assertions.assertEquals;
+
+public class DelegatedTest {
+    @Test
+    public void delegatedNatives() {
+        assertEquals("abab", Delegated.repeat("ab", 2));
+        assertEquals(3, Delegated.countWords("one two three"));
+        assertEquals("HEY", Delegated.shout("hey"));
+    }
+}
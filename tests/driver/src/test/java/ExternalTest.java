@@ -0,0 +1,12 @@
+import org.junit.jupiter.api.Test;
+
+import static org.junit.jupiter.api.Assertions.assertEquals;
+
+public class ExternalTest {
+    @Test
+    public void externalTypeNatives() {
+        Tally tally = new Tally(4);
+        assertEquals(5, tally.incremented());
+        assertEquals("tally = 4", tally.summary());
+    }
+}
@@ -0,0 +1,18 @@
+import org.junit.jupiter.api.Test;
+
+import static org.junit.jupiter.api.Assertions.assertEquals;
+import static org.junit.jupiter.api.Assertions.assertThrows;
+
+public class ModuleDefaultsTest {
+    @Test
+    public void moduleCallType() {
+        assertEquals(42, ModuleDefaults.twice(21));
+        assertEquals("HELLO", ModuleDefaults.shout("hello"));
+    }
+
+    @Test
+    public void methodCallTypeOverride() {
+        assertEquals(42, ModuleDefaults.parse("42"));
+        assertThrows(IllegalArgumentException.class, () -> ModuleDefaults.parse("x"));
+    }
+}
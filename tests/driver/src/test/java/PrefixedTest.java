@@ -0,0 +1,16 @@
+import org.junit.jupiter.api.Test;
+
+import static org.junit.jupiter.api.Assertions.assertEquals;
+
+public class PrefixedTest {
+    @Test
+    public void formattedNames() {
+        assertEquals(3, Prefixed.nativeAdd(1, 2));
+        assertEquals("#4 #4", Prefixed.nativeDescribeTwice(4));
+    }
+
+    @Test
+    public void javaNameOverride() {
+        assertEquals(97 + 98, Prefixed.checksum("ab"));
+    }
+}
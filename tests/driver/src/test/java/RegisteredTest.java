@@ -0,0 +1,46 @@
+import org.junit.jupiter.api.Test;
+
+import java.util.ArrayList;
+import java.util.Iterator;
+import java.util.List;
+
+import static org.junit.jupiter.api.Assertions.assertEquals;
+import static org.junit.jupiter.api.Assertions.assertNotSame;
+import static org.junit.jupiter.api.Assertions.assertTrue;
+
+public class RegisteredTest {
+    @Test
+    public void registeredNatives() {
+        assertEquals(3, Registered.add(1, 2));
+        assertEquals("Hello, world", Registered.greet("world"));
+        assertEquals(false, Registered.negate(true));
+        Registered.noop();
+        assertEquals(-1, Registered.subtract(1, 2));
+    }
+
+    @Test
+    public void envParameterForms() {
+        assertEquals(3, Registered.aliasedEnv("abc"));
+        assertEquals("ABC", Registered.mutEnv("abc"));
+        assertTrue(Registered.isRegisteredClass());
+    }
+
+    @Test
+    public void unloadHooks() {
+        Registered.addUnloadHook("first");
+        Registered.addUnloadHook("second");
+        assertEquals("second,first", Registered.runUnloadHooks());
+        assertEquals("", Registered.runUnloadHooks());
+    }
+
+    @Test
+    public void embeddedHelperClasses() {
+        Iterator<Integer> iterator = Registered.countdown(3);
+        List<Integer> items = new ArrayList<>();
+        iterator.forEachRemaining(items::add);
+
+        assertEquals(List.of(2, 1, 0), items);
+        assertEquals("robusta.RustIterator", iterator.getClass().getName());
+        assertNotSame(Registered.class.getClassLoader(), iterator.getClass().getClassLoader());
+    }
+}
@@ -0,0 +1,51 @@
+import java.util.ArrayList;
+import java.util.List;
+
+import org.junit.jupiter.api.Test;
+
+import static org.junit.jupiter.api.Assertions.assertEquals;
+import static org.junit.jupiter.api.Assertions.assertTrue;
+
+public class SensorTest {
+    @Test
+    public void firstUseHook() throws InterruptedException {
+        List<Thread> threads = new ArrayList<>();
+        for (int i = 0; i < 8; i++) {
+            Sensor sensor = new Sensor(i);
+            threads.add(new Thread(() -> assertEquals(sensor.read() * 2, Sensor.readTwice(sensor))));
+        }
+        threads.forEach(Thread::start);
+        for (Thread thread : threads) {
+            thread.join();
+        }
+
+        assertEquals(6.0, Sensor.readTwice(new Sensor(3)));
+        // Other tests may use the class first
+        assertEquals(1, Sensor.firstUseCount());
+    }
+
+    @Test
+    public void weakReferences() throws InterruptedException {
+        Sensor sensor = new Sensor(2);
+        Sensor.watch(sensor);
+        assertEquals(2.0, Sensor.watchedValue());
+
+        sensor = null;
+        for (int i = 0; i < 100 && !Double.isNaN(Sensor.watchedValue()); i++) {
+            System.gc();
+            Thread.sleep(10);
+        }
+        assertTrue(Double.isNaN(Sensor.watchedValue()));
+    }
+
+    @Test
+    public void instrumentation() {
+        Sensor sensor = new Sensor(2);
+        Sensor.startTracing();
+        double value = Sensor.readTwice(sensor);
+        String trace = Sensor.stopTracing();
+
+        assertEquals(4.0, value);
+        assertEquals("enter Sensor::readTwice(1), enter Sensor::read(0), exit Sensor::read, exit Sensor::readTwice", trace);
+    }
+}
@@ -0,0 +1,20 @@
+import java.util.ArrayList;
+import java.util.List;
+import org.junit.jupiter.api.Test;
+
+import static org.junit.jupiter.api.Assertions.assertEquals;
+import static org.junit.jupiter.api.Assertions.assertThrows;
+
+public class UncheckedDefaultsTest {
+    @Test
+    public void defaultCallType() {
+        assertEquals("a,b", UncheckedDefaults.join(new ArrayList<>(List.of("a", "b"))));
+        assertEquals(false, UncheckedDefaults.negate(true));
+    }
+
+    @Test
+    public void methodCallTypeOverride() {
+        assertEquals(42, UncheckedDefaults.parse("42"));
+        assertThrows(IllegalArgumentException.class, () -> UncheckedDefaults.parse("x"));
+    }
+}
@@ -1,11 +1,42 @@
 import org.junit.jupiter.api.BeforeEach;
 import org.junit.jupiter.api.Test;
 
+import java.io.File;
+import java.io.IOException;
+import java.lang.reflect.Constructor;
+import java.math.BigDecimal;
+import java.math.BigInteger;
+import java.net.InetAddress;
+import java.net.InetSocketAddress;
+import java.net.UnknownHostException;
+import java.nio.ByteBuffer;
+import java.nio.file.Path;
+import java.nio.file.Paths;
+import java.time.Duration;
+import java.time.Instant;
+import java.util.ArrayList;
+import java.util.Arrays;
+import java.util.BitSet;
+import java.util.Collections;
+import java.util.Iterator;
 import java.util.List;
+import java.util.Locale;
+import java.util.NoSuchElementException;
+import java.util.Optional;
 import java.util.function.Function;
+import java.util.logging.Handler;
+import java.util.logging.Level;
+import java.util.logging.LogRecord;
+import java.util.logging.Logger;
 
 import static org.junit.jupiter.api.Assertions.assertEquals;
 import static org.junit.jupiter.api.Assertions.assertArrayEquals;
+import static org.junit.jupiter.api.Assertions.assertTrue;
+import static org.junit.jupiter.api.Assertions.assertFalse;
+import static org.junit.jupiter.api.Assertions.assertSame;
+import static org.junit.jupiter.api.Assertions.assertNotSame;
+import static org.junit.jupiter.api.Assertions.assertNull;
+import static org.junit.jupiter.api.Assertions.assertThrows;
 
 public class UserTest {
     private User u;
@@ -22,6 +53,18 @@ public class UserTest {
         assertEquals(expected, actual);
     }
 
+    @Test
+    public void borrowedSelf() {
+        assertEquals("@user (user, 4 chars)", u.profile());
+        assertEquals("user -> admin", u.messageTo("admin"));
+    }
+
+    @Test
+    public void mutSelfWriteBack() {
+        u.appendToPassword("_new");
+        assertEquals("pass_new", u.getPassword());
+    }
+
     @Test
     public void intTest() {
         assertValueRoundTrip(u::getInt, u::intToString, 0, "0");
@@ -112,6 +155,18 @@ public class UserTest {
         assertValueRoundTrip(u::getShort, u::shortToString, Short.MIN_VALUE, "-32768");
     }
 
+    @Test
+    public void unsignedTest() {
+        assertEquals(7, u.getUnsignedInt(7));
+        assertEquals(Integer.MAX_VALUE, u.getUnsignedInt(Integer.MAX_VALUE));
+        RuntimeException e = assertThrows(RuntimeException.class, () -> u.getUnsignedInt(-1));
+        assertTrue(e.getMessage().contains("can't convert parameter 1 (`v`) from Java type `I` to Rust type `u32`"));
+        assertEquals("4294967295", u.unsignedIntToString(-1));
+        assertEquals("255", u.unsignedByteToString((byte) -1));
+        assertEquals(-1, u.maxUnsignedInt());
+        assertThrows(RuntimeException.class, () -> u.maxCheckedUnsignedShort());
+    }
+
     @Test
     public void stringTest() {
         assertValueRoundTrip(u::getString, Function.identity(), "", "");
@@ -140,6 +195,21 @@ public class UserTest {
     public void byteArrayTest() {
         assertArrayValueRoundTrip(u::getByteArray, u::byteArrayToString, new byte[0], "[]");
         assertArrayValueRoundTrip(u::getByteArray, u::byteArrayToString, new byte[] {1, 2, 3}, "[1, 2, 3]");
+        assertArrayValueRoundTrip(u::getByteArray, u::byteArrayToString, new byte[] {-1, -128}, "[255, 128]");
+    }
+
+    @Test
+    public void byteVecTest() {
+        assertArrayValueRoundTrip(u::getByteVec, u::byteVecToString, new byte[0], "[]");
+        assertArrayValueRoundTrip(u::getByteVec, u::byteVecToString, new byte[] {1, 2, 3}, "[1, 2, 3]");
+        assertArrayValueRoundTrip(u::getByteVec, u::byteVecToString, new byte[] {-1, -128}, "[255, 128]");
+        assertArrayValueRoundTrip(User::getByteVecUnchecked, u::byteVecToString, new byte[] {-1, 0, 127}, "[255, 0, 127]");
+    }
+
+    @Test
+    public void signedByteArrayTest() {
+        assertArrayValueRoundTrip(u::getSignedByteArray, u::signedByteArrayToString, new byte[0], "[]");
+        assertArrayValueRoundTrip(u::getSignedByteArray, u::signedByteArrayToString, new byte[] {1, -1, -128}, "[1, -1, -128]");
     }
 
     @Test
@@ -147,6 +217,715 @@ public class UserTest {
         assertEquals(String.valueOf(User.getTotalUsersCount()), User.userCountStatus());
     }
 
+    @Test
+    public void kotlinObjectAndCompanion() {
+        assertEquals("Hello, guest", User.greetDefaultUser());
+    }
+
+    @Test
+    public void lazyFields() {
+        assertEquals("loaded: false", u.lazyUsername(false));
+        assertEquals("user (loaded: true)", u.lazyUsername(true));
+        assertEquals("USER", u.lazyUsernameUnchecked());
+    }
+
+    @Test
+    public void fallbackSignatures() {
+        assertEquals("<rust>", User.formatNameWithFallback("rust"));
+        assertEquals("<ada> <ada lovelace>", User.formatNameFromSlices("ada", "lovelace"));
+        assertEquals("fallback", u.fallbackSelfMethod("fallback"));
+    }
+
+    @Test
+    public void verifyBindings() {
+        String report = User.verifyBindings();
+        assertFalse(report.contains("\n"), report);
+    }
+
+    @Test
+    public void constructorBuilder() {
+        assertEquals("secret", User.passwordOfBuiltUser("user", "secret"));
+        assertEquals("default", User.passwordOfBuiltUser("user", ""));
+        assertThrows(RuntimeException.class, () -> User.passwordOfBuiltUser("", "secret"));
+    }
+
+    @Test
+    public void constructorSignature() {
+        assertEquals("default", User.passwordOfNamedUser("user"));
+    }
+
+    @Test
+    public void opaqueObjects() {
+        assertEquals("it-IT", User.localeTag(Locale.ITALY));
+        assertSame(Locale.ITALY, User.sameLocale(Locale.ITALY));
+        assertNull(User.sameLocale(null));
+    }
+
+    @Test
+    public void panicsAreThrown() {
+        RuntimeException e = assertThrows(RuntimeException.class, () -> User.panicWith("boom"));
+        assertEquals("boom", e.getMessage());
+
+        assertEquals(3, User.checkedDivide(7, 2));
+        IllegalStateException ise = assertThrows(IllegalStateException.class, () -> User.checkedDivide(1, 0));
+        assertEquals("division by zero", ise.getMessage());
+    }
+
+    @Test
+    public void noBridge() {
+        assertEquals(42, User.callDisabledExport(21));
+        assertThrows(UnsatisfiedLinkError.class, () -> User.disabledExport(21));
+    }
+
+    @Test
+    public void describeObjects() {
+        User u = new User("user", "password");
+        String hash = Integer.toHexString(System.identityHashCode(u));
+        assertEquals("User@" + hash + "{" + u + "}", User.describeUser(u));
+        assertEquals("null", User.describeObject(null));
+
+        Object faulty = new Object() {
+            @Override
+            public String toString() {
+                throw new IllegalStateException("no description");
+            }
+        };
+        assertTrue(User.describeObject(faulty).endsWith("{<toString() threw java.lang.IllegalStateException>}"));
+    }
+
+    @Test
+    public void globalUsers() {
+        User u = new User("user", "password");
+        User.storeUser(u);
+        assertEquals("password", User.storedUserPassword());
+        assertSame(u, User.takeStoredUser());
+        assertEquals("", User.storedUserPassword());
+        assertThrows(RuntimeException.class, User::takeStoredUser);
+    }
+
+    @Test
+    public void userData() {
+        User u = new User("user", "password");
+        User copy = User.shoutedCopy(u);
+        assertNotSame(u, copy);
+        assertEquals("@USER", copy.display_name());
+        assertEquals("password", copy.getPassword());
+        assertEquals("@user", u.display_name());
+    }
+
+    @Test
+    public void classParameter() {
+        assertEquals("User", User.className());
+        assertTrue(User.sameClassUnchecked(new User("user", "password")));
+        assertFalse(User.sameClassUnchecked("user"));
+    }
+
+    @Test
+    public void lazyFieldAccessor() {
+        User u = new User("user", "password");
+        assertEquals(1, User.recordLogin(u));
+        assertEquals(2, User.recordLogin(u));
+        assertEquals(1, User.recordLogin(new User("other", "password")));
+    }
+
+    @Test
+    public void escapedSymbolNames() {
+        assertEquals(42, User.add_one(41));
+        assertEquals(42, User.Counter.next(41));
+    }
+
+    @Test
+    public void nestedClasses() {
+        assertEquals(41, User.Counter.previous(42));
+        assertTrue(User.Counter.isCounter(new User.Counter()));
+        assertFalse(User.Counter.isCounter(new User("user", "password")));
+    }
+
+    @Test
+    public void logForwarding() {
+        List<LogRecord> records = new ArrayList<>();
+        Handler handler = new Handler() {
+            @Override
+            public void publish(LogRecord record) {
+                records.add(record);
+            }
+
+            @Override
+            public void flush() {
+            }
+
+            @Override
+            public void close() {
+            }
+        };
+
+        Logger logger = Logger.getLogger("rust");
+        logger.addHandler(handler);
+        try {
+            User.logWarning("from native method");
+            User.logWarningFromThread("from native thread");
+        } finally {
+            logger.removeHandler(handler);
+        }
+
+        assertEquals(2, records.size());
+        assertEquals(Level.WARNING, records.get(0).getLevel());
+        assertEquals("from native method", records.get(0).getMessage());
+        assertEquals("native::jni", records.get(0).getSourceClassName());
+        assertEquals("from native thread", records.get(1).getMessage());
+    }
+
+    @Test
+    public void cachedClass() {
+        assertTrue(User.isUser(u));
+        assertFalse(User.isUser("user"));
+    }
+
+    @Test
+    public void aliasingCheck() {
+        User other = new User("other", "pass");
+        assertTrue(u.samePassword(other));
+        assertThrows(IllegalArgumentException.class, () -> u.samePassword(u));
+        assertTrue(u.samePasswordLenient(u));
+        String s = "abc";
+        assertEquals("abcabc", User.concatenate(s, s));
+    }
+
+    @Test
+    public void synchronizedMethods() {
+        assertTrue(u.holdsLock());
+        assertTrue(User.holdsClassLock());
+        assertFalse(Thread.holdsLock(u));
+    }
+
+    @Test
+    public void staticMethodsOfOtherClasses() {
+        assertEquals("ff", User.absHex(-255));
+    }
+
+    @Test
+    public void suspendFunctions() {
+        assertEquals("4/Hello, user/true/item-7", User.suspendCalls(new Fetcher("item-")));
+        assertThrows(IllegalStateException.class, () -> User.suspendFailure("failed"));
+    }
+
+    @Test
+    public void rawReferences() {
+        User user = new User("user", "password");
+        assertEquals("password", User.rawUserPassword(user));
+        assertSame(user, User.rawUser(user));
+    }
+
+    @Test
+    public void signatureConstants() {
+        assertEquals("pass (LUser;)Ljava/lang/String;", User.passwordBySignature(u));
+    }
+
+    @Test
+    public void bridgedClassArrays() {
+        assertArrayEquals(new String[] { "shape", "square" }, User.allShapeKinds());
+        Square square = new Square();
+        assertSame(square, User.firstShape(new Shape[] { square, new Shape() }));
+    }
+
+    @Test
+    public void methodDispatch() {
+        assertEquals("shape/shape/shape", User.shapeKinds(new Shape()));
+        assertEquals("square/shape/shape", User.shapeKinds(new Square()));
+        assertEquals("Hello, square", User.greet(new Square()));
+        assertEquals("Hi", User.greet(new Named() {
+            @Override
+            public String name() {
+                return "anonymous";
+            }
+
+            @Override
+            public String greeting() {
+                return "Hi";
+            }
+        }));
+    }
+
+    @Test
+    public void genericStructs() {
+        Holder<String> holder = new Holder<>("name", "value");
+        assertEquals("name: value", User.describeHolder(holder));
+        assertEquals("name: value", User.describeHolderUnchecked(holder));
+        assertEquals("outer/name", User.nestedHolderLabel(new Holder<>("outer", holder)));
+        assertSame(holder, User.echoHolder(holder));
+    }
+
+    @Test
+    public void accessorFields() {
+        Account account = new Account("owner", 10);
+        assertEquals("owner", account.deposit(5));
+        assertEquals(15, account.getBalance());
+        assertEquals("deposited 5", account.getNote());
+        assertEquals(15, account.balanceUnchecked());
+    }
+
+    @Test
+    public void collectionFields() {
+        Account account = new Account("owner", 10);
+        account.deposit(5);
+        account.deposit(1);
+        assertEquals(List.of("+5", "+1"), account.getHistory());
+        assertArrayEquals(new Point[]{new Point(15, 5, "owner"), new Point(16, 1, "owner")}, account.getCheckpoints());
+
+        assertEquals(List.of("+5", "+1"), account.clearHistoryUnchecked());
+        assertEquals(List.of(), account.getHistory());
+    }
+
+    @Test
+    public void byteArrayFields() {
+        Packet packet = new Packet(new byte[]{(byte) 0xff, 1}, new byte[]{2, 3}, new byte[]{4, (byte) 0x80}, new byte[]{5});
+        assertEquals(2 + 3, packet.checksumUnchecked());
+        assertEquals(4, packet.seal());
+        assertArrayEquals(new byte[]{(byte) 0xff, 1, 2, 3}, packet.getPayload());
+        assertEquals(255 + 1 + 2 + 3, packet.checksumUnchecked());
+
+        assertEquals(5, packet.signatureSumUnchecked());
+        assertEquals(3, packet.sign());
+        assertArrayEquals(new byte[]{5, (byte) 0x80, 4}, packet.getSignature());
+        assertEquals(5 + 128 + 4, packet.signatureSumUnchecked());
+    }
+
+    @Test
+    public void selfCollections() {
+        assertUsers(User.usersList(3));
+        assertUsers(List.of(User.usersArray(3)));
+        assertUsers(User.usersListUnchecked(3));
+        assertUsers(List.of(User.usersArrayUnchecked(3)));
+        assertEquals(0, User.usersArray(0).length);
+    }
+
+    @Test
+    public void exportedConstant() {
+        assertEquals(100, User.getMaxUsers());
+        assertEquals("Hello", User.getDefaultGreeting());
+    }
+
+    @Test
+    public void globalHandleFromThread() {
+        assertEquals("pass", u.passwordFromThread());
+    }
+
+    @Test
+    public void classLoaderFromThread() {
+        assertEquals("User", User.classNameFromThread());
+    }
+
+    @Test
+    public void javaNameOverride() {
+        assertEquals("@user", u.displayNameFromNative());
+    }
+
+    @Test
+    public void directByteBuffer() {
+        ByteBuffer buffer = ByteBuffer.allocateDirect(3);
+        buffer.put(new byte[] {1, 2, -1});
+        User.incrementBuffer(buffer);
+        assertEquals(2, buffer.get(0));
+        assertEquals(3, buffer.get(1));
+        assertEquals(0, buffer.get(2));
+
+        ByteBuffer copy = User.bufferFromBytes(new byte[] {4, 5});
+        assertTrue(copy.isDirect());
+        assertEquals(2, copy.capacity());
+        assertEquals(4, copy.get(0));
+        assertEquals(5, copy.get(1));
+    }
+
+    @Test
+    public void newtypes() {
+        assertArrayEquals(new String[] {"a", "b", "c"}, User.sortNames(new String[] {"c", "a", "b"}));
+        assertEquals(List.of(2, 1), User.nameGroupSizes(new String[][] {{"a", "b"}, {"c"}}));
+        assertEquals(42, User.nextUserId(41));
+    }
+
+    @Test
+    public void primitiveArrays() {
+        assertArrayEquals(new int[] {3, 2, 1}, User.reverseInts(new int[] {1, 2, 3}));
+        assertArrayEquals(new int[0], User.reverseInts(new int[0]));
+        assertEquals(Long.MAX_VALUE, User.sumLongs(new long[] {Long.MAX_VALUE - 1, 1}));
+        assertArrayEquals(new double[] {1.5, -3.0}, User.scaleDoubles(new double[] {0.5, -1.0}, 3.0), 0.0);
+        assertArrayEquals(new float[] {0.25f, 2f}, User.halveFloats(new float[] {0.5f, 4f}), 0f);
+        assertArrayEquals(new short[] {-1, Short.MAX_VALUE}, User.negateShorts(new short[] {1, -Short.MAX_VALUE}));
+        assertArrayEquals("\u00c9T\u00c9".toCharArray(), User.upperChars("\u00e9t\u00e9".toCharArray()));
+        assertArrayEquals(new int[][] {{1, 3}, {2, 4}}, User.transposeInts(new int[][] {{1, 2}, {3, 4}}));
+    }
+
+    @Test
+    public void vecArrays() {
+        assertArrayEquals(new String[] {"a", "b", "c"}, User.appendName(new String[] {"a", "b"}, "c"));
+        assertArrayEquals(new String[] {"a"}, User.appendNameUnchecked(new String[0], "a"));
+        assertArrayEquals(new String[][] {{"a"}, {"b", "c"}}, User.appendRow(new String[][] {{"a"}}, new String[] {"b", "c"}));
+    }
+
+    @Test
+    public void multiDimensionalArrays() {
+        assertArrayEquals(new String[][] {{"a", "c"}, {"b", "d"}}, User.transposeStrings(new String[][] {{"a", "b"}, {"c", "d"}}));
+        assertArrayEquals(new byte[][] {{0, 1, 2}, {3, 4, 5}}, User.byteMatrix(2, 3));
+        assertEquals("101", User.stringCube(2)[1][0][1]);
+
+        ArrayList<ArrayList<Integer>> lists = new ArrayList<>(List.of(new ArrayList<>(List.of(1, 2)), new ArrayList<>(List.of(3))));
+        assertEquals(List.of(3, 3), User.nestedListSums(lists));
+    }
+
+    @Test
+    public void enumConversion() {
+        assertEquals(Color.GREEN, User.nextColor(Color.RED));
+        assertEquals(Color.DARK_BLUE, User.nextColor(Color.GREEN));
+        assertEquals(Color.RED, User.nextColor(Color.DARK_BLUE));
+        RuntimeException unknown = assertThrows(RuntimeException.class, () -> User.nextColor(Color.TRANSPARENT));
+        assertTrue(unknown.getMessage().contains("Field not found: TRANSPARENT LColor;"), unknown.getMessage());
+        assertEquals("DarkBlue", User.colorToString(Color.DARK_BLUE));
+        assertArrayEquals(new Color[] { Color.DARK_BLUE, Color.RED }, User.reverseColors(new Color[] { Color.RED, Color.DARK_BLUE }));
+    }
+
+    @Test
+    public void recordConversion() {
+        assertEquals(new Point(3, 5, "origin moved"), User.translatePoint(new Point(1, 1, "origin"), 2, 4));
+        assertArrayEquals(new String[] { "a (1, 2)", "b (3, 4)" }, User.pointLabels(new Point[] { new Point(1, 2, "a"), new Point(3, 4, "b") }));
+    }
+
+    @Test
+    public void customConverters() {
+        assertEquals(0x7f000001, User.parseIp("127.0.0.1"));
+        assertEquals("127.0.0.2", User.nextIp(0x7f000001));
+        assertEquals("10.0.0.1", User.gatewayOf("10.0.0.7"));
+        assertEquals("ip:256", User.describeIp("0.0.1.0"));
+        assertTrue(User.defaultIpIsPrivate());
+        RuntimeException e = assertThrows(RuntimeException.class, () -> User.parseIp("localhost"));
+        assertTrue(e.getMessage().contains("Ipv4Addr"), e.getMessage());
+    }
+
+    @Test
+    public void dataStructConversion() {
+        assertEquals("3x4 cm", User.describeDimensions(new Dimensions(3, 4, "cm")));
+        assertEquals(12, User.dimensionsArea(new Dimensions(3, 4, "cm")));
+    }
+
+    @Test
+    public void exceptionCauses() {
+        RuntimeException e = assertThrows(RuntimeException.class, User::failWithCause);
+        assertEquals("JNI call error!. Cause: JNI call failed", e.getMessage());
+        assertEquals(IllegalStateException.class, e.getCause().getClass());
+        assertEquals("Not enough memory", e.getCause().getMessage());
+        assertNull(e.getCause().getCause());
+
+        e = assertThrows(RuntimeException.class, () -> User.parseIp("localhost"));
+        assertEquals(RuntimeException.class, e.getCause().getClass());
+        assertTrue(e.getCause().getMessage().startsWith("Invalid JValue type cast"), e.getCause().getMessage());
+
+        // Errors of the method's own type keep their whole source chain
+        assertEquals(8080, User.configuredPort("8080"));
+        e = assertThrows(RuntimeException.class, () -> User.configuredPort("http"));
+        assertEquals("JNI call error!. Cause: invalid configuration", e.getMessage());
+        assertEquals("invalid port `http`", e.getCause().getMessage());
+        assertEquals("invalid digit found in string", e.getCause().getCause().getMessage());
+        assertNull(e.getCause().getCause().getCause());
+    }
+
+    @Test
+    public void borrowedReturnValues() {
+        assertEquals("Hello from Rust", User.staticGreeting());
+        assertEquals("Hello from Rust", User.staticGreetingUnchecked());
+        assertEquals("Hello from Rust", User.greetingOrName(""));
+        assertEquals("Hello, user", User.greetingOrName("user"));
+        assertArrayEquals(new byte[] { 0x7f, 'E', 'L', 'F' }, User.magicBytes());
+        assertArrayEquals(new byte[] { 0x7f, 'E', 'L', 'F' }, User.magicBytesUnchecked());
+    }
+
+    @Test
+    public void optionalConversion() {
+        assertEquals(Optional.of(5), User.optionalLength(Optional.of("hello")));
+        assertEquals(Optional.empty(), User.optionalLength(Optional.empty()));
+        assertEquals(Optional.of(3), User.optionalLengthUnchecked(Optional.of("abc")));
+        assertEquals(Optional.empty(), User.optionalLengthUnchecked(Optional.empty()));
+    }
+
+    @Test
+    public void timeConversion() {
+        assertEquals(Instant.ofEpochSecond(1_000_000_001, 500), User.plusOneSecond(Instant.ofEpochSecond(1_000_000_000, 500)));
+        assertEquals(Instant.ofEpochSecond(-9, -250), User.plusOneSecond(Instant.ofEpochSecond(-10, -250)));
+        assertEquals(Duration.ofMillis(3000), User.doubleDuration(Duration.ofMillis(1500)));
+    }
+
+    @Test
+    public void overloadedNatives() {
+        assertEquals("int 42", User.overloaded(42));
+        assertEquals("strings a b c", User.overloaded("a", new String[] { "b", "c" }));
+        assertEquals("list [1, 2]", User.overloaded(new ArrayList<>(List.of(1, 2))));
+    }
+
+    @Test
+    public void wideIntegerConversion() {
+        BigInteger i128Max = BigInteger.ONE.shiftLeft(127).subtract(BigInteger.ONE);
+        BigInteger u128Max = BigInteger.ONE.shiftLeft(128).subtract(BigInteger.ONE);
+
+        assertEquals(BigInteger.valueOf(-42), User.negateWide(BigInteger.valueOf(42)));
+        assertEquals(i128Max.negate(), User.negateWide(i128Max));
+        assertEquals(i128Max, User.negateWide(i128Max.negate()));
+        assertThrows(RuntimeException.class, () -> User.negateWide(i128Max.add(BigInteger.ONE)));
+
+        assertEquals(u128Max, User.maxUnsignedWide());
+        assertEquals(u128Max.shiftRight(1), User.halveUnsignedWide(u128Max));
+        assertThrows(RuntimeException.class, () -> User.halveUnsignedWide(BigInteger.valueOf(-1)));
+        assertThrows(RuntimeException.class, () -> User.halveUnsignedWide(u128Max.add(BigInteger.ONE)));
+    }
+
+    @Test
+    public void decimalConversion() {
+        assertEquals(new BigDecimal("59.97"), User.lineTotal(new BigDecimal("19.99"), 3));
+        assertEquals(new BigDecimal("3000"), User.lineTotal(new BigDecimal("1E+3"), 3));
+        assertEquals(new BigDecimal("0.1"), User.oneTenth());
+        assertEquals(new BigDecimal("0.3"), User.oneTenth().add(User.oneTenth()).add(User.oneTenth()));
+        assertThrows(RuntimeException.class, () -> User.lineTotal(new BigDecimal("1e-40"), 1));
+    }
+
+    @Test
+    public void attachedThread() {
+        assertEquals(5, User.lengthOnAttachedThread("hello"));
+    }
+
+    @Test
+    public void bytesConversion() {
+        assertArrayEquals(new byte[] { -1, 0, 1 }, User.reversedBytes(new byte[] { 1, 0, -1 }));
+        assertArrayEquals(new byte[0], User.reversedBytes(new byte[0]));
+        assertEquals(3, User.bytesLength(new byte[] { 1, 2, 3 }));
+        assertArrayEquals(new byte[] { 1, 2, -1 }, User.concatBytes(List.of(new byte[] { 1, 2 }, new byte[0], new byte[] { -1 })));
+    }
+
+    @Test
+    public void serializedConversion() {
+        String order = "{\"items\":[\"apple\"],\"quantity\":3}";
+        assertEquals("{\"items\":[\"apple\",\"apple\"],\"quantity\":6}", User.doubledOrder(order));
+        assertEquals(order, User.orderFromCbor(User.orderToCbor(order)));
+        assertThrows(RuntimeException.class, () -> User.doubledOrder("{\"items\":[]}"));
+        assertThrows(RuntimeException.class, () -> User.orderFromCbor(new byte[] { 1, 2, 3 }));
+    }
+
+    @Test
+    public void pathConversion() {
+        assertEquals("notes.txt", User.fileName(new File("docs/notes.txt")));
+        assertEquals(Paths.get("docs/other.txt"), User.siblingPath(Paths.get("docs/notes.txt"), "other.txt"));
+        assertEquals(new File("/tmp/robusta.tmp"), User.tempFile());
+    }
+
+    @Test
+    public void checkedExceptions() throws IOException {
+        assertEquals("config of app.toml", User.readConfig("app.toml"));
+        IOException e = assertThrows(IOException.class, () -> User.readConfig(""));
+        assertTrue(e.getMessage().contains("empty string"), e.getMessage());
+    }
+
+    @Test
+    public void networkAddresses() throws UnknownHostException {
+        assertTrue(User.isLoopback(InetAddress.getByName("127.0.0.1")));
+        assertTrue(User.isLoopback(InetAddress.getByName("::1")));
+        assertFalse(User.isLoopback(InetAddress.getByName("192.168.1.1")));
+
+        InetAddress address = InetAddress.getByName("10.0.0.1");
+        assertEquals(new InetSocketAddress(address, 8081), User.nextPort(new InetSocketAddress(address, 8080)));
+        InetAddress v6 = InetAddress.getByName("fe80::1%1");
+        assertEquals(new InetSocketAddress(v6, 444), User.nextPort(new InetSocketAddress(v6, 443)));
+        assertThrows(RuntimeException.class, () -> User.nextPort(new InetSocketAddress(address, 65535)));
+        assertThrows(RuntimeException.class, () -> User.nextPort(InetSocketAddress.createUnresolved("example.com", 80)));
+
+        assertEquals(InetAddress.getByName("127.0.0.1"), User.localhost(false));
+        assertEquals(InetAddress.getByName("::1"), User.localhost(true));
+    }
+
+    @Test
+    public void tuples() throws ReflectiveOperationException {
+        Object minMax = User.minMax(new ArrayList<>(List.of(3, -1, 7)));
+        assertEquals("robusta.Tuple2", minMax.getClass().getName());
+        assertEquals("(-1, 7)", minMax.toString());
+        assertThrows(RuntimeException.class, () -> User.minMax(new ArrayList<>()));
+
+        // The tuple classes are defined by the library, in a class loader of their own
+        Constructor<?> pair = minMax.getClass().getConstructor(Object.class, Object.class);
+        assertEquals(pair.newInstance("five", 5), User.swapPair(pair.newInstance(5, "five")));
+        assertThrows(RuntimeException.class, () -> User.swapPair(null));
+
+        Object triple = User.rotateTriple(minMax.getClass().getClassLoader().loadClass("robusta.Tuple3")
+                .getConstructor(Object.class, Object.class, Object.class)
+                .newInstance("x", 1L, true));
+        assertEquals("(1, true, x)", triple.toString());
+    }
+
+    @Test
+    public void functionalInterfaces() {
+        assertEquals(9, User.applyTwice(x -> x * 3, 1));
+        assertEquals("<a><b>", User.mapBoth(s -> "<" + s + ">", "a", "b"));
+        assertEquals(42, User.supplyOnThread(() -> 42));
+        assertThrows(IllegalStateException.class, () -> User.applyTwice(x -> { throw new IllegalStateException(); }, 1));
+
+        assertEquals(7, User.adder(5).applyAsInt(2));
+        assertEquals(9, User.adder(5).andThen(User.adder(-1)).applyAsInt(5));
+        assertEquals("> hi", User.prefixer("> ").apply("hi"));
+    }
+
+    @Test
+    void iterators() {
+        assertEquals(10, User.totalLength(List.of("one", "two", "four").iterator()));
+        assertEquals(0, User.totalLength(Collections.emptyIterator()));
+
+        Iterator<Integer> range = User.range(3, 6);
+        List<Integer> values = new ArrayList<>();
+        range.forEachRemaining(values::add);
+        assertEquals(List.of(3, 4, 5), values);
+        assertFalse(range.hasNext());
+        assertThrows(NoSuchElementException.class, range::next);
+    }
+
+    @Test
+    void vmParameter() {
+        assertEquals(5, User.lengthOnThread("hello"));
+    }
+
+    @Test
+    public void nullParameters() {
+        assertEquals("Hello, stranger", User.greetOrDefault(null));
+        assertEquals("Hello, user", User.greetOrDefault("user"));
+        assertNull(User.nonEmpty(""));
+        assertEquals("a", User.nonEmpty("a"));
+
+        NullPointerException e = assertThrows(NullPointerException.class, () -> User.nonEmpty(null));
+        assertEquals("`User::nonEmpty`: parameter `s` (String) is null", e.getMessage());
+        assertThrows(NullPointerException.class, () -> User.checkedUpper(null));
+        assertEquals("A", User.checkedUpper("a"));
+
+        RuntimeException unchecked = assertThrows(RuntimeException.class, () -> User.uncheckedLength(null));
+        assertFalse(unchecked instanceof NullPointerException);
+    }
+
+    private static <T> ArrayList<T> listOf(T... values) {
+        return new ArrayList<>(Arrays.asList(values));
+    }
+
+    @Test
+    public void nullablePrimitiveCompositions() {
+        assertNull(User.nullTerminatedBooleans(null));
+        assertEquals(listOf(true, false, null), User.nullTerminatedBooleans(new boolean[] {true, false}));
+        assertNull(User.compactBooleans(null));
+        assertArrayEquals(new boolean[] {true, false}, User.compactBooleans(listOf(true, null, false)));
+
+        assertNull(User.nullTerminatedBytes(null));
+        assertEquals(listOf((byte) -1, (byte) 2, null), User.nullTerminatedBytes(new byte[] {-1, 2}));
+        assertNull(User.compactBytes(null));
+        assertArrayEquals(new byte[] {-1, 2}, User.compactBytes(listOf((byte) -1, null, (byte) 2)));
+
+        assertNull(User.nullTerminatedChars(null));
+        assertEquals(listOf('a', '\u00e9', null), User.nullTerminatedChars(new char[] {'a', '\u00e9'}));
+        assertNull(User.compactChars(null));
+        assertArrayEquals(new char[] {'a', '\u00e9'}, User.compactChars(listOf(null, 'a', '\u00e9')));
+
+        assertNull(User.nullTerminatedShorts(null));
+        assertEquals(listOf(Short.MIN_VALUE, (short) 3, null), User.nullTerminatedShorts(new short[] {Short.MIN_VALUE, 3}));
+        assertNull(User.compactShorts(null));
+        assertArrayEquals(new short[] {Short.MIN_VALUE, 3}, User.compactShorts(listOf(Short.MIN_VALUE, (short) 3, null)));
+
+        assertNull(User.nullTerminatedInts(null));
+        assertEquals(listOf(Integer.MAX_VALUE, -7, null), User.nullTerminatedInts(new int[] {Integer.MAX_VALUE, -7}));
+        assertNull(User.compactInts(null));
+        assertArrayEquals(new int[] {Integer.MAX_VALUE, -7}, User.compactInts(listOf(null, Integer.MAX_VALUE, null, -7)));
+
+        assertNull(User.nullTerminatedLongs(null));
+        assertEquals(listOf(Long.MIN_VALUE, 42L, null), User.nullTerminatedLongs(new long[] {Long.MIN_VALUE, 42L}));
+        assertNull(User.compactLongs(null));
+        assertArrayEquals(new long[] {Long.MIN_VALUE, 42L}, User.compactLongs(listOf(Long.MIN_VALUE, null, 42L)));
+
+        assertNull(User.nullTerminatedFloats(null));
+        assertEquals(listOf(0.5f, -1f, null), User.nullTerminatedFloats(new float[] {0.5f, -1f}));
+        assertNull(User.compactFloats(null));
+        assertArrayEquals(new float[] {0.5f, -1f}, User.compactFloats(listOf(0.5f, null, -1f)), 0f);
+
+        assertNull(User.nullTerminatedDoubles(null));
+        assertEquals(listOf(Double.MAX_VALUE, 0.25, null), User.nullTerminatedDoubles(new double[] {Double.MAX_VALUE, 0.25}));
+        assertNull(User.compactDoubles(null));
+        assertArrayEquals(new double[] {Double.MAX_VALUE, 0.25}, User.compactDoubles(listOf(Double.MAX_VALUE, 0.25, null)), 0.0);
+
+        assertEquals(0, User.nullTerminatedInts(new int[0]).size() - 1);
+        assertArrayEquals(new int[0], User.compactInts(listOf((Integer) null)));
+    }
+
+    @Test
+    public void nullableArrayElements() {
+        assertArrayEquals(new String[] {"a", null, null}, User.blankToNull(new String[] {"a", " ", null}));
+        assertEquals(3, User.countNulls(new String[][] {{null, "a"}, {}, {null, null}}));
+    }
+
+    @Test
+    public void criticalArrays() {
+        byte[] bytes = {0, 1, (byte) 0xf0};
+        User.invertBytes(bytes);
+        assertArrayEquals(new byte[]{(byte) 0xff, (byte) 0xfe, 0x0f}, bytes);
+        User.invertBytes(new byte[0]);
+
+        assertEquals("total: 3.5", User.weightedSum("total", new int[]{1, 2}, new double[]{0.5, 1.5}));
+        assertThrows(NullPointerException.class, () -> User.weightedSum("total", null, new double[0]));
+
+        User u = new User("user", "pass");
+        u.appendChecksum(new byte[]{1, 2, -5});
+        assertEquals("pass-2", u.getPassword());
+    }
+
+    @Test
+    public void localFrames() {
+        ArrayList<String> names = new ArrayList<>();
+        for (int i = 0; i < 10_000; i++) {
+            names.add("user" + i);
+        }
+
+        // Tests run with -Xcheck:jni, whose warnings fail the build if local references pile up beyond the frame capacity
+        ArrayList<String> formatted = User.formatNamesInFrame(names);
+        assertEquals(names.size(), formatted.size());
+        assertEquals("<user0>", formatted.get(0));
+        assertEquals("<user9999>", formatted.get(9_999));
+
+        // The frame is popped before the panic is thrown
+        RuntimeException e = assertThrows(RuntimeException.class, () -> User.panicInFrame(names));
+        assertEquals("10000 names", e.getMessage());
+        assertEquals(names.size(), User.formatNamesInFrame(names).size());
+    }
+
+    @Test
+    public void packedLists() {
+        ArrayList<Long> sums = User.cumulativeSums(new ArrayList<>(List.of(1L, 2L, 3L, Long.MAX_VALUE - 6)));
+        assertEquals(List.of(1L, 3L, 6L, Long.MAX_VALUE), sums);
+        sums.add(0L);
+        assertEquals(5, sums.size());
+
+        assertEquals(List.of(), User.scaled(new ArrayList<>(), 2.0));
+        assertEquals(List.of(1.0, -3.0), User.scaled(new ArrayList<>(List.of(0.5, -1.5)), 2.0));
+
+        ArrayList<Long> withNull = new ArrayList<>();
+        withNull.add(null);
+        assertThrows(NullPointerException.class, () -> User.cumulativeSums(withNull));
+    }
+
+    @Test
+    public void bitSetConversion() {
+        BitSet bits = new BitSet();
+        bits.set(0);
+        bits.set(100);
+
+        BitSet expected = new BitSet();
+        expected.set(1, 100);
+        expected.set(101, 130);
+        assertEquals(expected, User.invertBits(bits, 130));
+        assertEquals(bits, User.invertBits(expected, 130));
+    }
+
+    private void assertUsers(List<User> users) {
+        assertEquals(3, users.size());
+        for (int i = 0; i < users.size(); i++) {
+            assertEquals("pass" + i, users.get(i).getPassword());
+        }
+    }
+
     private <T> void assertValueRoundTrip(Function<T, T> func, Function<T, String> toString, T value, String text) {
         assertEquals(value, func.apply(value));
         assertEquals(text, toString.apply(value));
@@ -0,0 +1,250 @@
+//! Unit tests of bindings run in an embedded JVM, without Gradle.
+//!
+//! Run with `cargo test --test embedded --features testing,invocation`; the JVM library must be found by the dynamic
+//! loader, e.g. with `LD_LIBRARY_PATH=$JAVA_HOME/lib/server` on Linux.
+
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+
+use robusta_jni::bridge;
+use robusta_jni::convert::{
+    ClassCache, Critical, DirectByteBuffer, JIterator, PackedList, StaticMethodCache, TryFromJavaValue, TryIntoJavaValue,
+};
+use robusta_jni::jni::errors::Result as JniResult;
+use robusta_jni::jni::objects::{JObject, JValue, ReleaseMode};
+use robusta_jni::jni::JNIEnv;
+use robusta_jni::frame;
+use robusta_jni::loader::{self, clear_class_loader, set_class_loader};
+use robusta_jni::testing::{robusta_test, with_env};
+
+#[bridge]
+mod jdk {
+    use robusta_jni::convert::Signature;
+    use robusta_jni::jni::errors::Result as JniResult;
+    use robusta_jni::jni::JNIEnv;
+
+    #[derive(Signature)]
+    #[package(java.lang)]
+    pub struct Integer;
+
+    impl Integer {
+        pub extern "java" fn toHexString(env: &JNIEnv, value: i32) -> JniResult<String> {}
+
+        pub extern "java" fn parseInt(env: &JNIEnv, value: String) -> JniResult<i32> {}
+
+        #[class("java.lang.Math")]
+        pub extern "java" fn max(env: &JNIEnv, a: i32, b: i32) -> JniResult<i32> {}
+    }
+}
+
+use jdk::Integer;
+
+#[robusta_test]
+fn imported_static_methods(env: &JNIEnv) -> JniResult<()> {
+    assert_eq!(Integer::toHexString(env, 255)?, "ff");
+    assert_eq!(Integer::parseInt(env, "-42".into())?, -42);
+    Ok(())
+}
+
+#[robusta_test]
+fn imported_static_methods_are_cached(env: &JNIEnv) -> JniResult<()> {
+    for i in -1..2 {
+        assert_eq!(Integer::max(env, i, 0)?, i.max(0));
+    }
+
+    // Only the first call looks up the class and the method
+    static MAX: StaticMethodCache = StaticMethodCache::new();
+    assert!(!MAX.is_cached());
+    let lookups = Cell::new(0);
+    for i in -1..2 {
+        let max = MAX.call(
+            env,
+            || {
+                lookups.set(lookups.get() + 1);
+                env.find_class("java/lang/Math")
+            },
+            "max",
+            || "(II)I".to_string(),
+            &[JValue::Int(i), JValue::Int(0)],
+        )?;
+        assert_eq!(max.i()?, i.max(0));
+        assert!(MAX.is_cached());
+    }
+    assert_eq!(lookups.get(), 1);
+    Ok(())
+}
+
+#[robusta_test]
+fn cached_classes_outlive_the_cache(env: &JNIEnv) -> JniResult<()> {
+    static INTEGER: ClassCache = ClassCache::new();
+    let class = INTEGER.get(env, "java/lang/Integer")?;
+    assert!(INTEGER.is_cached());
+
+    // The cached global reference is deleted, but `class` is a reference of its own
+    INTEGER.clear();
+    let value = env.new_object(class, "(I)V", &[JValue::Int(7)])?;
+    assert!(env.is_instance_of(value, INTEGER.get(env, "java/lang/Integer")?)?);
+    Ok(())
+}
+
+#[robusta_test]
+fn java_exceptions_are_errors(env: &JNIEnv) {
+    assert!(Integer::parseInt(env, "forty-two".into()).is_err());
+    env.exception_clear().unwrap();
+}
+
+#[robusta_test]
+#[should_panic(expected = "java.lang.NumberFormatException")]
+fn pending_exceptions_fail_tests(env: &JNIEnv) {
+    let _ = Integer::parseInt(env, "forty-two".into());
+}
+
+// Same configuration as the other tests, whichever test starts the JVM
+#[robusta_test(jvm = robusta_jni::testing::default_vm)]
+fn list_round_trip(env: &JNIEnv) -> JniResult<()> {
+    let list = TryIntoJavaValue::try_into(PackedList(vec![1i64, -2, 3]), env)?;
+    let values: PackedList<i64> = TryFromJavaValue::try_from(unsafe { JObject::from_raw(list) }, env)?;
+
+    assert_eq!(values.into_vec(), vec![1, -2, 3]);
+    Ok(())
+}
+
+#[robusta_test]
+fn iterator_streaming(env: &JNIEnv) -> JniResult<()> {
+    let list = unsafe { JObject::from_raw(TryIntoJavaValue::try_into(PackedList(vec![4i32, 5, 6]), env)?) };
+
+    // Lists are `Iterable`s, iterated through `iterator()`
+    let values: JIterator<i32> = TryFromJavaValue::try_from(list, env)?;
+    assert_eq!(values.collect::<JniResult<Vec<_>>>()?, vec![4, 5, 6]);
+
+    let iterator = env.call_method(list, "iterator", "()Ljava/util/Iterator;", &[])?.l()?;
+    let mut values: JIterator<i32> = JIterator::new(env, iterator)?;
+    assert_eq!(values.next().transpose()?, Some(4));
+    assert_eq!(values.map(|v| v.unwrap()).sum::<i32>(), 11);
+    Ok(())
+}
+
+#[cfg(feature = "pool")]
+#[robusta_test]
+fn lists_are_reused_in_scope(env: &JNIEnv) -> JniResult<()> {
+    use robusta_jni::convert::pool::reuse_lists;
+
+    let new_list = |values: Vec<i32>| -> JniResult<JObject> {
+        Ok(unsafe { JObject::from_raw(TryIntoJavaValue::try_into(values, env)?) })
+    };
+
+    // Lists of a scope are distinct, and given back to the pool when it ends
+    let (first, second) = reuse_lists(|| -> JniResult<_> {
+        let first = env.new_global_ref(new_list(vec![1, 2])?)?;
+        let second = env.new_global_ref(new_list(vec![3])?)?;
+        assert!(!env.is_same_object(&first, &second)?);
+        Ok((first, second))
+    })?;
+
+    reuse_lists(|| -> JniResult<()> {
+        let list = new_list(vec![4])?;
+        assert!(env.is_same_object(&first, list)? || env.is_same_object(&second, list)?);
+        let values: Vec<i32> = TryFromJavaValue::try_from(list, env)?;
+        assert_eq!(values, vec![4]);
+        Ok(())
+    })?;
+
+    // Outside of a scope, lists are new
+    let list = new_list(vec![5])?;
+    assert!(!env.is_same_object(&first, list)? && !env.is_same_object(&second, list)?);
+    Ok(())
+}
+
+#[robusta_test]
+fn wide_integer_round_trip(env: &JNIEnv) -> JniResult<()> {
+    for value in [0, -1, 255, i128::MIN, i128::MAX] {
+        let big = TryIntoJavaValue::try_into(value, env)?;
+        assert_eq!(<i128 as TryFromJavaValue>::try_from(big, env)?, value);
+    }
+    for value in [0, 255, u64::MAX as u128 + 1, u128::MAX] {
+        let big = TryIntoJavaValue::try_into(value, env)?;
+        assert_eq!(<u128 as TryFromJavaValue>::try_from(big, env)?, value);
+    }
+
+    let negative = TryIntoJavaValue::try_into(-1i128, env)?;
+    assert!(<u128 as TryFromJavaValue>::try_from(negative, env).is_err());
+    Ok(())
+}
+
+#[robusta_test]
+fn direct_buffer_allocation(env: &JNIEnv) -> JniResult<()> {
+    let buffer = DirectByteBuffer::from_slice(env, &[1, 2, 3])?;
+    // The buffer was allocated by the JVM, and isn't shared with Java code
+    assert_eq!(unsafe { buffer.to_vec() }, [1, 2, 3]);
+
+    assert!(DirectByteBuffer::allocate(env, i32::MAX as usize + 1).is_err());
+    let exception = env.exception_occurred()?;
+    env.exception_clear()?;
+    assert!(env.is_instance_of(exception, "java/lang/IllegalArgumentException")?);
+    Ok(())
+}
+
+#[robusta_test]
+fn critical_arrays_check_element_type(env: &JNIEnv) -> JniResult<()> {
+    let ints = unsafe { JObject::from_raw(env.new_int_array(2)?) };
+    let mut critical = Critical::<i32>::new(env, ints)?;
+    critical.enter(ReleaseMode::NoCopyBack)?;
+    assert_eq!(&*critical, [0, 0]);
+    drop(critical);
+
+    // Reading 2 longs from an `int[2]` would read past its end
+    assert!(Critical::<i64>::new(env, ints).is_err());
+    Ok(())
+}
+
+#[robusta_test]
+fn local_frames_are_popped_on_panic(env: &JNIEnv) -> JniResult<()> {
+    let references = env.new_object("java/util/ArrayList", "()V", &[])?;
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        frame::with_local_frame(env, 16, || -> JniResult<()> {
+            // Only the frame's local reference keeps the string reachable
+            let value = env.new_string("in frame")?;
+            let reference = env.new_object("java/lang/ref/WeakReference", "(Ljava/lang/Object;)V", &[value.into()])?;
+            env.call_method(references, "add", "(Ljava/lang/Object;)Z", &[reference.into()])?;
+            panic!("in frame")
+        })
+    }));
+    assert!(result.is_err());
+
+    env.call_static_method("java/lang/System", "gc", "()V", &[])?;
+    let reference = env.call_method(references, "get", "(I)Ljava/lang/Object;", &[0.into()])?.l()?;
+    let referent = env.call_method(reference, "get", "()Ljava/lang/Object;", &[])?.l()?;
+    assert!(referent.is_null());
+    Ok(())
+}
+
+#[robusta_test]
+fn class_loader_lookups(env: &JNIEnv) -> JniResult<()> {
+    let system_loader = env
+        .call_static_method("java/lang/ClassLoader", "getSystemClassLoader", "()Ljava/lang/ClassLoader;", &[])?
+        .l()?;
+    set_class_loader(env, system_loader)?;
+
+    let integer = loader::find_class(env, "java/lang/Integer")?;
+    let integers = loader::find_class(env, "[Ljava/lang/Integer;")?;
+    assert!(env.is_assignable_from(integer, "java/lang/Number")?);
+    assert!(env.is_assignable_from(integers, "[Ljava/lang/Object;")?);
+
+    assert!(loader::find_class(env, "com/example/Missing").is_err());
+    let exception = env.exception_occurred()?;
+    env.exception_clear()?;
+    assert!(env.is_instance_of(exception, "java/lang/NoClassDefFoundError")?);
+
+    clear_class_loader();
+    Ok(())
+}
+
+#[test]
+fn threads_share_the_jvm() {
+    let handles: Vec<_> = (0..4)
+        .map(|i| std::thread::spawn(move || with_env(|env| Integer::toHexString(env, i).unwrap())))
+        .collect();
+    let hex: Vec<String> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+    assert_eq!(hex, ["0", "1", "2", "3"]);
+}
@@ -23,7 +23,7 @@ fn java_integration_tests() {
             Path::new(".").join("tests").join("driver").join(
                 if cfg!(target_os = "windows") { "gradlew.bat" } else { "gradlew" })
         ).expect("Gradle not found"))
-        .args(&["test", "-i"])
+        .args(["test", "-i"])
         .current_dir(
             Path::new(".").join("tests").join("driver").to_str().expect("Failed to get driver path")
         )
@@ -35,6 +35,27 @@ fn java_integration_tests() {
     assert!(exit_status.success())
 }
 
+#[cfg(feature = "testing")]
+#[test]
+fn java_versions_integration_tests() {
+    use robusta_jni::testing::JavaMatrix;
+
+    let driver_dir = Path::new(".").join("tests").join("driver");
+    let gradlew = fs::canonicalize(
+        driver_dir.join(if cfg!(target_os = "windows") { "gradlew.bat" } else { "gradlew" })
+    ).expect("Gradle not found");
+
+    let matrix = JavaMatrix::from_env().expect("Invalid Java installation");
+    let report = matrix.run(|_| {
+        let mut gradle = Command::new(&gradlew);
+        gradle.args(["test", "-i"]).current_dir(&driver_dir);
+        gradle
+    });
+
+    println!("{}", report);
+    report.assert_success();
+}
+
 #[test]
 fn vm_creation_and_object_usage() {
     let mut child = Command::new(
@@ -42,7 +63,7 @@ fn vm_creation_and_object_usage() {
             Path::new(".").join("tests").join("driver").join(
                 if cfg!(target_os = "windows") { "gradlew.bat" } else { "gradlew" })
         ).expect("Gradle not found"))
-        .args(&["test", "-i"])
+        .args(["test", "-i"])
         .current_dir(
             Path::new(".").join("tests").join("driver").to_str().expect("Failed to get driver path")
         )
@@ -64,7 +85,7 @@ fn vm_creation_and_object_usage() {
             std::env::var("DYLD_FALLBACK_LIBRARY_PATH").unwrap_or("".to_string()),
         ));
     let vm_args = InitArgsBuilder::new()
-        .option(&*format!(
+        .option(&format!(
             "-Djava.class.path={}",
             classpath.to_string_lossy()
         ))
@@ -73,12 +94,11 @@ fn vm_creation_and_object_usage() {
     let vm = JavaVM::new(vm_args).expect("can't create vm");
     let env = vm.attach_current_thread().expect("can't get vm env");
 
-    User::initNative();
+    User::initNative(&env);
 
     let count = User::getTotalUsersCount(&env)
-        .or_else(|e| {
+        .inspect_err(|_| {
             let _ = print_exception(&env);
-            Err(e)
         })
         .expect("can't get user count");
 
@@ -87,9 +107,8 @@ fn vm_creation_and_object_usage() {
     let u = User::new(&env, "user".into(), "password".into()).expect("can't create user instance");
 
     let count = User::getTotalUsersCount(&env)
-        .or_else(|e| {
+        .inspect_err(|_| {
             let _ = print_exception(&env);
-            Err(e)
         })
         .expect("can't get user count");
     assert_eq!(count, 1);